@@ -105,7 +105,7 @@ fn resolve_username() -> Option<String> {
     None
 }
 
-fn resolve_hostname() -> Option<String> {
+pub(crate) fn resolve_hostname() -> Option<String> {
     #[cfg(windows)]
     if let Ok(h) = std::env::var("COMPUTERNAME")
         && !h.trim().is_empty()
@@ -277,6 +277,19 @@ impl ApiContext {
         }
     }
 
+    /// Create a new API context authenticated only by `api_key`
+    /// Use this for backends that must never receive the user's login token or
+    /// the globally configured API key (e.g. per-organization sync backends)
+    pub fn with_api_key(base_url: String, api_key: String) -> Self {
+        Self {
+            base_url,
+            auth_token: None,
+            api_key: Some(api_key),
+            author_identity: resolve_git_identity(),
+            timeout_secs: Some(30),
+        }
+    }
+
     /// Set a custom timeout
     pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
         self.timeout_secs = Some(timeout_secs);
@@ -310,7 +323,7 @@ impl ApiContext {
         body: &T,
     ) -> Result<http::Response, GitAiError> {
         let url = self.build_url(endpoint)?;
-        let body_json = serde_json::to_string(body).map_err(GitAiError::JsonError)?;
+        let body_json = crate::redaction::outbound_json(body).map_err(GitAiError::JsonError)?;
 
         let (_agent, mut request) = Self::http_post(&url, self.timeout_secs);
         request = request.set("Content-Type", "application/json");
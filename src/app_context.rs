@@ -0,0 +1,168 @@
+//! Where git-ai keeps its files on disk.
+//!
+//! By default everything lives under `~/.git-ai`. Each location can be moved
+//! with an environment variable, or with the matching leading flag
+//! (`git-ai --state-dir <dir> <command>`), which sets the variable so the
+//! daemon and any git-ai processes spawned along the way use the same paths:
+//!
+//! | Location | Default | Variable | Flag |
+//! |---|---|---|---|
+//! | config file | `~/.git-ai/config.json` | `GIT_AI_CONFIG_FILE` | `--config` |
+//! | state (databases, daemon sockets, credentials, client backups, audit log) | `~/.git-ai/internal` | `GIT_AI_STATE_DIR` | `--state-dir` |
+//! | caches | the state dir | `GIT_AI_CACHE_DIR` | `--cache-dir` |
+//! | daemon logs | `<state dir>/daemon/logs` | `GIT_AI_LOG_DIR` | `--log-dir` |
+//!
+//! Code that needs one of these paths asks [`AppContext::current`] rather
+//! than joining onto the home directory itself.
+
+use crate::mdm::utils::home_dir;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_ENV: &str = "GIT_AI_CONFIG_FILE";
+pub const STATE_DIR_ENV: &str = "GIT_AI_STATE_DIR";
+pub const CACHE_DIR_ENV: &str = "GIT_AI_CACHE_DIR";
+pub const LOG_DIR_ENV: &str = "GIT_AI_LOG_DIR";
+
+/// Leading `git-ai` flags and the variable each one sets
+const PATH_FLAGS: &[(&str, &str)] = &[
+    ("--config", CONFIG_FILE_ENV),
+    ("--state-dir", STATE_DIR_ENV),
+    ("--cache-dir", CACHE_DIR_ENV),
+    ("--log-dir", LOG_DIR_ENV),
+];
+
+/// The resolved on-disk locations for this process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppContext {
+    pub config_file: PathBuf,
+    pub state_dir: PathBuf,
+    pub cache_dir: PathBuf,
+    pub log_dir: PathBuf,
+}
+
+impl AppContext {
+    /// Locations from the environment, falling back to `~/.git-ai`. Resolved
+    /// on every call, so a changed `HOME` is picked up.
+    pub fn current() -> Self {
+        Self::for_home(&home_dir())
+    }
+
+    /// Locations from the environment, falling back to `<home>/.git-ai`
+    pub fn for_home(home: &Path) -> Self {
+        Self::resolve(|name| std::env::var_os(name).map(PathBuf::from), home)
+    }
+
+    fn resolve(var: impl Fn(&str) -> Option<PathBuf>, home: &Path) -> Self {
+        let var = |name: &str| var(name).filter(|path| !path.as_os_str().is_empty());
+        let git_ai_dir = home.join(".git-ai");
+        let state_dir = var(STATE_DIR_ENV).unwrap_or_else(|| git_ai_dir.join("internal"));
+        Self {
+            config_file: var(CONFIG_FILE_ENV).unwrap_or_else(|| git_ai_dir.join("config.json")),
+            cache_dir: var(CACHE_DIR_ENV).unwrap_or_else(|| state_dir.clone()),
+            log_dir: var(LOG_DIR_ENV).unwrap_or_else(|| state_dir.join("daemon").join("logs")),
+            state_dir,
+        }
+    }
+
+    /// The daemon log directory for a daemon whose state is in `state_dir`:
+    /// `GIT_AI_LOG_DIR` when set, else `<state_dir>/daemon/logs`
+    pub fn log_dir_for(state_dir: &Path) -> PathBuf {
+        std::env::var_os(LOG_DIR_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| state_dir.join("daemon").join("logs"))
+    }
+}
+
+/// An environment variable and the path a flag set it to
+pub type PathOverride = (&'static str, PathBuf);
+
+/// Strip leading path flags (`--state-dir <dir>` or `--state-dir=<dir>`) from
+/// `args`, returning the rest and the `(variable, value)` pairs they set.
+/// Relative paths are made absolute so spawned processes agree on them.
+pub fn split_path_flags(args: &[String]) -> Result<(&[String], Vec<PathOverride>), String> {
+    let mut rest = args;
+    let mut overrides = Vec::new();
+    while let Some(first) = rest.first() {
+        let (flag, inline) = match first.split_once('=') {
+            Some((flag, value)) => (flag, Some(value)),
+            None => (first.as_str(), None),
+        };
+        let Some(&(flag, var)) = PATH_FLAGS.iter().find(|(name, _)| *name == flag) else {
+            break;
+        };
+        let (value, consumed) = match inline {
+            Some(value) => (value, 1),
+            None => match rest.get(1) {
+                Some(value) => (value.as_str(), 2),
+                None => return Err(format!("{} requires a path", flag)),
+            },
+        };
+        if value.is_empty() {
+            return Err(format!("{} requires a path", flag));
+        }
+        let path = std::path::absolute(value)
+            .map_err(|e| format!("{}: invalid path {}: {}", flag, value, e))?;
+        overrides.push((var, path));
+        rest = &rest[consumed..];
+    }
+    Ok((rest, overrides))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn defaults_live_under_home_and_follow_state_dir() {
+        let home = Path::new("/home/dev");
+        let defaults = AppContext::resolve(|_| None, home);
+        assert_eq!(
+            defaults.config_file,
+            PathBuf::from("/home/dev/.git-ai/config.json")
+        );
+        assert_eq!(
+            defaults.state_dir,
+            PathBuf::from("/home/dev/.git-ai/internal")
+        );
+        assert_eq!(defaults.cache_dir, defaults.state_dir);
+        assert_eq!(
+            defaults.log_dir,
+            PathBuf::from("/home/dev/.git-ai/internal/daemon/logs")
+        );
+
+        let env = HashMap::from([(STATE_DIR_ENV, "/tmp/state"), (CONFIG_FILE_ENV, "")]);
+        let moved = AppContext::resolve(|name| env.get(name).map(PathBuf::from), home);
+        assert_eq!(moved.config_file, defaults.config_file);
+        assert_eq!(moved.cache_dir, PathBuf::from("/tmp/state"));
+        assert_eq!(moved.log_dir, PathBuf::from("/tmp/state/daemon/logs"));
+    }
+
+    #[test]
+    fn path_flags_are_split_off_the_front() {
+        let args: Vec<String> = [
+            "--state-dir",
+            "/tmp/s",
+            "--config=/tmp/c.json",
+            "status",
+            "--config",
+            "x",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        let (rest, overrides) = split_path_flags(&args).unwrap();
+        assert_eq!(rest, &args[3..]);
+        assert_eq!(
+            overrides,
+            vec![
+                (STATE_DIR_ENV, std::path::absolute("/tmp/s").unwrap()),
+                (CONFIG_FILE_ENV, std::path::absolute("/tmp/c.json").unwrap()),
+            ]
+        );
+
+        let missing = vec!["--log-dir".to_string()];
+        assert!(split_path_flags(&missing).is_err());
+    }
+}
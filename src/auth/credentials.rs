@@ -35,7 +35,10 @@ impl CredentialStore {
             // Use Config::fresh() to support runtime config updates (daemon mode)
             let use_keyring = Config::fresh().get_feature_flags().auth_keyring;
 
-            if use_keyring && KeyringBackend::is_available(SERVICE_NAME) {
+            if use_keyring
+                && crate::capabilities::available(crate::capabilities::Capability::SecureStore)
+                && KeyringBackend::is_available(SERVICE_NAME)
+            {
                 Self {
                     backend: Box::new(KeyringBackend::new(SERVICE_NAME, USERNAME)),
                 }
@@ -81,10 +84,8 @@ impl CredentialStore {
 
     #[cfg(not(test))]
     fn default_production_path() -> PathBuf {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".git-ai")
-            .join("internal")
+        crate::app_context::AppContext::current()
+            .state_dir
             .join("credentials")
     }
 
@@ -3,9 +3,12 @@
 //! This library maintains attribution ranges as files are edited, preserving
 //! authorship information even through moves, edits, and whitespace changes.
 
-use crate::authorship::imara_diff_utils::{ByteDiff, ByteDiffOp, DiffOp, capture_diff_slices};
+use crate::authorship::imara_diff_utils::{
+    ByteDiff, ByteDiffOp, DiffAlgorithm, DiffOp, capture_diff_slices, capture_line_diff,
+};
 use crate::authorship::move_detection::{DeletedLine, InsertedLine, detect_moves};
 use crate::authorship::working_log::CheckpointKind;
+use crate::config::Config;
 use crate::error::GitAiError;
 use std::cmp::Ordering;
 use std::collections::HashMap;
@@ -261,12 +264,15 @@ struct DiffComputation {
 /// Configuration for the attribution tracker
 pub struct AttributionConfig {
     move_lines_threshold: usize,
+    /// Algorithm for the line-level pass that decides which lines changed
+    diff_algorithm: DiffAlgorithm,
 }
 
 impl Default for AttributionConfig {
     fn default() -> Self {
         AttributionConfig {
             move_lines_threshold: 3,
+            diff_algorithm: DiffAlgorithm::default(),
         }
     }
 }
@@ -277,10 +283,13 @@ pub struct AttributionTracker {
 }
 
 impl AttributionTracker {
-    /// Create a new attribution tracker with default configuration
+    /// Create a new attribution tracker using the configured diff algorithm
     pub fn new() -> Self {
         AttributionTracker {
-            config: AttributionConfig::default(),
+            config: AttributionConfig {
+                diff_algorithm: Config::get().attribution_diff_algorithm(),
+                ..Default::default()
+            },
         }
     }
 
@@ -315,7 +324,11 @@ impl AttributionTracker {
             .map(|line| &new_content[line.start..line.end])
             .collect();
 
-        let line_ops = capture_diff_slices(&old_line_slices, &new_line_slices);
+        let line_ops = capture_line_diff(
+            self.config.diff_algorithm,
+            &old_line_slices,
+            &new_line_slices,
+        );
         let line_ops_len = line_ops.len();
         tracing::debug!(
             "[BENCHMARK] capture_diff_slices produced {} ops in {:?}",
@@ -2445,6 +2458,7 @@ mod tests {
         let tracker = AttributionTracker::with_config(AttributionConfig {
             // Test with a one-line threshold
             move_lines_threshold: 1,
+            ..Default::default()
         });
         let old = "fn helper() { println!(\"helper\"); }\nfn main() { println!(\"main\"); }\n";
         let new = "fn main() { println!(\"main\"); }\nfn helper() { println!(\"helper\"); }\n";
@@ -2469,6 +2483,79 @@ mod tests {
         );
     }
 
+    const MOVED_BLOCK_BEFORE: &str =
+        include_str!("../../tests/fixtures/diff_algorithms/moved_block.before.rs");
+    const MOVED_BLOCK_AFTER: &str =
+        include_str!("../../tests/fixtures/diff_algorithms/moved_block.after.rs");
+
+    /// Attribute each top-level `fn` (through the blank line after it) to an
+    /// author named after the function
+    fn attribute_by_function(content: &str) -> Vec<Attribution> {
+        let mut starts: Vec<usize> = content.match_indices("fn ").map(|(i, _)| i).collect();
+        starts.push(content.len());
+        starts
+            .windows(2)
+            .map(|span| {
+                let name = content[span[0] + 3..].split('(').next().unwrap();
+                Attribution::new(span[0], span[1], name.to_string(), TEST_TS)
+            })
+            .collect()
+    }
+
+    fn line_owners(attributions: &[Attribution], content: &str) -> Vec<String> {
+        let mut owners = vec![String::new(); content.lines().count()];
+        for la in attributions_to_line_attributions(attributions, content) {
+            for line in la.start_line..=la.end_line {
+                owners[line as usize - 1] = la.author_id.clone();
+            }
+        }
+        owners
+    }
+
+    /// Non-blank lines of the reordered fixture still owned by the function
+    /// they belong to, out of all non-blank lines
+    fn correctly_attributed_moved_lines(algorithm: DiffAlgorithm) -> (usize, usize) {
+        let tracker = AttributionTracker::with_config(AttributionConfig {
+            diff_algorithm: algorithm,
+            ..Default::default()
+        });
+        let updated = tracker
+            .update_attributions(
+                MOVED_BLOCK_BEFORE,
+                MOVED_BLOCK_AFTER,
+                &attribute_by_function(MOVED_BLOCK_BEFORE),
+                "Charlie",
+                TEST_TS + 1,
+            )
+            .unwrap();
+        let expected = line_owners(&attribute_by_function(MOVED_BLOCK_AFTER), MOVED_BLOCK_AFTER);
+        let actual = line_owners(&updated, MOVED_BLOCK_AFTER);
+        // Blank lines carry no content to attribute
+        let scored: Vec<_> = MOVED_BLOCK_AFTER
+            .lines()
+            .zip(expected.iter().zip(&actual))
+            .filter(|(line, _)| !line.trim().is_empty())
+            .collect();
+        let correct = scored
+            .iter()
+            .filter(|(_, (expected, actual))| expected == actual)
+            .count();
+        (correct, scored.len())
+    }
+
+    #[test]
+    fn histogram_keeps_moved_block_with_its_author() {
+        // `footer` moves above `spacing`; both are mostly `out.push(..)` lines
+        let (correct, total) = correctly_attributed_moved_lines(DiffAlgorithm::Histogram);
+        assert_eq!(correct, total);
+        let (correct, total) = correctly_attributed_moved_lines(DiffAlgorithm::Patience);
+        assert_eq!(correct, total);
+        // Myers aligns the two functions' shared lines in place, so the moved
+        // signatures go to the editor and bodies to the function jumped over
+        let (correct, total) = correctly_attributed_moved_lines(DiffAlgorithm::Myers);
+        assert!(correct < total - 4, "myers got {}/{}", correct, total);
+    }
+
     #[test]
     fn move_block_preserves_original_authors_default_threshold() {
         // Test move detection with blocks of 3+ lines (the default threshold)
@@ -4,6 +4,7 @@
 //! for accurate line attribution tracking.
 
 use imara_diff::{Algorithm, Diff, InternedInput, TokenSource};
+use serde::{Deserialize, Serialize};
 use std::hash::Hash;
 
 // ============================================================================
@@ -119,6 +120,55 @@ impl<'a, T: Clone + Hash + Eq> TokenSource for SliceTokenSource<'a, T> {
     }
 }
 
+/// Line-matching algorithm used when diffing for attribution.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffAlgorithm {
+    /// git's default. Cheap, but matches lines greedily, so a moved block can
+    /// be aligned on blank lines and braces instead of its actual content.
+    Myers,
+    /// Default: anchors on low-occurrence lines first, like `git diff
+    /// --histogram`, which keeps moved and reordered blocks intact.
+    #[default]
+    Histogram,
+    /// Accepted for parity with `git diff --patience`. imara-diff has no
+    /// separate patience implementation; histogram is its generalisation
+    /// and produces the same anchors on unique lines.
+    Patience,
+}
+
+impl DiffAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffAlgorithm::Myers => "myers",
+            DiffAlgorithm::Histogram => "histogram",
+            DiffAlgorithm::Patience => "patience",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_lowercase().as_str() {
+            "myers" | "default" => Some(DiffAlgorithm::Myers),
+            "histogram" => Some(DiffAlgorithm::Histogram),
+            "patience" => Some(DiffAlgorithm::Patience),
+            _ => None,
+        }
+    }
+
+    fn to_imara(self) -> Algorithm {
+        match self {
+            DiffAlgorithm::Myers => Algorithm::Myers,
+            DiffAlgorithm::Histogram | DiffAlgorithm::Patience => Algorithm::Histogram,
+        }
+    }
+}
+
+impl std::fmt::Display for DiffAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Computes the diff between two slices and returns a vector of diff operations.
 ///
 /// This function uses imara-diff with the Myers algorithm.
@@ -135,6 +185,19 @@ pub fn capture_diff_slices<T: Hash + Eq + Clone>(old: &[T], new: &[T]) -> Vec<Di
     hunks_to_diff_ops(&diff, old.len(), new.len())
 }
 
+/// Diffs two sequences of lines with `algorithm`. Unlike [`capture_diff_slices`],
+/// hunks that could slide over repeated lines (closing braces, blank lines) are
+/// placed by git's indent heuristic, so a moved block keeps its own
+/// boundaries. Myers output is left as computed.
+pub fn capture_line_diff(algorithm: DiffAlgorithm, old: &[&str], new: &[&str]) -> Vec<DiffOp> {
+    let input = InternedInput::new(SliceTokenSource::new(old), SliceTokenSource::new(new));
+    let mut diff = Diff::compute(algorithm.to_imara(), &input);
+    if algorithm != DiffAlgorithm::Myers {
+        diff.postprocess_lines(&input);
+    }
+    hunks_to_diff_ops(&diff, old.len(), new.len())
+}
+
 /// Represents a change in a line-based diff.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LineChangeTag {
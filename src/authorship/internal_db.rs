@@ -1,8 +1,8 @@
 // DEPRECATED: The internal DB is deprecated and in the process of being removed.
 // It has been superseded by use-case-specific databases.
 
+use crate::app_context::AppContext;
 use crate::error::GitAiError;
-use dirs;
 use rusqlite::{Connection, params};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -184,9 +184,7 @@ impl InternalDatabase {
             return Ok(PathBuf::from(test_path));
         }
 
-        let home = dirs::home_dir()
-            .ok_or_else(|| GitAiError::Generic("Could not determine home directory".to_string()))?;
-        Ok(home.join(".git-ai").join("internal").join("db"))
+        Ok(AppContext::current().state_dir.join("db"))
     }
 
     /// Initialize schema and handle migrations
@@ -507,6 +505,45 @@ impl InternalDatabase {
 
         Ok(())
     }
+
+    /// Delete legacy prompt rows whose `human_author` satisfies `matches`.
+    /// Returns the number of matching rows; with `dry_run` nothing is deleted.
+    pub fn delete_prompts_by_author(
+        &mut self,
+        matches: impl Fn(&str) -> bool,
+        dry_run: bool,
+    ) -> Result<usize, GitAiError> {
+        let ids: Vec<String> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, human_author FROM prompts WHERE human_author IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            let mut ids = Vec::new();
+            for row in rows {
+                let (id, author) = row?;
+                if matches(&author) {
+                    ids.push(id);
+                }
+            }
+            ids
+        };
+
+        if dry_run || ids.is_empty() {
+            return Ok(ids.len());
+        }
+
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare("DELETE FROM prompts WHERE id = ?1")?;
+            for id in &ids {
+                stmt.execute(params![id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(ids.len())
+    }
 }
 
 /// Calculate next retry timestamp based on attempt number
@@ -570,6 +607,35 @@ mod tests {
         assert_eq!(version, "3");
     }
 
+    #[test]
+    fn test_delete_prompts_by_author() {
+        let (mut db, _temp_dir) = create_test_db();
+        for (id, author) in [
+            ("p1", Some("Alice <alice@example.com>")),
+            ("p2", Some("Bob <bob@example.com>")),
+            ("p3", None),
+        ] {
+            db.conn
+                .execute(
+                    "INSERT INTO prompts (id, tool, model, external_thread_id, messages, human_author, created_at, updated_at) \
+                     VALUES (?1, 'cursor', 'gpt', 't', '[]', ?2, 0, 0)",
+                    params![id, author],
+                )
+                .unwrap();
+        }
+        let count = |db: &InternalDatabase| -> i64 {
+            db.conn
+                .query_row("SELECT COUNT(*) FROM prompts", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        let is_alice = |author: &str| author.contains("alice@example.com");
+        assert_eq!(db.delete_prompts_by_author(is_alice, true).unwrap(), 1);
+        assert_eq!(count(&db), 3);
+        assert_eq!(db.delete_prompts_by_author(is_alice, false).unwrap(), 1);
+        assert_eq!(count(&db), 2);
+    }
+
     #[test]
     fn test_initialize_schema_handles_preexisting_cas_cache_table() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,314 @@
+//! Optional components git-ai can run without, and what happens when one is
+//! missing.
+//!
+//! Headless servers and minimal containers often lack a real git, network
+//! access or a secure credential store. [`available`] detects each of them on
+//! first use, so a command only probes what it needs, and [`check_command`]
+//! applies this matrix before a `git-ai` command runs, so a missing component
+//! is reported up front instead of surfacing as an unrelated error halfway
+//! through:
+//!
+//! | Feature | No real git | No network | No secure store |
+//! |---|---|---|---|
+//! | git proxy, repository commands (`checkpoint`, `blame`, `log`, ...) | error | — | — |
+//! | `fetch-notes`, `notes migrate`, `ci <provider> run` | error | error | — |
+//! | `ci local`, `ci attest`, `ci <provider> install` | error | — | — |
+//! | `install-hooks` | error | warning: JetBrains plugin download skipped | — |
+//! | `upgrade` | — | error | — |
+//! | `login`, `exchange-nonce` | — | error | file storage, with a note |
+//! | `logout`, `whoami` | — | — | file storage, with a note |
+//! | background update check | — | skipped silently | — |
+//! | `help`, `version`, `config`, `sync status`, `notes serve`, `clients`, `usage`, `report`, `privacy`, `debug`, ... | — | — | — |
+//!
+//! Commands not listed need a real git. The network only counts as missing
+//! when it certainly is: `GIT_AI_OFFLINE=1`, or on Linux no route at all
+//! beyond loopback (a container without networking). Anything less certain
+//! (no default route behind a proxy, say) counts as available, and the
+//! request itself reports a failure.
+
+use std::sync::OnceLock;
+
+pub const OFFLINE_ENV: &str = "GIT_AI_OFFLINE";
+
+/// An optional component some features need
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    RealGit,
+    Network,
+    SecureStore,
+}
+
+/// What a feature does when a capability it uses is missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Degradation {
+    /// Run without the parts that need it, after a warning
+    SkipWithWarning(&'static str),
+    /// Refuse to run
+    HardError,
+}
+
+static REAL_GIT: OnceLock<bool> = OnceLock::new();
+static NETWORK: OnceLock<bool> = OnceLock::new();
+static SECURE_STORE: OnceLock<bool> = OnceLock::new();
+
+/// Whether this machine has `capability`, detected the first time it's asked
+/// for in this process
+pub fn available(capability: Capability) -> bool {
+    match capability {
+        Capability::RealGit => {
+            *REAL_GIT.get_or_init(|| crate::config::Config::get().has_real_git())
+        }
+        Capability::Network => *NETWORK.get_or_init(network_available),
+        Capability::SecureStore => {
+            *SECURE_STORE.get_or_init(|| secure_store_available(|name| std::env::var(name).ok()))
+        }
+    }
+}
+
+impl Capability {
+    fn missing_message(self, command: &str) -> String {
+        match self {
+            Capability::RealGit => format!(
+                "git-ai {} needs git, but no real git binary was found.\n\
+                 Install Git or set 'git_path' in {}.",
+                command,
+                crate::app_context::AppContext::current()
+                    .config_file
+                    .display()
+            ),
+            Capability::Network => format!(
+                "git-ai {} needs network access, but none is available (set {}=0 if this is wrong).",
+                command, OFFLINE_ENV
+            ),
+            Capability::SecureStore => format!(
+                "git-ai {} needs a secure credential store, but none is available.",
+                command
+            ),
+        }
+    }
+}
+
+/// The matrix row for `git-ai <command> <rest...>`. Secure store fallbacks
+/// are handled by the credential store itself, so they don't appear here.
+pub fn command_requirements(
+    command: &str,
+    rest: &[String],
+) -> &'static [(Capability, Degradation)] {
+    use Capability::*;
+    use Degradation::*;
+    let subcommand = rest.first().map(String::as_str);
+    match (command, subcommand) {
+        (
+            "help" | "--help" | "-h" | "version" | "--version" | "-v" | "config" | "bg" | "d"
+            | "daemon" | "debug" | "clients" | "mdm" | "usage" | "report" | "privacy"
+            | "uninstall-hooks" | "git-path" | "logout" | "whoami" | "support-bundle"
+            | "server-hook" | "serve-dashboard" | "flush-metrics-db",
+            _,
+        ) => &[],
+        ("upgrade" | "login" | "exchange-nonce", _) => &[(Network, HardError)],
+        // `sync status` only reads the local upload queues
+        ("sync", _) => &[],
+        ("notes", Some("migrate")) | ("fetch-notes", _) => {
+            &[(RealGit, HardError), (Network, HardError)]
+        }
+        ("notes", _) => &[],
+        // `ci <provider> run` talks to the forge; `ci local`, `ci attest` and
+        // the template installers only touch the repository
+        ("ci", Some(_)) if rest.get(1).map(String::as_str) == Some("run") => {
+            &[(RealGit, HardError), (Network, HardError)]
+        }
+        ("ci", _) => &[(RealGit, HardError)],
+        ("install-hooks" | "install", _) => &[
+            (RealGit, HardError),
+            (
+                Network,
+                SkipWithWarning("JetBrains plugins will not be downloaded"),
+            ),
+        ],
+        _ => &[(RealGit, HardError)],
+    }
+}
+
+/// Apply the matrix to `git-ai <command> <rest...>`: warn about skipped
+/// parts, or fail if it can't run here. `has` is asked only about the
+/// capabilities the command uses.
+pub fn check_command(
+    command: &str,
+    rest: &[String],
+    has: impl Fn(Capability) -> bool,
+) -> Result<(), String> {
+    for &(capability, degradation) in command_requirements(command, rest) {
+        if has(capability) {
+            continue;
+        }
+        match degradation {
+            Degradation::HardError => {
+                return Err(capability.missing_message(command));
+            }
+            Degradation::SkipWithWarning(skipped) => {
+                let reason = match capability {
+                    Capability::RealGit => "no real git binary was found",
+                    Capability::Network => "no network access",
+                    Capability::SecureStore => "no secure credential store",
+                };
+                eprintln!(
+                    "{}",
+                    crate::output::Status::Warning.line(&format!("{}; {}", reason, skipped))
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn network_available() -> bool {
+    if let Ok(value) = std::env::var(OFFLINE_ENV) {
+        let value = value.trim();
+        if !value.is_empty() && value != "0" && !value.eq_ignore_ascii_case("false") {
+            return false;
+        }
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let ipv4 = std::fs::read_to_string("/proc/net/route").ok();
+        let ipv6 = std::fs::read_to_string("/proc/net/ipv6_route").ok();
+        // Without procfs there is nothing to go on
+        if ipv4.is_none() && ipv6.is_none() {
+            return true;
+        }
+        ipv4.as_deref().is_some_and(has_ipv4_route) || ipv6.as_deref().is_some_and(has_ipv6_route)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        true
+    }
+}
+
+/// Whether `/proc/net/route` lists any route on a non-loopback interface
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn has_ipv4_route(table: &str) -> bool {
+    table.lines().skip(1).any(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        fields.len() > 7 && fields[0] != "lo"
+    })
+}
+
+/// Whether `/proc/net/ipv6_route` lists any route on a non-loopback
+/// interface
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn has_ipv6_route(table: &str) -> bool {
+    table.lines().any(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        fields.len() == 10 && fields[9] != "lo"
+    })
+}
+
+/// Whether a system keyring can be reached. On Linux and the BSDs the Secret
+/// Service lives on the D-Bus session bus, which headless machines don't run;
+/// probing it there can hang, so its absence is detected up front.
+fn secure_store_available(var: impl Fn(&str) -> Option<String>) -> bool {
+    if !cfg!(feature = "keyring") {
+        return false;
+    }
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        return true;
+    }
+    var("DBUS_SESSION_BUS_ADDRESS").is_some_and(|address| !address.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(rest: &[&str]) -> Vec<String> {
+        rest.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn matrix_fails_or_warns_per_command() {
+        let all = |_: Capability| true;
+        let no_git = |capability: Capability| capability != Capability::RealGit;
+        let offline = |capability: Capability| capability != Capability::Network;
+        let none = |_: Capability| false;
+        let check = |command: &str, rest: &[&str], has: &dyn Fn(Capability) -> bool| {
+            check_command(command, &args(rest), has)
+        };
+
+        assert!(check("blame", &[], &all).is_ok());
+        assert!(check("blame", &[], &no_git).is_err());
+        assert!(check("blame", &[], &offline).is_ok());
+        assert!(check("upgrade", &[], &offline).is_err());
+        assert!(check("upgrade", &[], &no_git).is_ok());
+        assert!(check("fetch-notes", &[], &offline).is_err());
+        // Skips the plugin download with a warning rather than failing
+        assert!(check("install-hooks", &[], &offline).is_ok());
+        assert!(check("install-hooks", &[], &no_git).is_err());
+        for command in ["version", "config", "clients", "debug", "whoami"] {
+            assert!(check(command, &[], &none).is_ok(), "{command}");
+        }
+
+        let err = check("upgrade", &[], &offline).unwrap_err();
+        assert!(err.contains("needs network access"), "{err}");
+    }
+
+    #[test]
+    fn local_subcommands_run_offline() {
+        let offline = |capability: Capability| capability != Capability::Network;
+        let ok = |command: &str, rest: &[&str]| check_command(command, &args(rest), offline);
+
+        assert!(ok("sync", &["status"]).is_ok());
+        assert!(ok("notes", &["serve"]).is_ok());
+        assert!(ok("ci", &["local", "merge"]).is_ok());
+        assert!(ok("ci", &["attest", "--merge-commit-sha", "abc"]).is_ok());
+        assert!(ok("ci", &["github", "install"]).is_ok());
+
+        assert!(ok("notes", &["migrate"]).is_err());
+        assert!(ok("ci", &["github", "run"]).is_err());
+        assert!(ok("ci", &["gitlab", "run", "--no-cleanup"]).is_err());
+    }
+
+    #[test]
+    fn only_the_needed_capabilities_are_asked_about() {
+        let asked = std::cell::RefCell::new(Vec::new());
+        let has = |capability: Capability| {
+            asked.borrow_mut().push(capability);
+            true
+        };
+        check_command("git", &[], has).unwrap();
+        assert_eq!(*asked.borrow(), [Capability::RealGit]);
+    }
+
+    #[test]
+    fn routes_are_found_in_proc_tables() {
+        let header =
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n";
+        let default =
+            format!("{header}eth0\t00000000\t0100A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n");
+        assert!(has_ipv4_route(&default));
+        // No default route (a proxy-only or policy-routed host) still counts
+        let local_only =
+            format!("{header}eth0\t0000A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0\n");
+        assert!(has_ipv4_route(&local_only));
+        let loopback =
+            format!("{header}lo\t0000007F\t00000000\t0001\t0\t0\t0\t000000FF\t0\t0\t0\n");
+        assert!(!has_ipv4_route(&loopback));
+        assert!(!has_ipv4_route(""));
+
+        let ipv6_default = "00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00000003 eth0\n";
+        assert!(has_ipv6_route(ipv6_default));
+        let ipv6_loopback = "00000000000000000000000000000001 80 00000000000000000000000000000000 00 00000000000000000000000000000000 00000000 00000002 00000000 80200001 lo\n";
+        assert!(!has_ipv6_route(ipv6_loopback));
+    }
+
+    #[test]
+    fn secure_store_needs_a_session_bus_on_linux() {
+        if !cfg!(feature = "keyring") || cfg!(any(target_os = "macos", target_os = "windows")) {
+            return;
+        }
+        assert!(!secure_store_available(|_| None));
+        assert!(!secure_store_available(|_| Some(String::new())));
+        assert!(secure_store_available(|_| Some(
+            "unix:path=/run/user/1000/bus".to_string()
+        )));
+    }
+}
@@ -0,0 +1,319 @@
+//! SLSA provenance attestations for merges.
+//!
+//! `git-ai ci attest` describes what a merge brought in (source MR, commit
+//! range, attribution summary, tool versions) as an in-toto Statement with a
+//! SLSA v1 provenance predicate, wrapped in a DSSE envelope. The envelope is
+//! signed with the org's Ed25519 key through the `openssl` CLI, so no key
+//! material is handled in-process and the same key format works on every CI
+//! runner.
+
+use crate::authorship::ignore::effective_ignore_patterns;
+use crate::authorship::stats::stats_for_commit_stats;
+use crate::error::GitAiError;
+use crate::git::notes_api::{commits_with_notes, read_authorship};
+use crate::git::repository::Repository;
+use crate::repo_url::normalize_repo_url;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::Serialize;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+pub const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+pub const DSSE_PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+const BUILD_TYPE: &str = "https://github.com/git-ai-project/git-ai/attestation/merge/v1";
+const BUILDER_ID: &str = "https://github.com/git-ai-project/git-ai";
+
+/// The merge being attested
+#[derive(Debug, Clone)]
+pub struct MergeAttestationInput {
+    pub merge_commit_sha: String,
+    /// Target branch tip before the merge; the attested range is `base..merge`
+    pub base_sha: String,
+    pub merge_request: Option<String>,
+}
+
+/// Attribution totals over the commits a merge introduced
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct AttributionSummary {
+    pub commits: usize,
+    pub commits_with_notes: usize,
+    pub ai_additions: u32,
+    pub human_additions: u32,
+    pub unknown_additions: u32,
+    /// `tool/model` pairs recorded in the introduced commits' notes
+    pub agents: BTreeSet<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DsseSignature {
+    pub keyid: String,
+    pub sig: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DsseEnvelope {
+    pub payload_type: String,
+    pub payload: String,
+    pub signatures: Vec<DsseSignature>,
+}
+
+/// Build the in-toto Statement for a merge
+pub fn merge_statement(
+    repo: &Repository,
+    input: &MergeAttestationInput,
+) -> Result<Value, GitAiError> {
+    let commits = introduced_commits(repo, &input.base_sha, &input.merge_commit_sha)?;
+    let summary = attribution_summary(repo, &commits)?;
+    let repo_uri = repository_uri(repo)?;
+
+    Ok(statement(
+        input,
+        repo_uri.as_deref(),
+        &summary,
+        &git_version(repo)?,
+        &chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    ))
+}
+
+fn statement(
+    input: &MergeAttestationInput,
+    repo_uri: Option<&str>,
+    summary: &AttributionSummary,
+    git_version: &str,
+    finished_on: &str,
+) -> Value {
+    let subject_name = match repo_uri {
+        Some(uri) => format!("{}@{}", uri, input.merge_commit_sha),
+        None => input.merge_commit_sha.clone(),
+    };
+    let mut base_dependency = json!({
+        "name": "base",
+        "digest": { "gitCommit": input.base_sha },
+    });
+    if let Some(uri) = repo_uri {
+        base_dependency["uri"] = json!(uri);
+    }
+
+    json!({
+        "_type": STATEMENT_TYPE,
+        "subject": [{
+            "name": subject_name,
+            "digest": { "gitCommit": input.merge_commit_sha },
+        }],
+        "predicateType": PREDICATE_TYPE,
+        "predicate": {
+            "buildDefinition": {
+                "buildType": BUILD_TYPE,
+                "externalParameters": {
+                    "mergeCommit": input.merge_commit_sha,
+                    "base": input.base_sha,
+                    "mergeRequest": input.merge_request,
+                },
+                "resolvedDependencies": [base_dependency],
+            },
+            "runDetails": {
+                "builder": {
+                    "id": BUILDER_ID,
+                    "version": {
+                        "git-ai": env!("CARGO_PKG_VERSION"),
+                        "git": git_version,
+                    },
+                },
+                "metadata": { "finishedOn": finished_on },
+                "byproducts": [{
+                    "name": "attribution",
+                    "mediaType": "application/json",
+                    "annotations": summary,
+                }],
+            },
+        },
+    })
+}
+
+/// DSSE pre-authentication encoding: what the signature actually covers
+pub fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut encoded = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    encoded.extend_from_slice(payload);
+    encoded
+}
+
+/// Wrap a statement in a DSSE envelope, signed when `key_path` is given
+pub fn envelope(statement: &Value, key_path: Option<&Path>) -> Result<DsseEnvelope, GitAiError> {
+    let payload = serde_json::to_vec(statement)?;
+    let signatures = match key_path {
+        Some(key_path) => vec![DsseSignature {
+            keyid: key_id(key_path)?,
+            sig: BASE64.encode(openssl_sign(key_path, &pae(DSSE_PAYLOAD_TYPE, &payload))?),
+        }],
+        None => Vec::new(),
+    };
+    Ok(DsseEnvelope {
+        payload_type: DSSE_PAYLOAD_TYPE.to_string(),
+        payload: BASE64.encode(&payload),
+        signatures,
+    })
+}
+
+fn openssl(args: &[&str]) -> Result<Vec<u8>, GitAiError> {
+    let output = Command::new("openssl")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| GitAiError::Generic(format!("Failed to run openssl: {}", e)))?;
+    if !output.status.success() {
+        return Err(GitAiError::Generic(format!(
+            "openssl {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Ed25519 signs the message itself (no pre-hash), hence `-rawin`. openssl
+/// needs the input length up front for that, so it's passed as a file.
+pub(crate) fn openssl_sign(key_path: &Path, message: &[u8]) -> Result<Vec<u8>, GitAiError> {
+    let message_file = crate::utils::private_temp_file(message)?;
+    let key = key_path.to_string_lossy();
+    let input = message_file.path().to_string_lossy();
+    openssl(&["pkeyutl", "-sign", "-rawin", "-inkey", &key, "-in", &input])
+}
+
+/// SHA-256 of the DER public key, so verifiers can pick the right key
+pub(crate) fn key_id(key_path: &Path) -> Result<String, GitAiError> {
+    let key = key_path.to_string_lossy();
+    let public_der = openssl(&["pkey", "-in", &key, "-pubout", "-outform", "DER"])?;
+    Ok(format!("SHA256:{:x}", Sha256::digest(&public_der)))
+}
+
+fn introduced_commits(
+    repo: &Repository,
+    base_sha: &str,
+    merge_sha: &str,
+) -> Result<Vec<String>, GitAiError> {
+    Ok(repo
+        .git_output(&[
+            "rev-list",
+            "--no-merges",
+            &format!("{}..{}", base_sha, merge_sha),
+        ])?
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+fn attribution_summary(
+    repo: &Repository,
+    commits: &[String],
+) -> Result<AttributionSummary, GitAiError> {
+    let ignore_patterns = effective_ignore_patterns(repo, &[], &[]);
+    let mut summary = AttributionSummary {
+        commits: commits.len(),
+        commits_with_notes: commits_with_notes(repo, commits)?.len(),
+        ..Default::default()
+    };
+    for sha in commits {
+        let stats = stats_for_commit_stats(repo, sha, &ignore_patterns)?;
+        summary.ai_additions += stats.ai_additions;
+        summary.human_additions += stats.human_additions;
+        summary.unknown_additions += stats.unknown_additions;
+        if let Some(log) = read_authorship(repo, sha) {
+            let prompts = log.metadata.prompts.values().map(|p| &p.agent_id);
+            let sessions = log.metadata.sessions.values().map(|s| &s.agent_id);
+            for agent in prompts.chain(sessions) {
+                summary
+                    .agents
+                    .insert(format!("{}/{}", agent.tool, agent.model));
+            }
+        }
+    }
+    Ok(summary)
+}
+
+fn repository_uri(repo: &Repository) -> Result<Option<String>, GitAiError> {
+    let Some(remote) = repo.get_default_remote()? else {
+        return Ok(None);
+    };
+    Ok(repo
+        .remotes_with_urls()?
+        .into_iter()
+        .find(|(name, _)| *name == remote)
+        .and_then(|(_, url)| normalize_repo_url(&url).ok())
+        .map(|url| format!("git+{}", url)))
+}
+
+fn git_version(repo: &Repository) -> Result<String, GitAiError> {
+    let version = repo.git_output(&["--version"])?;
+    Ok(version
+        .trim()
+        .strip_prefix("git version ")
+        .unwrap_or(version.trim())
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pae_matches_the_dsse_spec_example() {
+        assert_eq!(
+            pae("http://example.com/HelloWorld", b"hello world"),
+            b"DSSEv1 29 http://example.com/HelloWorld 11 hello world".to_vec()
+        );
+    }
+
+    #[test]
+    fn statement_names_the_merge_and_its_base() {
+        let input = MergeAttestationInput {
+            merge_commit_sha: "a".repeat(40),
+            base_sha: "b".repeat(40),
+            merge_request: Some("#42".to_string()),
+        };
+        let summary = AttributionSummary {
+            commits: 2,
+            commits_with_notes: 1,
+            ai_additions: 10,
+            agents: BTreeSet::from(["claude/sonnet".to_string()]),
+            ..Default::default()
+        };
+        let statement = statement(
+            &input,
+            Some("git+https://github.com/acme/app"),
+            &summary,
+            "2.47.0",
+            "2026-01-01T00:00:00Z",
+        );
+
+        assert_eq!(statement["_type"], STATEMENT_TYPE);
+        assert_eq!(
+            statement["subject"][0]["name"],
+            format!("git+https://github.com/acme/app@{}", "a".repeat(40))
+        );
+        let predicate = &statement["predicate"];
+        assert_eq!(
+            predicate["buildDefinition"]["externalParameters"]["mergeRequest"],
+            "#42"
+        );
+        assert_eq!(
+            predicate["buildDefinition"]["resolvedDependencies"][0]["digest"]["gitCommit"],
+            "b".repeat(40)
+        );
+        let attribution = &predicate["runDetails"]["byproducts"][0]["annotations"];
+        assert_eq!(attribution["ai_additions"], 10);
+        assert_eq!(attribution["agents"][0], "claude/sonnet");
+    }
+}
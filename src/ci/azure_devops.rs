@@ -0,0 +1,404 @@
+use crate::ci::ci_context::{CiContext, CiEvent};
+use crate::ci::git_auth::{
+    CloneAuthMode, clone_args, credential_helper_clone_args, env_credential_helper,
+};
+use crate::disk_budget::{check_ci_clone_size, preflight_ci_clone};
+use crate::error::GitAiError;
+use crate::git::repository::exec_git;
+use crate::git::repository::find_repository_in_path;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+const AZURE_PIPELINES_TEMPLATE_YAML: &str = include_str!("workflow_templates/azure-pipelines.yml");
+
+/// The build's OAuth token. Pipelines only exposes it to scripts that map it
+/// into their environment (see the template).
+const TOKEN_ENV: &str = "SYSTEM_ACCESSTOKEN";
+
+/// A page of the pull request list endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct AzurePullRequestList {
+    #[serde(default)]
+    value: Vec<AzurePullRequest>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzurePullRequest {
+    pull_request_id: u64,
+    title: Option<String>,
+    status: String,
+    source_ref_name: String,
+    target_ref_name: String,
+    /// The commit the PR was merged as (merge, squash, or rebase tip)
+    last_merge_commit: Option<AzureCommitRef>,
+    /// The PR head that was merged
+    last_merge_source_commit: Option<AzureCommitRef>,
+    /// The target branch tip it was merged onto
+    last_merge_target_commit: Option<AzureCommitRef>,
+    fork_source: Option<AzureForkRef>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureCommitRef {
+    commit_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AzureForkRef {
+    repository: AzureRepository,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureRepository {
+    remote_url: String,
+}
+
+/// Build and send a GET to an Azure DevOps REST endpoint, authenticated with
+/// the build's OAuth token
+fn azure_api_get(endpoint: &str, token: &str) -> Result<crate::http::Response, String> {
+    let agent = crate::http::build_agent(Some(30));
+    let request = agent
+        .get(endpoint)
+        .set("Authorization", &format!("Bearer {}", token))
+        .set("Accept", "application/json")
+        .set(
+            "User-Agent",
+            &format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+        );
+    crate::http::send_cached(request)
+}
+
+/// The completed PR whose last merge commit is `commit_sha`
+fn find_completed_pull_request(
+    pull_requests: Vec<AzurePullRequest>,
+    commit_sha: &str,
+) -> Option<AzurePullRequest> {
+    pull_requests.into_iter().find(|pr| {
+        pr.status == "completed"
+            && pr
+                .last_merge_commit
+                .as_ref()
+                .is_some_and(|commit| commit.commit_id == commit_sha)
+    })
+}
+
+/// Azure Repos clone URLs carry the organization as a username
+/// (`https://acme@dev.azure.com/acme/...`); drop it so credentials can go in
+fn strip_userinfo(url: &str) -> String {
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let host = authority
+                .rsplit_once('@')
+                .map_or(authority, |(_, host)| host);
+            format!("{}://{}/{}", scheme, host, path)
+        }
+        None => url.to_string(),
+    }
+}
+
+/// Whether `sha` is a commit in the clone
+fn has_commit(clone_dir: &str, sha: &str) -> bool {
+    exec_git(&[
+        "-C".to_string(),
+        clone_dir.to_string(),
+        "cat-file".to_string(),
+        "-e".to_string(),
+        format!("{}^{{commit}}", sha),
+    ])
+    .is_ok()
+}
+
+/// Query the Azure DevOps API for completed PRs into the built branch and
+/// find the one merged as the current commit. Returns None if there is none
+/// (this is not an error - just means this commit wasn't from a merged PR).
+pub fn get_azure_pipelines_context(
+    auth_mode: CloneAuthMode,
+) -> Result<Option<CiContext>, GitAiError> {
+    let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+    let required = |name: &str| {
+        env(name)
+            .ok_or_else(|| GitAiError::Generic(format!("{} environment variable not set", name)))
+    };
+    let commit_sha = required("BUILD_SOURCEVERSION")?;
+    let collection_uri = required("SYSTEM_COLLECTIONURI")?;
+    let project = required("SYSTEM_TEAMPROJECTID").or_else(|_| required("SYSTEM_TEAMPROJECT"))?;
+    let repository_id = required("BUILD_REPOSITORY_ID")?;
+    let repository_uri = required("BUILD_REPOSITORY_URI")?;
+    let source_branch = required("BUILD_SOURCEBRANCH")?;
+    let provider = env("BUILD_REPOSITORY_PROVIDER").unwrap_or_default();
+
+    println!("[Azure Pipelines] Environment:");
+    println!("  BUILD_SOURCEVERSION: {}", commit_sha);
+    println!("  BUILD_SOURCEBRANCH: {}", source_branch);
+    println!("  BUILD_REPOSITORY_URI: {}", repository_uri);
+    println!("  BUILD_REPOSITORY_PROVIDER: {}", provider);
+
+    if !provider.is_empty() && provider != "TfsGit" {
+        return Err(GitAiError::Generic(format!(
+            "Repository provider {} is not supported; git-ai ci azure runs against Azure Repos Git",
+            provider
+        )));
+    }
+    let token = env(TOKEN_ENV).ok_or_else(|| {
+        GitAiError::Generic(format!(
+            "{} environment variable not set. Map it in the step's env: {}: $(System.AccessToken)",
+            TOKEN_ENV, TOKEN_ENV
+        ))
+    })?;
+
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("searchCriteria.status", "completed")
+        .append_pair("searchCriteria.targetRefName", &source_branch)
+        .append_pair("$top", "100")
+        .append_pair("api-version", "7.1")
+        .finish();
+    let endpoint = format!(
+        "{}/{}/_apis/git/repositories/{}/pullrequests?{}",
+        collection_uri.trim_end_matches('/'),
+        url::form_urlencoded::byte_serialize(project.as_bytes()).collect::<String>(),
+        repository_id,
+        query
+    );
+
+    println!("[Azure Pipelines] Querying API: {}", endpoint);
+
+    let response = azure_api_get(&endpoint, &token)
+        .map_err(|e| GitAiError::Generic(format!("Azure DevOps API request failed: {}", e)))?;
+    if response.status_code != 200 {
+        return Err(GitAiError::Generic(format!(
+            "Azure DevOps API returned status {}: {}",
+            response.status_code,
+            response.as_str().unwrap_or("unknown error")
+        )));
+    }
+    let list: AzurePullRequestList = serde_json::from_str(response.as_str().unwrap_or("{}"))
+        .map_err(|e| {
+            GitAiError::Generic(format!("Failed to parse Azure DevOps API response: {}", e))
+        })?;
+
+    println!(
+        "[Azure Pipelines] Found {} completed PRs into {}",
+        list.value.len(),
+        source_branch
+    );
+
+    let Some(pr) = find_completed_pull_request(list.value, &commit_sha) else {
+        println!("[Azure Pipelines] No completed PR found for this commit. Skipping...");
+        return Ok(None);
+    };
+    println!(
+        "[Azure Pipelines] Found matching PR #{}: \"{}\"",
+        pr.pull_request_id,
+        pr.title.as_deref().unwrap_or("(no title)")
+    );
+
+    let head_ref = pr
+        .source_ref_name
+        .strip_prefix("refs/heads/")
+        .unwrap_or(&pr.source_ref_name)
+        .to_string();
+    let base_ref = pr
+        .target_ref_name
+        .strip_prefix("refs/heads/")
+        .unwrap_or(&pr.target_ref_name)
+        .to_string();
+    let Some(head_sha) = pr
+        .last_merge_source_commit
+        .as_ref()
+        .map(|commit| commit.commit_id.clone())
+    else {
+        println!(
+            "[Azure Pipelines] PR #{} has no merged source commit. Skipping...",
+            pr.pull_request_id
+        );
+        return Ok(None);
+    };
+    let base_sha = pr
+        .last_merge_target_commit
+        .as_ref()
+        .map(|commit| commit.commit_id.clone())
+        .unwrap_or_default();
+
+    let clone_url = strip_userinfo(&repository_uri);
+    // A PR from a fork keeps its notes in the fork
+    let fork_clone_url = pr.fork_source.as_ref().map(|fork| {
+        println!(
+            "[Azure Pipelines] Detected fork PR: source repo {}",
+            fork.repository.remote_url
+        );
+        strip_userinfo(&fork.repository.remote_url)
+    });
+
+    // Credential-helper mode keeps every URL as-is so agent insteadOf
+    // rewrites still apply; otherwise the token goes in the URL
+    let use_credential_helper = auth_mode == CloneAuthMode::CredentialHelper;
+    let clone_auth_args = if use_credential_helper {
+        println!("[Azure Pipelines] Using git credential helper for clone/fetch/push");
+        credential_helper_clone_args(&clone_url, &env_credential_helper(&[("build", TOKEN_ENV)]))
+    } else {
+        Vec::new()
+    };
+    let authenticate = |url: &str| {
+        if use_credential_helper {
+            url.to_string()
+        } else {
+            url.replacen("https://", &format!("https://build:{}@", token), 1)
+        }
+    };
+    let authenticated_url = authenticate(&clone_url);
+    let authenticated_fork_url = fork_clone_url.as_deref().map(&authenticate);
+
+    let clone_dir = "git-ai-ci-clone".to_string();
+    println!("[Azure Pipelines] Cloning repository...");
+    preflight_ci_clone(Path::new(&clone_dir))?;
+    exec_git(&clone_args(
+        &clone_auth_args,
+        &base_ref,
+        &authenticated_url,
+        &clone_dir,
+    ))?;
+
+    // Azure Repos keeps the PR's merge ref after completion; its second
+    // parent is the PR head, so this brings in the PR commits even when the
+    // source branch was deleted on completion
+    println!(
+        "[Azure Pipelines] Fetching refs/pull/{}/merge...",
+        pr.pull_request_id
+    );
+    if let Err(e) = exec_git(&[
+        "-C".to_string(),
+        clone_dir.clone(),
+        "fetch".to_string(),
+        authenticated_url.clone(),
+        format!(
+            "refs/pull/{}/merge:refs/azure/pr/{}",
+            pr.pull_request_id, pr.pull_request_id
+        ),
+    ]) {
+        println!(
+            "[Azure Pipelines] Warning: could not fetch refs/pull/{}/merge: {}",
+            pr.pull_request_id, e
+        );
+    }
+
+    if !has_commit(&clone_dir, &head_sha) {
+        println!(
+            "[Azure Pipelines] PR #{}'s commits are no longer available. Skipping...",
+            pr.pull_request_id
+        );
+        return Ok(None);
+    }
+
+    check_ci_clone_size(Path::new(&clone_dir))?;
+
+    let repo = find_repository_in_path(&clone_dir)?;
+
+    println!(
+        "[Azure Pipelines] Created CiContext: merge_commit_sha={}, head_sha={}, head_ref={}, base_ref={}, base_sha={}",
+        commit_sha,
+        head_sha,
+        head_ref,
+        base_ref,
+        if base_sha.is_empty() {
+            "(unavailable)"
+        } else {
+            &base_sha
+        }
+    );
+
+    Ok(Some(CiContext {
+        repo,
+        event: CiEvent::Merge {
+            merge_commit_sha: commit_sha,
+            head_ref,
+            head_sha,
+            base_ref,
+            base_sha,
+            fork_clone_url: authenticated_fork_url,
+        },
+        temp_dir: PathBuf::from(clone_dir),
+    }))
+}
+
+/// Print the Azure Pipelines YAML snippet to stdout for users to copy into their azure-pipelines.yml
+pub fn print_azure_pipelines_yaml() {
+    println!("Add the following to your azure-pipelines.yml:");
+    println!();
+    println!("{}", AZURE_PIPELINES_TEMPLATE_YAML);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MERGE_SHA: &str = "2222222222222222222222222222222222222222";
+
+    fn pull_requests() -> Vec<AzurePullRequest> {
+        let json = r#"{
+            "value": [
+                {
+                    "pullRequestId": 7,
+                    "title": "Unrelated",
+                    "status": "completed",
+                    "sourceRefName": "refs/heads/other",
+                    "targetRefName": "refs/heads/main",
+                    "lastMergeCommit": { "commitId": "1111111111111111111111111111111111111111" }
+                },
+                {
+                    "pullRequestId": 8,
+                    "title": "Add feature",
+                    "status": "completed",
+                    "sourceRefName": "refs/heads/feature",
+                    "targetRefName": "refs/heads/main",
+                    "lastMergeCommit": { "commitId": "2222222222222222222222222222222222222222" },
+                    "lastMergeSourceCommit": { "commitId": "3333333333333333333333333333333333333333" },
+                    "lastMergeTargetCommit": { "commitId": "4444444444444444444444444444444444444444" },
+                    "forkSource": {
+                        "repository": { "remoteUrl": "https://acme@dev.azure.com/acme/proj/_git/fork" }
+                    }
+                }
+            ]
+        }"#;
+        serde_json::from_str::<AzurePullRequestList>(json)
+            .unwrap()
+            .value
+    }
+
+    #[test]
+    fn test_find_completed_pull_request_matches_last_merge_commit() {
+        let pr = find_completed_pull_request(pull_requests(), MERGE_SHA).unwrap();
+        assert_eq!(pr.pull_request_id, 8);
+        assert_eq!(
+            pr.last_merge_source_commit.unwrap().commit_id,
+            "3333333333333333333333333333333333333333"
+        );
+        assert_eq!(
+            pr.fork_source.unwrap().repository.remote_url,
+            "https://acme@dev.azure.com/acme/proj/_git/fork"
+        );
+        assert!(find_completed_pull_request(pull_requests(), "5555555").is_none());
+    }
+
+    #[test]
+    fn test_find_completed_pull_request_ignores_active_pull_requests() {
+        let mut prs = pull_requests();
+        prs[1].status = "active".to_string();
+        assert!(find_completed_pull_request(prs, MERGE_SHA).is_none());
+    }
+
+    #[test]
+    fn test_strip_userinfo_drops_the_organization_username() {
+        assert_eq!(
+            strip_userinfo("https://acme@dev.azure.com/acme/proj/_git/repo"),
+            "https://dev.azure.com/acme/proj/_git/repo"
+        );
+        assert_eq!(
+            strip_userinfo("https://acme.visualstudio.com/proj/_git/repo"),
+            "https://acme.visualstudio.com/proj/_git/repo"
+        );
+    }
+}
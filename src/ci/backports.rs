@@ -0,0 +1,317 @@
+//! Backport tracking for merges into release branches.
+//!
+//! Backport PRs are usually cherry-picked by a bot or in a fresh checkout
+//! without git-ai, so their commits arrive without notes. When a PR targets a
+//! configured release branch, its unattributed commits are matched by stable
+//! patch-id against attributed commits on the default branch and the other
+//! release branches, and the source notes are carried over exactly as a local
+//! `git cherry-pick` would.
+
+use crate::authorship::rewrite::{RewriteEvent, handle_rewrite_event};
+use crate::authorship::rewrite_cherry_pick::stable_patch_ids_for_commits;
+use crate::error::GitAiError;
+use crate::git::merge_request::originating_merge_request;
+use crate::git::notes_api::commits_with_notes;
+use crate::git::refs::ref_exists;
+use crate::git::repository::{Repository, exec_git};
+use glob::Pattern;
+use std::collections::{HashMap, HashSet};
+
+/// How far back each source branch is searched for the original commits
+const MAX_SOURCE_COMMITS_PER_BRANCH: usize = 1000;
+
+/// A backported commit and the commit it was cherry-picked from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackportLink {
+    pub backport: String,
+    pub source: String,
+    /// Remote-tracking ref the source was found on
+    pub source_ref: String,
+    /// PR/MR the source commit was merged through, when its messages name one
+    pub merge_request: Option<String>,
+}
+
+/// Whether `branch` matches one of the configured release branch globs
+pub fn is_release_branch(branch: &str, patterns: &[String]) -> bool {
+    let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+    patterns.iter().any(|pattern| match Pattern::new(pattern) {
+        Ok(glob) => glob.matches(branch),
+        Err(_) => pattern == branch,
+    })
+}
+
+/// Parse `git ls-remote --symref <remote> HEAD refs/heads/*` into the default
+/// branch and the list of branch names.
+fn parse_ls_remote(output: &str) -> (Option<String>, Vec<String>) {
+    let mut default_branch = None;
+    let mut branches = Vec::new();
+    for line in output.lines() {
+        if let Some(symref) = line.strip_prefix("ref: ") {
+            if let Some((target, "HEAD")) = symref.split_once('\t') {
+                default_branch = target.strip_prefix("refs/heads/").map(str::to_string);
+            }
+            continue;
+        }
+        if let Some((_, name)) = line.split_once('\t')
+            && let Some(branch) = name.strip_prefix("refs/heads/")
+        {
+            branches.push(branch.to_string());
+        }
+    }
+    (default_branch, branches)
+}
+
+/// Branches a backport into `base_ref` is expected to come from: the remote's
+/// default branch and every other release branch.
+fn select_source_branches(
+    default_branch: Option<String>,
+    branches: &[String],
+    base_ref: &str,
+    patterns: &[String],
+) -> Vec<String> {
+    let base = base_ref.strip_prefix("refs/heads/").unwrap_or(base_ref);
+    let mut selected: Vec<String> = Vec::new();
+    let candidates = default_branch.into_iter().chain(
+        branches
+            .iter()
+            .filter(|b| is_release_branch(b, patterns))
+            .cloned(),
+    );
+    for branch in candidates {
+        if branch != base && !selected.contains(&branch) {
+            selected.push(branch);
+        }
+    }
+    selected
+}
+
+fn git_stdout(repo: &Repository, args: &[String]) -> Result<String, GitAiError> {
+    let mut full_args = repo.global_args_for_exec();
+    full_args.extend(args.iter().cloned());
+    Ok(String::from_utf8(exec_git(&full_args)?.stdout)?)
+}
+
+/// Resolve (and unless `skip_fetch`, fetch) the source branches into
+/// `refs/remotes/<remote>/*`, returning the refs that exist locally.
+fn source_refs(
+    repo: &Repository,
+    remote: &str,
+    base_ref: &str,
+    patterns: &[String],
+    skip_fetch: bool,
+) -> Result<Vec<String>, GitAiError> {
+    let remote_prefix = format!("refs/remotes/{}/", remote);
+    let branches = if skip_fetch {
+        let listed = git_stdout(
+            repo,
+            &[
+                "for-each-ref".to_string(),
+                "--format=%(refname)".to_string(),
+                remote_prefix.clone(),
+            ],
+        )?;
+        let names: Vec<String> = listed
+            .lines()
+            .filter_map(|r| r.strip_prefix(&remote_prefix))
+            .filter(|name| *name != "HEAD")
+            .map(str::to_string)
+            .collect();
+        let default_branch = git_stdout(
+            repo,
+            &[
+                "symbolic-ref".to_string(),
+                "--quiet".to_string(),
+                "--short".to_string(),
+                format!("{}HEAD", remote_prefix),
+            ],
+        )
+        .ok()
+        .and_then(|s| {
+            s.trim()
+                .strip_prefix(&format!("{}/", remote))
+                .map(str::to_string)
+        })
+        // CI checkouts don't always set <remote>/HEAD
+        .or_else(|| {
+            ["main", "master"]
+                .iter()
+                .find(|name| names.iter().any(|n| n == *name))
+                .map(|name| name.to_string())
+        });
+        select_source_branches(default_branch, &names, base_ref, patterns)
+    } else {
+        let listed = git_stdout(
+            repo,
+            &[
+                "ls-remote".to_string(),
+                "--symref".to_string(),
+                remote.to_string(),
+                "HEAD".to_string(),
+                "refs/heads/*".to_string(),
+            ],
+        )?;
+        let (default_branch, names) = parse_ls_remote(&listed);
+        let selected = select_source_branches(default_branch, &names, base_ref, patterns);
+        if !selected.is_empty() {
+            let mut args = vec![
+                "fetch".to_string(),
+                "--no-tags".to_string(),
+                remote.to_string(),
+            ];
+            args.extend(
+                selected
+                    .iter()
+                    .map(|b| format!("+refs/heads/{}:{}{}", b, remote_prefix, b)),
+            );
+            git_stdout(repo, &args)?;
+        }
+        selected
+    };
+
+    Ok(branches
+        .iter()
+        .map(|b| format!("{}{}", remote_prefix, b))
+        .filter(|r| ref_exists(repo, r))
+        .collect())
+}
+
+/// Attribute the unattributed commits in `pr_commits` from the commits they
+/// were backported from. Returns one link per matched commit.
+pub fn link_backports(
+    repo: &Repository,
+    pr_commits: &[String],
+    base_ref: &str,
+    base_sha: &str,
+    patterns: &[String],
+    skip_fetch: bool,
+) -> Result<Vec<BackportLink>, GitAiError> {
+    let attributed = commits_with_notes(repo, pr_commits)?;
+    let targets: Vec<String> = pr_commits
+        .iter()
+        .filter(|sha| !attributed.contains(*sha))
+        .cloned()
+        .collect();
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Newest-first per branch; the first branch a commit shows up on wins
+    let mut source_ref_of: HashMap<String, String> = HashMap::new();
+    let mut candidates: Vec<String> = Vec::new();
+    for source_ref in source_refs(repo, "origin", base_ref, patterns, skip_fetch)? {
+        let mut args = vec![
+            "rev-list".to_string(),
+            "--no-merges".to_string(),
+            format!("--max-count={}", MAX_SOURCE_COMMITS_PER_BRANCH),
+            source_ref.clone(),
+        ];
+        if !base_sha.is_empty() {
+            args.push(format!("^{}", base_sha));
+        }
+        for sha in git_stdout(repo, &args)?.lines() {
+            if !source_ref_of.contains_key(sha) {
+                source_ref_of.insert(sha.to_string(), source_ref.clone());
+                candidates.push(sha.to_string());
+            }
+        }
+    }
+    let attributed_sources = commits_with_notes(repo, &candidates)?;
+    candidates.retain(|sha| attributed_sources.contains(sha));
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let patch_ids = stable_patch_ids_for_commits(
+        repo,
+        &candidates
+            .iter()
+            .chain(targets.iter())
+            .cloned()
+            .collect::<Vec<_>>(),
+    )?;
+    let mut source_by_patch_id: HashMap<&str, &str> = HashMap::new();
+    for sha in &candidates {
+        if let Some(patch_id) = patch_ids.get(sha) {
+            source_by_patch_id.entry(patch_id).or_insert(sha);
+        }
+    }
+
+    let mut used_sources: HashSet<&str> = HashSet::new();
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    for target in &targets {
+        let Some(source) = patch_ids
+            .get(target)
+            .and_then(|patch_id| source_by_patch_id.get(patch_id.as_str()))
+        else {
+            continue;
+        };
+        if used_sources.insert(source) {
+            pairs.push((source.to_string(), target.clone()));
+        }
+    }
+    if pairs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    handle_rewrite_event(
+        repo,
+        RewriteEvent::CherryPickComplete {
+            sources: pairs.iter().map(|(source, _)| source.clone()).collect(),
+            new_commits: pairs.iter().map(|(_, target)| target.clone()).collect(),
+        },
+    )?;
+
+    pairs
+        .into_iter()
+        .map(|(source, backport)| {
+            let source_ref = source_ref_of[&source].clone();
+            Ok(BackportLink {
+                merge_request: originating_merge_request(repo, &source, &source_ref)?,
+                backport,
+                source,
+                source_ref,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(globs: &[&str]) -> Vec<String> {
+        globs.iter().map(|g| g.to_string()).collect()
+    }
+
+    #[test]
+    fn release_branches_match_globs_and_exact_names() {
+        let globs = patterns(&["release/*", "stable"]);
+        assert!(is_release_branch("release/1.2", &globs));
+        assert!(is_release_branch("refs/heads/release/1.2", &globs));
+        assert!(is_release_branch("stable", &globs));
+        assert!(!is_release_branch("main", &globs));
+        assert!(!is_release_branch("main", &[]));
+    }
+
+    #[test]
+    fn ls_remote_output_yields_default_and_branches() {
+        let output = "ref: refs/heads/main\tHEAD\n\
+                      1111111111111111111111111111111111111111\tHEAD\n\
+                      1111111111111111111111111111111111111111\trefs/heads/main\n\
+                      2222222222222222222222222222222222222222\trefs/heads/release/1.0\n\
+                      3333333333333333333333333333333333333333\trefs/heads/release/2.0\n";
+        let (default_branch, branches) = parse_ls_remote(output);
+        assert_eq!(default_branch.as_deref(), Some("main"));
+        assert_eq!(branches, vec!["main", "release/1.0", "release/2.0"]);
+
+        assert_eq!(
+            select_source_branches(
+                default_branch,
+                &branches,
+                "refs/heads/release/1.0",
+                &patterns(&["release/*"])
+            ),
+            vec!["main", "release/2.0"]
+        );
+    }
+}
@@ -0,0 +1,489 @@
+use crate::ci::ci_context::{CiContext, CiEvent};
+use crate::ci::git_auth::{
+    CloneAuthMode, clone_args, credential_helper_clone_args, env_credential_helper,
+};
+use crate::disk_budget::{check_ci_clone_size, preflight_ci_clone};
+use crate::error::GitAiError;
+use crate::git::repository::exec_git;
+use crate::git::repository::find_repository_in_path;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+const BITBUCKET_PIPELINES_TEMPLATE_YAML: &str =
+    include_str!("workflow_templates/bitbucket-pipelines.yml");
+
+pub(crate) const BITBUCKET_API_URL: &str = "https://api.bitbucket.org/2.0";
+
+/// Repository access token (`x-token-auth`). Pipelines reserves the
+/// `BITBUCKET_` prefix for its own variables, hence `GIT_AI_`.
+const TOKEN_ENV: &str = "GIT_AI_BITBUCKET_TOKEN";
+/// Username and app password, the alternative to an access token
+const USERNAME_ENV: &str = "GIT_AI_BITBUCKET_USERNAME";
+const APP_PASSWORD_ENV: &str = "GIT_AI_BITBUCKET_APP_PASSWORD";
+
+/// A page of the pull request list endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketPage {
+    #[serde(default)]
+    values: Vec<BitbucketPullRequest>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketPullRequest {
+    id: u64,
+    title: Option<String>,
+    source: BitbucketEndpoint,
+    destination: BitbucketEndpoint,
+    merge_commit: Option<BitbucketCommit>,
+}
+
+/// One side of a pull request. `repository` is null once a fork is deleted.
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketEndpoint {
+    branch: BitbucketBranch,
+    commit: Option<BitbucketCommit>,
+    repository: Option<BitbucketRepository>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketBranch {
+    name: String,
+}
+
+/// Bitbucket abbreviates the hashes it returns with a pull request (12 hex
+/// digits), so they are matched by prefix and resolved in the clone.
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketCommit {
+    hash: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BitbucketRepository {
+    full_name: String,
+}
+
+/// How git-ai authenticates against Bitbucket Cloud
+enum BitbucketAuth {
+    AccessToken(String),
+    AppPassword { username: String, password: String },
+}
+
+impl BitbucketAuth {
+    fn from_env() -> Option<Self> {
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        if let Some(token) = env(TOKEN_ENV) {
+            return Some(Self::AccessToken(token));
+        }
+        Some(Self::AppPassword {
+            username: env(USERNAME_ENV)?,
+            password: env(APP_PASSWORD_ENV)?,
+        })
+    }
+
+    fn header(&self) -> String {
+        match self {
+            Self::AccessToken(token) => format!("Bearer {}", token),
+            Self::AppPassword { username, password } => format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD
+                    .encode(format!("{}:{}", username, password))
+            ),
+        }
+    }
+
+    fn userinfo(&self) -> String {
+        match self {
+            Self::AccessToken(token) => format!("x-token-auth:{}", token),
+            Self::AppPassword { username, password } => format!("{}:{}", username, password),
+        }
+    }
+
+    /// The credential helper's (username, variable) sources. The username
+    /// ends up in a shell script, so an unusual one is left out.
+    fn helper_sources(&self) -> Vec<(&str, &str)> {
+        let mut sources = vec![("x-token-auth", TOKEN_ENV)];
+        if let Self::AppPassword { username, .. } = self
+            && username
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '@'))
+        {
+            sources.push((username.as_str(), APP_PASSWORD_ENV));
+        }
+        sources
+    }
+}
+
+/// Build and send an authenticated GET to a Bitbucket Cloud REST endpoint
+fn bitbucket_api_get(
+    endpoint: &str,
+    auth: &BitbucketAuth,
+) -> Result<crate::http::Response, String> {
+    let agent = crate::http::build_agent(Some(30));
+    let request = agent
+        .get(endpoint)
+        .set("Authorization", &auth.header())
+        .set("Accept", "application/json")
+        .set(
+            "User-Agent",
+            &format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+        );
+    crate::http::send_cached(request)
+}
+
+/// Whether Bitbucket's abbreviated `hash` names `commit_sha`
+fn hash_matches(hash: &str, commit_sha: &str) -> bool {
+    hash.len() >= 7 && commit_sha.starts_with(hash)
+}
+
+/// The merged PR whose merge commit (a merge, squash, or fast-forward) is
+/// `commit_sha`
+fn find_merged_pull_request(
+    pull_requests: Vec<BitbucketPullRequest>,
+    commit_sha: &str,
+) -> Option<BitbucketPullRequest> {
+    pull_requests.into_iter().find(|pr| {
+        pr.merge_commit
+            .as_ref()
+            .is_some_and(|commit| hash_matches(&commit.hash, commit_sha))
+    })
+}
+
+/// `git rev-parse` an abbreviated hash in the clone; None when it isn't there
+fn resolve_commit(clone_dir: &str, hash: &str) -> Option<String> {
+    exec_git(&[
+        "-C".to_string(),
+        clone_dir.to_string(),
+        "rev-parse".to_string(),
+        "--verify".to_string(),
+        format!("{}^{{commit}}", hash),
+    ])
+    .ok()
+    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    .filter(|sha| !sha.is_empty())
+}
+
+/// Query the Bitbucket Cloud API for recently merged PRs and find the one
+/// whose merge commit is the current commit. Returns None if there is none
+/// (this is not an error - just means this commit wasn't from a merged PR).
+pub fn get_bitbucket_ci_context(auth_mode: CloneAuthMode) -> Result<Option<CiContext>, GitAiError> {
+    let commit_sha = std::env::var("BITBUCKET_COMMIT").map_err(|_| {
+        GitAiError::Generic("BITBUCKET_COMMIT environment variable not set".to_string())
+    })?;
+    let full_name = std::env::var("BITBUCKET_REPO_FULL_NAME").map_err(|_| {
+        GitAiError::Generic("BITBUCKET_REPO_FULL_NAME environment variable not set".to_string())
+    })?;
+    let api_url = std::env::var("GIT_AI_BITBUCKET_API_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| BITBUCKET_API_URL.to_string());
+
+    println!("[Bitbucket Pipelines] Environment:");
+    println!("  BITBUCKET_COMMIT: {}", commit_sha);
+    println!("  BITBUCKET_REPO_FULL_NAME: {}", full_name);
+
+    bitbucket_merge_context(&api_url, &full_name, commit_sha, auth_mode)
+}
+
+/// Find the recently merged PR in `full_name` whose merge commit is
+/// `commit_sha`, clone the repository and describe the merge. Returns None
+/// when there is no such PR. Also used by CI systems that build Bitbucket
+/// Cloud repositories themselves.
+pub(crate) fn bitbucket_merge_context(
+    api_url: &str,
+    full_name: &str,
+    commit_sha: String,
+    auth_mode: CloneAuthMode,
+) -> Result<Option<CiContext>, GitAiError> {
+    let auth = BitbucketAuth::from_env().ok_or_else(|| {
+        GitAiError::Generic(format!(
+            "Neither {} nor {} and {} environment variables are set",
+            TOKEN_ENV, USERNAME_ENV, APP_PASSWORD_ENV
+        ))
+    })?;
+
+    let lookback_minutes = std::env::var("GIT_AI_CI_LOOKBACK_MINUTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(15);
+    let cutoff = Utc::now() - Duration::minutes(lookback_minutes);
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair(
+            "q",
+            &format!(
+                "state=\"MERGED\" AND updated_on > {}",
+                cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+            ),
+        )
+        .append_pair("sort", "-updated_on")
+        .append_pair("pagelen", "50")
+        .finish();
+    let endpoint = format!(
+        "{}/repositories/{}/pullrequests?{}",
+        api_url.trim_end_matches('/'),
+        full_name,
+        query
+    );
+
+    println!("[Bitbucket Pipelines] Querying API: {}", endpoint);
+
+    let response = bitbucket_api_get(&endpoint, &auth)
+        .map_err(|e| GitAiError::Generic(format!("Bitbucket API request failed: {}", e)))?;
+    if response.status_code != 200 {
+        return Err(GitAiError::Generic(format!(
+            "Bitbucket API returned status {}: {}",
+            response.status_code,
+            response.as_str().unwrap_or("unknown error")
+        )));
+    }
+    let page: BitbucketPage =
+        serde_json::from_str(response.as_str().unwrap_or("{}")).map_err(|e| {
+            GitAiError::Generic(format!("Failed to parse Bitbucket API response: {}", e))
+        })?;
+
+    println!(
+        "[Bitbucket Pipelines] Found {} recently merged PRs",
+        page.values.len()
+    );
+    for pr in &page.values {
+        println!(
+            "[Bitbucket Pipelines] PR #{}: \"{}\" ({} -> {}, merge_commit: {})",
+            pr.id,
+            pr.title.as_deref().unwrap_or("(no title)"),
+            pr.source.branch.name,
+            pr.destination.branch.name,
+            pr.merge_commit
+                .as_ref()
+                .map_or("(none)", |commit| commit.hash.as_str())
+        );
+    }
+
+    let Some(pr) = find_merged_pull_request(page.values, &commit_sha) else {
+        println!(
+            "[Bitbucket Pipelines] No recent PR found corresponding to this commit. Skipping..."
+        );
+        return Ok(None);
+    };
+    println!("[Bitbucket Pipelines] Found matching PR #{}", pr.id);
+
+    let server_url = "https://bitbucket.org";
+    let clone_url = format!("{}/{}.git", server_url, full_name);
+    // A PR from a fork keeps its commits (and notes) in the fork
+    let fork_clone_url = pr
+        .source
+        .repository
+        .as_ref()
+        .filter(|repo| repo.full_name != full_name)
+        .map(|repo| {
+            println!(
+                "[Bitbucket Pipelines] Detected fork PR: source repo {} differs from {}",
+                repo.full_name, full_name
+            );
+            format!("{}/{}.git", server_url, repo.full_name)
+        });
+
+    // Credential-helper mode keeps every URL as-is so runner insteadOf
+    // rewrites still apply; otherwise the credentials go in the URL
+    let use_credential_helper = auth_mode == CloneAuthMode::CredentialHelper;
+    let clone_auth_args = if use_credential_helper {
+        println!("[Bitbucket Pipelines] Using git credential helper for clone/fetch/push");
+        credential_helper_clone_args(&clone_url, &env_credential_helper(&auth.helper_sources()))
+    } else {
+        Vec::new()
+    };
+    let userinfo = auth.userinfo();
+    let authenticate = |url: &str| {
+        if use_credential_helper {
+            url.to_string()
+        } else {
+            url.replacen("https://", &format!("https://{}@", userinfo), 1)
+        }
+    };
+    let authenticated_url = authenticate(&clone_url);
+    let authenticated_fork_url = fork_clone_url.as_deref().map(&authenticate);
+
+    let clone_dir = "git-ai-ci-clone".to_string();
+    println!("[Bitbucket Pipelines] Cloning repository...");
+    preflight_ci_clone(Path::new(&clone_dir))?;
+    exec_git(&clone_args(
+        &clone_auth_args,
+        &pr.destination.branch.name,
+        &authenticated_url,
+        &clone_dir,
+    ))?;
+
+    // Bitbucket Cloud has no pull request refs, so fetch the source branch.
+    // It is often deleted on merge; a merge commit still reaches its commits
+    // as a parent, but a squash doesn't, and then there is nothing to carry
+    // authorship over from.
+    let source_url = authenticated_fork_url
+        .as_ref()
+        .unwrap_or(&authenticated_url);
+    println!(
+        "[Bitbucket Pipelines] Fetching PR commits from {}...",
+        pr.source.branch.name
+    );
+    if let Err(e) = exec_git(&[
+        "-C".to_string(),
+        clone_dir.clone(),
+        "fetch".to_string(),
+        source_url.clone(),
+        format!(
+            "refs/heads/{}:refs/bitbucket/pr/{}",
+            pr.source.branch.name, pr.id
+        ),
+    ]) {
+        println!(
+            "[Bitbucket Pipelines] Warning: could not fetch source branch {}: {}",
+            pr.source.branch.name, e
+        );
+    }
+
+    let Some(head_sha) = pr
+        .source
+        .commit
+        .as_ref()
+        .and_then(|commit| resolve_commit(&clone_dir, &commit.hash))
+    else {
+        println!(
+            "[Bitbucket Pipelines] PR #{}'s commits are no longer available (source branch deleted?). Skipping...",
+            pr.id
+        );
+        return Ok(None);
+    };
+
+    check_ci_clone_size(Path::new(&clone_dir))?;
+
+    let repo = find_repository_in_path(&clone_dir)?;
+
+    // The destination tip the PR was merged onto; without it the retain
+    // filter in CiContext::run_with_options is skipped (see gitlab.rs)
+    let base_sha = pr
+        .destination
+        .commit
+        .as_ref()
+        .and_then(|commit| resolve_commit(&clone_dir, &commit.hash))
+        .unwrap_or_default();
+
+    println!(
+        "[Bitbucket Pipelines] Created CiContext: merge_commit_sha={}, head_sha={}, head_ref={}, base_ref={}, base_sha={}",
+        commit_sha,
+        head_sha,
+        pr.source.branch.name,
+        pr.destination.branch.name,
+        if base_sha.is_empty() {
+            "(unavailable)"
+        } else {
+            &base_sha
+        }
+    );
+
+    Ok(Some(CiContext {
+        repo,
+        event: CiEvent::Merge {
+            merge_commit_sha: commit_sha,
+            head_ref: pr.source.branch.name,
+            head_sha,
+            base_ref: pr.destination.branch.name,
+            base_sha,
+            fork_clone_url: authenticated_fork_url,
+        },
+        temp_dir: PathBuf::from(clone_dir),
+    }))
+}
+
+/// Print the Bitbucket Pipelines YAML snippet to stdout for users to copy into their bitbucket-pipelines.yml
+pub fn print_bitbucket_pipelines_yaml() {
+    println!("Add the following to your bitbucket-pipelines.yml:");
+    println!();
+    println!("{}", BITBUCKET_PIPELINES_TEMPLATE_YAML);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitbucket_merged_pull_request_is_found_by_abbreviated_hash() {
+        let json = r#"{
+            "pagelen": 50,
+            "values": [
+                {
+                    "id": 3,
+                    "title": "Other",
+                    "source": {
+                        "branch": { "name": "other" },
+                        "commit": { "hash": "111111111111" },
+                        "repository": { "full_name": "acme/repo" }
+                    },
+                    "destination": {
+                        "branch": { "name": "main" },
+                        "commit": { "hash": "aaaaaaaaaaaa" },
+                        "repository": { "full_name": "acme/repo" }
+                    },
+                    "merge_commit": { "hash": "bbbbbbbbbbbb" }
+                },
+                {
+                    "id": 7,
+                    "title": "Feature",
+                    "source": {
+                        "branch": { "name": "feature" },
+                        "commit": { "hash": "222222222222" },
+                        "repository": null
+                    },
+                    "destination": {
+                        "branch": { "name": "main" },
+                        "commit": { "hash": "aaaaaaaaaaaa" },
+                        "repository": { "full_name": "acme/repo" }
+                    },
+                    "merge_commit": { "hash": "cccccccccccc" }
+                }
+            ]
+        }"#;
+        let page: BitbucketPage = serde_json::from_str(json).unwrap();
+        let pr = find_merged_pull_request(
+            page.values.clone(),
+            "cccccccccccccccccccccccccccccccccccccccc",
+        )
+        .expect("merged PR");
+        assert_eq!(pr.id, 7);
+        assert_eq!(pr.source.branch.name, "feature");
+        assert!(pr.source.repository.is_none());
+        assert!(
+            find_merged_pull_request(page.values, "dddddddddddddddddddddddddddddddddddddddd")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_hash_matches_needs_a_real_prefix() {
+        assert!(hash_matches("cccccccccccc", &"c".repeat(40)));
+        assert!(!hash_matches("", &"c".repeat(40)));
+        assert!(!hash_matches("ccc", &"c".repeat(40)));
+        assert!(!hash_matches("cccccccccccd", &"c".repeat(40)));
+    }
+
+    #[test]
+    fn test_bitbucket_auth_headers_and_clone_userinfo() {
+        let token = BitbucketAuth::AccessToken("secret".to_string());
+        assert_eq!(token.header(), "Bearer secret");
+        assert_eq!(token.userinfo(), "x-token-auth:secret");
+
+        let app_password = BitbucketAuth::AppPassword {
+            username: "dev".to_string(),
+            password: "pw".to_string(),
+        };
+        assert_eq!(app_password.header(), "Basic ZGV2OnB3");
+        assert_eq!(app_password.userinfo(), "dev:pw");
+        assert_eq!(
+            app_password.helper_sources(),
+            vec![("x-token-auth", TOKEN_ENV), ("dev", APP_PASSWORD_ENV)]
+        );
+        let odd = BitbucketAuth::AppPassword {
+            username: "dev; rm".to_string(),
+            password: "pw".to_string(),
+        };
+        assert_eq!(odd.helper_sources().len(), 1);
+    }
+}
@@ -1,6 +1,9 @@
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::authorship::rewrite::{RewriteEvent, handle_rewrite_event};
+use crate::ci::backports::{BackportLink, is_release_branch, link_backports};
+use crate::config::Config;
 use crate::error::GitAiError;
+use crate::git::mr_metadata::prefetch_recent_history;
 use crate::git::notes_api::{read_authorship_v3, read_note};
 use crate::git::refs::{
     AI_AUTHORSHIP_FORK_TRACKING_REF, copy_missing_notes_for_commits_from_ref, ref_exists,
@@ -67,6 +70,11 @@ pub enum CiRunResult {
     },
     /// Fork notes were fetched and preserved for a merge commit from a fork
     ForkNotesPreserved,
+    /// Backported commits were attributed from the commits they were picked from
+    BackportAuthorshipLinked {
+        #[allow(dead_code)]
+        backport_count: usize,
+    },
     /// No AI authorship to track (pre-git-ai commits or human-only code)
     NoAuthorshipAvailable,
 }
@@ -138,10 +146,33 @@ impl CiContext {
                     }
                 }
 
+                // Backport PRs into release branches: attribute their commits
+                // from the originals first, so every merge style below sees notes.
+                let backports = if fork_clone_url.is_none() {
+                    self.link_release_backports(head_sha, base_ref, base_sha, options)?
+                } else {
+                    Vec::new()
+                };
+
                 // Only handle squash or rebase-like merges.
                 // Skip simple merge commits (2+ parents) and fast-forward merges (merge commit == head).
                 let merge_commit = self.repo.find_commit(merge_commit_sha.clone())?;
                 let parent_count = merge_commit.parents().count();
+                if parent_count > 1 || merge_commit_sha == head_sha {
+                    // The PR commits themselves landed on the base branch
+                    if !backports.is_empty() {
+                        if options.skip_push {
+                            println!("Skipping authorship push (--skip-push). Done.");
+                        } else {
+                            println!("Pushing authorship...");
+                            self.repo.push_authorship("origin")?;
+                            println!("Pushed authorship. Done.");
+                        }
+                        return Ok(CiRunResult::BackportAuthorshipLinked {
+                            backport_count: backports.len(),
+                        });
+                    }
+                }
                 if parent_count > 1 {
                     // For fork PRs with merge commits, the merged commits keep
                     // their fork SHAs. Import only notes for those PR commits,
@@ -571,6 +602,26 @@ impl CiContext {
         Ok(())
     }
 
+    /// Warm the MR/issue metadata cache for the commits a merge brought in, so
+    /// later reports on this machine (or a restored CI cache) don't resolve
+    /// them one by one. Returns how many commits were cached.
+    pub fn prefetch_mr_metadata(&self) -> usize {
+        let CiEvent::Merge {
+            merge_commit_sha,
+            base_sha,
+            ..
+        } = &self.event
+        else {
+            return 0;
+        };
+        prefetch_recent_history(
+            &self.repo,
+            Config::get().issue_trackers(),
+            &format!("{}..{}", base_sha, merge_commit_sha),
+            merge_commit_sha,
+        )
+    }
+
     /// Fetch authorship notes from a fork repository URL into the fork tracking ref.
     /// Returns Ok(true) if notes were found and fetched,
     /// Ok(false) if no notes exist on the fork.
@@ -679,6 +730,49 @@ impl CiContext {
         Ok(copied)
     }
 
+    /// When the PR targets a configured release branch, attribute its
+    /// unattributed commits from the commits they were backported from.
+    fn link_release_backports(
+        &self,
+        head_sha: &str,
+        base_ref: &str,
+        base_sha: &str,
+        options: CiRunOptions,
+    ) -> Result<Vec<BackportLink>, GitAiError> {
+        let patterns = Config::get().release_branches();
+        if !is_release_branch(base_ref, patterns) {
+            return Ok(Vec::new());
+        }
+
+        let (_source_base, pr_commits) = self.original_pr_commits(head_sha, base_ref, base_sha);
+        println!(
+            "{} is a release branch; checking {} commit(s) for backports",
+            base_ref,
+            pr_commits.len()
+        );
+        let links = link_backports(
+            &self.repo,
+            &pr_commits,
+            base_ref,
+            base_sha,
+            patterns,
+            options.skip_fetch_base,
+        )?;
+        for link in &links {
+            println!(
+                "Backport {} <- {} on {}{}",
+                link.backport,
+                link.source,
+                link.source_ref,
+                link.merge_request
+                    .as_deref()
+                    .map(|mr| format!(" ({})", mr))
+                    .unwrap_or_default()
+            );
+        }
+        Ok(links)
+    }
+
     fn has_notes_for_any_commit(&self, commit_shas: &[String]) -> Result<bool, GitAiError> {
         // Backend-aware: on the HTTP backend notes live in the notes-db cache,
         // so a refs/notes/ai-only check would always report "no notes".
@@ -0,0 +1,184 @@
+//! CircleCI builds GitHub, GitLab and Bitbucket repositories and has no
+//! merge event of its own: the merged PR is found through the VCS provider's
+//! API from `CIRCLE_SHA1` (and `CIRCLE_PULL_REQUEST`, when the build is for
+//! one), authenticated with the provider's token from a CircleCI context.
+
+use crate::ci::bitbucket::{BITBUCKET_API_URL, bitbucket_merge_context};
+use crate::ci::ci_context::CiContext;
+use crate::ci::git_auth::CloneAuthMode;
+use crate::ci::github::{PullRequestLookup, github_merge_context};
+use crate::ci::gitlab::{GitLabProjectRef, gitlab_merge_context};
+use crate::ci::jenkins::{github_api_url, parse_remote};
+use crate::error::GitAiError;
+
+const CIRCLECI_TEMPLATE_YAML: &str = include_str!("workflow_templates/circleci.yml");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Vcs {
+    GitHub,
+    GitLab,
+    Bitbucket,
+}
+
+impl Vcs {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "github" => Some(Self::GitHub),
+            "gitlab" => Some(Self::GitLab),
+            "bitbucket" => Some(Self::Bitbucket),
+            _ => None,
+        }
+    }
+}
+
+/// Which provider hosts `host`: `GIT_AI_CIRCLECI_VCS` when set, else a guess
+/// from the host name
+fn detect_vcs(host: &str, configured: Option<&str>) -> Result<Vcs, String> {
+    if let Some(configured) = configured {
+        return Vcs::parse(configured).ok_or_else(|| {
+            format!(
+                "Invalid GIT_AI_CIRCLECI_VCS '{}'. Expected github, gitlab or bitbucket",
+                configured
+            )
+        });
+    }
+    let host = host.to_ascii_lowercase();
+    if host.contains("github") {
+        Ok(Vcs::GitHub)
+    } else if host.contains("gitlab") {
+        Ok(Vcs::GitLab)
+    } else if host == "bitbucket.org" {
+        Ok(Vcs::Bitbucket)
+    } else {
+        Err(format!(
+            "Can't tell which VCS provider hosts {}; set GIT_AI_CIRCLECI_VCS to github, gitlab or bitbucket",
+            host
+        ))
+    }
+}
+
+/// The PR number at the end of a `CIRCLE_PULL_REQUEST` URL
+/// (`.../pull/12`, `.../-/merge_requests/12`, `.../pull-requests/12`)
+fn pull_request_number(url: &str) -> Option<u64> {
+    url.trim_end_matches('/').rsplit('/').next()?.parse().ok()
+}
+
+/// Find the merged PR a CircleCI build is for. A branch build is matched to
+/// the PR merged as `CIRCLE_SHA1`; a build with `CIRCLE_PULL_REQUEST` looks
+/// up that PR and proceeds once it's merged. Returns None when the build
+/// isn't for a merged PR.
+pub fn get_circleci_context(auth_mode: CloneAuthMode) -> Result<Option<CiContext>, GitAiError> {
+    let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+    let commit_sha = env("CIRCLE_SHA1").ok_or_else(|| {
+        GitAiError::Generic("CIRCLE_SHA1 environment variable not set".to_string())
+    })?;
+    let repository_url = env("CIRCLE_REPOSITORY_URL").ok_or_else(|| {
+        GitAiError::Generic("CIRCLE_REPOSITORY_URL environment variable not set".to_string())
+    })?;
+    let pull_request = env("CIRCLE_PULL_REQUEST");
+
+    println!("[CircleCI] Environment:");
+    println!("  CIRCLE_REPOSITORY_URL: {}", repository_url);
+    println!("  CIRCLE_SHA1: {}", commit_sha);
+    println!(
+        "  CIRCLE_BRANCH: {}",
+        env("CIRCLE_BRANCH").unwrap_or_default()
+    );
+    if let Some(pull_request) = &pull_request {
+        println!("  CIRCLE_PULL_REQUEST: {}", pull_request);
+    }
+
+    let (host, path) = parse_remote(&repository_url).ok_or_else(|| {
+        GitAiError::Generic(format!(
+            "Unrecognized CIRCLE_REPOSITORY_URL: {}",
+            repository_url
+        ))
+    })?;
+    let vcs =
+        detect_vcs(&host, env("GIT_AI_CIRCLECI_VCS").as_deref()).map_err(GitAiError::Generic)?;
+    let pull_request_number = pull_request
+        .as_deref()
+        .map(|url| {
+            pull_request_number(url)
+                .ok_or_else(|| GitAiError::Generic(format!("Invalid CIRCLE_PULL_REQUEST: {}", url)))
+        })
+        .transpose()?;
+
+    match vcs {
+        Vcs::GitHub => {
+            let api_url = env("GITHUB_API_URL").unwrap_or_else(|| github_api_url(&host));
+            let lookup = match pull_request_number {
+                Some(number) => PullRequestLookup::Number(number),
+                None => PullRequestLookup::Commit(commit_sha),
+            };
+            github_merge_context(
+                &api_url,
+                &path,
+                env("GITHUB_TOKEN").as_deref(),
+                lookup,
+                auth_mode,
+            )
+        }
+        Vcs::GitLab => {
+            let server_url = env("GITLAB_URL").unwrap_or_else(|| format!("https://{}", host));
+            let project = GitLabProjectRef {
+                api_url: env("GITLAB_API_URL")
+                    .unwrap_or_else(|| format!("{}/api/v4", server_url.trim_end_matches('/'))),
+                project_id: url::form_urlencoded::byte_serialize(path.as_bytes()).collect(),
+                commit_sha,
+                server_url,
+                project_path: path,
+            };
+            gitlab_merge_context(project, pull_request_number, auth_mode)
+        }
+        Vcs::Bitbucket => {
+            // Matched by merge commit only, so a PR build (whose CIRCLE_SHA1
+            // is the PR head) finds nothing until the merge is built
+            let api_url =
+                env("GIT_AI_BITBUCKET_API_URL").unwrap_or_else(|| BITBUCKET_API_URL.to_string());
+            bitbucket_merge_context(&api_url, &path, commit_sha, auth_mode)
+        }
+    }
+}
+
+/// Print the CircleCI config to stdout for users to merge into their .circleci/config.yml
+pub fn print_circleci_config_yaml() {
+    println!("Add the following to your .circleci/config.yml:");
+    println!();
+    println!("{}", CIRCLECI_TEMPLATE_YAML);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_vcs_prefers_the_configured_provider() {
+        assert_eq!(detect_vcs("github.com", None), Ok(Vcs::GitHub));
+        assert_eq!(detect_vcs("gitlab.com", None), Ok(Vcs::GitLab));
+        assert_eq!(detect_vcs("bitbucket.org", None), Ok(Vcs::Bitbucket));
+        assert_eq!(
+            detect_vcs("git.example.com", Some("GitHub")),
+            Ok(Vcs::GitHub)
+        );
+        assert!(detect_vcs("git.example.com", None).is_err());
+        assert!(detect_vcs("github.com", Some("gitea")).is_err());
+    }
+
+    #[test]
+    fn test_pull_request_number_reads_each_providers_url() {
+        assert_eq!(
+            pull_request_number("https://github.com/acme/repo/pull/42"),
+            Some(42)
+        );
+        assert_eq!(
+            pull_request_number("https://gitlab.com/acme/repo/-/merge_requests/7/"),
+            Some(7)
+        );
+        assert_eq!(
+            pull_request_number("https://bitbucket.org/acme/repo/pull-requests/3"),
+            Some(3)
+        );
+        assert_eq!(pull_request_number("https://github.com/acme/repo"), None);
+    }
+}
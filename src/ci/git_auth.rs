@@ -0,0 +1,160 @@
+//! How CI clones authenticate against the forge.
+//!
+//! The default splices the token into the clone URL. That fights with
+//! runners that configure `url.<base>.insteadOf` (the rewritten prefix no
+//! longer matches) or their own credential helpers (the URL credentials win).
+//! Credential-helper mode leaves URLs untouched and lets git's credential
+//! machinery ask for the token instead.
+
+/// Which way CI clone/fetch/push commands get their credentials
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CloneAuthMode {
+    /// Embed the token in the clone URL
+    #[default]
+    TokenUrl,
+    /// Keep URLs as-is and answer git's credential requests from the token
+    /// environment variables
+    CredentialHelper,
+}
+
+impl CloneAuthMode {
+    pub fn from_flag(credential_helper: bool) -> Self {
+        if credential_helper {
+            Self::CredentialHelper
+        } else {
+            Self::TokenUrl
+        }
+    }
+}
+
+/// A `credential.helper` command that answers `get` with the first of
+/// `sources` (username, environment variable) whose variable is set.
+///
+/// The helper reads the variables when git runs it, so the token never lands
+/// on a command line or in the clone's config, and only fills in what earlier
+/// helpers (the runner's own) left unanswered.
+pub fn env_credential_helper(sources: &[(&str, &str)]) -> String {
+    let mut script = String::from("!f() { test \"$1\" = get || return 0; ");
+    for (index, (username, env_var)) in sources.iter().enumerate() {
+        script.push_str(if index == 0 { "if" } else { "elif" });
+        script.push_str(&format!(
+            " [ -n \"${env_var}\" ]; then echo username={username}; echo \"password=${env_var}\"; "
+        ));
+    }
+    if !sources.is_empty() {
+        script.push_str("fi; ");
+    }
+    script.push_str("}; f");
+    script
+}
+
+/// The `scheme://host` part of an http(s) URL, which is what git matches
+/// `credential.<url>.*` keys against
+fn credential_scope(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    if scheme != "https" && scheme != "http" {
+        return None;
+    }
+    let authority = rest.split('/').next()?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    (!host.is_empty()).then(|| format!("{}://{}", scheme, host))
+}
+
+/// `git clone` options that install `helper` for `clone_url`'s host. `clone
+/// --config` applies it to the clone itself and keeps it in the new repo's
+/// config for the later fetches and pushes.
+pub fn credential_helper_clone_args(clone_url: &str, helper: &str) -> Vec<String> {
+    match credential_scope(clone_url) {
+        Some(scope) => vec![
+            "--config".to_string(),
+            format!("credential.{}.helper={}", scope, helper),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// `git clone --branch <branch> <url> <dir>` with `auth_args` as clone options
+pub fn clone_args(auth_args: &[String], branch: &str, url: &str, dir: &str) -> Vec<String> {
+    let mut args = vec!["clone".to_string()];
+    args.extend(auth_args.iter().cloned());
+    args.extend([
+        "--branch".to_string(),
+        branch.to_string(),
+        url.to_string(),
+        dir.to_string(),
+    ]);
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn credential_scope_keeps_scheme_and_host_only() {
+        assert_eq!(
+            credential_scope("https://github.com/acme/repo.git").as_deref(),
+            Some("https://github.com")
+        );
+        assert_eq!(
+            credential_scope("http://user@gitlab.internal:8080/group/repo.git").as_deref(),
+            Some("http://gitlab.internal:8080")
+        );
+        assert_eq!(credential_scope("git@github.com:acme/repo.git"), None);
+        assert!(credential_helper_clone_args("ssh://git@host/repo", "!true").is_empty());
+    }
+
+    #[test]
+    fn env_credential_helper_answers_git_credential_fill_from_first_set_var() {
+        let helper = env_credential_helper(&[
+            ("oauth2", "GIT_AI_TEST_PRIMARY_TOKEN"),
+            ("gitlab-ci-token", "GIT_AI_TEST_FALLBACK_TOKEN"),
+        ]);
+        let args = credential_helper_clone_args("https://gitlab.example.com/g/r.git", &helper);
+
+        let fill = |envs: &[(&str, &str)]| {
+            let mut command = Command::new("git");
+            command
+                .args(["-c", &args[1], "-c", "credential.interactive=false"])
+                .args(["credential", "fill"])
+                .env_remove("GIT_AI_TEST_PRIMARY_TOKEN")
+                .env_remove("GIT_AI_TEST_FALLBACK_TOKEN")
+                .env("GIT_TERMINAL_PROMPT", "0")
+                .envs(envs.iter().copied())
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null());
+            let mut child = command.spawn().expect("spawn git credential fill");
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(b"protocol=https\nhost=gitlab.example.com\n\n")
+                .unwrap();
+            String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap()
+        };
+
+        let both = fill(&[
+            ("GIT_AI_TEST_PRIMARY_TOKEN", "primary-secret"),
+            ("GIT_AI_TEST_FALLBACK_TOKEN", "fallback-secret"),
+        ]);
+        assert!(both.contains("username=oauth2\n"), "{}", both);
+        assert!(both.contains("password=primary-secret\n"), "{}", both);
+
+        let fallback = fill(&[("GIT_AI_TEST_FALLBACK_TOKEN", "fallback-secret")]);
+        assert!(
+            fallback.contains("username=gitlab-ci-token\n"),
+            "{}",
+            fallback
+        );
+        assert!(
+            fallback.contains("password=fallback-secret\n"),
+            "{}",
+            fallback
+        );
+    }
+}
@@ -1,10 +1,14 @@
 use crate::ci::ci_context::{CiContext, CiEvent};
+use crate::ci::git_auth::{
+    CloneAuthMode, clone_args, credential_helper_clone_args, env_credential_helper,
+};
+use crate::disk_budget::{check_ci_clone_size, preflight_ci_clone};
 use crate::error::GitAiError;
 use crate::git::repository::exec_git;
 use crate::git::repository::find_repository_in_path;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const GITHUB_CI_TEMPLATE_YAML: &str = include_str!("workflow_templates/github.yaml");
 
@@ -42,10 +46,122 @@ struct GithubCiRepository {
     clone_url: String,
 }
 
-pub fn get_github_ci_context() -> Result<Option<CiContext>, GitAiError> {
+/// A pull request from the REST API's "list pull requests associated with a
+/// commit" endpoint. Unlike the event payload it has no `merged` flag, only
+/// `merged_at`, and `head.repo` is null once a fork is deleted.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct GithubApiPullRequest {
+    number: u32,
+    base: GithubCiPullRequestReference,
+    head: GithubApiPullRequestReference,
+    #[serde(default)]
+    merged_at: Option<String>,
+    #[serde(default)]
+    merge_commit_sha: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct GithubApiPullRequestReference {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    sha: String,
+    #[serde(default)]
+    repo: Option<GithubCiRepository>,
+}
+
+/// Clone URLs with credentials applied for the chosen [`CloneAuthMode`]
+struct GithubCloneAuth {
+    url: String,
+    fork_url: Option<String>,
+    clone_args: Vec<String>,
+}
+
+fn github_clone_auth(
+    auth_mode: CloneAuthMode,
+    clone_url: &str,
+    fork_clone_url: Option<String>,
+) -> GithubCloneAuth {
+    let token = match auth_mode {
+        CloneAuthMode::TokenUrl => std::env::var("GITHUB_TOKEN").ok(),
+        CloneAuthMode::CredentialHelper => None,
+    };
+    // In credential-helper mode the URLs stay as-is so runner insteadOf
+    // rewrites still apply, and the helper supplies GITHUB_TOKEN on demand
+    let clone_args = match auth_mode {
+        CloneAuthMode::TokenUrl => Vec::new(),
+        CloneAuthMode::CredentialHelper => credential_helper_clone_args(
+            clone_url,
+            &env_credential_helper(&[("x-access-token", "GITHUB_TOKEN")]),
+        ),
+    };
+    let authenticate = |url: &str| match &token {
+        Some(token) => authenticate_clone_url(url, token),
+        None => url.to_string(),
+    };
+    GithubCloneAuth {
+        url: authenticate(clone_url),
+        fork_url: fork_clone_url.as_deref().map(authenticate),
+        clone_args,
+    }
+}
+
+/// The head repo's clone URL when it differs from the base repo's, i.e. a
+/// fork PR
+fn fork_clone_url(base_clone_url: &str, head_clone_url: Option<&str>) -> Option<String> {
+    let fork_url = head_clone_url.filter(|url| *url != base_clone_url)?;
+    println!(
+        "Detected fork PR: head repo {} differs from base repo {}",
+        fork_url, base_clone_url
+    );
+    Some(fork_url.to_string())
+}
+
+/// Clone `base_ref` and fetch PR `pr_number`'s commits for a merge event
+fn clone_merged_pull_request(
+    auth: &GithubCloneAuth,
+    pr_number: u32,
+    base_ref: &str,
+    event: CiEvent,
+) -> Result<CiContext, GitAiError> {
+    let clone_dir = "git-ai-ci-clone".to_string();
+    preflight_ci_clone(Path::new(&clone_dir))?;
+    exec_git(&clone_args(
+        &auth.clone_args,
+        base_ref,
+        &auth.url,
+        &clone_dir,
+    ))?;
+
+    // Fetch PR commits using GitHub's special PR refs
+    // This is necessary because the PR branch may be deleted after merge
+    // but GitHub keeps the commits accessible via pull/{number}/head
+    // We store the fetched commits in a local ref to ensure they're kept
+    exec_git(&[
+        "-C".to_string(),
+        clone_dir.clone(),
+        "fetch".to_string(),
+        auth.url.clone(),
+        format!("pull/{}/head:refs/github/pr/{}", pr_number, pr_number),
+    ])?;
+
+    check_ci_clone_size(Path::new(&clone_dir))?;
+
+    let repo = find_repository_in_path(&clone_dir.clone())?;
+
+    Ok(CiContext {
+        repo,
+        event,
+        temp_dir: PathBuf::from(clone_dir),
+    })
+}
+
+pub fn get_github_ci_context(auth_mode: CloneAuthMode) -> Result<Option<CiContext>, GitAiError> {
     let env_event_name = std::env::var("GITHUB_EVENT_NAME").unwrap_or_default();
     let env_event_path = std::env::var("GITHUB_EVENT_PATH").unwrap_or_default();
 
+    if env_event_name == "push" {
+        return get_github_push_context(auth_mode);
+    }
     if env_event_name != "pull_request" {
         return Ok(None);
     }
@@ -66,74 +182,28 @@ pub fn get_github_ci_context() -> Result<Option<CiContext>, GitAiError> {
     let base_sha = pull_request.base.sha.clone();
     let clone_url = pull_request.base.repo.clone_url.clone();
 
-    // Detect fork: if head repo URL differs from base repo URL, this is a fork PR
-    let fork_clone_url = if pull_request.head.repo.clone_url != pull_request.base.repo.clone_url {
-        let fork_url = pull_request.head.repo.clone_url.clone();
-        println!(
-            "Detected fork PR: head repo {} differs from base repo {}",
-            fork_url, clone_url
-        );
-        Some(fork_url)
-    } else {
-        None
-    };
-
+    let auth = github_clone_auth(
+        auth_mode,
+        &clone_url,
+        fork_clone_url(&clone_url, Some(&pull_request.head.repo.clone_url)),
+    );
     let clone_dir = "git-ai-ci-clone".to_string();
-
-    // Authenticate the clone URL with GITHUB_TOKEN if available
-    let authenticated_url = if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-        authenticate_clone_url(&clone_url, &token)
-    } else {
-        clone_url
-    };
-
-    // Authenticate the fork clone URL if this is a fork PR.
-    let authenticated_fork_url = fork_clone_url.map(|fork_url| {
-        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-            authenticate_clone_url(&fork_url, &token)
-        } else {
-            fork_url
-        }
-    });
+    let clone_auth_args = auth.clone_args.clone();
+    let authenticated_url = auth.url.clone();
+    let authenticated_fork_url = auth.fork_url.clone();
 
     if pull_request.merged
         && let Some(merge_commit_sha) = pull_request.merge_commit_sha
     {
-        // Clone the repo
-        exec_git(&[
-            "clone".to_string(),
-            "--branch".to_string(),
-            base_ref.clone(),
-            authenticated_url.clone(),
-            clone_dir.clone(),
-        ])?;
-
-        // Fetch PR commits using GitHub's special PR refs
-        // This is necessary because the PR branch may be deleted after merge
-        // but GitHub keeps the commits accessible via pull/{number}/head
-        // We store the fetched commits in a local ref to ensure they're kept
-        exec_git(&[
-            "-C".to_string(),
-            clone_dir.clone(),
-            "fetch".to_string(),
-            authenticated_url.clone(),
-            format!("pull/{}/head:refs/github/pr/{}", pr_number, pr_number),
-        ])?;
-
-        let repo = find_repository_in_path(&clone_dir.clone())?;
-
-        return Ok(Some(CiContext {
-            repo,
-            event: CiEvent::Merge {
-                merge_commit_sha,
-                head_ref: head_ref.clone(),
-                head_sha: head_sha.clone(),
-                base_ref: base_ref.clone(),
-                base_sha,
-                fork_clone_url: authenticated_fork_url,
-            },
-            temp_dir: PathBuf::from(clone_dir),
-        }));
+        let event = CiEvent::Merge {
+            merge_commit_sha,
+            head_ref,
+            head_sha,
+            base_ref: base_ref.clone(),
+            base_sha,
+            fork_clone_url: authenticated_fork_url,
+        };
+        return clone_merged_pull_request(&auth, pr_number, &base_ref, event).map(Some);
     }
 
     if event_payload.action.as_deref() != Some("synchronize") {
@@ -159,13 +229,13 @@ pub fn get_github_ci_context() -> Result<Option<CiContext>, GitAiError> {
     // push, the previous head is already reachable from the current PR ref. For
     // a non-fast-forward UI rebase, fetching by SHA keeps the old commits
     // available long enough for the local rebase rewrite command.
-    exec_git(&[
-        "clone".to_string(),
-        "--branch".to_string(),
-        base_ref.clone(),
-        authenticated_url.clone(),
-        clone_dir.clone(),
-    ])?;
+    preflight_ci_clone(Path::new(&clone_dir))?;
+    exec_git(&clone_args(
+        &clone_auth_args,
+        &base_ref,
+        &authenticated_url,
+        &clone_dir,
+    ))?;
 
     exec_git(&[
         "-C".to_string(),
@@ -205,6 +275,8 @@ pub fn get_github_ci_context() -> Result<Option<CiContext>, GitAiError> {
         ])?;
     }
 
+    check_ci_clone_size(Path::new(&clone_dir))?;
+
     let repo = find_repository_in_path(&clone_dir.clone())?;
 
     Ok(Some(CiContext {
@@ -226,6 +298,150 @@ pub fn get_github_ci_context() -> Result<Option<CiContext>, GitAiError> {
     }))
 }
 
+/// Build and send a GET to a GitHub REST endpoint, authenticated with
+/// `token` when there is one (public repos work without, at a lower rate
+/// limit)
+fn github_api_get(endpoint: &str, token: Option<&str>) -> Result<crate::http::Response, String> {
+    let agent = crate::http::build_agent(Some(30));
+    let mut request = agent
+        .get(endpoint)
+        .set("Accept", "application/vnd.github+json")
+        .set("X-GitHub-Api-Version", "2022-11-28")
+        .set(
+            "User-Agent",
+            &format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+        );
+    if let Some(token) = token {
+        request = request.set("Authorization", &format!("Bearer {}", token));
+    }
+    crate::http::send_cached(request)
+}
+
+/// The merged PR whose merge commit (a merge, squash, or the last rebased
+/// commit) is `commit_sha`
+fn find_merged_pull_request(
+    pull_requests: Vec<GithubApiPullRequest>,
+    commit_sha: &str,
+) -> Option<GithubApiPullRequest> {
+    pull_requests
+        .into_iter()
+        .find(|pr| pr.merged_at.is_some() && pr.merge_commit_sha.as_deref() == Some(commit_sha))
+}
+
+/// For a `push` to a branch, ask the REST API which merged PR produced
+/// `GITHUB_SHA` and treat it as that PR's merge. Returns None when the push
+/// didn't come from a merged PR (a direct push, say) - that's not an error.
+fn get_github_push_context(auth_mode: CloneAuthMode) -> Result<Option<CiContext>, GitAiError> {
+    let commit_sha = std::env::var("GITHUB_SHA")
+        .map_err(|_| GitAiError::Generic("GITHUB_SHA environment variable not set".to_string()))?;
+    let repository = std::env::var("GITHUB_REPOSITORY").map_err(|_| {
+        GitAiError::Generic("GITHUB_REPOSITORY environment variable not set".to_string())
+    })?;
+    let api_url = std::env::var("GITHUB_API_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| "https://api.github.com".to_string());
+    let token = std::env::var("GITHUB_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty());
+
+    println!("[GitHub CI] Push of {} to {}", commit_sha, repository);
+
+    github_merge_context(
+        &api_url,
+        &repository,
+        token.as_deref(),
+        PullRequestLookup::Commit(commit_sha),
+        auth_mode,
+    )
+}
+
+/// How [`github_merge_context`] finds the merged PR
+pub(crate) enum PullRequestLookup {
+    /// The PR whose merge commit this is
+    Commit(String),
+    /// This PR, once it's merged
+    Number(u64),
+}
+
+fn github_api_json<T: serde::de::DeserializeOwned>(
+    endpoint: &str,
+    token: Option<&str>,
+) -> Result<T, GitAiError> {
+    println!("[GitHub CI] Querying API: {}", endpoint);
+    let response = github_api_get(endpoint, token)
+        .map_err(|e| GitAiError::Generic(format!("GitHub API request failed: {}", e)))?;
+    if response.status_code != 200 {
+        return Err(GitAiError::Generic(format!(
+            "GitHub API returned status {}: {}",
+            response.status_code,
+            response.as_str().unwrap_or("unknown error")
+        )));
+    }
+    serde_json::from_str(response.as_str().unwrap_or_default())
+        .map_err(|e| GitAiError::Generic(format!("Failed to parse GitHub API response: {}", e)))
+}
+
+/// Find the merged PR in `repository` through the REST API at `api_url`,
+/// clone it and describe its merge. Returns None when there is no such PR.
+/// Also used by CI systems that build GitHub repositories themselves.
+pub(crate) fn github_merge_context(
+    api_url: &str,
+    repository: &str,
+    token: Option<&str>,
+    lookup: PullRequestLookup,
+    auth_mode: CloneAuthMode,
+) -> Result<Option<CiContext>, GitAiError> {
+    let api_url = api_url.trim_end_matches('/');
+    let found = match lookup {
+        PullRequestLookup::Commit(commit_sha) => {
+            let pull_requests: Vec<GithubApiPullRequest> = github_api_json(
+                &format!(
+                    "{}/repos/{}/commits/{}/pulls",
+                    api_url, repository, commit_sha
+                ),
+                token,
+            )?;
+            find_merged_pull_request(pull_requests, &commit_sha).map(|pr| (pr, commit_sha))
+        }
+        PullRequestLookup::Number(number) => {
+            let pr: GithubApiPullRequest = github_api_json(
+                &format!("{}/repos/{}/pulls/{}", api_url, repository, number),
+                token,
+            )?;
+            match &pr.merge_commit_sha {
+                Some(sha) if pr.merged_at.is_some() => {
+                    let sha = sha.clone();
+                    Some((pr, sha))
+                }
+                _ => None,
+            }
+        }
+    };
+    let Some((pr, merge_commit_sha)) = found else {
+        println!("[GitHub CI] No merged PR found for this commit. Skipping...");
+        return Ok(None);
+    };
+    println!("[GitHub CI] Found merged PR #{}", pr.number);
+
+    let clone_url = pr.base.repo.clone_url.clone();
+    let head_clone_url = pr.head.repo.as_ref().map(|repo| repo.clone_url.as_str());
+    let auth = github_clone_auth(
+        auth_mode,
+        &clone_url,
+        fork_clone_url(&clone_url, head_clone_url),
+    );
+    let event = CiEvent::Merge {
+        merge_commit_sha,
+        head_ref: pr.head.ref_name,
+        head_sha: pr.head.sha,
+        base_ref: pr.base.ref_name.clone(),
+        base_sha: pr.base.sha,
+        fork_clone_url: auth.fork_url.clone(),
+    };
+    clone_merged_pull_request(&auth, pr.number, &pr.base.ref_name, event).map(Some)
+}
+
 fn authenticate_clone_url(clone_url: &str, token: &str) -> String {
     format!(
         "https://x-access-token:{}@{}",
@@ -234,6 +450,14 @@ fn authenticate_clone_url(clone_url: &str, token: &str) -> String {
     )
 }
 
+/// Print the GitHub Actions workflow to stdout for users to save as
+/// .github/workflows/git-ai.yaml themselves
+pub fn print_github_actions_yaml() {
+    println!("Save the following as .github/workflows/git-ai.yaml:");
+    println!();
+    println!("{}", GITHUB_CI_TEMPLATE_YAML);
+}
+
 /// Install or update the GitHub Actions workflow in the current repository
 /// Writes the embedded template to .github/workflows/git-ai.yaml at the repo root
 pub fn install_github_ci_workflow() -> Result<PathBuf, GitAiError> {
@@ -314,4 +538,66 @@ mod tests {
         assert_eq!(pull_request.base.ref_name, "main");
         assert_eq!(pull_request.head.ref_name, "feature");
     }
+
+    #[test]
+    fn test_push_matches_the_merged_pull_request_by_merge_commit() {
+        let json = r#"[
+            {
+                "number": 7,
+                "base": {
+                    "ref": "main",
+                    "sha": "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                    "repo": { "clone_url": "https://github.com/org/repo.git" }
+                },
+                "head": {
+                    "ref": "open-pr",
+                    "sha": "3333333333333333333333333333333333333333",
+                    "repo": { "clone_url": "https://github.com/org/repo.git" }
+                },
+                "merged_at": null,
+                "merge_commit_sha": "cccccccccccccccccccccccccccccccccccccccc"
+            },
+            {
+                "number": 42,
+                "base": {
+                    "ref": "main",
+                    "sha": "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                    "repo": { "clone_url": "https://github.com/org/repo.git" }
+                },
+                "head": {
+                    "ref": "feature",
+                    "sha": "2222222222222222222222222222222222222222",
+                    "repo": null
+                },
+                "merged_at": "2026-01-01T00:00:00Z",
+                "merge_commit_sha": "cccccccccccccccccccccccccccccccccccccccc"
+            }
+        ]"#;
+
+        let pull_requests: Vec<GithubApiPullRequest> = serde_json::from_str(json).unwrap();
+        let pr = find_merged_pull_request(
+            pull_requests.clone(),
+            "cccccccccccccccccccccccccccccccccccccccc",
+        )
+        .expect("merged PR");
+        assert_eq!(pr.number, 42);
+        assert_eq!(pr.base.ref_name, "main");
+        // A deleted fork leaves no head repo to fetch from
+        assert!(pr.head.repo.is_none());
+        assert!(
+            find_merged_pull_request(pull_requests, "dddddddddddddddddddddddddddddddddddddddd")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_fork_clone_url_only_for_a_different_head_repo() {
+        let base = "https://github.com/org/repo.git";
+        assert_eq!(fork_clone_url(base, Some(base)), None);
+        assert_eq!(fork_clone_url(base, None), None);
+        assert_eq!(
+            fork_clone_url(base, Some("https://github.com/fork/repo.git")).as_deref(),
+            Some("https://github.com/fork/repo.git")
+        );
+    }
 }
@@ -1,10 +1,14 @@
 use crate::ci::ci_context::{CiContext, CiEvent};
+use crate::ci::git_auth::{
+    CloneAuthMode, clone_args, credential_helper_clone_args, env_credential_helper,
+};
+use crate::disk_budget::{check_ci_clone_size, preflight_ci_clone};
 use crate::error::GitAiError;
 use crate::git::repository::exec_git;
 use crate::git::repository::find_repository_in_path;
 use chrono::{Duration, Utc};
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const GITLAB_CI_TEMPLATE_YAML: &str = include_str!("workflow_templates/gitlab.yaml");
 
@@ -21,6 +25,9 @@ struct GitLabMergeRequest {
     squash: Option<bool>,
     source_project_id: u64,
     target_project_id: u64,
+    /// Only on the single-MR endpoint
+    #[serde(default)]
+    state: Option<String>,
 }
 
 /// GitLab Project API response (minimal fields for fork detection)
@@ -76,7 +83,8 @@ struct GitLabDiffRefs {
 /// Build and send an authenticated GET to a GitLab REST endpoint.
 ///
 /// Every GitLab API call in this module shares the same shape: 30s timeout,
-/// `User-Agent: git-ai/<version>`, one of `PRIVATE-TOKEN` / `JOB-TOKEN`.
+/// `User-Agent: git-ai/<version>`, one of `PRIVATE-TOKEN` / `JOB-TOKEN`, and
+/// the shared HTTP cache.
 /// Centralizing it here keeps the call sites focused on what they're after
 /// (URL + parsing) rather than re-stating transport boilerplate.
 fn gitlab_api_get(
@@ -89,7 +97,9 @@ fn gitlab_api_get(
         "User-Agent",
         &format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
     );
-    crate::http::send(request)
+    // Conditional requests keep repeat lookups off the rate limit of
+    // self-hosted instances
+    crate::http::send_cached(request)
 }
 
 /// Fetch the SHA we want to feed into `CiEvent::Merge.base_sha` (the
@@ -135,10 +145,23 @@ fn fetch_mr_base_sha(
     }
 }
 
+/// Where the GitLab project lives and which commit is being built. GitLab CI
+/// provides these as `CI_*` variables; other CI systems building a GitLab
+/// project work them out themselves.
+pub(crate) struct GitLabProjectRef {
+    /// `<server>/api/v4`
+    pub api_url: String,
+    /// Numeric id or URL-encoded path
+    pub project_id: String,
+    pub commit_sha: String,
+    pub server_url: String,
+    pub project_path: String,
+}
+
 /// Query GitLab API for recently merged MRs and find one matching the current commit SHA.
 /// Returns None if no matching MR is found (this is not an error - just means this commit
 /// wasn't from a merged MR).
-pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
+pub fn get_gitlab_ci_context(auth_mode: CloneAuthMode) -> Result<Option<CiContext>, GitAiError> {
     // Read required environment variables
     let api_url = std::env::var("CI_API_V4_URL").map_err(|_| {
         GitAiError::Generic("CI_API_V4_URL environment variable not set".to_string())
@@ -161,6 +184,35 @@ pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
     println!("  CI_PROJECT_ID: {}", project_id);
     println!("  CI_PROJECT_PATH: {}", project_path);
 
+    gitlab_merge_context(
+        GitLabProjectRef {
+            api_url,
+            project_id,
+            commit_sha,
+            server_url,
+            project_path,
+        },
+        None,
+        auth_mode,
+    )
+}
+
+/// Find the merged MR for `project.commit_sha` (or, with `merge_request_iid`,
+/// that MR once it's merged), clone the project and describe the merge.
+/// Returns None when there is no such MR.
+pub(crate) fn gitlab_merge_context(
+    project: GitLabProjectRef,
+    merge_request_iid: Option<u64>,
+    auth_mode: CloneAuthMode,
+) -> Result<Option<CiContext>, GitAiError> {
+    let GitLabProjectRef {
+        api_url,
+        project_id,
+        mut commit_sha,
+        server_url,
+        project_path,
+    } = project;
+
     // Get auth token - prefer GITLAB_TOKEN (explicitly configured with proper permissions),
     // fall back to CI_JOB_TOKEN (auto-provided but may lack API permissions)
     let (auth_header_name, auth_token) = if let Ok(gitlab_token) = std::env::var("GITLAB_TOKEN") {
@@ -175,38 +227,72 @@ pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
         ));
     };
 
-    // Calculate cutoff time (10 minutes ago) with safety buffer
-    let lookback_minutes = std::env::var("GIT_AI_CI_LOOKBACK_MINUTES")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(15);
-    let cutoff = Utc::now() - Duration::minutes(lookback_minutes);
+    let merge_requests: Vec<GitLabMergeRequest> = if let Some(iid) = merge_request_iid {
+        // A specific MR: the commit it was merged as is the one to rewrite to
+        let endpoint = format!("{}/projects/{}/merge_requests/{}", api_url, project_id, iid);
+        println!("[GitLab CI] Querying API: {}", endpoint);
+        let response = gitlab_api_get(&endpoint, auth_header_name, &auth_token)
+            .map_err(|e| GitAiError::Generic(format!("GitLab API request failed: {}", e)))?;
+        if response.status_code != 200 {
+            return Err(GitAiError::Generic(format!(
+                "GitLab API returned status {}: {}",
+                response.status_code,
+                response.as_str().unwrap_or("unknown error")
+            )));
+        }
+        let mr: GitLabMergeRequest = serde_json::from_str(response.as_str().unwrap_or("{}"))
+            .map_err(|e| {
+                GitAiError::Generic(format!("Failed to parse GitLab API response: {}", e))
+            })?;
+        let merged_as = mr
+            .merge_commit_sha
+            .clone()
+            .or_else(|| mr.squash_commit_sha.clone());
+        match merged_as {
+            Some(sha) if mr.state.as_deref() == Some("merged") => {
+                commit_sha = sha;
+                vec![mr]
+            }
+            _ => {
+                println!("[GitLab CI] MR !{} is not merged. Skipping...", iid);
+                return Ok(None);
+            }
+        }
+    } else {
+        // Calculate cutoff time (10 minutes ago) with safety buffer
+        let lookback_minutes = std::env::var("GIT_AI_CI_LOOKBACK_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(15);
+        let cutoff = Utc::now() - Duration::minutes(lookback_minutes);
 
-    let cutoff_str = cutoff.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let cutoff_str = cutoff.format("%Y-%m-%dT%H:%M:%SZ").to_string();
 
-    // Query GitLab API for recently merged MRs
-    let endpoint = format!(
-        "{}/projects/{}/merge_requests?state=merged&updated_after={}&order_by=updated_at&sort=desc&per_page=100",
-        api_url, project_id, cutoff_str
-    );
+        // Query GitLab API for recently merged MRs
+        let endpoint = format!(
+            "{}/projects/{}/merge_requests?state=merged&updated_after={}&order_by=updated_at&sort=desc&per_page=100",
+            api_url, project_id, cutoff_str
+        );
 
-    println!("[GitLab CI] Querying API: {}", endpoint);
+        println!("[GitLab CI] Querying API: {}", endpoint);
 
-    let response = gitlab_api_get(&endpoint, auth_header_name, &auth_token)
-        .map_err(|e| GitAiError::Generic(format!("GitLab API request failed: {}", e)))?;
+        let response = gitlab_api_get(&endpoint, auth_header_name, &auth_token)
+            .map_err(|e| GitAiError::Generic(format!("GitLab API request failed: {}", e)))?;
 
-    if response.status_code != 200 {
-        return Err(GitAiError::Generic(format!(
-            "GitLab API returned status {}: {}",
-            response.status_code,
-            response.as_str().unwrap_or("unknown error")
-        )));
-    }
+        if response.status_code != 200 {
+            return Err(GitAiError::Generic(format!(
+                "GitLab API returned status {}: {}",
+                response.status_code,
+                response.as_str().unwrap_or("unknown error")
+            )));
+        }
 
-    let merge_requests: Vec<GitLabMergeRequest> =
-        serde_json::from_str(response.as_str().unwrap_or("[]")).map_err(|e| {
-            GitAiError::Generic(format!("Failed to parse GitLab API response: {}", e))
-        })?;
+        let merge_requests: Vec<GitLabMergeRequest> =
+            serde_json::from_str(response.as_str().unwrap_or("[]")).map_err(|e| {
+                GitAiError::Generic(format!("Failed to parse GitLab API response: {}", e))
+            })?;
+        merge_requests
+    };
 
     println!(
         "[GitLab CI] Found {} recently merged MRs",
@@ -297,15 +383,7 @@ pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
         // Use the existing ureq-based HTTP wrapper to match the rest of this file
         // (avoids pulling in the minreq crate the original PR used).
         let source_project_endpoint = format!("{}/projects/{}", api_url, mr.source_project_id);
-        let agent = crate::http::build_agent(Some(30));
-        let request = agent
-            .get(&source_project_endpoint)
-            .set(auth_header_name, &auth_token)
-            .set(
-                "User-Agent",
-                &format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
-            );
-        match crate::http::send(request) {
+        match gitlab_api_get(&source_project_endpoint, auth_header_name, &auth_token) {
             Ok(resp) if resp.status_code == 200 => {
                 let body = String::from_utf8_lossy(resp.as_bytes());
                 match serde_json::from_str::<GitLabProject>(&body) {
@@ -357,8 +435,27 @@ pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
         .trim_start_matches("https://")
         .trim_start_matches("http://");
 
+    // Credential-helper mode keeps every URL as-is so runner insteadOf
+    // rewrites still apply; the helper hands git GITLAB_TOKEN (which can
+    // push) or else CI_JOB_TOKEN whenever it asks for the server
+    let use_credential_helper = auth_mode == CloneAuthMode::CredentialHelper;
+    let clone_auth_args = if use_credential_helper {
+        println!("[GitLab CI] Using git credential helper for clone/fetch/push");
+        credential_helper_clone_args(
+            &clone_url,
+            &env_credential_helper(&[
+                ("oauth2", "GITLAB_TOKEN"),
+                ("gitlab-ci-token", "CI_JOB_TOKEN"),
+            ]),
+        )
+    } else {
+        Vec::new()
+    };
+
     // Clone URL uses CI_JOB_TOKEN (available by default, read-only)
-    let clone_auth_url = if let Ok(job_token) = std::env::var("CI_JOB_TOKEN") {
+    let clone_auth_url = if use_credential_helper {
+        clone_url.clone()
+    } else if let Ok(job_token) = std::env::var("CI_JOB_TOKEN") {
         println!("[GitLab CI] Using CI_JOB_TOKEN for clone/fetch");
         clone_url.replace(
             &server_url,
@@ -370,7 +467,9 @@ pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
     };
 
     // Push URL uses GITLAB_TOKEN (needs write_repository scope)
-    let push_auth_url = if let Ok(gitlab_token) = std::env::var("GITLAB_TOKEN") {
+    let push_auth_url = if use_credential_helper {
+        clone_url.clone()
+    } else if let Ok(gitlab_token) = std::env::var("GITLAB_TOKEN") {
         println!("[GitLab CI] Using GITLAB_TOKEN for push (write_repository scope)");
         clone_url.replace(
             &server_url,
@@ -384,13 +483,13 @@ pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
 
     // Clone the repo using CI_JOB_TOKEN
     println!("[GitLab CI] Cloning repository...");
-    exec_git(&[
-        "clone".to_string(),
-        "--branch".to_string(),
-        mr.target_branch.clone(),
-        clone_auth_url.clone(),
-        clone_dir.clone(),
-    ])?;
+    preflight_ci_clone(Path::new(&clone_dir))?;
+    exec_git(&clone_args(
+        &clone_auth_args,
+        &mr.target_branch,
+        &clone_auth_url,
+        &clone_dir,
+    ))?;
 
     // Set origin URL to GITLAB_TOKEN URL for push
     println!("[GitLab CI] Setting origin URL for push...");
@@ -421,6 +520,8 @@ pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
         ),
     ])?;
 
+    check_ci_clone_size(Path::new(&clone_dir))?;
+
     let repo = find_repository_in_path(&clone_dir)?;
 
     // Fetch diff_refs.base_sha from the single-MR endpoint. The list endpoint
@@ -453,7 +554,9 @@ pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
 
     // Authenticate the fork clone URL for fetching notes
     let authenticated_fork_url = fork_clone_url.map(|fork_url| {
-        if let Ok(job_token) = std::env::var("CI_JOB_TOKEN") {
+        if use_credential_helper {
+            fork_url
+        } else if let Ok(job_token) = std::env::var("CI_JOB_TOKEN") {
             fork_url.replace(
                 &server_url,
                 &format!("{}://gitlab-ci-token:{}@{}", scheme, job_token, server_host),
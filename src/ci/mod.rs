@@ -1,3 +1,10 @@
+pub mod attestation;
+pub mod azure_devops;
+pub mod backports;
+pub mod bitbucket;
 pub mod ci_context;
+pub mod circleci;
+pub mod git_auth;
 pub mod github;
 pub mod gitlab;
+pub mod jenkins;
@@ -0,0 +1,367 @@
+//! `git-ai bisect-report` — attribution context for the commit `git bisect`
+//! blamed, so regressions can be correlated with AI-generated changes.
+
+use crate::authorship::ignore::effective_ignore_patterns;
+use crate::authorship::stats::{CommitStats, stats_for_commit_stats};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::mr_metadata::{MrMetadata, mr_metadata};
+use crate::git::notes_api::read_authorship;
+use crate::git::repository::Repository;
+use crate::issue_tracker::IssueLink;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+/// `git bisect log` records the result as `# first bad commit: [<sha>] <subject>`
+static FIRST_BAD_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^# first bad commit: \[([0-9a-f]{7,64})\]").unwrap());
+
+#[derive(Debug, Serialize)]
+struct CulpritReport {
+    commit: String,
+    subject: String,
+    author: String,
+    date: String,
+    merge_request: Option<String>,
+    issues: Vec<IssueLink>,
+    stats: CommitStats,
+    sessions: Vec<SessionSummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionSummary {
+    id: String,
+    tool: String,
+    model: String,
+    human_author: Option<String>,
+    additions: u32,
+    deletions: u32,
+    accepted_lines: u32,
+    messages_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CandidateSummary {
+    commit: String,
+    subject: String,
+    ai_additions: u32,
+    human_additions: u32,
+    unknown_additions: u32,
+}
+
+pub fn handle_bisect_report(args: &[String]) {
+    let mut range: Option<(String, String)> = None;
+    let mut json = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--help" | "-h" => {
+                print_help();
+                return;
+            }
+            spec if range.is_none() && !spec.starts_with('-') => {
+                let Some((bad, good)) = spec.split_once("..") else {
+                    usage_error("Expected a range of the form <bad>..<good>");
+                };
+                if bad.is_empty() || good.is_empty() {
+                    usage_error("Expected a range of the form <bad>..<good>");
+                }
+                range = Some((bad.to_string(), good.to_string()));
+            }
+            other => usage_error(&format!("Unknown argument: {}", other)),
+        }
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => fail(&format!("Failed to find repository: {}", e)),
+    };
+
+    if let Err(e) = run(&repo, range.as_ref(), json) {
+        fail(&e.to_string());
+    }
+}
+
+fn run(repo: &Repository, range: Option<&(String, String)>, json: bool) -> Result<(), GitAiError> {
+    let ignore_patterns = effective_ignore_patterns(repo, &[], &[]);
+
+    let culprit = match (bisect_culprit(repo)?, range) {
+        (Some(sha), _) => sha,
+        (None, Some((bad, good))) => {
+            let candidates = rev_list(repo, &format!("{}..{}", good, bad))?;
+            if candidates.len() != 1 {
+                let summaries = candidates
+                    .iter()
+                    .map(|sha| candidate_summary(repo, sha, &ignore_patterns))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return print_candidates(&summaries, json);
+            }
+            candidates[0].clone()
+        }
+        (None, None) => {
+            return Err(GitAiError::Generic(
+                "No bisect result found. Finish `git bisect` or pass <bad>..<good>.".to_string(),
+            ));
+        }
+    };
+
+    let report = culprit_report(repo, &culprit, &ignore_patterns)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_culprit(&report);
+    }
+    Ok(())
+}
+
+/// The first bad commit of a finished (not yet reset) bisect session
+fn bisect_culprit(repo: &Repository) -> Result<Option<String>, GitAiError> {
+    // `git bisect log` fails outside a bisect session
+    let Ok(log) = repo.git_output(&["bisect", "log"]) else {
+        return Ok(None);
+    };
+    match first_bad_commit_from_log(&log) {
+        Some(sha) => Ok(Some(
+            repo.git_output(&["rev-parse", "--verify", &format!("{}^{{commit}}", sha)])?
+                .trim()
+                .to_string(),
+        )),
+        None => Ok(None),
+    }
+}
+
+fn first_bad_commit_from_log(log: &str) -> Option<String> {
+    FIRST_BAD_RE.captures(log).map(|caps| caps[1].to_string())
+}
+
+fn rev_list(repo: &Repository, range: &str) -> Result<Vec<String>, GitAiError> {
+    Ok(repo
+        .git_output(&["rev-list", range])?
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+fn culprit_report(
+    repo: &Repository,
+    sha: &str,
+    ignore_patterns: &[String],
+) -> Result<CulpritReport, GitAiError> {
+    let info = repo.git_output(&[
+        "show",
+        "-s",
+        "--no-notes",
+        "--format=%H%x00%s%x00%an <%ae>%x00%aI",
+        sha,
+    ])?;
+    let mut fields = info.trim_end().splitn(4, '\0');
+    let mut next = || fields.next().unwrap_or("").to_string();
+    let (commit, subject, author, date) = (next(), next(), next(), next());
+
+    // Cached (or prefetched) after CI runs and fetches
+    let MrMetadata {
+        merge_request,
+        issues,
+    } = mr_metadata(repo, &commit)?;
+
+    let mut sessions: Vec<SessionSummary> = Vec::new();
+    if let Some(log) = read_authorship(repo, &commit) {
+        for (id, prompt) in &log.metadata.prompts {
+            sessions.push(SessionSummary {
+                id: id.clone(),
+                tool: prompt.agent_id.tool.clone(),
+                model: prompt.agent_id.model.clone(),
+                human_author: prompt.human_author.clone(),
+                additions: prompt.total_additions,
+                deletions: prompt.total_deletions,
+                accepted_lines: prompt.accepted_lines,
+                messages_url: prompt.messages_url.clone(),
+            });
+        }
+        for (id, session) in &log.metadata.sessions {
+            sessions.push(SessionSummary {
+                id: id.clone(),
+                tool: session.agent_id.tool.clone(),
+                model: session.agent_id.model.clone(),
+                human_author: session.human_author.clone(),
+                additions: 0,
+                deletions: 0,
+                accepted_lines: 0,
+                messages_url: None,
+            });
+        }
+    }
+
+    Ok(CulpritReport {
+        merge_request,
+        issues,
+        stats: stats_for_commit_stats(repo, &commit, ignore_patterns)?,
+        commit,
+        subject,
+        author,
+        date,
+        sessions,
+    })
+}
+
+fn candidate_summary(
+    repo: &Repository,
+    sha: &str,
+    ignore_patterns: &[String],
+) -> Result<CandidateSummary, GitAiError> {
+    let stats = stats_for_commit_stats(repo, sha, ignore_patterns)?;
+    Ok(CandidateSummary {
+        commit: sha.to_string(),
+        subject: repo
+            .git_output(&["show", "-s", "--no-notes", "--format=%s", sha])?
+            .trim()
+            .to_string(),
+        ai_additions: stats.ai_additions,
+        human_additions: stats.human_additions,
+        unknown_additions: stats.unknown_additions,
+    })
+}
+
+fn print_culprit(report: &CulpritReport) {
+    let stats = &report.stats;
+    println!("First bad commit: {}", report.commit);
+    println!("  {}", report.subject);
+    println!("Author: {}", report.author);
+    println!("Date:   {}", report.date);
+    println!(
+        "Merge request: {}",
+        report.merge_request.as_deref().unwrap_or("unknown")
+    );
+    for issue in &report.issues {
+        println!(
+            "Issue: {}{}{}  {}",
+            issue.key,
+            issue
+                .status
+                .as_deref()
+                .map(|status| format!(" [{}]", status))
+                .unwrap_or_default(),
+            issue
+                .title
+                .as_deref()
+                .map(|title| format!(" {}", title))
+                .unwrap_or_default(),
+            issue.url
+        );
+    }
+    println!();
+    println!(
+        "Added lines: {} ai ({} accepted unedited), {} human, {} untracked; {} deleted",
+        stats.ai_additions,
+        stats.ai_accepted,
+        stats.human_additions,
+        stats.unknown_additions,
+        stats.git_diff_deleted_lines
+    );
+    for (tool_model, tool_stats) in &stats.tool_model_breakdown {
+        println!("  {}: {} ai lines", tool_model, tool_stats.ai_additions);
+    }
+
+    if report.sessions.is_empty() {
+        println!();
+        println!("No AI sessions recorded for this commit.");
+        return;
+    }
+    println!();
+    println!("AI sessions:");
+    for session in &report.sessions {
+        println!(
+            "  {}  {}/{}  +{} -{}{}",
+            session.id,
+            session.tool,
+            session.model,
+            session.additions,
+            session.deletions,
+            session
+                .human_author
+                .as_deref()
+                .map(|author| format!("  ({})", author))
+                .unwrap_or_default()
+        );
+        if let Some(url) = &session.messages_url {
+            println!("    {}", url);
+        }
+    }
+    println!();
+    println!(
+        "Run 'git-ai show-prompt <id> --commit {}' for a session's full context.",
+        &report.commit[..report.commit.len().min(12)]
+    );
+}
+
+fn print_candidates(candidates: &[CandidateSummary], json: bool) -> Result<(), GitAiError> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(candidates)?);
+        return Ok(());
+    }
+    if candidates.is_empty() {
+        println!("No commits between good and bad.");
+        return Ok(());
+    }
+    println!(
+        "No bisect result yet; {} candidate commits:",
+        candidates.len()
+    );
+    for candidate in candidates {
+        println!(
+            "  {}  ai +{:<5} human +{:<5} untracked +{:<5} {}",
+            &candidate.commit[..candidate.commit.len().min(10)],
+            candidate.ai_additions,
+            candidate.human_additions,
+            candidate.unknown_additions,
+            candidate.subject
+        );
+    }
+    Ok(())
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!("Run 'git-ai bisect-report --help' for usage.");
+    std::process::exit(1);
+}
+
+fn print_help() {
+    eprintln!("git-ai bisect-report - Attribution context for a bisected regression");
+    eprintln!();
+    eprintln!("Usage: git-ai bisect-report [<bad>..<good>] [--json]");
+    eprintln!();
+    eprintln!("Reports on the first bad commit of the current `git bisect` session: its");
+    eprintln!("AI/human line breakdown, tools and models, originating merge request, and");
+    eprintln!("the AI sessions behind it. Without a bisect result, the range is used");
+    eprintln!("instead: a single commit is reported in full, otherwise each candidate is");
+    eprintln!("summarized.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --json    Print the report as JSON");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_bad_commit_is_read_from_bisect_log() {
+        let log = "git bisect start\n# bad: [1111111] broke it\ngit bisect bad 1111111\n# good: [2222222] fine\ngit bisect good 2222222\n# first bad commit: [abcdef0123] broke it\n";
+        assert_eq!(
+            first_bad_commit_from_log(log).as_deref(),
+            Some("abcdef0123")
+        );
+        assert_eq!(
+            first_bad_commit_from_log("git bisect start\n# bad: [1111111] x\n"),
+            None
+        );
+    }
+}
@@ -1,7 +1,16 @@
+use crate::ci::attestation::{MergeAttestationInput, envelope, merge_statement};
+use crate::ci::azure_devops::{get_azure_pipelines_context, print_azure_pipelines_yaml};
+use crate::ci::bitbucket::{get_bitbucket_ci_context, print_bitbucket_pipelines_yaml};
 use crate::ci::ci_context::{CiContext, CiEvent, CiRunOptions, CiRunResult};
-use crate::ci::github::{get_github_ci_context, install_github_ci_workflow};
+use crate::ci::circleci::{get_circleci_context, print_circleci_config_yaml};
+use crate::ci::git_auth::CloneAuthMode;
+use crate::ci::github::{
+    get_github_ci_context, install_github_ci_workflow, print_github_actions_yaml,
+};
 use crate::ci::gitlab::{get_gitlab_ci_context, print_gitlab_ci_yaml};
-use crate::git::repository::find_repository_in_path;
+use crate::ci::jenkins::{get_jenkins_ci_context, print_jenkinsfile_snippet};
+use crate::git::merge_request::merge_request_from_message;
+use crate::git::repository::{exec_git, find_repository_in_path};
 
 /// Print a human-readable message for a CiRunResult
 fn print_ci_result(result: &CiRunResult, prefix: &str) {
@@ -18,6 +27,12 @@ fn print_ci_result(result: &CiRunResult, prefix: &str) {
         CiRunResult::ForkNotesPreserved => {
             println!("{}: fork notes preserved", prefix);
         }
+        CiRunResult::BackportAuthorshipLinked { backport_count } => {
+            println!(
+                "{}: authorship linked for {} backported commits",
+                prefix, backport_count
+            );
+        }
         CiRunResult::SkippedFastForward => {
             println!("{}: skipped fast-forward merge", prefix);
         }
@@ -54,9 +69,24 @@ pub fn handle_ci(args: &[String]) {
         "gitlab" => {
             handle_ci_gitlab(&args[1..]);
         }
+        "bitbucket" => {
+            handle_ci_bitbucket(&args[1..]);
+        }
+        "jenkins" => {
+            handle_ci_jenkins(&args[1..]);
+        }
+        "circleci" => {
+            handle_ci_circleci(&args[1..]);
+        }
+        "azure" => {
+            handle_ci_azure(&args[1..]);
+        }
         "local" => {
             handle_ci_local(&args[1..]);
         }
+        "attest" => {
+            handle_ci_attest(&args[1..]);
+        }
         _ => {
             eprintln!("Unknown ci subcommand: {}", args[0]);
             print_ci_help_and_exit();
@@ -72,7 +102,9 @@ fn handle_ci_github(args: &[String]) {
     match args[0].as_str() {
         "run" => {
             let no_cleanup = args[1..].iter().any(|a| a == "--no-cleanup");
-            let ci_context = get_github_ci_context();
+            let auth_mode =
+                CloneAuthMode::from_flag(args[1..].iter().any(|a| a == "--credential-helper"));
+            let ci_context = get_github_ci_context(auth_mode);
             match ci_context {
                 Ok(Some(ci_context)) => {
                     tracing::debug!("GitHub CI context: {:?}", ci_context);
@@ -80,6 +112,8 @@ fn handle_ci_github(args: &[String]) {
                         Ok(result) => {
                             tracing::debug!("GitHub CI result: {:?}", result);
                             print_ci_result(&result, "GitHub CI");
+                            let prefetched = ci_context.prefetch_mr_metadata();
+                            tracing::debug!("Prefetched MR metadata for {} commits", prefetched);
                         }
                         Err(e) => {
                             eprintln!("Error running GitHub CI context: {}", e);
@@ -110,6 +144,10 @@ fn handle_ci_github(args: &[String]) {
                 }
             }
         }
+        "install" if args[1..].iter().any(|a| a == "--print") => {
+            print_github_actions_yaml();
+            std::process::exit(0);
+        }
         "install" => match install_github_ci_workflow() {
             Ok(path) => {
                 println!("Installed GitHub Actions workflow to {}", path.display());
@@ -135,7 +173,9 @@ fn handle_ci_gitlab(args: &[String]) {
     match args[0].as_str() {
         "run" => {
             let no_cleanup = args[1..].iter().any(|a| a == "--no-cleanup");
-            let ci_context = get_gitlab_ci_context();
+            let auth_mode =
+                CloneAuthMode::from_flag(args[1..].iter().any(|a| a == "--credential-helper"));
+            let ci_context = get_gitlab_ci_context(auth_mode);
             match ci_context {
                 Ok(Some(ci_context)) => {
                     tracing::debug!("GitLab CI context: {:?}", ci_context);
@@ -143,6 +183,8 @@ fn handle_ci_gitlab(args: &[String]) {
                         Ok(result) => {
                             tracing::debug!("GitLab CI result: {:?}", result);
                             print_ci_result(&result, "GitLab CI");
+                            let prefetched = ci_context.prefetch_mr_metadata();
+                            tracing::debug!("Prefetched MR metadata for {} commits", prefetched);
                         }
                         Err(e) => {
                             eprintln!("Error running GitLab CI context: {}", e);
@@ -181,6 +223,238 @@ fn handle_ci_gitlab(args: &[String]) {
     }
 }
 
+fn handle_ci_bitbucket(args: &[String]) {
+    if args.is_empty() {
+        print_ci_bitbucket_help_and_exit();
+    }
+    // Subcommands: install | run
+    match args[0].as_str() {
+        "run" => {
+            let no_cleanup = args[1..].iter().any(|a| a == "--no-cleanup");
+            let auth_mode =
+                CloneAuthMode::from_flag(args[1..].iter().any(|a| a == "--credential-helper"));
+            let ci_context = get_bitbucket_ci_context(auth_mode);
+            match ci_context {
+                Ok(Some(ci_context)) => {
+                    tracing::debug!("Bitbucket Pipelines context: {:?}", ci_context);
+                    match ci_context.run() {
+                        Ok(result) => {
+                            tracing::debug!("Bitbucket Pipelines result: {:?}", result);
+                            print_ci_result(&result, "Bitbucket Pipelines");
+                            let prefetched = ci_context.prefetch_mr_metadata();
+                            tracing::debug!("Prefetched MR metadata for {} commits", prefetched);
+                        }
+                        Err(e) => {
+                            eprintln!("Error running Bitbucket Pipelines context: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                    if !no_cleanup {
+                        if let Err(e) = ci_context.teardown() {
+                            eprintln!("Error tearing down Bitbucket Pipelines context: {}", e);
+                            std::process::exit(1);
+                        }
+                        tracing::debug!("Bitbucket Pipelines context teared down");
+                    } else {
+                        tracing::debug!("Skipping teardown (--no-cleanup)");
+                    }
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    eprintln!("Failed to get Bitbucket Pipelines context: {}", e);
+                    std::process::exit(1);
+                }
+                Ok(None) => {
+                    // No matching PR found - this is not an error, just nothing to do
+                    std::process::exit(0);
+                }
+            }
+        }
+        "install" => {
+            print_bitbucket_pipelines_yaml();
+            std::process::exit(0);
+        }
+        other => {
+            eprintln!("Unknown ci bitbucket subcommand: {}", other);
+            print_ci_help_and_exit();
+        }
+    }
+}
+
+fn handle_ci_jenkins(args: &[String]) {
+    if args.is_empty() {
+        print_ci_jenkins_help_and_exit();
+    }
+    // Subcommands: install | run
+    match args[0].as_str() {
+        "run" => {
+            let no_cleanup = args[1..].iter().any(|a| a == "--no-cleanup");
+            let auth_mode =
+                CloneAuthMode::from_flag(args[1..].iter().any(|a| a == "--credential-helper"));
+            let ci_context = get_jenkins_ci_context(auth_mode);
+            match ci_context {
+                Ok(Some(ci_context)) => {
+                    tracing::debug!("Jenkins context: {:?}", ci_context);
+                    match ci_context.run() {
+                        Ok(result) => {
+                            tracing::debug!("Jenkins result: {:?}", result);
+                            print_ci_result(&result, "Jenkins");
+                            let prefetched = ci_context.prefetch_mr_metadata();
+                            tracing::debug!("Prefetched MR metadata for {} commits", prefetched);
+                        }
+                        Err(e) => {
+                            eprintln!("Error running Jenkins context: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                    if !no_cleanup {
+                        if let Err(e) = ci_context.teardown() {
+                            eprintln!("Error tearing down Jenkins context: {}", e);
+                            std::process::exit(1);
+                        }
+                        tracing::debug!("Jenkins context teared down");
+                    } else {
+                        tracing::debug!("Skipping teardown (--no-cleanup)");
+                    }
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    eprintln!("Failed to get Jenkins context: {}", e);
+                    std::process::exit(1);
+                }
+                Ok(None) => {
+                    // No matching PR found - this is not an error, just nothing to do
+                    std::process::exit(0);
+                }
+            }
+        }
+        "install" => {
+            print_jenkinsfile_snippet();
+            std::process::exit(0);
+        }
+        other => {
+            eprintln!("Unknown ci jenkins subcommand: {}", other);
+            print_ci_help_and_exit();
+        }
+    }
+}
+
+fn handle_ci_circleci(args: &[String]) {
+    if args.is_empty() {
+        print_ci_circleci_help_and_exit();
+    }
+    // Subcommands: install | run
+    match args[0].as_str() {
+        "run" => {
+            let no_cleanup = args[1..].iter().any(|a| a == "--no-cleanup");
+            let auth_mode =
+                CloneAuthMode::from_flag(args[1..].iter().any(|a| a == "--credential-helper"));
+            let ci_context = get_circleci_context(auth_mode);
+            match ci_context {
+                Ok(Some(ci_context)) => {
+                    tracing::debug!("CircleCI context: {:?}", ci_context);
+                    match ci_context.run() {
+                        Ok(result) => {
+                            tracing::debug!("CircleCI result: {:?}", result);
+                            print_ci_result(&result, "CircleCI");
+                            let prefetched = ci_context.prefetch_mr_metadata();
+                            tracing::debug!("Prefetched MR metadata for {} commits", prefetched);
+                        }
+                        Err(e) => {
+                            eprintln!("Error running CircleCI context: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                    if !no_cleanup {
+                        if let Err(e) = ci_context.teardown() {
+                            eprintln!("Error tearing down CircleCI context: {}", e);
+                            std::process::exit(1);
+                        }
+                        tracing::debug!("CircleCI context teared down");
+                    } else {
+                        tracing::debug!("Skipping teardown (--no-cleanup)");
+                    }
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    eprintln!("Failed to get CircleCI context: {}", e);
+                    std::process::exit(1);
+                }
+                Ok(None) => {
+                    // No matching PR found - this is not an error, just nothing to do
+                    std::process::exit(0);
+                }
+            }
+        }
+        "install" => {
+            print_circleci_config_yaml();
+            std::process::exit(0);
+        }
+        other => {
+            eprintln!("Unknown ci circleci subcommand: {}", other);
+            print_ci_help_and_exit();
+        }
+    }
+}
+
+fn handle_ci_azure(args: &[String]) {
+    if args.is_empty() {
+        print_ci_azure_help_and_exit();
+    }
+    // Subcommands: install | run
+    match args[0].as_str() {
+        "run" => {
+            let no_cleanup = args[1..].iter().any(|a| a == "--no-cleanup");
+            let auth_mode =
+                CloneAuthMode::from_flag(args[1..].iter().any(|a| a == "--credential-helper"));
+            let ci_context = get_azure_pipelines_context(auth_mode);
+            match ci_context {
+                Ok(Some(ci_context)) => {
+                    tracing::debug!("Azure Pipelines context: {:?}", ci_context);
+                    match ci_context.run() {
+                        Ok(result) => {
+                            tracing::debug!("Azure Pipelines result: {:?}", result);
+                            print_ci_result(&result, "Azure Pipelines");
+                            let prefetched = ci_context.prefetch_mr_metadata();
+                            tracing::debug!("Prefetched MR metadata for {} commits", prefetched);
+                        }
+                        Err(e) => {
+                            eprintln!("Error running Azure Pipelines context: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                    if !no_cleanup {
+                        if let Err(e) = ci_context.teardown() {
+                            eprintln!("Error tearing down Azure Pipelines context: {}", e);
+                            std::process::exit(1);
+                        }
+                        tracing::debug!("Azure Pipelines context teared down");
+                    } else {
+                        tracing::debug!("Skipping teardown (--no-cleanup)");
+                    }
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    eprintln!("Failed to get Azure Pipelines context: {}", e);
+                    std::process::exit(1);
+                }
+                Ok(None) => {
+                    // No matching PR found - this is not an error, just nothing to do
+                    std::process::exit(0);
+                }
+            }
+        }
+        "install" => {
+            print_azure_pipelines_yaml();
+            std::process::exit(0);
+        }
+        other => {
+            eprintln!("Unknown ci azure subcommand: {}", other);
+            print_ci_help_and_exit();
+        }
+    }
+}
+
 fn handle_ci_local(args: &[String]) {
     if args.is_empty() {
         print_ci_local_help_and_exit();
@@ -292,6 +566,8 @@ fn handle_ci_local(args: &[String]) {
                 Ok(result) => {
                     tracing::debug!("Local CI result: {:?}", result);
                     print_ci_result(&result, "Local CI (merge)");
+                    let prefetched = ctx.prefetch_mr_metadata();
+                    tracing::debug!("Prefetched MR metadata for {} commits", prefetched);
                 }
                 Err(e) => {
                     eprintln!("Error running local CI: {}", e);
@@ -381,6 +657,97 @@ fn handle_ci_local(args: &[String]) {
     }
 }
 
+fn handle_ci_attest(args: &[String]) {
+    let mut merge_commit_sha: Option<String> = None;
+    let mut base_sha: Option<String> = None;
+    let mut merge_request: Option<String> = None;
+    let mut key_path = std::env::var_os("GIT_AI_ATTESTATION_KEY").map(std::path::PathBuf::from);
+    let mut output_path: Option<std::path::PathBuf> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        let value = || match args.get(i + 1) {
+            Some(v) => v.clone(),
+            None => {
+                eprintln!("Missing value for flag {}", args[i]);
+                std::process::exit(1);
+            }
+        };
+        match args[i].as_str() {
+            "--merge-commit-sha" => merge_commit_sha = Some(value()),
+            "--base-sha" => base_sha = Some(value()),
+            "--merge-request" => merge_request = Some(value()),
+            "--key" => key_path = Some(value().into()),
+            "--output" => output_path = Some(value().into()),
+            other => {
+                eprintln!("Unknown ci attest flag: {}", other);
+                print_ci_help_and_exit();
+            }
+        }
+        i += 2;
+    }
+
+    let (Some(merge_commit_sha), Some(base_sha)) = (merge_commit_sha, base_sha) else {
+        eprintln!("--merge-commit-sha and --base-sha are required");
+        std::process::exit(1);
+    };
+
+    let repo = match find_repository_in_path(".") {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Failed to open repository in current directory: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Fall back to the MR named in the merge commit's own message
+    let merge_request = merge_request.or_else(|| {
+        let mut show_args = repo.global_args_for_exec();
+        show_args.extend(
+            ["show", "-s", "--no-notes", "--format=%B", &merge_commit_sha]
+                .iter()
+                .map(|arg| arg.to_string()),
+        );
+        exec_git(&show_args)
+            .ok()
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .and_then(|message| merge_request_from_message(&message))
+    });
+
+    let input = MergeAttestationInput {
+        merge_commit_sha,
+        base_sha,
+        merge_request,
+    };
+    let result = merge_statement(&repo, &input)
+        .and_then(|statement| envelope(&statement, key_path.as_deref()))
+        .and_then(|envelope| Ok(serde_json::to_string_pretty(&envelope)?));
+    let json = match result {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("Failed to create attestation: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if key_path.is_none() {
+        eprintln!(
+            "warning: no signing key (--key or GIT_AI_ATTESTATION_KEY); attestation is unsigned"
+        );
+    }
+
+    match output_path {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, format!("{}\n", json)) {
+                eprintln!("Failed to write {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+            println!("Wrote attestation to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+    std::process::exit(0);
+}
+
 fn print_ci_help_and_exit() -> ! {
     eprintln!("git-ai ci - Continuous integration utilities");
     eprintln!();
@@ -388,11 +755,32 @@ fn print_ci_help_and_exit() -> ! {
     eprintln!();
     eprintln!("Subcommands:");
     eprintln!("  github           GitHub CI");
-    eprintln!("    run [--no-cleanup]  Run GitHub CI in current repo");
+    eprintln!("    run [--no-cleanup] [--credential-helper]  Run GitHub CI in current repo");
     eprintln!("    install        Install/update workflow in current repo");
     eprintln!("  gitlab           GitLab CI");
-    eprintln!("    run [--no-cleanup]  Run GitLab CI in current repo");
+    eprintln!("    run [--no-cleanup] [--credential-helper]  Run GitLab CI in current repo");
     eprintln!("    install        Print YAML snippet to add to .gitlab-ci.yml");
+    eprintln!("  bitbucket        Bitbucket Pipelines");
+    eprintln!(
+        "    run [--no-cleanup] [--credential-helper]  Run Bitbucket Pipelines in current repo"
+    );
+    eprintln!("    install        Print YAML snippet to add to bitbucket-pipelines.yml");
+    eprintln!("  jenkins          Jenkins multibranch pipelines building GitHub or GitLab repos");
+    eprintln!("    run [--no-cleanup] [--credential-helper]  Run Jenkins in current repo");
+    eprintln!("    install        Print a stage to add to the Jenkinsfile");
+    eprintln!("  circleci         CircleCI building GitHub, GitLab or Bitbucket repos");
+    eprintln!("    run [--no-cleanup] [--credential-helper]  Run CircleCI in current repo");
+    eprintln!("    install        Print config to add to .circleci/config.yml");
+    eprintln!("  azure            Azure Pipelines building Azure Repos Git repos");
+    eprintln!("    run [--no-cleanup] [--credential-helper]  Run Azure Pipelines in current repo");
+    eprintln!("    install        Print YAML snippet to add to azure-pipelines.yml");
+    eprintln!("  attest           Write a signed SLSA provenance attestation (DSSE) for a merge");
+    eprintln!(
+        "                   --merge-commit-sha <sha> --base-sha <sha> [--merge-request <id>]"
+    );
+    eprintln!(
+        "                   [--key <ed25519.pem>] [--output <file>]  (key defaults to $GIT_AI_ATTESTATION_KEY)"
+    );
     eprintln!("  local            Run CI locally by event name and flags");
     eprintln!("                   Usage: git-ai ci local <event> [flags]");
     eprintln!("                   Events:");
@@ -438,9 +826,17 @@ fn print_ci_github_help_and_exit() -> ! {
     eprintln!("Usage: git-ai ci github <subcommand> [args...]");
     eprintln!();
     eprintln!("Subcommands:");
-    eprintln!("  run [--no-cleanup]   Run GitHub CI in current repo");
+    eprintln!("  run [--no-cleanup] [--credential-helper]  Run GitHub CI in current repo");
     eprintln!("                       --no-cleanup  Skip teardown after run");
-    eprintln!("  install              Install/update workflow in current repo");
+    eprintln!(
+        "                       --credential-helper  Keep clone URLs as-is (honors insteadOf) and"
+    );
+    eprintln!("                         pass the CI token through a git credential helper");
+    eprintln!("                       Handles pull_request events, and push events whose");
+    eprintln!("                         commit is a merged PR's merge commit (looked up");
+    eprintln!("                         through the REST API with GITHUB_TOKEN)");
+    eprintln!("  install [--print]    Install/update workflow in current repo");
+    eprintln!("                       --print  Print the workflow instead of writing it");
     std::process::exit(1);
 }
 
@@ -450,8 +846,97 @@ fn print_ci_gitlab_help_and_exit() -> ! {
     eprintln!("Usage: git-ai ci gitlab <subcommand> [args...]");
     eprintln!();
     eprintln!("Subcommands:");
-    eprintln!("  run [--no-cleanup]   Run GitLab CI in current repo");
+    eprintln!("  run [--no-cleanup] [--credential-helper]  Run GitLab CI in current repo");
     eprintln!("                       --no-cleanup  Skip teardown after run");
+    eprintln!(
+        "                       --credential-helper  Keep clone URLs as-is (honors insteadOf) and"
+    );
+    eprintln!("                         pass the CI token through a git credential helper");
     eprintln!("  install              Print YAML snippet to add to .gitlab-ci.yml");
     std::process::exit(1);
 }
+
+fn print_ci_bitbucket_help_and_exit() -> ! {
+    eprintln!("git-ai ci bitbucket - Bitbucket Pipelines utilities");
+    eprintln!();
+    eprintln!("Usage: git-ai ci bitbucket <subcommand> [args...]");
+    eprintln!();
+    eprintln!("Subcommands:");
+    eprintln!(
+        "  run [--no-cleanup] [--credential-helper]  Run Bitbucket Pipelines in current repo"
+    );
+    eprintln!("                       --no-cleanup  Skip teardown after run");
+    eprintln!(
+        "                       --credential-helper  Keep clone URLs as-is (honors insteadOf) and"
+    );
+    eprintln!("                         pass the CI token through a git credential helper");
+    eprintln!("                       Needs GIT_AI_BITBUCKET_TOKEN (a repository access token)");
+    eprintln!(
+        "                         or GIT_AI_BITBUCKET_USERNAME and GIT_AI_BITBUCKET_APP_PASSWORD"
+    );
+    eprintln!("  install              Print YAML snippet to add to bitbucket-pipelines.yml");
+    std::process::exit(1);
+}
+
+fn print_ci_jenkins_help_and_exit() -> ! {
+    eprintln!("git-ai ci jenkins - Jenkins utilities");
+    eprintln!();
+    eprintln!("Usage: git-ai ci jenkins <subcommand> [args...]");
+    eprintln!();
+    eprintln!("Subcommands:");
+    eprintln!("  run [--no-cleanup] [--credential-helper]  Run Jenkins in current repo");
+    eprintln!("                       --no-cleanup  Skip teardown after run");
+    eprintln!(
+        "                       --credential-helper  Keep clone URLs as-is (honors insteadOf) and"
+    );
+    eprintln!("                         pass the CI token through a git credential helper");
+    eprintln!("                       Reads GIT_URL, GIT_COMMIT, CHANGE_ID and CHANGE_TARGET, and");
+    eprintln!("                         GITHUB_TOKEN or GITLAB_TOKEN for the forge's API;");
+    eprintln!("                         GIT_AI_JENKINS_SCM=github|gitlab names the forge when");
+    eprintln!("                         its host name doesn't");
+    eprintln!("  install              Print a stage to add to the Jenkinsfile");
+    std::process::exit(1);
+}
+
+fn print_ci_circleci_help_and_exit() -> ! {
+    eprintln!("git-ai ci circleci - CircleCI utilities");
+    eprintln!();
+    eprintln!("Usage: git-ai ci circleci <subcommand> [args...]");
+    eprintln!();
+    eprintln!("Subcommands:");
+    eprintln!("  run [--no-cleanup] [--credential-helper]  Run CircleCI in current repo");
+    eprintln!("                       --no-cleanup  Skip teardown after run");
+    eprintln!(
+        "                       --credential-helper  Keep clone URLs as-is (honors insteadOf) and"
+    );
+    eprintln!("                         pass the CI token through a git credential helper");
+    eprintln!(
+        "                       Reads CIRCLE_REPOSITORY_URL, CIRCLE_SHA1 and CIRCLE_PULL_REQUEST,"
+    );
+    eprintln!(
+        "                         and GITHUB_TOKEN, GITLAB_TOKEN or GIT_AI_BITBUCKET_TOKEN for"
+    );
+    eprintln!(
+        "                         the provider's API; GIT_AI_CIRCLECI_VCS=github|gitlab|bitbucket"
+    );
+    eprintln!("                         names the provider when its host name doesn't");
+    eprintln!("  install              Print config to add to .circleci/config.yml");
+    std::process::exit(1);
+}
+
+fn print_ci_azure_help_and_exit() -> ! {
+    eprintln!("git-ai ci azure - Azure Pipelines utilities");
+    eprintln!();
+    eprintln!("Usage: git-ai ci azure <subcommand> [args...]");
+    eprintln!();
+    eprintln!("Subcommands:");
+    eprintln!("  run [--no-cleanup] [--credential-helper]  Run Azure Pipelines in current repo");
+    eprintln!("                       --no-cleanup  Skip teardown after run");
+    eprintln!(
+        "                       --credential-helper  Keep clone URLs as-is (honors insteadOf) and"
+    );
+    eprintln!("                         pass the CI token through a git credential helper");
+    eprintln!("                       Needs SYSTEM_ACCESSTOKEN mapped into the step's env");
+    eprintln!("  install              Print YAML snippet to add to azure-pipelines.yml");
+    std::process::exit(1);
+}
@@ -0,0 +1,1113 @@
+//! `git-ai clients` — manage the git client preferences `install-hooks`
+//! changed. `check` and `install` cover just the git clients, with `--json`
+//! output for fleet tooling, `install --emit-patch` to review the changes as
+//! patches first and `install --interactive` to pick clients from a checklist;
+//! `restore` reverts a client to the snapshot taken before its preferences were
+//! last modified; `watch` keeps re-applying the preferences when a client
+//! resets them; `drift` compares each client's configured git with the shim;
+//! `uninstall --all` reverts every client and removes the git shim, for
+//! offboarding.
+
+use crate::commands::clients_all_users::{run_all_users, take_all_users_options};
+use crate::commands::clients_drift::{DriftOptions, run_drift};
+use crate::commands::clients_select::run_interactive;
+use crate::commands::install_hooks::{
+    GitClientReport, GitClientRunOptions, InstallStatus, find_running_pids,
+    run_git_client_installers,
+};
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::mdm::audit_log::{self, AuditAction};
+use crate::mdm::discovery_cache::bypass_discovery_cache;
+use crate::mdm::git_client_installer::{
+    GitClientInstaller, GitClientInstallerParams, InstallScope, check_clients_parallel,
+    check_system_install, is_per_user_path,
+};
+use crate::mdm::git_clients::get_all_git_client_installers;
+use crate::mdm::path_template::resolve_path_template;
+use crate::mdm::prefs_backup::{BackupSession, backups_root, list_snapshots, restore_snapshot};
+use crate::mdm::spinner::print_diff;
+use crate::mdm::utils::{
+    ensure_git_shim, get_current_binary_path, git_shim_path, home_dir, remove_git_shim,
+    write_atomic,
+};
+use crate::observability::log_message;
+use crate::output::{self, Status};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default time between `clients watch` passes
+const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, PartialEq, Eq)]
+struct WatchOptions {
+    interval: Duration,
+    /// Run a single pass and exit
+    once: bool,
+}
+
+/// `install`-only ways of running the installers
+#[derive(Debug, Default, PartialEq, Eq)]
+struct InstallModes {
+    /// Write pending changes here as patches instead of applying them
+    emit_patch: Option<PathBuf>,
+    /// Choose the clients from a checklist
+    interactive: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct UninstallOptions {
+    dry_run: bool,
+    json: bool,
+    scope: InstallScope,
+}
+
+/// What `uninstall --all` did to one client: the diff reverted, if any
+struct Reverted {
+    id: String,
+    name: String,
+    result: Result<Option<String>, String>,
+}
+
+/// What `uninstall --all` did with the git shim
+#[derive(Debug, PartialEq, Eq)]
+enum ShimOutcome {
+    Removed,
+    Absent,
+    Kept(String),
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct RestoreOptions {
+    client: String,
+    snapshot: Option<String>,
+    list: bool,
+}
+
+/// What `git-ai clients` exits with, so MDM scripts can branch on the
+/// result without parsing output. A failure outranks finding no clients,
+/// which outranks changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ClientsExit {
+    /// Every client is up to date and nothing was changed (or `restore` and
+    /// `watch` succeeded)
+    UpToDate = 0,
+    /// Changes are pending (`check`, `drift`, dry runs) or were made
+    Changed = 1,
+    /// Some client failed, or the command couldn't run at all
+    Failed = 2,
+    /// No supported git client was found
+    NoClients = 3,
+}
+
+impl ClientsExit {
+    /// The exit code for a set of client reports
+    pub(crate) fn from_reports(reports: &[GitClientReport]) -> Self {
+        let statuses = || reports.iter().map(|report| report.result.status);
+        if statuses().any(|status| status == InstallStatus::Failed) {
+            Self::Failed
+        } else if !statuses().any(|status| {
+            matches!(
+                status,
+                InstallStatus::Installed | InstallStatus::AlreadyInstalled
+            )
+        }) {
+            Self::NoClients
+        } else if statuses().any(|status| status == InstallStatus::Installed) {
+            Self::Changed
+        } else {
+            Self::UpToDate
+        }
+    }
+
+    pub(crate) fn exit(self) -> ! {
+        std::process::exit(self as i32)
+    }
+}
+
+/// Print a command line error and the help, then exit
+fn usage_error(err: String) -> ! {
+    eprintln!("Error: {}", err);
+    print_help();
+    ClientsExit::Failed.exit()
+}
+
+fn finish(result: Result<ClientsExit, GitAiError>) -> ! {
+    match result {
+        Ok(code) => code.exit(),
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            ClientsExit::Failed.exit()
+        }
+    }
+}
+
+pub fn handle_clients(args: &[String]) {
+    // Any subcommand may rediscover the clients from scratch
+    let no_cache = args.iter().any(|arg| arg == "--no-cache");
+    if no_cache {
+        bypass_discovery_cache();
+    }
+    let args: Vec<String> = args
+        .iter()
+        .filter(|arg| arg.as_str() != "--no-cache")
+        .cloned()
+        .collect();
+    match args.first().map(String::as_str) {
+        Some(subcommand @ ("check" | "install")) => {
+            let summary = args.iter().any(|arg| arg == "--summary");
+            let args: Vec<String> = args
+                .iter()
+                .filter(|arg| arg.as_str() != "--summary")
+                .cloned()
+                .collect();
+            let (all_users, args) =
+                take_all_users_options(&args[1..]).unwrap_or_else(|err| usage_error(err));
+            let parsed = if subcommand == "install" {
+                take_install_modes(&args).and_then(|(modes, rest)| {
+                    let options = parse_run_options(false, &rest)?;
+                    if modes.interactive
+                        && (options.quiet || options.dry_run || options.shim_path.is_some())
+                    {
+                        return Err(
+                            "--interactive can't be combined with --json, --dry-run or --shim-path"
+                                .to_string(),
+                        );
+                    }
+                    if options.verify && (options.dry_run || modes.interactive) {
+                        return Err("--verify can't be combined with --dry-run or --interactive"
+                            .to_string());
+                    }
+                    Ok((options, modes))
+                })
+            } else {
+                parse_run_options(true, &args).map(|options| (options, InstallModes::default()))
+            };
+            let (options, modes) = parsed.unwrap_or_else(|err| usage_error(err));
+            if summary && (!options.dry_run || modes != InstallModes::default()) {
+                usage_error(
+                    "--summary needs check or install --dry-run, without --emit-patch or --interactive"
+                        .to_string(),
+                );
+            }
+            if let Some(all_users) = all_users {
+                if modes != InstallModes::default() || options.scope == InstallScope::System {
+                    usage_error(
+                        "--all-users can't be combined with --interactive, --emit-patch or --scope system"
+                            .to_string(),
+                    );
+                }
+                let mut profile_args = vec!["clients".to_string(), subcommand.to_string()];
+                profile_args.extend(args);
+                if no_cache {
+                    profile_args.push("--no-cache".to_string());
+                }
+                if summary {
+                    profile_args.push("--summary".to_string());
+                }
+                finish(run_all_users(
+                    &all_users,
+                    &profile_args,
+                    options.shim_path.as_deref(),
+                    options.dry_run,
+                    options.quiet,
+                ));
+            }
+            finish(if modes.interactive {
+                run_interactive_install(options)
+            } else {
+                run_clients(options, modes.emit_patch.as_deref(), summary)
+            })
+        }
+        Some("drift") => {
+            let options = parse_drift_options(&args[1..]).unwrap_or_else(|err| usage_error(err));
+            finish(run_drift(&options))
+        }
+        Some("uninstall") => {
+            let options =
+                parse_uninstall_options(&args[1..]).unwrap_or_else(|err| usage_error(err));
+            finish(run_uninstall_all(&options))
+        }
+        Some("restore") => {
+            let options = parse_restore_options(&args[1..]).unwrap_or_else(|err| usage_error(err));
+            finish(run_restore(&options).map(|()| ClientsExit::UpToDate))
+        }
+        Some("watch") => {
+            let options = parse_watch_options(&args[1..]).unwrap_or_else(|err| usage_error(err));
+            finish(run_watch(&options).map(|()| ClientsExit::UpToDate))
+        }
+        Some("--help") | Some("-h") | Some("help") => {
+            print_help();
+            std::process::exit(0);
+        }
+        Some(other) => usage_error(format!("unknown clients subcommand: {}", other)),
+        None => usage_error("missing clients subcommand".to_string()),
+    }
+}
+
+/// `check` is a dry-run `install`
+fn parse_run_options(check: bool, args: &[String]) -> Result<GitClientRunOptions, String> {
+    let mut options = GitClientRunOptions {
+        dry_run: check,
+        ..Default::default()
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => options.quiet = true,
+            "--verbose" | "-v" => options.verbose = true,
+            "--keep-partial" if !check => options.keep_partial = true,
+            "--force" if !check => options.force = true,
+            "--dry-run" if !check => options.dry_run = true,
+            "--verify" if !check => options.verify = true,
+            "--shim-path" => options.shim_path = Some(parse_shim_path(args.next())?),
+            "--scope" => {
+                let scope = args
+                    .next()
+                    .ok_or_else(|| "--scope requires user or system".to_string())?;
+                options.scope = InstallScope::parse(scope).ok_or_else(|| {
+                    format!("invalid --scope: {} (expected user or system)", scope)
+                })?;
+            }
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+    Ok(options)
+}
+
+/// `--shim-path <template>`, resolved for the user being configured (see
+/// [`crate::mdm::path_template`])
+fn parse_shim_path(template: Option<&String>) -> Result<PathBuf, String> {
+    let template = template.ok_or_else(|| "--shim-path requires a path".to_string())?;
+    let path = resolve_path_template(template)?;
+    if !path.is_absolute() {
+        return Err(format!(
+            "--shim-path must be absolute, not {}",
+            path.display()
+        ));
+    }
+    Ok(path)
+}
+
+/// Split `install`'s `--emit-patch <dir>` and `--interactive` from the
+/// options it shares with `check`
+fn take_install_modes(args: &[String]) -> Result<(InstallModes, Vec<String>), String> {
+    let mut modes = InstallModes::default();
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--emit-patch" => {
+                let dir = args
+                    .next()
+                    .ok_or_else(|| "--emit-patch requires a directory".to_string())?;
+                modes.emit_patch = Some(PathBuf::from(dir));
+            }
+            "--interactive" | "-i" => modes.interactive = true,
+            _ => rest.push(arg.clone()),
+        }
+    }
+    if modes.interactive && modes.emit_patch.is_some() {
+        return Err("--interactive and --emit-patch can't be combined".to_string());
+    }
+    Ok((modes, rest))
+}
+
+/// `clients install --interactive`: pick the clients from a checklist
+fn run_interactive_install(options: GitClientRunOptions) -> Result<ClientsExit, GitAiError> {
+    let binary_path = get_current_binary_path()?;
+    if options.scope == InstallScope::System {
+        check_system_install(&git_shim_path(&binary_path), false)?;
+    }
+    run_interactive(&binary_path, options.scope, options.force)
+}
+
+/// Run the git client installers and work out the exit code. `--json`
+/// runs them quietly and prints the reports instead. With `emit_patch` the
+/// run is a quiet dry run whose pending changes are written to that
+/// directory as one `<client>.patch` each. With `summary` a dry run prints
+/// only its consolidated [`crate::mdm::change_summary::ChangeSummary`], as
+/// text or JSON.
+fn run_clients(
+    mut options: GitClientRunOptions,
+    emit_patch: Option<&Path>,
+    summary: bool,
+) -> Result<ClientsExit, GitAiError> {
+    let json = options.quiet;
+    if summary {
+        options.quiet = true;
+    }
+    if emit_patch.is_some() {
+        options.dry_run = true;
+        options.quiet = true;
+    }
+    let binary_path = get_current_binary_path()?;
+    if options.scope == InstallScope::System {
+        let shim = options
+            .shim_path
+            .clone()
+            .unwrap_or_else(|| git_shim_path(&binary_path));
+        check_system_install(&shim, options.dry_run)?;
+    }
+    let run = run_git_client_installers(&binary_path, options);
+
+    if let Some(dir) = emit_patch {
+        let written = write_patches(dir, &run.reports)?;
+        if !json {
+            match written {
+                0 => println!("No pending changes; no patches written."),
+                count => println!(
+                    "Wrote {} patch{} to {}",
+                    count,
+                    if count == 1 { "" } else { "es" },
+                    dir.display()
+                ),
+            }
+        }
+    }
+    if summary {
+        let changes = run.summary();
+        if json {
+            println!("{}", serde_json::to_string_pretty(&changes.to_json())?);
+        } else if changes.is_empty() {
+            println!("No pending changes.");
+        } else {
+            changes.print();
+        }
+    } else if json {
+        let reports: Vec<_> = run.reports.iter().map(GitClientReport::to_json).collect();
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else if !run.any_checked {
+        println!("No git clients detected.");
+    }
+    Ok(ClientsExit::from_reports(&run.reports))
+}
+
+/// Write each report's pending diff to `<dir>/<client id>.patch`; returns how
+/// many were written
+fn write_patches(dir: &Path, reports: &[GitClientReport]) -> Result<usize, GitAiError> {
+    fs::create_dir_all(dir)?;
+    let mut written = 0;
+    for report in reports {
+        let Some(diff) = report.diff.as_deref().filter(|diff| !diff.is_empty()) else {
+            continue;
+        };
+        write_atomic(&dir.join(format!("{}.patch", report.id)), diff.as_bytes())?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+fn parse_drift_options(args: &[String]) -> Result<DriftOptions, String> {
+    let mut options = DriftOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => options.json = true,
+            "--shim-path" => options.shim_path = Some(parse_shim_path(args.next())?),
+            "--scope" => {
+                let scope = args
+                    .next()
+                    .ok_or_else(|| "--scope requires user or system".to_string())?;
+                options.scope = InstallScope::parse(scope).ok_or_else(|| {
+                    format!("invalid --scope: {} (expected user or system)", scope)
+                })?;
+            }
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+    Ok(options)
+}
+
+/// `uninstall` only tears everything down, so `--all` is required
+fn parse_uninstall_options(args: &[String]) -> Result<UninstallOptions, String> {
+    let mut options = UninstallOptions::default();
+    let mut all = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--dry-run" => options.dry_run = true,
+            "--json" => options.json = true,
+            "--scope" => {
+                let scope = args
+                    .next()
+                    .ok_or_else(|| "--scope requires user or system".to_string())?;
+                options.scope = InstallScope::parse(scope).ok_or_else(|| {
+                    format!("invalid --scope: {} (expected user or system)", scope)
+                })?;
+            }
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+    if !all {
+        return Err(
+            "uninstall needs --all (it reverts every client and removes the git shim)".to_string(),
+        );
+    }
+    Ok(options)
+}
+
+/// Revert every supported client's preferences, then remove the git shim.
+/// The clients.include/clients.exclude lists don't apply: offboarding undoes
+/// whatever git-ai configured.
+fn run_uninstall_all(options: &UninstallOptions) -> Result<ClientsExit, GitAiError> {
+    let binary_path = get_current_binary_path()?;
+    let params = GitClientInstallerParams {
+        git_shim_path: git_shim_path(&binary_path),
+        scope: options.scope,
+    };
+    if options.scope == InstallScope::System {
+        check_system_install(&params.git_shim_path, options.dry_run)?;
+    }
+
+    let installers: Vec<_> = get_all_git_client_installers()
+        .into_iter()
+        .filter(|installer| installer.is_platform_supported())
+        .filter(|installer| {
+            options.scope == InstallScope::User || installer.supports_system_scope()
+        })
+        .collect();
+    let root = backups_root().filter(|_| !options.dry_run);
+    let reverted: Vec<Reverted> = installers
+        .iter()
+        .map(|installer| {
+            // Snapshot first, so `clients restore` can bring a client back
+            let backup = root
+                .as_ref()
+                .map(|root| BackupSession::begin(root, installer.id()));
+            let result = installer
+                .uninstall_prefs(&params, options.dry_run)
+                .map_err(|e| e.to_string());
+            drop(backup);
+            if let Ok(Some(diff)) = &result
+                && !options.dry_run
+            {
+                audit_log::record(AuditAction::Uninstall, installer.id(), Some(diff), None);
+            }
+            Reverted {
+                id: installer.id().to_string(),
+                name: installer.name().to_string(),
+                result,
+            }
+        })
+        .collect();
+
+    let ok = reverted.iter().all(|client| client.result.is_ok());
+    let shim = if !ok {
+        ShimOutcome::Kept("a client still points at it".to_string())
+    } else if !is_per_user_path(&params.git_shim_path, &home_dir()) {
+        ShimOutcome::Kept("it's installed machine-wide, where other users may use it".to_string())
+    } else if remove_git_shim(&binary_path, options.dry_run)? {
+        ShimOutcome::Removed
+    } else {
+        ShimOutcome::Absent
+    };
+
+    if options.json {
+        let json = uninstall_json(&reverted, &params.git_shim_path, &shim, options.dry_run);
+        println!("{}", serde_json::to_string_pretty(&json)?);
+    } else {
+        print_uninstall(&reverted, &params.git_shim_path, &shim, options.dry_run);
+    }
+    Ok(if !ok {
+        ClientsExit::Failed
+    } else if installers.is_empty() {
+        ClientsExit::NoClients
+    } else if shim == ShimOutcome::Removed
+        || reverted
+            .iter()
+            .any(|client| matches!(client.result, Ok(Some(_))))
+    {
+        ClientsExit::Changed
+    } else {
+        ClientsExit::UpToDate
+    })
+}
+
+fn uninstall_json(
+    reverted: &[Reverted],
+    shim_path: &Path,
+    shim: &ShimOutcome,
+    dry_run: bool,
+) -> serde_json::Value {
+    let changed = if dry_run { "pending" } else { "reverted" };
+    let clients: Vec<_> = reverted
+        .iter()
+        .map(|client| {
+            let (status, diff, message) = match &client.result {
+                Ok(Some(diff)) => (changed, Some(diff.as_str()), None),
+                Ok(None) => ("unchanged", None, None),
+                Err(err) => ("failed", None, Some(err.as_str())),
+            };
+            serde_json::json!({
+                "id": client.id,
+                "name": client.name,
+                "status": status,
+                "diff": diff,
+                "message": message,
+            })
+        })
+        .collect();
+    let (status, message) = match shim {
+        ShimOutcome::Removed if dry_run => ("pending", None),
+        ShimOutcome::Removed => ("removed", None),
+        ShimOutcome::Absent => ("absent", None),
+        ShimOutcome::Kept(reason) => ("kept", Some(reason.as_str())),
+    };
+    serde_json::json!({
+        "clients": clients,
+        "shim": {
+            "path": shim_path.display().to_string(),
+            "status": status,
+            "message": message,
+        },
+    })
+}
+
+/// One line per client that changed or failed, then the consolidated diff
+fn print_uninstall(reverted: &[Reverted], shim_path: &Path, shim: &ShimOutcome, dry_run: bool) {
+    println!("{}", output::paint(output::BOLD, "Git Clients"));
+    let mut diffs = Vec::new();
+    for client in reverted {
+        match &client.result {
+            Ok(Some(diff)) if dry_run => {
+                println!(
+                    "{}",
+                    Status::Pending.line(&format!("{}: Pending preference restore", client.name))
+                );
+                diffs.push(diff);
+            }
+            Ok(Some(diff)) => {
+                println!(
+                    "{}",
+                    Status::Ok.line(&format!("{}: Preferences restored", client.name))
+                );
+                diffs.push(diff);
+            }
+            Ok(None) => {}
+            Err(err) => println!(
+                "{}",
+                Status::Failed.line(&format!("{}: {}", client.name, err))
+            ),
+        }
+    }
+    if reverted
+        .iter()
+        .all(|client| matches!(client.result, Ok(None)))
+    {
+        println!("  No client preferences point at the git shim.");
+    }
+
+    let shim_line = match shim {
+        ShimOutcome::Removed if dry_run => Status::Pending.line(&format!(
+            "Git shim: Pending removal of {}",
+            shim_path.display()
+        )),
+        ShimOutcome::Removed => {
+            Status::Ok.line(&format!("Git shim: Removed {}", shim_path.display()))
+        }
+        ShimOutcome::Absent => Status::Skipped.line("Git shim: Not installed"),
+        ShimOutcome::Kept(reason) => Status::Warning.line(&format!(
+            "Git shim: Kept {} because {}",
+            shim_path.display(),
+            reason
+        )),
+    };
+    println!("\n{}", shim_line);
+
+    if !diffs.is_empty() {
+        println!("\n{}", output::paint(output::BOLD, "Reverted changes"));
+        for diff in diffs {
+            print_diff(diff);
+        }
+    }
+    if dry_run {
+        println!(
+            "\n{}",
+            Status::Warning.line("Dry run. No changes were made.")
+        );
+    }
+}
+
+fn parse_watch_options(args: &[String]) -> Result<WatchOptions, String> {
+    let mut options = WatchOptions {
+        interval: DEFAULT_WATCH_INTERVAL,
+        once: false,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--once" => options.once = true,
+            "--interval" => {
+                let seconds = args
+                    .next()
+                    .ok_or_else(|| "--interval requires a number of seconds".to_string())?;
+                options.interval = match seconds.parse::<u64>() {
+                    Ok(seconds) if seconds > 0 => Duration::from_secs(seconds),
+                    _ => return Err(format!("invalid --interval: {}", seconds)),
+                };
+            }
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+    Ok(options)
+}
+
+/// Re-check the clients every `interval` and re-apply the preferences of
+/// any client git-ai configured that has since reset them (e.g. an update
+/// that wiped the custom git path). Runs until killed unless `once`; meant
+/// to be kept alive by launchd, systemd or a scheduled task.
+fn run_watch(options: &WatchOptions) -> Result<(), GitAiError> {
+    let binary_path = get_current_binary_path()?;
+    let clients_config = Config::get().clients();
+    let installers: Vec<_> = get_all_git_client_installers()
+        .into_iter()
+        .filter(|installer| installer.is_platform_supported())
+        .filter(|installer| clients_config.allows(installer.id()))
+        .collect();
+    let params = GitClientInstallerParams {
+        git_shim_path: git_shim_path(&binary_path),
+        // Machine-wide preferences are left to `clients install --scope system`
+        scope: InstallScope::User,
+    };
+    // Clients git-ai has configured: those with a saved snapshot, plus any
+    // seen configured while watching
+    let mut managed: HashSet<String> = match backups_root() {
+        Some(root) => installers
+            .iter()
+            .filter(|installer| !list_snapshots(&root, installer.id()).is_empty())
+            .map(|installer| installer.id().to_string())
+            .collect(),
+        None => HashSet::new(),
+    };
+
+    if !options.once {
+        println!(
+            "Watching git client preferences every {}s (Ctrl-C to stop)",
+            options.interval.as_secs()
+        );
+    }
+    loop {
+        watch_pass(&installers, &params, &binary_path, &mut managed);
+        if options.once {
+            return Ok(());
+        }
+        std::thread::sleep(options.interval);
+    }
+}
+
+/// One `watch` pass: correct every managed client whose preferences drifted
+fn watch_pass(
+    installers: &[Box<dyn GitClientInstaller>],
+    params: &GitClientInstallerParams,
+    binary_path: &Path,
+    managed: &mut HashSet<String>,
+) {
+    let checks = check_clients_parallel(installers, params);
+    for (installer, check) in installers.iter().zip(checks) {
+        let (id, name) = (installer.id(), installer.name());
+        let check = match check {
+            Ok(check) => check,
+            Err(err) => {
+                log_watch(id, &format!("{}: preference check failed: {}", name, err));
+                continue;
+            }
+        };
+        if !check.client_installed || check.unsupported_reason.is_some() {
+            continue;
+        }
+        if check.prefs_up_to_date {
+            managed.insert(id.to_string());
+            continue;
+        }
+        if !managed.contains(id) {
+            continue;
+        }
+
+        let running = find_running_pids(&installer.process_names());
+        if !running.is_empty() {
+            log_watch(
+                id,
+                &format!(
+                    "{}: preferences were reset; waiting for {} to quit before re-applying",
+                    name, name
+                ),
+            );
+            continue;
+        }
+        if let Err(err) = ensure_git_shim(binary_path) {
+            log_watch(id, &format!("{}: could not create git shim: {}", name, err));
+            continue;
+        }
+        let backup = backups_root().map(|root| BackupSession::begin(&root, id));
+        let result = installer.install_prefs(params, false);
+        drop(backup);
+        match result {
+            Ok(Some(diff)) => {
+                audit_log::record(AuditAction::Install, id, Some(&diff), None);
+                log_watch(id, &format!("{}: preferences were reset; re-applied", name))
+            }
+            Ok(None) => {}
+            Err(err) => log_watch(
+                id,
+                &format!(
+                    "{}: preferences were reset; re-applying failed: {}",
+                    name, err
+                ),
+            ),
+        }
+    }
+}
+
+/// Print a timestamped watch event and send it to the git-ai logs
+fn log_watch(client_id: &str, message: &str) {
+    println!("{} {}", chrono::Utc::now().to_rfc3339(), message);
+    log_message(
+        message,
+        "info",
+        Some(serde_json::json!({ "command": "clients watch", "client": client_id })),
+    );
+}
+
+fn parse_restore_options(args: &[String]) -> Result<RestoreOptions, String> {
+    let mut options = RestoreOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--list" => options.list = true,
+            "--snapshot" => {
+                let snapshot = args
+                    .next()
+                    .ok_or_else(|| "--snapshot requires a timestamp".to_string())?;
+                options.snapshot = Some(snapshot.clone());
+            }
+            other if other.starts_with('-') => {
+                return Err(format!("unknown restore argument: {}", other));
+            }
+            client if options.client.is_empty() => options.client = client.to_string(),
+            extra => return Err(format!("unexpected argument: {}", extra)),
+        }
+    }
+    if options.client.is_empty() {
+        return Err("restore requires a client id (e.g. vscode, fork)".to_string());
+    }
+    Ok(options)
+}
+
+fn run_restore(options: &RestoreOptions) -> Result<(), GitAiError> {
+    let known: Vec<String> = get_all_git_client_installers()
+        .iter()
+        .map(|installer| installer.id().to_string())
+        .collect();
+    if !known.contains(&options.client) {
+        return Err(GitAiError::Generic(format!(
+            "unknown client '{}'; expected one of: {}",
+            options.client,
+            known.join(", ")
+        )));
+    }
+    let root = backups_root()
+        .ok_or_else(|| GitAiError::Generic("could not locate ~/.git-ai".to_string()))?;
+    let snapshots = list_snapshots(&root, &options.client);
+
+    if options.list {
+        if snapshots.is_empty() {
+            println!("No backups for {}", options.client);
+        }
+        for snapshot in &snapshots {
+            println!("{}", snapshot_name(snapshot));
+        }
+        return Ok(());
+    }
+
+    let snapshot = select_snapshot(&snapshots, options.snapshot.as_deref()).ok_or_else(|| {
+        GitAiError::Generic(match &options.snapshot {
+            Some(name) => format!("no backup {} for {}", name, options.client),
+            None => format!(
+                "no backups for {}; preferences are only saved when install-hooks changes them",
+                options.client
+            ),
+        })
+    })?;
+
+    let restored = restore_snapshot(snapshot)?;
+    audit_log::record(AuditAction::Restore, &options.client, None, Some(snapshot));
+    println!(
+        "Restored {} preferences from {}",
+        options.client,
+        snapshot_name(snapshot)
+    );
+    for entry in &restored {
+        println!("  {}", entry.describe());
+    }
+    Ok(())
+}
+
+/// The named snapshot, or the newest one
+fn select_snapshot<'a>(snapshots: &'a [PathBuf], name: Option<&str>) -> Option<&'a PathBuf> {
+    match name {
+        Some(name) => snapshots
+            .iter()
+            .find(|snapshot| snapshot_name(snapshot) == name),
+        None => snapshots.last(),
+    }
+}
+
+fn snapshot_name(snapshot: &Path) -> String {
+    snapshot
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn print_help() {
+    eprintln!("git-ai clients - Manage git client preferences changed by install-hooks");
+    eprintln!();
+    eprintln!("Usage:");
+    eprintln!(
+        "  git-ai clients check [--scope user|system] [--shim-path <template>] [--summary] [--json]"
+    );
+    eprintln!("  git-ai clients check|install --all-users [--users-dir <dir>] [options]");
+    eprintln!(
+        "  git-ai clients install [--scope user|system] [--dry-run] [--keep-partial] [--force] [--json]"
+    );
+    eprintln!("                         [--verify] [--shim-path <template>] [--summary]");
+    eprintln!("                         [--emit-patch <dir> | --interactive]");
+    eprintln!("  git-ai clients drift [--scope user|system] [--shim-path <template>] [--json]");
+    eprintln!("  git-ai clients uninstall --all [--scope user|system] [--dry-run] [--json]");
+    eprintln!("  git-ai clients watch [--interval <seconds>] [--once]");
+    eprintln!("  git-ai clients restore <client> [--snapshot <timestamp>]");
+    eprintln!("  git-ai clients restore <client> --list");
+    eprintln!();
+    eprintln!("check reports each client's state and pending changes without changing");
+    eprintln!("anything. --json prints an array of {{id, name, client_installed,");
+    eprintln!("prefs_configured, prefs_up_to_date, diff, running, verified, status}}");
+    eprintln!("objects instead of text.");
+    eprintln!();
+    eprintln!("A dry run ends with one summary of the clients to change, the keys");
+    eprintln!("affected and the files touched. --summary prints only that summary; with");
+    eprintln!("--json it is a {{clients_to_change, keys_affected, files_touched, clients}}");
+    eprintln!("object whose clients are {{id, name, files: [{{path, keys}}]}}.");
+    eprintln!();
+    eprintln!("The clients.include and clients.exclude config lists (client ids, see");
+    eprintln!("`git-ai config`) limit which clients are touched; the others are reported");
+    eprintln!("with status excluded.");
+    eprintln!();
+    eprintln!("install --emit-patch changes nothing and writes each pending change to");
+    eprintln!("<dir>/<client>.patch as a unified diff instead. File changes apply later");
+    eprintln!("with `patch -d / -p1 < <dir>/<client>.patch`; macOS preferences and registry");
+    eprintln!("values appear as `key = value` entries for review and are applied by install.");
+    eprintln!();
+    eprintln!("install --verify re-reads each configured client's preferences afterwards,");
+    eprintln!("plus client-specific checks (such as Fork needing a bash.exe beside the");
+    eprintln!("shim on Windows), and reports verified true or false; a client that fails");
+    eprintln!("gets a warning but stays configured.");
+    eprintln!();
+    eprintln!("install --interactive lists the detected clients with their state and lets");
+    eprintln!("you tick the ones to configure; unticking a configured client reverts it.");
+    eprintln!();
+    eprintln!("Clients such as Fork and Sublime Merge write their preferences back when");
+    eprintln!("they quit, so install skips them while they are running unless --force.");
+    eprintln!();
+    eprintln!("--scope system configures the machine-wide preferences every user inherits");
+    eprintln!("(/Library/Preferences on macOS, HKLM on Windows) for the clients that read");
+    eprintln!("them: Fork and Nova on macOS, TortoiseGit, and clients.toml clients with a");
+    eprintln!("system_settings_path. Installing needs root (sudo, or an elevated prompt on");
+    eprintln!("Windows) and a git-ai installed outside any home directory.");
+    eprintln!();
+    eprintln!("--all-users runs check or install for every user profile on the machine");
+    eprintln!("(/Users on macOS, /home on Linux, the profile list on Windows, or the");
+    eprintln!("profiles under --users-dir, e.g. a mounted golden image). Install needs");
+    eprintln!("root or an elevated prompt and a machine-wide git-ai; on macOS and Linux");
+    eprintln!("each profile is configured as its owner. --json prints an array of {{user,");
+    eprintln!("home, exit_code, clients, error}} objects. TortoiseGit keeps its settings in");
+    eprintln!("each user's registry and is skipped for other users.");
+    eprintln!();
+    eprintln!("--shim-path points the clients at an existing shim instead of the one next");
+    eprintln!("to this git-ai. ${{NAME}} and %NAME% variables and a leading ~ are resolved for");
+    eprintln!("the user being configured (HOME, USERPROFILE, APPDATA, LOCALAPPDATA, USER,");
+    eprintln!("or any environment variable), so with --all-users a template such as");
+    eprintln!("${{HOME}}/.git-ai/bin/git names each profile's own install.");
+    eprintln!();
+    eprintln!("Every preference change (install, uninstall, restore, rollback, watch) is");
+    eprintln!("appended to audit.log in the state dir (~/.git-ai/internal unless --state-dir");
+    eprintln!("moves it) as one JSON line: timestamp, action, client,");
+    eprintln!("user, target_user, and each changed key's before and after values. Set");
+    eprintln!("clients.audit_syslog to also send them to syslog or the Windows event log.");
+    eprintln!();
+    eprintln!("Discovered clients and their versions (Spotlight and registry lookups,");
+    eprintln!("version probes) are cached for an hour in the cache dir; pass");
+    eprintln!("--no-cache to any subcommand to look again, e.g. after installing a client.");
+    eprintln!();
+    eprintln!("drift lists each installed client's configured git next to the shim it");
+    eprintln!("should use: in_sync, stale_shim (an old git-ai shim, e.g. after the home");
+    eprintln!("directory moved), other_git, not_configured or outdated. --json prints an");
+    eprintln!("array of {{id, name, state, drift, configured_git_path,");
+    eprintln!("configured_git_path_exists, expected_git_path, message}} objects.");
+    eprintln!();
+    eprintln!("uninstall --all reverts every client git-ai pointed at the shim (whatever");
+    eprintln!("clients.include/clients.exclude say), snapshotting each first, then removes");
+    eprintln!("the git shim so `git` on PATH is the real git again. It prints the combined");
+    eprintln!("diff of what was reverted. The shim is kept if a client failed to revert or");
+    eprintln!("git-ai is installed machine-wide.");
+    eprintln!();
+    eprintln!("watch re-checks the clients every --interval seconds (default 60) and");
+    eprintln!("re-applies the preferences of any client git-ai configured that has reset");
+    eprintln!("them, logging each correction. --once runs a single pass.");
+    eprintln!();
+    eprintln!("Preferences are saved to <state dir>/backups/<client>/<timestamp> before");
+    eprintln!("install-hooks changes them. restore reverts to the newest snapshot unless");
+    eprintln!("--snapshot names one.");
+    eprintln!();
+    eprintln!("Exit status:");
+    eprintln!("  0  every client is up to date (restore and watch: success)");
+    eprintln!("  1  changes are pending (check, drift, --dry-run) or were made");
+    eprintln!("  2  some client failed, or the command couldn't run");
+    eprintln!("  3  no supported git client was found");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn exit_codes_follow_the_worst_client() {
+        use crate::commands::install_hooks::InstallResult;
+        let exit = |results: Vec<InstallResult>| {
+            let reports: Vec<_> = results
+                .into_iter()
+                .map(|result| GitClientReport::new("gitfiend", "GitFiend", result))
+                .collect();
+            ClientsExit::from_reports(&reports)
+        };
+        assert_eq!(
+            exit(vec![
+                InstallResult::already_installed(),
+                InstallResult::not_found()
+            ]),
+            ClientsExit::UpToDate
+        );
+        assert_eq!(
+            exit(vec![
+                InstallResult::already_installed(),
+                InstallResult::installed()
+            ]),
+            ClientsExit::Changed
+        );
+        assert_eq!(
+            exit(vec![
+                InstallResult::installed(),
+                InstallResult::failed("unreadable")
+            ]),
+            ClientsExit::Failed
+        );
+        assert_eq!(
+            exit(vec![
+                InstallResult::not_found(),
+                InstallResult::unsupported("flatpak"),
+                InstallResult::excluded()
+            ]),
+            ClientsExit::NoClients
+        );
+    }
+
+    #[test]
+    fn parses_restore_arguments() {
+        assert_eq!(
+            parse_restore_options(&args(&["fork", "--snapshot", "20261017T120000.000Z"])),
+            Ok(RestoreOptions {
+                client: "fork".to_string(),
+                snapshot: Some("20261017T120000.000Z".to_string()),
+                list: false,
+            })
+        );
+        assert!(parse_restore_options(&args(&[])).is_err());
+        assert!(parse_restore_options(&args(&["fork", "vscode"])).is_err());
+        assert!(parse_restore_options(&args(&["fork", "--snapshot"])).is_err());
+    }
+
+    #[test]
+    fn parses_check_and_install_arguments() {
+        let check = parse_run_options(true, &args(&["--json"])).unwrap();
+        assert!(check.dry_run && check.quiet && !check.keep_partial);
+        assert!(parse_run_options(true, &args(&["--keep-partial"])).is_err());
+
+        let install = parse_run_options(false, &args(&["--keep-partial", "-v"])).unwrap();
+        assert!(!install.dry_run && install.keep_partial && install.verbose);
+        assert!(!install.quiet);
+        assert!(parse_run_options(false, &args(&["fork"])).is_err());
+        assert!(parse_run_options(false, &args(&["--force"])).unwrap().force);
+        assert!(parse_run_options(true, &args(&["--force"])).is_err());
+        assert_eq!(
+            parse_run_options(true, &args(&["--scope", "system"]))
+                .unwrap()
+                .scope,
+            InstallScope::System
+        );
+        assert_eq!(install.scope, InstallScope::User);
+        assert!(parse_run_options(false, &args(&["--scope", "machine"])).is_err());
+        assert!(parse_run_options(false, &args(&["--scope"])).is_err());
+
+        let shim = parse_run_options(false, &args(&["--shim-path", "~/.git-ai/bin/git"]))
+            .unwrap()
+            .shim_path;
+        assert_eq!(shim, Some(home_dir().join(".git-ai/bin/git")));
+        assert!(parse_run_options(true, &args(&["--shim-path", "${NO_SUCH_VAR_X}/git"])).is_err());
+        assert!(parse_run_options(true, &args(&["--shim-path", "bin/git"])).is_err());
+        assert!(parse_run_options(true, &args(&["--shim-path"])).is_err());
+    }
+
+    #[test]
+    fn install_modes_are_split_from_install_arguments() {
+        let (modes, rest) =
+            take_install_modes(&args(&["--force", "--emit-patch", "patches", "--json"])).unwrap();
+        assert_eq!(modes.emit_patch, Some(PathBuf::from("patches")));
+        assert!(!modes.interactive);
+        assert_eq!(rest, args(&["--force", "--json"]));
+        assert_eq!(
+            take_install_modes(&args(&["-v"])).unwrap().0,
+            InstallModes::default()
+        );
+        assert!(take_install_modes(&args(&["--emit-patch"])).is_err());
+
+        let (modes, rest) = take_install_modes(&args(&["-i", "--scope", "system"])).unwrap();
+        assert!(modes.interactive);
+        assert_eq!(rest, args(&["--scope", "system"]));
+        assert!(take_install_modes(&args(&["--interactive", "--emit-patch", "p"])).is_err());
+    }
+
+    #[test]
+    fn parses_watch_arguments() {
+        let defaults = parse_watch_options(&[]).unwrap();
+        assert_eq!(defaults.interval, DEFAULT_WATCH_INTERVAL);
+        assert!(!defaults.once);
+
+        let options = parse_watch_options(&args(&["--interval", "5", "--once"])).unwrap();
+        assert_eq!(options.interval, Duration::from_secs(5));
+        assert!(options.once);
+        assert!(parse_watch_options(&args(&["--interval", "0"])).is_err());
+        assert!(parse_watch_options(&args(&["--interval"])).is_err());
+    }
+
+    #[test]
+    fn newest_snapshot_is_restored_by_default() {
+        let snapshots = vec![
+            PathBuf::from("/b/fork/20261001T000000.000Z"),
+            PathBuf::from("/b/fork/20261017T000000.000Z"),
+        ];
+        assert_eq!(select_snapshot(&snapshots, None), Some(&snapshots[1]));
+        assert_eq!(
+            select_snapshot(&snapshots, Some("20261001T000000.000Z")),
+            Some(&snapshots[0])
+        );
+        assert_eq!(select_snapshot(&snapshots, Some("missing")), None);
+        assert_eq!(select_snapshot(&[], None), None);
+    }
+}
@@ -0,0 +1,376 @@
+//! `git-ai clients check|install --all-users` — run the command for every
+//! user profile on the machine, for admins preparing shared machines or
+//! golden images. Each profile gets its own `git-ai clients` run with HOME
+//! (and on Windows USERPROFILE, APPDATA and LOCALAPPDATA) pointing into it.
+//! Run elevated on macOS and Linux, that run is made as the profile's owner,
+//! so whatever it writes belongs to them.
+
+use crate::commands::clients::ClientsExit;
+use crate::error::GitAiError;
+use crate::mdm::audit_log::{INVOKING_USER_ENV, invoking_user};
+use crate::mdm::git_client_installer::{OTHER_USER_ENV, is_per_user_path};
+use crate::mdm::utils::{ensure_git_shim, get_current_binary_path, git_shim_path, home_dir};
+use crate::output;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Profile directories that don't belong to a person
+const SHARED_PROFILES: &[&str] = &[
+    "All Users",
+    "Default",
+    "Default User",
+    "Guest",
+    "Public",
+    "Shared",
+    "defaultuser0",
+    "lost+found",
+];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct AllUsersOptions {
+    /// Where the profiles are, instead of the machine's own (`/Users`,
+    /// `/home`, or the Windows profile list); e.g. a mounted golden image
+    pub users_dir: Option<PathBuf>,
+}
+
+/// Split `--all-users` and `--users-dir <dir>` from the other arguments,
+/// which are passed on to each profile's run
+pub(crate) fn take_all_users_options(
+    args: &[String],
+) -> Result<(Option<AllUsersOptions>, Vec<String>), String> {
+    let mut all_users = false;
+    let mut options = AllUsersOptions::default();
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all-users" => all_users = true,
+            "--users-dir" => {
+                let dir = args
+                    .next()
+                    .ok_or_else(|| "--users-dir requires a directory".to_string())?;
+                options.users_dir = Some(PathBuf::from(dir));
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    if !all_users && options.users_dir.is_some() {
+        return Err("--users-dir only applies with --all-users".to_string());
+    }
+    Ok((all_users.then_some(options), rest))
+}
+
+/// A user's home directory and the name it goes by
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Profile {
+    user: String,
+    home: PathBuf,
+}
+
+impl Profile {
+    fn from_home(home: PathBuf) -> Option<Self> {
+        let user = home.file_name()?.to_string_lossy().into_owned();
+        Some(Self { user, home })
+    }
+}
+
+/// The user homes directly under `dir`, skipping hidden, shared and template
+/// profiles and symlinks
+fn profiles_in(dir: &Path) -> Vec<Profile> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut profiles: Vec<Profile> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            !name.starts_with('.') && !SHARED_PROFILES.contains(&name.as_str())
+        })
+        .filter_map(|entry| Profile::from_home(entry.path()))
+        .collect();
+    profiles.sort_by(|a, b| a.user.cmp(&b.user));
+    profiles
+}
+
+/// Local accounts' profiles from the registry's ProfileList; service
+/// accounts (SIDs outside S-1-5-21) are left out
+#[cfg(windows)]
+fn machine_profiles() -> Vec<Profile> {
+    use winreg::{RegKey, enums::HKEY_LOCAL_MACHINE};
+
+    let Ok(list) = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\ProfileList")
+    else {
+        return Vec::new();
+    };
+    let system_drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+    let mut profiles: Vec<Profile> = list
+        .enum_keys()
+        .flatten()
+        .filter(|sid| sid.starts_with("S-1-5-21-"))
+        .filter_map(|sid| {
+            let path: String = list
+                .open_subkey(&sid)
+                .ok()?
+                .get_value("ProfileImagePath")
+                .ok()?;
+            let home = PathBuf::from(path.replace("%SystemDrive%", &system_drive));
+            home.is_dir().then_some(home)
+        })
+        .filter_map(Profile::from_home)
+        .collect();
+    profiles.sort_by(|a, b| a.user.cmp(&b.user));
+    profiles
+}
+
+#[cfg(target_os = "macos")]
+fn machine_profiles() -> Vec<Profile> {
+    profiles_in(Path::new("/Users"))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn machine_profiles() -> Vec<Profile> {
+    profiles_in(Path::new("/home"))
+}
+
+/// The `git-ai clients` run for `profile`. As root on macOS and Linux it
+/// runs as the home directory's owner. Otherwise (and always on Windows) it
+/// can't, so it's told which user it's standing in for, and clients whose
+/// preferences live outside the profile are looked up there or skipped.
+fn profile_command(binary: &Path, profile: &Profile, args: &[String]) -> Command {
+    let mut command = Command::new(binary);
+    command
+        .args(args)
+        .env("HOME", &profile.home)
+        .env(INVOKING_USER_ENV, invoking_user())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME");
+
+    #[cfg(windows)]
+    {
+        let app_data = profile.home.join("AppData");
+        command
+            .env("USERPROFILE", &profile.home)
+            .env("APPDATA", app_data.join("Roaming"))
+            .env("LOCALAPPDATA", app_data.join("Local"))
+            .env_remove("HOMEDRIVE")
+            .env_remove("HOMEPATH")
+            .env(OTHER_USER_ENV, &profile.user);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        use std::os::unix::process::CommandExt;
+
+        let owner = fs::metadata(&profile.home)
+            .ok()
+            .map(|metadata| (metadata.uid(), metadata.gid()));
+        let euid = unsafe { libc::geteuid() };
+        match owner {
+            Some((uid, _)) if uid == euid => {}
+            Some((uid, gid)) if crate::utils::is_running_as_superuser() => {
+                command
+                    .uid(uid)
+                    .gid(gid)
+                    .env("USER", &profile.user)
+                    .env("LOGNAME", &profile.user);
+            }
+            _ => {
+                command.env(OTHER_USER_ENV, &profile.user);
+            }
+        }
+    }
+
+    command
+}
+
+/// Refuse an `--all-users` run that can't work: without `--shim-path` every
+/// profile's clients are pointed at this install's shim, so git-ai has to be
+/// installed outside any home directory, and changing other users'
+/// preferences needs root (an elevated Administrator on Windows)
+fn check_all_users_install(git_shim_path: Option<&Path>, dry_run: bool) -> Result<(), GitAiError> {
+    if let Some(git_shim_path) = git_shim_path
+        && is_per_user_path(git_shim_path, &home_dir())
+    {
+        return Err(GitAiError::Generic(format!(
+            "--all-users needs git-ai installed where every user can run it, not {}; \
+             install it machine-wide (e.g. under /opt or Program Files) and re-run",
+            git_shim_path.display()
+        )));
+    }
+    if !dry_run && !crate::utils::is_running_as_superuser() {
+        let how = if cfg!(windows) {
+            "from an elevated (Run as administrator) prompt"
+        } else {
+            "with sudo"
+        };
+        return Err(GitAiError::Generic(format!(
+            "--all-users changes other users' preferences; re-run {}",
+            how
+        )));
+    }
+    Ok(())
+}
+
+/// The exit code for the whole machine from each profile's: a failure (or a
+/// run that didn't exit normally) outranks changes, which outrank up to date
+fn combined_exit(codes: &[Option<i32>]) -> ClientsExit {
+    let has = |code: i32| codes.contains(&Some(code));
+    if codes
+        .iter()
+        .any(|code| !matches!(code, Some(0) | Some(1) | Some(3)))
+    {
+        ClientsExit::Failed
+    } else if has(1) {
+        ClientsExit::Changed
+    } else if has(0) {
+        ClientsExit::UpToDate
+    } else {
+        ClientsExit::NoClients
+    }
+}
+
+/// Run `git-ai clients <args>` for every profile. With `json` each run's
+/// reports are collected into one array of `{user, home, exit_code, clients,
+/// error}` objects; otherwise each run prints under a heading naming its user.
+/// `shim_path` is a `--shim-path` resolved for this process; each profile's
+/// run resolves the template again for itself, so it may name a per-user
+/// install, and the shim is left to that install.
+pub(crate) fn run_all_users(
+    options: &AllUsersOptions,
+    args: &[String],
+    shim_path: Option<&Path>,
+    dry_run: bool,
+    json: bool,
+) -> Result<ClientsExit, GitAiError> {
+    let binary_path = get_current_binary_path()?;
+    let own_shim = shim_path.is_none().then(|| git_shim_path(&binary_path));
+    check_all_users_install(own_shim.as_deref(), dry_run)?;
+    let profiles = match &options.users_dir {
+        Some(dir) => profiles_in(dir),
+        None => machine_profiles(),
+    };
+    if profiles.is_empty() {
+        if json {
+            println!("[]");
+        } else {
+            println!("No user profiles found.");
+        }
+        return Ok(ClientsExit::NoClients);
+    }
+    // Created once here; the runs made as each user may not be allowed to
+    if !dry_run && shim_path.is_none() {
+        ensure_git_shim(&binary_path)?;
+    }
+
+    let mut codes = Vec::new();
+    let mut reports = Vec::new();
+    for profile in &profiles {
+        let mut command = profile_command(&binary_path, profile, args);
+        if json {
+            let result = command.stderr(Stdio::inherit()).output();
+            let (code, clients, error) = match result {
+                Ok(output) => {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    match serde_json::from_str::<serde_json::Value>(stdout.trim()) {
+                        Ok(clients) => (output.status.code(), clients, None),
+                        Err(e) => (
+                            None,
+                            serde_json::Value::Null,
+                            Some(format!("unreadable output: {}", e)),
+                        ),
+                    }
+                }
+                Err(e) => (None, serde_json::Value::Null, Some(e.to_string())),
+            };
+            reports.push(serde_json::json!({
+                "user": profile.user,
+                "home": profile.home.display().to_string(),
+                "exit_code": code,
+                "clients": clients,
+                "error": error,
+            }));
+            codes.push(code);
+        } else {
+            println!(
+                "\n{}",
+                output::paint(
+                    output::BOLD,
+                    &format!("User {} ({})", profile.user, profile.home.display())
+                )
+            );
+            match command.status() {
+                Ok(status) => codes.push(status.code()),
+                Err(e) => {
+                    eprintln!("  Error: could not run git-ai for {}: {}", profile.user, e);
+                    codes.push(None);
+                }
+            }
+        }
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    }
+    Ok(combined_exit(&codes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn all_users_options_are_split_from_the_rest() {
+        assert_eq!(
+            take_all_users_options(&args(&[
+                "--json",
+                "--all-users",
+                "--users-dir",
+                "/mnt/Users"
+            ])),
+            Ok((
+                Some(AllUsersOptions {
+                    users_dir: Some(PathBuf::from("/mnt/Users"))
+                }),
+                args(&["--json"])
+            ))
+        );
+        assert_eq!(
+            take_all_users_options(&args(&["--force"])),
+            Ok((None, args(&["--force"])))
+        );
+        assert!(take_all_users_options(&args(&["--users-dir", "/mnt/Users"])).is_err());
+        assert!(take_all_users_options(&args(&["--all-users", "--users-dir"])).is_err());
+    }
+
+    #[test]
+    fn shared_and_hidden_profiles_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["bob", "alice", "Shared", "Guest", ".localized"] {
+            fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        fs::write(dir.path().join("notes.txt"), "").unwrap();
+        let users: Vec<String> = profiles_in(dir.path())
+            .into_iter()
+            .map(|profile| profile.user)
+            .collect();
+        assert_eq!(users, vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn the_worst_profile_decides_the_exit_code() {
+        assert_eq!(combined_exit(&[Some(0), Some(3)]), ClientsExit::UpToDate);
+        assert_eq!(combined_exit(&[Some(0), Some(1)]), ClientsExit::Changed);
+        assert_eq!(combined_exit(&[Some(1), Some(2)]), ClientsExit::Failed);
+        // Killed by a signal
+        assert_eq!(combined_exit(&[Some(0), None]), ClientsExit::Failed);
+        assert_eq!(combined_exit(&[Some(3)]), ClientsExit::NoClients);
+        assert_eq!(combined_exit(&[]), ClientsExit::NoClients);
+    }
+}
@@ -0,0 +1,375 @@
+//! `git-ai clients drift` — each git client's configured git next to the
+//! shim it should run. Unlike `check`, which only says whether a client is up
+//! to date, this tells a client pointed at an old shim (left behind when the
+//! home directory or git-ai moved) apart from one using some other git.
+
+use crate::commands::clients::ClientsExit;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstallerParams, InstallScope, check_clients_parallel,
+};
+use crate::mdm::git_clients::get_all_git_client_installers;
+use crate::mdm::utils::{get_current_binary_path, git_shim_path};
+use crate::output::{self, Status};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct DriftOptions {
+    pub json: bool,
+    pub scope: InstallScope,
+    /// The shim to compare with, from `--shim-path`
+    pub shim_path: Option<PathBuf>,
+}
+
+/// How a client's actual git compares with the shim it should use
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DriftState {
+    InSync,
+    /// Configured to a git-ai shim, but not the current one
+    StaleShim,
+    /// Configured to a git that isn't git-ai's
+    OtherGit,
+    NotConfigured,
+    /// Pointed at the shim, but some of its preferences need updating
+    Outdated,
+    Unsupported(String),
+    NotInstalled,
+    Excluded,
+    Failed(String),
+}
+
+impl DriftState {
+    fn classify(check: &GitClientCheckResult, expected: &Path) -> Self {
+        if !check.client_installed {
+            return Self::NotInstalled;
+        }
+        if let Some(reason) = &check.unsupported_reason {
+            return Self::Unsupported(reason.clone());
+        }
+        if check.prefs_up_to_date {
+            return Self::InSync;
+        }
+        match check.configured_git_path.as_deref() {
+            Some(path) if Path::new(path) == expected => Self::Outdated,
+            Some(path) if looks_like_git_ai_shim(Path::new(path)) => Self::StaleShim,
+            Some(_) => Self::OtherGit,
+            None if check.prefs_configured => Self::Outdated,
+            None => Self::NotConfigured,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::InSync => "in_sync",
+            Self::StaleShim => "stale_shim",
+            Self::OtherGit => "other_git",
+            Self::NotConfigured => "not_configured",
+            Self::Outdated => "outdated",
+            Self::Unsupported(_) => "unsupported",
+            Self::NotInstalled => "not_installed",
+            Self::Excluded => "excluded",
+            Self::Failed(_) => "failed",
+        }
+    }
+
+    /// Whether the client isn't in the state install would leave it in
+    fn is_drift(&self) -> bool {
+        matches!(
+            self,
+            Self::StaleShim
+                | Self::OtherGit
+                | Self::NotConfigured
+                | Self::Outdated
+                | Self::Failed(_)
+        )
+    }
+
+    fn status(&self) -> Status {
+        match self {
+            Self::InSync => Status::Ok,
+            Self::StaleShim | Self::OtherGit | Self::Outdated => Status::Warning,
+            Self::NotConfigured => Status::Pending,
+            Self::Failed(_) => Status::Failed,
+            Self::Unsupported(_) | Self::NotInstalled | Self::Excluded => Status::Skipped,
+        }
+    }
+
+    fn message(&self) -> Option<&str> {
+        match self {
+            Self::Unsupported(message) | Self::Failed(message) => Some(message),
+            _ => None,
+        }
+    }
+}
+
+/// A git under a `.git-ai` directory, or with a git-ai binary beside it. The
+/// first still matches once the old home directory is gone.
+fn looks_like_git_ai_shim(path: &Path) -> bool {
+    path.components()
+        .any(|component| component.as_os_str() == ".git-ai")
+        || path
+            .parent()
+            .is_some_and(|dir| dir.join("git-ai").is_file() || dir.join("git-ai.exe").is_file())
+}
+
+struct DriftRow {
+    id: String,
+    name: String,
+    state: DriftState,
+    configured: Option<String>,
+}
+
+impl DriftRow {
+    fn to_json(&self, expected: &Path) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "state": self.state.as_str(),
+            "drift": self.state.is_drift(),
+            "configured_git_path": self.configured,
+            "configured_git_path_exists": self
+                .configured
+                .as_deref()
+                .map(|path| Path::new(path).exists()),
+            "expected_git_path": expected.display().to_string(),
+            "message": self.state.message(),
+        })
+    }
+}
+
+/// Report drift for every supported client. Drift exits like pending
+/// changes do.
+pub(crate) fn run_drift(options: &DriftOptions) -> Result<ClientsExit, GitAiError> {
+    let binary_path = get_current_binary_path()?;
+    let params = GitClientInstallerParams {
+        git_shim_path: options
+            .shim_path
+            .clone()
+            .unwrap_or_else(|| git_shim_path(&binary_path)),
+        scope: options.scope,
+    };
+    let clients_config = Config::get().clients();
+    let (installers, excluded): (Vec<_>, Vec<_>) = get_all_git_client_installers()
+        .into_iter()
+        .filter(|installer| installer.is_platform_supported())
+        .filter(|installer| {
+            options.scope == InstallScope::User || installer.supports_system_scope()
+        })
+        .partition(|installer| clients_config.allows(installer.id()));
+
+    let checks = check_clients_parallel(&installers, &params);
+    let mut rows: Vec<DriftRow> = installers
+        .iter()
+        .zip(checks)
+        .map(|(installer, check)| {
+            let (state, configured) = match check {
+                Ok(check) => (
+                    DriftState::classify(&check, &params.git_shim_path),
+                    check.configured_git_path,
+                ),
+                Err(err) => (DriftState::Failed(err.to_string()), None),
+            };
+            DriftRow {
+                id: installer.id().to_string(),
+                name: installer.name().to_string(),
+                state,
+                configured,
+            }
+        })
+        .collect();
+    rows.extend(excluded.iter().map(|installer| DriftRow {
+        id: installer.id().to_string(),
+        name: installer.name().to_string(),
+        state: DriftState::Excluded,
+        configured: None,
+    }));
+
+    if options.json {
+        let json: Vec<_> = rows
+            .iter()
+            .map(|row| row.to_json(&params.git_shim_path))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json)?);
+    } else {
+        print_table(&rows, &params.git_shim_path);
+    }
+
+    Ok(drift_exit(&rows))
+}
+
+fn drift_exit(rows: &[DriftRow]) -> ClientsExit {
+    let states = || rows.iter().map(|row| &row.state);
+    if states().any(|state| matches!(state, DriftState::Failed(_))) {
+        ClientsExit::Failed
+    } else if !states().any(|state| {
+        !matches!(
+            state,
+            DriftState::Unsupported(_) | DriftState::NotInstalled | DriftState::Excluded
+        )
+    }) {
+        ClientsExit::NoClients
+    } else if states().any(DriftState::is_drift) {
+        ClientsExit::Changed
+    } else {
+        ClientsExit::UpToDate
+    }
+}
+
+/// Installed clients only; the rest are summed up in one line
+fn print_table(rows: &[DriftRow], expected: &Path) {
+    let shown: Vec<&DriftRow> = rows
+        .iter()
+        .filter(|row| !matches!(row.state, DriftState::NotInstalled))
+        .collect();
+    println!(
+        "{} {}",
+        output::paint(output::BOLD, "Expected git:"),
+        expected.display()
+    );
+    if shown.is_empty() {
+        println!("No git clients detected.");
+        return;
+    }
+
+    let name_width = shown.iter().map(|row| row.name.len()).max().unwrap_or(0);
+    let state_width = shown
+        .iter()
+        .map(|row| row.state.as_str().len())
+        .max()
+        .unwrap_or(0);
+    println!();
+    for row in &shown {
+        let detail = match (&row.state, row.configured.as_deref()) {
+            (DriftState::Unsupported(reason) | DriftState::Failed(reason), _) => reason.clone(),
+            (DriftState::StaleShim, Some(path)) if !Path::new(path).exists() => {
+                format!("{} (missing)", path)
+            }
+            (_, Some(path)) => path.to_string(),
+            (_, None) => String::new(),
+        };
+        println!(
+            "{} {:name_width$}  {:state_width$}  {}",
+            row.state.status().mark(),
+            row.name,
+            row.state.as_str(),
+            detail,
+        );
+    }
+
+    let drifted = shown.iter().filter(|row| row.state.is_drift()).count();
+    println!();
+    match drifted {
+        0 => println!("No drift."),
+        count => println!(
+            "{} client{} drifted; `git-ai clients install` brings {} back in sync.",
+            count,
+            if count == 1 { " has" } else { "s have" },
+            if count == 1 { "it" } else { "them" }
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(configured: bool, up_to_date: bool, path: Option<&str>) -> GitClientCheckResult {
+        GitClientCheckResult {
+            client_installed: true,
+            prefs_configured: configured,
+            prefs_up_to_date: up_to_date,
+            unsupported_reason: None,
+            configured_git_path: path.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn stale_shims_are_told_apart_from_other_gits() {
+        let expected = Path::new("/home/new/.git-ai/bin/git");
+        let classify = |check: GitClientCheckResult| DriftState::classify(&check, expected);
+
+        assert_eq!(
+            classify(check(true, true, Some("/home/new/.git-ai/bin/git"))),
+            DriftState::InSync
+        );
+        // The home directory moved
+        assert_eq!(
+            classify(check(false, false, Some("/home/old/.git-ai/bin/git"))),
+            DriftState::StaleShim
+        );
+        assert_eq!(
+            classify(check(false, false, Some("/usr/bin/git"))),
+            DriftState::OtherGit
+        );
+        assert_eq!(
+            classify(check(false, false, None)),
+            DriftState::NotConfigured
+        );
+        // One of several settings files still needs the shim
+        assert_eq!(
+            classify(check(true, false, Some("/home/new/.git-ai/bin/git"))),
+            DriftState::Outdated
+        );
+        assert_eq!(classify(check(true, false, None)), DriftState::Outdated);
+        assert_eq!(
+            classify(GitClientCheckResult::not_installed()),
+            DriftState::NotInstalled
+        );
+    }
+
+    #[test]
+    fn a_git_beside_a_git_ai_binary_is_a_shim() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = dir.path().join("git");
+        assert!(!looks_like_git_ai_shim(&git));
+        std::fs::write(dir.path().join("git-ai"), "").unwrap();
+        assert!(looks_like_git_ai_shim(&git));
+    }
+
+    #[test]
+    fn exit_codes_follow_the_worst_client() {
+        let rows = |states: Vec<DriftState>| -> Vec<DriftRow> {
+            states
+                .into_iter()
+                .map(|state| DriftRow {
+                    id: "gitfiend".to_string(),
+                    name: "GitFiend".to_string(),
+                    state,
+                    configured: None,
+                })
+                .collect()
+        };
+        assert_eq!(
+            drift_exit(&rows(vec![DriftState::InSync, DriftState::NotInstalled])),
+            ClientsExit::UpToDate
+        );
+        assert_eq!(
+            drift_exit(&rows(vec![DriftState::InSync, DriftState::StaleShim])),
+            ClientsExit::Changed
+        );
+        assert_eq!(
+            drift_exit(&rows(vec![
+                DriftState::StaleShim,
+                DriftState::Failed("unreadable".into())
+            ])),
+            ClientsExit::Failed
+        );
+        assert_eq!(
+            drift_exit(&rows(vec![
+                DriftState::NotInstalled,
+                DriftState::Unsupported("flatpak".into())
+            ])),
+            ClientsExit::NoClients
+        );
+    }
+
+    #[test]
+    fn only_real_drift_counts() {
+        assert!(DriftState::StaleShim.is_drift());
+        assert!(DriftState::NotConfigured.is_drift());
+        assert!(!DriftState::InSync.is_drift());
+        assert!(!DriftState::Excluded.is_drift());
+        assert!(!DriftState::Unsupported("flatpak".into()).is_drift());
+    }
+}
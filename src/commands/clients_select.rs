@@ -0,0 +1,423 @@
+//! `git-ai clients install --interactive` — a checklist of the detected git
+//! clients, pre-ticked for those already pointed at the shim. Ticking a client
+//! configures it and unticking one reverts it; nothing else is touched.
+
+use crate::commands::clients::ClientsExit;
+use crate::commands::install_hooks::find_running_pids;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::mdm::audit_log::{self, AuditAction};
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams, InstallScope,
+    check_clients_parallel,
+};
+use crate::mdm::git_clients::get_all_git_client_installers;
+use crate::mdm::prefs_backup::{BackupSession, backups_root};
+use crate::mdm::utils::{ensure_git_shim, git_shim_path};
+use crate::output::Status;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{
+        self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode,
+        enable_raw_mode,
+    },
+};
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+/// What a client's check found, as the checklist shows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ClientState {
+    Configured,
+    /// Pointed at the shim, but some of its preferences need updating
+    Outdated,
+    NotConfigured,
+    /// Can't be toggled; holds the reason
+    Unavailable(String),
+}
+
+impl ClientState {
+    fn from_check(check: &Result<GitClientCheckResult, GitAiError>) -> Option<Self> {
+        match check {
+            Ok(check) if !check.client_installed => None,
+            Ok(check) => Some(match &check.unsupported_reason {
+                Some(reason) => Self::Unavailable(reason.clone()),
+                None if check.prefs_up_to_date => Self::Configured,
+                None if check.prefs_configured => Self::Outdated,
+                None => Self::NotConfigured,
+            }),
+            Err(err) => Some(Self::Unavailable(format!("check failed: {}", err))),
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::Configured => "configured".to_string(),
+            Self::Outdated => "configured, needs updating".to_string(),
+            Self::NotConfigured => "not configured".to_string(),
+            Self::Unavailable(reason) => reason.clone(),
+        }
+    }
+
+    fn is_configured(&self) -> bool {
+        matches!(self, Self::Configured | Self::Outdated)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ClientRow {
+    /// Index into the installers the checklist was built from
+    installer: usize,
+    name: String,
+    state: ClientState,
+    selected: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Configure,
+    Revert,
+}
+
+/// How the user left the checklist
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+    Apply,
+    Cancel,
+}
+
+#[derive(Debug)]
+struct Checklist {
+    rows: Vec<ClientRow>,
+    cursor: usize,
+}
+
+impl Checklist {
+    fn new(rows: Vec<ClientRow>) -> Self {
+        Self { rows, cursor: 0 }
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> Option<Outcome> {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Outcome::Cancel),
+            KeyCode::Enter => return Some(Outcome::Apply),
+            KeyCode::Char('j') | KeyCode::Down if self.cursor + 1 < self.rows.len() => {
+                self.cursor += 1
+            }
+            KeyCode::Char('k') | KeyCode::Up => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Char(' ') => {
+                if let Some(row) = self.rows.get_mut(self.cursor)
+                    && !matches!(row.state, ClientState::Unavailable(_))
+                {
+                    row.selected = !row.selected;
+                }
+            }
+            KeyCode::Char('a') => self.select_all(true),
+            KeyCode::Char('n') => self.select_all(false),
+            _ => {}
+        }
+        None
+    }
+
+    fn select_all(&mut self, selected: bool) {
+        for row in &mut self.rows {
+            if !matches!(row.state, ClientState::Unavailable(_)) {
+                row.selected = selected;
+            }
+        }
+    }
+
+    /// The changes the ticks ask for: configure ticked clients that aren't
+    /// up to date, revert unticked ones that are pointed at the shim
+    fn plan(&self) -> Vec<(&ClientRow, Action)> {
+        self.rows
+            .iter()
+            .filter_map(|row| match (&row.state, row.selected) {
+                (ClientState::Unavailable(_), _) | (ClientState::Configured, true) => None,
+                (_, true) => Some((row, Action::Configure)),
+                (state, false) if state.is_configured() => Some((row, Action::Revert)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Run the checklist and apply the chosen changes
+pub(crate) fn run_interactive(
+    binary_path: &Path,
+    scope: InstallScope,
+    force: bool,
+) -> Result<ClientsExit, GitAiError> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(GitAiError::Generic(
+            "--interactive needs a terminal; use --json or --emit-patch from scripts".to_string(),
+        ));
+    }
+
+    let clients_config = Config::get().clients();
+    let installers: Vec<_> = get_all_git_client_installers()
+        .into_iter()
+        .filter(|installer| installer.is_platform_supported())
+        .filter(|installer| scope == InstallScope::User || installer.supports_system_scope())
+        .filter(|installer| clients_config.allows(installer.id()))
+        .collect();
+    let params = GitClientInstallerParams {
+        git_shim_path: git_shim_path(binary_path),
+        scope,
+    };
+    let checks = check_clients_parallel(&installers, &params);
+    let rows: Vec<ClientRow> = installers
+        .iter()
+        .zip(&checks)
+        .enumerate()
+        .filter_map(|(index, (installer, check))| {
+            let state = ClientState::from_check(check)?;
+            Some(ClientRow {
+                installer: index,
+                name: installer.name().to_string(),
+                selected: state.is_configured(),
+                state,
+            })
+        })
+        .collect();
+    if rows.is_empty() {
+        println!("No git clients detected.");
+        return Ok(ClientsExit::NoClients);
+    }
+
+    let mut checklist = Checklist::new(rows);
+    if select(&mut checklist)? == Outcome::Cancel {
+        println!("No changes made.");
+        return Ok(ClientsExit::UpToDate);
+    }
+    let plan = checklist.plan();
+    if plan.is_empty() {
+        println!("No changes made.");
+        return Ok(ClientsExit::UpToDate);
+    }
+
+    let mut exit = ClientsExit::UpToDate;
+    for (row, action) in plan {
+        let installer = &installers[row.installer];
+        match apply(installer.as_ref(), &params, binary_path, action, force) {
+            Ok(true) if exit == ClientsExit::UpToDate => exit = ClientsExit::Changed,
+            Ok(_) => {}
+            Err(err) => {
+                println!("{}", Status::Failed.line(&format!("{}: {}", row.name, err)));
+                exit = ClientsExit::Failed;
+            }
+        }
+    }
+    Ok(exit)
+}
+
+/// Configure or revert one client; `true` when its preferences changed
+fn apply(
+    installer: &dyn GitClientInstaller,
+    params: &GitClientInstallerParams,
+    binary_path: &Path,
+    action: Action,
+    force: bool,
+) -> Result<bool, GitAiError> {
+    let name = installer.name();
+    let running = find_running_pids(&installer.process_names());
+    if !running.is_empty() && !force {
+        println!(
+            "{}",
+            Status::Skipped.line(&format!("{}: quit {} first, or pass --force", name, name))
+        );
+        return Ok(false);
+    }
+    if action == Action::Configure {
+        ensure_git_shim(binary_path)?;
+    }
+    // Snapshot first so `git-ai clients restore` can undo either change
+    let backup = backups_root().map(|root| BackupSession::begin(&root, installer.id()));
+    let result = match action {
+        Action::Configure => installer.install_prefs(params, false),
+        Action::Revert => installer.uninstall_prefs(params, false),
+    };
+    drop(backup);
+    let diff = result?;
+    if let Some(diff) = &diff {
+        let audit_action = match action {
+            Action::Configure => AuditAction::Install,
+            Action::Revert => AuditAction::Uninstall,
+        };
+        audit_log::record(audit_action, installer.id(), Some(diff), None);
+    }
+    let changed = diff.is_some();
+    let message = match (action, changed) {
+        (Action::Configure, true) => "Preferences updated",
+        (Action::Revert, true) => "Preferences reverted",
+        (_, false) => "Nothing to change",
+    };
+    println!("{}", Status::Ok.line(&format!("{}: {}", name, message)));
+    Ok(changed)
+}
+
+/// Show the checklist until the user applies or cancels it
+fn select(checklist: &mut Checklist) -> Result<Outcome, GitAiError> {
+    let mut stdout = io::stdout();
+    let _guard = TerminalGuard::enter(&mut stdout)?;
+    loop {
+        draw(&mut stdout, checklist)?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && let Some(outcome) = checklist.handle_key(key.code)
+        {
+            return Ok(outcome);
+        }
+    }
+}
+
+fn draw(stdout: &mut io::Stdout, checklist: &Checklist) -> Result<(), GitAiError> {
+    let (width, height) = terminal::size()?;
+    let width = usize::from(width);
+    queue!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
+    queue!(
+        stdout,
+        SetAttribute(Attribute::Bold),
+        Print("Choose the git clients to point at the git-ai shim"),
+        SetAttribute(Attribute::Reset)
+    )?;
+
+    let name_width = checklist
+        .rows
+        .iter()
+        .map(|row| row.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let visible = usize::from(height.saturating_sub(3)).max(1);
+    let first = checklist.cursor.saturating_sub(visible - 1);
+    for (offset, row) in checklist.rows.iter().enumerate().skip(first).take(visible) {
+        let mark = match (&row.state, row.selected) {
+            (ClientState::Unavailable(_), _) => "[-]",
+            (_, true) => "[x]",
+            (_, false) => "[ ]",
+        };
+        let pointer = if offset == checklist.cursor { ">" } else { " " };
+        let line = format!(
+            "{} {} {:<name_width$}  {}",
+            pointer,
+            mark,
+            row.name,
+            row.state.label()
+        );
+        let line: String = line.chars().take(width).collect();
+        queue!(stdout, MoveTo(0, (offset - first + 2) as u16))?;
+        if offset == checklist.cursor {
+            queue!(
+                stdout,
+                SetAttribute(Attribute::Reverse),
+                Print(line),
+                SetAttribute(Attribute::Reset)
+            )?;
+        } else {
+            queue!(stdout, Print(line))?;
+        }
+    }
+
+    let help = " ↑/↓ move  space toggle  a all  n none  enter apply  q cancel ";
+    queue!(
+        stdout,
+        MoveTo(0, height.saturating_sub(1)),
+        SetAttribute(Attribute::Reverse),
+        Print(help.chars().take(width).collect::<String>()),
+        SetAttribute(Attribute::Reset)
+    )?;
+    stdout.flush()?;
+    Ok(())
+}
+
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter(stdout: &mut io::Stdout) -> Result<Self, GitAiError> {
+        enable_raw_mode()?;
+        if let Err(error) = execute!(stdout, EnterAlternateScreen, Hide) {
+            let _ = disable_raw_mode();
+            return Err(error.into());
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(name: &str, state: ClientState) -> ClientRow {
+        ClientRow {
+            installer: 0,
+            name: name.to_string(),
+            selected: state.is_configured(),
+            state,
+        }
+    }
+
+    #[test]
+    fn toggles_skip_unavailable_clients() {
+        let mut checklist = Checklist::new(vec![
+            row("Fork", ClientState::NotConfigured),
+            row("Xcode", ClientState::Unavailable("sandboxed".to_string())),
+        ]);
+        assert_eq!(checklist.handle_key(KeyCode::Char(' ')), None);
+        assert!(checklist.rows[0].selected);
+        checklist.handle_key(KeyCode::Down);
+        checklist.handle_key(KeyCode::Down);
+        assert_eq!(checklist.cursor, 1);
+        checklist.handle_key(KeyCode::Char(' '));
+        assert!(!checklist.rows[1].selected);
+        checklist.handle_key(KeyCode::Char('n'));
+        assert!(!checklist.rows[0].selected);
+        checklist.handle_key(KeyCode::Char('a'));
+        assert!(checklist.rows[0].selected && !checklist.rows[1].selected);
+        assert_eq!(checklist.handle_key(KeyCode::Enter), Some(Outcome::Apply));
+        assert_eq!(checklist.handle_key(KeyCode::Esc), Some(Outcome::Cancel));
+    }
+
+    #[test]
+    fn plan_configures_ticked_and_reverts_unticked_clients() {
+        let mut checklist = Checklist::new(vec![
+            row("Fork", ClientState::NotConfigured),
+            row("GitFiend", ClientState::Configured),
+            row("Nova", ClientState::Outdated),
+            row("Sublime Merge", ClientState::Configured),
+            row("Gitg", ClientState::NotConfigured),
+        ]);
+        // Untouched, only the outdated client needs anything
+        let names = |checklist: &Checklist| -> Vec<(String, Action)> {
+            checklist
+                .plan()
+                .into_iter()
+                .map(|(row, action)| (row.name.clone(), action))
+                .collect()
+        };
+        assert_eq!(
+            names(&checklist),
+            vec![("Nova".to_string(), Action::Configure)]
+        );
+
+        checklist.rows[0].selected = true;
+        checklist.rows[3].selected = false;
+        assert_eq!(
+            names(&checklist),
+            vec![
+                ("Fork".to_string(), Action::Configure),
+                ("Nova".to_string(), Action::Configure),
+                ("Sublime Merge".to_string(), Action::Revert),
+            ]
+        );
+    }
+}
@@ -2,7 +2,11 @@ use dirs;
 use serde_json::Value;
 use std::collections::HashMap;
 
-use crate::config::{AuthorConfig, CodexHooksFormat, NotesBackendKind};
+use crate::authorship::imara_diff_utils::DiffAlgorithm;
+use crate::config::{
+    AuthorConfig, ClientsConfig, CodexHooksFormat, CommitLintConfig, CommitLintMode, ConfigSource,
+    DiskBudgetConfig, NotesBackendKind, RedactionConfig, SyncBackendConfig, config_key_source,
+};
 use crate::git::repository::find_repository_in_path;
 
 /// Determines the type of pattern value provided
@@ -95,6 +99,8 @@ fn print_config_help() {
     println!("  git-ai config set <key> <value> --add    Add to array (extends existing)");
     println!("  git-ai config --add <key> <value>        Add to array or upsert into object");
     println!("  git-ai config unset <key>    Remove config value (reverts to default)");
+    println!("  git-ai config explain <key>  Show a value and where it was set (env/file/default)");
+    println!("  git-ai config dump --provenance  Show every value with where it was set");
     println!();
     println!("Configuration Keys:");
     println!("  git_path                     Path to git binary");
@@ -122,11 +128,50 @@ fn print_config_help() {
     println!("  max_checkpoint_file_size_bytes      Per-file checkpoint content limit in bytes");
     println!("  max_checkpoint_total_size_bytes     Per-checkpoint content limit in bytes");
     println!("  max_checkpoint_total_lines          Per-checkpoint content limit in lines");
+    println!(
+        "  report_min_group_size        Hide report groups with fewer distinct authors (0 = off)"
+    );
+    println!("  report_noise_epsilon         Add Laplace noise to report counts (0 = off)");
+    println!(
+        "  daemon_idle_shutdown_secs    Stop the background daemon after this many idle seconds (0 = never)"
+    );
+    println!(
+        "  commit_lint.mode             Conventional Commits check on commit (off/warn/strict)"
+    );
+    println!("  commit_lint.types            Allowed commit types (comma-separated or JSON array)");
+    println!("  commit_lint.scopes           Allowed scopes; any scope when unset");
+    println!("  commit_lint.max_subject_length  Max header length (default: 72)");
+    println!(
+        "  redaction                    Outbound payload redaction (JSON: hash_repo_urls, salt, strip_local_paths)"
+    );
+    println!(
+        "  disk_budgets                 Disk limits in bytes (JSON: cache_max_bytes, notes_db_max_bytes, ci_clone_max_bytes, min_free_bytes)"
+    );
+    println!(
+        "  clients.include              Only configure these git clients, by id (comma-separated or JSON array)"
+    );
+    println!("  clients.exclude              Never configure these git clients, by id");
+    println!(
+        "  clients.policy_url           HTTPS URL of the signed policy `git-ai mdm sync` enforces"
+    );
+    println!(
+        "  clients.policy_public_key    PEM Ed25519 public key the policy must be signed with"
+    );
+    println!(
+        "  clients.audit_syslog         Also send audit.log entries to syslog / the event log"
+    );
+    println!("  release_branches             Branch globs checked for backports in CI (array)");
     println!("  custom_attributes            Custom telemetry attributes, string->string (object)");
     println!("  git_ai_hooks                 Hook name -> shell commands map (object)");
     println!("  codex_hooks_format           Codex hook install format (config_toml/hooks_json)");
+    println!(
+        "  attribution_diff_algorithm   Line diff used for attribution (histogram/patience/myers)"
+    );
     println!("  notes_backend.kind           Notes backend kind (git_notes/http)");
     println!("  notes_backend.backend_url    Notes backend base URL. Required when kind=http.");
+    println!(
+        "  sync_backends                Per-org HTTP notes backends (JSON array of {{name, remotes, backend_url, api_key_env}})"
+    );
     println!(
         "                               May include a path prefix; endpoints are appended to it."
     );
@@ -159,6 +204,7 @@ fn print_config_help() {
     println!("  git-ai config set custom_attributes '{{\"team\":\"platform\"}}'");
     println!("  git-ai config --add custom_attributes.team platform");
     println!("  git-ai config unset exclude_repositories");
+    println!("  git-ai config explain notes_backend.kind");
     println!();
     std::process::exit(0);
 }
@@ -211,6 +257,24 @@ pub fn handle_config(args: &[String]) {
                 println!("Run `git-ai bg restart` for changes to take effect.");
             }
         }
+        "explain" => {
+            if filtered_args.len() < 2 {
+                eprintln!("Error: explain requires <key>");
+                eprintln!("Usage: git-ai config explain <key>");
+                std::process::exit(1);
+            }
+            if let Err(e) = explain_config_key(filtered_args[1].as_str()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        "dump" => {
+            if let Err(e) = dump_config(&filtered_args[1..]) {
+                eprintln!("Error: {}", e);
+                eprintln!("Usage: git-ai config dump [--provenance]");
+                std::process::exit(1);
+            }
+        }
         "unset" => {
             if filtered_args.len() < 2 {
                 eprintln!("Error: unset requires <key>");
@@ -248,6 +312,15 @@ pub fn handle_config(args: &[String]) {
 }
 
 fn show_all_config() -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&effective_config()?)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    println!("{}", json);
+    Ok(())
+}
+
+/// Every top-level key with its effective value, as `git-ai config` shows it
+fn effective_config() -> Result<serde_json::Map<String, Value>, String> {
     let file_config = crate::config::load_file_config_public()?;
 
     // Build a complete effective config representation
@@ -259,7 +332,9 @@ fn show_all_config() -> Result<(), String> {
     // Add fields with their effective values
     effective_config.insert(
         "git_path".to_string(),
-        Value::String(runtime_config.git_cmd().to_string()),
+        runtime_config
+            .real_git()
+            .map_or(Value::Null, |git| Value::String(git.to_string())),
     );
 
     // Arrays
@@ -360,6 +435,16 @@ fn show_all_config() -> Result<(), String> {
         Value::String(runtime_config.codex_hooks_format().as_str().to_string()),
     );
 
+    effective_config.insert(
+        "attribution_diff_algorithm".to_string(),
+        Value::String(
+            runtime_config
+                .attribution_diff_algorithm()
+                .as_str()
+                .to_string(),
+        ),
+    );
+
     effective_config.insert(
         "allow_superuser".to_string(),
         Value::Bool(runtime_config.allow_superuser()),
@@ -390,6 +475,58 @@ fn show_all_config() -> Result<(), String> {
         Value::Number(runtime_config.max_checkpoint_total_lines().into()),
     );
 
+    // Report privacy controls: surface "off" as 0 so it round-trips through `config set`.
+    effective_config.insert(
+        "report_min_group_size".to_string(),
+        Value::Number(runtime_config.report_min_group_size().unwrap_or(0).into()),
+    );
+    effective_config.insert(
+        "report_noise_epsilon".to_string(),
+        serde_json::json!(runtime_config.report_noise_epsilon().unwrap_or(0.0)),
+    );
+
+    effective_config.insert(
+        "daemon_idle_shutdown_secs".to_string(),
+        Value::Number(
+            runtime_config
+                .daemon_idle_shutdown()
+                .map_or(0, |idle| idle.as_secs())
+                .into(),
+        ),
+    );
+
+    effective_config.insert(
+        "commit_lint".to_string(),
+        serde_json::to_value(runtime_config.commit_lint())
+            .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+    );
+
+    effective_config.insert(
+        "redaction".to_string(),
+        redaction_display_value(runtime_config.redaction()),
+    );
+
+    effective_config.insert(
+        "disk_budgets".to_string(),
+        disk_budgets_display_value(runtime_config.disk_budgets()),
+    );
+
+    effective_config.insert(
+        "clients".to_string(),
+        serde_json::to_value(runtime_config.clients())
+            .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+    );
+
+    effective_config.insert(
+        "sync_backends".to_string(),
+        serde_json::json!(runtime_config.sync_backends()),
+    );
+
+    effective_config.insert(
+        "release_branches".to_string(),
+        serde_json::json!(runtime_config.release_branches()),
+    );
+
     effective_config.insert(
         "custom_attributes".to_string(),
         serde_json::to_value(runtime_config.custom_attributes())
@@ -407,8 +544,8 @@ fn show_all_config() -> Result<(), String> {
         Value::String(runtime_config.api_base_url().to_string()),
     );
 
-    // API key - show masked value if set
-    if let Some(ref key) = file_config.api_key {
+    // API key - show masked value if set (by the file or GIT_AI_API_KEY)
+    if let Some(key) = runtime_config.api_key() {
         let masked = mask_api_key(key);
         effective_config.insert("api_key".to_string(), Value::String(masked));
     }
@@ -427,13 +564,95 @@ fn show_all_config() -> Result<(), String> {
         effective_config.insert("notes_backend".to_string(), Value::Object(nb_map));
     }
 
-    let json = serde_json::to_string_pretty(&effective_config)
-        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    Ok(effective_config)
+}
+
+/// Objects whose fields are overridden individually, so provenance is
+/// reported per field
+const PROVENANCE_PER_FIELD_KEYS: &[&str] = &["feature_flags", "notes_backend"];
+
+fn provenance_entry(value: Value, source: &ConfigSource) -> Value {
+    let mut entry = serde_json::Map::new();
+    entry.insert("value".to_string(), value);
+    entry.insert(
+        "source".to_string(),
+        Value::String(source.kind().to_string()),
+    );
+    match source {
+        ConfigSource::Env(var) => {
+            entry.insert("env".to_string(), Value::String(var.clone()));
+        }
+        ConfigSource::File(path) => {
+            entry.insert(
+                "path".to_string(),
+                Value::String(path.to_string_lossy().into_owned()),
+            );
+        }
+        ConfigSource::Default => {}
+    }
+    Value::Object(entry)
+}
 
+/// `git-ai config dump --provenance`: each effective value with the layer
+/// that set it
+fn provenance_dump() -> Result<serde_json::Map<String, Value>, String> {
+    let file_config = crate::config::load_file_config_public()?;
+    let mut dump = serde_json::Map::new();
+    for (key, value) in effective_config()? {
+        match value {
+            Value::Object(fields) if PROVENANCE_PER_FIELD_KEYS.contains(&key.as_str()) => {
+                for (field, value) in fields {
+                    let path = format!("{}.{}", key, field);
+                    let source = config_key_source(&path, &file_config);
+                    dump.insert(path, provenance_entry(value, &source));
+                }
+            }
+            value => {
+                let source = config_key_source(&key, &file_config);
+                dump.insert(key, provenance_entry(value, &source));
+            }
+        }
+    }
+    Ok(dump)
+}
+
+fn dump_config(args: &[&String]) -> Result<(), String> {
+    let dump = match args.first().map(|arg| arg.as_str()) {
+        None => effective_config()?,
+        Some("--provenance") if args.len() == 1 => provenance_dump()?,
+        Some(other) => return Err(format!("Unknown dump argument: {}", other)),
+    };
+    let json = serde_json::to_string_pretty(&dump)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
     println!("{}", json);
     Ok(())
 }
 
+/// `git-ai config explain <key>`: the effective value of one key and where
+/// it came from
+fn explain_config_key(key: &str) -> Result<(), String> {
+    let file_config = crate::config::load_file_config_public()?;
+    let effective = effective_config()?;
+    let key_path = parse_key_path(key);
+    let Some(top) = effective.get(&key_path[0]) else {
+        return Err(format!("Unknown config key: {}", key_path[0]));
+    };
+    let value = key_path[1..]
+        .iter()
+        .try_fold(top, |value, part| value.get(part))
+        .cloned()
+        .unwrap_or(Value::Null);
+    let source = config_key_source(&key_path.join("."), &file_config);
+
+    println!("{} = {}", key, value);
+    println!("  source: {}", source.describe());
+    Ok(())
+}
+
+const CLIENTS_FIELD_ERROR: &str = "clients requires a field name (clients.include, clients.exclude, clients.policy_url, clients.policy_public_key, or clients.audit_syslog)";
+
+const COMMIT_LINT_FIELD_ERROR: &str = "commit_lint requires a field name (commit_lint.mode, commit_lint.types, commit_lint.scopes, or commit_lint.max_subject_length)";
+
 fn get_config_value(key: &str) -> Result<(), String> {
     let file_config = crate::config::load_file_config_public()?;
     let runtime_config = crate::config::Config::get();
@@ -443,7 +662,9 @@ fn get_config_value(key: &str) -> Result<(), String> {
     // Handle top-level keys
     if key_path.len() == 1 {
         let value = match key_path[0].as_str() {
-            "git_path" => Value::String(runtime_config.git_cmd().to_string()),
+            "git_path" => runtime_config
+                .real_git()
+                .map_or(Value::Null, |git| Value::String(git.to_string())),
             "exclude_prompts_in_repositories" => {
                 if let Some(ref repos) = file_config.exclude_prompts_in_repositories {
                     serde_json::to_value(repos).unwrap()
@@ -512,6 +733,12 @@ fn get_config_value(key: &str) -> Result<(), String> {
             "codex_hooks_format" => {
                 Value::String(runtime_config.codex_hooks_format().as_str().to_string())
             }
+            "attribution_diff_algorithm" => Value::String(
+                runtime_config
+                    .attribution_diff_algorithm()
+                    .as_str()
+                    .to_string(),
+            ),
             "allow_superuser" => Value::Bool(runtime_config.allow_superuser()),
             "transcript_streaming_lookback_days" => Value::Number(
                 runtime_config
@@ -528,6 +755,26 @@ fn get_config_value(key: &str) -> Result<(), String> {
             "max_checkpoint_total_lines" => {
                 Value::Number(runtime_config.max_checkpoint_total_lines().into())
             }
+            "report_min_group_size" => {
+                Value::Number(runtime_config.report_min_group_size().unwrap_or(0).into())
+            }
+            "report_noise_epsilon" => {
+                serde_json::json!(runtime_config.report_noise_epsilon().unwrap_or(0.0))
+            }
+            "daemon_idle_shutdown_secs" => Value::Number(
+                runtime_config
+                    .daemon_idle_shutdown()
+                    .map_or(0, |idle| idle.as_secs())
+                    .into(),
+            ),
+            "commit_lint" => serde_json::to_value(runtime_config.commit_lint())
+                .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+            "redaction" => redaction_display_value(runtime_config.redaction()),
+            "disk_budgets" => disk_budgets_display_value(runtime_config.disk_budgets()),
+            "clients" => serde_json::to_value(runtime_config.clients())
+                .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+            "release_branches" => serde_json::json!(runtime_config.release_branches()),
+            "sync_backends" => serde_json::json!(runtime_config.sync_backends()),
             "custom_attributes" => serde_json::to_value(runtime_config.custom_attributes())
                 .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
             "notes_backend" => {
@@ -621,6 +868,47 @@ fn get_config_value(key: &str) -> Result<(), String> {
         return Ok(());
     }
 
+    if key_path[0] == "commit_lint" {
+        if key_path.len() != 2 {
+            return Err(COMMIT_LINT_FIELD_ERROR.to_string());
+        }
+        let lint = runtime_config.commit_lint();
+        let value = match key_path[1].as_str() {
+            "mode" => Value::String(lint.mode.as_str().to_string()),
+            "types" => serde_json::to_value(&lint.types).unwrap_or(Value::Null),
+            "scopes" => serde_json::to_value(&lint.scopes).unwrap_or(Value::Null),
+            "max_subject_length" => {
+                serde_json::to_value(lint.max_subject_length).unwrap_or(Value::Null)
+            }
+            other => return Err(format!("Unknown commit_lint field: {}", other)),
+        };
+        let json = serde_json::to_string_pretty(&value)
+            .map_err(|e| format!("Failed to serialize value: {}", e))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    if key_path[0] == "clients" {
+        if key_path.len() != 2 {
+            return Err(CLIENTS_FIELD_ERROR.to_string());
+        }
+        let clients = runtime_config.clients();
+        let value = match key_path[1].as_str() {
+            "include" => serde_json::to_value(&clients.include).unwrap_or(Value::Null),
+            "exclude" => serde_json::to_value(&clients.exclude).unwrap_or(Value::Null),
+            "policy_url" => serde_json::to_value(&clients.policy_url).unwrap_or(Value::Null),
+            "policy_public_key" => {
+                serde_json::to_value(&clients.policy_public_key).unwrap_or(Value::Null)
+            }
+            "audit_syslog" => serde_json::to_value(clients.audit_syslog).unwrap_or(Value::Null),
+            other => return Err(format!("Unknown clients field: {}", other)),
+        };
+        let json = serde_json::to_string_pretty(&value)
+            .map_err(|e| format!("Failed to serialize value: {}", e))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
     if key_path[0] == "custom_attributes" {
         if key_path.len() != 2 {
             return Err(
@@ -641,7 +929,7 @@ fn get_config_value(key: &str) -> Result<(), String> {
     }
 
     Err(
-        "Nested keys are only supported for feature_flags, git_ai_hooks, notes_backend, author, and custom_attributes"
+        "Nested keys are only supported for feature_flags, git_ai_hooks, notes_backend, author, commit_lint, clients, and custom_attributes"
             .to_string(),
     )
 }
@@ -801,6 +1089,56 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
                         .map_err(|e| format!("Failed to serialize author: {}", e))?
                 );
             }
+            "commit_lint" => {
+                if add_mode {
+                    return Err(
+                        "Cannot use --add with commit_lint. Use commit_lint.types or commit_lint.scopes."
+                            .to_string(),
+                    );
+                }
+                let lint = parse_commit_lint_config_object(value)?;
+                file_config.commit_lint = Some(lint.clone());
+                crate::config::save_file_config(&file_config)?;
+                println!(
+                    "[commit_lint]: {}",
+                    serde_json::to_string(&lint)
+                        .map_err(|e| format!("Failed to serialize commit_lint: {}", e))?
+                );
+            }
+            "redaction" => {
+                if add_mode {
+                    return Err("Cannot use --add with redaction".to_string());
+                }
+                let redaction = parse_redaction_config_object(value)?;
+                file_config.redaction = Some(redaction.clone());
+                crate::config::save_file_config(&file_config)?;
+                println!("[redaction]: {}", redaction_display_value(&redaction));
+            }
+            "disk_budgets" => {
+                if add_mode {
+                    return Err("Cannot use --add with disk_budgets".to_string());
+                }
+                let budgets = parse_disk_budgets_config_object(value)?;
+                file_config.disk_budgets = Some(budgets.clone());
+                crate::config::save_file_config(&file_config)?;
+                println!("[disk_budgets]: {}", disk_budgets_display_value(&budgets));
+            }
+            "clients" => {
+                if add_mode {
+                    return Err(
+                        "Cannot use --add with clients. Use clients.include or clients.exclude."
+                            .to_string(),
+                    );
+                }
+                let clients = parse_clients_config_object(value)?;
+                file_config.clients = Some(clients.clone());
+                crate::config::save_file_config(&file_config)?;
+                println!(
+                    "[clients]: {}",
+                    serde_json::to_string(&clients)
+                        .map_err(|e| format!("Failed to serialize clients: {}", e))?
+                );
+            }
             "git_ai_hooks" => {
                 if add_mode {
                     return Err("Cannot use --add with git_ai_hooks at top level. Use dot notation: git_ai_hooks.post_notes_updated".to_string());
@@ -815,6 +1153,12 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
                 crate::config::save_file_config(&file_config)?;
                 println!("[codex_hooks_format]: {}", format.as_str());
             }
+            "attribution_diff_algorithm" => {
+                let algorithm = parse_diff_algorithm(value)?;
+                file_config.attribution_diff_algorithm = Some(algorithm.as_str().to_string());
+                crate::config::save_file_config(&file_config)?;
+                println!("[attribution_diff_algorithm]: {}", algorithm.as_str());
+            }
             "allow_superuser" => {
                 let bool_value = parse_bool(value)?;
                 file_config.allow_superuser = Some(bool_value);
@@ -865,6 +1209,78 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
                 crate::config::save_file_config(&file_config)?;
                 println!("[max_checkpoint_total_lines]: {}", lines);
             }
+            "report_min_group_size" => {
+                let k = value.trim().parse::<u32>().map_err(|_| {
+                    format!(
+                        "Invalid report_min_group_size value '{}'. Expected a non-negative integer (0 = off)",
+                        value
+                    )
+                })?;
+                file_config.report_min_group_size = Some(k);
+                crate::config::save_file_config(&file_config)?;
+                println!("[report_min_group_size]: {}", k);
+            }
+            "report_noise_epsilon" => {
+                let epsilon = value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|e| e.is_finite() && *e >= 0.0)
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid report_noise_epsilon value '{}'. Expected a non-negative number (0 = off)",
+                            value
+                        )
+                    })?;
+                file_config.report_noise_epsilon = Some(epsilon);
+                crate::config::save_file_config(&file_config)?;
+                println!("[report_noise_epsilon]: {}", epsilon);
+            }
+            "daemon_idle_shutdown_secs" => {
+                let secs = value.trim().parse::<u64>().map_err(|_| {
+                    format!(
+                        "Invalid daemon_idle_shutdown_secs value '{}'. Expected a non-negative integer in seconds (0 = never)",
+                        value
+                    )
+                })?;
+                file_config.daemon_idle_shutdown_secs = Some(secs);
+                crate::config::save_file_config(&file_config)?;
+                println!("[daemon_idle_shutdown_secs]: {}", secs);
+            }
+            "sync_backends" => {
+                let backends = parse_sync_backends(value)?;
+                match file_config.sync_backends.as_mut() {
+                    Some(existing) if add_mode => {
+                        for backend in &backends {
+                            existing.retain(|b| b.name != backend.name);
+                            existing.push(backend.clone());
+                        }
+                    }
+                    _ => file_config.sync_backends = Some(backends.clone()),
+                }
+                crate::config::save_file_config(&file_config)?;
+                for backend in &backends {
+                    println!(
+                        "+ [sync_backends]: {} -> {}",
+                        backend.name, backend.backend_url
+                    );
+                }
+            }
+            "release_branches" => {
+                let items = parse_string_list(value)?;
+                match file_config.release_branches.as_mut() {
+                    Some(existing) if add_mode => {
+                        for item in &items {
+                            if !existing.contains(item) {
+                                existing.push(item.clone());
+                            }
+                        }
+                    }
+                    _ => file_config.release_branches = Some(items.clone()),
+                }
+                crate::config::save_file_config(&file_config)?;
+                log_array_changes(&items, add_mode);
+            }
             "custom_attributes" => {
                 if add_mode {
                     return Err("Cannot use --add with custom_attributes at top level. Use dot notation: custom_attributes.key".to_string());
@@ -995,6 +1411,111 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
         return Ok(());
     }
 
+    if key_path[0] == "commit_lint" {
+        if key_path.len() != 2 {
+            return Err(COMMIT_LINT_FIELD_ERROR.to_string());
+        }
+        let field = key_path[1].as_str();
+        let mut lint = file_config.commit_lint.clone().unwrap_or_default();
+        match field {
+            "mode" => lint.mode = parse_commit_lint_mode(value)?,
+            "types" | "scopes" => {
+                let items = parse_string_list(value)?;
+                let target = if field == "types" {
+                    &mut lint.types
+                } else {
+                    &mut lint.scopes
+                };
+                match target {
+                    Some(existing) if add_mode => {
+                        for item in items {
+                            if !existing.contains(&item) {
+                                existing.push(item);
+                            }
+                        }
+                    }
+                    _ => *target = Some(items),
+                }
+            }
+            "max_subject_length" => {
+                let max = value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|max| *max > 0)
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid commit_lint.max_subject_length '{}'. Expected a positive number",
+                            value
+                        )
+                    })?;
+                lint.max_subject_length = Some(max);
+            }
+            other => return Err(format!("Unknown commit_lint field: {}", other)),
+        }
+        file_config.commit_lint = Some(lint);
+        crate::config::save_file_config(&file_config)?;
+        println!("[commit_lint.{}]: {}", field, value);
+        return Ok(());
+    }
+
+    if key_path[0] == "clients" {
+        if key_path.len() != 2 {
+            return Err(CLIENTS_FIELD_ERROR.to_string());
+        }
+        let field = key_path[1].as_str();
+        let mut clients = file_config.clients.clone().unwrap_or_default();
+        if field == "audit_syslog" {
+            if add_mode {
+                return Err("Cannot use --add with clients.audit_syslog".to_string());
+            }
+            clients.audit_syslog = Some(parse_bool(value.trim())?);
+            file_config.clients = Some(clients);
+            crate::config::save_file_config(&file_config)?;
+            println!("[clients.audit_syslog]: {}", value.trim());
+            return Ok(());
+        }
+        if let Some(target) = match field {
+            "policy_url" => Some(&mut clients.policy_url),
+            "policy_public_key" => Some(&mut clients.policy_public_key),
+            _ => None,
+        } {
+            if add_mode {
+                return Err(format!("Cannot use --add with clients.{}", field));
+            }
+            let value = value.trim();
+            if value.is_empty() {
+                return Err(format!("clients.{} cannot be empty", field));
+            }
+            *target = Some(value.to_string());
+            file_config.clients = Some(clients);
+            crate::config::save_file_config(&file_config)?;
+            println!("[clients.{}]: {}", field, value);
+            return Ok(());
+        }
+        let target = match field {
+            "include" => &mut clients.include,
+            "exclude" => &mut clients.exclude,
+            other => return Err(format!("Unknown clients field: {}", other)),
+        };
+        let items = parse_string_list(value)?;
+        match target {
+            Some(existing) if add_mode => {
+                for item in items {
+                    if !existing.contains(&item) {
+                        existing.push(item);
+                    }
+                }
+            }
+            _ => *target = Some(items),
+        }
+        file_config.clients = Some(clients);
+        crate::config::save_file_config(&file_config)?;
+        let prefix = if add_mode { "+ " } else { "" };
+        println!("{}[clients.{}]: {}", prefix, field, value);
+        return Ok(());
+    }
+
     if key_path[0] == "author" {
         if add_mode {
             return Err("Cannot use --add with author fields".to_string());
@@ -1041,7 +1562,7 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
     }
 
     Err(
-        "Nested keys are only supported for feature_flags, git_ai_hooks, notes_backend, author, and custom_attributes"
+        "Nested keys are only supported for feature_flags, git_ai_hooks, notes_backend, author, commit_lint, clients, and custom_attributes"
             .to_string(),
     )
 }
@@ -1176,6 +1697,42 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     );
                 }
             }
+            "commit_lint" => {
+                let old_value = file_config.commit_lint.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    println!(
+                        "- [commit_lint]: {}",
+                        serde_json::to_string(&v)
+                            .map_err(|e| format!("Failed to serialize commit_lint: {}", e))?
+                    );
+                }
+            }
+            "redaction" => {
+                let old_value = file_config.redaction.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    println!("- [redaction]: {}", redaction_display_value(&v));
+                }
+            }
+            "disk_budgets" => {
+                let old_value = file_config.disk_budgets.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    println!("- [disk_budgets]: {}", disk_budgets_display_value(&v));
+                }
+            }
+            "clients" => {
+                let old_value = file_config.clients.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    println!(
+                        "- [clients]: {}",
+                        serde_json::to_string(&v)
+                            .map_err(|e| format!("Failed to serialize clients: {}", e))?
+                    );
+                }
+            }
             "git_ai_hooks" => {
                 let old_value = file_config.git_ai_hooks.take();
                 crate::config::save_file_config(&file_config)?;
@@ -1190,6 +1747,13 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     println!("- [codex_hooks_format]: {}", v);
                 }
             }
+            "attribution_diff_algorithm" => {
+                let old_value = file_config.attribution_diff_algorithm.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    println!("- [attribution_diff_algorithm]: {}", v);
+                }
+            }
             "allow_superuser" => {
                 let old_value = file_config.allow_superuser.take();
                 crate::config::save_file_config(&file_config)?;
@@ -1225,6 +1789,44 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     println!("- [max_checkpoint_total_lines]: {}", v);
                 }
             }
+            "report_min_group_size" => {
+                let old_value = file_config.report_min_group_size.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    println!("- [report_min_group_size]: {}", v);
+                }
+            }
+            "report_noise_epsilon" => {
+                let old_value = file_config.report_noise_epsilon.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    println!("- [report_noise_epsilon]: {}", v);
+                }
+            }
+            "daemon_idle_shutdown_secs" => {
+                let old_value = file_config.daemon_idle_shutdown_secs.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    println!("- [daemon_idle_shutdown_secs]: {}", v);
+                }
+            }
+            "sync_backends" => {
+                let old_values = file_config.sync_backends.take();
+                crate::config::save_file_config(&file_config)?;
+                for backend in old_values.unwrap_or_default() {
+                    println!(
+                        "- [sync_backends]: {} -> {}",
+                        backend.name, backend.backend_url
+                    );
+                }
+            }
+            "release_branches" => {
+                let old_values = file_config.release_branches.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(items) = old_values {
+                    log_array_removals(&items);
+                }
+            }
             "custom_attributes" => {
                 let old_value = file_config.custom_attributes.take();
                 crate::config::save_file_config(&file_config)?;
@@ -1353,6 +1955,60 @@ fn unset_config_value(key: &str) -> Result<(), String> {
         return Ok(());
     }
 
+    if key_path[0] == "commit_lint" {
+        if key_path.len() != 2 {
+            return Err(COMMIT_LINT_FIELD_ERROR.to_string());
+        }
+        let mut lint = file_config.commit_lint.clone().unwrap_or_default();
+        let old_value = match key_path[1].as_str() {
+            "mode" => {
+                let old = lint.mode;
+                lint.mode = CommitLintMode::Off;
+                (old != CommitLintMode::Off).then(|| old.as_str().to_string())
+            }
+            "types" => lint.types.take().map(|v| v.join(",")),
+            "scopes" => lint.scopes.take().map(|v| v.join(",")),
+            "max_subject_length" => lint.max_subject_length.take().map(|v| v.to_string()),
+            other => return Err(format!("Unknown commit_lint field: {}", other)),
+        };
+
+        file_config.commit_lint = if lint == CommitLintConfig::default() {
+            None
+        } else {
+            Some(lint)
+        };
+        crate::config::save_file_config(&file_config)?;
+        if let Some(v) = old_value {
+            println!("- [commit_lint.{}]: {}", key_path[1], v);
+        }
+        return Ok(());
+    }
+
+    if key_path[0] == "clients" {
+        if key_path.len() != 2 {
+            return Err(CLIENTS_FIELD_ERROR.to_string());
+        }
+        let mut clients = file_config.clients.clone().unwrap_or_default();
+        let old_value = match key_path[1].as_str() {
+            "include" => clients.include.take().map(|ids| ids.join(",")),
+            "exclude" => clients.exclude.take().map(|ids| ids.join(",")),
+            "policy_url" => clients.policy_url.take(),
+            "policy_public_key" => clients.policy_public_key.take(),
+            "audit_syslog" => clients.audit_syslog.take().map(|value| value.to_string()),
+            other => return Err(format!("Unknown clients field: {}", other)),
+        };
+        file_config.clients = if clients == ClientsConfig::default() {
+            None
+        } else {
+            Some(clients)
+        };
+        crate::config::save_file_config(&file_config)?;
+        if let Some(v) = old_value {
+            println!("- [clients.{}]: {}", key_path[1], v);
+        }
+        return Ok(());
+    }
+
     if key_path[0] == "author" {
         if key_path.len() != 2 {
             return Err("author requires a field name (author.name or author.email)".to_string());
@@ -1405,7 +2061,7 @@ fn unset_config_value(key: &str) -> Result<(), String> {
     }
 
     Err(
-        "Nested keys are only supported for feature_flags, git_ai_hooks, notes_backend, author, and custom_attributes"
+        "Nested keys are only supported for feature_flags, git_ai_hooks, notes_backend, author, commit_lint, clients, and custom_attributes"
             .to_string(),
     )
 }
@@ -1636,6 +2292,129 @@ fn parse_author_config_object(value: &str) -> Result<AuthorConfig, String> {
         .map_err(|e| format!("Invalid author config: {}", e))
 }
 
+fn parse_commit_lint_config_object(value: &str) -> Result<CommitLintConfig, String> {
+    let parsed: Value =
+        serde_json::from_str(value).map_err(|e| format!("Invalid JSON for commit_lint: {}", e))?;
+    if !parsed.is_object() {
+        return Err("commit_lint must be a JSON object".to_string());
+    }
+
+    serde_json::from_value::<CommitLintConfig>(parsed)
+        .map_err(|e| format!("Invalid commit_lint config: {}", e))
+}
+
+/// A JSON array of sync backends, or a single object (handy with `--add`)
+fn parse_sync_backends(value: &str) -> Result<Vec<SyncBackendConfig>, String> {
+    let parsed: Value = serde_json::from_str(value)
+        .map_err(|e| format!("Invalid JSON for sync_backends: {}", e))?;
+    let items = match parsed {
+        Value::Array(items) => items,
+        object @ Value::Object(_) => vec![object],
+        _ => return Err("sync_backends must be a JSON array or object".to_string()),
+    };
+    let mut backends = Vec::new();
+    for item in items {
+        let backend = serde_json::from_value::<SyncBackendConfig>(item)
+            .map_err(|e| format!("Invalid sync_backends entry: {}", e))?;
+        if backend.name.trim().is_empty() || backend.backend_url.trim().is_empty() {
+            return Err("sync_backends entries need a name and a backend_url".to_string());
+        }
+        if backend.remotes.is_empty() {
+            return Err(format!(
+                "sync_backends entry '{}' needs at least one remotes pattern",
+                backend.name
+            ));
+        }
+        backends.push(backend);
+    }
+    Ok(backends)
+}
+
+fn parse_redaction_config_object(value: &str) -> Result<RedactionConfig, String> {
+    let parsed: Value =
+        serde_json::from_str(value).map_err(|e| format!("Invalid JSON for redaction: {}", e))?;
+    if !parsed.is_object() {
+        return Err("redaction must be a JSON object".to_string());
+    }
+
+    serde_json::from_value::<RedactionConfig>(parsed)
+        .map_err(|e| format!("Invalid redaction config: {}", e))
+}
+
+fn parse_disk_budgets_config_object(value: &str) -> Result<DiskBudgetConfig, String> {
+    let parsed: Value =
+        serde_json::from_str(value).map_err(|e| format!("Invalid JSON for disk_budgets: {}", e))?;
+    if !parsed.is_object() {
+        return Err("disk_budgets must be a JSON object".to_string());
+    }
+
+    serde_json::from_value::<DiskBudgetConfig>(parsed).map_err(|e| {
+        format!(
+            "Invalid disk_budgets config: {}. Sizes are non-negative integers in bytes",
+            e
+        )
+    })
+}
+
+fn parse_clients_config_object(value: &str) -> Result<ClientsConfig, String> {
+    let parsed: Value =
+        serde_json::from_str(value).map_err(|e| format!("Invalid JSON for clients: {}", e))?;
+    if !parsed.is_object() {
+        return Err("clients must be a JSON object".to_string());
+    }
+
+    serde_json::from_value::<ClientsConfig>(parsed).map_err(|e| {
+        format!(
+            "Invalid clients config: {}. include and exclude are arrays of client ids",
+            e
+        )
+    })
+}
+
+/// Shows the effective free-space floor even when only the default applies
+fn disk_budgets_display_value(budgets: &DiskBudgetConfig) -> Value {
+    let mut value =
+        serde_json::to_value(budgets).unwrap_or_else(|_| Value::Object(serde_json::Map::new()));
+    value["min_free_bytes"] = Value::Number(budgets.min_free_bytes().into());
+    value
+}
+
+/// The salt is an org secret, so it's masked like an API key when displayed
+fn redaction_display_value(redaction: &RedactionConfig) -> Value {
+    let mut value =
+        serde_json::to_value(redaction).unwrap_or_else(|_| Value::Object(serde_json::Map::new()));
+    if let Some(salt) = &redaction.salt {
+        value["salt"] = Value::String(mask_api_key(salt));
+    }
+    value
+}
+
+fn parse_commit_lint_mode(value: &str) -> Result<CommitLintMode, String> {
+    match value.trim().to_lowercase().as_str() {
+        "off" => Ok(CommitLintMode::Off),
+        "warn" => Ok(CommitLintMode::Warn),
+        "strict" => Ok(CommitLintMode::Strict),
+        _ => Err(format!(
+            "Invalid commit_lint.mode '{}'. Expected 'off', 'warn', or 'strict'",
+            value
+        )),
+    }
+}
+
+/// Parse a list given either as a JSON array or as comma-separated values
+fn parse_string_list(value: &str) -> Result<Vec<String>, String> {
+    let items: Vec<String> = if value.trim_start().starts_with('[') {
+        serde_json::from_str(value).map_err(|e| format!("Invalid JSON array: {}", e))?
+    } else {
+        value.split(',').map(str::to_string).collect()
+    };
+    Ok(items
+        .into_iter()
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect())
+}
+
 /// Mask an API key for display (show first 4 and last 4 chars if long enough)
 fn mask_api_key(key: &str) -> String {
     if key.len() > 8 {
@@ -1668,6 +2447,15 @@ fn parse_codex_hooks_format(value: &str) -> Result<CodexHooksFormat, String> {
     }
 }
 
+fn parse_diff_algorithm(value: &str) -> Result<DiffAlgorithm, String> {
+    DiffAlgorithm::parse(value).ok_or_else(|| {
+        format!(
+            "Invalid attribution_diff_algorithm '{}'. Expected 'histogram', 'patience', or 'myers'",
+            value
+        )
+    })
+}
+
 /// Validate prompt_storage value
 fn validate_prompt_storage_value(value: &str) -> Result<(), String> {
     if value != "default" && value != "notes" && value != "local" {
@@ -1731,6 +2519,18 @@ mod tests {
         assert!(err.contains("hooks_json"));
     }
 
+    #[test]
+    fn test_diff_algorithm_values() {
+        assert_eq!(
+            parse_diff_algorithm("Histogram").unwrap(),
+            DiffAlgorithm::Histogram
+        );
+        assert_eq!(parse_diff_algorithm("myers").unwrap(), DiffAlgorithm::Myers);
+        let err = parse_diff_algorithm("minimal").unwrap_err();
+        assert!(err.contains("histogram"));
+        assert!(err.contains("patience"));
+    }
+
     #[test]
     fn test_parse_bool_valid_true_values() {
         for value in ["true", "1", "yes", "on", "TRUE", "True", "YES", "ON"] {
@@ -314,28 +314,68 @@ fn start_daemon_detached_with_config(
         return Ok(config);
     }
 
+    spawn_daemon_unless_blocked(&config)?;
+    if wait_for_daemon_up(&config, timeout) {
+        return Ok(config);
+    }
+
+    Err(format!(
+        "timed out after {:?} waiting for daemon sockets {} and {}",
+        timeout,
+        config.control_socket_path.display(),
+        config.trace_socket_path.display()
+    ))
+}
+
+#[cfg(not(any(test, feature = "test-support")))]
+fn spawn_daemon_unless_blocked(config: &DaemonConfig) -> Result<(), String> {
     ensure_daemon_start_allowed()?;
 
-    remove_stale_daemon_files(&config);
+    remove_stale_daemon_files(config);
 
-    if daemon_startup_is_blocked(&config) {
+    if daemon_startup_is_blocked(config) {
         return Err(format!(
             "daemon startup blocked: lock held at {}",
             config.lock_path.display()
         ));
     }
 
-    spawn_daemon_run_detached(&config)?;
-    if wait_for_daemon_up(&config, timeout) {
-        return Ok(config);
+    spawn_daemon_run_detached(config)
+}
+
+/// Start the daemon without waiting for it, when its sockets are missing.
+/// For the wrapped-git hot path: a running daemon costs two `stat` calls,
+/// and a stopped one is spawned to come up alongside the command.
+pub(crate) fn start_daemon_in_background() -> Result<(), String> {
+    let config = daemon_config_from_env_or_default_paths()?;
+    #[cfg(not(windows))]
+    if config.control_socket_path.exists() && config.trace_socket_path.exists() {
+        return Ok(());
+    }
+    // Named pipes can't be stat'ed, so probe them instead
+    #[cfg(windows)]
+    if daemon_is_up(&config) {
+        return Ok(());
     }
 
-    Err(format!(
-        "timed out after {:?} waiting for daemon sockets {} and {}",
-        timeout,
-        config.control_socket_path.display(),
-        config.trace_socket_path.display()
-    ))
+    #[cfg(any(test, feature = "test-support"))]
+    {
+        Err("daemon not running (test build: auto-spawn disabled)".to_string())
+    }
+
+    #[cfg(not(any(test, feature = "test-support")))]
+    {
+        if std::env::var("_GITAI_INTERNAL_DISABLE_WRAPPER_DAEMON_AUTOSPAWN")
+            .is_ok_and(|v| v == "1" || v == "true")
+        {
+            return Err(
+                "daemon auto-spawn disabled (_GITAI_INTERNAL_DISABLE_WRAPPER_DAEMON_AUTOSPAWN)"
+                    .to_string(),
+            );
+        }
+
+        spawn_daemon_unless_blocked(&config)
+    }
 }
 
 fn daemon_runtime_dir(config: &DaemonConfig) -> Result<PathBuf, String> {
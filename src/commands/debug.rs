@@ -24,7 +24,7 @@ const DEBUG_COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(100);
 const SKIP_TRACE2_CHECKS_FLAG: &str = "--skip-trace2-checks";
 
 #[derive(Debug, Clone, Copy, Default)]
-struct DebugOptions {
+pub(crate) struct DebugOptions {
     skip_trace2_checks: bool,
 }
 
@@ -83,10 +83,11 @@ fn debug_progress(message: impl AsRef<str>) {
     );
 }
 
-fn build_debug_report(options: DebugOptions) -> String {
+pub(crate) fn build_debug_report(options: DebugOptions) -> String {
     debug_progress("starting debug report");
     let config = config::Config::get();
-    let git_cmd = config.git_cmd().to_string();
+    // Without a real git the probes below report their own failures
+    let git_cmd = config.real_git().unwrap_or_default().to_string();
     debug_progress("resolving configured and shell git paths");
     let git_cmd_realpath = realpath_for_display(&git_cmd);
     let shell_git_lookup = collect_shell_git_lookup();
@@ -136,7 +137,11 @@ fn build_debug_report(options: DebugOptions) -> String {
             .map(|p| p.display().to_string())
             .unwrap_or_else(|e| format!("<unavailable: {}>", e))
     );
-    let _ = writeln!(out, "Git binary path: {}", git_cmd);
+    let _ = writeln!(
+        out,
+        "Git binary path: {}",
+        config.real_git().unwrap_or("<not found>")
+    );
     let _ = writeln!(out, "Git binary realpath: {}", git_cmd_realpath);
     let _ = writeln!(
         out,
@@ -9,6 +9,7 @@
 
 use crate::auth::CredentialStore;
 use crate::auth::client::OAuthClient;
+use crate::output::Status;
 
 /// Handle the exchange-nonce command (internal - called by install scripts)
 ///
@@ -49,6 +50,6 @@ fn exchange_nonce(nonce: &str, api_base: &str) -> Result<(), String> {
     let store = CredentialStore::new();
     store.store(&credentials)?;
 
-    eprintln!("\x1b[32m✓ Logged in automatically\x1b[0m");
+    eprintln!("{}", Status::Ok.line("Logged in automatically"));
     Ok(())
 }
@@ -3,8 +3,9 @@
 //! Uploads pending metrics database rows to the API.
 
 use crate::api::{ApiClient, ApiContext, metrics_upload_allowed, upload_metrics_with_retry};
+use crate::config::Config;
 use crate::metrics::db::MetricsDatabase;
-use crate::metrics::{MetricEvent, MetricsBatch};
+use crate::metrics::{MetricEvent, MetricsBatch, batch_for_upload};
 
 /// Max events per batch upload
 const MAX_BATCH_SIZE: usize = 1000;
@@ -82,6 +83,7 @@ pub fn handle_flush_metrics_db(_args: &[String]) {
 
         let event_count = events.len();
         let metrics_batch = MetricsBatch::new(events);
+        let metrics_batch = batch_for_upload(&metrics_batch, Config::get());
 
         // Upload with the HTTP helper's short retry, then persist DB backoff on failure.
         match upload_metrics_with_retry(&client, &metrics_batch, "flush_metrics_db") {
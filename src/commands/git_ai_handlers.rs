@@ -1,7 +1,9 @@
+use crate::app_context;
 use crate::authorship::ignore::effective_ignore_patterns;
 use crate::authorship::internal_db::InternalDatabase;
 use crate::authorship::range_authorship;
 use crate::authorship::stats::stats_command;
+use crate::capabilities;
 use crate::commands;
 use crate::config;
 use crate::daemon::ControlRequest;
@@ -24,11 +26,44 @@ pub fn handle_git_ai(args: &[String]) {
             None
         };
 
+    // Global flags are only recognized before the command: `git-ai log
+    // --plain` already means something to git.
+    let mut args = args;
+    loop {
+        if args.first().is_some_and(|first| first == "--plain") {
+            crate::output::set_plain(true);
+            args = &args[1..];
+            continue;
+        }
+        let (rest, overrides) = match app_context::split_path_flags(args) {
+            Ok(split) => split,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                std::process::exit(1);
+            }
+        };
+        if overrides.is_empty() {
+            break;
+        }
+        for (var, path) in overrides {
+            // SAFETY: this runs at startup, before git-ai spawns any threads.
+            // Exporting the override lets the daemon and git-ai child
+            // processes resolve the same paths.
+            unsafe { std::env::set_var(var, path) };
+        }
+        args = rest;
+    }
+
     if args.is_empty() {
         print_help();
         return;
     }
 
+    if let Err(err) = capabilities::check_command(&args[0], &args[1..], capabilities::available) {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+
     // Initialize the global telemetry handle so that observability and CAS
     // events are routed over the control socket instead of being written to
     // per-PID log files.
@@ -53,7 +88,14 @@ pub fn handle_git_ai(args: &[String]) {
             | "install-hooks"
             | "install"
             | "uninstall-hooks"
+            | "clients"
+            | "mdm"
             | "usage"
+            | "report"
+            | "privacy"
+            | "server-hook"
+            | "serve-dashboard"
+            | "commit-msg-editor"
     );
     if needs_daemon {
         use crate::daemon::telemetry_handle::{
@@ -112,6 +154,24 @@ pub fn handle_git_ai(args: &[String]) {
         "usage" => {
             commands::usage::handle_usage(&args[1..]);
         }
+        "report" => {
+            commands::report::handle_report(&args[1..]);
+        }
+        "privacy" => {
+            commands::privacy::handle_privacy(&args[1..]);
+        }
+        "split" => {
+            commands::split::handle_split(&args[1..]);
+        }
+        "bisect-report" => {
+            commands::bisect_report::handle_bisect_report(&args[1..]);
+        }
+        "server-hook" => {
+            commands::server_hook::handle_server_hook(&args[1..]);
+        }
+        "serve-dashboard" => {
+            commands::serve_dashboard::handle_serve_dashboard(&args[1..]);
+        }
         "analyze" => {
             commands::analyze::handle_analyze(&args[1..]);
             if is_interactive_terminal() {
@@ -168,6 +228,12 @@ pub fn handle_git_ai(args: &[String]) {
                 std::process::exit(1);
             }
         },
+        "clients" => {
+            commands::clients::handle_clients(&args[1..]);
+        }
+        "mdm" => {
+            commands::mdm::handle_mdm(&args[1..]);
+        }
         "uninstall-hooks" => match commands::install_hooks::run_uninstall(&args[1..]) {
             Ok(statuses) => {
                 if let Ok(statuses_value) = serde_json::to_value(&statuses) {
@@ -215,6 +281,18 @@ pub fn handle_git_ai(args: &[String]) {
         "fetch-notes" => {
             commands::fetch_notes::handle_fetch_notes(&args[1..]);
         }
+        "sync" => {
+            commands::sync::handle_sync(&args[1..]);
+        }
+        "selftest" => {
+            commands::selftest::handle_selftest(&args[1..]);
+        }
+        "support-bundle" => {
+            commands::support_bundle::handle_support_bundle(&args[1..]);
+        }
+        commands::git_handlers::COMMIT_MSG_EDITOR_COMMAND => {
+            commands::git_handlers::handle_commit_msg_editor(&args[1..]);
+        }
         "effective-ignore-patterns" => {
             handle_effective_ignore_patterns_internal(&args[1..]);
         }
@@ -231,6 +309,15 @@ pub fn handle_git_ai(args: &[String]) {
             handle_notes_subcommand(&args[1..]);
         }
         _ => {
+            if let Some(plugin) = commands::plugin::find_plugin(&args[0]) {
+                match commands::plugin::run_plugin(&plugin, &args[0], &args[1..]) {
+                    Ok(code) => std::process::exit(code),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
             println!("Unknown git-ai command: {}", args[0]);
             std::process::exit(1);
         }
@@ -315,7 +402,19 @@ fn handle_notes_serve(args: &[String]) {
 fn print_help() {
     eprintln!("git-ai - git proxy with AI authorship tracking");
     eprintln!();
-    eprintln!("Usage: git-ai <command> [args...]");
+    eprintln!("Usage: git-ai [global flags] <command> [args...]");
+    eprintln!();
+    eprintln!("Global flags:");
+    eprintln!("  --plain            Screen-reader friendly output: no colors, spinners or");
+    eprintln!("                     box drawing, and status words instead of symbols");
+    eprintln!("                     (also GIT_AI_PLAIN_OUTPUT=1 or TERM=dumb)");
+    eprintln!(
+        "  --config <file>    Config file (GIT_AI_CONFIG_FILE; default ~/.git-ai/config.json)"
+    );
+    eprintln!("  --state-dir <dir>  Databases, daemon and credentials (GIT_AI_STATE_DIR;");
+    eprintln!("                     default ~/.git-ai/internal)");
+    eprintln!("  --cache-dir <dir>  Caches (GIT_AI_CACHE_DIR; default the state dir)");
+    eprintln!("  --log-dir <dir>    Daemon logs (GIT_AI_LOG_DIR; default <state dir>/daemon/logs)");
     eprintln!();
     eprintln!("Commands:");
     eprintln!("  checkpoint         Checkpoint working changes and attribute author");
@@ -346,6 +445,21 @@ fn print_help() {
     eprintln!("  usage              Show local AI usage statistics");
     eprintln!("    --period <1d|3d|7d|30d>  Time window (default: 30d)");
     eprintln!("    --json                 Output in JSON format");
+    eprintln!("  report             Show AI share of committed code over time");
+    eprintln!("    --trend <daily|weekly|monthly>  Period size (default: monthly)");
+    eprintln!("    --group-by <key>       Split each period by a custom attribute");
+    eprintln!("    --format <table|csv|json>  Output format (default: table)");
+    eprintln!("  serve-dashboard    Serve a read-only AI usage dashboard");
+    eprintln!("    --bind <addr>          Listen address (default: 127.0.0.1:8787)");
+    eprintln!("  split              Split staged changes into separate commits");
+    eprintln!("    --yes                  Commit the proposed split without prompting");
+    eprintln!("    --dry-run              Print the proposed split and exit");
+    eprintln!("  bisect-report [<bad>..<good>]  Attribution context for a bisected regression");
+    eprintln!("    --json                 Output in JSON format");
+    eprintln!("  server-hook        Check attribution policy as a pre-receive/update hook");
+    eprintln!("    --mode <off|warn|enforce>  Override git-ai.serverHook.mode");
+    eprintln!("  privacy delete --author <email>  Remove a person's attribution records");
+    eprintln!("    --dry-run              Report what would change without modifying anything");
     eprintln!("  analyze [beta]      Analyze agent sessions and effectiveness");
     eprintln!("  status             Show uncommitted AI authorship status (debug)");
     eprintln!("    --json                 Output in JSON format");
@@ -364,13 +478,63 @@ fn print_help() {
     eprintln!("    set <key> <value>     Set a config value (arrays: single value = [value])");
     eprintln!("    --add <key> <value>   Add to array or upsert into object");
     eprintln!("    unset <key>           Remove config value (reverts to default)");
+    eprintln!("    explain <key>         Show a value and where it was set");
+    eprintln!("    dump --provenance     Show every value and where it was set");
     eprintln!("  debug              Print support/debug diagnostics");
+    eprintln!("  selftest           Check that the git shim never changes git's behavior");
+    eprintln!("    --json                Output results as JSON");
+    eprintln!("    --report <file>       Write a Markdown report for bug filing");
+    eprintln!("  support-bundle     Collect redacted diagnostics into a zip for bug reports");
+    eprintln!("    -o, --output <file>   Archive path");
+    eprintln!("    --exclude <name>      Leave out a file from the bundle");
+    eprintln!("    -y, --yes             Skip the confirmation prompt");
     eprintln!("  bg                 Run and control git-ai background service");
     eprintln!("  install-hooks      Install git hooks for AI authorship tracking");
     eprintln!("    --skills               Also install agent skill files");
     eprintln!("    --visual-studio-extension");
     eprintln!("                           Also install the Visual Studio extension on Windows");
+    eprintln!("    --keep-partial         Keep configured git clients if a later one fails");
+    eprintln!("    --force                Configure git clients even while they are running");
     eprintln!("  uninstall-hooks    Remove git-ai hooks from all detected tools");
+    eprintln!("  clients check      Report git client preferences and pending changes");
+    eprintln!("    --json                 Output an array of per-client results");
+    eprintln!("    --no-cache             Rediscover clients instead of using the cached results");
+    eprintln!("    --summary              Print only the consolidated summary of pending changes");
+    eprintln!("  clients install    Point detected git clients at the git shim");
+    eprintln!("    --json                 Output an array of per-client results");
+    eprintln!("    --keep-partial         Keep configured clients if a later one fails");
+    eprintln!("    --force                Configure clients even while they are running");
+    eprintln!("    --scope system         Machine-wide preferences for every user (needs root)");
+    eprintln!("    --emit-patch <dir>     Write pending changes as patches instead of applying");
+    eprintln!("    --interactive          Pick the clients to configure or revert from a list");
+    eprintln!("    --verify               Re-check each client after configuring it");
+    eprintln!(
+        "    --all-users            Configure every user profile on the machine (needs root)"
+    );
+    eprintln!("    --shim-path <template> Use an existing shim, e.g. ${{HOME}}/.git-ai/bin/git");
+    eprintln!("  clients drift      Compare each client's configured git with the shim");
+    eprintln!("    --json                 Output an array of per-client results");
+    eprintln!("  clients uninstall --all  Revert every git client and remove the git shim");
+    eprintln!("    --dry-run              Show what would be reverted");
+    eprintln!("    --json                 Output per-client and shim results");
+    eprintln!("  clients watch      Re-apply git client preferences when a client resets them");
+    eprintln!("    --interval <seconds>   Time between checks (default: 60)");
+    eprintln!("    --once                 Check once and exit");
+    eprintln!("  clients restore <client>  Revert git client preferences changed by install-hooks");
+    eprintln!("    --list                 List saved snapshots");
+    eprintln!("    --snapshot <timestamp> Restore a specific snapshot (default: newest)");
+    eprintln!("  mdm export-profile Write a macOS .mobileconfig enforcing client preferences");
+    eprintln!("    --shim-path <path>     The shim's path on managed machines");
+    eprintln!("    --output <file>        Write to a file instead of stdout");
+    eprintln!("    --sign <identity>      Sign with a keychain identity (macOS)");
+    eprintln!(
+        "  mdm export-policy  Write a Windows .reg, DSC or Intune policy for client settings"
+    );
+    eprintln!("    --format <format>      reg, dsc or intune");
+    eprintln!("    --scope user           Target HKCU instead of HKLM");
+    eprintln!("  mdm sync           Apply the signed client policy from clients.policy_url");
+    eprintln!("    --dry-run              Report what the policy would change");
+    eprintln!("    --json                 Output the policy and per-client results");
     eprintln!("  ci                 Continuous integration utilities");
     eprintln!("    github                 GitHub CI helpers");
     eprintln!("  git-path           Print the path to the underlying git executable");
@@ -381,12 +545,17 @@ fn print_help() {
     eprintln!("  fetch-notes [remote] Synchronously fetch AI authorship notes");
     eprintln!("    --remote <name>       Explicit remote name (default: upstream or origin)");
     eprintln!("    --json                Output result as JSON");
+    eprintln!("  sync status        Show queue health for each notes sync backend");
     eprintln!("  login              Authenticate with Git AI");
     eprintln!("  logout             Clear stored credentials");
     eprintln!("  whoami             Show auth state and login identity");
     eprintln!("  version, -v, --version     Print the git-ai version");
     eprintln!("  help, -h, --help           Show this help message");
     eprintln!();
+    eprintln!("Any other <command> runs a `git-ai-<command>` executable from PATH, with");
+    eprintln!("GIT_AI_BINARY, GIT_AI_CONFIG_PATH, GIT_AI_GIT_PATH and GIT_AI_REPO_ROOT set");
+    eprintln!("and a JSON context object on stdin.");
+    eprintln!();
     std::process::exit(0);
 }
 
@@ -1,8 +1,11 @@
 use crate::commands::git_hook_handlers::ENV_SKIP_MANAGED_HOOKS;
+use crate::commit_lint::{MessageSource, commit_message_source, lint_message, suggest_fix};
 use crate::config;
-use crate::git::cli_parser::{ParsedGitInvocation, parse_git_cli_args};
+use crate::config::CommitLintMode;
+use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run, parse_git_cli_args};
 use crate::git::find_repository;
-use crate::git::repository::Repository;
+use crate::git::repository::{Repository, exec_git, resolve_command_base_dir};
+use crate::mdm::utils::normalize_windows_path_for_shell;
 #[cfg(windows)]
 use crate::utils::CREATE_NO_WINDOW;
 #[cfg(windows)]
@@ -53,6 +56,12 @@ fn uninstall_forwarding_handlers() {
 }
 
 pub fn handle_git(args: &[String]) {
+    if let Err(err) = crate::capabilities::check_command("git", &[], crate::capabilities::available)
+    {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+
     // If we're being invoked from a shell completion context, bypass git-ai logic
     // and delegate directly to the real git so existing completion scripts work.
     if in_shell_completion_context() {
@@ -76,7 +85,38 @@ pub fn handle_git(args: &[String]) {
     }
 
     let repository = find_repository(&parsed.global_args).ok();
-    let exit_status = proxy_to_git(args, false);
+
+    let is_commit = parsed.command.as_deref() == Some("commit") && !parsed.is_help;
+    let message_source = is_commit
+        .then(|| commit_lint_message_source(&parsed))
+        .flatten();
+    if let Some(MessageSource::Explicit(message)) = &message_source
+        && !check_commit_message(message, false)
+    {
+        std::process::exit(1);
+    }
+    // Strict mode checks an editor-composed message as the editor closes,
+    // before git creates the commit.
+    let editor_env = (message_source == Some(MessageSource::Deferred)
+        && config::Config::get().commit_lint().mode == CommitLintMode::Strict)
+        .then(|| repository.as_ref().and_then(commit_lint_editor_env))
+        .flatten()
+        .unwrap_or_default();
+
+    let exit_status = proxy_to_git_with_env(args, false, &editor_env);
+
+    // A message git didn't open an editor for (reused with `-C`/`--no-edit`,
+    // or read from stdin) can only be reported once the commit exists.
+    if exit_status.success()
+        && message_source == Some(MessageSource::Deferred)
+        && let Some(repo) = repository.as_ref()
+        && let Some(message) = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok()?.summary().ok())
+    {
+        check_commit_message(&message, true);
+    }
 
     // After a successful commit, wait briefly for the daemon to produce an
     // authorship note so we can show stats inline (same UX as plain wrapper mode).
@@ -90,6 +130,122 @@ pub fn handle_git(args: &[String]) {
     exit_with_status(exit_status);
 }
 
+/// Message source for a `git commit` that should be linted, or `None` when
+/// Conventional Commits checks are off or bypassed with `--no-verify`.
+fn commit_lint_message_source(parsed: &ParsedGitInvocation) -> Option<MessageSource> {
+    if config::Config::get().commit_lint().mode == CommitLintMode::Off
+        || parsed.has_command_flag("--no-verify")
+        || parsed.has_command_flag("-n")
+        || is_dry_run(&parsed.command_args)
+    {
+        return None;
+    }
+    let base_dir = resolve_command_base_dir(&parsed.global_args).ok()?;
+    Some(commit_message_source(&parsed.command_args, &base_dir))
+}
+
+/// Print Conventional Commits violations for `message`. Returns `false` when
+/// the commit should be rejected: strict mode, with the commit not made yet.
+/// A commit that already exists is only reported.
+fn check_commit_message(message: &str, already_committed: bool) -> bool {
+    let lint_config = config::Config::get().commit_lint();
+    let issues = lint_message(message, lint_config);
+    if issues.is_empty() {
+        return true;
+    }
+
+    let reject = lint_config.mode == CommitLintMode::Strict && !already_committed;
+    eprintln!(
+        "[git-ai] commit message does not follow Conventional Commits{}:",
+        if reject { "; commit aborted" } else { "" }
+    );
+    for issue in &issues {
+        eprintln!("  - {}", issue);
+    }
+    if let Some(fixed) = suggest_fix(message, lint_config) {
+        let header = fixed.lines().next().unwrap_or_default();
+        if already_committed {
+            eprintln!("  suggested: git commit --amend -m \"{}\"", header);
+        } else {
+            eprintln!("  suggested: {}", header);
+        }
+    }
+    !reject
+}
+
+/// Hidden `git-ai` subcommand strict mode sets as git's editor for a commit
+pub const COMMIT_MSG_EDITOR_COMMAND: &str = "commit-msg-editor";
+
+/// The user's own editor, for `git-ai commit-msg-editor` to run
+const ENV_COMMIT_MSG_EDITOR: &str = "GIT_AI_COMMIT_MSG_EDITOR";
+
+/// Alias the user's editor is run through, so it goes through git's shell
+/// exactly as git itself would run it (on Windows too)
+const EDITOR_ALIAS: &str = "git-ai-commit-msg-editor";
+
+/// Environment that makes git open `git-ai commit-msg-editor` in place of the
+/// user's editor. `None` when git has no editor to run (a dumb terminal with
+/// none configured), which git then reports itself.
+fn commit_lint_editor_env(repo: &Repository) -> Option<Vec<(&'static str, String)>> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["var", "GIT_EDITOR"].map(String::from));
+    let output = exec_git(&args).ok()?;
+    let editor = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let exe = crate::utils::current_git_ai_exe().ok()?;
+    let exe = normalize_windows_path_for_shell(&exe);
+    let wrapper = format!(
+        "'{}' {}",
+        exe.replace('\'', "'\\''"),
+        COMMIT_MSG_EDITOR_COMMAND
+    );
+    Some(vec![
+        ("GIT_EDITOR", wrapper),
+        (ENV_COMMIT_MSG_EDITOR, editor),
+    ])
+}
+
+/// `git-ai commit-msg-editor <file>`: run the user's editor on `file`, then
+/// fail when the commit message it leaves breaks the rules, so git aborts the
+/// commit before creating it. Other files git opens an editor for during a
+/// commit (`commit -p` hunk edits) are passed through unchecked.
+pub fn handle_commit_msg_editor(args: &[String]) {
+    let Some(file) = args.first() else {
+        eprintln!("usage: git-ai {} <file>", COMMIT_MSG_EDITOR_COMMAND);
+        std::process::exit(1);
+    };
+    // The alias below runs from the repository root
+    let path = std::path::absolute(file).unwrap_or_else(|_| file.into());
+    let editor = std::env::var(ENV_COMMIT_MSG_EDITOR).unwrap_or_else(|_| "vi".to_string());
+    let status = Command::new(config::Config::get().git_cmd())
+        .arg("-c")
+        .arg(format!("alias.{}=!{}", EDITOR_ALIAS, editor))
+        .arg(EDITOR_ALIAS)
+        .arg(&path)
+        .env("GIT_TRACE2_EVENT", "0")
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => {
+            eprintln!("[git-ai] failed to run editor '{}': {}", editor, e);
+            std::process::exit(1);
+        }
+    }
+
+    if path.file_name().is_none_or(|name| name != "COMMIT_EDITMSG") {
+        return;
+    }
+    let message = std::fs::read_to_string(&path).unwrap_or_default();
+    // An empty message is git's to refuse, with its own error
+    let only_comments = message
+        .lines()
+        .all(|line| line.trim().is_empty() || line.starts_with('#'));
+    if !only_comments && !check_commit_message(&message, false) {
+        eprintln!("  edit and retry: git commit -e -F {}", path.display());
+        std::process::exit(1);
+    }
+}
+
 #[cfg(feature = "test-support")]
 pub fn resolve_alias_invocation(
     parsed_args: &ParsedGitInvocation,
@@ -201,7 +357,6 @@ fn parse_alias_tokens(value: &str) -> Option<Vec<String>> {
 fn maybe_show_async_post_commit_stats(parsed: &ParsedGitInvocation, repo: &Repository) {
     use crate::authorship::ignore::effective_ignore_patterns;
     use crate::authorship::stats::{stats_for_commit_stats, write_stats_to_terminal};
-    use crate::git::cli_parser::is_dry_run;
     use crate::git::notes_api::read_note;
     use std::io::IsTerminal;
 
@@ -297,7 +452,16 @@ fn maybe_show_async_post_commit_stats(parsed: &ParsedGitInvocation, repo: &Repos
     }
 }
 
-fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::ExitStatus {
+pub(crate) fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::ExitStatus {
+    proxy_to_git_with_env(args, exit_on_completion, &[])
+}
+
+/// [`proxy_to_git`] with extra environment for the git process
+fn proxy_to_git_with_env(
+    args: &[String],
+    exit_on_completion: bool,
+    extra_env: &[(&str, String)],
+) -> std::process::ExitStatus {
     // Suppress trace2 for read-only invocations to avoid hitting the daemon
     // with events that can never produce meaningful state changes.
     let suppress_trace2 = {
@@ -310,6 +474,16 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
         })
     };
 
+    // With idle shutdown enabled the daemon may have exited. Bring it back
+    // without holding up the command; until its sockets are up, trace2 events
+    // go nowhere, as they do whenever the daemon is down.
+    if !suppress_trace2
+        && config::Config::get().daemon_idle_shutdown().is_some()
+        && let Err(e) = crate::commands::daemon::start_daemon_in_background()
+    {
+        tracing::debug!("failed to restart idle-stopped daemon: {}", e);
+    }
+
     // Use spawn for interactive commands
     let child = {
         #[cfg(unix)]
@@ -323,6 +497,7 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
             let mut cmd = Command::new(config::Config::get().git_cmd());
             cmd.args(args);
             cmd.env(ENV_SKIP_MANAGED_HOOKS, "1");
+            cmd.envs(extra_env.iter().map(|(key, value)| (key, value)));
             if suppress_trace2 {
                 cmd.env("GIT_TRACE2_EVENT", "0");
             }
@@ -347,6 +522,7 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
             let mut cmd = Command::new(config::Config::get().git_cmd());
             cmd.args(args);
             cmd.env(ENV_SKIP_MANAGED_HOOKS, "1");
+            cmd.envs(extra_env.iter().map(|(key, value)| (key, value)));
             if suppress_trace2 {
                 cmd.env("GIT_TRACE2_EVENT", "0");
             }
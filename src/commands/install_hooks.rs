@@ -2,10 +2,21 @@ use crate::config;
 use crate::daemon::DaemonConfig;
 use crate::error::GitAiError;
 use crate::mdm::agents::get_all_installers;
+use crate::mdm::audit_log::{self, AuditAction};
+use crate::mdm::change_summary::ChangeSummary;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstallTransaction, GitClientInstaller,
+    GitClientInstallerParams, InstallScope, check_clients_parallel,
+};
+use crate::mdm::git_clients::get_all_git_client_installers;
 use crate::mdm::hook_installer::HookInstallerParams;
+use crate::mdm::prefs_backup::{BackupSession, backups_root};
 use crate::mdm::skills_installer;
 use crate::mdm::spinner::{Spinner, print_diff};
-use crate::mdm::utils::get_current_binary_path;
+use crate::mdm::utils::{
+    ShimStrategy, ensure_git_shim, get_current_binary_path, git_shim_path, installed_shim_strategy,
+};
+use crate::output::{self, Status};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -22,6 +33,8 @@ struct InstallOptions {
     verbose: bool,
     install_skills: bool,
     include_visual_studio_extension: bool,
+    keep_partial: bool,
+    force: bool,
     api_base: Option<String>,
     api_key: Option<String>,
 }
@@ -37,6 +50,10 @@ pub enum InstallStatus {
     AlreadyInstalled,
     /// Installation attempted but failed
     Failed,
+    /// Tool was detected but can't be configured
+    Unsupported,
+    /// Left alone because the `clients` config doesn't allow it
+    Excluded,
 }
 
 impl InstallStatus {
@@ -47,6 +64,8 @@ impl InstallStatus {
             InstallStatus::Installed => "installed",
             InstallStatus::AlreadyInstalled => "already_installed",
             InstallStatus::Failed => "failed",
+            InstallStatus::Unsupported => "unsupported",
+            InstallStatus::Excluded => "excluded",
         }
     }
 }
@@ -92,6 +111,22 @@ impl InstallResult {
         }
     }
 
+    pub fn unsupported(reason: impl Into<String>) -> Self {
+        Self {
+            status: InstallStatus::Unsupported,
+            error: None,
+            warnings: vec![reason.into()],
+        }
+    }
+
+    pub fn excluded() -> Self {
+        Self {
+            status: InstallStatus::Excluded,
+            error: None,
+            warnings: vec!["excluded by the clients.include/clients.exclude config".to_string()],
+        }
+    }
+
     #[allow(dead_code)]
     pub fn with_warning(mut self, warning: impl Into<String>) -> Self {
         self.warnings.push(warning.into());
@@ -126,7 +161,7 @@ fn print_amp_plugins_note(installer_id: &str) {
 
 /// Find PIDs of running processes that match any of the given process names.
 /// Returns a list of (pid, process_name) tuples for each match found.
-fn find_running_pids(process_names: &[&str]) -> Vec<(u32, String)> {
+pub(crate) fn find_running_pids(process_names: &[&str]) -> Vec<(u32, String)> {
     if process_names.is_empty() {
         return vec![];
     }
@@ -199,6 +234,13 @@ fn find_running_pids(process_names: &[&str]) -> Vec<(u32, String)> {
     results
 }
 
+fn join_pids(pids: &[u32]) -> String {
+    pids.iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn set_global_git_config_value(git_cmd: &str, key: &str, value: &str) -> Result<(), GitAiError> {
     let mut command = Command::new(git_cmd);
     command
@@ -365,6 +407,8 @@ fn parse_install_options(args: &[String]) -> Result<InstallOptions, GitAiError>
             "--verbose" | "-v" => options.verbose = true,
             "--skills" => options.install_skills = true,
             "--visual-studio-extension" => options.include_visual_studio_extension = true,
+            "--keep-partial" => options.keep_partial = true,
+            "--force" => options.force = true,
             value if value.starts_with("--api-base=") => {
                 options.api_base = non_empty_value(&value[11..]);
             }
@@ -535,7 +579,7 @@ async fn async_run_install(
     let mut detailed_results: Vec<(String, InstallResult)> = Vec::new();
 
     // === Coding Agents ===
-    println!("\n\x1b[1mCoding Agents\x1b[0m");
+    println!("\n{}", output::paint(output::BOLD, "Coding Agents"));
 
     let installers = get_all_installers();
     let mut installed_tools: HashSet<String> = HashSet::new();
@@ -700,6 +744,28 @@ async fn async_run_install(
         }
     }
 
+    // === Git Clients ===
+    let git_clients = run_git_client_installers(
+        &params.binary_path,
+        GitClientRunOptions {
+            dry_run: options.dry_run,
+            verbose: options.verbose,
+            keep_partial: options.keep_partial,
+            force: options.force,
+            scope: InstallScope::User,
+            quiet: false,
+            verify: false,
+            shim_path: None,
+            clients: None,
+        },
+    );
+    any_checked |= git_clients.any_checked;
+    has_changes |= git_clients.has_changes;
+    for report in git_clients.reports {
+        statuses.insert(report.id.clone(), report.result.status);
+        detailed_results.push((report.id, report.result));
+    }
+
     if options.install_skills {
         if let Ok(result) =
             skills_installer::install_skills(options.dry_run, options.verbose, &installed_tools)
@@ -716,9 +782,15 @@ async fn async_run_install(
     if !any_checked {
         println!("No compatible IDEs or agent configurations detected. Nothing to install.");
     } else if has_changes && options.dry_run {
-        println!("\n\x1b[33m⚠ Dry-run mode (default). No changes were made.\x1b[0m");
+        println!(
+            "\n{}",
+            Status::Warning.line("Dry-run mode (default). No changes were made.")
+        );
         println!("To apply these changes, run:");
-        println!("\x1b[1m  git-ai install-hooks --dry-run=false\x1b[0m");
+        println!(
+            "  {}",
+            output::paint(output::BOLD, "git-ai install-hooks --dry-run=false")
+        );
     }
 
     // Check for running agents that had hooks updated and warn about restart
@@ -731,14 +803,17 @@ async fn async_run_install(
             if !pids.is_empty() {
                 if !any_running {
                     println!(
-                        "\n\x1b[33m⚠ The following agents are currently running and must be restarted:\x1b[0m"
+                        "\n{}",
+                        Status::Warning.line(
+                            "The following agents are currently running and must be restarted:"
+                        )
                     );
                     any_running = true;
                 }
                 let pid_list: Vec<String> = pids.iter().map(|(pid, _)| pid.to_string()).collect();
                 println!(
-                    "  \x1b[1m{}\x1b[0m (PID: {})",
-                    agent_name,
+                    "  {} (PID: {})",
+                    output::paint(output::BOLD, agent_name),
                     pid_list.join(", ")
                 );
             }
@@ -747,7 +822,11 @@ async fn async_run_install(
         if any_running {
             println!();
             println!(
-                "\x1b[33mRestart the agents listed above for git-ai attribution to take effect.\x1b[0m"
+                "{}",
+                output::paint(
+                    output::YELLOW,
+                    "Restart the agents listed above for git-ai attribution to take effect."
+                )
             );
             println!(
                 "Any work done before installing git-ai (or before restarting) will be attributed as human."
@@ -772,6 +851,473 @@ async fn async_run_install(
     Ok(statuses)
 }
 
+/// Options for [`run_git_client_installers`]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GitClientRunOptions {
+    pub dry_run: bool,
+    pub verbose: bool,
+    pub keep_partial: bool,
+    /// Write preferences even while the client is running
+    pub force: bool,
+    /// Configure the user's preferences or the machine-wide ones
+    pub scope: InstallScope,
+    /// Print nothing; callers report from the returned [`GitClientReport`]s
+    pub quiet: bool,
+    /// Re-check each configured client afterwards (see
+    /// [`GitClientInstaller::verify_prefs`])
+    pub verify: bool,
+    /// An existing shim to point the clients at, resolved from a
+    /// `--shim-path` template, instead of the one next to this binary
+    pub shim_path: Option<PathBuf>,
+    /// Which clients to configure, replacing the `clients` config (from a
+    /// `git-ai mdm sync` policy)
+    pub clients: Option<config::ClientsConfig>,
+}
+
+/// One git client's state and what the run did to it
+#[derive(Debug, Clone)]
+pub(crate) struct GitClientReport {
+    pub id: String,
+    pub name: String,
+    pub client_installed: bool,
+    pub prefs_configured: bool,
+    pub prefs_up_to_date: bool,
+    /// The preference change made, or pending on a dry run
+    pub diff: Option<String>,
+    /// `diff` is only pending (dry run)
+    pub pending: bool,
+    /// The client was running when its preferences were due to change
+    pub running: bool,
+    /// Whether the post-install verification passed; `None` when it didn't run
+    pub verified: Option<bool>,
+    pub result: InstallResult,
+}
+
+impl GitClientReport {
+    pub(crate) fn new(id: &str, name: &str, result: InstallResult) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            client_installed: false,
+            prefs_configured: false,
+            prefs_up_to_date: false,
+            diff: None,
+            pending: false,
+            running: false,
+            verified: None,
+            result,
+        }
+    }
+
+    fn checked(id: &str, name: &str, check: &GitClientCheckResult, result: InstallResult) -> Self {
+        Self {
+            client_installed: check.client_installed,
+            prefs_configured: check.prefs_configured,
+            prefs_up_to_date: check.prefs_up_to_date,
+            ..Self::new(id, name, result)
+        }
+    }
+
+    /// Record the outcome of [`verify_client`]; a problem becomes a warning
+    fn set_verification(&mut self, verification: Option<Result<(), String>>) {
+        if let Some(verification) = verification {
+            self.verified = Some(verification.is_ok());
+            if let Err(problem) = verification {
+                self.result
+                    .warnings
+                    .push(format!("not verified: {}", problem));
+            }
+        }
+    }
+
+    /// The object `--json` emits. The check fields describe the client as
+    /// found, before this run changed anything.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut value = serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "client_installed": self.client_installed,
+            "prefs_configured": self.prefs_configured,
+            "prefs_up_to_date": self.prefs_up_to_date,
+            "diff": self.diff,
+            "running": self.running,
+            "verified": self.verified,
+            "status": if self.pending {
+                "pending"
+            } else {
+                self.result.status.as_str()
+            },
+        });
+        if let Some(message) = self.result.message_for_metrics() {
+            value["message"] = serde_json::Value::String(message);
+        }
+        value
+    }
+}
+
+/// Result of [`run_git_client_installers`]
+#[derive(Debug, Default)]
+pub(crate) struct GitClientRun {
+    pub reports: Vec<GitClientReport>,
+    /// Whether any client was detected
+    pub any_checked: bool,
+    pub has_changes: bool,
+}
+
+impl GitClientRun {
+    /// The pending changes of a dry run, in one place
+    pub(crate) fn summary(&self) -> ChangeSummary {
+        ChangeSummary::from_diffs(
+            self.reports
+                .iter()
+                .filter(|report| report.pending)
+                .filter_map(|report| {
+                    let diff = report.diff.as_deref()?;
+                    Some((report.id.as_str(), report.name.as_str(), diff))
+                }),
+        )
+    }
+}
+
+/// Run `installer`'s post-install verification; `Err` says why it failed
+fn verify_client(
+    installer: &dyn GitClientInstaller,
+    params: &GitClientInstallerParams,
+) -> Result<(), String> {
+    match installer.verify_prefs(params) {
+        Ok(None) => Ok(()),
+        Ok(Some(problem)) => Err(problem),
+        Err(e) => Err(format!("verification failed: {}", e)),
+    }
+}
+
+/// Point every detected git client at the shim (or, on a dry run, work out
+/// what would change). Unless `keep_partial`, the first failure stops the
+/// run and rolls back the clients already configured. A client that is
+/// running and would write its old preferences back on quit is left alone
+/// unless `force`. In [`InstallScope::System`] only clients that read
+/// machine-wide preferences are considered.
+pub(crate) fn run_git_client_installers(
+    binary_path: &Path,
+    options: GitClientRunOptions,
+) -> GitClientRun {
+    let spinner = |message: String| {
+        if options.quiet {
+            Spinner::hidden()
+        } else {
+            Spinner::new(&message)
+        }
+    };
+    let report_error = |error_msg: &str| {
+        if !options.quiet {
+            eprintln!("  Error: {}", error_msg);
+        }
+    };
+
+    let clients_config = options
+        .clients
+        .as_ref()
+        .unwrap_or_else(|| config::Config::get().clients());
+    let (git_client_installers, excluded): (Vec<_>, Vec<_>) = get_all_git_client_installers()
+        .into_iter()
+        .filter(|installer| installer.is_platform_supported())
+        .filter(|installer| {
+            options.scope == InstallScope::User || installer.supports_system_scope()
+        })
+        .partition(|installer| clients_config.allows(installer.id()));
+    if !git_client_installers.is_empty() && !options.quiet {
+        println!("\n{}", output::paint(output::BOLD, "Git Clients"));
+    }
+
+    let git_client_params = GitClientInstallerParams {
+        git_shim_path: options
+            .shim_path
+            .clone()
+            .unwrap_or_else(|| git_shim_path(binary_path)),
+        scope: options.scope,
+    };
+    let mut run = GitClientRun::default();
+    // Excluded clients aren't even checked, but are still reported
+    run.reports.extend(excluded.iter().map(|installer| {
+        GitClientReport::new(installer.id(), installer.name(), InstallResult::excluded())
+    }));
+    let mut git_shim_ready = false;
+    // Unless --keep-partial, the first client failure stops the run and
+    // undoes the clients already configured
+    let mut transaction = GitClientInstallTransaction::default();
+    let mut failed_client: Option<String> = None;
+    // Clients configured while running under --force
+    let mut restart_needed: Vec<(String, Vec<u32>)> = Vec::new();
+    let checks = check_clients_parallel(&git_client_installers, &git_client_params);
+
+    for (installer, check) in git_client_installers.iter().zip(checks) {
+        if failed_client.is_some() && !options.keep_partial && !options.dry_run {
+            break;
+        }
+        let name = installer.name();
+        let id = installer.id();
+
+        match check {
+            Ok(check_result) => {
+                if !check_result.client_installed {
+                    run.reports.push(GitClientReport::checked(
+                        id,
+                        name,
+                        &check_result,
+                        InstallResult::not_found(),
+                    ));
+                    continue;
+                }
+
+                run.any_checked = true;
+                let spinner = spinner(format!("{}: checking preferences", name));
+                spinner.start();
+
+                if let Some(reason) = &check_result.unsupported_reason {
+                    spinner.skipped(&format!("{}: {}", name, reason));
+                    run.reports.push(GitClientReport::checked(
+                        id,
+                        name,
+                        &check_result,
+                        InstallResult::unsupported(reason.clone()),
+                    ));
+                    continue;
+                }
+
+                let running_pids: Vec<u32> = if check_result.prefs_up_to_date {
+                    Vec::new()
+                } else {
+                    find_running_pids(&installer.process_names())
+                        .into_iter()
+                        .map(|(pid, _)| pid)
+                        .collect()
+                };
+                let running = !running_pids.is_empty();
+                if running && !options.dry_run && !options.force {
+                    let error_msg = format!(
+                        "{} is running (PID: {}) and would restore its old preferences when it quits; quit it and re-run, or pass --force",
+                        name,
+                        join_pids(&running_pids)
+                    );
+                    spinner.error(&format!("{}: Quit {} before installing", name, name));
+                    report_error(&error_msg);
+                    // Nothing was written, so the other clients stay configured
+                    let mut report = GitClientReport::checked(
+                        id,
+                        name,
+                        &check_result,
+                        InstallResult::failed(error_msg),
+                    );
+                    report.running = true;
+                    run.reports.push(report);
+                    continue;
+                }
+
+                // The shim must exist before any client is pointed at it
+                if !options.dry_run && !git_shim_ready {
+                    let shim = match &options.shim_path {
+                        // A given shim belongs to another install; it's never created
+                        Some(path) if !path.exists() => Err(GitAiError::Generic(format!(
+                            "git shim {} does not exist",
+                            path.display()
+                        ))),
+                        Some(_) => Ok(false),
+                        None => ensure_git_shim(binary_path),
+                    };
+                    if let Err(e) = shim {
+                        let error_msg = e.to_string();
+                        spinner.error(&format!("{}: Failed to create git shim", name));
+                        report_error(&error_msg);
+                        failed_client.get_or_insert_with(|| name.to_string());
+                        run.reports.push(GitClientReport::checked(
+                            id,
+                            name,
+                            &check_result,
+                            InstallResult::failed(error_msg),
+                        ));
+                        continue;
+                    }
+                    git_shim_ready = true;
+                }
+
+                // Snapshot whatever gets overwritten so `git-ai clients restore`
+                // can put it back
+                let backup = backups_root()
+                    .filter(|_| !options.dry_run)
+                    .map(|root| BackupSession::begin(&root, id));
+                let result = installer.install_prefs(&git_client_params, options.dry_run);
+                let snapshot = backup.as_ref().and_then(BackupSession::snapshot_dir);
+                drop(backup);
+                let verification = (options.verify && !options.dry_run && result.is_ok())
+                    .then(|| verify_client(installer.as_ref(), &git_client_params));
+                let report_verification = |verification: &Option<Result<(), String>>| {
+                    if let Some(Err(problem)) = verification
+                        && !options.quiet
+                    {
+                        println!(
+                            "{}",
+                            Status::Warning.line(&format!("{}: Not verified: {}", name, problem))
+                        );
+                    }
+                };
+
+                match result {
+                    Ok(Some(diff)) => {
+                        if options.dry_run && running {
+                            spinner.pending(&format!(
+                                "{}: Pending preference updates (quit {} first)",
+                                name, name
+                            ));
+                        } else if options.dry_run {
+                            spinner.pending(&format!("{}: Pending preference updates", name));
+                        } else {
+                            spinner.success(&format!("{}: Preferences updated", name));
+                            audit_log::record(AuditAction::Install, id, Some(&diff), None);
+                        }
+                        if options.verbose && !options.quiet {
+                            println!();
+                            print_diff(&diff);
+                            if let Some(snapshot) = &snapshot {
+                                println!("  Previous preferences saved to {}", snapshot.display());
+                            }
+                        }
+                        if let Some(snapshot) = snapshot {
+                            transaction.record(id, snapshot);
+                        }
+                        run.has_changes = true;
+                        let mut report = GitClientReport::checked(
+                            id,
+                            name,
+                            &check_result,
+                            InstallResult::installed(),
+                        );
+                        report.diff = Some(diff);
+                        report.pending = options.dry_run;
+                        report.running = running;
+                        report_verification(&verification);
+                        report.set_verification(verification);
+                        run.reports.push(report);
+                        if running && !options.dry_run {
+                            restart_needed.push((name.to_string(), running_pids));
+                        }
+                    }
+                    Ok(None) => {
+                        spinner.success(&format!("{}: Preferences already up to date", name));
+                        report_verification(&verification);
+                        let mut report = GitClientReport::checked(
+                            id,
+                            name,
+                            &check_result,
+                            InstallResult::already_installed(),
+                        );
+                        report.set_verification(verification);
+                        run.reports.push(report);
+                    }
+                    Err(e) => {
+                        let error_msg = e.to_string();
+                        spinner.error(&format!("{}: Failed to update preferences", name));
+                        report_error(&error_msg);
+                        // Anything the installer wrote before failing is undone too
+                        if let Some(snapshot) = snapshot {
+                            transaction.record(id, snapshot);
+                        }
+                        failed_client.get_or_insert_with(|| name.to_string());
+                        run.reports.push(GitClientReport::checked(
+                            id,
+                            name,
+                            &check_result,
+                            InstallResult::failed(error_msg),
+                        ));
+                    }
+                }
+            }
+            Err(check_error) => {
+                let error_msg = check_error.to_string();
+                run.any_checked = true;
+                let spinner = spinner(format!("{}: checking preferences", name));
+                spinner.start();
+                spinner.error(&format!("{}: Preference check failed", name));
+                report_error(&error_msg);
+                failed_client.get_or_insert_with(|| name.to_string());
+                run.reports.push(GitClientReport::new(
+                    id,
+                    name,
+                    InstallResult::failed(error_msg),
+                ));
+            }
+        }
+    }
+
+    if let Some(failed_client) = &failed_client
+        && !options.keep_partial
+        && !transaction.is_empty()
+    {
+        if !options.quiet {
+            println!(
+                "  Rolling back git client preferences because {} failed (use --keep-partial to keep them)",
+                failed_client
+            );
+        }
+        for (client_id, result) in transaction.rollback() {
+            if let Err(e) = result {
+                eprintln!(
+                    "  Error: could not roll back {}: {} (try `git-ai clients restore {}`)",
+                    client_id, e, client_id
+                );
+                continue;
+            }
+            audit_log::record(AuditAction::Rollback, &client_id, None, None);
+            let reason = format!("rolled back because {} failed", failed_client);
+            for report in run.reports.iter_mut() {
+                if report.id == client_id && report.result.status != InstallStatus::Failed {
+                    report.result = InstallResult::failed(reason.clone());
+                }
+            }
+        }
+    }
+
+    if !restart_needed.is_empty() && !options.quiet {
+        println!(
+            "\n{}",
+            Status::Warning.line(
+                "These clients were running and write their old preferences back when they quit:"
+            )
+        );
+        for (name, pids) in &restart_needed {
+            println!(
+                "  {} (PID: {})",
+                output::paint(output::BOLD, name),
+                join_pids(pids)
+            );
+        }
+        println!("Force-quit them to keep the change, or quit them normally and run");
+        println!("`git-ai clients install` again.");
+    }
+
+    if options.dry_run && !options.quiet {
+        let summary = run.summary();
+        if !summary.is_empty() {
+            summary.print();
+        }
+    }
+
+    // Copies are the norm on Windows; elsewhere they mean the install dir
+    // can't hold symlinks, which is worth knowing when the shim goes stale
+    if git_shim_ready
+        && options.shim_path.is_none()
+        && !options.quiet
+        && !cfg!(windows)
+        && installed_shim_strategy(binary_path) == Some(ShimStrategy::Copy)
+    {
+        println!(
+            "  git shim: copied to {} (the filesystem does not support symlinks)",
+            git_client_params.git_shim_path.display()
+        );
+    }
+
+    run
+}
+
 /// Minimum git version required for git-ai to function correctly.
 /// git 2.22.0 introduced `git worktree list --porcelain` output format improvements
 /// and trace2 event logging used by git-ai for attribution.
@@ -813,20 +1359,33 @@ fn warn_if_git_version_too_old() {
         if v < (maj, min, patch) {
             let (vmaj, vmin, vpatch) = v;
             eprintln!();
+            if output::is_plain() {
+                eprintln!("WARNING: git version too old. git-ai will not work.");
+            } else {
+                eprintln!(
+                    "\x1b[1;31m╔══════════════════════════════════════════════════════════════╗\x1b[0m"
+                );
+                eprintln!(
+                    "\x1b[1;31m║  WARNING: git version too old — git-ai will not work         ║\x1b[0m"
+                );
+                eprintln!(
+                    "\x1b[1;31m╚══════════════════════════════════════════════════════════════╝\x1b[0m"
+                );
+            }
             eprintln!(
-                "\x1b[1;31m╔══════════════════════════════════════════════════════════════╗\x1b[0m"
-            );
-            eprintln!(
-                "\x1b[1;31m║  WARNING: git version too old — git-ai will not work         ║\x1b[0m"
-            );
-            eprintln!(
-                "\x1b[1;31m╚══════════════════════════════════════════════════════════════╝\x1b[0m"
+                "{}",
+                output::paint(
+                    output::BOLD_RED,
+                    &format!(
+                        "Detected git {}.{}.{} — git-ai requires git >= {}.{}.{}",
+                        vmaj, vmin, vpatch, maj, min, patch
+                    )
+                )
             );
             eprintln!(
-                "\x1b[1;31mDetected git {}.{}.{} — git-ai requires git >= {}.{}.{}\x1b[0m",
-                vmaj, vmin, vpatch, maj, min, patch
+                "{}",
+                output::paint(output::YELLOW, "Please upgrade git before using git-ai:")
             );
-            eprintln!("\x1b[33mPlease upgrade git before using git-ai:\x1b[0m");
             eprintln!("  macOS:   brew install git");
             eprintln!(
                 "  Ubuntu:  sudo add-apt-repository ppa:git-core/ppa && sudo apt-get update && sudo apt-get install git"
@@ -878,7 +1437,7 @@ async fn async_run_uninstall(
     }
 
     // === Coding Agents ===
-    println!("\n\x1b[1mCoding Agents\x1b[0m");
+    println!("\n{}", output::paint(output::BOLD, "Coding Agents"));
 
     let installers = get_all_installers();
 
@@ -964,12 +1523,87 @@ async fn async_run_uninstall(
         }
     }
 
+    // === Git Clients ===
+    let clients_config = config::Config::get().clients();
+    let (git_client_installers, excluded): (Vec<_>, Vec<_>) = get_all_git_client_installers()
+        .into_iter()
+        .filter(|installer| installer.is_platform_supported())
+        .partition(|installer| clients_config.allows(installer.id()));
+    for installer in &excluded {
+        statuses.insert(installer.id().to_string(), InstallStatus::Excluded);
+    }
+    if !git_client_installers.is_empty() {
+        println!("\n{}", output::paint(output::BOLD, "Git Clients"));
+    }
+
+    let git_client_params = GitClientInstallerParams {
+        git_shim_path: git_shim_path(&params.binary_path),
+        scope: InstallScope::User,
+    };
+
+    let checks = check_clients_parallel(&git_client_installers, &git_client_params);
+
+    for (installer, check) in git_client_installers.iter().zip(checks) {
+        let name = installer.name();
+        let id = installer.id();
+
+        match check {
+            Ok(check_result) => {
+                if !check_result.client_installed || !check_result.prefs_configured {
+                    statuses.insert(id.to_string(), InstallStatus::NotFound);
+                    continue;
+                }
+
+                any_checked = true;
+
+                let spinner = Spinner::new(&format!("{}: restoring preferences", name));
+                spinner.start();
+
+                match installer.uninstall_prefs(&git_client_params, dry_run) {
+                    Ok(Some(diff)) => {
+                        if dry_run {
+                            spinner.pending(&format!("{}: Pending preference restore", name));
+                        } else {
+                            spinner.success(&format!("{}: Preferences restored", name));
+                            audit_log::record(AuditAction::Uninstall, id, Some(&diff), None);
+                        }
+                        if verbose {
+                            println!();
+                            print_diff(&diff);
+                        }
+                        has_changes = true;
+                        statuses.insert(id.to_string(), InstallStatus::Installed);
+                    }
+                    Ok(None) => {
+                        spinner.success(&format!("{}: No preferences to restore", name));
+                        statuses.insert(id.to_string(), InstallStatus::AlreadyInstalled);
+                    }
+                    Err(e) => {
+                        spinner.error(&format!("{}: Failed to restore preferences", name));
+                        eprintln!("  Error: {}", e);
+                        statuses.insert(id.to_string(), InstallStatus::Failed);
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("  Error checking {}: {}", name, e);
+                statuses.insert(id.to_string(), InstallStatus::Failed);
+            }
+        }
+    }
+
     if !any_checked {
         println!("No git-ai hooks found to uninstall.");
     } else if has_changes && dry_run {
-        println!("\n\x1b[33m⚠ Dry-run mode (default). No changes were made.\x1b[0m");
+        println!(
+            "\n{}",
+            Status::Warning.line("Dry-run mode (default). No changes were made.")
+        );
         println!("To apply these changes, run:");
-        println!("\x1b[1m  git-ai uninstall-hooks --dry-run=false\x1b[0m");
+        println!(
+            "  {}",
+            output::paint(output::BOLD, "git-ai uninstall-hooks --dry-run=false")
+        );
     } else if !has_changes {
         println!("All git-ai hooks have been removed.");
     }
@@ -982,10 +1616,7 @@ async fn async_run_uninstall(
 /// All telemetry now flows through the daemon control socket, so the per-PID
 /// log file system under `~/.git-ai/internal/logs/` is no longer needed.
 fn cleanup_legacy_envelope_logs() {
-    let Some(home) = dirs::home_dir() else {
-        return;
-    };
-    let internal = home.join(".git-ai").join("internal");
+    let internal = crate::app_context::AppContext::current().state_dir;
 
     // Remove the entire logs directory
     let logs_dir = internal.join("logs");
@@ -1105,6 +1736,13 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn parse_install_options_keep_partial_flag() {
+        assert!(!parse_install_options(&[]).unwrap().keep_partial);
+        let options = parse_install_options(&["--keep-partial".to_string()]).unwrap();
+        assert!(options.keep_partial);
+    }
+
     #[test]
     fn parse_install_options_accepts_package_api_configuration() {
         let args = vec![
@@ -0,0 +1,531 @@
+//! `git-ai mdm` — artifacts for fleets that manage git-ai through an MDM.
+//! `export-profile` writes a macOS configuration profile, and `export-policy`
+//! a Windows `.reg` file, DSC configuration or Intune profile, that enforce
+//! the git client preferences `install-hooks` would otherwise write on each
+//! machine. `sync` instead fetches a signed policy from a server and applies
+//! it here (see [`crate::mdm::remote_policy`]).
+
+use crate::commands::clients::ClientsExit;
+use crate::commands::install_hooks::{
+    GitClientReport, GitClientRunOptions, run_git_client_installers,
+};
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientInstaller, GitClientInstallerParams, InstallScope, check_system_install,
+    is_per_user_path,
+};
+use crate::mdm::git_clients::get_all_git_client_installers;
+use crate::mdm::path_template::{
+    has_windows_variables, is_template, resolve_path_template, to_windows_variables,
+};
+use crate::mdm::policy::{PolicyFormat, build_policy, is_machine_wide_windows_path};
+use crate::mdm::profile::{
+    DEFAULT_PROFILE_IDENTIFIER, ProfileOptions, build_profile, sign_profile,
+};
+use crate::mdm::remote_policy::{Enforcement, PolicySource, fetch_policy};
+use crate::mdm::utils::{get_current_binary_path, git_shim_path, home_dir, write_atomic};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, Eq)]
+struct ExportOptions {
+    /// Client ids to include; every client with managed preferences if empty
+    clients: Vec<String>,
+    /// The shim's path on the managed machines
+    shim_path: Option<PathBuf>,
+    output: Option<PathBuf>,
+    profile: ProfileOptions,
+    /// Keychain identity to sign with
+    sign: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct PolicyExportOptions {
+    /// Client ids to include; every client with registry settings if empty
+    clients: Vec<String>,
+    /// The shim's path on the managed machines
+    shim_path: Option<PathBuf>,
+    output: Option<PathBuf>,
+    format: PolicyFormat,
+    /// HKLM for `System`, HKCU for `User`
+    scope: InstallScope,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SyncOptions {
+    /// Overrides `clients.policy_url`
+    url: Option<String>,
+    /// Overrides `clients.policy_public_key`
+    public_key: Option<PathBuf>,
+    /// Only report, whatever the policy's enforcement
+    dry_run: bool,
+    json: bool,
+}
+
+pub fn handle_mdm(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("sync") => {
+            let options = match parse_sync_options(&args[1..]) {
+                Ok(options) => options,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    print_help();
+                    ClientsExit::Failed.exit();
+                }
+            };
+            match run_sync(&options) {
+                Ok(exit) => exit.exit(),
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    ClientsExit::Failed.exit();
+                }
+            }
+        }
+        Some("export-profile") => {
+            let options = match parse_export_options(&args[1..]) {
+                Ok(options) => options,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    print_help();
+                    std::process::exit(1);
+                }
+            };
+            if let Err(err) = run_export_profile(&options) {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+        Some("export-policy") => {
+            let options = match parse_policy_options(&args[1..]) {
+                Ok(options) => options,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    print_help();
+                    std::process::exit(1);
+                }
+            };
+            if let Err(err) = run_export_policy(&options) {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+        Some("--help") | Some("-h") | Some("help") => {
+            print_help();
+            std::process::exit(0);
+        }
+        Some(other) => {
+            eprintln!("Error: unknown mdm subcommand: {}", other);
+            print_help();
+            std::process::exit(1);
+        }
+        None => {
+            print_help();
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_export_options(args: &[String]) -> Result<ExportOptions, String> {
+    let mut options = ExportOptions {
+        clients: Vec::new(),
+        shim_path: None,
+        output: None,
+        profile: ProfileOptions {
+            identifier: DEFAULT_PROFILE_IDENTIFIER.to_string(),
+            organization: None,
+        },
+        sign: None,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} requires a value", flag))
+        };
+        match arg.as_str() {
+            "--client" => options.clients.push(value("--client")?),
+            "--shim-path" => options.shim_path = Some(PathBuf::from(value("--shim-path")?)),
+            "--output" | "-o" => options.output = Some(PathBuf::from(value("--output")?)),
+            "--identifier" => options.profile.identifier = value("--identifier")?,
+            "--organization" => options.profile.organization = Some(value("--organization")?),
+            "--sign" => options.sign = Some(value("--sign")?),
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+    if options.sign.is_some() && options.output.is_none() {
+        return Err("--sign writes a binary profile and needs --output".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_policy_options(args: &[String]) -> Result<PolicyExportOptions, String> {
+    let mut clients = Vec::new();
+    let mut shim_path = None;
+    let mut output = None;
+    let mut format = None;
+    let mut scope = InstallScope::System;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} requires a value", flag))
+        };
+        match arg.as_str() {
+            "--client" => clients.push(value("--client")?),
+            "--shim-path" => shim_path = Some(PathBuf::from(value("--shim-path")?)),
+            "--output" | "-o" => output = Some(PathBuf::from(value("--output")?)),
+            "--format" => format = Some(PolicyFormat::parse(&value("--format")?)?),
+            "--scope" => {
+                let value = value("--scope")?;
+                scope = InstallScope::parse(&value).ok_or_else(|| {
+                    format!("invalid --scope: {} (expected user or system)", value)
+                })?;
+            }
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+    let format = format.ok_or_else(|| "--format reg, dsc or intune is required".to_string())?;
+    Ok(PolicyExportOptions {
+        clients,
+        shim_path,
+        output,
+        format,
+        scope,
+    })
+}
+
+fn parse_sync_options(args: &[String]) -> Result<SyncOptions, String> {
+    let mut options = SyncOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} requires a value", flag))
+        };
+        match arg.as_str() {
+            "--url" => options.url = Some(value("--url")?),
+            "--public-key" => options.public_key = Some(PathBuf::from(value("--public-key")?)),
+            "--dry-run" => options.dry_run = true,
+            "--json" => options.json = true,
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+    Ok(options)
+}
+
+/// Fetch the policy and run the git client installers as it says. Exits
+/// like `git-ai clients install`.
+fn run_sync(options: &SyncOptions) -> Result<ClientsExit, GitAiError> {
+    let clients_config = Config::get().clients();
+    let url = options
+        .url
+        .clone()
+        .or_else(|| clients_config.policy_url.clone())
+        .ok_or_else(|| {
+            GitAiError::Generic(
+                "no policy URL; set clients.policy_url with `git-ai config set` or pass --url"
+                    .to_string(),
+            )
+        })?;
+    let public_key = options
+        .public_key
+        .clone()
+        .or_else(|| clients_config.policy_public_key.as_ref().map(PathBuf::from))
+        .ok_or_else(|| {
+            GitAiError::Generic(
+                "no key to verify the policy with; set clients.policy_public_key or pass \
+                 --public-key"
+                    .to_string(),
+            )
+        })?;
+    let source = PolicySource { url, public_key };
+    let fetched = fetch_policy(&source)?;
+    let policy = fetched.policy;
+    if let Some(reason) = &fetched.fallback_reason {
+        eprintln!(
+            "Warning: couldn't fetch the policy ({}); using the last one that verified",
+            reason
+        );
+    }
+
+    let shim_path = policy
+        .shim_path
+        .as_deref()
+        .map(resolve_path_template)
+        .transpose()
+        .map_err(GitAiError::Generic)?;
+    let dry_run = options.dry_run || policy.enforcement == Enforcement::Audit;
+    let binary_path = get_current_binary_path()?;
+    if policy.scope == InstallScope::System {
+        let shim = shim_path
+            .clone()
+            .unwrap_or_else(|| git_shim_path(&binary_path));
+        check_system_install(&shim, dry_run)?;
+    }
+    let run = run_git_client_installers(
+        &binary_path,
+        GitClientRunOptions {
+            dry_run,
+            force: policy.force,
+            scope: policy.scope,
+            quiet: options.json,
+            shim_path: shim_path.clone(),
+            clients: policy.clients.clone(),
+            ..Default::default()
+        },
+    );
+
+    if options.json {
+        let reports: Vec<_> = run.reports.iter().map(GitClientReport::to_json).collect();
+        let out = serde_json::json!({
+            "policy": {
+                "url": source.url,
+                "serial": policy.serial,
+                "enforcement": policy.enforcement.as_str(),
+                "scope": policy.scope.as_str(),
+                "shim_path": shim_path.map(|path| path.display().to_string()),
+                "fallback_reason": fetched.fallback_reason,
+            },
+            "clients": reports,
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+    } else if !run.any_checked {
+        println!("No git clients detected.");
+    }
+    Ok(ClientsExit::from_reports(&run.reports))
+}
+
+fn run_export_profile(options: &ExportOptions) -> Result<(), GitAiError> {
+    let shim = match &options.shim_path {
+        Some(path) => path.clone(),
+        None => git_shim_path(&get_current_binary_path()?),
+    };
+    // macOS doesn't expand variables in managed preferences
+    if is_template(&shim.to_string_lossy()) {
+        return Err(GitAiError::Generic(format!(
+            "a configuration profile can't resolve the variables in {} for each user; \
+             pass --shim-path with where the fleet installs git-ai (e.g. \
+             /usr/local/git-ai/bin/git), or run `git-ai clients install --all-users \
+             --shim-path` on each machine",
+            shim.display()
+        )));
+    }
+    // The profile applies to every user of every managed machine
+    if !shim.is_absolute() || is_per_user_path(&shim, &home_dir()) {
+        return Err(GitAiError::Generic(format!(
+            "the profile needs the shim's machine-wide path, not {}; pass --shim-path with \
+             where the fleet installs git-ai (e.g. /usr/local/git-ai/bin/git)",
+            shim.display()
+        )));
+    }
+
+    let preferences = collect_managed(
+        &options.clients,
+        &shim,
+        |installer, params| installer.managed_preferences(params),
+        "isn't configured through macOS preferences, so a profile can't manage it",
+    )?;
+    let profile = build_profile(&preferences, &options.profile);
+    let bytes = match &options.sign {
+        Some(identity) => sign_profile(profile.as_bytes(), identity)?,
+        None => profile.into_bytes(),
+    };
+    write_output(options.output.as_deref(), &bytes)
+}
+
+fn run_export_policy(options: &PolicyExportOptions) -> Result<(), GitAiError> {
+    let shim = match &options.shim_path {
+        Some(path) if is_template(&path.to_string_lossy()) => {
+            to_windows_variables(&path.to_string_lossy())
+                .map(PathBuf::from)
+                .map_err(GitAiError::Generic)?
+        }
+        Some(path) => path.clone(),
+        None => git_shim_path(&get_current_binary_path()?),
+    };
+    let shim_str = shim.to_string_lossy();
+    // Policies apply to every managed machine, usually from an admin's own.
+    // A template rooted in a variable is resolved per user by Windows.
+    let per_user_template = shim_str.starts_with('%') && has_windows_variables(&shim_str);
+    if !per_user_template && !is_machine_wide_windows_path(&shim_str) {
+        return Err(GitAiError::Generic(format!(
+            "the policy needs the shim's machine-wide Windows path, not {}; pass --shim-path \
+             with where the fleet installs git-ai (e.g. C:\\Program Files\\git-ai\\bin\\git.exe)",
+            shim.display()
+        )));
+    }
+
+    let values = collect_managed(
+        &options.clients,
+        &shim,
+        |installer, params| installer.managed_registry_values(params),
+        "isn't configured through the registry, so a policy can't manage it",
+    )?;
+    let bytes =
+        build_policy(&values, options.format, options.scope).map_err(GitAiError::Generic)?;
+    write_output(options.output.as_deref(), &bytes)
+}
+
+fn write_output(output: Option<&Path>, bytes: &[u8]) -> Result<(), GitAiError> {
+    match output {
+        Some(path) => {
+            write_atomic(path, bytes)?;
+            eprintln!("Wrote {}", path.display());
+        }
+        None => std::io::stdout().write_all(bytes)?,
+    }
+    Ok(())
+}
+
+/// The managed settings of `client_ids`, or of every client that has some.
+/// Naming a client without any is an error, explained by `unmanaged`.
+fn collect_managed<T>(
+    client_ids: &[String],
+    shim: &Path,
+    managed: impl Fn(&dyn GitClientInstaller, &GitClientInstallerParams) -> Vec<T>,
+    unmanaged: &str,
+) -> Result<Vec<T>, GitAiError> {
+    let params = GitClientInstallerParams {
+        git_shim_path: shim.to_path_buf(),
+        scope: InstallScope::System,
+    };
+    let installers = get_all_git_client_installers();
+    for id in client_ids {
+        match installers.iter().find(|installer| installer.id() == id) {
+            None => {
+                return Err(GitAiError::Generic(format!("unknown client: {}", id)));
+            }
+            Some(installer) if managed(installer.as_ref(), &params).is_empty() => {
+                return Err(GitAiError::Generic(format!(
+                    "{} {}",
+                    installer.name(),
+                    unmanaged
+                )));
+            }
+            Some(_) => {}
+        }
+    }
+    let settings: Vec<T> = installers
+        .iter()
+        .filter(|installer| {
+            client_ids.is_empty() || client_ids.iter().any(|id| id == installer.id())
+        })
+        .flat_map(|installer| managed(installer.as_ref(), &params))
+        .collect();
+    if settings.is_empty() {
+        return Err(GitAiError::Generic(
+            "no client can be managed this way".to_string(),
+        ));
+    }
+    Ok(settings)
+}
+
+fn print_help() {
+    eprintln!("git-ai mdm - Artifacts for managing git-ai through an MDM");
+    eprintln!();
+    eprintln!("Usage:");
+    eprintln!("  git-ai mdm export-profile [--client <id>]... [--shim-path <path>]");
+    eprintln!("                            [--output <file>] [--identifier <id>]");
+    eprintln!("                            [--organization <name>] [--sign <identity>]");
+    eprintln!("  git-ai mdm sync [--url <url>] [--public-key <pem>] [--dry-run] [--json]");
+    eprintln!("  git-ai mdm export-policy --format reg|dsc|intune [--scope system|user]");
+    eprintln!("                           [--client <id>]... [--shim-path <path>]");
+    eprintln!("                           [--output <file>]");
+    eprintln!();
+    eprintln!("sync fetches the signed policy at clients.policy_url (or --url), checks its");
+    eprintln!("Ed25519 signature (<url>.sig, base64) against clients.policy_public_key (or");
+    eprintln!("--public-key), and configures the clients it names: with enforcement");
+    eprintln!("\"enforce\" like `git-ai clients install`, with \"audit\" or --dry-run like");
+    eprintln!("`git-ai clients check`. The policy (JSON or TOML) can also set scope,");
+    eprintln!("shim_path (a template such as ${{HOME}}/.git-ai/bin/git) and force. When the");
+    eprintln!("server can't be reached the last policy that verified is used. It exits like");
+    eprintln!("`git-ai clients install`: 0 up to date, 1 changed or pending, 2 failed, 3 no");
+    eprintln!("clients.");
+    eprintln!();
+    eprintln!("export-profile prints a macOS configuration profile (.mobileconfig) that");
+    eprintln!("points Fork, Nova and clients.toml plist clients at the git shim, for an MDM");
+    eprintln!("such as Jamf or Kandji to enforce instead of install-hooks writing the");
+    eprintln!("preferences on each machine. --client limits it to the given clients.");
+    eprintln!();
+    eprintln!("--shim-path is the shim's path on the managed machines (default: this");
+    eprintln!("install's), which must be outside any home directory. --sign signs the");
+    eprintln!("profile with a keychain identity through `security cms` (macOS only) and");
+    eprintln!("needs --output; unsigned profiles can be signed by the MDM instead.");
+    eprintln!();
+    eprintln!("export-policy prints the registry settings that point TortoiseGit at the");
+    eprintln!("git shim, for Group Policy or Intune to deploy: a regedit file (reg), a");
+    eprintln!("PowerShell DSC configuration (dsc), or a Graph custom configuration profile");
+    eprintln!("that ingests an ADMX (intune). --scope system (default) targets HKLM and");
+    eprintln!("user targets HKCU. --shim-path is the shim's Windows path on the managed");
+    eprintln!("machines and is required when exporting from another OS. It may start with");
+    eprintln!(
+        "a variable, as in %LOCALAPPDATA%\\git-ai\\bin\\git.exe or ${{HOME}}\\.git-ai\\bin\\git.exe,"
+    );
+    eprintln!("for per-user installs; such values are written as expandable strings that");
+    eprintln!("Windows resolves for each user.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_export_profile_arguments() {
+        let options = parse_export_options(&args(&[
+            "--client",
+            "fork",
+            "--client",
+            "nova",
+            "--shim-path",
+            "/opt/git-ai/bin/git",
+            "-o",
+            "git-ai.mobileconfig",
+            "--organization",
+            "Example",
+        ]))
+        .unwrap();
+        assert_eq!(options.clients, vec!["fork", "nova"]);
+        assert_eq!(
+            options.shim_path,
+            Some(PathBuf::from("/opt/git-ai/bin/git"))
+        );
+        assert_eq!(options.output, Some(PathBuf::from("git-ai.mobileconfig")));
+        assert_eq!(options.profile.identifier, DEFAULT_PROFILE_IDENTIFIER);
+        assert_eq!(options.profile.organization.as_deref(), Some("Example"));
+
+        assert!(parse_export_options(&args(&["--client"])).is_err());
+        assert!(parse_export_options(&args(&["--bogus"])).is_err());
+        let err = parse_export_options(&args(&["--sign", "Developer ID"])).unwrap_err();
+        assert!(err.contains("--output"), "{err}");
+    }
+
+    #[test]
+    fn parses_export_policy_arguments() {
+        let options = parse_policy_options(&args(&[
+            "--format",
+            "intune",
+            "--scope",
+            "user",
+            "--shim-path",
+            r"C:\Program Files\git-ai\bin\git.exe",
+        ]))
+        .unwrap();
+        assert_eq!(options.format, PolicyFormat::Intune);
+        assert_eq!(options.scope, InstallScope::User);
+        assert!(options.clients.is_empty());
+
+        let defaults = parse_policy_options(&args(&["--format", "reg"])).unwrap();
+        assert_eq!(defaults.scope, InstallScope::System);
+        assert!(parse_policy_options(&args(&[])).is_err());
+        assert!(parse_policy_options(&args(&["--format", "msi"])).is_err());
+        assert!(parse_policy_options(&args(&["--format", "reg", "--scope", "all"])).is_err());
+    }
+}
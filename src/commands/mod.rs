@@ -1,8 +1,13 @@
 pub mod analyze;
 pub mod r#await;
+pub mod bisect_report;
 pub mod blame;
 pub mod checkpoint_agent;
 pub mod ci_handlers;
+pub mod clients;
+pub mod clients_all_users;
+pub mod clients_drift;
+pub mod clients_select;
 pub mod config;
 pub mod daemon;
 pub mod debug;
@@ -17,11 +22,21 @@ pub mod install_hooks;
 pub mod log;
 pub mod login;
 pub mod logout;
+pub mod mdm;
 pub mod notes_migrate;
 pub mod personal_dashboard;
+pub mod plugin;
+pub mod privacy;
+pub mod report;
+pub mod selftest;
+pub mod serve_dashboard;
+pub mod server_hook;
 pub mod show;
 pub mod show_prompt;
+pub mod split;
 pub mod status;
+pub mod support_bundle;
+pub mod sync;
 pub mod upgrade;
 pub mod usage;
 pub mod whoami;
@@ -0,0 +1,185 @@
+//! External subcommands: like git's `git-foo` convention, `git-ai xyz` runs
+//! a `git-ai-xyz` executable from `PATH` when `xyz` isn't built in.
+//!
+//! The plugin inherits the terminal's stdout/stderr and gets its arguments
+//! as-is, plus:
+//!
+//! - `GIT_AI_PLUGIN_PROTOCOL` — `1`; bumped if the contract changes
+//! - `GIT_AI_BINARY` — the running git-ai executable, for calling back in
+//! - `GIT_AI_VERSION` — the running git-ai version
+//! - `GIT_AI_CONFIG_PATH` — `~/.git-ai/config.json` (may not exist)
+//! - `GIT_AI_GIT_PATH` — the real git binary git-ai wraps
+//! - `GIT_AI_REPO_ROOT` — the working tree containing the current
+//!   directory, when there is one
+//! - `GIT_AI_PLAIN_OUTPUT` — `1` under `git-ai --plain`, so the plugin can
+//!   match (see [`crate::output`])
+//!
+//! and a JSON object on stdin carrying the same context:
+//! `{protocol, version, command, args, cwd, repo_root, config_path, git_path}`.
+//! stdin is closed after the object, so plugins are non-interactive.
+
+use crate::config;
+use crate::git::repository::find_repository_in_path;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub const PLUGIN_PROTOCOL_VERSION: u32 = 1;
+
+/// The context a plugin receives on stdin
+#[derive(Debug, Serialize)]
+pub struct PluginContext {
+    pub protocol: u32,
+    pub version: &'static str,
+    pub command: String,
+    pub args: Vec<String>,
+    pub cwd: Option<PathBuf>,
+    pub repo_root: Option<PathBuf>,
+    pub config_path: Option<PathBuf>,
+    pub git_path: String,
+}
+
+impl PluginContext {
+    fn current(command: &str, args: &[String]) -> Self {
+        let repo_root = find_repository_in_path(".")
+            .ok()
+            .and_then(|repo| repo.workdir().ok());
+        Self {
+            protocol: PLUGIN_PROTOCOL_VERSION,
+            version: env!("CARGO_PKG_VERSION"),
+            command: command.to_string(),
+            args: args.to_vec(),
+            cwd: std::env::current_dir().ok(),
+            repo_root,
+            config_path: config::config_file_path_public(),
+            git_path: config::Config::get().git_cmd().to_string(),
+        }
+    }
+}
+
+/// Plugin names are a single path-safe word, so `git-ai ../x` or
+/// `git-ai --flag` never resolve to a file
+fn is_plugin_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The `git-ai-<name>` executable on `PATH`, if any
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    if !is_plugin_name(name) {
+        return None;
+    }
+    find_in_path(&format!("git-ai-{}", name), &std::env::var_os("PATH")?)
+}
+
+fn find_in_path(file_name: &str, path: &std::ffi::OsStr) -> Option<PathBuf> {
+    #[cfg(windows)]
+    let candidates: Vec<String> = std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| format!("{}{}", file_name, ext.to_ascii_lowercase()))
+        .collect();
+    #[cfg(not(windows))]
+    let candidates = [file_name.to_string()];
+
+    std::env::split_paths(path)
+        .filter(|dir| dir.is_absolute())
+        .flat_map(|dir| {
+            candidates
+                .iter()
+                .map(|candidate| dir.join(candidate))
+                .collect::<Vec<_>>()
+        })
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Run the plugin at `path` for `git-ai <command> <args>` and return its
+/// exit code
+pub fn run_plugin(path: &Path, command: &str, args: &[String]) -> Result<i32, String> {
+    let context = PluginContext::current(command, args);
+    let mut cmd = Command::new(path);
+    cmd.args(args)
+        .stdin(Stdio::piped())
+        .env(
+            "GIT_AI_PLUGIN_PROTOCOL",
+            PLUGIN_PROTOCOL_VERSION.to_string(),
+        )
+        .env("GIT_AI_VERSION", context.version)
+        .env("GIT_AI_GIT_PATH", &context.git_path);
+    if let Ok(binary) = std::env::current_exe() {
+        cmd.env("GIT_AI_BINARY", binary);
+    }
+    if let Some(config_path) = &context.config_path {
+        cmd.env("GIT_AI_CONFIG_PATH", config_path);
+    }
+    match &context.repo_root {
+        Some(repo_root) => cmd.env("GIT_AI_REPO_ROOT", repo_root),
+        None => cmd.env_remove("GIT_AI_REPO_ROOT"),
+    };
+    if crate::output::is_plain() {
+        cmd.env("GIT_AI_PLAIN_OUTPUT", "1");
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("failed to run {}: {}", path.display(), e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let json = serde_json::to_vec(&context).map_err(|e| e.to_string())?;
+        // A plugin that ignores its context may exit before reading it
+        let _ = stdin.write_all(&json);
+    }
+    let status = child
+        .wait()
+        .map_err(|e| format!("failed to wait for {}: {}", path.display(), e))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_names_are_single_words() {
+        assert!(is_plugin_name("lint-prompts"));
+        assert!(is_plugin_name("team_report2"));
+        assert!(!is_plugin_name(""));
+        assert!(!is_plugin_name("--help"));
+        assert!(!is_plugin_name("../evil"));
+        assert!(!is_plugin_name("a/b"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn finds_the_first_executable_on_path() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        // Not executable, so skipped
+        std::fs::write(first.path().join("git-ai-hello"), "").unwrap();
+        let plugin = second.path().join("git-ai-hello");
+        std::fs::write(&plugin, "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path = std::env::join_paths([first.path(), second.path()]).unwrap();
+        assert_eq!(find_in_path("git-ai-hello", &path), Some(plugin));
+        assert_eq!(find_in_path("git-ai-missing", &path), None);
+    }
+}
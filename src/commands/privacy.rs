@@ -0,0 +1,471 @@
+//! `git-ai privacy delete` — remove one person's attribution records from the
+//! current repository's authorship notes and the local databases.
+
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::internal_db::InternalDatabase;
+use crate::ci::attestation::{key_id, openssl_sign};
+use crate::config::{Config, NotesBackendKind, internal_dir_path};
+use crate::error::GitAiError;
+use crate::git::notes_api;
+use crate::git::repository::{Repository, find_repository_in_path};
+use crate::metrics::db::MetricsDatabase;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rand::RngExt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Replaces a matching identity in `humans` records. The entry itself is kept
+/// because attestations reference it by hash.
+const REDACTED_AUTHOR: &str = "[redacted]";
+
+/// Ed25519 PEM private key the report is signed with, when `--signing-key`
+/// isn't given
+const SIGNING_KEY_ENV: &str = "GIT_AI_PRIVACY_SIGNING_KEY";
+
+#[derive(Debug, Serialize)]
+struct DeletionReport {
+    /// HMAC-SHA256 of the normalized email under this install's subject key,
+    /// so the report neither re-discloses it nor lets anyone confirm a guess
+    /// without that key
+    subject_hmac_sha256: String,
+    generated_at: String,
+    git_ai_version: &'static str,
+    dry_run: bool,
+    repository: Option<String>,
+    notes_backend: String,
+    notes_rewritten: Vec<String>,
+    metric_events_deleted: usize,
+    local_prompts_deleted: usize,
+    /// Why the notes couldn't be searched or rewritten; the local databases
+    /// are cleaned up regardless
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notes_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeletionReportEnvelope {
+    report: DeletionReport,
+    /// SHA-256 over the RFC 8785 canonical JSON of `report`
+    sha256: String,
+    /// Ed25519 signature of the same bytes; absent without a signing key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<ReportSignature>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReportSignature {
+    /// SHA-256 of the DER public key, as in `ci attest`
+    keyid: String,
+    /// Base64
+    sig: String,
+}
+
+pub fn handle_privacy(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("delete") => handle_delete(&args[1..]),
+        Some("--help") | Some("-h") | None => print_help(),
+        Some(other) => usage_error(&format!("Unknown privacy subcommand: {}", other)),
+    }
+}
+
+fn handle_delete(args: &[String]) {
+    let mut email: Option<String> = None;
+    let mut dry_run = false;
+    let mut signing_key = std::env::var_os(SIGNING_KEY_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from);
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--author" => {
+                i += 1;
+                email = Some(
+                    args.get(i)
+                        .cloned()
+                        .unwrap_or_else(|| usage_error("--author requires an email address")),
+                );
+            }
+            "--dry-run" => dry_run = true,
+            "--signing-key" => {
+                i += 1;
+                signing_key = Some(
+                    args.get(i)
+                        .map(PathBuf::from)
+                        .unwrap_or_else(|| usage_error("--signing-key requires a path")),
+                );
+            }
+            "--help" | "-h" => {
+                print_help();
+                return;
+            }
+            other => usage_error(&format!("Unknown argument: {}", other)),
+        }
+        i += 1;
+    }
+
+    let email = match email.map(|e| e.trim().to_lowercase()) {
+        Some(email) if email.contains('@') => email,
+        _ => usage_error("--author <email> is required"),
+    };
+
+    if signing_key.is_none() {
+        eprintln!(
+            "warning: no signing key (--signing-key or {}); the report is unsigned",
+            SIGNING_KEY_ENV
+        );
+    }
+
+    let report = match delete_author_records(&email, dry_run) {
+        Ok(report) => report,
+        Err(e) => fail(&e.to_string()),
+    };
+    let notes_failed = report.notes_error.is_some();
+    match seal_report(report, signing_key.as_deref()) {
+        Ok(envelope) => match serde_json::to_string_pretty(&envelope) {
+            Ok(s) => println!("{}", s),
+            Err(e) => fail(&format!("error serializing JSON: {}", e)),
+        },
+        Err(e) => fail(&e.to_string()),
+    }
+    if notes_failed {
+        std::process::exit(1);
+    }
+}
+
+fn delete_author_records(email: &str, dry_run: bool) -> Result<DeletionReport, GitAiError> {
+    let matches = |identity: &str| author_matches(identity, email);
+
+    let repo = find_repository_in_path(".").ok();
+    // A failure here mustn't stop the local rows from being deleted below
+    let (notes_rewritten, notes_error) = match repo.as_ref() {
+        Some(repo) => match redact_notes(repo, email, &matches, dry_run) {
+            Ok(rewritten) => (rewritten, None),
+            Err(e) => {
+                eprintln!("error: failed to redact authorship notes: {}", e);
+                (Vec::new(), Some(e.to_string()))
+            }
+        },
+        None => {
+            eprintln!("Not in a git repository; skipping authorship notes.");
+            (Vec::new(), None)
+        }
+    };
+
+    let metric_events_deleted = {
+        let db = MetricsDatabase::global()?;
+        let mut db = db
+            .lock()
+            .map_err(|e| GitAiError::Generic(format!("metrics db lock: {}", e)))?;
+        db.delete_events_by_author(matches, dry_run)?
+    };
+
+    let local_prompts_deleted = {
+        let db = InternalDatabase::global()?;
+        let mut db = db
+            .lock()
+            .map_err(|e| GitAiError::Generic(format!("internal db lock: {}", e)))?;
+        db.delete_prompts_by_author(matches, dry_run)?
+    };
+
+    let notes_backend = Config::get().notes_backend_kind();
+    if !dry_run && !notes_rewritten.is_empty() && notes_backend == NotesBackendKind::GitNotes {
+        eprintln!(
+            "Rewrote {} note(s) locally. Push refs/notes/ai to propagate the redaction.",
+            notes_rewritten.len()
+        );
+    }
+
+    Ok(DeletionReport {
+        subject_hmac_sha256: hex(&hmac_sha256(&subject_key()?, email.as_bytes())),
+        generated_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        git_ai_version: env!("CARGO_PKG_VERSION"),
+        dry_run,
+        repository: repo
+            .as_ref()
+            .and_then(|r| r.workdir().ok())
+            .map(|p| p.display().to_string()),
+        notes_backend: notes_backend.to_string(),
+        notes_rewritten,
+        metric_events_deleted,
+        local_prompts_deleted,
+        notes_error,
+    })
+}
+
+/// Redact every note mentioning `email` and write the results back through the
+/// notes API (the HTTP backend uploads them). Returns the rewritten commit SHAs.
+fn redact_notes(
+    repo: &Repository,
+    email: &str,
+    matches: &impl Fn(&str) -> bool,
+    dry_run: bool,
+) -> Result<Vec<String>, GitAiError> {
+    let mut entries = Vec::new();
+    for sha in notes_api::search_notes_ignore_case(repo, email)? {
+        let Some(content) = notes_api::read_note(repo, &sha) else {
+            continue;
+        };
+        let Ok(mut log) = AuthorshipLog::deserialize_from_string(&content) else {
+            continue;
+        };
+        if !redact_authorship_log(&mut log, matches) {
+            continue;
+        }
+        let redacted = log
+            .serialize_to_string()
+            .map_err(|e| GitAiError::Generic(format!("Failed to serialize note: {}", e)))?;
+        entries.push((sha, redacted));
+    }
+
+    if !dry_run {
+        notes_api::write_notes_batch(repo, &entries)?;
+    }
+    Ok(entries.into_iter().map(|(sha, _)| sha).collect())
+}
+
+/// Strip matching identities from a note's metadata. Returns whether anything
+/// changed.
+fn redact_authorship_log(log: &mut AuthorshipLog, matches: &impl Fn(&str) -> bool) -> bool {
+    let mut changed = false;
+    let metadata = &mut log.metadata;
+
+    for prompt in metadata.prompts.values_mut() {
+        if prompt.human_author.as_deref().is_some_and(matches) {
+            prompt.human_author = None;
+            changed = true;
+        }
+    }
+    for session in metadata.sessions.values_mut() {
+        if session.human_author.as_deref().is_some_and(matches) {
+            session.human_author = None;
+            changed = true;
+        }
+    }
+    for human in metadata.humans.values_mut() {
+        if matches(&human.author) {
+            human.author = REDACTED_AUTHOR.to_string();
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// Whether a stored identity ("Name <email>" or a bare email) belongs to `email`
+/// (already lowercased).
+fn author_matches(identity: &str, email: &str) -> bool {
+    let identity = identity.trim().to_lowercase();
+    identity == email || identity.contains(&format!("<{}>", email))
+}
+
+fn seal_report(
+    report: DeletionReport,
+    signing_key: Option<&Path>,
+) -> Result<DeletionReportEnvelope, GitAiError> {
+    let canonical = serde_json_canonicalizer::to_string(&report)
+        .map_err(|e| GitAiError::Generic(format!("Failed to canonicalize report: {}", e)))?;
+    let signature = match signing_key {
+        Some(key) => Some(ReportSignature {
+            keyid: key_id(key)?,
+            sig: BASE64.encode(openssl_sign(key, canonical.as_bytes())?),
+        }),
+        None => None,
+    };
+    Ok(DeletionReportEnvelope {
+        sha256: hex(&Sha256::digest(canonical.as_bytes())),
+        signature,
+        report,
+    })
+}
+
+/// This install's random key for [`DeletionReport::subject_hmac_sha256`],
+/// created on first use
+fn subject_key() -> Result<Vec<u8>, GitAiError> {
+    let path = internal_dir_path()
+        .ok_or_else(|| GitAiError::Generic("no git-ai state directory".to_string()))?
+        .join("privacy_subject_key");
+    match fs::read(&path) {
+        Ok(key) if !key.is_empty() => return Ok(key),
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let key: [u8; 32] = rand::rng().random();
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    match options.open(&path) {
+        Ok(mut file) => {
+            use std::io::Write;
+            file.write_all(&key)?;
+            Ok(key.to_vec())
+        }
+        // Another run created it first
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(fs::read(&path)?),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!("Run 'git-ai privacy --help' for usage.");
+    std::process::exit(1);
+}
+
+fn print_help() {
+    eprintln!("git-ai privacy - Manage personal data in attribution records");
+    eprintln!();
+    eprintln!(
+        "Usage: git-ai privacy delete --author <email> [--dry-run] [--signing-key <ed25519.pem>]"
+    );
+    eprintln!();
+    eprintln!("Removes the author's identity from this repository's authorship notes and");
+    eprintln!("deletes their rows from the local metrics and prompt databases. Prints a");
+    eprintln!("JSON deletion report with a SHA-256 digest over its canonical form, signed");
+    eprintln!("with the Ed25519 key when one is given. The subject is identified by an");
+    eprintln!("HMAC under a key kept in this install's state directory.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --author <email>    Identity to remove (matched case-insensitively)");
+    eprintln!("  --dry-run           Report what would change without modifying anything");
+    eprintln!(
+        "  --signing-key <pem> Ed25519 private key to sign the report (default: ${})",
+        SIGNING_KEY_ENV
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log::{HumanRecord, PromptRecord};
+    use crate::authorship::working_log::AgentId;
+
+    fn prompt(author: Option<&str>) -> PromptRecord {
+        PromptRecord {
+            agent_id: AgentId {
+                tool: "cursor".to_string(),
+                id: "session".to_string(),
+                model: "gpt".to_string(),
+            },
+            human_author: author.map(str::to_string),
+            messages_url: None,
+            total_additions: 0,
+            total_deletions: 0,
+            accepted_lines: 0,
+            overriden_lines: 0,
+            custom_attributes: None,
+        }
+    }
+
+    #[test]
+    fn author_matches_email_in_identity_case_insensitively() {
+        let email = "alice@example.com";
+        assert!(author_matches("Alice <Alice@Example.com>", email));
+        assert!(author_matches("alice@example.com", email));
+        assert!(!author_matches("Alice <alice@example.com.evil>", email));
+        assert!(!author_matches("Malice <malice@example.com>", email));
+    }
+
+    #[test]
+    fn redact_authorship_log_strips_only_matching_identities() {
+        let mut log = AuthorshipLog::new();
+        let meta = &mut log.metadata;
+        meta.prompts
+            .insert("p1".into(), prompt(Some("Alice <alice@example.com>")));
+        meta.prompts
+            .insert("p2".into(), prompt(Some("Bob <bob@example.com>")));
+        meta.humans.insert(
+            "h_1".into(),
+            HumanRecord {
+                author: "Alice <alice@example.com>".into(),
+            },
+        );
+
+        let matches = |identity: &str| author_matches(identity, "alice@example.com");
+        assert!(redact_authorship_log(&mut log, &matches));
+        assert_eq!(log.metadata.prompts["p1"].human_author, None);
+        assert_eq!(
+            log.metadata.prompts["p2"].human_author.as_deref(),
+            Some("Bob <bob@example.com>")
+        );
+        assert_eq!(log.metadata.humans["h_1"].author, REDACTED_AUTHOR);
+
+        assert!(!redact_authorship_log(&mut log, &matches));
+    }
+
+    #[test]
+    fn hmac_sha256_matches_rfc_4231() {
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn report_digest_covers_canonical_report() {
+        let report = || DeletionReport {
+            subject_hmac_sha256: hex(&hmac_sha256(b"key", b"alice@example.com")),
+            generated_at: "2026-01-01T00:00:00Z".into(),
+            git_ai_version: "0.0.0",
+            dry_run: false,
+            repository: None,
+            notes_backend: "git_notes".into(),
+            notes_rewritten: vec!["abc".into()],
+            metric_events_deleted: 2,
+            local_prompts_deleted: 0,
+            notes_error: None,
+        };
+        let sealed = seal_report(report(), None).unwrap();
+        assert!(sealed.signature.is_none());
+        assert_eq!(sealed.sha256, seal_report(report(), None).unwrap().sha256);
+
+        let mut tampered = report();
+        tampered.metric_events_deleted = 3;
+        assert_ne!(sealed.sha256, seal_report(tampered, None).unwrap().sha256);
+    }
+}
@@ -0,0 +1,425 @@
+//! `git-ai report` — AI share trends from persisted metric events.
+
+use crate::config::Config;
+use crate::metrics::local_stats::{
+    BucketGranularity, GROUP_BY_AUTHOR, NOISE_CONTRIBUTION_BOUND, TrendPoint, add_laplace_noise,
+    compute_trend, suppress_small_groups,
+};
+use crate::output::{self, Palette};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+pub fn handle_report(args: &[String]) {
+    let mut granularity = BucketGranularity::Monthly;
+    let mut days: Option<u64> = None;
+    let mut repo: Option<String> = None;
+    let mut group_by: Option<String> = None;
+    let mut format = OutputFormat::Table;
+    let mut anonymize = false;
+    let mut salt: Option<String> = None;
+    let mut min_group_size: Option<u32> = None;
+    let mut noise_epsilon: Option<f64> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--trend" => {
+                i += 1;
+                granularity = match args.get(i).map(String::as_str) {
+                    Some("daily") => BucketGranularity::Daily,
+                    Some("weekly") => BucketGranularity::Weekly,
+                    Some("monthly") => BucketGranularity::Monthly,
+                    _ => usage_error("--trend requires one of: daily, weekly, monthly"),
+                };
+            }
+            "--days" => {
+                i += 1;
+                days = match args.get(i).and_then(|v| v.parse::<u64>().ok()) {
+                    Some(n) if n > 0 => Some(n),
+                    _ => usage_error("--days requires a positive number"),
+                };
+            }
+            "--repo" => {
+                i += 1;
+                repo = Some(
+                    args.get(i)
+                        .cloned()
+                        .unwrap_or_else(|| usage_error("--repo requires a repository URL")),
+                );
+            }
+            "--group-by" => {
+                i += 1;
+                group_by = Some(
+                    args.get(i)
+                        .cloned()
+                        .unwrap_or_else(|| usage_error("--group-by requires an attribute key")),
+                );
+            }
+            "--format" => {
+                i += 1;
+                format = match args.get(i).map(String::as_str) {
+                    Some("table") => OutputFormat::Table,
+                    Some("csv") => OutputFormat::Csv,
+                    Some("json") => OutputFormat::Json,
+                    _ => usage_error("--format requires one of: table, csv, json"),
+                };
+            }
+            "--anonymize" => anonymize = true,
+            "--salt" => {
+                i += 1;
+                salt = Some(
+                    args.get(i)
+                        .cloned()
+                        .unwrap_or_else(|| usage_error("--salt requires a value")),
+                );
+            }
+            "--min-group-size" => {
+                i += 1;
+                min_group_size = match args.get(i).and_then(|v| v.parse::<u32>().ok()) {
+                    Some(k) => Some(k),
+                    None => usage_error("--min-group-size requires a non-negative number"),
+                };
+            }
+            "--noise" => {
+                i += 1;
+                noise_epsilon = match args.get(i).and_then(|v| v.parse::<f64>().ok()) {
+                    Some(e) if e.is_finite() && e > 0.0 => Some(e),
+                    _ => usage_error("--noise requires a positive epsilon"),
+                };
+            }
+            "--json" => format = OutputFormat::Json,
+            "--help" | "-h" => {
+                print_help();
+                return;
+            }
+            other => usage_error(&format!("Unknown argument: {}", other)),
+        }
+        i += 1;
+    }
+
+    // A salt is required so pseudonyms can't be reversed by hashing known
+    // emails, and so the same salt yields comparable reports across runs.
+    let salt = match (anonymize, salt) {
+        (true, Some(salt)) if !salt.is_empty() => Some(salt),
+        (true, _) => usage_error("--anonymize requires --salt <value>"),
+        (false, _) => None,
+    };
+
+    let days = days.unwrap_or(match granularity {
+        BucketGranularity::Daily => 30,
+        BucketGranularity::Weekly => 90,
+        BucketGranularity::Monthly => 365,
+    });
+
+    // Policy from config is a floor: flags can tighten it but never relax it.
+    let config = Config::get();
+    let min_group_size = min_group_size.max(config.report_min_group_size());
+    let noise_epsilon = match (noise_epsilon, config.report_noise_epsilon()) {
+        (Some(flag), Some(policy)) => Some(flag.min(policy)),
+        (flag, policy) => flag.or(policy),
+    };
+
+    let mut points = match compute_trend(
+        days_ago(days),
+        granularity,
+        repo.as_deref(),
+        group_by.as_deref(),
+        noise_epsilon.map(|_| NOISE_CONTRIBUTION_BOUND),
+    ) {
+        Ok(points) => points,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(k) = min_group_size.filter(|k| *k > 1) {
+        let suppressed = suppress_small_groups(&mut points, k);
+        if suppressed > 0 {
+            eprintln!(
+                "Suppressed {} group(s) with fewer than {} distinct authors.",
+                suppressed, k
+            );
+        }
+    }
+    if let Some(epsilon) = noise_epsilon {
+        add_laplace_noise(
+            &mut points,
+            epsilon,
+            NOISE_CONTRIBUTION_BOUND,
+            &mut rand::rng(),
+        );
+    }
+
+    if let Some(salt) = salt.as_deref()
+        && group_by.as_deref() == Some(GROUP_BY_AUTHOR)
+    {
+        anonymize_groups(&mut points, salt);
+    }
+
+    match format {
+        OutputFormat::Json => match serde_json::to_string_pretty(&points) {
+            Ok(s) => println!("{}", s),
+            Err(e) => {
+                eprintln!("error serializing JSON: {}", e);
+                std::process::exit(1);
+            }
+        },
+        OutputFormat::Csv => print!("{}", format_csv(&points, group_by.is_some())),
+        OutputFormat::Table => {
+            if points.is_empty() {
+                eprintln!("No commits found in the last {} days.", days);
+                std::process::exit(1);
+            }
+            print_table(&points, group_by.as_deref());
+        }
+    }
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!("Run 'git-ai report --help' for usage.");
+    std::process::exit(1);
+}
+
+fn days_ago(days: u64) -> u32 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    now.saturating_sub(days * 24 * 3600).min(u32::MAX as u64) as u32
+}
+
+fn print_help() {
+    eprintln!("git-ai report - Show AI share of committed code over time");
+    eprintln!();
+    eprintln!("Usage: git-ai report [options]");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --trend <daily|weekly|monthly>    Period size (default: monthly)");
+    eprintln!("  --days <n>                        Window in days (default: 30/90/365 by period)");
+    eprintln!("  --repo <url>                      Only include one repository");
+    eprintln!("  --group-by <key>                  Split each period by a custom attribute");
+    eprintln!("  --format <table|csv|json>         Output format (default: table)");
+    eprintln!("  --min-group-size <k>              Hide groups with fewer than k distinct authors");
+    eprintln!(
+        "  --noise <epsilon>                 Add Laplace noise to counts (smaller = noisier)"
+    );
+    eprintln!("  --json                            Shorthand for --format json");
+    eprintln!("  --anonymize                       Replace author identities with pseudonyms");
+    eprintln!(
+        "  --salt <value>                    Salt for --anonymize (same salt, same pseudonyms)"
+    );
+    eprintln!("  --help                            Show this help");
+    eprintln!();
+    eprintln!("Group by 'author' to split by commit author instead of a custom attribute.");
+    eprintln!("report_min_group_size and report_noise_epsilon in config set a minimum");
+    eprintln!("that --min-group-size and --noise can tighten but not relax.");
+    eprintln!("Reads committed metric events recorded locally on this machine.");
+    eprintln!("Metric rows older than approximately 365 days are pruned locally.");
+}
+
+/// Replace each author group with a salted, deterministic pseudonym.
+fn anonymize_groups(points: &mut [TrendPoint], salt: &str) {
+    for point in points.iter_mut() {
+        if let Some(group) = point.group.as_mut()
+            && group != "unknown"
+        {
+            *group = pseudonym(salt, group);
+        }
+    }
+}
+
+fn pseudonym(salt: &str, identity: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(identity.trim().to_lowercase().as_bytes());
+    let digest = format!("{:x}", hasher.finalize());
+    format!("author-{}", &digest[..12])
+}
+
+fn format_csv(points: &[TrendPoint], grouped: bool) -> String {
+    let mut out = String::new();
+    out.push_str("period_start,period,");
+    if grouped {
+        out.push_str("group,");
+    }
+    out.push_str("commits,ai_lines,human_lines,diff_added_lines,ai_share_pct\n");
+    for p in points {
+        out.push_str(&format!(
+            "{},{},",
+            p.period_start.format("%Y-%m-%d"),
+            csv_field(&p.period)
+        ));
+        if grouped {
+            out.push_str(&csv_field(p.group.as_deref().unwrap_or_default()));
+            out.push(',');
+        }
+        out.push_str(&format!(
+            "{},{},{},{},{}\n",
+            p.commits,
+            p.ai_lines,
+            p.human_lines,
+            p.diff_added_lines,
+            p.ai_share_pct.map(|v| v.to_string()).unwrap_or_default()
+        ));
+    }
+    out
+}
+
+/// Quote a CSV field when it contains a delimiter, quote, or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn print_table(points: &[TrendPoint], group_by: Option<&str>) {
+    const BAR_W: usize = 20;
+    let Palette {
+        gray,
+        bold,
+        reset,
+        orange,
+        bar_filled,
+        bar_empty,
+        bar_none,
+        ..
+    } = Palette::current();
+
+    let period_w = points
+        .iter()
+        .map(|p| p.period.chars().count())
+        .max()
+        .unwrap_or(0);
+    let group_w = points
+        .iter()
+        .filter_map(|p| p.group.as_deref())
+        .map(|g| g.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(group_by.map(str::len).unwrap_or(0));
+
+    println!();
+    match group_by {
+        Some(key) => println!("  {bold}AI share by {key}{reset}"),
+        None => println!("  {bold}AI share{reset}"),
+    }
+    println!();
+
+    for p in points {
+        let group_col = match p.group.as_deref() {
+            Some(g) => format!("  {:<width$}", g, width = group_w),
+            None => String::new(),
+        };
+        let (bar, pct) = match p.ai_share_pct {
+            Some(pct) => {
+                let filled = ((pct / 100.0) * BAR_W as f64).round().min(BAR_W as f64) as usize;
+                (
+                    format!(
+                        "{orange}{}{gray}{}{reset}",
+                        bar_filled.repeat(filled),
+                        bar_empty.repeat(BAR_W - filled)
+                    ),
+                    format!("{:>5.1}%", pct),
+                )
+            }
+            None => (
+                format!("{gray}{}{reset}", bar_none.repeat(BAR_W)),
+                if output::is_plain() {
+                    "   n/a".to_string()
+                } else {
+                    "     —".to_string()
+                },
+            ),
+        };
+        let commit_label = if p.commits == 1 { "commit " } else { "commits" };
+        println!(
+            "  {:<period_w$}{}  {}  {}  {gray}{:>5} {}  +{} AI / +{} human{reset}",
+            p.period,
+            group_col,
+            bar,
+            pct,
+            p.commits,
+            commit_label,
+            p.ai_lines,
+            p.human_lines,
+            period_w = period_w,
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn point(group: Option<&str>, ai_share_pct: Option<f64>) -> TrendPoint {
+        TrendPoint {
+            period_start: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            period: "Mar 2026".to_string(),
+            group: group.map(str::to_string),
+            commits: 3,
+            ai_lines: 30,
+            human_lines: 10,
+            diff_added_lines: 45,
+            contributors: 2,
+            ai_share_pct,
+        }
+    }
+
+    #[test]
+    fn pseudonyms_are_stable_per_salt_and_hide_identity() {
+        let a = pseudonym("s1", "alice@example.com");
+        assert_eq!(a, pseudonym("s1", "Alice@Example.com "));
+        assert_ne!(a, pseudonym("s2", "alice@example.com"));
+        assert_ne!(a, pseudonym("s1", "bob@example.com"));
+        assert!(a.starts_with("author-"));
+        assert!(!a.contains("alice"));
+    }
+
+    #[test]
+    fn anonymize_keeps_unknown_group() {
+        let mut points = [
+            point(Some("alice@example.com"), None),
+            point(Some("unknown"), None),
+        ];
+        anonymize_groups(&mut points, "salt");
+        assert_eq!(
+            points[0].group,
+            Some(pseudonym("salt", "alice@example.com"))
+        );
+        assert_eq!(points[1].group.as_deref(), Some("unknown"));
+    }
+
+    #[test]
+    fn csv_has_header_and_one_row_per_point() {
+        let csv = format_csv(&[point(None, Some(75.0)), point(None, None)], false);
+        assert_eq!(
+            csv,
+            "period_start,period,commits,ai_lines,human_lines,diff_added_lines,ai_share_pct\n\
+             2026-03-01,Mar 2026,3,30,10,45,75\n\
+             2026-03-01,Mar 2026,3,30,10,45,\n"
+        );
+    }
+
+    #[test]
+    fn csv_includes_and_quotes_group_column() {
+        let csv = format_csv(&[point(Some("web, \"core\""), Some(75.0))], true);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("2026-03-01,Mar 2026,\"web, \"\"core\"\"\",3,30,10,45,75")
+        );
+    }
+}
@@ -0,0 +1,863 @@
+//! `git-ai selftest` — check that the git shim never changes what git does.
+//!
+//! Every scenario runs twice in mirrored sandboxes: once against the real git
+//! binary and once through a `git` shim of this git-ai binary placed first on
+//! PATH, so nested invocations (submodules, `bisect run`, rebase editors) go
+//! through it too. Each step's exit code, stdout and stderr, and the final
+//! refs/status of every repository, must match byte for byte once the
+//! sandbox path is masked. Authorship notes (`refs/notes/*`) are the one
+//! intended difference and are left out of the comparison.
+
+use crate::config;
+use crate::mdm::utils::binary_exists;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Fixed identity and dates so both sides produce identical object IDs
+const IDENTITY_ENV: &[(&str, &str)] = &[
+    ("GIT_AUTHOR_NAME", "git-ai selftest"),
+    ("GIT_AUTHOR_EMAIL", "selftest@git-ai.invalid"),
+    ("GIT_AUTHOR_DATE", "2024-01-01T00:00:00+0000"),
+    ("GIT_COMMITTER_NAME", "git-ai selftest"),
+    ("GIT_COMMITTER_EMAIL", "selftest@git-ai.invalid"),
+    ("GIT_COMMITTER_DATE", "2024-01-01T00:00:00+0000"),
+    ("GIT_EDITOR", "true"),
+    ("GIT_SEQUENCE_EDITOR", "true"),
+    ("GIT_PAGER", "cat"),
+    ("GIT_TERMINAL_PROMPT", "0"),
+    ("LC_ALL", "C"),
+];
+
+/// What stands in for the sandbox directory in compared output
+const SANDBOX_PLACEHOLDER: &str = "<sandbox>";
+
+/// One action in a scenario, run from `dir` (relative to the scenario
+/// directory).
+#[derive(Debug, Clone)]
+enum Step {
+    Git {
+        dir: &'static str,
+        args: Vec<String>,
+        env: Vec<(&'static str, String)>,
+    },
+    Write {
+        path: String,
+        contents: &'static str,
+    },
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self {
+            Step::Git { dir, args, env } => {
+                let mut out = String::new();
+                for (key, value) in env {
+                    write!(out, "{}={:?} ", key, value).unwrap();
+                }
+                if *dir != "." {
+                    write!(out, "(in {}) ", dir).unwrap();
+                }
+                write!(out, "git {}", args.join(" ")).unwrap();
+                out
+            }
+            Step::Write { path, .. } => format!("write {}", path),
+        }
+    }
+}
+
+fn git(dir: &'static str, args: &[&str]) -> Step {
+    Step::Git {
+        dir,
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        env: Vec::new(),
+    }
+}
+
+fn git_env(dir: &'static str, args: &[&str], env: &[(&'static str, &str)]) -> Step {
+    Step::Git {
+        dir,
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        env: env
+            .iter()
+            .map(|(key, value)| (*key, value.to_string()))
+            .collect(),
+    }
+}
+
+fn write(path: &str, contents: &'static str) -> Step {
+    Step::Write {
+        path: path.to_string(),
+        contents,
+    }
+}
+
+/// `init` plus one commit of `file.txt` in `dir`
+fn seeded_repo(dir: &'static str) -> Vec<Step> {
+    vec![
+        git(".", &["init", "-q", "-b", "main", dir]),
+        write(&format!("{}/file.txt", dir), "one\n"),
+        git(dir, &["add", "file.txt"]),
+        git(dir, &["commit", "-m", "first"]),
+    ]
+}
+
+struct Scenario {
+    name: &'static str,
+    steps: Vec<Step>,
+    /// Repositories whose final state is compared
+    repos: Vec<&'static str>,
+    /// Why the scenario can't run on this machine
+    skip_reason: Option<String>,
+}
+
+fn scenarios(signing_key: Option<&Path>) -> Vec<Scenario> {
+    let mut all = Vec::new();
+
+    let mut steps = seeded_repo("repo");
+    steps.extend([
+        write("repo/file.txt", "one\ntwo\n"),
+        git("repo", &["diff"]),
+        git("repo", &["commit", "-am", "second"]),
+        git("repo", &["log", "--stat"]),
+        git("repo", &["show", "HEAD~1"]),
+        git("repo", &["commit", "--amend", "-m", "second, amended"]),
+        git("repo", &["status"]),
+    ]);
+    all.push(Scenario {
+        name: "commit",
+        steps,
+        repos: vec!["repo"],
+        skip_reason: None,
+    });
+
+    let mut steps = seeded_repo("origin");
+    steps.extend([
+        git(".", &["clone", "origin", "clone"]),
+        write("clone/file.txt", "one\nfrom clone\n"),
+        git("clone", &["commit", "-am", "from clone"]),
+        git("clone", &["push", "origin", "HEAD:refs/heads/from-clone"]),
+        git("clone", &["fetch", "origin"]),
+        git("clone", &["pull", "--ff-only", "origin", "main"]),
+        git("clone", &["branch", "-a"]),
+    ]);
+    all.push(Scenario {
+        name: "clone-fetch-push",
+        steps,
+        repos: vec!["origin", "clone"],
+        skip_reason: None,
+    });
+
+    let mut steps = seeded_repo("repo");
+    steps.extend([
+        write("repo/file.txt", "one\ntwo\n"),
+        git("repo", &["commit", "-am", "second"]),
+        write("repo/file.txt", "one\ntwo\nthree\n"),
+        git("repo", &["commit", "-am", "third"]),
+        // Squash the last commit into its parent through a real editor
+        git_env(
+            "repo",
+            &["rebase", "-i", "HEAD~2"],
+            &[("GIT_SEQUENCE_EDITOR", "sed -i.bak -e '2s/^pick/squash/'")],
+        ),
+        git("repo", &["log", "--format=%H %s"]),
+    ]);
+    all.push(Scenario {
+        name: "rebase-interactive",
+        steps,
+        repos: vec!["repo"],
+        skip_reason: (!binary_exists("sed")).then(|| "sed is not on PATH".to_string()),
+    });
+
+    let mut steps = seeded_repo("repo");
+    steps.extend([
+        write("repo/file.txt", "one\nwork in progress\n"),
+        write("repo/untracked.txt", "untracked\n"),
+        git("repo", &["stash", "push", "-u", "-m", "wip"]),
+        git("repo", &["stash", "list"]),
+        git("repo", &["stash", "show", "-p", "stash@{0}"]),
+        git("repo", &["stash", "pop"]),
+        git("repo", &["status", "--short"]),
+    ]);
+    all.push(Scenario {
+        name: "stash",
+        steps,
+        repos: vec!["repo"],
+        skip_reason: None,
+    });
+
+    let mut steps = seeded_repo("repo");
+    steps.extend([
+        write("repo/file.txt", "one\ntwo\n"),
+        git("repo", &["commit", "-am", "second"]),
+        write("repo/bad.txt", "regression\n"),
+        git("repo", &["add", "bad.txt"]),
+        git("repo", &["commit", "-m", "third"]),
+        write("repo/file.txt", "one\ntwo\nfour\n"),
+        git("repo", &["commit", "-am", "fourth"]),
+        git("repo", &["bisect", "start", "HEAD", "HEAD~3"]),
+        git("repo", &["bisect", "run", "sh", "-c", "test ! -e bad.txt"]),
+        git("repo", &["bisect", "log"]),
+        git("repo", &["bisect", "reset"]),
+    ]);
+    all.push(Scenario {
+        name: "bisect",
+        steps,
+        repos: vec!["repo"],
+        skip_reason: None,
+    });
+
+    let mut steps = seeded_repo("repo");
+    steps.extend([
+        git("repo", &["worktree", "add", "-b", "feature", "../wt"]),
+        write("wt/feature.txt", "feature\n"),
+        git("wt", &["add", "feature.txt"]),
+        git("wt", &["commit", "-m", "feature"]),
+        git("repo", &["worktree", "list", "--porcelain"]),
+        git("repo", &["worktree", "remove", "../wt"]),
+        git("repo", &["log", "--oneline", "feature"]),
+    ]);
+    all.push(Scenario {
+        name: "worktree",
+        steps,
+        repos: vec!["repo"],
+        skip_reason: None,
+    });
+
+    let mut steps = seeded_repo("lib");
+    steps.extend(seeded_repo("repo"));
+    steps.extend([
+        // Local-path submodules need file transport since git 2.38.1
+        git(
+            "repo",
+            &[
+                "-c",
+                "protocol.file.allow=always",
+                "submodule",
+                "add",
+                "../lib",
+                "lib",
+            ],
+        ),
+        git("repo", &["commit", "-m", "add lib submodule"]),
+        git("repo", &["submodule", "status"]),
+        git(
+            ".",
+            &[
+                "-c",
+                "protocol.file.allow=always",
+                "clone",
+                "--recurse-submodules",
+                "repo",
+                "clone",
+            ],
+        ),
+        git("clone", &["submodule", "status"]),
+    ]);
+    all.push(Scenario {
+        name: "submodule",
+        steps,
+        repos: vec!["repo", "clone", "clone/lib"],
+        skip_reason: None,
+    });
+
+    // OpenPGP signatures embed the signing time, so runs can never be byte
+    // identical; ed25519 SSH signatures are deterministic and go through the
+    // same `commit -S` / gpg.program plumbing.
+    let (steps, skip_reason) = match signing_key {
+        Some(key) => {
+            let key = key.to_string_lossy().to_string();
+            let signing_config = [
+                "-c".to_string(),
+                "gpg.format=ssh".to_string(),
+                "-c".to_string(),
+                format!("user.signingkey={}", key),
+            ];
+            let mut steps = seeded_repo("repo");
+            steps.extend([
+                write("repo/file.txt", "one\nsigned\n"),
+                Step::Git {
+                    dir: "repo",
+                    args: signing_config
+                        .iter()
+                        .cloned()
+                        .chain(["commit", "-S", "-am", "signed"].map(String::from))
+                        .collect(),
+                    env: Vec::new(),
+                },
+                git("repo", &["cat-file", "commit", "HEAD"]),
+            ]);
+            (steps, None)
+        }
+        None => (
+            Vec::new(),
+            Some("ssh-keygen is not available to create a signing key".to_string()),
+        ),
+    };
+    all.push(Scenario {
+        name: "signed-commit",
+        steps,
+        repos: vec!["repo"],
+        skip_reason,
+    });
+
+    let mut steps = seeded_repo("repo");
+    steps.extend([
+        git("repo", &["switch", "-c", "topic"]),
+        write("repo/file.txt", "topic\n"),
+        git("repo", &["commit", "-am", "topic"]),
+        git("repo", &["switch", "main"]),
+        write("repo/file.txt", "main\n"),
+        git("repo", &["commit", "-am", "main"]),
+        // Exits 1 with a conflict; the shim must pass that through untouched
+        git("repo", &["merge", "topic"]),
+        git("repo", &["status", "--short"]),
+        git("repo", &["merge", "--abort"]),
+    ]);
+    all.push(Scenario {
+        name: "merge-conflict",
+        steps,
+        repos: vec!["repo"],
+        skip_reason: None,
+    });
+
+    all
+}
+
+/// How one side invokes git
+struct Side {
+    program: PathBuf,
+    root: PathBuf,
+    env: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct StepOutput {
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+}
+
+impl Side {
+    fn run_git(
+        &self,
+        dir: &Path,
+        args: &[String],
+        extra_env: &[(&str, String)],
+    ) -> Result<StepOutput, String> {
+        let mut command = Command::new(&self.program);
+        command
+            .args(args)
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .envs(IDENTITY_ENV.iter().copied())
+            .envs(self.env.iter().map(|(key, value)| (key, value)))
+            .envs(extra_env.iter().map(|(key, value)| (key, value)));
+        let output = command
+            .output()
+            .map_err(|e| format!("failed to run {}: {}", self.program.display(), e))?;
+        Ok(StepOutput {
+            exit_code: output.status.code(),
+            stdout: self.mask(&String::from_utf8_lossy(&output.stdout)),
+            stderr: self.mask(&String::from_utf8_lossy(&output.stderr)),
+        })
+    }
+
+    fn mask(&self, text: &str) -> String {
+        let root = self.root.to_string_lossy();
+        text.replace(root.as_ref(), SANDBOX_PLACEHOLDER)
+            .replace(&root.replace('\\', "/"), SANDBOX_PLACEHOLDER)
+    }
+
+    fn run_scenario(&self, scenario: &Scenario) -> Result<(Vec<StepOutput>, String), String> {
+        let base = self.root.join(scenario.name);
+        fs::create_dir_all(&base).map_err(|e| format!("create {}: {}", base.display(), e))?;
+
+        let mut outputs = Vec::new();
+        for step in &scenario.steps {
+            match step {
+                Step::Git { dir, args, env } => {
+                    outputs.push(self.run_git(&base.join(dir), args, env)?);
+                }
+                Step::Write { path, contents } => {
+                    let path = base.join(path);
+                    fs::write(&path, contents)
+                        .map_err(|e| format!("write {}: {}", path.display(), e))?;
+                }
+            }
+        }
+
+        let mut state = String::new();
+        for repo in &scenario.repos {
+            writeln!(state, "## {}", repo).unwrap();
+            state.push_str(&self.repo_state(&base.join(repo))?);
+        }
+        Ok((outputs, state))
+    }
+
+    /// Refs (minus notes), HEAD, status and stash of the repo at `dir`
+    fn repo_state(&self, dir: &Path) -> Result<String, String> {
+        let mut state = String::new();
+        let refs = self.run_git(
+            dir,
+            &["for-each-ref", "--format=%(refname) %(objectname)"].map(String::from),
+            &[],
+        )?;
+        for line in refs.stdout.lines() {
+            if !line.starts_with("refs/notes/") {
+                writeln!(state, "{}", line).unwrap();
+            }
+        }
+        for args in [
+            &["symbolic-ref", "-q", "HEAD"][..],
+            &["rev-parse", "HEAD"][..],
+            &[
+                "status",
+                "--porcelain=v2",
+                "--branch",
+                "--untracked-files=all",
+            ][..],
+            &["stash", "list"][..],
+        ] {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            let output = self.run_git(dir, &args, &[])?;
+            state.push_str(&output.stdout);
+        }
+        Ok(state)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Divergence {
+    step: String,
+    field: &'static str,
+    direct: String,
+    shim: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum ScenarioOutcome {
+    Passed,
+    Failed { divergences: Vec<Divergence> },
+    Skipped { reason: String },
+    Error { message: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScenarioResult {
+    name: &'static str,
+    #[serde(flatten)]
+    outcome: ScenarioOutcome,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SelftestReport {
+    git_ai_version: &'static str,
+    git_version: String,
+    platform: String,
+    real_git: String,
+    scenarios: Vec<ScenarioResult>,
+}
+
+impl SelftestReport {
+    fn passed(&self) -> bool {
+        self.scenarios.iter().all(|scenario| {
+            matches!(
+                scenario.outcome,
+                ScenarioOutcome::Passed | ScenarioOutcome::Skipped { .. }
+            )
+        })
+    }
+}
+
+fn compare_outputs(steps: &[Step], direct: &[StepOutput], shim: &[StepOutput]) -> Vec<Divergence> {
+    let git_steps = steps.iter().filter(|step| matches!(step, Step::Git { .. }));
+    let mut divergences = Vec::new();
+    for ((step, direct), shim) in git_steps.zip(direct).zip(shim) {
+        let fields = [
+            (
+                "exit code",
+                format!("{:?}", direct.exit_code),
+                format!("{:?}", shim.exit_code),
+            ),
+            ("stdout", direct.stdout.clone(), shim.stdout.clone()),
+            ("stderr", direct.stderr.clone(), shim.stderr.clone()),
+        ];
+        for (field, direct, shim) in fields {
+            if direct != shim {
+                divergences.push(Divergence {
+                    step: step.describe(),
+                    field,
+                    direct,
+                    shim,
+                });
+            }
+        }
+    }
+    divergences
+}
+
+fn run_selftest(real_git: &Path, shim_program: &Path) -> Result<SelftestReport, String> {
+    let unique = format!(
+        "git-ai-selftest-{}-{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    );
+    let temp_dir = std::env::temp_dir().join(unique);
+    fs::create_dir_all(&temp_dir).map_err(|e| format!("create sandbox: {}", e))?;
+    // Canonical so masking also catches paths git resolves (e.g. /private/var on macOS)
+    let temp_dir = temp_dir.canonicalize().unwrap_or(temp_dir);
+
+    let result = run_selftest_in(&temp_dir, real_git, shim_program);
+    let _ = fs::remove_dir_all(&temp_dir);
+    result
+}
+
+fn run_selftest_in(
+    temp_dir: &Path,
+    real_git: &Path,
+    shim_program: &Path,
+) -> Result<SelftestReport, String> {
+    let shim_dir = temp_dir.join("bin");
+    fs::create_dir_all(&shim_dir).map_err(|e| format!("create shim dir: {}", e))?;
+    let shim = install_temp_shim(shim_program, &shim_dir)?;
+    let path_var = std::env::join_paths(std::iter::once(shim_dir.clone()).chain(
+        std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default()),
+    ))
+    .map_err(|e| format!("build PATH: {}", e))?;
+
+    let direct = Side {
+        program: real_git.to_path_buf(),
+        root: temp_dir.join("direct"),
+        // Keep the daemon out of the baseline run
+        env: vec![("GIT_TRACE2_EVENT".to_string(), "0".to_string())],
+    };
+    let through_shim = Side {
+        program: shim,
+        root: temp_dir.join("shim"),
+        env: vec![("PATH".to_string(), path_var.to_string_lossy().to_string())],
+    };
+
+    let signing_key = create_signing_key(&temp_dir.join("keys"));
+    let mut results = Vec::new();
+    for scenario in scenarios(signing_key.as_deref()) {
+        let outcome = if let Some(reason) = &scenario.skip_reason {
+            ScenarioOutcome::Skipped {
+                reason: reason.clone(),
+            }
+        } else {
+            match (
+                direct.run_scenario(&scenario),
+                through_shim.run_scenario(&scenario),
+            ) {
+                (Ok((direct_steps, direct_state)), Ok((shim_steps, shim_state))) => {
+                    let mut divergences =
+                        compare_outputs(&scenario.steps, &direct_steps, &shim_steps);
+                    if direct_state != shim_state {
+                        divergences.push(Divergence {
+                            step: "final state".to_string(),
+                            field: "repository state",
+                            direct: direct_state,
+                            shim: shim_state,
+                        });
+                    }
+                    if divergences.is_empty() {
+                        ScenarioOutcome::Passed
+                    } else {
+                        ScenarioOutcome::Failed { divergences }
+                    }
+                }
+                (Err(message), _) | (_, Err(message)) => ScenarioOutcome::Error { message },
+            }
+        };
+        results.push(ScenarioResult {
+            name: scenario.name,
+            outcome,
+        });
+    }
+
+    let git_version = Command::new(real_git)
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|e| format!("unavailable ({})", e));
+    Ok(SelftestReport {
+        git_ai_version: env!("CARGO_PKG_VERSION"),
+        git_version,
+        platform: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        real_git: real_git.display().to_string(),
+        scenarios: results,
+    })
+}
+
+/// Any binary not named `git-ai` proxies to git, so the shim under test is a
+/// `git` link to (or copy of, on Windows) `shim_program`.
+fn install_temp_shim(shim_program: &Path, shim_dir: &Path) -> Result<PathBuf, String> {
+    let shim = crate::mdm::utils::git_shim_path(&shim_dir.join("git-ai"));
+    #[cfg(windows)]
+    fs::copy(shim_program, &shim).map_err(|e| format!("copy shim: {}", e))?;
+    #[cfg(not(windows))]
+    std::os::unix::fs::symlink(shim_program, &shim).map_err(|e| format!("link shim: {}", e))?;
+    Ok(shim)
+}
+
+/// A throwaway ed25519 key shared by both sides, or `None` without ssh-keygen
+fn create_signing_key(dir: &Path) -> Option<PathBuf> {
+    if !binary_exists("ssh-keygen") {
+        return None;
+    }
+    fs::create_dir_all(dir).ok()?;
+    let key = dir.join("signing");
+    let status = Command::new("ssh-keygen")
+        .args([
+            "-q",
+            "-t",
+            "ed25519",
+            "-N",
+            "",
+            "-C",
+            "git-ai selftest",
+            "-f",
+        ])
+        .arg(&key)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .ok()?;
+    status.success().then_some(key)
+}
+
+fn render_summary(report: &SelftestReport) -> String {
+    let mut out = String::new();
+    for scenario in &report.scenarios {
+        match &scenario.outcome {
+            ScenarioOutcome::Passed => writeln!(out, "  ok    {}", scenario.name).unwrap(),
+            ScenarioOutcome::Skipped { reason } => {
+                writeln!(out, "  skip  {} ({})", scenario.name, reason).unwrap()
+            }
+            ScenarioOutcome::Failed { divergences } => {
+                writeln!(out, "  FAIL  {}", scenario.name).unwrap();
+                for divergence in divergences {
+                    writeln!(out, "        {}: {}", divergence.step, divergence.field).unwrap();
+                }
+            }
+            ScenarioOutcome::Error { message } => {
+                writeln!(out, "  ERROR {} ({})", scenario.name, message).unwrap()
+            }
+        }
+    }
+    out
+}
+
+/// Markdown suitable for pasting into a bug report
+fn render_report_markdown(report: &SelftestReport) -> String {
+    let mut out = String::new();
+    writeln!(out, "# git-ai selftest report").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "- git-ai: {}", report.git_ai_version).unwrap();
+    writeln!(out, "- git: {} ({})", report.git_version, report.real_git).unwrap();
+    writeln!(out, "- platform: {}", report.platform).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "```").unwrap();
+    out.push_str(&render_summary(report));
+    writeln!(out, "```").unwrap();
+
+    for scenario in &report.scenarios {
+        let ScenarioOutcome::Failed { divergences } = &scenario.outcome else {
+            continue;
+        };
+        writeln!(out).unwrap();
+        writeln!(out, "## {}", scenario.name).unwrap();
+        for divergence in divergences {
+            writeln!(out).unwrap();
+            writeln!(out, "`{}` — {} differs", divergence.step, divergence.field).unwrap();
+            for (label, text) in [
+                ("direct git", &divergence.direct),
+                ("shim", &divergence.shim),
+            ] {
+                writeln!(out).unwrap();
+                writeln!(out, "{}:", label).unwrap();
+                writeln!(out, "```").unwrap();
+                writeln!(out, "{}", text.trim_end()).unwrap();
+                writeln!(out, "```").unwrap();
+            }
+        }
+    }
+    out
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SelftestOptions {
+    json: bool,
+    report_path: Option<PathBuf>,
+}
+
+fn parse_selftest_options(args: &[String]) -> Result<SelftestOptions, String> {
+    let mut options = SelftestOptions::default();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--json" => options.json = true,
+            "--report" => {
+                let path = args
+                    .get(i + 1)
+                    .ok_or_else(|| "--report requires a file path".to_string())?;
+                options.report_path = Some(PathBuf::from(path));
+                i += 1;
+            }
+            other => return Err(format!("unknown selftest argument: {}", other)),
+        }
+        i += 1;
+    }
+    Ok(options)
+}
+
+pub fn handle_selftest(args: &[String]) {
+    if args
+        .iter()
+        .any(|arg| arg == "--help" || arg == "-h" || arg == "help")
+    {
+        print_help();
+        std::process::exit(0);
+    }
+
+    let options = match parse_selftest_options(args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            print_help();
+            std::process::exit(1);
+        }
+    };
+
+    let shim_program = match std::env::current_exe() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Error: could not locate the git-ai binary: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let real_git = PathBuf::from(config::Config::get().git_cmd());
+
+    if !options.json {
+        eprintln!("Running git through the shim and directly, comparing results...");
+    }
+    let report = match run_selftest(&real_git, &shim_program) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("Error: selftest could not run: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if options.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+    } else {
+        print!("{}", render_summary(&report));
+    }
+
+    if let Some(path) = &options.report_path {
+        if let Err(e) = fs::write(path, render_report_markdown(&report)) {
+            eprintln!("Error: failed to write report {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+        if !options.json {
+            eprintln!("Report written to {}", path.display());
+        }
+    }
+
+    if !report.passed() {
+        if !options.json && options.report_path.is_none() {
+            eprintln!(
+                "The shim changed git's behavior. Re-run with --report <file> and attach it to a bug report."
+            );
+        }
+        std::process::exit(1);
+    }
+}
+
+fn print_help() {
+    eprintln!("git-ai selftest - Check that the git shim never changes git's behavior");
+    eprintln!();
+    eprintln!("Runs clone, commit, rebase -i, stash, bisect, worktree, submodule, signed");
+    eprintln!("commit and merge scenarios through the shim and through real git in");
+    eprintln!("throwaway repositories, and fails if any output or resulting state differs.");
+    eprintln!();
+    eprintln!("Usage:");
+    eprintln!("  git-ai selftest [--json] [--report <file>]");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --json             Print results as JSON");
+    eprintln!("  --report <file>    Write a Markdown report for bug filing");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_outputs_reports_each_differing_field() {
+        let steps = vec![
+            write("repo/file.txt", "x\n"),
+            git("repo", &["status"]),
+            git("repo", &["log"]),
+        ];
+        let same = StepOutput {
+            exit_code: Some(0),
+            stdout: "clean\n".to_string(),
+            stderr: String::new(),
+        };
+        let shim_log = StepOutput {
+            exit_code: Some(1),
+            stdout: same.stdout.clone(),
+            stderr: "extra\n".to_string(),
+        };
+
+        let divergences = compare_outputs(
+            &steps,
+            &[same.clone(), same.clone()],
+            &[same.clone(), shim_log],
+        );
+        let fields: Vec<(&str, &str)> = divergences
+            .iter()
+            .map(|d| (d.step.as_str(), d.field))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("(in repo) git log", "exit code"),
+                ("(in repo) git log", "stderr")
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_selftest_options_accepts_json_and_report() {
+        let args: Vec<String> = ["--json", "--report", "out.md"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            parse_selftest_options(&args).unwrap(),
+            SelftestOptions {
+                json: true,
+                report_path: Some(PathBuf::from("out.md")),
+            }
+        );
+        assert!(parse_selftest_options(&["--report".to_string()]).is_err());
+        assert!(parse_selftest_options(&["--bogus".to_string()]).is_err());
+    }
+}
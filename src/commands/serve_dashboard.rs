@@ -0,0 +1,403 @@
+//! `git-ai serve-dashboard` — a small read-only web dashboard for teams
+//! without a BI stack.
+//!
+//! Serves one embedded page plus two JSON endpoints on a local port:
+//!
+//! - `GET /api/trend?granularity=<daily|weekly|monthly>&days=<n>` — the same
+//!   AI share trend as `git-ai report`, from the local metrics store
+//! - `GET /api/merges?limit=<n>` — recent first-parent commits on `HEAD` of
+//!   the repository the server was started in, with their originating
+//!   merge request, AI/human line counts, and how many of the commits each
+//!   one brought in carry authorship notes
+//!
+//! Requests are handled one at a time on the calling thread; only `GET` is
+//! accepted.
+
+use crate::authorship::ignore::effective_ignore_patterns;
+use crate::authorship::stats::stats_for_commit_stats;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::merge_request::merge_request_from_message;
+use crate::git::notes_api::commits_with_notes;
+use crate::git::repository::Repository;
+use crate::issue_tracker::{IssueLink, link_issues};
+use crate::metrics::local_stats::{
+    BucketGranularity, NOISE_CONTRIBUTION_BOUND, add_laplace_noise, compute_trend,
+    suppress_small_groups,
+};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DASHBOARD_HTML: &str = include_str!("serve_dashboard/index.html");
+
+const DEFAULT_BIND: &str = "127.0.0.1:8787";
+
+const DEFAULT_MERGE_LIMIT: usize = 20;
+const MAX_MERGE_LIMIT: usize = 200;
+
+/// Commits inspected per merge when checking note coverage
+const MAX_COMMITS_PER_MERGE: usize = 500;
+
+#[derive(Debug, Serialize)]
+struct MergeSummary {
+    commit: String,
+    subject: String,
+    author: String,
+    date: String,
+    merge_request: Option<String>,
+    issues: Vec<IssueLink>,
+    commits: usize,
+    commits_with_notes: usize,
+    ai_additions: u32,
+    human_additions: u32,
+    unknown_additions: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct MergesResponse {
+    repository: Option<String>,
+    merges: Vec<MergeSummary>,
+}
+
+pub fn handle_serve_dashboard(args: &[String]) {
+    let mut bind = DEFAULT_BIND.to_string();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--bind" => {
+                i += 1;
+                bind = args
+                    .get(i)
+                    .cloned()
+                    .unwrap_or_else(|| usage_error("--bind requires an address"));
+            }
+            "--port" => {
+                i += 1;
+                let port = match args.get(i).and_then(|v| v.parse::<u16>().ok()) {
+                    Some(port) => port,
+                    None => usage_error("--port requires a port number"),
+                };
+                bind = format!("127.0.0.1:{}", port);
+            }
+            "--help" | "-h" => {
+                print_help();
+                return;
+            }
+            other => usage_error(&format!("Unknown argument: {}", other)),
+        }
+        i += 1;
+    }
+
+    // Outside a repository the trend still works; merges come back empty
+    let repo = find_repository(&Vec::<String>::new()).ok();
+
+    if let Err(e) = serve(&bind, repo.as_ref()) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn serve(bind: &str, repo: Option<&Repository>) -> Result<(), GitAiError> {
+    let listener = TcpListener::bind(bind)
+        .map_err(|e| GitAiError::Generic(format!("bind {}: {}", bind, e)))?;
+    let addr = listener.local_addr()?;
+    eprintln!("git-ai dashboard listening on http://{}", addr);
+    eprintln!("Press Ctrl-C to stop.");
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle_connection(stream, repo) {
+                    eprintln!("dashboard: connection error: {}", e);
+                }
+            }
+            Err(e) => eprintln!("dashboard: accept error: {}", e),
+        }
+    }
+    Ok(())
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(value: &impl Serialize) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => Self {
+                status: 200,
+                content_type: "application/json",
+                body,
+            },
+            Err(e) => Self::error(500, &e.to_string()),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::json!({ "error": message })
+                .to_string()
+                .into_bytes(),
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, repo: Option<&Repository>) -> Result<(), GitAiError> {
+    // A stalled client shouldn't block the single-threaded loop for long
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain headers; nothing in them matters for a read-only GET
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let target = parts.next().unwrap_or("");
+    let response = dispatch(method, target, repo);
+
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    let header = format!(
+        "HTTP/1.1 {} {}\r\n\
+         Content-Type: {}\r\n\
+         Content-Length: {}\r\n\
+         Cache-Control: no-store\r\n\
+         Connection: close\r\n\
+         \r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(header.as_bytes())?;
+    stream.write_all(&response.body)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn dispatch(method: &str, target: &str, repo: Option<&Repository>) -> Response {
+    if method != "GET" {
+        return Response::error(405, "the dashboard is read-only");
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    match path {
+        "/" | "/index.html" => Response {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: DASHBOARD_HTML.as_bytes().to_vec(),
+        },
+        "/api/trend" => trend_response(query),
+        "/api/merges" => {
+            let limit = match query_param(query, "limit").map(|v| v.parse::<usize>()) {
+                None => DEFAULT_MERGE_LIMIT,
+                Some(Ok(n)) if n > 0 => n.min(MAX_MERGE_LIMIT),
+                Some(_) => return Response::error(400, "limit must be a positive number"),
+            };
+            match recent_merges(repo, limit) {
+                Ok(merges) => Response::json(&merges),
+                Err(e) => Response::error(500, &e.to_string()),
+            }
+        }
+        _ => Response::error(404, "not found"),
+    }
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn trend_response(query: &str) -> Response {
+    let granularity = match query_param(query, "granularity") {
+        None | Some("weekly") => BucketGranularity::Weekly,
+        Some("daily") => BucketGranularity::Daily,
+        Some("monthly") => BucketGranularity::Monthly,
+        Some(_) => return Response::error(400, "granularity must be daily, weekly or monthly"),
+    };
+    let days = match query_param(query, "days").map(|v| v.parse::<u64>()) {
+        None => 90,
+        Some(Ok(n)) if n > 0 => n,
+        Some(_) => return Response::error(400, "days must be a positive number"),
+    };
+
+    // Honor the same privacy policy `git-ai report` enforces
+    let config = Config::get();
+    let noise_epsilon = config.report_noise_epsilon();
+    let bound = noise_epsilon.map(|_| NOISE_CONTRIBUTION_BOUND);
+    match compute_trend(days_ago(days), granularity, None, None, bound) {
+        Ok(mut points) => {
+            if let Some(k) = config.report_min_group_size().filter(|k| *k > 1) {
+                suppress_small_groups(&mut points, k);
+            }
+            if let Some(epsilon) = noise_epsilon {
+                add_laplace_noise(
+                    &mut points,
+                    epsilon,
+                    NOISE_CONTRIBUTION_BOUND,
+                    &mut rand::rng(),
+                );
+            }
+            Response::json(&points)
+        }
+        Err(e) => Response::error(500, &e.to_string()),
+    }
+}
+
+fn days_ago(days: u64) -> u32 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    now.saturating_sub(days * 24 * 3600).min(u32::MAX as u64) as u32
+}
+
+fn recent_merges(repo: Option<&Repository>, limit: usize) -> Result<MergesResponse, GitAiError> {
+    let Some(repo) = repo else {
+        return Ok(MergesResponse {
+            repository: None,
+            merges: Vec::new(),
+        });
+    };
+    let ignore_patterns = effective_ignore_patterns(repo, &[], &[]);
+    let trackers = Config::get().issue_trackers();
+
+    let log = repo.git_output(&[
+        "log",
+        "--first-parent",
+        "--no-notes",
+        &format!("--max-count={}", limit),
+        "--format=%H%x00%P%x00%an%x00%aI%x00%B%x1e",
+        "HEAD",
+    ])?;
+
+    let mut merges = Vec::new();
+    for record in log.split('\x1e').map(str::trim).filter(|r| !r.is_empty()) {
+        let mut fields = record.splitn(5, '\0');
+        let mut next = || fields.next().unwrap_or("").to_string();
+        let (commit, parents, author, date, message) = (next(), next(), next(), next(), next());
+
+        // What the merge brought in: the side branch for merge commits,
+        // otherwise (squash/rebase/direct) the commit itself
+        let introduced = match parents.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [first, _, ..] => repo
+                .git_output(&[
+                    "rev-list",
+                    "--no-merges",
+                    &format!("--max-count={}", MAX_COMMITS_PER_MERGE),
+                    &format!("{}..{}", first, commit),
+                ])?
+                .lines()
+                .map(str::to_string)
+                .collect(),
+            _ => vec![commit.clone()],
+        };
+
+        let noted = commits_with_notes(repo, &introduced)?;
+        let (mut ai, mut human, mut unknown) = (0, 0, 0);
+        for sha in &introduced {
+            let stats = stats_for_commit_stats(repo, sha, &ignore_patterns)?;
+            ai += stats.ai_additions;
+            human += stats.human_additions;
+            unknown += stats.unknown_additions;
+        }
+
+        merges.push(MergeSummary {
+            subject: message.lines().next().unwrap_or("").to_string(),
+            merge_request: merge_request_from_message(&message),
+            issues: link_issues(trackers, &[&message])?,
+            commits: introduced.len(),
+            commits_with_notes: noted.len(),
+            ai_additions: ai,
+            human_additions: human,
+            unknown_additions: unknown,
+            commit,
+            author,
+            date,
+        });
+    }
+
+    Ok(MergesResponse {
+        repository: Some(repo.workdir()?.display().to_string()),
+        merges,
+    })
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!("Run 'git-ai serve-dashboard --help' for usage.");
+    std::process::exit(1);
+}
+
+fn print_help() {
+    eprintln!("git-ai serve-dashboard - Serve a read-only AI usage dashboard");
+    eprintln!();
+    eprintln!("Usage: git-ai serve-dashboard [--bind <addr> | --port <port>]");
+    eprintln!();
+    eprintln!("Shows the AI share trend from local metrics and, when started inside a");
+    eprintln!("repository, recent merges on HEAD with their note coverage.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!(
+        "  --bind <addr>    Address to listen on (default: {})",
+        DEFAULT_BIND
+    );
+    eprintln!("  --port <port>    Listen on 127.0.0.1:<port>");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_get_routes_are_served() {
+        assert_eq!(dispatch("POST", "/api/trend", None).status, 405);
+        assert_eq!(dispatch("GET", "/nope", None).status, 404);
+
+        let index = dispatch("GET", "/", None);
+        assert_eq!(index.status, 200);
+        assert!(index.content_type.starts_with("text/html"));
+
+        assert_eq!(
+            dispatch("GET", "/api/trend?granularity=hourly", None).status,
+            400
+        );
+        assert_eq!(dispatch("GET", "/api/merges?limit=0", None).status, 400);
+
+        let merges = dispatch("GET", "/api/merges", None);
+        assert_eq!(merges.status, 200);
+        assert_eq!(
+            String::from_utf8(merges.body).unwrap(),
+            r#"{"repository":null,"merges":[]}"#
+        );
+    }
+
+    #[test]
+    fn query_params_are_looked_up_by_name() {
+        let query = "granularity=daily&days=14";
+        assert_eq!(query_param(query, "days"), Some("14"));
+        assert_eq!(query_param(query, "granularity"), Some("daily"));
+        assert_eq!(query_param(query, "limit"), None);
+    }
+}
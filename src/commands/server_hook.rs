@@ -0,0 +1,525 @@
+//! `git-ai server-hook` — attribution policy for pushes, run as a
+//! `pre-receive` or `update` hook on a self-hosted server.
+//!
+//! Policy is read from the receiving repository's own git config
+//! (`git-ai.serverHook.*`), so each hosted repository can opt in separately.
+//! Incoming commits are listed with a streamed `rev-list`, and notes are
+//! looked up in the notes commit being pushed alongside them when there is
+//! one, otherwise in the server's current `refs/notes/ai`.
+
+use crate::authorship::authorship_log::LineRange;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git, exec_git_stdin, exec_git_stdin_streaming};
+use glob::Pattern;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+const NOTES_REF: &str = "refs/notes/ai";
+
+/// Violations listed per ref before the rest are summarized
+const MAX_LISTED_VIOLATIONS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HookMode {
+    Off,
+    Warn,
+    Enforce,
+}
+
+impl HookMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "off" => Some(Self::Off),
+            "warn" => Some(Self::Warn),
+            "enforce" => Some(Self::Enforce),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HookPolicy {
+    mode: HookMode,
+    /// Ref globs the policy applies to
+    refs: Vec<String>,
+    require_authorship: bool,
+    /// Reject a ref update when AI-attributed lines exceed this share of the
+    /// lines it adds
+    max_ai_percent: Option<u32>,
+}
+
+impl Default for HookPolicy {
+    fn default() -> Self {
+        Self {
+            mode: HookMode::Warn,
+            refs: Vec::new(),
+            require_authorship: true,
+            max_ai_percent: None,
+        }
+    }
+}
+
+impl HookPolicy {
+    /// Parse `git config --get-regexp '^git-ai\.serverHook\.'` output. The
+    /// subsection keeps its case; variable names come back lowercased.
+    fn from_config_output(output: &str) -> Result<Self, GitAiError> {
+        let mut policy = Self::default();
+        for line in output.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = value.trim();
+            match key.strip_prefix("git-ai.serverHook.") {
+                Some("mode") => {
+                    policy.mode = HookMode::parse(value).ok_or_else(|| {
+                        GitAiError::Generic(format!(
+                            "git-ai.serverHook.mode must be off, warn or enforce (got '{}')",
+                            value
+                        ))
+                    })?;
+                }
+                Some("ref") => policy.refs.push(value.to_string()),
+                Some("requireauthorship") => {
+                    policy.require_authorship = parse_git_bool(value).ok_or_else(|| {
+                        GitAiError::Generic(format!(
+                            "git-ai.serverHook.requireAuthorship is not a boolean: '{}'",
+                            value
+                        ))
+                    })?;
+                }
+                Some("maxaipercent") => {
+                    let percent = value.parse::<u32>().ok().filter(|p| *p <= 100);
+                    policy.max_ai_percent = Some(percent.ok_or_else(|| {
+                        GitAiError::Generic(format!(
+                            "git-ai.serverHook.maxAiPercent must be 0-100 (got '{}')",
+                            value
+                        ))
+                    })?);
+                }
+                _ => {}
+            }
+        }
+        if policy.refs.is_empty() {
+            policy.refs.push("refs/heads/*".to_string());
+        }
+        Ok(policy)
+    }
+
+    fn applies_to(&self, refname: &str) -> bool {
+        self.refs.iter().any(|glob| match Pattern::new(glob) {
+            Ok(pattern) => pattern.matches(refname),
+            Err(_) => glob == refname,
+        })
+    }
+}
+
+fn parse_git_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        // A bare `key` line (no value) is true in git config
+        "" | "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RefUpdate {
+    old: String,
+    new: String,
+    refname: String,
+}
+
+fn is_zero_oid(oid: &str) -> bool {
+    !oid.is_empty() && oid.bytes().all(|b| b == b'0')
+}
+
+/// Parse `pre-receive` stdin: one `<old> <new> <ref>` line per update
+fn parse_ref_updates(input: &str) -> Vec<RefUpdate> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(RefUpdate {
+                old: fields.next()?.to_string(),
+                new: fields.next()?.to_string(),
+                refname: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn handle_server_hook(args: &[String]) {
+    let mut mode_override: Option<HookMode> = None;
+    let mut positional: Vec<&str> = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--help" | "-h" => {
+                print_help();
+                return;
+            }
+            "--mode" => {
+                let Some(value) = args.get(i + 1) else {
+                    usage_error("--mode requires a value");
+                };
+                mode_override = Some(HookMode::parse(value).unwrap_or_else(|| {
+                    usage_error(&format!("Unknown mode: {}", value));
+                }));
+                i += 1;
+            }
+            other if other.starts_with('-') => usage_error(&format!("Unknown argument: {}", other)),
+            other => positional.push(other),
+        }
+        i += 1;
+    }
+
+    // `update` hooks get `<ref> <old> <new>` as arguments, `pre-receive`
+    // hooks get the same triples on stdin
+    let updates = match positional.as_slice() {
+        [] => {
+            let mut input = String::new();
+            if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+                fail(&format!("Failed to read ref updates from stdin: {}", e));
+            }
+            parse_ref_updates(&input)
+        }
+        [refname, old, new] => vec![RefUpdate {
+            old: old.to_string(),
+            new: new.to_string(),
+            refname: refname.to_string(),
+        }],
+        _ => usage_error("Expected no arguments (pre-receive) or <ref> <old> <new> (update)"),
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => fail(&format!("Failed to find repository: {}", e)),
+    };
+
+    match run(&repo, &updates, mode_override) {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => fail(&e.to_string()),
+    }
+}
+
+/// Check every update. Returns false when the push must be rejected.
+fn run(
+    repo: &Repository,
+    updates: &[RefUpdate],
+    mode_override: Option<HookMode>,
+) -> Result<bool, GitAiError> {
+    let mut policy = HookPolicy::from_config_output(&git_output_allow_nonzero(
+        repo,
+        &["config", "--get-regexp", r"^git-ai\.serverHook\."],
+    )?)?;
+    if let Some(mode) = mode_override {
+        policy.mode = mode;
+    }
+    if policy.mode == HookMode::Off {
+        return Ok(true);
+    }
+
+    // Notes pushed in the same push aren't visible under refs/notes/ai yet
+    let notes_tip = match updates.iter().find(|u| u.refname == NOTES_REF) {
+        Some(update) if !is_zero_oid(&update.new) => Some(update.new.clone()),
+        _ => {
+            let current = git_output_allow_nonzero(
+                repo,
+                &[
+                    "rev-parse",
+                    "--quiet",
+                    "--verify",
+                    &format!("{}^{{commit}}", NOTES_REF),
+                ],
+            )?;
+            Some(current.trim().to_string()).filter(|sha| !sha.is_empty())
+        }
+    };
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut accepted = true;
+    for update in updates {
+        if is_zero_oid(&update.new) || !policy.applies_to(&update.refname) {
+            continue;
+        }
+        let commits: Vec<String> = new_commits(repo, &update.new)?
+            .into_iter()
+            .filter(|sha| seen.insert(sha.clone()))
+            .collect();
+        if commits.is_empty() {
+            continue;
+        }
+
+        let violations = check_commits(repo, &policy, notes_tip.as_deref(), &commits)?;
+        if violations.is_empty() {
+            continue;
+        }
+
+        let label = if policy.mode == HookMode::Enforce {
+            accepted = false;
+            "rejected"
+        } else {
+            "warning"
+        };
+        eprintln!("git-ai: {} {}:", label, update.refname);
+        for violation in violations.iter().take(MAX_LISTED_VIOLATIONS) {
+            eprintln!("git-ai:   {}", violation);
+        }
+        if violations.len() > MAX_LISTED_VIOLATIONS {
+            eprintln!(
+                "git-ai:   ... and {} more",
+                violations.len() - MAX_LISTED_VIOLATIONS
+            );
+        }
+    }
+
+    if !accepted {
+        eprintln!(
+            "git-ai: push authorship notes with the branch: git push <remote> <branch> {}",
+            NOTES_REF
+        );
+    }
+    Ok(accepted)
+}
+
+fn git_output_allow_nonzero(repo: &Repository, args: &[&str]) -> Result<String, GitAiError> {
+    let mut full_args = repo.global_args_for_exec();
+    full_args.extend(args.iter().map(|arg| arg.to_string()));
+    match exec_git(&full_args) {
+        Ok(output) => Ok(String::from_utf8(output.stdout)?),
+        // `git config --get-regexp` and `rev-parse --quiet` exit 1 on no match
+        Err(GitAiError::GitCliError { code: Some(1), .. }) => Ok(String::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Non-merge commits reachable from `tip` that no existing ref reaches yet.
+/// Objects from the push are visible here through git's quarantine env.
+fn new_commits(repo: &Repository, tip: &str) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        ["rev-list", "--no-merges", tip, "--not", "--all"]
+            .iter()
+            .map(|arg| arg.to_string()),
+    );
+    let mut commits = Vec::new();
+    exec_git_stdin_streaming(&args, &[], |line| {
+        if !line.is_empty() {
+            commits.push(line.to_string());
+        }
+    })?;
+    Ok(commits)
+}
+
+fn check_commits(
+    repo: &Repository,
+    policy: &HookPolicy,
+    notes_tip: Option<&str>,
+    commits: &[String],
+) -> Result<Vec<String>, GitAiError> {
+    let notes = match notes_tip {
+        Some(tip) => read_notes(repo, tip, commits)?,
+        None => HashMap::new(),
+    };
+
+    let mut violations = Vec::new();
+    if policy.require_authorship {
+        for sha in commits.iter().filter(|sha| !notes.contains_key(*sha)) {
+            violations.push(format!("{} has no authorship note", short(sha)));
+        }
+    }
+
+    if let Some(max_percent) = policy.max_ai_percent {
+        let added = added_lines(repo, commits)?;
+        let total_added: u64 = added.values().sum();
+        let ai_lines: u64 = notes.values().map(|note| ai_line_count(note)).sum();
+        if total_added > 0 && ai_lines * 100 > u64::from(max_percent) * total_added {
+            violations.push(format!(
+                "AI-attributed lines are {}% of the {} lines added (limit {}%)",
+                ai_lines * 100 / total_added,
+                total_added,
+                max_percent
+            ));
+        }
+    }
+    Ok(violations)
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..sha.len().min(12)]
+}
+
+/// Read the notes for `commits` from the notes commit `notes_tip`, trying
+/// each fanout layout git may have used for the note path.
+fn read_notes(
+    repo: &Repository,
+    notes_tip: &str,
+    commits: &[String],
+) -> Result<HashMap<String, String>, GitAiError> {
+    let mut input = String::new();
+    for sha in commits {
+        for path in note_paths(sha) {
+            input.push_str(&format!("{}:{}\n", notes_tip, path));
+        }
+    }
+    let mut args = repo.global_args_for_exec();
+    args.extend(["cat-file".to_string(), "--batch".to_string()]);
+    let output = exec_git_stdin(&args, input.as_bytes())?;
+
+    let blobs = parse_cat_file_batch(&output.stdout);
+    let mut notes = HashMap::new();
+    for (sha, candidates) in commits.iter().zip(blobs.chunks(3)) {
+        if let Some(content) = candidates.iter().flatten().next() {
+            notes.insert(sha.clone(), String::from_utf8_lossy(content).to_string());
+        }
+    }
+    Ok(notes)
+}
+
+fn note_paths(sha: &str) -> [String; 3] {
+    [
+        sha.to_string(),
+        format!("{}/{}", &sha[..2], &sha[2..]),
+        format!("{}/{}/{}", &sha[..2], &sha[2..4], &sha[4..]),
+    ]
+}
+
+/// Split `git cat-file --batch` output into one entry per input line
+fn parse_cat_file_batch(output: &[u8]) -> Vec<Option<&[u8]>> {
+    let mut entries = Vec::new();
+    let mut rest = output;
+    while let Some(newline) = rest.iter().position(|b| *b == b'\n') {
+        let header = String::from_utf8_lossy(&rest[..newline]);
+        rest = &rest[newline + 1..];
+        let size = header
+            .rsplit(' ')
+            .next()
+            .and_then(|size| size.parse::<usize>().ok());
+        match size {
+            Some(size) if !header.ends_with(" missing") && size <= rest.len() => {
+                entries.push(Some(&rest[..size]));
+                // Content is followed by a newline
+                rest = &rest[(size + 1).min(rest.len())..];
+            }
+            _ => entries.push(None),
+        }
+    }
+    entries
+}
+
+/// Lines the note attributes to AI (anything not attested to a human)
+fn ai_line_count(note: &str) -> u64 {
+    let Ok(log) = AuthorshipLog::deserialize_from_string(note) else {
+        return 0;
+    };
+    log.attestations
+        .iter()
+        .flat_map(|file| &file.entries)
+        .filter(|entry| !entry.hash.starts_with("h_"))
+        .flat_map(|entry| &entry.line_ranges)
+        .map(|range| match range {
+            LineRange::Single(_) => 1,
+            LineRange::Range(start, end) => u64::from(end.saturating_sub(*start)) + 1,
+        })
+        .sum()
+}
+
+/// Lines added by each commit against its first parent
+fn added_lines(repo: &Repository, commits: &[String]) -> Result<HashMap<String, u64>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        ["diff-tree", "--stdin", "--root", "-r", "--numstat"]
+            .iter()
+            .map(|arg| arg.to_string()),
+    );
+    let input = commits.join("\n") + "\n";
+    let mut added: HashMap<String, u64> = HashMap::new();
+    let mut current: Option<String> = None;
+    exec_git_stdin_streaming(&args, input.as_bytes(), |line| {
+        let mut fields = line.split('\t');
+        match (fields.next(), fields.next()) {
+            (Some(count), Some(_)) => {
+                if let (Some(sha), Ok(count)) = (&current, count.parse::<u64>()) {
+                    *added.entry(sha.clone()).or_default() += count;
+                }
+            }
+            (Some(sha), None) if !sha.is_empty() => current = Some(sha.to_string()),
+            _ => {}
+        }
+    })?;
+    Ok(added)
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("git-ai: {}", message);
+    std::process::exit(1);
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!("Run 'git-ai server-hook --help' for usage.");
+    std::process::exit(1);
+}
+
+fn print_help() {
+    eprintln!("git-ai server-hook - Check attribution policy on pushes (server side)");
+    eprintln!();
+    eprintln!("Usage:");
+    eprintln!("  git-ai server-hook [--mode <mode>]                    as a pre-receive hook");
+    eprintln!("  git-ai server-hook [--mode <mode>] <ref> <old> <new>  as an update hook");
+    eprintln!();
+    eprintln!("Policy is read from the receiving repository's git config:");
+    eprintln!("  git-ai.serverHook.mode               off, warn (default) or enforce");
+    eprintln!("  git-ai.serverHook.ref                Ref glob to check (repeatable;");
+    eprintln!("                                       default refs/heads/*)");
+    eprintln!("  git-ai.serverHook.requireAuthorship  Require a note on every new commit");
+    eprintln!("                                       (default true)");
+    eprintln!("  git-ai.serverHook.maxAiPercent       Highest AI share of added lines per");
+    eprintln!("                                       ref update");
+    eprintln!();
+    eprintln!("git-ai clients push refs/notes/ai after the branch, so enforce mode needs");
+    eprintln!("notes pushed together with the branch or already on the server.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_is_parsed_from_git_config_output() {
+        let policy = HookPolicy::from_config_output(
+            "git-ai.serverHook.mode enforce\n\
+             git-ai.serverHook.ref refs/heads/main\n\
+             git-ai.serverHook.ref refs/heads/release/*\n\
+             git-ai.serverHook.requireauthorship false\n\
+             git-ai.serverHook.maxaipercent 60\n",
+        )
+        .unwrap();
+        assert_eq!(policy.mode, HookMode::Enforce);
+        assert!(!policy.require_authorship);
+        assert_eq!(policy.max_ai_percent, Some(60));
+        assert!(policy.applies_to("refs/heads/release/2.1"));
+        assert!(!policy.applies_to("refs/heads/feature"));
+
+        let defaults = HookPolicy::from_config_output("").unwrap();
+        assert_eq!(defaults.mode, HookMode::Warn);
+        assert!(defaults.applies_to("refs/heads/feature"));
+        assert!(!defaults.applies_to("refs/tags/v1"));
+
+        assert!(HookPolicy::from_config_output("git-ai.serverHook.maxaipercent 150").is_err());
+    }
+
+    #[test]
+    fn cat_file_batch_output_keeps_input_order() {
+        let output = b"abc:def missing\n\
+                       1111111111111111111111111111111111111111 blob 5\nhello\n\
+                       abc:x/y missing\n";
+        let entries = parse_cat_file_batch(output);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0], None);
+        assert_eq!(entries[1], Some(&b"hello"[..]));
+        assert_eq!(entries[2], None);
+    }
+}
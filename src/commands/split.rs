@@ -0,0 +1,478 @@
+//! `git-ai split` — break the staged changes into several commits.
+//!
+//! Staged files are grouped by kind (build, source, tests, docs, CI) and by
+//! component directory. The user can merge groups, move files, and reword the
+//! proposed messages before anything is committed. Each commit is made with the
+//! real git so the daemon attributes it like any other commit; AI/human line
+//! attribution for files left staged carries over to the next commit.
+
+use crate::commands::git_handlers::proxy_to_git;
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git, exec_git_stdin, find_repository_in_path};
+use std::collections::BTreeMap;
+use std::io::{BufRead, IsTerminal, Write};
+
+#[derive(Debug, Clone, PartialEq)]
+struct SplitGroup {
+    message: String,
+    files: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SplitCommand {
+    Accept,
+    Quit,
+    /// Merge the second group into the first (1-based)
+    Merge(usize, usize),
+    /// Move a file into a group (1-based)
+    Move(String, usize),
+    Reword(usize, String),
+}
+
+const DIRECTORY_ROOTS: &[&str] = &[
+    "src", "lib", "pkg", "app", "crates", "packages", "tests", "test",
+];
+
+const BUILD_FILES: &[&str] = &[
+    "Cargo.toml",
+    "Cargo.lock",
+    "build.rs",
+    "package.json",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "go.mod",
+    "go.sum",
+    "pyproject.toml",
+    "requirements.txt",
+    "Makefile",
+    "Dockerfile",
+];
+
+pub fn handle_split(args: &[String]) {
+    let mut yes = false;
+    let mut dry_run = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "--yes" | "-y" => yes = true,
+            "--dry-run" => dry_run = true,
+            "--help" | "-h" => {
+                print_help();
+                return;
+            }
+            other => usage_error(&format!("Unknown argument: {}", other)),
+        }
+    }
+
+    let repo = match find_repository_in_path(".") {
+        Ok(repo) => repo,
+        Err(_) => fail("not in a git repository"),
+    };
+    let staged = staged_files(&repo).unwrap_or_else(|e| fail(&e.to_string()));
+    if staged.is_empty() {
+        eprintln!("No staged changes to split.");
+        std::process::exit(1);
+    }
+
+    let mut groups = propose_groups(&staged);
+    if groups.len() < 2 && !dry_run {
+        eprintln!("Staged changes form a single logical group; nothing to split.");
+        return;
+    }
+
+    if dry_run {
+        print_groups(&groups);
+        return;
+    }
+
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            usage_error("stdin is not a terminal; pass --yes to accept the proposed split");
+        }
+        if !adjust_interactively(&mut groups) {
+            eprintln!("Split cancelled; the index is unchanged.");
+            return;
+        }
+    }
+
+    if let Err(e) = commit_groups(&repo, &groups) {
+        fail(&e.to_string());
+    }
+}
+
+fn staged_files(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        ["diff", "--cached", "--name-only", "-z", "--no-renames"]
+            .iter()
+            .map(|s| s.to_string()),
+    );
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Conventional Commits type for a path, used as the first grouping key
+fn classify(path: &str) -> &'static str {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let first = path.split('/').next().unwrap_or(path);
+    let extension = file_name.rsplit_once('.').map(|(_, ext)| ext);
+
+    if matches!(first, ".github" | ".circleci" | ".gitlab") || file_name == ".gitlab-ci.yml" {
+        "ci"
+    } else if BUILD_FILES.contains(&file_name) {
+        "build"
+    } else if matches!(first, "docs" | "doc") || matches!(extension, Some("md" | "rst" | "adoc")) {
+        "docs"
+    } else if matches!(first, "tests" | "test" | "__tests__")
+        || file_name.starts_with("test_")
+        || [".test.", ".spec.", "_test."]
+            .iter()
+            .any(|marker| file_name.contains(marker))
+    {
+        "test"
+    } else {
+        "chore"
+    }
+}
+
+/// Component a path belongs to: the directory under a conventional root such
+/// as `src/`, otherwise the top-level directory. Root files have none.
+fn component(path: &str) -> Option<String> {
+    let dirs: Vec<&str> = path.split('/').collect();
+    let dirs = &dirs[..dirs.len().saturating_sub(1)];
+    match dirs {
+        [root, sub, ..] if DIRECTORY_ROOTS.contains(root) => Some(sub.to_string()),
+        [first, ..] if !DIRECTORY_ROOTS.contains(first) => Some(first.to_string()),
+        _ => None,
+    }
+}
+
+fn kind_rank(kind: &str) -> usize {
+    match kind {
+        "build" => 0,
+        "chore" => 1,
+        "test" => 2,
+        "docs" => 3,
+        _ => 4,
+    }
+}
+
+fn propose_groups(paths: &[String]) -> Vec<SplitGroup> {
+    let mut buckets: BTreeMap<(usize, &'static str, Option<String>), Vec<String>> = BTreeMap::new();
+    for path in paths {
+        let kind = classify(path);
+        // Docs, build, and CI changes rarely need splitting by directory
+        let scope = matches!(kind, "chore" | "test")
+            .then(|| component(path))
+            .flatten();
+        buckets
+            .entry((kind_rank(kind), kind, scope))
+            .or_default()
+            .push(path.clone());
+    }
+
+    buckets
+        .into_iter()
+        .map(|((_, kind, scope), files)| SplitGroup {
+            message: proposed_message(kind, scope.as_deref(), &files),
+            files,
+        })
+        .collect()
+}
+
+fn proposed_message(kind: &str, scope: Option<&str>, files: &[String]) -> String {
+    let scope = scope.map(|s| format!("({})", s)).unwrap_or_default();
+    let what = match files {
+        [only] => only.rsplit('/').next().unwrap_or(only).to_string(),
+        _ => format!("{} files", files.len()),
+    };
+    format!("{}{}: update {}", kind, scope, what)
+}
+
+fn parse_command(line: &str) -> Result<SplitCommand, String> {
+    let line = line.trim();
+    let (verb, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    let index = |value: &str| {
+        value
+            .parse::<usize>()
+            .ok()
+            .filter(|i| *i > 0)
+            .ok_or_else(|| format!("'{}' is not a group number", value))
+    };
+
+    match verb {
+        "" | "y" | "yes" | "a" | "accept" => Ok(SplitCommand::Accept),
+        "q" | "quit" => Ok(SplitCommand::Quit),
+        "m" | "merge" => {
+            let mut parts = rest.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(a), Some(b)) => Ok(SplitCommand::Merge(index(a)?, index(b)?)),
+                _ => Err("usage: m <into> <from>".to_string()),
+            }
+        }
+        "mv" | "move" => match rest.rsplit_once(char::is_whitespace) {
+            Some((path, group)) => Ok(SplitCommand::Move(path.trim().to_string(), index(group)?)),
+            None => Err("usage: mv <path> <group>".to_string()),
+        },
+        "r" | "reword" => match rest.split_once(char::is_whitespace) {
+            Some((group, message)) if !message.trim().is_empty() => Ok(SplitCommand::Reword(
+                index(group)?,
+                message.trim().to_string(),
+            )),
+            _ => Err("usage: r <group> <message>".to_string()),
+        },
+        other => Err(format!("unknown command '{}'", other)),
+    }
+}
+
+fn apply_command(groups: &mut Vec<SplitGroup>, command: SplitCommand) -> Result<(), String> {
+    let check = |i: usize, len: usize| {
+        (1..=len)
+            .contains(&i)
+            .then(|| i - 1)
+            .ok_or_else(|| format!("there is no group {}", i))
+    };
+
+    match command {
+        SplitCommand::Accept | SplitCommand::Quit => {}
+        SplitCommand::Merge(into, from) => {
+            let (into, from) = (check(into, groups.len())?, check(from, groups.len())?);
+            if into == from {
+                return Err("cannot merge a group into itself".to_string());
+            }
+            let moved = groups.remove(from);
+            let into = if from < into { into - 1 } else { into };
+            groups[into].files.extend(moved.files);
+        }
+        SplitCommand::Move(path, to) => {
+            let to = check(to, groups.len())?;
+            let from = groups
+                .iter()
+                .position(|g| g.files.contains(&path))
+                .ok_or_else(|| format!("'{}' is not staged", path))?;
+            if from != to {
+                groups[from].files.retain(|f| f != &path);
+                groups[to].files.push(path);
+                if groups[from].files.is_empty() {
+                    groups.remove(from);
+                }
+            }
+        }
+        SplitCommand::Reword(i, message) => {
+            let i = check(i, groups.len())?;
+            groups[i].message = message;
+        }
+    }
+    Ok(())
+}
+
+fn print_groups(groups: &[SplitGroup]) {
+    println!("Proposed commits:");
+    for (i, group) in groups.iter().enumerate() {
+        println!("  {}. {}", i + 1, group.message);
+        for file in &group.files {
+            println!("       {}", file);
+        }
+    }
+}
+
+/// Returns false if the user quit.
+fn adjust_interactively(groups: &mut Vec<SplitGroup>) -> bool {
+    let stdin = std::io::stdin();
+    loop {
+        print_groups(groups);
+        println!();
+        println!(
+            "[enter] accept, m <into> <from> merge, mv <path> <group> move, r <group> <message> reword, q quit"
+        );
+        print!("> ");
+        let _ = std::io::stdout().flush();
+
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) | Err(_) => return false,
+            Ok(_) => {}
+        }
+        match parse_command(&line) {
+            Ok(SplitCommand::Accept) => return true,
+            Ok(SplitCommand::Quit) => return false,
+            Ok(command) => {
+                if let Err(e) = apply_command(groups, command) {
+                    eprintln!("{}", e);
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+/// Commit each group in order. The full staged tree is snapshotted first and
+/// each group's paths are restored from it onto a HEAD-based index, so partially
+/// staged files keep exactly their staged content.
+fn commit_groups(repo: &Repository, groups: &[SplitGroup]) -> Result<(), GitAiError> {
+    let git = |extra: &[&str]| {
+        let mut args = repo.global_args_for_exec();
+        args.extend(extra.iter().map(|s| s.to_string()));
+        args
+    };
+
+    if repo.head().and_then(|head| head.target()).is_err() {
+        return Err(GitAiError::Generic(
+            "git-ai split needs an existing commit to split onto".to_string(),
+        ));
+    }
+
+    let output = exec_git(&git(&["write-tree"]))?;
+    let staged_tree = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    exec_git(&git(&["read-tree", "HEAD"]))?;
+
+    for (i, group) in groups.iter().enumerate() {
+        let source = format!("--source={}", staged_tree);
+        let pathspecs = group.files.join("\0");
+        let step = exec_git_stdin(
+            &git(&[
+                "restore",
+                "--staged",
+                &source,
+                "--pathspec-from-file=-",
+                "--pathspec-file-nul",
+            ]),
+            pathspecs.as_bytes(),
+        )
+        .and_then(|_| {
+            let status = proxy_to_git(&git(&["commit", "-m", &group.message]), false);
+            if status.success() {
+                Ok(())
+            } else {
+                Err(GitAiError::Generic(format!(
+                    "git commit failed for '{}'",
+                    group.message
+                )))
+            }
+        });
+
+        if let Err(e) = step {
+            // Put back everything not yet committed so no staged work is lost
+            exec_git(&git(&["read-tree", &staged_tree]))?;
+            return Err(GitAiError::Generic(format!(
+                "{} ({} of {} commits made; remaining changes are staged again)",
+                e,
+                i,
+                groups.len()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!("Run 'git-ai split --help' for usage.");
+    std::process::exit(1);
+}
+
+fn print_help() {
+    eprintln!("git-ai split - Split staged changes into separate commits");
+    eprintln!();
+    eprintln!("Usage: git-ai split [--yes] [--dry-run]");
+    eprintln!();
+    eprintln!("Groups staged files by kind (build, source, tests, docs, CI) and component");
+    eprintln!("directory, lets you adjust the proposal, then commits each group.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --yes, -y    Commit the proposed split without prompting");
+    eprintln!("  --dry-run    Print the proposed split and exit");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn proposes_groups_by_kind_and_component() {
+        let groups = propose_groups(&paths(&[
+            "README.md",
+            "src/mdm/vscode.rs",
+            "Cargo.toml",
+            "src/mdm/zed.rs",
+            "src/commands/split.rs",
+            "tests/integration/split.rs",
+            ".github/workflows/ci.yml",
+        ]));
+        let summary: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|g| (g.message.as_str(), g.files.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("build: update Cargo.toml", 1),
+                ("chore(commands): update split.rs", 1),
+                ("chore(mdm): update 2 files", 2),
+                ("test(integration): update split.rs", 1),
+                ("docs: update README.md", 1),
+                ("ci: update ci.yml", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn classifies_colocated_tests_and_docs() {
+        assert_eq!(classify("web/app.test.ts"), "test");
+        assert_eq!(classify("pkg/server/handler_test.go"), "test");
+        assert_eq!(classify("docs/guide/setup.txt"), "docs");
+        assert_eq!(classify("main.rs"), "chore");
+        assert_eq!(component("main.rs"), None);
+        assert_eq!(component("web/app.ts").as_deref(), Some("web"));
+    }
+
+    #[test]
+    fn parses_adjustment_commands() {
+        assert_eq!(parse_command("\n"), Ok(SplitCommand::Accept));
+        assert_eq!(parse_command("m 1 3"), Ok(SplitCommand::Merge(1, 3)));
+        assert_eq!(
+            parse_command("mv src/a b.rs 2"),
+            Ok(SplitCommand::Move("src/a b.rs".to_string(), 2))
+        );
+        assert_eq!(
+            parse_command("r 2 feat(cli): add split"),
+            Ok(SplitCommand::Reword(2, "feat(cli): add split".to_string()))
+        );
+        assert!(parse_command("m 1").is_err());
+        assert!(parse_command("r 0 msg").is_err());
+        assert!(parse_command("x").is_err());
+    }
+
+    #[test]
+    fn applies_merge_move_and_reword() {
+        let mut groups = propose_groups(&paths(&["Cargo.toml", "src/a/x.rs", "README.md"]));
+        assert_eq!(groups.len(), 3);
+
+        apply_command(&mut groups, SplitCommand::Merge(2, 1)).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].files, paths(&["src/a/x.rs", "Cargo.toml"]));
+
+        apply_command(&mut groups, SplitCommand::Move("README.md".to_string(), 1)).unwrap();
+        assert_eq!(groups.len(), 1);
+
+        apply_command(&mut groups, SplitCommand::Reword(1, "feat: x".to_string())).unwrap();
+        assert_eq!(groups[0].message, "feat: x");
+        assert!(apply_command(&mut groups, SplitCommand::Merge(1, 2)).is_err());
+    }
+}
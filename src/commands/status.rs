@@ -9,6 +9,7 @@ use crate::git::find_repository;
 use crate::git::repo_storage::InitialAttributions;
 use crate::git::repository::{InternalGitProfile, Repository, exec_git_with_profile};
 use crate::git::status::MAX_PATHSPEC_ARGS;
+use crate::output;
 use serde::Serialize;
 use std::collections::{BTreeMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -203,7 +204,7 @@ fn run_status(json: bool, diff_only: bool) -> Result<(), GitAiError> {
         );
 
         if cp.is_human {
-            println!("\x1b[90m{}\x1b[0m", line);
+            println!("{}", output::paint(output::GRAY, &line));
         } else {
             println!("{}", line);
         }
@@ -0,0 +1,171 @@
+use crate::auth::format_unix_timestamp;
+use crate::config;
+use crate::notes::backends::{SyncTarget, all_targets};
+use crate::notes::db::{BackendQueueStatus, NotesDatabase};
+use std::fmt::Write as _;
+
+pub fn handle_sync(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("status") => handle_sync_status(&args[1..]),
+        Some("--help") | Some("-h") | Some("help") | None => {
+            print_help();
+            std::process::exit(0);
+        }
+        Some(other) => {
+            eprintln!("Unknown git-ai sync subcommand: {}", other);
+            print_help();
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_sync_status(args: &[String]) {
+    if args
+        .iter()
+        .any(|arg| arg == "--help" || arg == "-h" || arg == "help")
+    {
+        print_help();
+        std::process::exit(0);
+    }
+    if !args.is_empty() {
+        eprintln!("Error: unknown sync status argument(s): {}", args.join(" "));
+        print_help();
+        std::process::exit(1);
+    }
+
+    // Use Config::fresh() to support runtime config updates (daemon mode).
+    let config = config::Config::fresh();
+    let targets = all_targets(&config);
+    let db = NotesDatabase::global().map_err(|e| e.to_string());
+    let statuses: Vec<Result<BackendQueueStatus, String>> = targets
+        .iter()
+        .map(|target| {
+            let db = db.as_ref().map_err(String::clone)?;
+            let db = db
+                .lock()
+                .map_err(|_| "notes DB lock poisoned".to_string())?;
+            db.backend_queue_status(&target.queue)
+                .map_err(|e| e.to_string())
+        })
+        .collect();
+    let rows: Vec<(&SyncTarget, bool, Result<&BackendQueueStatus, &str>)> = targets
+        .iter()
+        .zip(&statuses)
+        .map(|(target, status)| {
+            (
+                target,
+                target.client().is_some(),
+                status.as_ref().map_err(String::as_str),
+            )
+        })
+        .collect();
+
+    print!("{}", render_sync_status(&rows));
+}
+
+fn render_sync_status(rows: &[(&SyncTarget, bool, Result<&BackendQueueStatus, &str>)]) -> String {
+    let mut out = String::new();
+    if rows.is_empty() {
+        writeln!(
+            out,
+            "Notes sync is off (notes_backend.kind is not \"http\")."
+        )
+        .unwrap();
+        return out;
+    }
+
+    for (index, (target, authenticated, status)) in rows.iter().enumerate() {
+        if index > 0 {
+            writeln!(out).unwrap();
+        }
+        writeln!(out, "Backend: {}", target.display_name()).unwrap();
+        writeln!(
+            out,
+            "  URL: {}",
+            target.backend_url.as_deref().unwrap_or("<not configured>")
+        )
+        .unwrap();
+        if target.is_default() {
+            writeln!(out, "  Remotes: <all others>").unwrap();
+        } else {
+            writeln!(out, "  Remotes: {}", target.remotes.join(", ")).unwrap();
+        }
+        writeln!(
+            out,
+            "  Credentials: {}",
+            if *authenticated { "present" } else { "missing" }
+        )
+        .unwrap();
+        match status {
+            Ok(status) => {
+                writeln!(
+                    out,
+                    "  Queue: {} pending ({} retrying), {} stopped, {} synced",
+                    status.pending, status.retrying, status.stopped, status.synced
+                )
+                .unwrap();
+                writeln!(
+                    out,
+                    "  Last sync: {}",
+                    status
+                        .last_synced_at
+                        .map(format_unix_timestamp)
+                        .unwrap_or_else(|| "never".to_string())
+                )
+                .unwrap();
+                if let Some(error) = &status.latest_error {
+                    writeln!(out, "  Latest error: {}", error).unwrap();
+                }
+            }
+            Err(err) => {
+                writeln!(out, "  Queue: unavailable ({})", err).unwrap();
+            }
+        }
+    }
+    out
+}
+
+fn print_help() {
+    eprintln!("git-ai sync - Inspect notes sync backends");
+    eprintln!();
+    eprintln!("Usage:");
+    eprintln!("  git-ai sync status    Show queue health for each configured backend");
+    eprintln!("  git-ai sync --help");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SyncBackendConfig;
+
+    #[test]
+    fn render_sync_status_reports_each_backend_separately() {
+        let default = SyncTarget::default_backend(Some("https://notes.example"));
+        let acme = SyncTarget::from_config(&SyncBackendConfig {
+            name: "acme".to_string(),
+            remotes: vec!["https://github.com/acme/*".to_string()],
+            backend_url: "https://notes.acme.example".to_string(),
+            api_key_env: None,
+        });
+        let healthy = BackendQueueStatus {
+            synced: 3,
+            last_synced_at: Some(0),
+            ..Default::default()
+        };
+        let failing = BackendQueueStatus {
+            pending: 2,
+            retrying: 2,
+            latest_error: Some("HTTP 401".to_string()),
+            ..Default::default()
+        };
+        let out =
+            render_sync_status(&[(&default, true, Ok(&healthy)), (&acme, false, Ok(&failing))]);
+
+        assert!(out.contains("Backend: default\n  URL: https://notes.example\n"));
+        assert!(out.contains("Last sync: 1970-01-01T00:00:00+00:00"));
+        assert!(out.contains("Backend: acme\n  URL: https://notes.acme.example\n"));
+        assert!(out.contains("  Remotes: https://github.com/acme/*\n  Credentials: missing\n"));
+        assert!(out.contains("  Queue: 2 pending (2 retrying), 0 stopped, 0 synced\n"));
+        assert!(out.contains("  Latest error: HTTP 401\n"));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::api::client::ApiContext;
 use crate::config::{self, UpdateChannel};
 use crate::observability::log_message;
+use crate::output::{self, Status};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
@@ -582,7 +583,11 @@ fn run_install_script(script_content: &str, tag: &str, silent: bool) -> Result<(
             Ok(_) => {
                 if !silent {
                     println!(
-                        "\x1b[1;33mNote: The installation is running in the background on Windows.\x1b[0m"
+                        "{}",
+                        output::paint(
+                            output::BOLD_YELLOW,
+                            "Note: The installation is running in the background on Windows."
+                        )
                     );
                     println!(
                         "This allows the current git-ai process to exit and release file locks."
@@ -729,7 +734,10 @@ fn run_impl_with_url(
             println!("You are already on the latest version!");
             println!();
             println!("To reinstall anyway, run:");
-            println!("  \x1b[1;36mgit-ai upgrade --force\x1b[0m");
+            println!(
+                "  {}",
+                output::paint(output::BOLD_CYAN, "git-ai upgrade --force")
+            );
             return action;
         }
         UpgradeAction::RunningNewerVersion => {
@@ -737,17 +745,26 @@ fn run_impl_with_url(
             println!("(This usually means you're running a development build)");
             println!();
             println!("To reinstall the selected release anyway, run:");
-            println!("  \x1b[1;36mgit-ai upgrade --force\x1b[0m");
+            println!(
+                "  {}",
+                output::paint(output::BOLD_CYAN, "git-ai upgrade --force")
+            );
             return action;
         }
         UpgradeAction::ForceReinstall => {
             println!(
-                "\x1b[1;33mForce mode enabled - reinstalling {}\x1b[0m",
-                release.tag
+                "{}",
+                output::paint(
+                    output::BOLD_YELLOW,
+                    &format!("Force mode enabled - reinstalling {}", release.tag)
+                )
             );
         }
         UpgradeAction::UpgradeAvailable => {
-            println!("\x1b[1;33mA new version is available!\x1b[0m");
+            println!(
+                "{}",
+                output::paint(output::BOLD_YELLOW, "A new version is available!")
+            );
         }
     }
     println!();
@@ -762,7 +779,7 @@ fn run_impl_with_url(
     let checksums =
         match fetch_and_verify_checksums(api_base_url, channel.as_str(), &release.checksum) {
             Ok(checksums) => {
-                println!("\x1b[1;32m✓\x1b[0m SHA256SUMS verified");
+                println!("{} SHA256SUMS verified", Status::Ok.mark());
                 checksums
             }
             Err(err) => {
@@ -776,9 +793,9 @@ fn run_impl_with_url(
         match fetch_and_verify_install_script(api_base_url, channel.as_str(), &checksums) {
             Ok(content) => {
                 #[cfg(windows)]
-                println!("\x1b[1;32m✓\x1b[0m install.ps1 verified");
+                println!("{} install.ps1 verified", Status::Ok.mark());
                 #[cfg(not(windows))]
-                println!("\x1b[1;32m✓\x1b[0m install.sh verified");
+                println!("{} install.sh verified", Status::Ok.mark());
                 content
             }
             Err(err) => {
@@ -796,7 +813,11 @@ fn run_impl_with_url(
             // On Windows, we spawn the installer in the background and can't verify success
             #[cfg(not(windows))]
             {
-                println!("\x1b[1;32m✓\x1b[0m Successfully installed {}!", release.tag);
+                println!(
+                    "{} Successfully installed {}!",
+                    Status::Ok.mark(),
+                    release.tag
+                );
             }
 
             log_message(
@@ -840,13 +861,21 @@ fn print_cached_notice(cache: &UpdateCache) {
     let available_version = cache.available_semver.as_deref().unwrap_or("");
 
     eprintln!();
-    eprintln!(
-        "\x1b[1;33mA new version of git-ai is available: \x1b[1;32mv{}\x1b[0m → \x1b[1;32mv{}\x1b[0m",
-        current_version, available_version
-    );
-    eprintln!(
-        "\x1b[1;33mRun \x1b[1;36mgit-ai upgrade\x1b[0m \x1b[1;33mto upgrade to the latest version.\x1b[0m"
-    );
+    if output::is_plain() {
+        eprintln!(
+            "A new version of git-ai is available: v{} to v{}",
+            current_version, available_version
+        );
+        eprintln!("Run git-ai upgrade to upgrade to the latest version.");
+    } else {
+        eprintln!(
+            "\x1b[1;33mA new version of git-ai is available: \x1b[1;32mv{}\x1b[0m → \x1b[1;32mv{}\x1b[0m",
+            current_version, available_version
+        );
+        eprintln!(
+            "\x1b[1;33mRun \x1b[1;36mgit-ai upgrade\x1b[0m \x1b[1;33mto upgrade to the latest version.\x1b[0m"
+        );
+    }
     eprintln!();
 }
 
@@ -867,7 +896,9 @@ pub fn maybe_schedule_background_update_check() {
         print_cached_notice(cache);
     }
 
-    if !should_check_for_updates(channel, cache.as_ref()) {
+    if !should_check_for_updates(channel, cache.as_ref())
+        || !crate::capabilities::available(crate::capabilities::Capability::Network)
+    {
         return;
     }
 
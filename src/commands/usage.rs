@@ -3,6 +3,7 @@
 use crate::metrics::local_stats::{
     BucketGranularity, LocalActivityStats, RepoActivitySummary, compute_all,
 };
+use crate::output::Palette;
 use chrono::{Datelike, Duration, NaiveDate};
 use serde::Serialize;
 use std::collections::HashMap;
@@ -111,10 +112,15 @@ fn print_help() {
 }
 
 fn print_terminal(stats: &LocalActivityStats, repos: &[RepoActivitySummary]) {
-    const GRAY: &str = "\x1b[90m";
-    const BOLD: &str = "\x1b[1m";
-    const RESET: &str = "\x1b[0m";
-    const ORANGE: &str = "\x1b[38;5;208m";
+    let Palette {
+        gray,
+        bold,
+        reset,
+        orange,
+        bar_filled,
+        bar_empty,
+        ..
+    } = Palette::current();
 
     // Capitalize the first letter for the header (period_label is lower-case
     // elsewhere where it reads mid-sentence, e.g. "Activity — last 30 days").
@@ -126,7 +132,7 @@ fn print_terminal(stats: &LocalActivityStats, repos: &[RepoActivitySummary]) {
         }
     };
     println!();
-    println!("  {BOLD}{header}{RESET}");
+    println!("  {bold}{header}{reset}");
 
     // --- Top bar: AI vs Human split ---
     println!();
@@ -138,12 +144,12 @@ fn print_terminal(stats: &LocalActivityStats, repos: &[RepoActivitySummary]) {
         let human_pct = 100 - ai_pct;
         let filled = (ai_pct * 40 / 100).min(40) as usize;
         let bar = format!(
-            "{ORANGE}{}{GRAY}{}{RESET}",
-            "█".repeat(filled),
-            "░".repeat(40 - filled),
+            "{orange}{}{gray}{}{reset}",
+            bar_filled.repeat(filled),
+            bar_empty.repeat(40 - filled),
         );
         println!(
-            "  {}  {BOLD}AI{RESET} {:>3}% · {BOLD}Human{RESET} {:>3}%",
+            "  {}  {bold}AI{reset} {:>3}% · {bold}Human{reset} {:>3}%",
             bar, ai_pct, human_pct,
         );
     }
@@ -152,7 +158,7 @@ fn print_terminal(stats: &LocalActivityStats, repos: &[RepoActivitySummary]) {
     // Only shown when there are multiple repos — a single-row table adds nothing.
     if repos.len() > 1 {
         println!();
-        println!("  {BOLD}Repositories{RESET}");
+        println!("  {bold}Repositories{reset}");
 
         // Pre-compute display strings for column alignment.
         let names: Vec<&str> = repos
@@ -183,12 +189,12 @@ fn print_terminal(stats: &LocalActivityStats, repos: &[RepoActivitySummary]) {
                 "sessions"
             };
             let cost_str = if r.estimated_cost_usd > 0.0 {
-                format!("  {GRAY}{}{RESET}", format_cost(r.estimated_cost_usd))
+                format!("  {gray}{}{reset}", format_cost(r.estimated_cost_usd))
             } else {
                 String::new()
             };
             println!(
-                "    {GRAY}{}  {} lines  {} {}{}{RESET}",
+                "    {gray}{}  {} lines  {} {}{}{reset}",
                 name_col, lines_col, sessions_col, session_label, cost_str,
             );
         }
@@ -257,14 +263,17 @@ fn print_terminal(stats: &LocalActivityStats, repos: &[RepoActivitySummary]) {
 /// For windows wider than the terminal, consecutive days are bucketed (max) into
 /// columns so the strip always fits and fills the width.
 fn print_calendar(stats: &LocalActivityStats) {
-    const GRAY: &str = "\x1b[90m";
-    const BOLD: &str = "\x1b[1m";
-    const RESET: &str = "\x1b[0m";
-    const ORANGE: &str = "\x1b[38;5;208m";
-    // Distinct from orange so lines and spend don't blend together.
-    const BLUE: &str = "\x1b[38;5;33m";
+    // Blue is distinct from orange so lines and spend don't blend together.
     // Quartile levels 1–4 (level 0 = empty `·`, rendered gray).
-    const LEVEL_CHARS: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+    let Palette {
+        gray,
+        bold,
+        reset,
+        orange,
+        blue,
+        levels,
+        ..
+    } = Palette::current();
     // Usable strip width after the 2-space indent (assumes ~80-col terminal).
     const MAX_W: usize = 74;
     // Left gutter for the "lines"/"spend" row labels (label + 2 spaces).
@@ -369,9 +378,9 @@ fn print_calendar(stats: &LocalActivityStats) {
                     4
                 };
                 if lvl == 0 {
-                    format!("{GRAY}{}{RESET}", LEVEL_CHARS[0])
+                    format!("{gray}{}{reset}", levels[0])
                 } else {
-                    format!("{color}{}{RESET}", LEVEL_CHARS[lvl])
+                    format!("{color}{}{reset}", levels[lvl])
                 }
             })
             .collect::<Vec<_>>()
@@ -383,12 +392,12 @@ fn print_calendar(stats: &LocalActivityStats) {
     println!();
     if has_spend {
         println!(
-            "  {BOLD}Activity{RESET} {GRAY}— {}{RESET}",
+            "  {bold}Activity{reset} {gray}— {}{reset}",
             stats.period_label
         );
     } else {
         println!(
-            "  {BOLD}Activity{RESET} {GRAY}— generated lines/day · {}{RESET}",
+            "  {bold}Activity{reset} {gray}— generated lines/day · {}{reset}",
             stats.period_label
         );
     }
@@ -414,21 +423,24 @@ fn print_calendar(stats: &LocalActivityStats) {
     }
     let tick_line: String = tick_row.iter().collect();
     let gutter = " ".repeat(label_w);
-    println!("  {gutter}{GRAY}{}{RESET}", tick_line.trim_end());
+    println!("  {gutter}{gray}{}{reset}", tick_line.trim_end());
 
     if has_spend {
-        println!("  {GRAY}lines  {RESET}{}", render_row(&col_lines, ORANGE));
-        println!("  {GRAY}spend  {RESET}{}", render_row(&col_spend, BLUE));
+        println!("  {gray}lines  {reset}{}", render_row(&col_lines, orange));
+        println!("  {gray}spend  {reset}{}", render_row(&col_spend, blue));
     } else {
-        println!("  {}", render_row(&col_lines, ORANGE));
+        println!("  {}", render_row(&col_lines, orange));
     }
 
     println!();
     if has_spend {
         // Two colors share one intensity scale — keep the gradient neutral.
-        println!("  {GRAY}Less ░ ▒ ▓ █ More{RESET}");
+        println!("  {gray}Less {} More{reset}", levels[1..].join(" "));
     } else {
-        println!("  {GRAY}Less{RESET} {ORANGE}░ ▒ ▓ █{RESET} {GRAY}More{RESET}");
+        println!(
+            "  {gray}Less{reset} {orange}{}{reset} {gray}More{reset}",
+            levels[1..].join(" ")
+        );
     }
 }
 
@@ -0,0 +1,397 @@
+//! Opt-in Conventional Commits validation for commits made through the git shim.
+//!
+//! Only the header line is checked: `type(scope)!: description`. Messages that
+//! git generates itself (merges, reverts, fixup/squash) are always accepted.
+
+use crate::config::CommitLintConfig;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fmt;
+use std::path::Path;
+
+/// Types from the Conventional Commits / Angular convention, used when
+/// `commit_lint.types` is not configured.
+pub const DEFAULT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+pub const DEFAULT_MAX_SUBJECT_LENGTH: usize = 72;
+
+static HEADER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?P<type>[A-Za-z]+)(?:\((?P<scope>[^()]*)\))?(?P<breaking>!)?:(?P<space>\s*)(?P<desc>.*)$")
+        .unwrap()
+});
+
+const GIT_GENERATED_PREFIXES: &[&str] = &["Merge ", "Revert \"", "fixup! ", "squash! ", "amend! "];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintIssue {
+    EmptyMessage,
+    MissingType,
+    UnknownType(String),
+    MissingScope,
+    DisallowedScope(String),
+    MissingSpaceAfterColon,
+    EmptyDescription,
+    SubjectTooLong { len: usize, max: usize },
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintIssue::EmptyMessage => write!(f, "commit message is empty"),
+            LintIssue::MissingType => {
+                write!(f, "header must look like 'type(scope): description'")
+            }
+            LintIssue::UnknownType(ty) => write!(f, "unknown commit type '{}'", ty),
+            LintIssue::MissingScope => write!(f, "empty scope '()'"),
+            LintIssue::DisallowedScope(scope) => write!(f, "scope '{}' is not allowed", scope),
+            LintIssue::MissingSpaceAfterColon => write!(f, "expected a single space after ':'"),
+            LintIssue::EmptyDescription => write!(f, "description after ':' is empty"),
+            LintIssue::SubjectTooLong { len, max } => {
+                write!(f, "header is {} characters (max {})", len, max)
+            }
+        }
+    }
+}
+
+/// Where a `git commit` invocation takes its message from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageSource {
+    /// `-m`/`--message` values (joined as paragraphs) or the contents of `-F`
+    Explicit(String),
+    /// Composed in an editor, reused from another commit, or read from stdin;
+    /// not known up front
+    Deferred,
+}
+
+/// Work out the commit message from `git commit` arguments. A relative `-F`
+/// path is read from `base_dir`, the directory git runs in after any `-C`.
+pub fn commit_message_source(command_args: &[String], base_dir: &Path) -> MessageSource {
+    let mut paragraphs = Vec::new();
+    let mut file: Option<String> = None;
+
+    let mut i = 0;
+    while i < command_args.len() {
+        let arg = command_args[i].as_str();
+        let mut next_value = || {
+            i += 1;
+            command_args.get(i).cloned()
+        };
+        if arg == "--" {
+            break;
+        } else if arg == "--message" {
+            paragraphs.extend(next_value());
+        } else if let Some(value) = arg.strip_prefix("--message=") {
+            paragraphs.push(value.to_string());
+        } else if arg == "--file" {
+            file = next_value();
+        } else if let Some(value) = arg.strip_prefix("--file=") {
+            file = Some(value.to_string());
+        } else if arg.starts_with('-') && !arg.starts_with("--") {
+            // Short option cluster such as `-am <msg>` or `-mmsg`
+            for (idx, flag) in arg.char_indices().skip(1) {
+                let rest = &arg[idx + flag.len_utf8()..];
+                let value = |next: &mut dyn FnMut() -> Option<String>| {
+                    if rest.is_empty() {
+                        next()
+                    } else {
+                        Some(rest.to_string())
+                    }
+                };
+                match flag {
+                    'm' => {
+                        paragraphs.extend(value(&mut next_value));
+                        break;
+                    }
+                    'F' => {
+                        file = value(&mut next_value);
+                        break;
+                    }
+                    'c' | 'C' | 't' => {
+                        let _ = value(&mut next_value);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        i += 1;
+    }
+
+    if !paragraphs.is_empty() {
+        return MessageSource::Explicit(paragraphs.join("\n\n"));
+    }
+    match file.filter(|path| path != "-") {
+        Some(path) => std::fs::read_to_string(base_dir.join(path))
+            .map(MessageSource::Explicit)
+            .unwrap_or(MessageSource::Deferred),
+        None => MessageSource::Deferred,
+    }
+}
+
+/// First non-comment, non-blank line of a commit message.
+fn header_line(message: &str) -> Option<&str> {
+    message
+        .lines()
+        .map(str::trim_end)
+        .find(|line| !line.trim().is_empty() && !line.starts_with('#'))
+}
+
+fn allowed_types(config: &CommitLintConfig) -> Vec<String> {
+    match &config.types {
+        Some(types) if !types.is_empty() => types.clone(),
+        _ => DEFAULT_TYPES.iter().map(|t| t.to_string()).collect(),
+    }
+}
+
+/// Check a commit message against the configured rules.
+pub fn lint_message(message: &str, config: &CommitLintConfig) -> Vec<LintIssue> {
+    let Some(header) = header_line(message) else {
+        return vec![LintIssue::EmptyMessage];
+    };
+    if GIT_GENERATED_PREFIXES
+        .iter()
+        .any(|prefix| header.starts_with(prefix))
+    {
+        return Vec::new();
+    }
+
+    let mut issues = Vec::new();
+    let max = config
+        .max_subject_length
+        .unwrap_or(DEFAULT_MAX_SUBJECT_LENGTH);
+    let len = header.chars().count();
+    if len > max {
+        issues.push(LintIssue::SubjectTooLong { len, max });
+    }
+
+    let Some(caps) = HEADER_RE.captures(header) else {
+        issues.insert(0, LintIssue::MissingType);
+        return issues;
+    };
+
+    let ty = &caps["type"];
+    if !allowed_types(config).iter().any(|allowed| allowed == ty) {
+        issues.push(LintIssue::UnknownType(ty.to_string()));
+    }
+    if let Some(scope) = caps.name("scope").map(|m| m.as_str()) {
+        if scope.trim().is_empty() {
+            issues.push(LintIssue::MissingScope);
+        } else if let Some(scopes) = config.scopes.as_ref().filter(|s| !s.is_empty())
+            && !scopes.iter().any(|allowed| allowed == scope)
+        {
+            issues.push(LintIssue::DisallowedScope(scope.to_string()));
+        }
+    }
+    if caps["desc"].trim().is_empty() {
+        issues.push(LintIssue::EmptyDescription);
+    } else if &caps["space"] != " " {
+        issues.push(LintIssue::MissingSpaceAfterColon);
+    }
+
+    issues
+}
+
+/// Propose a corrected header (keeping the body) for the mechanical problems:
+/// type casing, colon spacing, an empty scope, and a missing type, which is
+/// guessed from the leading verb. Returns `None` when no fix applies or the
+/// fix would still fail the rules (e.g. a header that is simply too long).
+pub fn suggest_fix(message: &str, config: &CommitLintConfig) -> Option<String> {
+    let header = header_line(message)?;
+    let types = allowed_types(config);
+
+    let fixed_header = match HEADER_RE.captures(header) {
+        Some(caps) => {
+            let ty = caps["type"].to_lowercase();
+            let scope = caps
+                .name("scope")
+                .map(|m| m.as_str().trim())
+                .filter(|scope| !scope.is_empty())
+                .map(|scope| format!("({})", scope))
+                .unwrap_or_default();
+            let breaking = caps.name("breaking").map_or("", |m| m.as_str());
+            format!("{}{}{}: {}", ty, scope, breaking, caps["desc"].trim())
+        }
+        None => {
+            let ty = guess_type(header, &types)?;
+            format!("{}: {}", ty, tidy_description(header))
+        }
+    };
+
+    if fixed_header == header {
+        return None;
+    }
+    let fixed = message.replacen(header, &fixed_header, 1);
+    lint_message(&fixed, config).is_empty().then_some(fixed)
+}
+
+/// Map a free-form header's leading verb to one of the allowed types.
+fn guess_type<'a>(header: &str, types: &'a [String]) -> Option<&'a str> {
+    let first = header.split_whitespace().next()?.to_lowercase();
+    let candidate = match first.trim_end_matches([':', ',']) {
+        "add" | "added" | "adds" | "implement" | "implemented" | "introduce" | "support" => "feat",
+        "fix" | "fixed" | "fixes" | "resolve" | "resolved" | "correct" | "corrected" => "fix",
+        "doc" | "docs" | "document" | "documented" => "docs",
+        "refactor" | "refactored" | "restructure" | "simplify" | "rename" | "move" => "refactor",
+        "test" | "tests" | "tested" => "test",
+        "revert" | "reverted" => "revert",
+        "bump" | "update" | "updated" | "upgrade" | "remove" | "removed" | "delete" => "chore",
+        _ => return None,
+    };
+    types
+        .iter()
+        .find(|t| t.as_str() == candidate)
+        .map(String::as_str)
+}
+
+/// Lowercase the first letter and drop a trailing period, per convention.
+fn tidy_description(header: &str) -> String {
+    let header = header.trim().trim_end_matches('.');
+    let mut chars = header.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CommitLintConfig {
+        CommitLintConfig::default()
+    }
+
+    #[test]
+    fn accepts_valid_headers_and_git_generated_messages() {
+        for message in [
+            "feat: add report command",
+            "fix(cli)!: reject empty salt\n\nBody text",
+            "# comment\n\nchore: bump deps",
+            "Merge branch 'main' into topic",
+            "Revert \"feat: add thing\"",
+            "fixup! feat: add report command",
+        ] {
+            assert_eq!(lint_message(message, &config()), Vec::new(), "{message}");
+        }
+    }
+
+    #[test]
+    fn reports_each_violation() {
+        let cfg = CommitLintConfig {
+            scopes: Some(vec!["cli".to_string()]),
+            max_subject_length: Some(20),
+            ..config()
+        };
+        assert_eq!(lint_message("", &cfg), vec![LintIssue::EmptyMessage]);
+        assert_eq!(
+            lint_message("Update stuff", &cfg),
+            vec![LintIssue::MissingType]
+        );
+        assert_eq!(
+            lint_message("feature(cli): x", &cfg),
+            vec![LintIssue::UnknownType("feature".to_string())]
+        );
+        assert_eq!(
+            lint_message("fix(daemon): x", &cfg),
+            vec![LintIssue::DisallowedScope("daemon".to_string())]
+        );
+        assert_eq!(
+            lint_message("fix:x", &cfg),
+            vec![LintIssue::MissingSpaceAfterColon]
+        );
+        assert_eq!(
+            lint_message("fix: ", &cfg),
+            vec![LintIssue::EmptyDescription]
+        );
+        assert_eq!(
+            lint_message("fix: a very long description", &cfg),
+            vec![LintIssue::SubjectTooLong { len: 28, max: 20 }]
+        );
+    }
+
+    #[test]
+    fn configured_types_replace_the_defaults() {
+        let cfg = CommitLintConfig {
+            types: Some(vec!["feature".to_string()]),
+            ..config()
+        };
+        assert!(lint_message("feature: x", &cfg).is_empty());
+        assert_eq!(
+            lint_message("feat: x", &cfg),
+            vec![LintIssue::UnknownType("feat".to_string())]
+        );
+    }
+
+    #[test]
+    fn suggests_mechanical_fixes() {
+        assert_eq!(
+            suggest_fix("Fix:handle empty notes", &config()).as_deref(),
+            Some("fix: handle empty notes")
+        );
+        assert_eq!(
+            suggest_fix("Added retry to uploads.\n\nDetails", &config()).as_deref(),
+            Some("feat: added retry to uploads\n\nDetails")
+        );
+        assert_eq!(
+            suggest_fix("docs(): typo", &config()).as_deref(),
+            Some("docs: typo")
+        );
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn commit_message_source_reads_message_flags() {
+        assert_eq!(
+            commit_message_source(&args(&["-am", "feat: x", "-m", "body"]), Path::new(".")),
+            MessageSource::Explicit("feat: x\n\nbody".to_string())
+        );
+        assert_eq!(
+            commit_message_source(&args(&["-mfix: y", "--message=more"]), Path::new(".")),
+            MessageSource::Explicit("fix: y\n\nmore".to_string())
+        );
+        assert_eq!(
+            commit_message_source(&args(&["-C", "HEAD", "--amend"]), Path::new(".")),
+            MessageSource::Deferred
+        );
+        assert_eq!(
+            commit_message_source(&args(&["-F", "-"]), Path::new(".")),
+            MessageSource::Deferred
+        );
+        assert_eq!(
+            commit_message_source(&args(&["--", "-m"]), Path::new(".")),
+            MessageSource::Deferred
+        );
+    }
+
+    #[test]
+    fn commit_message_source_reads_message_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("MSG");
+        std::fs::write(&path, "docs: readme\n").unwrap();
+        assert_eq!(
+            commit_message_source(&args(&["-F", path.to_str().unwrap()]), Path::new(".")),
+            MessageSource::Explicit("docs: readme\n".to_string())
+        );
+        // Relative paths are read from the directory git runs in
+        assert_eq!(
+            commit_message_source(&args(&["--file=MSG"]), dir.path()),
+            MessageSource::Explicit("docs: readme\n".to_string())
+        );
+    }
+
+    #[test]
+    fn no_suggestion_when_fix_is_not_mechanical() {
+        assert_eq!(suggest_fix("feat: fine", &config()), None);
+        assert_eq!(suggest_fix("Whatever happened here", &config()), None);
+        let short = CommitLintConfig {
+            max_subject_length: Some(10),
+            ..config()
+        };
+        assert_eq!(suggest_fix("Fix: a long header", &short), None);
+    }
+}
@@ -9,6 +9,8 @@ use std::time::{Duration, Instant};
 use glob::Pattern;
 use serde::{Deserialize, Serialize, Serializer};
 
+use crate::app_context::AppContext;
+use crate::authorship::imara_diff_utils::DiffAlgorithm;
 use crate::feature_flags::FeatureFlags;
 use crate::git::repository::Repository;
 use crate::mdm::utils::home_dir;
@@ -57,6 +59,191 @@ pub struct NotesBackendConfig {
     pub backend_url: Option<String>,
 }
 
+/// An additional HTTP notes backend, used instead of `notes_backend.backend_url`
+/// for repositories whose remote matches one of `remotes`. Each backend keeps
+/// its own upload queue and credentials, so one org's server being down or
+/// rejecting a key never holds up another's.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyncBackendConfig {
+    /// Short label shown in `git-ai sync status` and used as the queue key
+    pub name: String,
+    /// Remote URL globs, matched like `allow_repositories`
+    pub remotes: Vec<String>,
+    pub backend_url: String,
+    /// Environment variable holding this backend's API key. Without one,
+    /// the backend is skipped rather than sent the default login or key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+}
+
+impl SyncBackendConfig {
+    pub fn api_key(&self) -> Option<String> {
+        self.api_key_env
+            .as_deref()
+            .and_then(|var| env::var(var).ok())
+            .filter(|key| !key.trim().is_empty())
+    }
+}
+
+/// How strictly Conventional Commits are enforced for commits made through the shim.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitLintMode {
+    /// Default: no validation
+    #[default]
+    Off,
+    /// Print violations and a suggested fix, but let the commit through
+    Warn,
+    /// Reject commits whose message violates the rules; an editor-composed
+    /// message is checked when the editor closes, before git commits. A
+    /// reused or piped message is only reported.
+    Strict,
+}
+
+impl CommitLintMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommitLintMode::Off => "off",
+            CommitLintMode::Warn => "warn",
+            CommitLintMode::Strict => "strict",
+        }
+    }
+}
+
+/// What to scrub from telemetry, logs and API payloads before they leave the
+/// machine. Everything is off by default.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct RedactionConfig {
+    /// Replace repository URLs with salted hashes
+    #[serde(default)]
+    pub hash_repo_urls: bool,
+    /// Org-wide salt, so hashes match across machines but can't be reversed
+    /// by hashing well-known repository names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+    /// Remove absolute local paths and this machine's hostname
+    #[serde(default)]
+    pub strip_local_paths: bool,
+}
+
+impl RedactionConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.hash_repo_urls || self.strip_local_paths
+    }
+}
+
+/// Disk space git-ai may use, for CI runners and other small disks. Unset
+/// size budgets are unlimited.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct DiskBudgetConfig {
+    /// Cap on the MR/issue metadata cache; least recently used entries are
+    /// evicted past it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_max_bytes: Option<u64>,
+    /// Cap on the notes database; the least recently synced cached notes are
+    /// evicted past it (unsynced notes are never dropped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_db_max_bytes: Option<u64>,
+    /// Cap on a CI clone; a run whose clone exceeds it fails and is removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci_clone_max_bytes: Option<u64>,
+    /// Free space required before a CI clone starts
+    /// (default: [`DEFAULT_MIN_FREE_BYTES`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_free_bytes: Option<u64>,
+}
+
+/// Free space CI clones require unless `disk_budgets.min_free_bytes` is set
+pub const DEFAULT_MIN_FREE_BYTES: u64 = 256 * 1024 * 1024;
+
+impl DiskBudgetConfig {
+    pub fn min_free_bytes(&self) -> u64 {
+        self.min_free_bytes.unwrap_or(DEFAULT_MIN_FREE_BYTES)
+    }
+}
+
+/// Which git clients `git-ai clients`, install-hooks and uninstall-hooks may
+/// configure, by installer id (e.g. `fork`, `vscode`), for fleets that only
+/// sanction some of them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ClientsConfig {
+    /// Only these clients; every client when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<String>>,
+    /// Never these clients, even when included
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<String>>,
+    /// HTTPS URL of the signed policy `git-ai mdm sync` enforces
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_url: Option<String>,
+    /// PEM Ed25519 public key the policy must be signed with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_public_key: Option<String>,
+    /// Also send audit log entries to syslog or the Windows event log
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_syslog: Option<bool>,
+}
+
+impl ClientsConfig {
+    pub fn allows(&self, client_id: &str) -> bool {
+        let listed = |ids: &Option<Vec<String>>| {
+            ids.as_ref()
+                .is_some_and(|ids| ids.iter().any(|id| id == client_id))
+        };
+        (self.include.is_none() || listed(&self.include)) && !listed(&self.exclude)
+    }
+}
+
+/// Opt-in Conventional Commits validation for `git commit`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct CommitLintConfig {
+    #[serde(default)]
+    pub mode: CommitLintMode,
+    /// Allowed types; the standard Conventional Commits set when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub types: Option<Vec<String>>,
+    /// Allowed scopes; any scope is accepted when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_subject_length: Option<usize>,
+}
+
+/// Issue tracker API used to resolve keys found in commit messages and branch names.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueTrackerKind {
+    Jira,
+    Linear,
+}
+
+impl IssueTrackerKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueTrackerKind::Jira => "jira",
+            IssueTrackerKind::Linear => "linear",
+        }
+    }
+}
+
+/// An issue tracker whose keys are linked in analysis output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IssueTrackerConfig {
+    pub kind: IssueTrackerKind,
+    /// Jira site URL, or Linear workspace URL (`https://linear.app/<workspace>`)
+    pub url: String,
+    /// Regex matching issue keys; uppercase `ABC-123` keys when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Environment variable holding an API token. Without one, keys are
+    /// linked but their title and status aren't looked up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+    /// Jira Cloud account email, sent with the token as basic auth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
 /// Optional git-ai author override for authorship metadata.
 ///
 /// Any unset field falls back to the effective Git committer identity.
@@ -156,7 +343,7 @@ impl std::fmt::Display for PromptStorageMode {
 
 #[derive(Serialize)]
 pub struct Config {
-    git_path: String,
+    git_path: Option<String>,
     #[serde(serialize_with = "serialize_patterns")]
     exclude_prompts_in_repositories: Vec<Pattern>,
     #[serde(serialize_with = "serialize_patterns")]
@@ -182,11 +369,22 @@ pub struct Config {
     custom_attributes: HashMap<String, String>,
     git_ai_hooks: HashMap<String, Vec<String>>,
     codex_hooks_format: CodexHooksFormat,
+    attribution_diff_algorithm: DiffAlgorithm,
     notes_backend: NotesBackendConfig,
+    sync_backends: Vec<SyncBackendConfig>,
     transcript_streaming_lookback_days: Option<u32>,
     max_checkpoint_file_size_bytes: usize,
     max_checkpoint_total_size_bytes: usize,
     max_checkpoint_total_lines: usize,
+    report_min_group_size: Option<u32>,
+    report_noise_epsilon: Option<f64>,
+    commit_lint: CommitLintConfig,
+    release_branches: Vec<String>,
+    issue_trackers: Vec<IssueTrackerConfig>,
+    daemon_idle_shutdown_secs: Option<u64>,
+    redaction: RedactionConfig,
+    disk_budgets: DiskBudgetConfig,
+    clients: ClientsConfig,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize)]
@@ -265,8 +463,12 @@ pub struct FileConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub codex_hooks_format: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution_diff_algorithm: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes_backend: Option<NotesBackendConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_backends: Option<Vec<SyncBackendConfig>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub transcript_streaming_lookback_days: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_checkpoint_file_size_bytes: Option<usize>,
@@ -274,6 +476,24 @@ pub struct FileConfig {
     pub max_checkpoint_total_size_bytes: Option<usize>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_checkpoint_total_lines: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_min_group_size: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_noise_epsilon: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_lint: Option<CommitLintConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_branches: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_trackers: Option<Vec<IssueTrackerConfig>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daemon_idle_shutdown_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<RedactionConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_budgets: Option<DiskBudgetConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clients: Option<ClientsConfig>,
 }
 
 static CONFIG: OnceLock<Config> = OnceLock::new();
@@ -330,8 +550,12 @@ pub struct ConfigPatch {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub codex_hooks_format: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution_diff_algorithm: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes_backend: Option<NotesBackendConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_backends: Option<Vec<SyncBackendConfig>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub transcript_streaming_lookback_days: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_checkpoint_file_size_bytes: Option<usize>,
@@ -339,6 +563,8 @@ pub struct ConfigPatch {
     pub max_checkpoint_total_size_bytes: Option<usize>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_checkpoint_total_lines: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_lint: Option<CommitLintConfig>,
 }
 
 impl Config {
@@ -400,9 +626,24 @@ impl Config {
         }
     }
 
-    /// Returns the command to invoke git.
+    /// Returns the command to invoke git. Exits when no real git was found,
+    /// since whatever asked is about to run it; check
+    /// [`Config::has_real_git`] or use [`Config::real_git`] to cope without.
     pub fn git_cmd(&self) -> &str {
-        &self.git_path
+        match &self.git_path {
+            Some(path) => path,
+            None => exit_without_real_git(),
+        }
+    }
+
+    /// The real git binary (not the git-ai shim), if one was found
+    pub fn real_git(&self) -> Option<&str> {
+        self.git_path.as_deref()
+    }
+
+    /// Whether a real git binary (not the git-ai shim) was found
+    pub fn has_real_git(&self) -> bool {
+        self.git_path.is_some()
     }
 
     pub fn has_repository_filters(&self) -> bool {
@@ -641,6 +882,38 @@ impl Config {
         matches!(self.notes_backend.kind, NotesBackendKind::Http)
     }
 
+    /// Per-org HTTP notes backends, in match order.
+    pub fn sync_backends(&self) -> &[SyncBackendConfig] {
+        &self.sync_backends
+    }
+
+    /// The first sync backend with a pattern matching any of `remotes`, or
+    /// `None` when the repository belongs to the default backend.
+    pub fn sync_backend_for_remotes(
+        &self,
+        remotes: &[(String, String)],
+    ) -> Option<&SyncBackendConfig> {
+        self.sync_backends.iter().find(|backend| {
+            let patterns: Vec<Pattern> = backend
+                .remotes
+                .iter()
+                .filter_map(|pattern| Pattern::new(pattern).ok())
+                .collect();
+            remotes
+                .iter()
+                .any(|(_, url)| remote_matches_patterns(&patterns, url))
+        })
+    }
+
+    /// The sync backend owning `repo`'s notes, if not the default one.
+    pub fn sync_backend_for_repository(&self, repo: &Repository) -> Option<&SyncBackendConfig> {
+        if self.sync_backends.is_empty() {
+            return None;
+        }
+        let remotes = repo.remotes_with_urls().ok()?;
+        self.sync_backend_for_remotes(&remotes)
+    }
+
     pub fn transcript_streaming_lookback_days(&self) -> Option<u32> {
         self.transcript_streaming_lookback_days
     }
@@ -660,6 +933,57 @@ impl Config {
         self.max_checkpoint_total_lines
     }
 
+    /// Minimum distinct authors per reported group (k-anonymity), if enforced.
+    pub fn report_min_group_size(&self) -> Option<u32> {
+        self.report_min_group_size
+    }
+
+    /// Privacy budget for Laplace noise on reported counts, if enforced.
+    pub fn report_noise_epsilon(&self) -> Option<f64> {
+        self.report_noise_epsilon
+    }
+
+    /// Whether either aggregate privacy control is in force.
+    pub fn report_privacy_enabled(&self) -> bool {
+        self.report_min_group_size.is_some_and(|k| k > 1) || self.report_noise_epsilon.is_some()
+    }
+
+    /// Conventional Commits validation settings for shim commits.
+    pub fn commit_lint(&self) -> &CommitLintConfig {
+        &self.commit_lint
+    }
+
+    /// Branch name globs (e.g. `release/*`) whose merges are checked for backports in CI.
+    pub fn release_branches(&self) -> &[String] {
+        &self.release_branches
+    }
+
+    /// Trackers whose issue keys are linked in `bisect-report` and the dashboard.
+    pub fn issue_trackers(&self) -> &[IssueTrackerConfig] {
+        &self.issue_trackers
+    }
+
+    /// How long the background daemon may sit idle before exiting, if it
+    /// should exit at all. The next git-ai or wrapped git invocation restarts it.
+    pub fn daemon_idle_shutdown(&self) -> Option<Duration> {
+        self.daemon_idle_shutdown_secs.map(Duration::from_secs)
+    }
+
+    /// Redaction applied to every outbound payload.
+    pub fn redaction(&self) -> &RedactionConfig {
+        &self.redaction
+    }
+
+    /// Size budgets for caches, the notes DB and CI clones.
+    pub fn disk_budgets(&self) -> &DiskBudgetConfig {
+        &self.disk_budgets
+    }
+
+    /// The git clients git-ai may configure.
+    pub fn clients(&self) -> &ClientsConfig {
+        &self.clients
+    }
+
     /// Returns true if quiet mode is enabled (suppresses chart output after commits)
     pub fn is_quiet(&self) -> bool {
         self.quiet
@@ -693,6 +1017,11 @@ impl Config {
         self.codex_hooks_format
     }
 
+    /// Line-matching algorithm used when attributing edits
+    pub fn attribution_diff_algorithm(&self) -> DiffAlgorithm {
+        self.attribution_diff_algorithm
+    }
+
     /// Serialize the effective runtime config into pretty JSON.
     /// Sensitive values are redacted via field serializers.
     pub fn to_printable_json_pretty(&self) -> Result<String, String> {
@@ -1153,6 +1482,21 @@ fn build_config() -> Config {
         })
         .unwrap_or_default();
 
+    let attribution_diff_algorithm = file_cfg
+        .as_ref()
+        .and_then(|c| c.attribution_diff_algorithm.as_deref())
+        .and_then(|value| {
+            let parsed = DiffAlgorithm::parse(value);
+            if parsed.is_none() {
+                eprintln!(
+                    "Warning: Invalid attribution_diff_algorithm value '{}', using 'histogram'",
+                    value
+                );
+            }
+            parsed
+        })
+        .unwrap_or_default();
+
     // Resolve notes_backend config: env vars override file config, which overrides defaults.
     let file_backend = file_cfg.as_ref().and_then(|c| c.notes_backend.clone());
     let kind_from_env = env::var("GIT_AI_NOTES_BACKEND_KIND")
@@ -1211,6 +1555,80 @@ fn build_config() -> Config {
         .or_else(|| file_cfg.as_ref().and_then(|c| c.max_checkpoint_total_lines))
         .unwrap_or(DEFAULT_MAX_CHECKPOINT_TOTAL_LINES);
 
+    // Report privacy policy: 0 (or a non-positive epsilon) disables each control.
+    let report_min_group_size = file_cfg
+        .as_ref()
+        .and_then(|c| c.report_min_group_size)
+        .filter(|k| *k > 0);
+    let report_noise_epsilon = file_cfg
+        .as_ref()
+        .and_then(|c| c.report_noise_epsilon)
+        .filter(|e| e.is_finite() && *e > 0.0);
+
+    let commit_lint = file_cfg
+        .as_ref()
+        .and_then(|c| c.commit_lint.clone())
+        .unwrap_or_default();
+
+    // Release branches: env (comma-separated, for CI runners) > file > none.
+    let release_branches = env::var("GIT_AI_RELEASE_BRANCHES")
+        .ok()
+        .map(|v| {
+            v.split(',')
+                .map(str::trim)
+                .filter(|b| !b.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .or_else(|| file_cfg.as_ref().and_then(|c| c.release_branches.clone()))
+        .unwrap_or_default();
+
+    let issue_trackers = file_cfg
+        .as_ref()
+        .and_then(|c| c.issue_trackers.clone())
+        .unwrap_or_default();
+
+    // 0 (the default) keeps the daemon resident.
+    let daemon_idle_shutdown_secs = file_cfg
+        .as_ref()
+        .and_then(|c| c.daemon_idle_shutdown_secs)
+        .filter(|secs| *secs > 0);
+
+    let redaction = file_cfg
+        .as_ref()
+        .and_then(|c| c.redaction.clone())
+        .unwrap_or_default();
+
+    let disk_budgets = file_cfg
+        .as_ref()
+        .and_then(|c| c.disk_budgets.clone())
+        .unwrap_or_default();
+
+    let clients = file_cfg
+        .as_ref()
+        .and_then(|c| c.clients.clone())
+        .unwrap_or_default();
+
+    // Names key the upload queues, so unnamed entries and repeats are dropped.
+    let mut sync_backends: Vec<SyncBackendConfig> = Vec::new();
+    for backend in file_cfg
+        .as_ref()
+        .and_then(|c| c.sync_backends.clone())
+        .unwrap_or_default()
+    {
+        let name = backend.name.trim();
+        if name.is_empty()
+            || backend.backend_url.trim().is_empty()
+            || sync_backends.iter().any(|existing| existing.name == name)
+        {
+            continue;
+        }
+        sync_backends.push(SyncBackendConfig {
+            name: name.to_string(),
+            ..backend
+        });
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     {
         let mut config = Config {
@@ -1235,11 +1653,22 @@ fn build_config() -> Config {
             custom_attributes: custom_attributes.clone(),
             git_ai_hooks: git_ai_hooks.clone(),
             codex_hooks_format,
+            attribution_diff_algorithm,
             notes_backend,
+            sync_backends,
             transcript_streaming_lookback_days,
             max_checkpoint_file_size_bytes,
             max_checkpoint_total_size_bytes,
             max_checkpoint_total_lines,
+            report_min_group_size,
+            report_noise_epsilon,
+            commit_lint,
+            release_branches,
+            issue_trackers,
+            daemon_idle_shutdown_secs,
+            redaction,
+            disk_budgets,
+            clients,
         };
         apply_test_config_patch(&mut config);
         config
@@ -1268,11 +1697,22 @@ fn build_config() -> Config {
         custom_attributes,
         git_ai_hooks,
         codex_hooks_format,
+        attribution_diff_algorithm,
         notes_backend,
+        sync_backends,
         transcript_streaming_lookback_days,
         max_checkpoint_file_size_bytes,
         max_checkpoint_total_size_bytes,
         max_checkpoint_total_lines,
+        report_min_group_size,
+        report_noise_epsilon,
+        commit_lint,
+        release_branches,
+        issue_trackers,
+        daemon_idle_shutdown_secs,
+        redaction,
+        disk_budgets,
+        clients,
     }
 }
 
@@ -1332,7 +1772,10 @@ fn build_feature_flags(file_cfg: &Option<FileConfig>) -> FeatureFlags {
     FeatureFlags::from_env_and_file(file_flags)
 }
 
-fn resolve_git_path(file_cfg: &Option<FileConfig>) -> String {
+/// The real git to run, if there is one. Commands that need git refuse to
+/// run without it (see [`crate::capabilities`]); anything else that reaches
+/// [`Config::git_cmd`] exits.
+fn resolve_git_path(file_cfg: &Option<FileConfig>) -> Option<String> {
     // 1) From config file
     if let Some(cfg) = file_cfg
         && let Some(path) = cfg.git_path.as_ref()
@@ -1341,7 +1784,7 @@ fn resolve_git_path(file_cfg: &Option<FileConfig>) -> String {
         if !trimmed.is_empty() {
             let p = Path::new(trimmed);
             if is_executable(p) && !path_is_git_ai_binary(p) {
-                return trimmed.to_string();
+                return Some(trimmed.to_string());
             }
         }
     }
@@ -1407,7 +1850,7 @@ fn resolve_git_path(file_cfg: &Option<FileConfig>) -> String {
         .map(Path::new)
         .find(|p| is_executable(p) && !path_is_git_ai_binary(p))
     {
-        return found.to_string_lossy().to_string();
+        return Some(found.to_string_lossy().to_string());
     }
 
     // 3) Windows-only: try `where.exe git.exe` as a PATH-based fallback
@@ -1423,12 +1866,16 @@ fn resolve_git_path(file_cfg: &Option<FileConfig>) -> String {
                 let trimmed = line.trim();
                 let p = Path::new(trimmed);
                 if is_executable(p) && !path_is_git_ai_binary(p) {
-                    return trimmed.to_string();
+                    return Some(trimmed.to_string());
                 }
             }
         }
     }
 
+    None
+}
+
+fn exit_without_real_git() -> ! {
     eprintln!(
         "Fatal: Could not locate a real 'git' binary.\n\
          Expected a valid 'git_path' in {cfg_path} or in standard locations.\n\
@@ -1454,7 +1901,7 @@ fn parse_file_config_bytes(data: &[u8]) -> Result<FileConfig, serde_json::Error>
 }
 
 fn config_file_path() -> Option<PathBuf> {
-    Some(home_dir().join(".git-ai").join("config.json"))
+    Some(AppContext::current().config_file)
 }
 
 /// Public accessor for config file path
@@ -1468,10 +1915,11 @@ pub fn git_ai_dir_path() -> Option<PathBuf> {
     Some(home_dir().join(".git-ai"))
 }
 
-/// Returns the path to the internal state directory (~/.git-ai/internal)
+/// Returns the path to the internal state directory (~/.git-ai/internal, or
+/// the `--state-dir` override)
 /// This is where git-ai stores internal files like distinct_id, update_check, etc.
 pub fn internal_dir_path() -> Option<PathBuf> {
-    git_ai_dir_path().map(|dir| dir.join("internal"))
+    Some(AppContext::current().state_dir)
 }
 
 /// Returns the path to the skills directory (~/.git-ai/skills)
@@ -1562,6 +2010,154 @@ pub fn save_file_config(config: &FileConfig) -> Result<(), String> {
     fs::write(&path, json).map_err(|e| format!("Failed to write config file: {}", e))
 }
 
+/// Which layer set the effective value of a config key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// An environment variable, overriding the config file
+    Env(String),
+    /// `~/.git-ai/config.json`
+    File(PathBuf),
+    /// Neither; the built-in default (or auto-detection) applies
+    Default,
+}
+
+impl ConfigSource {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConfigSource::Env(_) => "env",
+            ConfigSource::File(_) => "file",
+            ConfigSource::Default => "default",
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            ConfigSource::Env(var) => format!("env {}", var),
+            ConfigSource::File(path) => format!("file {}", path.display()),
+            ConfigSource::Default => "default".to_string(),
+        }
+    }
+}
+
+/// An env var that overrides a config key when set to a value `build_config`
+/// accepts
+struct EnvOverride {
+    key: &'static str,
+    var: &'static str,
+    accepts: fn(&str) -> bool,
+}
+
+/// `api_base_url` is absent because its env var is only a fallback behind
+/// the file
+const CONFIG_ENV_OVERRIDES: &[EnvOverride] = &[
+    EnvOverride {
+        key: "api_key",
+        var: "GIT_AI_API_KEY",
+        accepts: |v| !v.is_empty(),
+    },
+    EnvOverride {
+        key: "custom_attributes",
+        var: "GIT_AI_CUSTOM_ATTRIBUTES",
+        accepts: |v| serde_json::from_str::<HashMap<String, serde_json::Value>>(v).is_ok(),
+    },
+    EnvOverride {
+        key: "max_checkpoint_file_size_bytes",
+        var: "GIT_AI_MAX_CHECKPOINT_FILE_SIZE_BYTES",
+        accepts: |v| v.parse::<usize>().is_ok(),
+    },
+    EnvOverride {
+        key: "max_checkpoint_total_lines",
+        var: "GIT_AI_MAX_CHECKPOINT_TOTAL_LINES",
+        accepts: |v| v.parse::<usize>().is_ok(),
+    },
+    EnvOverride {
+        key: "max_checkpoint_total_size_bytes",
+        var: "GIT_AI_MAX_CHECKPOINT_TOTAL_SIZE_BYTES",
+        accepts: |v| v.parse::<usize>().is_ok(),
+    },
+    EnvOverride {
+        key: "notes_backend.backend_url",
+        var: "GIT_AI_NOTES_BACKEND_URL",
+        accepts: |_| true,
+    },
+    EnvOverride {
+        key: "notes_backend.kind",
+        var: "GIT_AI_NOTES_BACKEND_KIND",
+        accepts: |v| matches!(v, "http" | "git_notes" | "git-notes"),
+    },
+    EnvOverride {
+        key: "release_branches",
+        var: "GIT_AI_RELEASE_BRANCHES",
+        accepts: |_| true,
+    },
+    EnvOverride {
+        key: "transcript_streaming_lookback_days",
+        var: "GIT_AI_TRANSCRIPT_STREAMING_LOOKBACK_DAYS",
+        accepts: |v| v.parse::<u32>().is_ok(),
+    },
+];
+
+/// Where the effective value of `key` (a dotted path such as
+/// `notes_backend.kind`) comes from. A parent key reports an env var when
+/// any of its fields is overridden.
+pub fn config_key_source(key: &str, file_config: &FileConfig) -> ConfigSource {
+    let in_scope = |candidate: &str| {
+        candidate == key
+            || candidate
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.starts_with('.'))
+    };
+    for EnvOverride {
+        key: candidate,
+        var,
+        accepts,
+    } in CONFIG_ENV_OVERRIDES
+    {
+        if in_scope(candidate) && env::var(var).is_ok_and(|value| accepts(&value)) {
+            return ConfigSource::Env(var.to_string());
+        }
+    }
+    if let Some(flag) = key.strip_prefix("feature_flags.") {
+        if let Some(var) = crate::feature_flags::env_override_var(flag) {
+            return ConfigSource::Env(var);
+        }
+    } else if key == "feature_flags"
+        && let Some(var) = serde_json::to_value(FeatureFlags::default())
+            .ok()
+            .and_then(|flags| {
+                flags.as_object().and_then(|flags| {
+                    flags
+                        .keys()
+                        .find_map(|flag| crate::feature_flags::env_override_var(flag))
+                })
+            })
+    {
+        return ConfigSource::Env(var);
+    }
+
+    // `config show` reports the file's `telemetry_oss` as a boolean
+    let file_key = match key {
+        "telemetry_oss_disabled" => "telemetry_oss",
+        other => other,
+    };
+    let in_file = serde_json::to_value(file_config)
+        .ok()
+        .and_then(|root| {
+            file_key
+                .split('.')
+                .try_fold(root, |value, part| value.get(part).cloned())
+        })
+        .is_some_and(|value| !value.is_null());
+    if in_file && let Some(path) = config_file_path() {
+        return ConfigSource::File(path);
+    }
+
+    if key == "api_base_url" && env::var("GIT_AI_API_BASE_URL").is_ok() {
+        return ConfigSource::Env("GIT_AI_API_BASE_URL".to_string());
+    }
+    ConfigSource::Default
+}
+
 fn is_executable(path: &Path) -> bool {
     if !path.exists() || !path.is_file() {
         return false;
@@ -1710,12 +2306,25 @@ fn apply_test_config_patch(config: &mut Config) {
                 );
             }
         }
+        if let Some(algorithm) = patch.attribution_diff_algorithm {
+            if let Some(algorithm) = DiffAlgorithm::parse(&algorithm) {
+                config.attribution_diff_algorithm = algorithm;
+            } else {
+                eprintln!(
+                    "Warning: Invalid test attribution_diff_algorithm value '{}', ignoring",
+                    algorithm
+                );
+            }
+        }
         if let Some(nb) = patch.notes_backend {
             config.notes_backend.kind = nb.kind;
             if let Some(url) = nb.backend_url {
                 config.notes_backend.backend_url = Some(url);
             }
         }
+        if let Some(backends) = patch.sync_backends {
+            config.sync_backends = backends;
+        }
         if let Some(days) = patch.transcript_streaming_lookback_days {
             config.transcript_streaming_lookback_days = if days == 0 { None } else { Some(days) };
         }
@@ -1728,6 +2337,9 @@ fn apply_test_config_patch(config: &mut Config) {
         if let Some(max_lines) = patch.max_checkpoint_total_lines {
             config.max_checkpoint_total_lines = max_lines;
         }
+        if let Some(commit_lint) = patch.commit_lint {
+            config.commit_lint = commit_lint;
+        }
     }
 }
 
@@ -1740,7 +2352,7 @@ mod tests {
         exclude_repositories: Vec<String>,
     ) -> Config {
         Config {
-            git_path: "/usr/bin/git".to_string(),
+            git_path: Some("/usr/bin/git".to_string()),
             exclude_prompts_in_repositories: vec![],
             include_prompts_in_repositories: vec![],
             allow_repositories: allow_repositories
@@ -1767,11 +2379,22 @@ mod tests {
             custom_attributes: HashMap::new(),
             git_ai_hooks: HashMap::new(),
             codex_hooks_format: CodexHooksFormat::ConfigToml,
+            attribution_diff_algorithm: DiffAlgorithm::default(),
             notes_backend: NotesBackendConfig::default(),
+            sync_backends: Vec::new(),
             transcript_streaming_lookback_days: Some(7),
             max_checkpoint_file_size_bytes: DEFAULT_MAX_CHECKPOINT_FILE_SIZE_BYTES,
             max_checkpoint_total_size_bytes: DEFAULT_MAX_CHECKPOINT_TOTAL_SIZE_BYTES,
             max_checkpoint_total_lines: DEFAULT_MAX_CHECKPOINT_TOTAL_LINES,
+            report_min_group_size: None,
+            report_noise_epsilon: None,
+            commit_lint: CommitLintConfig::default(),
+            release_branches: Vec::new(),
+            issue_trackers: Vec::new(),
+            daemon_idle_shutdown_secs: None,
+            redaction: RedactionConfig::default(),
+            disk_budgets: DiskBudgetConfig::default(),
+            clients: ClientsConfig::default(),
         }
     }
 
@@ -1988,7 +2611,7 @@ mod tests {
 
     fn create_test_config_with_exclude_prompts(exclude_prompts_patterns: Vec<String>) -> Config {
         Config {
-            git_path: "/usr/bin/git".to_string(),
+            git_path: Some("/usr/bin/git".to_string()),
             exclude_prompts_in_repositories: exclude_prompts_patterns
                 .into_iter()
                 .filter_map(|s| Pattern::new(&s).ok())
@@ -2012,11 +2635,22 @@ mod tests {
             custom_attributes: HashMap::new(),
             git_ai_hooks: HashMap::new(),
             codex_hooks_format: CodexHooksFormat::ConfigToml,
+            attribution_diff_algorithm: DiffAlgorithm::default(),
             notes_backend: NotesBackendConfig::default(),
+            sync_backends: Vec::new(),
             transcript_streaming_lookback_days: Some(7),
             max_checkpoint_file_size_bytes: DEFAULT_MAX_CHECKPOINT_FILE_SIZE_BYTES,
             max_checkpoint_total_size_bytes: DEFAULT_MAX_CHECKPOINT_TOTAL_SIZE_BYTES,
             max_checkpoint_total_lines: DEFAULT_MAX_CHECKPOINT_TOTAL_LINES,
+            report_min_group_size: None,
+            report_noise_epsilon: None,
+            commit_lint: CommitLintConfig::default(),
+            release_branches: Vec::new(),
+            issue_trackers: Vec::new(),
+            daemon_idle_shutdown_secs: None,
+            redaction: RedactionConfig::default(),
+            disk_budgets: DiskBudgetConfig::default(),
+            clients: ClientsConfig::default(),
         }
     }
 
@@ -2133,7 +2767,7 @@ mod tests {
         default_prompt_storage: Option<&str>,
     ) -> Config {
         Config {
-            git_path: "/usr/bin/git".to_string(),
+            git_path: Some("/usr/bin/git".to_string()),
             exclude_prompts_in_repositories: exclude_patterns
                 .into_iter()
                 .filter_map(|s| Pattern::new(&s).ok())
@@ -2160,11 +2794,22 @@ mod tests {
             custom_attributes: HashMap::new(),
             git_ai_hooks: HashMap::new(),
             codex_hooks_format: CodexHooksFormat::ConfigToml,
+            attribution_diff_algorithm: DiffAlgorithm::default(),
             notes_backend: NotesBackendConfig::default(),
+            sync_backends: Vec::new(),
             transcript_streaming_lookback_days: Some(7),
             max_checkpoint_file_size_bytes: DEFAULT_MAX_CHECKPOINT_FILE_SIZE_BYTES,
             max_checkpoint_total_size_bytes: DEFAULT_MAX_CHECKPOINT_TOTAL_SIZE_BYTES,
             max_checkpoint_total_lines: DEFAULT_MAX_CHECKPOINT_TOTAL_LINES,
+            report_min_group_size: None,
+            report_noise_epsilon: None,
+            commit_lint: CommitLintConfig::default(),
+            release_branches: Vec::new(),
+            issue_trackers: Vec::new(),
+            daemon_idle_shutdown_secs: None,
+            redaction: RedactionConfig::default(),
+            disk_budgets: DiskBudgetConfig::default(),
+            clients: ClientsConfig::default(),
         }
     }
 
@@ -2588,6 +3233,26 @@ mod tests {
         assert!(serialized.contains("http"));
     }
 
+    #[test]
+    fn test_clients_config_include_and_exclude() {
+        let parsed: FileConfig = serde_json::from_str(
+            r#"{"clients": {"include": ["fork", "vscode"], "exclude": ["vscode"]}}"#,
+        )
+        .unwrap();
+        let clients = parsed.clients.expect("clients should be set");
+        assert!(clients.allows("fork"));
+        assert!(!clients.allows("vscode"));
+        assert!(!clients.allows("gitfiend"));
+
+        let exclude_only = ClientsConfig {
+            exclude: Some(vec!["xcode".to_string()]),
+            ..Default::default()
+        };
+        assert!(exclude_only.allows("fork"));
+        assert!(!exclude_only.allows("xcode"));
+        assert!(ClientsConfig::default().allows("anything"));
+    }
+
     #[test]
     fn test_notes_backend_kind_as_str() {
         assert_eq!(NotesBackendKind::GitNotes.as_str(), "git_notes");
@@ -2613,6 +3278,28 @@ mod tests {
         assert!(!config.notes_backend_enabled());
     }
 
+    #[test]
+    fn test_config_key_source_reports_file_and_default() {
+        let file_config = FileConfig {
+            telemetry_oss: Some("off".to_string()),
+            notes_backend: Some(NotesBackendConfig {
+                kind: NotesBackendKind::Http,
+                backend_url: None,
+            }),
+            ..Default::default()
+        };
+        let in_file = |key| matches!(config_key_source(key, &file_config), ConfigSource::File(_));
+
+        assert!(in_file("telemetry_oss_disabled"));
+        assert!(in_file("notes_backend"));
+        assert!(in_file("notes_backend.kind"));
+        assert!(!in_file("notes_backend.backend_url"));
+        assert_eq!(
+            config_key_source("prompt_storage", &file_config),
+            ConfigSource::Default
+        );
+    }
+
     #[test]
     fn test_notes_backend_kind_env_var_parsing() {
         // Test the parsing logic that build_config() uses for GIT_AI_NOTES_BACKEND_KIND.
@@ -1,3 +1,4 @@
+use crate::app_context::AppContext;
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::checkpoint_content_budget::CheckpointContentBudget;
 use crate::config;
@@ -231,8 +232,7 @@ impl DaemonConfig {
     }
 
     pub fn from_home(home: &Path) -> Self {
-        let internal_dir = home.join(".git-ai").join("internal");
-        Self::from_internal_dir(internal_dir)
+        Self::from_internal_dir(AppContext::for_home(home).state_dir)
     }
 
     pub fn from_default_paths() -> Result<Self, GitAiError> {
@@ -1170,6 +1170,36 @@ fn apply_pull_notes_sync_side_effect(
     Ok(())
 }
 
+/// Set while a background MR metadata prefetch runs, so back-to-back fetches
+/// don't stack up prefetchers
+static MR_METADATA_PREFETCH_RUNNING: std::sync::atomic::AtomicBool =
+    std::sync::atomic::AtomicBool::new(false);
+
+/// Warm the MR/issue metadata cache for recent history off the event path.
+/// Only done with issue trackers configured: without them resolution is
+/// local-only and cheap enough on demand.
+fn spawn_mr_metadata_prefetch(worktree: &str) {
+    use std::sync::atomic::Ordering;
+
+    let trackers = crate::config::Config::fresh().issue_trackers().to_vec();
+    if trackers.is_empty() || MR_METADATA_PREFETCH_RUNNING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let worktree = worktree.to_string();
+    std::thread::spawn(move || {
+        match find_repository_in_path(&worktree) {
+            Ok(repo) => {
+                let written = crate::git::mr_metadata::prefetch_recent_history(
+                    &repo, &trackers, "HEAD", "HEAD",
+                );
+                tracing::debug!(worktree = %worktree, written, "prefetched MR metadata");
+            }
+            Err(e) => tracing::debug!("MR metadata prefetch skipped for {}: {}", worktree, e),
+        }
+        MR_METADATA_PREFETCH_RUNNING.store(false, Ordering::Release);
+    });
+}
+
 fn apply_clone_notes_sync_side_effect(worktree: &str) -> Result<(), GitAiError> {
     use crate::config::NotesBackendKind;
 
@@ -2144,7 +2174,7 @@ pub fn daemon_log_file_path(config: &DaemonConfig) -> Result<PathBuf, GitAiError
         ))
     })?;
     let meta: DaemonPidMeta = serde_json::from_str(&contents)?;
-    let log_dir = config.internal_dir.join("daemon").join("logs");
+    let log_dir = AppContext::log_dir_for(&config.internal_dir);
     Ok(log_dir.join(format!("{}.log", meta.pid)))
 }
 
@@ -2216,7 +2246,7 @@ fn daemon_is_test_mode() -> bool {
 }
 
 fn daemon_log_dir(config: &DaemonConfig) -> PathBuf {
-    config.internal_dir.join("daemon").join("logs")
+    AppContext::log_dir_for(&config.internal_dir)
 }
 
 /// Redirect stdout and stderr to a per-PID log file inside the daemon logs
@@ -2553,6 +2583,9 @@ pub struct ActorDaemonCoordinator {
     shutdown_notify: Notify,
     shutdown_condvar: std::sync::Condvar,
     shutdown_condvar_mutex: Mutex<()>,
+    /// Last time a client sent a control request or git sent a trace event,
+    /// consulted by the idle-shutdown loop.
+    last_activity_at: Mutex<std::time::Instant>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -2644,6 +2677,7 @@ impl ActorDaemonCoordinator {
             shutdown_notify: Notify::new(),
             shutdown_condvar: std::sync::Condvar::new(),
             shutdown_condvar_mutex: Mutex::new(()),
+            last_activity_at: Mutex::new(std::time::Instant::now()),
         }
     }
 
@@ -2868,6 +2902,35 @@ impl ActorDaemonCoordinator {
         self.request_shutdown();
     }
 
+    fn record_activity(&self) {
+        *self
+            .last_activity_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = std::time::Instant::now();
+    }
+
+    /// True when nothing has reached the daemon for `idle` and stopping now
+    /// would not drop any state: queued trace work, open AI edits and bash
+    /// sessions all live only in memory.
+    fn idle_for(&self, idle: Duration) -> bool {
+        let last_activity_at = *self
+            .last_activity_at
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if last_activity_at.elapsed() < idle || self.has_pending_daemon_work() {
+            return false;
+        }
+        let no_pending_edits = self
+            .pending_ai_edits_by_family
+            .lock()
+            .is_ok_and(|map| map.values().all(|files| files.is_empty()));
+        let no_bash_sessions = self
+            .bash_sessions
+            .lock()
+            .is_ok_and(|sessions| sessions.is_empty());
+        no_pending_edits && no_bash_sessions
+    }
+
     fn shutdown_action(&self) -> DaemonExitAction {
         DaemonExitAction::from_u8(self.shutdown_action.load(Ordering::SeqCst))
     }
@@ -3741,6 +3804,7 @@ impl ActorDaemonCoordinator {
     }
 
     fn enqueue_trace_payload(&self, payload: Value) -> Result<(), GitAiError> {
+        self.record_activity();
         let tx =
             self.trace_ingest_tx.get().cloned().ok_or_else(|| {
                 GitAiError::Generic("trace ingest worker not started".to_string())
@@ -5302,6 +5366,10 @@ impl ActorDaemonCoordinator {
                             cmd.invoked_command.as_deref(),
                             &cmd.invoked_args,
                         )?;
+                        spawn_mr_metadata_prefetch(&worktree);
+                    }
+                    crate::daemon::domain::SemanticEvent::FetchCompleted { .. } => {
+                        spawn_mr_metadata_prefetch(&worktree);
                     }
                     crate::daemon::domain::SemanticEvent::PushCompleted { .. } => {
                         apply_push_side_effect(
@@ -6135,6 +6203,7 @@ impl ActorDaemonCoordinator {
     }
 
     async fn handle_control_request(&self, request: ControlRequest) -> ControlResponse {
+        self.record_activity();
         let result = match request {
             ControlRequest::Ping => Ok(ControlResponse::ok(None, None)),
             ControlRequest::CheckpointRun { request } => {
@@ -7270,6 +7339,41 @@ fn daemon_socket_health_check_loop(
     }
 }
 
+const DAEMON_IDLE_CHECK_MAX_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Background loop, enabled by `daemon_idle_shutdown_secs`, that stops the
+/// daemon once it has gone unused for the configured time. Editor
+/// integrations then pay startup cost only on the first call after a quiet
+/// period: the next `git-ai` or wrapped mutating `git` command starts a fresh
+/// daemon before it needs one.
+fn daemon_idle_shutdown_loop(coordinator: Arc<ActorDaemonCoordinator>, idle: Duration) {
+    let interval = idle.min(DAEMON_IDLE_CHECK_MAX_INTERVAL);
+    tracing::info!(idle_secs = idle.as_secs(), "idle shutdown enabled");
+
+    loop {
+        {
+            let guard = coordinator
+                .shutdown_condvar_mutex
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if coordinator.is_shutting_down() {
+                return;
+            }
+            let _ = coordinator.shutdown_condvar.wait_timeout(guard, interval);
+        }
+
+        if coordinator.is_shutting_down() {
+            return;
+        }
+
+        if coordinator.idle_for(idle) {
+            tracing::info!(idle_secs = idle.as_secs(), "daemon idle, shutting down");
+            coordinator.request_stop();
+            return;
+        }
+    }
+}
+
 /// Background loop that periodically checks for available updates.
 ///
 /// Sleeps in short increments so it can exit promptly when the coordinator
@@ -7492,6 +7596,11 @@ pub(crate) async fn run_daemon(config: DaemonConfig) -> Result<DaemonExitAction,
         daemon_socket_health_check_loop(health_coord, health_control, health_trace);
     });
 
+    let idle_thread = config::Config::get().daemon_idle_shutdown().map(|idle| {
+        let idle_coord = coordinator.clone();
+        std::thread::spawn(move || daemon_idle_shutdown_loop(idle_coord, idle))
+    });
+
     coordinator.wait_for_shutdown().await;
 
     // Best-effort wake listeners to allow clean process exit.
@@ -7512,7 +7621,10 @@ pub(crate) async fn run_daemon(config: DaemonConfig) -> Result<DaemonExitAction,
         ("trace", trace_thread),
         ("update", update_thread),
         ("health", health_thread),
-    ] {
+    ]
+    .into_iter()
+    .chain(idle_thread.map(|thread| ("idle", thread)))
+    {
         let remaining = join_deadline.saturating_duration_since(std::time::Instant::now());
         if remaining.is_zero() {
             tracing::debug!("skipping join for {} thread (deadline exceeded)", name);
@@ -8383,6 +8495,40 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn idle_shutdown_waits_for_quiet_period_and_open_edits() {
+        let coordinator = ActorDaemonCoordinator::new();
+        assert!(coordinator.idle_for(Duration::ZERO));
+        assert!(!coordinator.idle_for(Duration::from_secs(3600)));
+
+        coordinator
+            .pending_ai_edits_by_family
+            .lock()
+            .unwrap()
+            .entry("family".to_string())
+            .or_default()
+            .insert("/repo/src/lib.rs".to_string(), now_unix_nanos());
+        assert!(
+            !coordinator.idle_for(Duration::ZERO),
+            "an in-flight AI edit must keep the daemon alive"
+        );
+        coordinator
+            .pending_ai_edits_by_family
+            .lock()
+            .unwrap()
+            .clear();
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(coordinator.idle_for(Duration::from_millis(10)));
+        coordinator
+            .handle_control_request(ControlRequest::Ping)
+            .await;
+        assert!(
+            !coordinator.idle_for(Duration::from_millis(10)),
+            "a control request resets the idle clock"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // Readonly command ingress fast-path tests
     //
@@ -1,3 +1,4 @@
+use crate::app_context::AppContext;
 use crate::authorship::working_log::AgentId;
 use crate::error::GitAiError;
 use rusqlite::{Connection, OptionalExtension, params};
@@ -245,12 +246,7 @@ impl BashHistoryDatabase {
             return Ok(PathBuf::from(test_path));
         }
 
-        let home = dirs::home_dir()
-            .ok_or_else(|| GitAiError::Generic("Could not determine home directory".to_string()))?;
-        Ok(home
-            .join(".git-ai")
-            .join("internal")
-            .join("bash-checkpoints-db"))
+        Ok(AppContext::current().state_dir.join("bash-checkpoints-db"))
     }
 
     fn initialize_schema(&mut self) -> Result<(), GitAiError> {
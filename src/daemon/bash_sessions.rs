@@ -60,6 +60,13 @@ impl BashSessionState {
         );
     }
 
+    /// Whether no non-stale session is still waiting for its end event
+    pub fn is_empty(&self) -> bool {
+        self.sessions
+            .values()
+            .all(|s| s.started_at.elapsed() >= Duration::from_secs(STALE_SESSION_SECS))
+    }
+
     pub fn end_session(&mut self, session_id: &str, tool_use_id: &str) -> Option<BashSession> {
         self.sessions
             .remove(&(session_id.to_string(), tool_use_id.to_string()))
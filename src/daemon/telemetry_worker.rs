@@ -740,12 +740,13 @@ fn flush_pending_metrics_from_db(
     client: &ApiClient,
     deadline: std::time::Instant,
 ) -> Result<PendingMetricsFlushResult, GitAiError> {
+    let config = Config::fresh();
     flush_pending_metric_records_with(
         read_pending_metrics_batch,
         mark_metric_records_delivered,
         mark_metric_records_failed,
         mark_metric_records_undeliverable,
-        |batch| client.upload_metrics(batch),
+        |batch| client.upload_metrics(&crate::metrics::batch_for_upload(batch, &config)),
         deadline,
         MAX_METRICS_PER_ENVELOPE,
     )
@@ -1250,46 +1251,76 @@ fn flush_sentry_and_posthog(
                 .set("Content-Type", "application/json");
             let _ = crate::http::send_with_body(
                 request,
-                &serde_json::to_string(&ph_event).unwrap_or_default(),
+                &crate::redaction::outbound_json(&ph_event).unwrap_or_default(),
             );
         }
     }
 }
 
-/// Flush pending notes from `notes-db` to the remote HTTP backend.
+/// Flush pending notes from `notes-db` to the remote HTTP backends.
 ///
-/// Skips silently when:
+/// Each backend (the default plus any `sync_backends`) drains its own queue
+/// with its own credentials, so an outage or rejected key on one leaves the
+/// others syncing. A backend is skipped silently when:
 /// - `notes_backend.kind != Http`
 /// - Not authenticated (no API key and not logged in)
 pub fn flush_notes() {
-    use crate::api::types::{NoteEntry, NotesUploadRequest};
-    use crate::config::NotesBackendKind;
-
     let cfg = Config::fresh();
-    if cfg.notes_backend_kind() != NotesBackendKind::Http {
+    let targets = crate::notes::backends::all_targets(&cfg);
+    if targets.is_empty() {
         tracing::debug!("notes: skipping flush, backend is not Http");
         return;
     }
 
-    let backend_url = match cfg.notes_backend_url() {
-        Some(url) => url.to_string(),
-        None => {
-            tracing::debug!("notes: skipping flush, notes_backend.backend_url is not configured");
-            return;
+    for target in &targets {
+        if target.backend_url.is_none() {
+            tracing::debug!(
+                backend = target.display_name(),
+                "notes: skipping flush, backend_url is not configured"
+            );
+            continue;
         }
-    };
-    let context = ApiContext::new(Some(backend_url));
-    let client = ApiClient::new(context);
+        let Some(client) = target.client() else {
+            tracing::debug!(
+                backend = target.display_name(),
+                "notes: skipping flush, not authenticated"
+            );
+            continue;
+        };
+        flush_notes_to_backend(&target.queue, &client);
+    }
 
-    if !client.is_logged_in() && !client.has_api_key() {
-        tracing::debug!("notes: skipping flush, not authenticated");
-        return;
+    // Opportunistic cache eviction (~every 5 minutes at 3s flush interval).
+    use std::sync::atomic::{AtomicU32, Ordering};
+    static FLUSH_COUNT: AtomicU32 = AtomicU32::new(0);
+    if FLUSH_COUNT
+        .fetch_add(1, Ordering::Relaxed)
+        .is_multiple_of(100)
+        && let Ok(db) = crate::notes::db::NotesDatabase::global()
+        && let Ok(mut lock) = db.lock()
+    {
+        let _ = lock.evict_stale_cache(10_000, 90 * 24 * 3600);
+        if let Some(max_bytes) = Config::fresh().disk_budgets().notes_db_max_bytes {
+            match lock.evict_to_size_budget(max_bytes) {
+                Ok(0) => {}
+                Ok(deleted) => tracing::debug!(
+                    deleted,
+                    "notes: evicted cached notes over disk_budgets.notes_db_max_bytes"
+                ),
+                Err(e) => tracing::warn!(%e, "notes: size budget eviction failed"),
+            }
+        }
     }
+}
+
+/// Upload one batch from `queue` with `client`.
+fn flush_notes_to_backend(queue: &str, client: &ApiClient) {
+    use crate::api::types::{NoteEntry, NotesUploadRequest};
 
     // Dequeue up to 50 pending notes.
     let pending = match crate::notes::db::NotesDatabase::global() {
         Ok(db) => match db.lock() {
-            Ok(mut lock) => match lock.dequeue_pending(50) {
+            Ok(mut lock) => match lock.dequeue_pending_for_backend(queue, 50) {
                 Ok(rows) => rows,
                 Err(e) => {
                     tracing::warn!(%e, "notes: failed to dequeue pending rows");
@@ -1359,48 +1390,41 @@ pub fn flush_notes() {
             }
         }
     }
-
-    // Opportunistic cache eviction (~every 5 minutes at 3s flush interval).
-    use std::sync::atomic::{AtomicU32, Ordering};
-    static FLUSH_COUNT: AtomicU32 = AtomicU32::new(0);
-    if FLUSH_COUNT
-        .fetch_add(1, Ordering::Relaxed)
-        .is_multiple_of(100)
-        && let Ok(db) = crate::notes::db::NotesDatabase::global()
-        && let Ok(mut lock) = db.lock()
-    {
-        let _ = lock.evict_stale_cache(10_000, 90 * 24 * 3600);
-    }
 }
 
 fn flush_notes_for_await() -> usize {
-    use crate::config::NotesBackendKind;
-
     let cfg = Config::fresh();
-    if cfg.notes_backend_kind() != NotesBackendKind::Http || cfg.notes_backend_url().is_none() {
-        return 0;
-    }
-
-    let client = ApiClient::new(ApiContext::new(cfg.notes_backend_url().map(str::to_string)));
-    if !client.is_logged_in() && !client.has_api_key() {
+    // Only queues with a reachable, authenticated backend can drain.
+    let queues: Vec<String> = crate::notes::backends::all_targets(&cfg)
+        .into_iter()
+        .filter(|target| target.client().is_some())
+        .map(|target| target.queue)
+        .collect();
+    if queues.is_empty() {
         return 0;
     }
 
     for _ in 0..1_000 {
-        let remaining = count_pending_notes_for_await();
+        let remaining = count_pending_notes_for_await(&queues);
         if remaining == 0 {
             return 0;
         }
         flush_notes();
     }
 
-    count_pending_notes_for_await()
+    count_pending_notes_for_await(&queues)
 }
 
-fn count_pending_notes_for_await() -> usize {
+fn count_pending_notes_for_await(queues: &[String]) -> usize {
     match crate::notes::db::NotesDatabase::global() {
         Ok(db) => match db.lock() {
-            Ok(lock) => lock.count_pending_uploadable().unwrap_or(0),
+            Ok(lock) => queues
+                .iter()
+                .map(|queue| {
+                    lock.count_pending_uploadable_for_backend(queue)
+                        .unwrap_or(0)
+                })
+                .sum(),
             Err(_) => 0,
         },
         Err(_) => 0,
@@ -1495,7 +1519,7 @@ impl SentryClient {
             env!("CARGO_PKG_VERSION")
         );
 
-        let body = serde_json::to_string(&event)?;
+        let body = crate::redaction::outbound_json(&event)?;
         let agent = crate::http::build_agent(Some(30));
         let request = agent
             .post(&self.endpoint)
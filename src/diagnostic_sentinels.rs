@@ -13,9 +13,8 @@ pub fn is_debug_self_check_remote_url(url: &str) -> bool {
 }
 
 pub fn debug_self_check_root() -> PathBuf {
-    crate::mdm::utils::home_dir()
-        .join(".git-ai")
-        .join("internal")
+    crate::app_context::AppContext::current()
+        .state_dir
         .join(DEBUG_SELF_CHECK_DIR_NAME)
 }
 
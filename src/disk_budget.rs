@@ -0,0 +1,241 @@
+//! Disk-space guardrails: free-space preflight checks and size budgets with
+//! least-recently-used eviction, configured through `disk_budgets`.
+//!
+//! CI runners have small disks shared across jobs, so clones and caches
+//! that quietly grow can fail unrelated steps. Budgets are enforced where the
+//! data is written: the MR metadata cache after it is warmed, the notes DB
+//! alongside its periodic eviction, and CI clones around cloning.
+
+use crate::config::Config;
+use crate::error::GitAiError;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Bytes available to unprivileged users on the filesystem holding `path`.
+/// `None` where it can't be determined (including Windows), in which case
+/// preflight checks pass.
+#[cfg(unix)]
+pub fn available_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is NUL-terminated and `stat` is a valid out-pointer
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+pub fn available_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Fail with a clear error unless the filesystem holding `path` (or its
+/// nearest existing ancestor) has at least `required` bytes free
+pub fn ensure_free_space(path: &Path, required: u64, purpose: &str) -> Result<(), GitAiError> {
+    if required == 0 {
+        return Ok(());
+    }
+    // Relative paths like the CI clone dir bottom out in the working directory
+    let Some(existing) = path
+        .ancestors()
+        .map(|dir| {
+            if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            }
+        })
+        .find(|dir| dir.exists())
+    else {
+        return Ok(());
+    };
+    match available_space(existing) {
+        Some(available) if available < required => Err(GitAiError::Generic(format!(
+            "Not enough disk space for {}: {} free on {}, {} required. Free up space or lower disk_budgets.min_free_bytes",
+            purpose,
+            format_bytes(available),
+            existing.display(),
+            format_bytes(required)
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Free-space preflight before a CI clone into `clone_dir`
+pub fn preflight_ci_clone(clone_dir: &Path) -> Result<(), GitAiError> {
+    let budgets = Config::get().disk_budgets();
+    ensure_free_space(clone_dir, budgets.min_free_bytes(), "the CI clone")
+}
+
+/// Enforce `disk_budgets.ci_clone_max_bytes` on a finished clone. An
+/// oversized clone is removed before the error is returned, so it can't
+/// crowd out later jobs on the runner.
+pub fn check_ci_clone_size(clone_dir: &Path) -> Result<(), GitAiError> {
+    let Some(max_bytes) = Config::get().disk_budgets().ci_clone_max_bytes else {
+        return Ok(());
+    };
+    let size = dir_size(clone_dir);
+    if size <= max_bytes {
+        return Ok(());
+    }
+    let _ = fs::remove_dir_all(clone_dir);
+    Err(GitAiError::Generic(format!(
+        "CI clone {} is {}, over disk_budgets.ci_clone_max_bytes ({}); the clone was removed",
+        clone_dir.display(),
+        format_bytes(size),
+        format_bytes(max_bytes)
+    )))
+}
+
+/// Total size of the regular files under `dir`; symlinks are not followed
+pub fn dir_size(dir: &Path) -> u64 {
+    files_under(dir).iter().map(|file| file.len).sum()
+}
+
+struct CachedFile {
+    path: PathBuf,
+    len: u64,
+    used_at: SystemTime,
+}
+
+fn files_under(dir: &Path) -> Vec<CachedFile> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                files.push(CachedFile {
+                    path: entry.path(),
+                    len: metadata.len(),
+                    used_at: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                });
+            }
+        }
+    }
+    files
+}
+
+/// What [`evict_lru`] removed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Eviction {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Delete the least recently used files under `dir` until it fits in
+/// `max_bytes`. Recency is the modification time, so caches bump it on a
+/// hit (see [`touch`]).
+pub fn evict_lru(dir: &Path, max_bytes: u64) -> Eviction {
+    let mut files = files_under(dir);
+    let mut total: u64 = files.iter().map(|file| file.len).sum();
+    let mut eviction = Eviction::default();
+    if total <= max_bytes {
+        return eviction;
+    }
+
+    files.sort_by_key(|file| file.used_at);
+    for file in files {
+        if total <= max_bytes {
+            break;
+        }
+        if fs::remove_file(&file.path).is_ok() {
+            total = total.saturating_sub(file.len);
+            eviction.files += 1;
+            eviction.bytes += file.len;
+        }
+    }
+    eviction
+}
+
+/// Mark a cache file as just used, for [`evict_lru`]
+pub fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0usize;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filetime::{FileTime, set_file_mtime};
+
+    #[test]
+    fn evicts_least_recently_used_files_until_under_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("ab")).unwrap();
+        for (index, name) in ["ab/old", "ab/middle", "new"].iter().enumerate() {
+            let path = dir.path().join(name);
+            fs::write(&path, [0u8; 100]).unwrap();
+            set_file_mtime(&path, FileTime::from_unix_time(1_000 + index as i64, 0)).unwrap();
+        }
+        // A hit makes the oldest entry the most recent
+        touch(&dir.path().join("ab/old"));
+
+        assert_eq!(dir_size(dir.path()), 300);
+        assert_eq!(evict_lru(dir.path(), 300), Eviction::default());
+        assert_eq!(
+            evict_lru(dir.path(), 150),
+            Eviction {
+                files: 2,
+                bytes: 200
+            }
+        );
+        assert!(dir.path().join("ab/old").exists());
+        assert!(!dir.path().join("ab/middle").exists());
+        assert!(!dir.path().join("new").exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn free_space_preflight_reports_what_is_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("not-yet").join("clone");
+        assert!(ensure_free_space(&target, 1, "the CI clone").is_ok());
+        assert!(ensure_free_space(&target, 0, "the CI clone").is_ok());
+
+        let err = ensure_free_space(&target, u64::MAX, "the CI clone")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Not enough disk space for the CI clone"),
+            "{}",
+            err
+        );
+        assert!(err.contains("min_free_bytes"), "{}", err);
+    }
+
+    #[test]
+    fn formats_sizes_for_messages() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(256 * 1024 * 1024), "256.0 MB");
+    }
+}
@@ -12,6 +12,13 @@ fn parse_bool_env(value: Option<String>) -> Option<bool> {
     })
 }
 
+/// The `GIT_AI_*` variable overriding `flag`, if it is set to a value that
+/// takes effect
+pub(crate) fn env_override_var(flag: &str) -> Option<String> {
+    let var = format!("GIT_AI_{}", flag.to_uppercase());
+    parse_bool_env(std::env::var(&var).ok()).map(|_| var)
+}
+
 macro_rules! define_feature_flags {
     (
         $(
@@ -0,0 +1,103 @@
+//! Recover the pull/merge request a commit arrived through from commit
+//! messages, since notes don't record it.
+
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// GitHub squash merges append `(#123)` to the subject
+static SQUASH_PR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\(#(\d+)\)\s*$").unwrap());
+
+static GITHUB_MERGE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^Merge pull request #(\d+)").unwrap());
+
+static GITLAB_MERGE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^See merge request (\S*![0-9]+)").unwrap());
+
+/// Look for a PR/MR reference (`#12`, `group/app!17`) in a commit message
+pub fn merge_request_from_message(message: &str) -> Option<String> {
+    let subject = message.lines().next().unwrap_or("");
+    if let Some(caps) = GITHUB_MERGE_RE.captures(subject) {
+        return Some(format!("#{}", &caps[1]));
+    }
+    if let Some(caps) = SQUASH_PR_RE.captures(subject) {
+        return Some(format!("#{}", &caps[1]));
+    }
+    GITLAB_MERGE_RE
+        .captures(message)
+        .map(|caps| caps[1].to_string())
+}
+
+/// The PR/MR that brought `sha` into `tip`: named in the commit itself
+/// (squash merge) or in the earliest merge on the ancestry path.
+pub fn originating_merge_request(
+    repo: &Repository,
+    sha: &str,
+    tip: &str,
+) -> Result<Option<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(
+        ["show", "-s", "--no-notes", "--format=%B", sha]
+            .iter()
+            .map(|arg| arg.to_string()),
+    );
+    let own_message = String::from_utf8(exec_git(&args)?.stdout)?;
+    if let Some(mr) = merge_request_from_message(&own_message) {
+        return Ok(Some(mr));
+    }
+
+    Ok(merge_messages_on_path(repo, sha, tip)?
+        .iter()
+        .find_map(|message| merge_request_from_message(message)))
+}
+
+/// Messages of the merges on the ancestry path from `sha` to `tip`, earliest
+/// (the one that brought `sha` in) first.
+pub fn merge_messages_on_path(
+    repo: &Repository,
+    sha: &str,
+    tip: &str,
+) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "log".to_string(),
+        "--merges".to_string(),
+        "--ancestry-path".to_string(),
+        "--reverse".to_string(),
+        "--no-notes".to_string(),
+        "--format=%B%x1e".to_string(),
+        format!("{}..{}", sha, tip),
+    ]);
+    let merges = String::from_utf8(exec_git(&args)?.stdout)?;
+    Ok(merges
+        .split('\x1e')
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_requests_are_found_in_common_message_formats() {
+        assert_eq!(
+            merge_request_from_message("Merge pull request #42 from dev/fix\n\nFix it"),
+            Some("#42".to_string())
+        );
+        assert_eq!(
+            merge_request_from_message("Fix parser crash (#108)\n\n* details"),
+            Some("#108".to_string())
+        );
+        assert_eq!(
+            merge_request_from_message(
+                "Merge branch 'fix' into 'main'\n\nFix it\n\nSee merge request group/app!17"
+            ),
+            Some("group/app!17".to_string())
+        );
+        assert_eq!(merge_request_from_message("Fix #12 in parser"), None);
+    }
+}
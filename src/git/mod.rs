@@ -1,6 +1,8 @@
 pub mod cli_parser;
 pub mod command_classification;
 pub mod fast_reader;
+pub mod merge_request;
+pub mod mr_metadata;
 pub mod notes_api;
 pub mod refs;
 pub mod repo_state;
@@ -0,0 +1,339 @@
+//! Per-commit PR/MR and issue-link metadata with an on-disk cache.
+//!
+//! Resolving a commit's originating merge request walks the ancestry path,
+//! and linking its issues may call the Jira/Linear APIs, so reports that cover
+//! many commits are slow when done serially on demand. Results are cached by
+//! commit SHA under `~/.git-ai/internal/mr_metadata` for [`CACHE_TTL_SECS`],
+//! and [`prefetch_mr_metadata`] warms the cache with bounded concurrency
+//! after CI runs and fetches.
+
+use crate::config::{self, Config, IssueTrackerConfig};
+use crate::disk_budget::{Eviction, evict_lru, format_bytes, touch};
+use crate::error::GitAiError;
+use crate::git::merge_request::{merge_messages_on_path, originating_merge_request};
+use crate::git::repository::{Repository, exec_git};
+use crate::issue_tracker::{IssueLink, link_issues};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Entries older than this are resolved again: a commit that wasn't merged
+/// yet picks up its MR, and issue titles/statuses refresh
+pub const CACHE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Worker threads used by [`prefetch_mr_metadata`]
+pub const PREFETCH_CONCURRENCY: usize = 4;
+
+/// Commits of recent history warmed after a fetch
+pub const PREFETCH_HISTORY_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MrMetadata {
+    /// `#123` or `group/app!17`
+    pub merge_request: Option<String>,
+    pub issues: Vec<IssueLink>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: u64,
+    metadata: MrMetadata,
+}
+
+/// On-disk cache of [`MrMetadata`] keyed by commit SHA
+#[derive(Debug, Clone)]
+pub struct MrMetadataCache {
+    dir: PathBuf,
+    ttl_secs: u64,
+    max_bytes: Option<u64>,
+}
+
+impl MrMetadataCache {
+    pub fn new(dir: PathBuf, ttl_secs: u64) -> Self {
+        Self {
+            dir,
+            ttl_secs,
+            max_bytes: None,
+        }
+    }
+
+    /// Cap the cache at `max_bytes`, enforced by [`Self::enforce_budget`]
+    pub fn with_max_bytes(mut self, max_bytes: Option<u64>) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// The shared cache under the git-ai internal directory, capped by
+    /// `disk_budgets.cache_max_bytes`
+    pub fn default_location() -> Option<Self> {
+        let max_bytes = Config::get().disk_budgets().cache_max_bytes;
+        config::internal_dir_path()
+            .map(|dir| Self::new(dir.join("mr_metadata"), CACHE_TTL_SECS).with_max_bytes(max_bytes))
+    }
+
+    /// Evict least recently used entries until the cache fits its budget
+    pub fn enforce_budget(&self) -> Eviction {
+        match self.max_bytes {
+            Some(max_bytes) => evict_lru(&self.dir, max_bytes),
+            None => Eviction::default(),
+        }
+    }
+
+    fn entry_path(&self, sha: &str) -> Option<PathBuf> {
+        // Only full hex SHAs become file names
+        let valid = sha.len() >= 40 && sha.chars().all(|c| c.is_ascii_hexdigit());
+        valid.then(|| self.dir.join(&sha[..2]).join(format!("{}.json", sha)))
+    }
+
+    /// The cached metadata for `sha`, unless missing or older than the TTL
+    pub fn get(&self, sha: &str) -> Option<MrMetadata> {
+        let path = self.entry_path(sha)?;
+        let content = fs::read(&path).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&content).ok()?;
+        let fresh = now_secs().saturating_sub(entry.fetched_at) < self.ttl_secs;
+        if fresh {
+            // Freshness comes from `fetched_at`, so the mtime is free to
+            // track use for eviction
+            touch(&path);
+        }
+        fresh.then_some(entry.metadata)
+    }
+
+    pub fn put(&self, sha: &str, metadata: &MrMetadata) -> Result<(), GitAiError> {
+        let Some(path) = self.entry_path(sha) else {
+            return Ok(());
+        };
+        let entry = CacheEntry {
+            fetched_at: now_secs(),
+            metadata: metadata.clone(),
+        };
+        write_entry(&path, &serde_json::to_vec(&entry)?)
+    }
+}
+
+fn write_entry(path: &Path, content: &[u8]) -> Result<(), GitAiError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Concurrent prefetchers may race on the same SHA; rename keeps readers
+    // from seeing a partial file
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Resolve `sha`'s PR/MR (relative to `tip`) and linked issues, bypassing the cache
+pub fn resolve_mr_metadata(
+    repo: &Repository,
+    trackers: &[IssueTrackerConfig],
+    sha: &str,
+    tip: &str,
+) -> Result<MrMetadata, GitAiError> {
+    let merge_request = originating_merge_request(repo, sha, tip)?;
+
+    // Issue keys live in the commit message or, via the branch name, in the
+    // merge that brought the commit in
+    let issues = if trackers.is_empty() {
+        Vec::new()
+    } else {
+        let mut args = repo.global_args_for_exec();
+        args.extend(
+            ["show", "-s", "--no-notes", "--format=%B", sha]
+                .iter()
+                .map(|arg| arg.to_string()),
+        );
+        let message = String::from_utf8(exec_git(&args)?.stdout)?;
+        let merge_message = merge_messages_on_path(repo, sha, tip)?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        link_issues(trackers, &[&message, &merge_message])?
+    };
+
+    Ok(MrMetadata {
+        merge_request,
+        issues,
+    })
+}
+
+/// Metadata for `sha` relative to `HEAD`, from the cache when fresh
+pub fn mr_metadata(repo: &Repository, sha: &str) -> Result<MrMetadata, GitAiError> {
+    let cache = MrMetadataCache::default_location();
+    if let Some(metadata) = cache.as_ref().and_then(|cache| cache.get(sha)) {
+        return Ok(metadata);
+    }
+    let metadata = resolve_mr_metadata(repo, Config::get().issue_trackers(), sha, "HEAD")?;
+    if let Some(cache) = &cache
+        && let Err(e) = cache.put(sha, &metadata)
+    {
+        tracing::debug!("failed to cache MR metadata for {}: {}", sha, e);
+    }
+    Ok(metadata)
+}
+
+/// Resolve (relative to `tip`) and cache metadata for every uncached SHA in
+/// `shas`, using up to `concurrency` worker threads, then trim the cache to
+/// its budget. Returns how many entries were written; per-commit failures are
+/// logged and skipped.
+pub fn prefetch_mr_metadata(
+    repo: &Repository,
+    cache: &MrMetadataCache,
+    trackers: &[IssueTrackerConfig],
+    shas: &[String],
+    tip: &str,
+    concurrency: usize,
+) -> usize {
+    let pending: Vec<&String> = shas.iter().filter(|sha| cache.get(sha).is_none()).collect();
+    if pending.is_empty() {
+        return 0;
+    }
+
+    let next = AtomicUsize::new(0);
+    let written = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, pending.len()) {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(sha) = pending.get(index) else {
+                        break;
+                    };
+                    let result = resolve_mr_metadata(repo, trackers, sha, tip)
+                        .and_then(|metadata| cache.put(sha, &metadata));
+                    match result {
+                        Ok(()) => {
+                            written.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(e) => tracing::debug!("MR metadata prefetch for {} failed: {}", sha, e),
+                    }
+                }
+            });
+        }
+    });
+
+    let eviction = cache.enforce_budget();
+    if eviction.files > 0 {
+        tracing::debug!(
+            "evicted {} MR metadata cache entries ({}) over disk_budgets.cache_max_bytes",
+            eviction.files,
+            format_bytes(eviction.bytes)
+        );
+    }
+    written.into_inner()
+}
+
+/// The newest `limit` commits in `rev` (a rev or `a..b` range)
+pub fn recent_commits(repo: &Repository, rev: &str, limit: usize) -> Vec<String> {
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "rev-list".to_string(),
+        format!("--max-count={}", limit),
+        rev.to_string(),
+    ]);
+    exec_git(&args)
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Warm the shared cache for the newest commits in `range` (a rev or
+/// `a..b` range), resolving their MRs relative to `tip`
+pub fn prefetch_recent_history(
+    repo: &Repository,
+    trackers: &[IssueTrackerConfig],
+    range: &str,
+    tip: &str,
+) -> usize {
+    let Some(cache) = MrMetadataCache::default_location() else {
+        return 0;
+    };
+    let shas = recent_commits(repo, range, PREFETCH_HISTORY_LIMIT);
+    prefetch_mr_metadata(repo, &cache, trackers, &shas, tip, PREFETCH_CONCURRENCY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    #[test]
+    fn cache_round_trips_and_expires() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MrMetadataCache::new(dir.path().to_path_buf(), CACHE_TTL_SECS);
+        let metadata = MrMetadata {
+            merge_request: Some("#42".to_string()),
+            issues: Vec::new(),
+        };
+
+        assert_eq!(cache.get(SHA), None);
+        cache.put(SHA, &metadata).unwrap();
+        assert_eq!(cache.get(SHA), Some(metadata.clone()));
+        assert!(dir.path().join("01").join(format!("{SHA}.json")).exists());
+
+        let expired = MrMetadataCache::new(dir.path().to_path_buf(), 0);
+        assert_eq!(expired.get(SHA), None);
+
+        // Abbreviated or malformed revs are never cached
+        cache.put("HEAD", &metadata).unwrap();
+        assert_eq!(cache.get("HEAD"), None);
+    }
+
+    #[test]
+    fn prefetch_resolves_each_commit_once() {
+        let tmp = TmpRepo::new().unwrap();
+        tmp.write_file("a.txt", "a\n", true).unwrap();
+        tmp.commit_with_message("Add a (#7)").unwrap();
+        tmp.write_file("b.txt", "b\n", true).unwrap();
+        tmp.commit_with_message("Add b").unwrap();
+        let repo = tmp.gitai_repo();
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = MrMetadataCache::new(dir.path().to_path_buf(), CACHE_TTL_SECS);
+        let shas = recent_commits(repo, "HEAD", 10);
+        assert_eq!(shas.len(), 2);
+
+        assert_eq!(prefetch_mr_metadata(repo, &cache, &[], &shas, "HEAD", 4), 2);
+        assert_eq!(prefetch_mr_metadata(repo, &cache, &[], &shas, "HEAD", 4), 0);
+        assert_eq!(
+            cache.get(&shas[1]).unwrap().merge_request.as_deref(),
+            Some("#7")
+        );
+        assert_eq!(cache.get(&shas[0]).unwrap().merge_request, None);
+    }
+
+    #[test]
+    fn budget_evicts_entries_until_the_cache_fits() {
+        let dir = tempfile::tempdir().unwrap();
+        let unlimited = MrMetadataCache::new(dir.path().to_path_buf(), CACHE_TTL_SECS);
+        let metadata = MrMetadata {
+            merge_request: Some("#42".to_string()),
+            issues: Vec::new(),
+        };
+        let other = "fedcba9876543210fedcba9876543210fedcba98";
+        unlimited.put(SHA, &metadata).unwrap();
+        unlimited.put(other, &metadata).unwrap();
+        assert_eq!(unlimited.enforce_budget(), Eviction::default());
+
+        let capped = unlimited.with_max_bytes(Some(1));
+        assert_eq!(capped.enforce_budget().files, 2);
+        assert_eq!(capped.get(SHA), None);
+    }
+}
@@ -20,7 +20,7 @@ pub use crate::git::refs::CommitAuthorship;
 
 pub fn write_note(repo: &Repository, commit_sha: &str, content: &str) -> Result<(), GitAiError> {
     match Config::get().notes_backend_kind() {
-        NotesBackendKind::Http => http_write_note(commit_sha, content),
+        NotesBackendKind::Http => http_write_note(&http_queue_for(repo), commit_sha, content),
         NotesBackendKind::GitNotes => crate::git::refs::notes_add(repo, commit_sha, content),
     }
 }
@@ -33,7 +33,7 @@ pub fn write_notes_batch(
         return Ok(());
     }
     match Config::get().notes_backend_kind() {
-        NotesBackendKind::Http => http_write_batch(entries),
+        NotesBackendKind::Http => http_write_batch(&http_queue_for(repo), entries),
         NotesBackendKind::GitNotes => crate::git::refs::notes_add_batch(repo, entries),
     }
 }
@@ -72,7 +72,7 @@ pub fn read_notes_batch(
                 .cloned()
                 .collect();
             if !missing_after_cache.is_empty() {
-                notes.extend(http_fetch_and_cache_notes(&missing_after_cache));
+                notes.extend(http_fetch_and_cache_notes(repo, &missing_after_cache));
             }
 
             let missing_after_http: Vec<String> = commit_shas
@@ -257,6 +257,18 @@ pub fn search_notes(repo: &Repository, pattern: &str) -> Result<Vec<String>, Git
     }
 }
 
+/// [`search_notes`], ignoring case. The HTTP
+/// backend's cache search (SQLite `LIKE`) is case-insensitive already.
+pub fn search_notes_ignore_case(
+    repo: &Repository,
+    pattern: &str,
+) -> Result<Vec<String>, GitAiError> {
+    match Config::get().notes_backend_kind() {
+        NotesBackendKind::Http => http_search_notes(repo, pattern),
+        NotesBackendKind::GitNotes => crate::git::refs::grep_ai_notes_ignore_case(repo, pattern),
+    }
+}
+
 fn http_search_notes(repo: &Repository, pattern: &str) -> Result<Vec<String>, GitAiError> {
     let mut shas: HashSet<String> = {
         let db = crate::notes::db::NotesDatabase::global()?;
@@ -381,7 +393,6 @@ pub fn materialize_notes_for_display(repo: &Repository, limit: usize) -> Result<
 /// This function is a best-effort operation: errors are logged but not propagated
 /// (callers should treat failure as a cache miss, not a hard error).
 pub fn warm_cache_for_remote(repo: &Repository, remote: &str) -> Result<(), GitAiError> {
-    use crate::api::client::{ApiClient, ApiContext};
     use crate::git::repository::exec_git;
 
     // 1. Walk recent history. Prefer the remote's default branch; fall back to HEAD.
@@ -474,25 +485,19 @@ pub fn warm_cache_for_remote(repo: &Repository, remote: &str) -> Result<(), GitA
         uncached.len()
     );
 
-    // 3. Batch-fetch from the HTTP backend (chunks of 100).
-    let cfg = crate::config::Config::fresh();
-    let backend_url = match cfg.notes_backend_url() {
-        Some(url) => url.to_string(),
-        None => {
-            tracing::debug!(
-                "warm_cache_for_remote: notes_backend.backend_url is not configured; skipping"
-            );
-            return Ok(());
-        }
-    };
-    let ctx = ApiContext::new(Some(backend_url));
-    let client = ApiClient::new(ctx);
-
+    // 3. Batch-fetch from the backend owning this repository (chunks of 100).
+    let target = crate::notes::backends::target_for_repository(&Config::fresh(), repo);
+    if target.backend_url.is_none() {
+        tracing::debug!(
+            "warm_cache_for_remote: notes_backend.backend_url is not configured; skipping"
+        );
+        return Ok(());
+    }
     // Skip when not authenticated (matches daemon flush_notes pattern).
-    if !client.is_logged_in() && !client.has_api_key() {
+    let Some(client) = target.client() else {
         tracing::debug!("warm_cache_for_remote: not authenticated; skipping");
         return Ok(());
-    }
+    };
 
     for chunk in uncached.chunks(100) {
         let sha_refs: Vec<&str> = chunk.iter().map(|s| s.as_str()).collect();
@@ -539,23 +544,21 @@ pub fn warm_cache_for_remote(repo: &Repository, remote: &str) -> Result<(), GitA
 
 // --- HTTP backend helpers (private) ---
 
-fn http_write_note(commit_sha: &str, content: &str) -> Result<(), GitAiError> {
-    let db = crate::notes::db::NotesDatabase::global()?;
-    let mut db_lock = db
-        .lock()
-        .map_err(|e| GitAiError::Generic(format!("notes-db lock: {}", e)))?;
-    db_lock.upsert_note(commit_sha, content)?;
-    drop(db_lock);
-    crate::daemon::telemetry_handle::submit_notes();
-    Ok(())
+/// The upload queue for notes written in `repo`
+fn http_queue_for(repo: &Repository) -> String {
+    crate::notes::backends::target_for_repository(Config::get(), repo).queue
 }
 
-fn http_write_batch(entries: &[(String, String)]) -> Result<(), GitAiError> {
+fn http_write_note(queue: &str, commit_sha: &str, content: &str) -> Result<(), GitAiError> {
+    http_write_batch(queue, &[(commit_sha.to_string(), content.to_string())])
+}
+
+fn http_write_batch(queue: &str, entries: &[(String, String)]) -> Result<(), GitAiError> {
     let db = crate::notes::db::NotesDatabase::global()?;
     let mut db_lock = db
         .lock()
         .map_err(|e| GitAiError::Generic(format!("notes-db lock: {}", e)))?;
-    db_lock.upsert_notes_batch(entries)?;
+    db_lock.upsert_notes_batch_for_backend(queue, entries)?;
     drop(db_lock);
     crate::daemon::telemetry_handle::submit_notes();
     Ok(())
@@ -578,22 +581,19 @@ fn http_read_notes(commit_shas: &[String]) -> HashMap<String, String> {
     db_lock.get_notes(&refs).unwrap_or_default()
 }
 
-fn http_fetch_and_cache_notes(commit_shas: &[String]) -> HashMap<String, String> {
+fn http_fetch_and_cache_notes(
+    repo: &Repository,
+    commit_shas: &[String],
+) -> HashMap<String, String> {
     if commit_shas.is_empty() {
         return HashMap::new();
     }
 
-    let cfg = Config::fresh();
-    let Some(backend_url) = cfg.notes_backend_url().map(str::to_string) else {
+    let target = crate::notes::backends::target_for_repository(&Config::fresh(), repo);
+    let Some(client) = target.client() else {
         return HashMap::new();
     };
 
-    let ctx = crate::api::client::ApiContext::new(Some(backend_url));
-    let client = crate::api::client::ApiClient::new(ctx);
-    if !client.is_logged_in() && !client.has_api_key() {
-        return HashMap::new();
-    }
-
     let mut fetched = HashMap::new();
     for chunk in commit_shas.chunks(100) {
         let refs: Vec<&str> = chunk.iter().map(String::as_str).collect();
@@ -628,6 +628,7 @@ fn http_check_exists(commit_shas: &[String]) -> HashSet<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::notes::db::DEFAULT_BACKEND;
 
     /// With kind=Http, the http helpers upsert into notes-db (synced=0) and the
     /// read helper returns the cached value. This tests the private http_* helpers
@@ -646,7 +647,12 @@ mod tests {
         }
 
         // Write directly via http helper (no repo needed).
-        http_write_note("abc123def456abc123def456abc123def456abc1", "test content").expect("write");
+        http_write_note(
+            DEFAULT_BACKEND,
+            "abc123def456abc123def456abc123def456abc1",
+            "test content",
+        )
+        .expect("write");
 
         // Read back from cache.
         let content = http_read_note("abc123def456abc123def456abc123def456abc1");
@@ -686,8 +692,8 @@ mod tests {
         let sha2 = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb".to_string();
         let sha3 = "cccccccccccccccccccccccccccccccccccccccc".to_string();
 
-        http_write_note(&sha1, "content-a").expect("write sha1");
-        http_write_note(&sha2, "content-b").expect("write sha2");
+        http_write_note(DEFAULT_BACKEND, &sha1, "content-a").expect("write sha1");
+        http_write_note(DEFAULT_BACKEND, &sha2, "content-b").expect("write sha2");
 
         // sha3 is not written — should not appear in result.
         let result = http_read_notes(&[sha1.clone(), sha2.clone(), sha3.clone()]);
@@ -718,7 +724,12 @@ mod tests {
 
         let tmp = TmpRepo::new().expect("TmpRepo::new");
         let sha = "dddddddddddddddddddddddddddddddddddddddd";
-        http_write_note(sha, r#"{"sessions": {"s_searchable123456": {}}}"#).expect("write");
+        http_write_note(
+            DEFAULT_BACKEND,
+            sha,
+            r#"{"sessions": {"s_searchable123456": {}}}"#,
+        )
+        .expect("write");
 
         let matches =
             http_search_notes(tmp.gitai_repo(), "\"s_searchable123456\"").expect("search");
@@ -810,7 +821,7 @@ mod tests {
         let sha = repo.commit_all("msg").expect("commit");
 
         // Write a note for this SHA using the Http helper.
-        http_write_note(&sha, "some-note-content").expect("http write");
+        http_write_note(DEFAULT_BACKEND, &sha, "some-note-content").expect("http write");
 
         // Confirm it is in notes-db with synced=0.
         let db = crate::notes::db::NotesDatabase::global().expect("global db");
@@ -868,7 +879,7 @@ mod tests {
         let sha = repo.commit_all("test commit").expect("commit");
 
         // Put a note in the cache for this commit.
-        http_write_note(&sha, "display-note-content").expect("write note");
+        http_write_note(DEFAULT_BACKEND, &sha, "display-note-content").expect("write note");
 
         // Materialize the cache into refs/notes/ai-display.
         let count = materialize_notes_for_display(repo.gitai_repo(), 50).expect("materialize");
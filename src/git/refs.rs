@@ -2,8 +2,10 @@ use crate::authorship::authorship_log_serialization::{AUTHORSHIP_LOG_VERSION, Au
 use crate::authorship::working_log::Checkpoint;
 use crate::error::GitAiError;
 use crate::git::repository::{Repository, exec_git, exec_git_allow_nonzero, exec_git_stdin};
+use crate::utils::LockFile;
 use serde_json;
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 // Modern refspecs without force to enable proper merging
 pub const AI_AUTHORSHIP_REFNAME: &str = "ai";
@@ -11,6 +13,29 @@ pub const AI_AUTHORSHIP_FULL_REF: &str = "refs/notes/ai";
 pub const AI_AUTHORSHIP_FORK_TRACKING_REF: &str = "refs/notes/ai-remote/fork";
 pub const AI_AUTHORSHIP_PUSH_REFSPEC: &str = "refs/notes/ai:refs/notes/ai";
 
+/// Longest a notes write waits for another worktree's write to the same ref
+const NOTES_REF_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Take the per-ref lock guarding a read-tip/fast-import update of a notes ref.
+///
+/// Every worktree of a repository shares `refs/notes/ai` through the common
+/// git dir, so hooks committing concurrently in different worktrees would
+/// otherwise each build on the same tip and all but one fast-import would be
+/// rejected as a non-fast-forward. The lock lives in the common dir and is
+/// held only for the update itself; hooks run after it is released.
+fn lock_notes_ref(repo: &Repository, notes_ref: &str) -> Result<LockFile, GitAiError> {
+    let lock_dir = repo.common_dir().join("ai").join("locks");
+    std::fs::create_dir_all(&lock_dir)?;
+    let lock_path = lock_dir.join(format!("{}.lock", notes_ref.replace('/', "-")));
+    LockFile::acquire(&lock_path, NOTES_REF_LOCK_TIMEOUT).ok_or_else(|| {
+        GitAiError::Generic(format!(
+            "Timed out after {}s waiting for another git-ai process to update {}",
+            NOTES_REF_LOCK_TIMEOUT.as_secs(),
+            notes_ref
+        ))
+    })
+}
+
 pub(in crate::git) fn notes_add(
     repo: &Repository,
     commit_sha: &str,
@@ -376,6 +401,7 @@ pub(in crate::git) fn notes_add_batch(
         return Ok(());
     }
 
+    let notes_lock = lock_notes_ref(repo, AI_AUTHORSHIP_FULL_REF)?;
     let mut args = repo.global_args_for_exec();
     args.push("rev-parse".to_string());
     args.push("--verify".to_string());
@@ -435,6 +461,7 @@ pub(in crate::git) fn notes_add_batch(
     fast_import_args.push("fast-import".to_string());
     fast_import_args.push("--quiet".to_string());
     exec_git_stdin(&fast_import_args, &script)?;
+    drop(notes_lock);
     crate::authorship::git_ai_hooks::post_notes_updated(repo, &deduped_entries);
 
     Ok(())
@@ -452,6 +479,7 @@ pub(in crate::git) fn notes_add_blob_batch(
         return Ok(());
     }
 
+    let notes_lock = lock_notes_ref(repo, AI_AUTHORSHIP_FULL_REF)?;
     let mut args = repo.global_args_for_exec();
     args.push("rev-parse".to_string());
     args.push("--verify".to_string());
@@ -502,6 +530,7 @@ pub(in crate::git) fn notes_add_blob_batch(
     fast_import_args.push("fast-import".to_string());
     fast_import_args.push("--quiet".to_string());
     exec_git_stdin(&fast_import_args, &script)?;
+    drop(notes_lock);
 
     let has_post_notes_updated_hooks = crate::config::Config::get()
         .git_ai_hook_commands("post_notes_updated")
@@ -792,6 +821,7 @@ pub fn merge_notes_from_ref(repo: &Repository, source_ref: &str) -> Result<(), G
 /// large monorepos with thousands of notes.
 pub fn fallback_merge_notes_ours(repo: &Repository, source_ref: &str) -> Result<(), GitAiError> {
     let local_ref = format!("refs/notes/{}", AI_AUTHORSHIP_REFNAME);
+    let _notes_lock = lock_notes_ref(repo, &local_ref)?;
 
     // 1. List notes from both refs
     let source_notes = list_all_notes(repo, source_ref)?;
@@ -909,14 +939,40 @@ pub(in crate::git) fn grep_ai_notes(
     repo: &Repository,
     pattern: &str,
 ) -> Result<Vec<String>, GitAiError> {
+    grep_ai_notes_with(repo, pattern, false)
+}
+
+/// [`grep_ai_notes`], ignoring case
+pub(in crate::git) fn grep_ai_notes_ignore_case(
+    repo: &Repository,
+    pattern: &str,
+) -> Result<Vec<String>, GitAiError> {
+    grep_ai_notes_with(repo, pattern, true)
+}
+
+/// `git grep` the notes tree. No notes ref, or no match (grep's exit 1), is
+/// an empty result rather than an error.
+fn grep_ai_notes_with(
+    repo: &Repository,
+    pattern: &str,
+    ignore_case: bool,
+) -> Result<Vec<String>, GitAiError> {
+    if !ref_exists(repo, "refs/notes/ai") {
+        return Ok(Vec::new());
+    }
+
     let mut args = repo.global_args_for_exec();
     args.push("--no-pager".to_string());
     args.push("grep".to_string());
-    args.push("-nI".to_string());
+    args.push(if ignore_case { "-niI" } else { "-nI" }.to_string());
     args.push(pattern.to_string());
     args.push("refs/notes/ai".to_string());
 
-    let output = exec_git(&args)?;
+    let output = match exec_git(&args) {
+        Ok(output) => output,
+        Err(GitAiError::GitCliError { code: Some(1), .. }) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
     let stdout = String::from_utf8(output.stdout)
         .map_err(|_| GitAiError::Generic("Failed to parse git grep output".to_string()))?;
 
@@ -1017,6 +1073,13 @@ pub mod git_backend_for_tests {
         super::grep_ai_notes(repo, pattern)
     }
 
+    pub fn grep_ai_notes_ignore_case(
+        repo: &Repository,
+        pattern: &str,
+    ) -> Result<Vec<String>, GitAiError> {
+        super::grep_ai_notes_ignore_case(repo, pattern)
+    }
+
     pub fn note_blob_oids_for_commits(
         repo: &Repository,
         commit_shas: &[String],
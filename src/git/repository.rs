@@ -1102,6 +1102,13 @@ impl Repository {
         args
     }
 
+    // Util to run a git command against this repository and return its stdout
+    pub(crate) fn git_output(&self, args: &[&str]) -> Result<String, GitAiError> {
+        let mut full_args = self.global_args_for_exec();
+        full_args.extend(args.iter().map(|arg| arg.to_string()));
+        Ok(String::from_utf8(exec_git(&full_args)?.stdout)?)
+    }
+
     pub fn require_pre_command_head(&mut self) {
         if self.pre_command_base_commit.is_some() || self.pre_command_refname.is_some() {
             return;
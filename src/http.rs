@@ -1,6 +1,12 @@
+use crate::app_context::AppContext;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
 use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Build a ureq Agent that uses the platform's native TLS library.
 ///
@@ -69,3 +75,301 @@ pub fn send_with_body(request: ureq::Request, body: &str) -> Result<Response, St
         Err(ureq::Error::Transport(err)) => Err(err.to_string()),
     }
 }
+
+/// Size cap for [`HttpCache::default_location`] when
+/// `disk_budgets.cache_max_bytes` isn't set
+pub const DEFAULT_HTTP_CACHE_MAX_BYTES: u64 = 32 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Unix seconds until which `Cache-Control: max-age` lets the body be
+    /// reused without asking the server
+    fresh_until: Option<u64>,
+    /// Base64
+    body: String,
+}
+
+/// What a response's `Cache-Control` allows
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CacheControl {
+    no_store: bool,
+    max_age: Option<u64>,
+}
+
+impl CacheControl {
+    fn parse(header: Option<&str>) -> Self {
+        let mut control = CacheControl::default();
+        for directive in header.unwrap_or_default().split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+            if directive == "no-store" {
+                control.no_store = true;
+            } else if directive == "no-cache" {
+                control.max_age = Some(0);
+            } else if let Some(secs) = directive.strip_prefix("max-age=") {
+                // no-cache wins over any max-age
+                if control.max_age != Some(0) {
+                    control.max_age = secs.trim_matches('"').parse().ok();
+                }
+            }
+        }
+        control
+    }
+}
+
+/// On-disk cache for host API GETs. Responses with an `ETag` or
+/// `Last-Modified` are revalidated with `If-None-Match` /
+/// `If-Modified-Since`, so an unchanged resource costs a 304 instead of a
+/// full response; `Cache-Control: max-age` skips the request entirely while
+/// fresh, and `no-store` responses are never written.
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl HttpCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    /// `http_cache` in the cache dir (see [`AppContext`]), capped by
+    /// `disk_budgets.cache_max_bytes`
+    pub fn default_location() -> Option<Self> {
+        let max_bytes = crate::config::Config::get()
+            .disk_budgets()
+            .cache_max_bytes
+            .unwrap_or(DEFAULT_HTTP_CACHE_MAX_BYTES);
+        Some(Self::new(
+            AppContext::current().cache_dir.join("http_cache"),
+            max_bytes,
+        ))
+    }
+
+    /// Entries are keyed by method, URL and request headers, so responses
+    /// fetched with one token are never served for another
+    fn entry_path(&self, request: &ureq::Request) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(request.method());
+        hasher.update([0]);
+        hasher.update(request.url());
+        let mut names = request.header_names();
+        names.sort();
+        for name in names {
+            hasher.update([0]);
+            hasher.update(&name);
+            hasher.update([0]);
+            hasher.update(request.header(&name).unwrap_or_default());
+        }
+        let key = format!("{:x}", hasher.finalize());
+        self.dir.join(&key[..2]).join(format!("{}.json", key))
+    }
+
+    fn read_entry(path: &Path) -> Option<CachedResponse> {
+        serde_json::from_slice(&fs::read(path).ok()?).ok()
+    }
+
+    fn write_entry(&self, path: &Path, entry: &CachedResponse) {
+        let result = (|| -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+            fs::write(&tmp, serde_json::to_vec(entry)?)?;
+            fs::rename(&tmp, path)
+        })();
+        if let Err(e) = result {
+            tracing::debug!("failed to write HTTP cache entry {}: {}", path.display(), e);
+            return;
+        }
+        crate::disk_budget::evict_lru(&self.dir, self.max_bytes);
+    }
+
+    /// Like [`send`], answering from the cache when the server allows it.
+    /// Non-GET requests are sent as-is.
+    pub fn send(&self, request: ureq::Request) -> Result<Response, String> {
+        if request.method() != "GET" {
+            return send(request);
+        }
+        let path = self.entry_path(&request);
+        let cached = Self::read_entry(&path);
+        let now = now_secs();
+
+        let mut request = request;
+        if let Some(entry) = &cached {
+            if entry.fresh_until.is_some_and(|until| now < until)
+                && let Some(body) = decode_body(entry)
+            {
+                crate::disk_budget::touch(&path);
+                return Ok(Response {
+                    status_code: 200,
+                    body,
+                });
+            }
+            if let Some(etag) = &entry.etag {
+                request = request.set("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.set("If-Modified-Since", last_modified);
+            }
+        }
+
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_code, response)) => return read_ureq_response(response),
+            Err(ureq::Error::Transport(err)) => return Err(err.to_string()),
+        };
+        let control = CacheControl::parse(response.header("Cache-Control"));
+        let fresh_until = control.max_age.map(|secs| now.saturating_add(secs));
+
+        if response.status() == 304
+            && let Some(mut entry) = cached
+            && let Some(body) = decode_body(&entry)
+        {
+            entry.fresh_until = fresh_until;
+            self.write_entry(&path, &entry);
+            return Ok(Response {
+                status_code: 200,
+                body,
+            });
+        }
+
+        let etag = response.header("ETag").map(str::to_string);
+        let last_modified = response.header("Last-Modified").map(str::to_string);
+        let response = read_ureq_response(response)?;
+        if response.status_code == 200
+            && !control.no_store
+            && (etag.is_some() || last_modified.is_some() || fresh_until.is_some())
+        {
+            let entry = CachedResponse {
+                etag,
+                last_modified,
+                fresh_until,
+                body: base64::engine::general_purpose::STANDARD.encode(&response.body),
+            };
+            self.write_entry(&path, &entry);
+        } else if control.no_store {
+            let _ = fs::remove_file(&path);
+        }
+        Ok(response)
+    }
+}
+
+fn decode_body(entry: &CachedResponse) -> Option<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(&entry.body)
+        .ok()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// [`send`] through the shared [`HttpCache`], falling back to a plain send
+/// when there is no home directory to cache in
+pub fn send_cached(request: ureq::Request) -> Result<Response, String> {
+    match HttpCache::default_location() {
+        Some(cache) => cache.send(request),
+        None => send(request),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// Serve `responses` in order, one connection each, returning the
+    /// request heads received
+    fn serve(responses: Vec<&'static str>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/issue", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut heads = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut head = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    head.push_str(&line.to_ascii_lowercase());
+                }
+                stream.write_all(response.as_bytes()).unwrap();
+                heads.push(head);
+            }
+            heads
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn revalidates_with_etag_and_serves_body_on_304() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello",
+            "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::new(dir.path().to_path_buf(), DEFAULT_HTTP_CACHE_MAX_BYTES);
+        let agent = build_agent(Some(5));
+
+        for _ in 0..2 {
+            let response = cache
+                .send(agent.get(&url).set("Authorization", "t"))
+                .unwrap();
+            assert_eq!(response.status_code, 200);
+            assert_eq!(response.as_bytes(), b"hello");
+        }
+
+        let heads = server.join().unwrap();
+        assert!(!heads[0].contains("if-none-match"), "{}", heads[0]);
+        assert!(heads[1].contains("if-none-match: \"v1\""), "{}", heads[1]);
+    }
+
+    #[test]
+    fn fresh_max_age_skips_the_request_and_no_store_is_not_cached() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 200 OK\r\nCache-Control: private, max-age=600\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+            "HTTP/1.1 200 OK\r\nCache-Control: no-store\r\nETag: \"x\"\r\nContent-Length: 2\r\nConnection: close\r\n\r\nno",
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::new(dir.path().to_path_buf(), DEFAULT_HTTP_CACHE_MAX_BYTES);
+        let agent = build_agent(Some(5));
+
+        // The second call is answered from the cache
+        for _ in 0..2 {
+            let response = cache.send(agent.get(&url)).unwrap();
+            assert_eq!(response.as_bytes(), b"ok");
+        }
+        // A different token is a different entry
+        let response = cache
+            .send(agent.get(&url).set("Authorization", "other"))
+            .unwrap();
+        assert_eq!(response.as_bytes(), b"no");
+        assert_eq!(server.join().unwrap().len(), 2);
+        // Only the max-age response was stored (one key-prefix directory)
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn parses_cache_control() {
+        assert_eq!(CacheControl::parse(None), CacheControl::default());
+        assert_eq!(
+            CacheControl::parse(Some("public, max-age=60")).max_age,
+            Some(60)
+        );
+        assert_eq!(
+            CacheControl::parse(Some("max-age=60, no-cache")).max_age,
+            Some(0)
+        );
+        assert!(CacheControl::parse(Some("No-Store")).no_store);
+    }
+}
@@ -0,0 +1,256 @@
+//! Link issue keys found in commit messages and branch names to the
+//! configured Jira/Linear trackers, resolving title and status when the
+//! tracker's API token is available.
+
+use crate::config::{IssueTrackerConfig, IssueTrackerKind};
+use crate::error::GitAiError;
+use crate::http;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Jira and Linear both use `PROJ-123` keys
+const DEFAULT_KEY_PATTERN: &str = r"\b[A-Z][A-Z0-9]+-[1-9][0-9]*\b";
+
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+
+const LINEAR_ISSUE_QUERY: &str = "query($id: String!) { issue(id: $id) { title state { name } } }";
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IssueLink {
+    pub key: String,
+    pub tracker: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct IssueDetails {
+    title: Option<String>,
+    status: Option<String>,
+}
+
+/// Lookups by (tracker URL, key), so long-running servers don't refetch
+static RESOLVED: Lazy<Mutex<HashMap<(String, String), IssueDetails>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Keys matched by `pattern` in `texts`, uppercased, in first-seen order.
+/// A pattern with a capture group contributes the first group.
+pub fn find_issue_keys(pattern: &Regex, texts: &[&str]) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for text in texts {
+        for caps in pattern.captures_iter(text) {
+            let Some(m) = caps.get(1).or_else(|| caps.get(0)) else {
+                continue;
+            };
+            let key = m.as_str().to_uppercase();
+            let valid = !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if valid && !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+    keys
+}
+
+/// Issue links for every configured tracker whose keys appear in `texts`
+pub fn link_issues(
+    trackers: &[IssueTrackerConfig],
+    texts: &[&str],
+) -> Result<Vec<IssueLink>, GitAiError> {
+    let mut links = Vec::new();
+    for tracker in trackers {
+        let pattern_src = tracker.pattern.as_deref().unwrap_or(DEFAULT_KEY_PATTERN);
+        let pattern = Regex::new(pattern_src).map_err(|e| {
+            GitAiError::Generic(format!(
+                "Invalid issue key pattern '{}' for {}: {}",
+                pattern_src, tracker.url, e
+            ))
+        })?;
+        for key in find_issue_keys(&pattern, texts) {
+            let details = resolve(tracker, &key);
+            links.push(IssueLink {
+                url: issue_url(tracker, &key),
+                tracker: tracker.kind.as_str().to_string(),
+                title: details.title,
+                status: details.status,
+                key,
+            });
+        }
+    }
+    Ok(links)
+}
+
+fn issue_url(tracker: &IssueTrackerConfig, key: &str) -> String {
+    let base = tracker.url.trim_end_matches('/');
+    match tracker.kind {
+        IssueTrackerKind::Jira => format!("{}/browse/{}", base, key),
+        IssueTrackerKind::Linear => format!("{}/issue/{}", base, key),
+    }
+}
+
+fn resolve(tracker: &IssueTrackerConfig, key: &str) -> IssueDetails {
+    let Some(token) = tracker
+        .token_env
+        .as_deref()
+        .and_then(|name| std::env::var(name).ok())
+        .filter(|token| !token.is_empty())
+    else {
+        return IssueDetails::default();
+    };
+
+    let cache_key = (tracker.url.clone(), key.to_string());
+    if let Some(details) = RESOLVED.lock().unwrap().get(&cache_key) {
+        return details.clone();
+    }
+
+    let fetched = match tracker.kind {
+        IssueTrackerKind::Jira => fetch_jira(tracker, key, &token),
+        IssueTrackerKind::Linear => fetch_linear(key, &token),
+    };
+    // Failed lookups degrade to a plain link rather than failing the report
+    let details = fetched.unwrap_or_else(|e| {
+        tracing::debug!("Failed to resolve issue {}: {}", key, e);
+        IssueDetails::default()
+    });
+    RESOLVED.lock().unwrap().insert(cache_key, details.clone());
+    details
+}
+
+fn fetch_jira(
+    tracker: &IssueTrackerConfig,
+    key: &str,
+    token: &str,
+) -> Result<IssueDetails, String> {
+    let url = format!(
+        "{}/rest/api/2/issue/{}?fields=summary,status",
+        tracker.url.trim_end_matches('/'),
+        key
+    );
+    // Jira Cloud takes email + API token as basic auth; Server/Data Center
+    // personal access tokens are bearer tokens
+    let authorization = match tracker.email.as_deref() {
+        Some(email) => format!(
+            "Basic {}",
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", email, token))
+        ),
+        None => format!("Bearer {}", token),
+    };
+    let request = http::build_agent(Some(REQUEST_TIMEOUT_SECS))
+        .get(&url)
+        .set("Accept", "application/json")
+        .set("Authorization", &authorization);
+    // Reports and prefetches look up the same issues repeatedly
+    let body = send_json(http::send_cached(request)?)?;
+    Ok(IssueDetails {
+        title: string_at(&body, &["fields", "summary"]),
+        status: string_at(&body, &["fields", "status", "name"]),
+    })
+}
+
+fn fetch_linear(key: &str, token: &str) -> Result<IssueDetails, String> {
+    let payload = serde_json::json!({
+        "query": LINEAR_ISSUE_QUERY,
+        "variables": { "id": key },
+    });
+    let request = http::build_agent(Some(REQUEST_TIMEOUT_SECS))
+        .post(LINEAR_API_URL)
+        .set("Content-Type", "application/json")
+        .set("Authorization", token);
+    let body = send_json(http::send_with_body(request, &payload.to_string())?)?;
+    Ok(IssueDetails {
+        title: string_at(&body, &["data", "issue", "title"]),
+        status: string_at(&body, &["data", "issue", "state", "name"]),
+    })
+}
+
+fn send_json(response: http::Response) -> Result<serde_json::Value, String> {
+    if !(200..300).contains(&response.status_code) {
+        return Err(format!("HTTP {}", response.status_code));
+    }
+    serde_json::from_slice(response.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn string_at(value: &serde_json::Value, path: &[&str]) -> Option<String> {
+    path.iter()
+        .try_fold(value, |node, field| node.get(field))
+        .and_then(|node| node.as_str())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(kind: IssueTrackerKind, url: &str, pattern: Option<&str>) -> IssueTrackerConfig {
+        IssueTrackerConfig {
+            kind,
+            url: url.to_string(),
+            pattern: pattern.map(str::to_string),
+            token_env: None,
+            email: None,
+        }
+    }
+
+    #[test]
+    fn keys_come_from_messages_and_branch_names_once_each() {
+        let pattern = Regex::new(DEFAULT_KEY_PATTERN).unwrap();
+        let keys = find_issue_keys(
+            &pattern,
+            &[
+                "Merge pull request #7 from acme/PAY-142-retry-webhooks",
+                "Retry webhooks (PAY-142)\n\nAlso touches OPS-9.",
+            ],
+        );
+        assert_eq!(keys, vec!["PAY-142".to_string(), "OPS-9".to_string()]);
+    }
+
+    #[test]
+    fn custom_patterns_use_their_first_group() {
+        let pattern = Regex::new(r"(?i)\b(eng-\d+)-").unwrap();
+        assert_eq!(
+            find_issue_keys(&pattern, &["dev/eng-311-fix-login"]),
+            vec!["ENG-311".to_string()]
+        );
+    }
+
+    #[test]
+    fn links_point_at_each_tracker_without_a_token() {
+        let trackers = [
+            tracker(IssueTrackerKind::Jira, "https://acme.atlassian.net/", None),
+            tracker(
+                IssueTrackerKind::Linear,
+                "https://linear.app/acme",
+                Some(r"\bENG-\d+\b"),
+            ),
+        ];
+        let links = link_issues(&trackers, &["Fix PAY-1 and ENG-2"]).unwrap();
+        let urls: Vec<&str> = links.iter().map(|link| link.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://acme.atlassian.net/browse/PAY-1",
+                "https://acme.atlassian.net/browse/ENG-2",
+                "https://linear.app/acme/issue/ENG-2",
+            ]
+        );
+        assert!(links.iter().all(|link| link.status.is_none()));
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported() {
+        let trackers = [tracker(IssueTrackerKind::Jira, "https://j", Some("("))];
+        assert!(link_issues(&trackers, &["PAY-1"]).is_err());
+    }
+}
@@ -1,22 +1,29 @@
 pub mod api;
+pub mod app_context;
 pub mod auth;
 pub mod authorship;
+pub mod capabilities;
 pub(crate) mod checkpoint_content_budget;
 pub mod ci;
 pub mod commands;
+pub mod commit_lint;
 pub mod config;
 pub mod daemon;
 pub mod diagnostic_sentinels;
 pub mod diagnostics;
+pub mod disk_budget;
 pub mod error;
 pub mod feature_flags;
 pub mod git;
 pub mod http;
+pub mod issue_tracker;
 pub mod mdm;
 pub mod metrics;
 pub mod notes;
 pub mod observability;
+pub mod output;
 pub mod process_timeout;
+pub mod redaction;
 pub mod repo_url;
 pub(crate) mod sandbox;
 pub mod sqlite;
@@ -1,4 +1,5 @@
 use clap::Parser;
+use git_ai::app_context::split_path_flags;
 use git_ai::commands;
 use git_ai::utils::{SuperuserCheckResult, check_superuser_guard, print_superuser_warning};
 
@@ -13,6 +14,8 @@ struct Cli {
 }
 
 fn is_superuser_exempt_command(args: &[String]) -> bool {
+    // Look past global path flags (`--state-dir <dir>`, ...) to the subcommand
+    let args = split_path_flags(args).map_or(args, |(rest, _)| rest);
     let first = match args.first() {
         Some(a) => a.as_str(),
         None => return true,
@@ -28,10 +31,22 @@ fn is_superuser_exempt_command(args: &[String]) -> bool {
             | "upgrade"
             | "debug"
             | "uninstall-hooks"
+            // Run by git as the editor for a commit made through the proxy
+            | "commit-msg-editor"
     ) || (first == "bg" || first == "d" || first == "daemon")
         && args
             .get(1)
             .is_some_and(|s| s == "run" || s == "status" || s == "shutdown")
+        // Machine-wide client preferences, and every user's profile, can only
+        // be written as root
+        || first == "clients"
+            && (args
+                .windows(2)
+                .any(|pair| pair[0] == "--scope" && pair[1] == "system")
+                || args.iter().any(|arg| arg == "--all-users"))
+        // MDM agents run it as root, and the policy's scope (possibly
+        // system) is only known once it's fetched
+        || first == "mdm" && args.get(1).is_some_and(|s| s == "sync")
 }
 
 fn main() {
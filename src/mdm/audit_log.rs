@@ -0,0 +1,183 @@
+//! Append-only record of every preference change git-ai makes to a git
+//! client, for security teams tracing configuration changes. Each change is
+//! one JSON line in `<state dir>/audit.log` with when, what, which client, each
+//! setting's value before and after, and who ran it. With
+//! `clients.audit_syslog` it also goes to syslog (`logger`) or, on Windows,
+//! the Application event log (`eventcreate`).
+
+use crate::app_context::AppContext;
+use crate::config::Config;
+use crate::mdm::change_summary::key_changes;
+use crate::mdm::git_client_installer::other_user;
+use serde_json::{Value, json};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Who started the run, passed on to the runs `clients --all-users` starts as
+/// each profile's owner
+pub const INVOKING_USER_ENV: &str = "GIT_AI_AUDIT_INVOKING_USER";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    Install,
+    Uninstall,
+    /// Put back from a snapshot by `clients restore`
+    Restore,
+    /// Put back from a snapshot after a later client failed
+    Rollback,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::Install => "install",
+            AuditAction::Uninstall => "uninstall",
+            AuditAction::Restore => "restore",
+            AuditAction::Rollback => "rollback",
+        }
+    }
+}
+
+pub fn audit_log_path() -> Option<PathBuf> {
+    Some(AppContext::current().state_dir.join("audit.log"))
+}
+
+/// The user who ran git-ai, as opposed to the one it configures
+pub fn invoking_user() -> String {
+    [INVOKING_USER_ENV, "SUDO_USER", "USER", "USERNAME"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok().filter(|user| !user.is_empty()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Record that `action` changed `client_id`'s preferences by `diff`, or, for
+/// a restore or rollback, from `snapshot`. Failing to record is a warning,
+/// never a failure of the change itself.
+pub fn record(action: AuditAction, client_id: &str, diff: Option<&str>, snapshot: Option<&Path>) {
+    let entry = entry(action, client_id, diff, snapshot);
+    if let Err(e) = append(&entry) {
+        eprintln!("Warning: could not write the audit log: {}", e);
+    }
+    if Config::get().clients().audit_syslog == Some(true) {
+        send_to_system_log(&entry);
+    }
+}
+
+fn entry(
+    action: AuditAction,
+    client_id: &str,
+    diff: Option<&str>,
+    snapshot: Option<&Path>,
+) -> Value {
+    let changes: Vec<Value> = diff
+        .map(key_changes)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|change| {
+            json!({
+                "file": change.file,
+                "key": change.key,
+                "before": change.before,
+                "after": change.after,
+            })
+        })
+        .collect();
+    let mut entry = json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "action": action.as_str(),
+        "client": client_id,
+        "user": invoking_user(),
+        "changes": changes,
+    });
+    if let Some(target) = other_user() {
+        entry["target_user"] = json!(target);
+    }
+    if let Some(snapshot) = snapshot {
+        entry["snapshot"] = json!(snapshot.display().to_string());
+    }
+    entry
+}
+
+fn append(entry: &Value) -> std::io::Result<()> {
+    let path = audit_log_path()
+        .ok_or_else(|| std::io::Error::other("no home directory for the audit log"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", entry)
+}
+
+fn send_to_system_log(entry: &Value) {
+    let message = format!("git-ai audit: {}", entry);
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("eventcreate");
+        command.args([
+            "/L",
+            "APPLICATION",
+            "/T",
+            "INFORMATION",
+            "/SO",
+            "git-ai",
+            "/ID",
+            "100",
+            "/D",
+            &message,
+        ]);
+        command
+    } else {
+        let mut command = Command::new("logger");
+        command.args(["-t", "git-ai", "-p", "auth.info", "--", &message]);
+        command
+    };
+    let sent = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    if !matches!(sent, Ok(status) if status.success()) {
+        eprintln!("Warning: could not send the audit entry to the system log");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_records_each_setting_before_and_after() {
+        let diff = "--- a/home/dev/.config/GitFiend/config.json\n\
+            +++ b/home/dev/.config/GitFiend/config.json\n\
+            @@ -1,3 +1,3 @@\n\
+             {\n\
+            -  \"gitPath\": \"\"\n\
+            +  \"gitPath\": \"/home/dev/.git-ai/bin/git\"\n\
+             }\n";
+        let installed = entry(AuditAction::Install, "gitfiend", Some(diff), None);
+        assert_eq!(installed["action"], "install");
+        assert_eq!(installed["client"], "gitfiend");
+        assert!(installed["user"].is_string());
+        assert_eq!(
+            installed["changes"][0],
+            json!({
+                "file": "/home/dev/.config/GitFiend/config.json",
+                "key": "gitPath",
+                "before": "",
+                "after": "/home/dev/.git-ai/bin/git",
+            })
+        );
+        assert!(installed.get("snapshot").is_none());
+
+        let restored = entry(
+            AuditAction::Restore,
+            "gitfiend",
+            None,
+            Some(Path::new("/snapshots/1")),
+        );
+        assert_eq!(restored["action"], "restore");
+        assert_eq!(restored["changes"], json!([]));
+        assert_eq!(restored["snapshot"], "/snapshots/1");
+    }
+}
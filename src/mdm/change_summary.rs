@@ -0,0 +1,366 @@
+//! One summary of a dry run's pending client changes: which clients would
+//! change, the files (or preference domains and registry keys) each would
+//! touch, and the settings within them, instead of per-client diffs to piece
+//! together. Built from the unified diffs the installers return.
+
+use crate::output;
+use serde_json::{Value, json};
+
+/// The settings a client's pending change touches in one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientChange {
+    pub id: String,
+    pub name: String,
+    pub files: Vec<FileChange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSummary {
+    pub clients: Vec<ClientChange>,
+}
+
+impl ChangeSummary {
+    /// From each client's `(id, name, diff)`; empty diffs are skipped
+    pub fn from_diffs<'a>(diffs: impl IntoIterator<Item = (&'a str, &'a str, &'a str)>) -> Self {
+        let clients = diffs
+            .into_iter()
+            .filter(|(_, _, diff)| !diff.trim().is_empty())
+            .map(|(id, name, diff)| ClientChange {
+                id: id.to_string(),
+                name: name.to_string(),
+                files: parse_diff(diff),
+            })
+            .collect();
+        Self { clients }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Every file touched, sorted, each once
+    pub fn files(&self) -> Vec<&str> {
+        let mut files: Vec<&str> = self
+            .clients
+            .iter()
+            .flat_map(|client| client.files.iter().map(|file| file.path.as_str()))
+            .collect();
+        files.sort_unstable();
+        files.dedup();
+        files
+    }
+
+    pub fn key_count(&self) -> usize {
+        self.clients
+            .iter()
+            .flat_map(|client| &client.files)
+            .map(|file| file.keys.len())
+            .sum()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "clients_to_change": self.clients.len(),
+            "keys_affected": self.key_count(),
+            "files_touched": self.files(),
+            "clients": self.clients.iter().map(|client| json!({
+                "id": client.id,
+                "name": client.name,
+                "files": client.files.iter().map(|file| json!({
+                    "path": file.path,
+                    "keys": file.keys,
+                })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    /// The human form, printed after the per-client lines of a dry run
+    pub fn print(&self) {
+        let plural = |count: usize, word: &str| {
+            format!("{} {}{}", count, word, if count == 1 { "" } else { "s" })
+        };
+        println!(
+            "\n{}",
+            output::paint(
+                output::BOLD,
+                &format!(
+                    "Dry-run summary: {} to change, {} in {}",
+                    plural(self.clients.len(), "client"),
+                    plural(self.key_count(), "key"),
+                    plural(self.files().len(), "file"),
+                )
+            )
+        );
+        for client in &self.clients {
+            println!("  {}", client.name);
+            for file in &client.files {
+                if file.keys.is_empty() {
+                    println!("    {}", file.path);
+                } else {
+                    println!("    {}: {}", file.path, file.keys.join(", "));
+                }
+            }
+        }
+    }
+}
+
+/// The files a unified diff touches and the settings its changed lines set
+fn parse_diff(diff: &str) -> Vec<FileChange> {
+    let mut files: Vec<FileChange> = Vec::new();
+    let mut old_path: Option<&str> = None;
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("--- ") {
+            old_path = path.strip_prefix("a/");
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            // A deleted file only names itself on the `---` line
+            let Some(path) = path.strip_prefix("b/").or(old_path) else {
+                continue;
+            };
+            let path = display_path(path);
+            if !files.iter().any(|file| file.path == path) {
+                files.push(FileChange {
+                    path,
+                    keys: Vec::new(),
+                });
+            }
+        } else if let Some(content) = line.strip_prefix(['+', '-'])
+            && let Some(file) = files.last_mut()
+            && let Some(key) = setting_key(content)
+            && !file.keys.contains(&key)
+        {
+            file.keys.push(key);
+        }
+    }
+    files
+}
+
+/// Diffs name files without their leading `/`; put it back unless the path
+/// is a Windows drive path or a registry key
+fn display_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let drive = bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':';
+    if drive || path.starts_with("HKCU/") || path.starts_with("HKLM/") {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+/// A setting a diff changes, with its value on each side; `None` where the
+/// setting is added or removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChange {
+    pub file: String,
+    pub key: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Every setting `diff` changes, in the order they first appear
+pub fn key_changes(diff: &str) -> Vec<KeyChange> {
+    let mut changes: Vec<KeyChange> = Vec::new();
+    let mut file: Option<String> = None;
+    let mut old_path: Option<&str> = None;
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("--- ") {
+            old_path = path.strip_prefix("a/");
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            file = path.strip_prefix("b/").or(old_path).map(display_path);
+        } else if let Some(file) = &file
+            && let Some((side, content)) = line
+                .strip_prefix('-')
+                .map(|content| (false, content))
+                .or_else(|| line.strip_prefix('+').map(|content| (true, content)))
+            && let Some((key, value)) = setting(content)
+        {
+            let index = match changes
+                .iter()
+                .position(|change| &change.file == file && change.key == key)
+            {
+                Some(index) => index,
+                None => {
+                    changes.push(KeyChange {
+                        file: file.clone(),
+                        key,
+                        before: None,
+                        after: None,
+                    });
+                    changes.len() - 1
+                }
+            };
+            let slot = if side {
+                &mut changes[index].after
+            } else {
+                &mut changes[index].before
+            };
+            *slot = Some(value);
+        }
+    }
+    changes
+}
+
+/// The setting a changed line sets: the key of a JSON member, an INI, YAML or
+/// `key = value` entry, an XML `name` attribute, or an Emacs `setq`
+fn setting_key(content: &str) -> Option<String> {
+    setting(content).map(|(key, _)| key)
+}
+
+/// [`setting_key`] and the value the line gives it
+fn setting(content: &str) -> Option<(String, String)> {
+    let line = content.trim();
+    if let Some(quoted) = line.strip_prefix('"') {
+        let (key, rest) = quoted.split_once('"')?;
+        let value = rest.trim_start().strip_prefix(':')?;
+        return Some((key.to_string(), unquote(value.trim().trim_end_matches(','))));
+    }
+    if let Some((_, rest)) = line.split_once("name=\"") {
+        let (key, rest) = rest.split_once('"')?;
+        let value = rest
+            .split_once("value=\"")
+            .and_then(|(_, value)| value.split_once('"'))
+            .map_or(line, |(value, _)| value);
+        return Some((key.to_string(), value.to_string()));
+    }
+    if let Some(rest) = line.strip_prefix("(setq ") {
+        let (key, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let key = key.trim_end_matches(')');
+        return Some((key.to_string(), unquote(value.trim().trim_end_matches(')'))));
+    }
+    let end = line.find(['=', ':'])?;
+    let key = line[..end].trim().trim_start_matches("- ");
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '[' | ']'));
+    is_key.then(|| (key.to_string(), unquote(&line[end + 1..])))
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GITFIEND: &str = "--- /dev/null\n\
+        +++ b/home/dev/.config/GitFiend/config.json\n\
+        @@ -0,0 +1,3 @@\n\
+        +{\n\
+        +  \"gitPath\": \"/home/dev/.git-ai/bin/git\"\n\
+        +}\n";
+
+    const FORK: &str = "--- a/Users/dev/Library/Preferences/com.DanPristupov.Fork.plist\n\
+        +++ b/Users/dev/Library/Preferences/com.DanPristupov.Fork.plist\n\
+        @@ -1 +1,2 @@\n\
+        -gitInstanceType = 0\n\
+        +gitInstanceType = 3\n\
+        +customGitInstancePath = /Users/dev/.git-ai/bin/git\n";
+
+    #[test]
+    fn summarizes_files_and_keys_per_client() {
+        let summary = ChangeSummary::from_diffs([
+            ("gitfiend", "GitFiend", GITFIEND),
+            ("fork", "Fork", FORK),
+            ("nova", "Nova", ""),
+        ]);
+        assert_eq!(summary.clients.len(), 2);
+        assert_eq!(
+            summary.clients[0].files,
+            vec![FileChange {
+                path: "/home/dev/.config/GitFiend/config.json".to_string(),
+                keys: vec!["gitPath".to_string()],
+            }]
+        );
+        assert_eq!(
+            summary.clients[1].files[0].keys,
+            vec!["gitInstanceType", "customGitInstancePath"]
+        );
+        assert_eq!(summary.key_count(), 3);
+        assert_eq!(summary.files().len(), 2);
+
+        let json = summary.to_json();
+        assert_eq!(json["clients_to_change"], 2);
+        assert_eq!(json["keys_affected"], 3);
+        assert_eq!(
+            json["clients"][1]["files"][0]["keys"][1],
+            "customGitInstancePath"
+        );
+        assert!(ChangeSummary::from_diffs([]).is_empty());
+    }
+
+    #[test]
+    fn recognizes_setting_formats() {
+        assert_eq!(
+            setting_key("  \"git.path\": \"/x\",").as_deref(),
+            Some("git.path")
+        );
+        assert_eq!(setting_key("gitPath=/x").as_deref(), Some("gitPath"));
+        assert_eq!(setting_key("  path: /x").as_deref(), Some("path"));
+        assert_eq!(
+            setting_key("<option name=\"myPathToGit\" value=\"/x\" />").as_deref(),
+            Some("myPathToGit")
+        );
+        assert_eq!(
+            setting_key("(setq magit-git-executable \"/x\")").as_deref(),
+            Some("magit-git-executable")
+        );
+        assert_eq!(setting_key("{"), None);
+        assert_eq!(setting_key("\"not a key\""), None);
+        assert_eq!(setting_key("some prose: here"), None);
+    }
+
+    #[test]
+    fn pairs_each_keys_old_and_new_value() {
+        let changes = key_changes(FORK);
+        assert_eq!(
+            changes[0],
+            KeyChange {
+                file: "/Users/dev/Library/Preferences/com.DanPristupov.Fork.plist".to_string(),
+                key: "gitInstanceType".to_string(),
+                before: Some("0".to_string()),
+                after: Some("3".to_string()),
+            }
+        );
+        assert_eq!(changes[1].before, None);
+        assert_eq!(
+            changes[1].after.as_deref(),
+            Some("/Users/dev/.git-ai/bin/git")
+        );
+        let changes = key_changes(GITFIEND);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].after.as_deref(),
+            Some("/home/dev/.git-ai/bin/git")
+        );
+        assert_eq!(
+            setting("(setq magit-git-executable \"/x\")"),
+            Some(("magit-git-executable".to_string(), "/x".to_string()))
+        );
+    }
+
+    #[test]
+    fn keeps_drive_and_registry_paths() {
+        assert_eq!(display_path("C:/Users/dev/x.json"), "C:/Users/dev/x.json");
+        assert_eq!(
+            display_path("HKCU/Software/TortoiseGit"),
+            "HKCU/Software/TortoiseGit"
+        );
+        assert_eq!(display_path("etc/gitconfig"), "/etc/gitconfig");
+        let removed = "--- a/home/dev/x.el\n+++ /dev/null\n@@ -1 +0,0 @@\n-(setq a 1)\n";
+        let files = parse_diff(removed);
+        assert_eq!(files[0].path, "/home/dev/x.el");
+        assert_eq!(files[0].keys, vec!["a"]);
+    }
+}
@@ -0,0 +1,183 @@
+//! On-disk cache of git client discovery: Spotlight and bundle lookups on
+//! macOS, registry and package manager scans on Windows, and the version
+//! probes that run each client's binary. Results are kept for
+//! [`DISCOVERY_CACHE_TTL`] in `<cache dir>/client_discovery`, so
+//! repeated `clients check` runs across a fleet don't repeat them.
+//! `--no-cache` rediscovers everything and refreshes the cache.
+
+use crate::app_context::AppContext;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a discovery result is trusted
+pub const DISCOVERY_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Set by `--no-cache`
+static BYPASS: AtomicBool = AtomicBool::new(false);
+
+static CACHE: OnceLock<Mutex<DiscoveryCache>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    /// Seconds since the epoch
+    discovered_at: u64,
+    value: serde_json::Value,
+}
+
+#[derive(Debug, Default)]
+struct DiscoveryCache {
+    path: Option<PathBuf>,
+    entries: HashMap<String, Entry>,
+}
+
+impl DiscoveryCache {
+    /// A missing or unreadable cache file is an empty cache
+    fn load(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_deref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self { path, entries }
+    }
+
+    /// The cached value for `key`, unless it's older than the TTL or no
+    /// longer deserializes
+    fn get<T: DeserializeOwned>(&self, key: &str, now: u64) -> Option<T> {
+        let entry = self.entries.get(key)?;
+        if now.saturating_sub(entry.discovered_at) > DISCOVERY_CACHE_TTL.as_secs() {
+            return None;
+        }
+        serde_json::from_value(entry.value.clone()).ok()
+    }
+
+    fn put<T: Serialize>(&mut self, key: &str, value: &T, now: u64) {
+        let Ok(value) = serde_json::to_value(value) else {
+            return;
+        };
+        self.entries.insert(
+            key.to_string(),
+            Entry {
+                discovered_at: now,
+                value,
+            },
+        );
+        self.save();
+    }
+
+    /// Best effort: a cache that can't be written is only slower
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let Ok(json) = serde_json::to_vec(&self.entries) else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        if fs::write(&tmp, json).is_ok() && fs::rename(&tmp, path).is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+}
+
+/// Ignore cached results for the rest of this process (`--no-cache`). Fresh
+/// results are still written back.
+pub fn bypass_discovery_cache() {
+    BYPASS.store(true, Ordering::Relaxed);
+}
+
+fn cache() -> &'static Mutex<DiscoveryCache> {
+    CACHE.get_or_init(|| {
+        Mutex::new(DiscoveryCache::load(Some(
+            AppContext::current().cache_dir.join("client_discovery"),
+        )))
+    })
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// The cached result of `discover` under `key`, or a fresh one when there is
+/// none, it has expired, or `still_valid` rejects it (say, a cached install
+/// directory has since been removed). `discover` runs without the cache lock
+/// held, so parallel client checks don't wait on each other.
+pub fn cached_discovery<T, D, V>(key: &str, still_valid: V, discover: D) -> T
+where
+    T: Serialize + DeserializeOwned,
+    D: FnOnce() -> T,
+    V: FnOnce(&T) -> bool,
+{
+    if !BYPASS.load(Ordering::Relaxed) {
+        let hit = cache()
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get::<T>(key, now()));
+        if let Some(value) = hit.filter(still_valid) {
+            return value;
+        }
+    }
+    let value = discover();
+    if let Ok(mut cache) = cache().lock() {
+        cache.put(key, &value, now());
+    }
+    value
+}
+
+/// For results that are paths: still valid while the path exists
+pub fn path_exists(path: &Option<PathBuf>) -> bool {
+    path.as_deref().is_none_or(Path::exists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_expire_after_the_ttl_and_survive_a_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("internal").join("client_discovery");
+        let mut cache = DiscoveryCache::load(Some(path.clone()));
+        assert_eq!(cache.get::<Option<u32>>("smerge_build", 1_000), None);
+
+        cache.put("smerge_build", &Some(2096u32), 1_000);
+        let reloaded = DiscoveryCache::load(Some(path));
+        assert_eq!(
+            reloaded.get::<Option<u32>>("smerge_build", 1_000 + 60),
+            Some(Some(2096))
+        );
+        let expired = 1_000 + DISCOVERY_CACHE_TTL.as_secs() + 1;
+        assert_eq!(reloaded.get::<Option<u32>>("smerge_build", expired), None);
+        // A type change (after an upgrade) is a miss, not an error
+        assert_eq!(reloaded.get::<Vec<String>>("smerge_build", 1_000), None);
+    }
+
+    #[test]
+    fn a_corrupt_cache_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("client_discovery");
+        fs::write(&path, "{not json").unwrap();
+        let cache = DiscoveryCache::load(Some(path));
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn removed_paths_are_rediscovered() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(path_exists(&None));
+        assert!(path_exists(&Some(dir.path().to_path_buf())));
+        assert!(!path_exists(&Some(dir.path().join("Fork.app"))));
+    }
+}
@@ -0,0 +1,600 @@
+use crate::error::GitAiError;
+use crate::mdm::prefs_backup::restore_snapshot;
+use crate::mdm::utils::{home_dir, version_meets_requirement};
+use std::path::{Path, PathBuf};
+
+/// Parameters passed to git client installers
+#[derive(Clone)]
+pub struct GitClientInstallerParams {
+    /// Path to the git shim (a `git` executable that routes through git-ai)
+    pub git_shim_path: PathBuf,
+    /// Whose preferences to change
+    pub scope: InstallScope,
+}
+
+/// Set, to the profile's user name, in the `clients` runs `--all-users`
+/// starts for a profile whose owner they can't run as (always on Windows)
+pub const OTHER_USER_ENV: &str = "GIT_AI_CLIENTS_OTHER_USER";
+
+/// The user whose profile this run configures without running as them.
+/// Files under HOME are theirs, but HKCU and the `defaults` domains are
+/// still this process's own.
+pub fn other_user() -> Option<String> {
+    std::env::var(OTHER_USER_ENV)
+        .ok()
+        .filter(|user| !user.is_empty())
+}
+
+/// Whose preferences an install changes: the current user's, or the
+/// machine-wide defaults every user inherits (for shared build machines).
+/// A user's own setting still overrides the machine-wide one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InstallScope {
+    #[default]
+    User,
+    System,
+}
+
+impl InstallScope {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user" => Some(Self::User),
+            "system" => Some(Self::System),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::System => "system",
+        }
+    }
+
+    /// The macOS `defaults` domain for `bundle_id`: the user's, or the plist
+    /// in `/Library/Preferences` that applies to every user. Another user's
+    /// domain is named by its plist in their profile.
+    #[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+    pub fn defaults_domain(&self, bundle_id: &str) -> String {
+        match self {
+            Self::User if other_user().is_some() => home_dir()
+                .join("Library")
+                .join("Preferences")
+                .join(bundle_id)
+                .to_string_lossy()
+                .into_owned(),
+            Self::User => bundle_id.to_string(),
+            Self::System => format!("/Library/Preferences/{}", bundle_id),
+        }
+    }
+
+    /// A registry key for the registry helpers in `mdm::utils`: under HKCU,
+    /// or under HKLM with an `HKLM\` prefix
+    #[cfg_attr(not(any(windows, test)), allow(dead_code))]
+    pub fn registry_key(&self, subkey: &str) -> String {
+        match self {
+            Self::User => subkey.to_string(),
+            Self::System => format!("HKLM\\{}", subkey),
+        }
+    }
+}
+
+/// Refuse a system-scope install that can't work. Machine-wide preferences
+/// can only be written as root (an elevated Administrator on Windows), and
+/// they must point at a shim every user can run, so git-ai itself has to be
+/// installed outside a home directory (e.g. under `/opt` or Program Files).
+pub fn check_system_install(git_shim_path: &Path, dry_run: bool) -> Result<(), GitAiError> {
+    if is_per_user_path(git_shim_path, &home_dir()) {
+        return Err(GitAiError::Generic(format!(
+            "--scope system needs git-ai installed where every user can run it, not {}; \
+             install it machine-wide (e.g. under /opt or Program Files) and re-run",
+            git_shim_path.display()
+        )));
+    }
+    if !dry_run && !crate::utils::is_running_as_superuser() {
+        let how = if cfg!(windows) {
+            "from an elevated (Run as administrator) prompt"
+        } else {
+            "with sudo"
+        };
+        return Err(GitAiError::Generic(format!(
+            "--scope system writes machine-wide preferences; re-run {}",
+            how
+        )));
+    }
+    Ok(())
+}
+
+/// Whether `path` belongs to one user: under `home`, or under a `.git-ai`
+/// directory (another user's home when run through sudo)
+pub(crate) fn is_per_user_path(path: &Path, home: &Path) -> bool {
+    path.starts_with(home)
+        || path
+            .components()
+            .any(|component| component.as_os_str() == ".git-ai")
+}
+
+/// A preference a macOS configuration profile can enforce, for fleets whose
+/// MDM manages client settings instead of git-ai writing them locally
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagedPreference {
+    /// Preference domain, normally the app's bundle identifier
+    pub domain: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// A registry value Group Policy or Intune can enforce, the Windows
+/// counterpart of [`ManagedPreference`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagedRegistryValue {
+    /// Key below the hive the policy targets, e.g. `Software\TortoiseGit`
+    pub key: String,
+    pub name: String,
+    pub value: String,
+}
+
+/// Result of checking a git client's preferences
+pub struct GitClientCheckResult {
+    /// Whether the client is installed
+    pub client_installed: bool,
+    /// Whether the client's preferences point at the git shim
+    pub prefs_configured: bool,
+    /// Whether the configured preferences are up to date
+    #[allow(dead_code)]
+    pub prefs_up_to_date: bool,
+    /// Why the installed client can't be configured, if it can't
+    pub unsupported_reason: Option<String>,
+    /// The git executable the client's preferences name, for clients that
+    /// name one; where several files do, the first that isn't the shim
+    pub configured_git_path: Option<String>,
+}
+
+impl GitClientCheckResult {
+    /// The client was not detected on this machine
+    pub fn not_installed() -> Self {
+        Self {
+            client_installed: false,
+            prefs_configured: false,
+            prefs_up_to_date: false,
+            unsupported_reason: None,
+            configured_git_path: None,
+        }
+    }
+
+    /// The client is installed but exposes no way to route git through the shim
+    pub fn unsupported(reason: impl Into<String>) -> Self {
+        Self {
+            client_installed: true,
+            prefs_configured: false,
+            prefs_up_to_date: false,
+            unsupported_reason: Some(reason.into()),
+            configured_git_path: None,
+        }
+    }
+
+    /// An installed client whose preferences name one git executable,
+    /// configured (and up to date) when that's `expected`
+    pub fn with_git_path(configured_git_path: Option<String>, expected: &str) -> Self {
+        let configured = configured_git_path.as_deref() == Some(expected);
+        Self {
+            client_installed: true,
+            prefs_configured: configured,
+            prefs_up_to_date: configured,
+            unsupported_reason: None,
+            configured_git_path,
+        }
+    }
+}
+
+/// Trait for pointing git GUI clients and editors at the git shim
+pub trait GitClientInstaller: Send + Sync {
+    /// Human-readable name of the client (e.g., "Fork", "GitUp")
+    fn name(&self) -> &str;
+
+    /// Short identifier for status maps (e.g., "fork", "gitup")
+    fn id(&self) -> &str;
+
+    /// Whether this client can be detected/configured on the current platform
+    fn is_platform_supported(&self) -> bool;
+
+    /// Whether the client reads machine-wide preferences, so it can be
+    /// configured with [`InstallScope::System`]
+    fn supports_system_scope(&self) -> bool {
+        false
+    }
+
+    /// Process names (without `.exe`) of a running client that holds its
+    /// preferences in memory and writes them back on quit, undoing the
+    /// install. Install won't write them while it runs unless forced.
+    fn process_names(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    /// Preferences that point the client at the shim, for `git-ai mdm
+    /// export-profile`. Only clients configured through macOS defaults have
+    /// any; they don't depend on the client being installed here.
+    fn managed_preferences(&self, _params: &GitClientInstallerParams) -> Vec<ManagedPreference> {
+        Vec::new()
+    }
+
+    /// String values that point the client at the shim, for `git-ai mdm
+    /// export-policy`. Only clients configured through the registry have any.
+    fn managed_registry_values(
+        &self,
+        _params: &GitClientInstallerParams,
+    ) -> Vec<ManagedRegistryValue> {
+        Vec::new()
+    }
+
+    /// Check if the client is installed and whether its preferences use the shim
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError>;
+
+    /// Point the client at the git shim
+    /// Returns Ok(Some(diff)) if changes were made, Ok(None) if already up to date
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError>;
+
+    /// Restore the client's default git resolution
+    /// Returns Ok(Some(diff)) if changes were made, Ok(None) if nothing to uninstall
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError>;
+
+    /// After [`install_prefs`](Self::install_prefs), confirm the client will
+    /// actually run the shim. The default re-reads the preferences from disk
+    /// (or the defaults domain); installers add checks of their own where the
+    /// client needs more than the setting.
+    /// Returns Ok(Some(problem)) if it won't, Ok(None) if verified
+    fn verify_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<Option<String>, GitAiError> {
+        Ok(prefs_problem(&self.check_client(params)?))
+    }
+}
+
+/// Why a client re-checked after install won't use the shim, if it won't
+pub fn prefs_problem(check: &GitClientCheckResult) -> Option<String> {
+    if let Some(reason) = &check.unsupported_reason {
+        Some(reason.clone())
+    } else if !check.prefs_up_to_date {
+        Some("its preferences don't point at the git shim after writing them".to_string())
+    } else {
+        None
+    }
+}
+
+/// Run [`GitClientInstaller::check_client`] for every installer at once, one
+/// thread each, since checks stat install locations and spawn `defaults`-style
+/// reads. Results are in `installers` order; a check that panics is reported
+/// as that client's error without affecting the others.
+pub fn check_clients_parallel(
+    installers: &[Box<dyn GitClientInstaller>],
+    params: &GitClientInstallerParams,
+) -> Vec<Result<GitClientCheckResult, GitAiError>> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = installers
+            .iter()
+            .map(|installer| scope.spawn(move || installer.check_client(params)))
+            .collect();
+        handles
+            .into_iter()
+            .zip(installers)
+            .map(|(handle, installer)| {
+                handle.join().unwrap_or_else(|_| {
+                    Err(GitAiError::Generic(format!(
+                        "{} preference check panicked",
+                        installer.name()
+                    )))
+                })
+            })
+            .collect()
+    })
+}
+
+/// The client preference changes made during one install run. When a later
+/// client fails, [`rollback`](Self::rollback) puts every recorded client back
+/// so the machine isn't left with only some of them pointed at the shim.
+#[derive(Debug, Default)]
+pub struct GitClientInstallTransaction {
+    /// `(client id, snapshot of its preferences before the change)`, in the
+    /// order they were applied
+    applied: Vec<(String, PathBuf)>,
+}
+
+impl GitClientInstallTransaction {
+    /// Record that `client_id`'s preferences changed; `snapshot` is the
+    /// `prefs_backup` snapshot taken while they were written
+    pub fn record(&mut self, client_id: &str, snapshot: PathBuf) {
+        self.applied.push((client_id.to_string(), snapshot));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty()
+    }
+
+    /// Restore every recorded snapshot, most recent first. A failed restore
+    /// doesn't stop the others; each client's outcome is returned.
+    pub fn rollback(self) -> Vec<(String, Result<(), GitAiError>)> {
+        self.applied
+            .into_iter()
+            .rev()
+            .map(|(client_id, snapshot)| {
+                let result = restore_snapshot(&snapshot).map(|_| ());
+                (client_id, result)
+            })
+            .collect()
+    }
+}
+
+/// A `(major, minor)` version range: `min` inclusive, `below` exclusive, open
+/// ends unbounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionRange {
+    pub min: Option<(u32, u32)>,
+    pub below: Option<(u32, u32)>,
+}
+
+impl VersionRange {
+    pub const ANY: Self = Self {
+        min: None,
+        below: None,
+    };
+
+    pub const fn from(min: (u32, u32)) -> Self {
+        Self {
+            min: Some(min),
+            below: None,
+        }
+    }
+
+    pub const fn below(below: (u32, u32)) -> Self {
+        Self {
+            min: None,
+            below: Some(below),
+        }
+    }
+
+    /// Whether `version` falls in the range. An undetectable version is
+    /// assumed current, so it only matches ranges without an upper bound.
+    pub fn contains(&self, version: Option<(u32, u32)>) -> bool {
+        let Some(version) = version else {
+            return self.below.is_none();
+        };
+        self.min
+            .is_none_or(|min| version_meets_requirement(version, min))
+            && self
+                .below
+                .is_none_or(|below| !version_meets_requirement(version, below))
+    }
+}
+
+/// One row of an installer's behavior table: the preferences to write when
+/// both the OS and the client version match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrefsVariant<T> {
+    pub os: VersionRange,
+    pub app: VersionRange,
+    pub prefs: T,
+}
+
+/// First row of `table` matching the detected OS and client versions. Tables
+/// list rows newest first, so an undetectable version picks the current layout.
+pub fn select_prefs_variant<T>(
+    table: &[PrefsVariant<T>],
+    os_version: Option<(u32, u32)>,
+    app_version: Option<(u32, u32)>,
+) -> Option<&T> {
+    table
+        .iter()
+        .find(|row| row.os.contains(os_version) && row.app.contains(app_version))
+        .map(|row| &row.prefs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mdm::prefs_backup::BackupSession;
+    use crate::mdm::utils::write_atomic;
+    use std::fs;
+
+    const TABLE: &[PrefsVariant<&str>] = &[
+        PrefsVariant {
+            os: VersionRange::from((14, 0)),
+            app: VersionRange::from((2, 0)),
+            prefs: "container-v2",
+        },
+        PrefsVariant {
+            os: VersionRange::ANY,
+            app: VersionRange::from((2, 0)),
+            prefs: "v2",
+        },
+        PrefsVariant {
+            os: VersionRange::ANY,
+            app: VersionRange::below((2, 0)),
+            prefs: "v1",
+        },
+    ];
+
+    #[test]
+    fn version_range_bounds() {
+        let range = VersionRange {
+            min: Some((1, 5)),
+            below: Some((2, 0)),
+        };
+        assert!(!range.contains(Some((1, 4))));
+        assert!(range.contains(Some((1, 5))));
+        assert!(range.contains(Some((1, 99))));
+        assert!(!range.contains(Some((2, 0))));
+        assert!(!range.contains(None));
+        assert!(VersionRange::from((2, 0)).contains(None));
+    }
+
+    #[test]
+    fn first_matching_row_wins() {
+        assert_eq!(
+            select_prefs_variant(TABLE, Some((15, 1)), Some((2, 3))),
+            Some(&"container-v2")
+        );
+        assert_eq!(
+            select_prefs_variant(TABLE, Some((13, 6)), Some((2, 3))),
+            Some(&"v2")
+        );
+        assert_eq!(
+            select_prefs_variant(TABLE, Some((15, 1)), Some((1, 9))),
+            Some(&"v1")
+        );
+        // Unknown versions are treated as current
+        assert_eq!(
+            select_prefs_variant(TABLE, None, None),
+            Some(&"container-v2")
+        );
+        assert_eq!(select_prefs_variant(TABLE, None, Some((1, 2))), Some(&"v1"));
+    }
+
+    struct FakeInstaller {
+        id: &'static str,
+        installed: Option<bool>,
+    }
+
+    impl GitClientInstaller for FakeInstaller {
+        fn name(&self) -> &str {
+            self.id
+        }
+
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn is_platform_supported(&self) -> bool {
+            true
+        }
+
+        fn check_client(
+            &self,
+            _params: &GitClientInstallerParams,
+        ) -> Result<GitClientCheckResult, GitAiError> {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            match self.installed {
+                Some(true) => Ok(GitClientCheckResult::unsupported("fake")),
+                Some(false) => Ok(GitClientCheckResult::not_installed()),
+                None => panic!("check failed"),
+            }
+        }
+
+        fn install_prefs(
+            &self,
+            _params: &GitClientInstallerParams,
+            _dry_run: bool,
+        ) -> Result<Option<String>, GitAiError> {
+            Ok(None)
+        }
+
+        fn uninstall_prefs(
+            &self,
+            _params: &GitClientInstallerParams,
+            _dry_run: bool,
+        ) -> Result<Option<String>, GitAiError> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn parallel_checks_keep_order_and_isolate_failures() {
+        let installers: Vec<Box<dyn GitClientInstaller>> = vec![
+            Box::new(FakeInstaller {
+                id: "a",
+                installed: Some(true),
+            }),
+            Box::new(FakeInstaller {
+                id: "b",
+                installed: None,
+            }),
+            Box::new(FakeInstaller {
+                id: "c",
+                installed: Some(false),
+            }),
+        ];
+        let params = GitClientInstallerParams {
+            git_shim_path: PathBuf::from("/shim/git"),
+            scope: InstallScope::User,
+        };
+
+        let results = check_clients_parallel(&installers, &params);
+        assert_eq!(results.len(), 3);
+        assert!(results[0].as_ref().unwrap().client_installed);
+        assert!(
+            results[1]
+                .as_ref()
+                .err()
+                .unwrap()
+                .to_string()
+                .contains("b preference check panicked")
+        );
+        assert!(!results[2].as_ref().unwrap().client_installed);
+    }
+
+    #[test]
+    fn system_scope_locations_and_per_user_paths() {
+        assert_eq!(InstallScope::parse("system"), Some(InstallScope::System));
+        assert_eq!(InstallScope::parse("machine"), None);
+        assert_eq!(
+            InstallScope::System.defaults_domain("com.DanPristupov.Fork"),
+            "/Library/Preferences/com.DanPristupov.Fork"
+        );
+        assert_eq!(
+            InstallScope::User.defaults_domain("com.DanPristupov.Fork"),
+            "com.DanPristupov.Fork"
+        );
+        assert_eq!(
+            InstallScope::System.registry_key(r"Software\TortoiseGit"),
+            r"HKLM\Software\TortoiseGit"
+        );
+
+        let home = Path::new("/home/dev");
+        assert!(is_per_user_path(
+            Path::new("/home/dev/.git-ai/bin/git"),
+            home
+        ));
+        assert!(is_per_user_path(
+            Path::new("/Users/other/.git-ai/bin/git"),
+            home
+        ));
+        assert!(!is_per_user_path(Path::new("/opt/git-ai/bin/git"), home));
+        assert!(check_system_install(&home_dir().join(".git-ai/bin/git"), true).is_err());
+        assert!(check_system_install(Path::new("/opt/git-ai/bin/git"), true).is_ok());
+    }
+
+    #[test]
+    fn rollback_restores_every_recorded_client() {
+        let root = tempfile::tempdir().unwrap();
+        let prefs = tempfile::tempdir().unwrap();
+        let vscode = prefs.path().join("settings.json");
+        let fork = prefs.path().join("fork.plist");
+        fs::write(&vscode, "before").unwrap();
+
+        let mut transaction = GitClientInstallTransaction::default();
+        assert!(transaction.is_empty());
+        for (id, path) in [("vscode", &vscode), ("fork", &fork)] {
+            let session = BackupSession::begin(root.path(), id);
+            write_atomic(path, b"shim").unwrap();
+            transaction.record(id, session.snapshot_dir().unwrap());
+        }
+
+        let outcomes = transaction.rollback();
+        let ids: Vec<&str> = outcomes.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["fork", "vscode"]);
+        assert!(outcomes.iter().all(|(_, result)| result.is_ok()));
+        assert_eq!(fs::read_to_string(&vscode).unwrap(), "before");
+        assert!(!fork.exists());
+    }
+}
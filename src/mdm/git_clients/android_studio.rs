@@ -0,0 +1,210 @@
+use super::jetbrains::{
+    ANDROID_STUDIO_PRODUCT_CODE, check_git_xml_paths, install_git_xml_paths,
+    uninstall_git_xml_paths,
+};
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+use crate::mdm::jetbrains::{find_jetbrains_installations, get_config_dir};
+#[cfg(target_os = "macos")]
+use crate::mdm::utils::home_dir;
+#[cfg(windows)]
+use crate::mdm::utils::windows_app_data_dir;
+#[cfg(all(unix, not(target_os = "macos")))]
+use crate::mdm::utils::{LinuxPackaging, linux_config_homes};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Every channel's config directory starts with this: `AndroidStudio2024.2`,
+/// `AndroidStudioPreview2025.1`, and older `AndroidStudio4.2` alike
+const CONFIG_DIR_PREFIX: &str = "AndroidStudio";
+
+/// Points Android Studio at the git shim through the IntelliJ `options/git.xml`
+/// (`myPathToGit`). Its config lives under Google's directories, one per
+/// version and channel (stable, Beta/Canary previews), each configured
+/// separately.
+pub struct AndroidStudioInstaller;
+
+impl AndroidStudioInstaller {
+    /// Google's config roots, each holding a directory per Android Studio
+    /// version
+    #[cfg(target_os = "macos")]
+    fn google_config_roots() -> Vec<PathBuf> {
+        vec![
+            home_dir()
+                .join("Library")
+                .join("Application Support")
+                .join("Google"),
+        ]
+    }
+
+    #[cfg(windows)]
+    fn google_config_roots() -> Vec<PathBuf> {
+        windows_app_data_dir("Google").into_iter().collect()
+    }
+
+    /// The native config home, plus the Flatpak's when that is installed. The
+    /// Snap is a classic snap and shares the native one.
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn google_config_roots() -> Vec<PathBuf> {
+        linux_config_homes(LinuxPackaging {
+            flatpak_id: Some("com.google.AndroidStudio"),
+            snap_name: None,
+        })
+        .into_iter()
+        .map(|config_home| config_home.dir.join("Google"))
+        .collect()
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn google_config_roots() -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// `options/git.xml` for every channel that has been run, plus any
+    /// detected install whose config directory doesn't exist yet
+    fn git_xml_paths() -> Vec<PathBuf> {
+        let mut config_dirs: Vec<PathBuf> = Self::google_config_roots()
+            .iter()
+            .flat_map(|root| channel_config_dirs(root))
+            .collect();
+        for detected in find_jetbrains_installations() {
+            if detected.ide.product_code != ANDROID_STUDIO_PRODUCT_CODE {
+                continue;
+            }
+            if let Some(config_dir) = get_config_dir(&detected)
+                && !config_dirs.contains(&config_dir)
+            {
+                config_dirs.push(config_dir);
+            }
+        }
+        config_dirs
+            .into_iter()
+            .map(|dir| dir.join("options").join("git.xml"))
+            .collect()
+    }
+}
+
+/// Android Studio config directories directly under `root`, sorted by name
+fn channel_config_dirs(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(CONFIG_DIR_PREFIX))
+        })
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+impl GitClientInstaller for AndroidStudioInstaller {
+    fn name(&self) -> &str {
+        "Android Studio"
+    }
+
+    fn id(&self) -> &str {
+        "android-studio"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        true
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        check_git_xml_paths(&Self::git_xml_paths(), params)
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        install_git_xml_paths(&Self::git_xml_paths(), params, dry_run)
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        uninstall_git_xml_paths(&Self::git_xml_paths(), params, dry_run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mdm::git_client_installer::InstallScope;
+
+    #[test]
+    fn every_channel_is_configured_and_other_google_dirs_are_ignored() {
+        let root = tempfile::tempdir().unwrap();
+        for dir in [
+            "AndroidStudio2024.2",
+            "AndroidStudioPreview2025.1",
+            "AndroidStudio4.2",
+            "Chrome",
+        ] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+        fs::write(root.path().join("AndroidStudio.txt"), "").unwrap();
+
+        let dirs = channel_config_dirs(root.path());
+        let names: Vec<_> = dirs
+            .iter()
+            .map(|dir| dir.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "AndroidStudio2024.2",
+                "AndroidStudio4.2",
+                "AndroidStudioPreview2025.1"
+            ]
+        );
+
+        let paths: Vec<PathBuf> = dirs
+            .iter()
+            .map(|dir| dir.join("options").join("git.xml"))
+            .collect();
+        let params = GitClientInstallerParams {
+            git_shim_path: PathBuf::from("/home/dev/.git-ai/bin/git"),
+            scope: InstallScope::User,
+        };
+        assert!(
+            install_git_xml_paths(&paths, &params, false)
+                .unwrap()
+                .is_some()
+        );
+        let result = check_git_xml_paths(&paths, &params).unwrap();
+        assert!(result.prefs_configured && result.prefs_up_to_date);
+        assert!(
+            uninstall_git_xml_paths(&paths, &params, false)
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            !check_git_xml_paths(&paths, &params)
+                .unwrap()
+                .prefs_configured
+        );
+    }
+
+    #[test]
+    fn missing_google_root_means_no_channels() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(channel_config_dirs(&root.path().join("Google")).is_empty());
+    }
+}
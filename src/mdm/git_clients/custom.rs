@@ -0,0 +1,575 @@
+//! Git clients described in `~/.config/git-ai/clients.toml`, for in-house or
+//! niche GUIs that have no built-in installer:
+//!
+//! ```toml
+//! [[client]]
+//! id = "acme-git"
+//! name = "Acme Git"
+//! settings_path = "~/.config/acme/settings.json"
+//! format = "json"  # json | ini | yaml | plist
+//!
+//! [client.keys]
+//! gitPath = "{shim}"
+//! ```
+//!
+//! Each `keys` entry names a setting and the value it should hold; `{shim}`
+//! is replaced by the git shim path and `{shim_dir}` by its directory. A
+//! setting is a top-level key for `json`, `section.key` for `ini`, a dotted
+//! path for `yaml`, and a `defaults` key for `plist` (macOS only).
+//!
+//! A client counts as installed once its settings directory exists (for
+//! `plist`, the file itself). Uninstall removes only the keys that still hold
+//! git-ai's values. An app that rewrites its settings when it quits can list
+//! `process_names = ["acme-git"]`, so install waits for it to be closed.
+//!
+//! A client with machine-wide settings that every user inherits can name
+//! them with an absolute `system_settings_path`, in the same format, for
+//! `git-ai clients install --scope system`.
+
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams, InstallScope,
+    ManagedPreference,
+};
+use crate::mdm::utils::{
+    home_dir, read_ini_setting, read_jsonc_string_setting, read_yaml_setting, update_ini_setting,
+    update_jsonc_string_setting, update_yaml_setting,
+};
+#[cfg(target_os = "macos")]
+use crate::mdm::utils::{read_macos_default, update_macos_default};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClientsFile {
+    #[serde(default)]
+    client: Vec<CustomClientDefinition>,
+}
+
+/// One `[[client]]` table of `clients.toml`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomClientDefinition {
+    pub id: String,
+    pub name: String,
+    /// Absolute, or relative to the home directory with a leading `~/`
+    pub settings_path: String,
+    /// Machine-wide settings, for `--scope system`; must be absolute
+    #[serde(default)]
+    pub system_settings_path: Option<String>,
+    pub format: SettingsFormat,
+    /// Setting name to value template
+    pub keys: BTreeMap<String, String>,
+    /// Processes to quit before installing, for apps that rewrite their
+    /// settings on exit
+    #[serde(default)]
+    pub process_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsFormat {
+    Json,
+    Ini,
+    Yaml,
+    Plist,
+}
+
+/// `clients.toml`, under `$XDG_CONFIG_HOME` when that is set
+pub fn custom_clients_path() -> PathBuf {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| home_dir().join(".config"))
+        .join("git-ai")
+        .join("clients.toml")
+}
+
+/// Load the clients defined in `path`; a missing file defines none. Ids must
+/// be unique and must not shadow one of `builtin_ids`.
+pub fn load_custom_clients(
+    path: &Path,
+    builtin_ids: &[&str],
+) -> Result<Vec<CustomClientInstaller>, GitAiError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    let file: ClientsFile = toml::from_str(&content)
+        .map_err(|e| GitAiError::Generic(format!("Failed to parse {}: {}", path.display(), e)))?;
+
+    let mut seen = HashSet::new();
+    file.client
+        .into_iter()
+        .map(|definition| {
+            let invalid = |reason: &str| {
+                GitAiError::Generic(format!(
+                    "{}: client {:?} {}",
+                    path.display(),
+                    definition.id,
+                    reason
+                ))
+            };
+            if definition.id.trim().is_empty() || definition.name.trim().is_empty() {
+                return Err(invalid("needs a non-empty id and name"));
+            }
+            if builtin_ids.contains(&definition.id.as_str()) {
+                return Err(invalid("has the id of a built-in client"));
+            }
+            if !seen.insert(definition.id.clone()) {
+                return Err(invalid("is defined more than once"));
+            }
+            if definition.keys.is_empty() {
+                return Err(invalid("has no keys"));
+            }
+            if definition.format == SettingsFormat::Ini
+                && let Some(key) = definition.keys.keys().find(|key| !key.contains('.'))
+            {
+                return Err(invalid(&format!(
+                    "has ini key {:?}, which must be written as section.key",
+                    key
+                )));
+            }
+            let settings_path = expand_settings_path(&definition.settings_path)
+                .ok_or_else(|| invalid("needs an absolute or ~/ settings_path"))?;
+            let system_settings_path = match &definition.system_settings_path {
+                Some(raw) => Some(
+                    Some(PathBuf::from(raw))
+                        .filter(|path| path.is_absolute())
+                        .ok_or_else(|| invalid("needs an absolute system_settings_path"))?,
+                ),
+                None => None,
+            };
+            Ok(CustomClientInstaller {
+                definition,
+                settings_path,
+                system_settings_path,
+            })
+        })
+        .collect()
+}
+
+fn expand_settings_path(raw: &str) -> Option<PathBuf> {
+    let path = match raw.strip_prefix("~/") {
+        Some(rest) => home_dir().join(rest),
+        None => PathBuf::from(raw),
+    };
+    path.is_absolute().then_some(path)
+}
+
+/// `defaults` takes a plist path without its extension as the domain
+#[cfg(target_os = "macos")]
+fn plist_domain(path: &Path) -> String {
+    path.with_extension("").to_string_lossy().into_owned()
+}
+
+/// The preferences domain a plist in `Library/Preferences` holds
+fn preferences_domain(path: &Path) -> Option<String> {
+    if path.extension()? != "plist" || path.parent()?.file_name()? != "Preferences" {
+        return None;
+    }
+    path.file_stem()?.to_str().map(str::to_string)
+}
+
+/// Points a `clients.toml` client at the git shim by writing its mapped keys
+pub struct CustomClientInstaller {
+    definition: CustomClientDefinition,
+    settings_path: PathBuf,
+    system_settings_path: Option<PathBuf>,
+}
+
+impl CustomClientInstaller {
+    /// The settings file for `scope`; system scope only reaches clients with
+    /// a `system_settings_path`
+    fn path_for(&self, scope: InstallScope) -> &Path {
+        match (scope, &self.system_settings_path) {
+            (InstallScope::System, Some(path)) => path,
+            _ => &self.settings_path,
+        }
+    }
+
+    fn is_installed(&self, path: &Path) -> bool {
+        match self.definition.format {
+            SettingsFormat::Plist => path.is_file(),
+            _ => path.parent().is_some_and(Path::is_dir),
+        }
+    }
+
+    /// `(key, value git-ai writes)` for every mapped key
+    fn expected_values(&self, params: &GitClientInstallerParams) -> Vec<(&str, String)> {
+        let shim = params.git_shim_path.to_string_lossy();
+        let shim_dir = params
+            .git_shim_path
+            .parent()
+            .map(|dir| dir.to_string_lossy())
+            .unwrap_or_default();
+        self.definition
+            .keys
+            .iter()
+            .map(|(key, template)| {
+                let value = template
+                    .replace("{shim_dir}", &shim_dir)
+                    .replace("{shim}", &shim);
+                (key.as_str(), value)
+            })
+            .collect()
+    }
+
+    fn read(&self, path: &Path, key: &str) -> Result<Option<String>, GitAiError> {
+        match self.definition.format {
+            SettingsFormat::Json => read_jsonc_string_setting(path, key),
+            SettingsFormat::Ini => {
+                let (section, key) = key.split_once('.').unwrap_or(("", key));
+                read_ini_setting(path, section, key)
+            }
+            SettingsFormat::Yaml => read_yaml_setting(path, key),
+            #[cfg(target_os = "macos")]
+            SettingsFormat::Plist => Ok(read_macos_default(&plist_domain(path), key)),
+            #[cfg(not(target_os = "macos"))]
+            SettingsFormat::Plist => Err(self.plist_unsupported()),
+        }
+    }
+
+    fn write(
+        &self,
+        path: &Path,
+        key: &str,
+        value: Option<&str>,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        match self.definition.format {
+            SettingsFormat::Json => update_jsonc_string_setting(path, key, value, dry_run),
+            SettingsFormat::Ini => {
+                let (section, key) = key.split_once('.').unwrap_or(("", key));
+                update_ini_setting(path, section, key, value, dry_run)
+            }
+            SettingsFormat::Yaml => update_yaml_setting(path, key, value, dry_run),
+            #[cfg(target_os = "macos")]
+            SettingsFormat::Plist => update_macos_default(&plist_domain(path), key, value, dry_run),
+            #[cfg(not(target_os = "macos"))]
+            SettingsFormat::Plist => Err(self.plist_unsupported()),
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn plist_unsupported(&self) -> GitAiError {
+        GitAiError::Generic(format!(
+            "{}: plist settings are only supported on macOS",
+            self.definition.name
+        ))
+    }
+}
+
+impl GitClientInstaller for CustomClientInstaller {
+    fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    fn id(&self) -> &str {
+        &self.definition.id
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        self.definition.format != SettingsFormat::Plist || cfg!(target_os = "macos")
+    }
+
+    fn supports_system_scope(&self) -> bool {
+        self.system_settings_path.is_some()
+    }
+
+    /// Only a `plist` client whose settings are a preferences domain (a file
+    /// in a `Library/Preferences` directory) can be managed by a profile
+    fn managed_preferences(&self, params: &GitClientInstallerParams) -> Vec<ManagedPreference> {
+        if self.definition.format != SettingsFormat::Plist {
+            return Vec::new();
+        }
+        let Some(domain) = preferences_domain(&self.settings_path) else {
+            return Vec::new();
+        };
+        self.expected_values(params)
+            .into_iter()
+            .map(|(key, value)| ManagedPreference {
+                domain: domain.clone(),
+                key: key.to_string(),
+                value,
+            })
+            .collect()
+    }
+
+    fn process_names(&self) -> Vec<&str> {
+        self.definition
+            .process_names
+            .iter()
+            .map(String::as_str)
+            .collect()
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let path = self.path_for(params.scope);
+        if !self.is_installed(path) {
+            return Ok(GitClientCheckResult::not_installed());
+        }
+
+        let mut configured = true;
+        for (key, expected) in self.expected_values(params) {
+            if self.read(path, key)?.as_deref() != Some(expected.as_str()) {
+                configured = false;
+                break;
+            }
+        }
+        Ok(GitClientCheckResult {
+            client_installed: true,
+            prefs_configured: configured,
+            prefs_up_to_date: configured,
+            unsupported_reason: None,
+            configured_git_path: None,
+        })
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let path = self.path_for(params.scope);
+        if !self.is_installed(path) {
+            return Ok(None);
+        }
+        let mut diffs = Vec::new();
+        for (key, expected) in self.expected_values(params) {
+            diffs.extend(self.write(path, key, Some(&expected), dry_run)?);
+        }
+        Ok((!diffs.is_empty()).then(|| diffs.concat()))
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let path = self.path_for(params.scope);
+        if !self.is_installed(path) {
+            return Ok(None);
+        }
+        let mut diffs = Vec::new();
+        for (key, expected) in self.expected_values(params) {
+            // Leave a value the user has since changed alone
+            if self.read(path, key)?.as_deref() == Some(expected.as_str()) {
+                diffs.extend(self.write(path, key, None, dry_run)?);
+            }
+        }
+        Ok((!diffs.is_empty()).then(|| diffs.concat()))
+    }
+}
+
+/// Stands in for `clients.toml` when it can't be loaded, so the problem is
+/// reported as a failed client by check, install and uninstall instead of
+/// the custom clients silently disappearing
+pub struct InvalidCustomClients {
+    error: String,
+}
+
+impl InvalidCustomClients {
+    pub fn new(error: GitAiError) -> Self {
+        Self {
+            error: error.to_string(),
+        }
+    }
+}
+
+impl GitClientInstaller for InvalidCustomClients {
+    fn name(&self) -> &str {
+        "clients.toml"
+    }
+
+    fn id(&self) -> &str {
+        "clients-toml"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        true
+    }
+
+    fn check_client(
+        &self,
+        _params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        Err(GitAiError::Generic(self.error.clone()))
+    }
+
+    fn install_prefs(
+        &self,
+        _params: &GitClientInstallerParams,
+        _dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        Err(GitAiError::Generic(self.error.clone()))
+    }
+
+    fn uninstall_prefs(
+        &self,
+        _params: &GitClientInstallerParams,
+        _dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        Err(GitAiError::Generic(self.error.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_clients(dir: &Path, settings_dir: &Path) -> PathBuf {
+        let path = dir.join("clients.toml");
+        fs::write(
+            &path,
+            format!(
+                r#"
+[[client]]
+id = "acme-json"
+name = "Acme JSON"
+settings_path = {json:?}
+format = "json"
+keys = {{ gitPath = "{{shim}}", gitDir = "{{shim_dir}}" }}
+
+[[client]]
+id = "acme-ini"
+name = "Acme INI"
+settings_path = {ini:?}
+format = "ini"
+keys = {{ "General.GitExecutable" = "{{shim}}" }}
+"#,
+                json = settings_dir.join("settings.json"),
+                ini = settings_dir.join("acme.ini"),
+            ),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn custom_clients_install_check_and_uninstall() {
+        let tmp = tempfile::tempdir().unwrap();
+        let clients = write_clients(tmp.path(), tmp.path());
+        let installers = load_custom_clients(&clients, &["fork"]).unwrap();
+        assert_eq!(installers.len(), 2);
+
+        let params = GitClientInstallerParams {
+            git_shim_path: tmp.path().join("bin").join("git"),
+            scope: InstallScope::User,
+        };
+        for installer in &installers {
+            let check = installer.check_client(&params).unwrap();
+            assert!(check.client_installed && !check.prefs_configured);
+            assert!(installer.install_prefs(&params, false).unwrap().is_some());
+            assert!(installer.check_client(&params).unwrap().prefs_configured);
+            assert!(installer.install_prefs(&params, false).unwrap().is_none());
+        }
+
+        let json = tmp.path().join("settings.json");
+        let shim_dir = tmp.path().join("bin");
+        assert_eq!(
+            read_jsonc_string_setting(&json, "gitDir")
+                .unwrap()
+                .as_deref(),
+            Some(shim_dir.to_string_lossy().as_ref())
+        );
+
+        // A value the user changed since install is left in place
+        update_jsonc_string_setting(&json, "gitPath", Some("/usr/bin/git"), false).unwrap();
+        installers[0].uninstall_prefs(&params, false).unwrap();
+        assert_eq!(
+            read_jsonc_string_setting(&json, "gitPath")
+                .unwrap()
+                .as_deref(),
+            Some("/usr/bin/git")
+        );
+        assert_eq!(read_jsonc_string_setting(&json, "gitDir").unwrap(), None);
+
+        installers[1].uninstall_prefs(&params, false).unwrap();
+        assert!(
+            !installers[1]
+                .check_client(&params)
+                .unwrap()
+                .prefs_configured
+        );
+    }
+
+    #[test]
+    fn system_scope_writes_the_system_settings_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (user_dir, system_dir) = (tmp.path().join("user"), tmp.path().join("system"));
+        fs::create_dir_all(&user_dir).unwrap();
+        fs::create_dir_all(&system_dir).unwrap();
+        let clients = tmp.path().join("clients.toml");
+        fs::write(
+            &clients,
+            format!(
+                "[[client]]\nid = \"acme\"\nname = \"Acme\"\nsettings_path = {:?}\nsystem_settings_path = {:?}\nformat = \"json\"\nkeys = {{ gitPath = \"{{shim}}\" }}\n",
+                user_dir.join("settings.json"),
+                system_dir.join("settings.json")
+            ),
+        )
+        .unwrap();
+        let installers = load_custom_clients(&clients, &[]).unwrap();
+        assert!(installers[0].supports_system_scope());
+
+        let params = GitClientInstallerParams {
+            git_shim_path: PathBuf::from("/opt/git-ai/bin/git"),
+            scope: InstallScope::System,
+        };
+        assert!(
+            installers[0]
+                .install_prefs(&params, false)
+                .unwrap()
+                .is_some()
+        );
+        assert!(system_dir.join("settings.json").exists());
+        assert!(!user_dir.join("settings.json").exists());
+
+        fs::write(
+            &clients,
+            "[[client]]\nid = \"x\"\nname = \"X\"\nsettings_path = \"~/x.json\"\nsystem_settings_path = \"~/y.json\"\nformat = \"json\"\nkeys = { gitPath = \"{shim}\" }\n",
+        )
+        .unwrap();
+        let relative = load_custom_clients(&clients, &[]).err().unwrap();
+        assert!(
+            relative.to_string().contains("system_settings_path"),
+            "{relative}"
+        );
+    }
+
+    #[test]
+    fn custom_clients_reject_bad_definitions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let clients = write_clients(tmp.path(), tmp.path());
+        let shadowing = load_custom_clients(&clients, &["acme-ini"]).err().unwrap();
+        assert!(shadowing.to_string().contains("built-in"), "{shadowing}");
+
+        fs::write(
+            &clients,
+            "[[client]]\nid = \"x\"\nname = \"X\"\nsettings_path = \"relative/settings.ini\"\nformat = \"ini\"\nkeys = { \"General.Git\" = \"{shim}\" }\n",
+        )
+        .unwrap();
+        let relative = load_custom_clients(&clients, &[]).err().unwrap();
+        assert!(relative.to_string().contains("settings_path"), "{relative}");
+
+        fs::write(
+            &clients,
+            "[[client]]\nid = \"x\"\nname = \"X\"\nsettings_path = \"/tmp/x\"\nformat = \"xml\"\nkeys = {}\n",
+        )
+        .unwrap();
+        assert!(load_custom_clients(&clients, &[]).is_err());
+
+        assert!(
+            load_custom_clients(&tmp.path().join("missing.toml"), &[])
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,1111 @@
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+#[cfg(target_os = "linux")]
+use crate::mdm::utils::gsettings_schema_installed;
+#[cfg(windows)]
+use crate::mdm::utils::windows_app_data_dir;
+#[cfg(target_os = "macos")]
+use crate::mdm::utils::{MacApp, find_app_by_bundle_id, find_macos_app};
+#[cfg(windows)]
+use crate::mdm::utils::{WindowsApp, find_windows_install_dir, registry_key_exists};
+use crate::mdm::utils::{binary_exists, home_dir, shim_first_on_path};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A client git-ai can detect but never configure: it either commits without
+/// running a git executable, or runs whichever `git` comes first on PATH and
+/// has no setting to point elsewhere. Install and uninstall write nothing;
+/// `check_client` reports why, or whether PATH already reaches the shim.
+pub struct DetectionOnlyInstaller {
+    name: &'static str,
+    id: &'static str,
+    platform_supported: bool,
+    check: fn(&GitClientInstallerParams) -> GitClientCheckResult,
+}
+
+/// An installed client that commits `how` and never runs a git executable
+fn runs_git_internally(name: &str, how: &str) -> GitClientCheckResult {
+    GitClientCheckResult::unsupported(format!(
+        "{} {} and has no git executable setting",
+        name, how
+    ))
+}
+
+/// An installed client that runs git from PATH, covered exactly when the
+/// shim's directory comes first there
+fn runs_git_from_path(name: &str, shim_first: bool, shim_dir: &Path) -> GitClientCheckResult {
+    if shim_first {
+        return GitClientCheckResult {
+            client_installed: true,
+            prefs_configured: true,
+            prefs_up_to_date: true,
+            unsupported_reason: None,
+            configured_git_path: None,
+        };
+    }
+    GitClientCheckResult::unsupported(format!(
+        "{} runs git from PATH; add {} to the front of PATH in your shell profile",
+        name,
+        shim_dir.display()
+    ))
+}
+
+fn shim_first(params: &GitClientInstallerParams) -> bool {
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    shim_first_on_path(&path_var, &params.git_shim_path)
+}
+
+fn shim_dir(params: &GitClientInstallerParams) -> &Path {
+    params.git_shim_path.parent().unwrap_or(Path::new("."))
+}
+
+fn any_exists(candidates: &[PathBuf]) -> bool {
+    candidates.iter().any(|path| path.exists())
+}
+
+impl DetectionOnlyInstaller {
+    /// Eclipse EGit reads and writes repositories through JGit, in-process.
+    /// Its Git preferences only point at a native install to find the
+    /// system-wide gitconfig, so commits never reach the shim.
+    pub fn egit() -> Self {
+        Self {
+            name: "Eclipse EGit",
+            id: "egit",
+            platform_supported: true,
+            check: |_| {
+                let workspaces = egit_workspaces();
+                if workspaces.is_empty() {
+                    return GitClientCheckResult::not_installed();
+                }
+                let mut result = runs_git_internally("Eclipse EGit", "commits through JGit");
+                if let Some(reason) = result.unsupported_reason.as_mut() {
+                    reason.push_str(&format!(" (workspaces: {})", list_paths(&workspaces)));
+                }
+                result
+            },
+        }
+    }
+
+    /// Git's bundled Tcl tools. Neither has a git executable setting: the
+    /// `gui.*` keys in gitconfig only cover fonts, diff context, spelling and
+    /// the like. `gitk` spawns a plain `git` from PATH, so it reaches the shim
+    /// once the shim's directory comes first. `git gui` is started by git
+    /// itself, which puts its exec-path (holding the real `git`) at the front
+    /// of PATH for the subcommand, so it never reaches the shim.
+    pub fn git_gui() -> Self {
+        Self {
+            name: "Git GUI / gitk",
+            id: "git-gui",
+            platform_supported: true,
+            check: |params| {
+                tcl_tools_check(
+                    binary_exists("gitk"),
+                    git_gui_path().as_deref(),
+                    shim_first(params),
+                    shim_dir(params),
+                )
+            },
+        }
+    }
+
+    /// GitAhead reads and writes repositories through libgit2 and never execs
+    /// `git` for commits; its settings only cover diff/merge tools, the
+    /// terminal and credential storage.
+    pub fn gitahead() -> Self {
+        Self {
+            name: "GitAhead",
+            id: "gitahead",
+            platform_supported: cfg!(any(target_os = "macos", target_os = "linux", windows)),
+            check: |_| {
+                if !gitahead_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_internally("GitAhead", "commits through libgit2")
+            },
+        }
+    }
+
+    /// GitButler creates commits and rewrites branches in-process with
+    /// gitoxide, and only spawns `git` from PATH for fetch/push. Its
+    /// `settings.json` has no git executable or hooks option.
+    pub fn gitbutler() -> Self {
+        Self {
+            name: "GitButler",
+            id: "gitbutler",
+            platform_supported: cfg!(any(target_os = "macos", target_os = "linux", windows)),
+            check: |_| {
+                if !gitbutler_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_internally("GitButler", "commits in-process (gitoxide)")
+            },
+        }
+    }
+
+    /// gitg works on repositories through libgit2-glib and never execs `git`.
+    /// Its `org.gnome.gitg` GSettings schema only covers display and commit
+    /// message preferences.
+    pub fn gitg() -> Self {
+        Self {
+            name: "gitg",
+            id: "gitg",
+            platform_supported: cfg!(target_os = "linux"),
+            check: |_| {
+                if !gitg_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_internally("gitg", "commits through libgit2")
+            },
+        }
+    }
+
+    /// GitHub Desktop always runs the git bundled by dugite. Its preferences
+    /// (desktop/desktop `app/src/ui/preferences`, checked as of 3.4) have no
+    /// git executable setting. The only override is dugite's
+    /// `LOCAL_GIT_DIRECTORY` environment variable (`resolveGitDir` in
+    /// dugite's `lib/git-environment.ts`), which must be set when the app is
+    /// launched and names a whole git distribution (`bin/git` or
+    /// `cmd/git.exe` next to `libexec/git-core`), not a single executable like
+    /// the shim. So there is nothing durable to point at the shim.
+    pub fn github_desktop() -> Self {
+        Self {
+            name: "GitHub Desktop",
+            id: "github-desktop",
+            platform_supported: cfg!(any(target_os = "macos", windows)),
+            check: |_| {
+                if !github_desktop_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_internally("GitHub Desktop", "uses its bundled git (dugite)")
+            },
+        }
+    }
+
+    /// Gittyup (a GitAhead fork) reads and writes repositories through
+    /// libgit2 and never execs `git` for commits. Its external tools settings
+    /// only launch diff/merge tools.
+    pub fn gittyup() -> Self {
+        Self {
+            name: "Gittyup",
+            id: "gittyup",
+            platform_supported: cfg!(any(target_os = "linux", windows)),
+            check: |_| {
+                if !gittyup_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_internally("Gittyup", "commits through libgit2")
+            },
+        }
+    }
+
+    /// gitui reads, stages and commits through libgit2 (via its `asyncgit`
+    /// crate) rather than exec'ing `git`. Its `key_bindings.ron` and
+    /// `theme.ron` only cover keys and colours.
+    pub fn gitui() -> Self {
+        Self {
+            name: "gitui",
+            id: "gitui",
+            platform_supported: true,
+            check: |_| {
+                if !gitui_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_internally("gitui", "commits through libgit2")
+            },
+        }
+    }
+
+    /// lazygit's `config.yml` has no git executable setting: the `git:`
+    /// section covers paging, commit and log options and `os:` only
+    /// editor/open commands. Every git call is a plain `git` spawned from the
+    /// terminal's PATH.
+    pub fn lazygit() -> Self {
+        Self {
+            name: "lazygit",
+            id: "lazygit",
+            platform_supported: true,
+            check: |params| {
+                if !lazygit_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_from_path("lazygit", shim_first(params), shim_dir(params))
+            },
+        }
+    }
+
+    /// GitUp drives repositories through libgit2 (GitUpKit) and never spawns
+    /// a git executable.
+    pub fn gitup() -> Self {
+        Self {
+            name: "GitUp",
+            id: "gitup",
+            platform_supported: cfg!(target_os = "macos"),
+            check: |_| {
+                if !gitup_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_internally("GitUp", "uses libgit2 internally")
+            },
+        }
+    }
+
+    /// NetBeans' Git support is built on JGit (`org.netbeans.libs.git.jgit`)
+    /// and runs in-process. Unlike its Subversion and Mercurial modules it has
+    /// no executable path in `nbpreferences`.
+    pub fn netbeans() -> Self {
+        Self {
+            name: "NetBeans",
+            id: "netbeans",
+            platform_supported: true,
+            check: |_| {
+                let user_dirs = netbeans_git_user_dirs();
+                if user_dirs.is_empty() {
+                    return GitClientCheckResult::not_installed();
+                }
+                let mut result = runs_git_internally("NetBeans", "commits through JGit");
+                if let Some(reason) = result.unsupported_reason.as_mut() {
+                    reason.push_str(&format!(" (user dirs: {})", list_paths(&user_dirs)));
+                }
+                result
+            },
+        }
+    }
+
+    /// Visual Studio runs the Git for Windows copy bundled under
+    /// `Common7\IDE\CommonExtensions\Microsoft\TeamFoundation\Team Explorer\Git`.
+    /// Its Git options (Tools > Options > Source Control) cover identity and
+    /// repository defaults only, and the IDE keeps them in its private
+    /// registry hive.
+    pub fn visual_studio() -> Self {
+        Self {
+            name: "Visual Studio 2022",
+            id: "visual-studio",
+            platform_supported: cfg!(windows),
+            check: |_| {
+                if !visual_studio_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_internally("Visual Studio 2022", "runs its bundled Git")
+            },
+        }
+    }
+
+    /// Xcode's source control runs `Contents/Developer/usr/bin/git` from its
+    /// own bundle by absolute path. `DEVELOPER_DIR` and `xcode-select` only
+    /// steer `xcrun` and the `/usr/bin/git` stub, which terminal use already
+    /// reaches through the shim on PATH; replacing the binary inside the
+    /// bundle would break Xcode's code signature.
+    pub fn xcode() -> Self {
+        Self {
+            name: "Xcode",
+            id: "xcode",
+            platform_supported: cfg!(target_os = "macos"),
+            check: |_| {
+                if !xcode_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_internally("Xcode", "runs the git inside its app bundle")
+            },
+        }
+    }
+
+    /// Zed has no setting for the git executable: `~/.config/zed/settings.json`
+    /// only covers gutter/blame display. It spawns whichever `git` comes first
+    /// on the PATH it captures from the user's login shell.
+    pub fn zed() -> Self {
+        Self {
+            name: "Zed",
+            id: "zed",
+            platform_supported: cfg!(any(target_os = "macos", target_os = "linux")),
+            check: |params| {
+                if !zed_installed() {
+                    return GitClientCheckResult::not_installed();
+                }
+                runs_git_from_path("Zed", shim_first(params), shim_dir(params))
+            },
+        }
+    }
+}
+
+impl GitClientInstaller for DetectionOnlyInstaller {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        self.platform_supported
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        Ok((self.check)(params))
+    }
+
+    fn install_prefs(
+        &self,
+        _params: &GitClientInstallerParams,
+        _dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        // Nothing to write: the client has no git executable preference
+        Ok(None)
+    }
+
+    fn uninstall_prefs(
+        &self,
+        _params: &GitClientInstallerParams,
+        _dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        Ok(None)
+    }
+}
+
+fn list_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Eclipse EGit
+
+/// Preference file where the Eclipse launcher records recently used workspaces
+const ECLIPSE_IDE_PREFS: &str = "configuration/.settings/org.eclipse.ui.ide.prefs";
+
+/// Workspace metadata EGit creates the first time it is used there
+const EGIT_WORKSPACE_STATE: &str = ".metadata/.plugins/org.eclipse.egit.core";
+
+/// Per-user Eclipse configuration areas, one per installed product
+fn eclipse_configuration_prefs() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(home_dir().join(".eclipse")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path().join(ECLIPSE_IDE_PREFS))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Recent workspaces that have EGit state, across all installations
+fn egit_workspaces() -> Vec<PathBuf> {
+    let mut workspaces: Vec<PathBuf> = Vec::new();
+    for prefs in eclipse_configuration_prefs() {
+        let Ok(content) = fs::read_to_string(&prefs) else {
+            continue;
+        };
+        for workspace in recent_workspaces(&content) {
+            if uses_egit(&workspace) && !workspaces.contains(&workspace) {
+                workspaces.push(workspace);
+            }
+        }
+    }
+    workspaces
+}
+
+fn uses_egit(workspace: &Path) -> bool {
+    workspace.join(EGIT_WORKSPACE_STATE).is_dir()
+}
+
+/// Workspaces listed under `RECENT_WORKSPACES` in a Java properties file
+fn recent_workspaces(prefs: &str) -> Vec<PathBuf> {
+    prefs
+        .lines()
+        .find_map(|line| line.strip_prefix("RECENT_WORKSPACES="))
+        .map(|value| {
+            unescape_property(value)
+                .lines()
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Undo Java properties escaping (`\n`, `\:`, `\\`, ...)
+fn unescape_property(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+// Git GUI / gitk
+
+/// Where git keeps its subcommands, `git-gui` among them
+fn git_exec_path() -> Option<PathBuf> {
+    let output = Command::new(Config::get().real_git()?)
+        .arg("--exec-path")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let path = String::from_utf8(output.stdout).ok()?;
+    Some(PathBuf::from(path.trim()))
+}
+
+/// The `git gui` subcommand, when this git ships it
+fn git_gui_path() -> Option<PathBuf> {
+    let exec_path = git_exec_path()?;
+    ["git-gui", "git-gui.exe"]
+        .iter()
+        .map(|name| exec_path.join(name))
+        .find(|path| path.is_file())
+}
+
+fn tcl_tools_check(
+    has_gitk: bool,
+    git_gui: Option<&Path>,
+    shim_first: bool,
+    shim_dir: &Path,
+) -> GitClientCheckResult {
+    if !has_gitk && git_gui.is_none() {
+        return GitClientCheckResult::not_installed();
+    }
+    let gitk = runs_git_from_path("gitk", shim_first, shim_dir);
+    match git_gui {
+        Some(git_gui) if gitk.prefs_configured => GitClientCheckResult::unsupported(format!(
+            "`git gui` runs the git next to {} ahead of PATH; launch gitk directly, or commit from a shell",
+            git_gui.display()
+        )),
+        _ => gitk,
+    }
+}
+
+// GitAhead
+
+#[cfg(target_os = "macos")]
+fn gitahead_candidates() -> Vec<PathBuf> {
+    let home = home_dir();
+    vec![
+        PathBuf::from("/Applications/GitAhead.app"),
+        home.join("Applications").join("GitAhead.app"),
+        home.join("Library")
+            .join("Preferences")
+            .join("com.gitahead.GitAhead.plist"),
+    ]
+}
+
+#[cfg(target_os = "linux")]
+fn gitahead_candidates() -> Vec<PathBuf> {
+    let home = home_dir();
+    vec![
+        PathBuf::from("/opt/gitahead/GitAhead"),
+        // The self-extracting installer unpacks into ./GitAhead
+        home.join("GitAhead").join("GitAhead"),
+        home.join(".config").join("gitahead.com"),
+    ]
+}
+
+#[cfg(windows)]
+fn gitahead_candidates() -> Vec<PathBuf> {
+    let registered = find_windows_install_dir(&WindowsApp {
+        display_name: "GitAhead",
+        scoop: Some("gitahead"),
+        ..Default::default()
+    })
+    .map(|dir| dir.join("GitAhead.exe"));
+    ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var))
+        .map(|dir| PathBuf::from(dir).join("GitAhead").join("GitAhead.exe"))
+        .chain(registered)
+        .collect()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+fn gitahead_candidates() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+#[cfg(windows)]
+fn gitahead_installed() -> bool {
+    // QSettings keeps GitAhead's preferences under HKCU\Software\gitahead.com
+    registry_key_exists(r"Software\gitahead.com") || any_exists(&gitahead_candidates())
+}
+
+#[cfg(not(windows))]
+fn gitahead_installed() -> bool {
+    any_exists(&gitahead_candidates())
+}
+
+// GitButler
+
+/// Tauri bundle identifier, also the name of its per-user data directory
+#[cfg(any(target_os = "macos", target_os = "linux", windows))]
+const GITBUTLER_APP_ID: &str = "com.gitbutler.app";
+
+#[cfg(target_os = "macos")]
+fn gitbutler_candidates() -> Vec<PathBuf> {
+    let home = home_dir();
+    vec![
+        PathBuf::from("/Applications/GitButler.app"),
+        home.join("Applications").join("GitButler.app"),
+        home.join("Library")
+            .join("Application Support")
+            .join(GITBUTLER_APP_ID),
+    ]
+}
+
+#[cfg(target_os = "linux")]
+fn gitbutler_candidates() -> Vec<PathBuf> {
+    let home = home_dir();
+    vec![
+        PathBuf::from("/usr/bin/gitbutler-tauri"),
+        home.join(".local").join("share").join(GITBUTLER_APP_ID),
+        home.join(".config").join("gitbutler"),
+    ]
+}
+
+#[cfg(windows)]
+fn gitbutler_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+        candidates.push(
+            PathBuf::from(local_app_data)
+                .join("GitButler")
+                .join("GitButler.exe"),
+        );
+    }
+    if let Ok(app_data) = std::env::var("APPDATA") {
+        candidates.push(PathBuf::from(app_data).join(GITBUTLER_APP_ID));
+    }
+    candidates
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+fn gitbutler_candidates() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+#[cfg(target_os = "macos")]
+fn gitbutler_installed() -> bool {
+    find_app_by_bundle_id(GITBUTLER_APP_ID).is_some() || any_exists(&gitbutler_candidates())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn gitbutler_installed() -> bool {
+    any_exists(&gitbutler_candidates())
+}
+
+// gitg
+
+#[cfg(target_os = "linux")]
+const GITG_ID: &str = "org.gnome.gitg";
+
+#[cfg(target_os = "linux")]
+fn gitg_installed() -> bool {
+    let flatpaks = [
+        PathBuf::from("/var/lib/flatpak/app").join(GITG_ID),
+        home_dir().join(".local/share/flatpak/app").join(GITG_ID),
+    ];
+    binary_exists("gitg") || gsettings_schema_installed(GITG_ID) || any_exists(&flatpaks)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn gitg_installed() -> bool {
+    false
+}
+
+// GitHub Desktop
+
+#[cfg(target_os = "macos")]
+fn github_desktop_installed() -> bool {
+    find_macos_app(&MacApp {
+        bundle_id: "com.github.GitHubClient",
+        app_name: "GitHub Desktop.app",
+        cask: Some("github"),
+    })
+    .is_some()
+}
+
+#[cfg(windows)]
+fn github_desktop_installed() -> bool {
+    // Squirrel installs per-user under %LOCALAPPDATA%\GitHubDesktop; the
+    // machine-wide MSI deploys it there at each user's first logon
+    let mut candidates: Vec<PathBuf> = find_windows_install_dir(&WindowsApp {
+        display_name: "GitHub Desktop",
+        scoop: Some("github"),
+        chocolatey: Some("github-desktop"),
+        winget: Some("GitHub.GitHubDesktop"),
+    })
+    .map(|dir| dir.join("GitHubDesktop.exe"))
+    .into_iter()
+    .collect();
+    if let Ok(local_app_data) = std::env::var("LOCALAPPDATA") {
+        candidates.push(
+            PathBuf::from(local_app_data)
+                .join("GitHubDesktop")
+                .join("GitHubDesktop.exe"),
+        );
+    }
+    any_exists(&candidates)
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn github_desktop_installed() -> bool {
+    false
+}
+
+// Gittyup
+
+#[cfg(target_os = "linux")]
+fn gittyup_installed() -> bool {
+    const FLATPAK_ID: &str = "com.github.Murmele.Gittyup";
+    let home = home_dir();
+    any_exists(&[
+        PathBuf::from("/usr/bin/gittyup"),
+        PathBuf::from("/usr/local/bin/gittyup"),
+        PathBuf::from("/var/lib/flatpak/app").join(FLATPAK_ID),
+        home.join(".local/share/flatpak/app").join(FLATPAK_ID),
+        home.join(".config").join("gittyup"),
+    ])
+}
+
+#[cfg(windows)]
+fn gittyup_installed() -> bool {
+    let registered = find_windows_install_dir(&WindowsApp {
+        display_name: "Gittyup",
+        winget: Some("Murmele.Gittyup"),
+        ..Default::default()
+    })
+    .map(|dir| dir.join("Gittyup.exe"));
+    let candidates: Vec<PathBuf> = ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var))
+        .map(|dir| PathBuf::from(dir).join("Gittyup").join("Gittyup.exe"))
+        .chain(registered)
+        .collect();
+    // QSettings keeps Gittyup's preferences under HKCU\Software\gittyup
+    registry_key_exists(r"Software\gittyup") || any_exists(&candidates)
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn gittyup_installed() -> bool {
+    false
+}
+
+// gitui
+
+/// Config directories gitui uses across releases and platforms
+fn gitui_config_dirs() -> Vec<PathBuf> {
+    let home = home_dir();
+    let mut dirs = Vec::new();
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME") {
+        dirs.push(PathBuf::from(xdg).join("gitui"));
+    }
+    #[cfg(target_os = "macos")]
+    dirs.push(
+        home.join("Library")
+            .join("Application Support")
+            .join("gitui"),
+    );
+    #[cfg(windows)]
+    if let Some(app_data) = std::env::var_os("APPDATA") {
+        dirs.push(PathBuf::from(app_data).join("gitui"));
+    }
+    dirs.push(home.join(".config").join("gitui"));
+    dirs
+}
+
+fn gitui_installed() -> bool {
+    binary_exists("gitui") || gitui_config_dirs().iter().any(|dir| dir.is_dir())
+}
+
+// GitUp
+
+#[cfg(target_os = "macos")]
+fn gitup_installed() -> bool {
+    find_app_by_bundle_id("co.gitup.mac").is_some()
+        || any_exists(&[
+            PathBuf::from("/Applications/GitUp.app"),
+            home_dir().join("Applications").join("GitUp.app"),
+        ])
+}
+
+#[cfg(not(target_os = "macos"))]
+fn gitup_installed() -> bool {
+    false
+}
+
+// lazygit
+
+/// Config directories lazygit reads, in its own lookup order
+fn lazygit_config_dirs() -> Vec<PathBuf> {
+    let home = home_dir();
+    let mut dirs = Vec::new();
+    if let Some(dir) = std::env::var_os("CONFIG_DIR") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME") {
+        dirs.push(PathBuf::from(xdg).join("lazygit"));
+    }
+    #[cfg(target_os = "macos")]
+    dirs.push(
+        home.join("Library")
+            .join("Application Support")
+            .join("lazygit"),
+    );
+    #[cfg(windows)]
+    if let Some(app_data) = std::env::var_os("APPDATA") {
+        dirs.push(PathBuf::from(app_data).join("lazygit"));
+    }
+    dirs.push(home.join(".config").join("lazygit"));
+    dirs
+}
+
+fn lazygit_installed() -> bool {
+    binary_exists("lazygit") || lazygit_config_dirs().iter().any(|dir| dir.is_dir())
+}
+
+// NetBeans
+
+/// Preferences the NetBeans Git module writes once it has been used
+const NETBEANS_GIT_MODULE_PREFS: &str = "config/Preferences/org/netbeans/modules/git.properties";
+
+/// Directories holding one user dir per NetBeans version
+#[cfg(target_os = "macos")]
+fn netbeans_user_dir_roots() -> Vec<PathBuf> {
+    vec![
+        home_dir()
+            .join("Library")
+            .join("Application Support")
+            .join("NetBeans"),
+    ]
+}
+
+#[cfg(windows)]
+fn netbeans_user_dir_roots() -> Vec<PathBuf> {
+    windows_app_data_dir("NetBeans").into_iter().collect()
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn netbeans_user_dir_roots() -> Vec<PathBuf> {
+    // Older releases used ~/.netbeans; current ones
+    // follow XDG_DATA_HOME
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| home_dir().join(".local").join("share"));
+    vec![data_home.join("NetBeans"), home_dir().join(".netbeans")]
+}
+
+#[cfg(not(any(unix, windows)))]
+fn netbeans_user_dir_roots() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// User dirs whose Git module has been used, across all versions
+fn netbeans_git_user_dirs() -> Vec<PathBuf> {
+    netbeans_user_dir_roots()
+        .iter()
+        .flat_map(|root| versioned_user_dirs(root))
+        .filter(|dir| uses_git_module(dir))
+        .collect()
+}
+
+/// Version-named user dirs (`21`, `12.6`, `dev`) directly under `root`,
+/// sorted by name. Skips siblings like `cache` or `registration`.
+fn versioned_user_dirs(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name == "dev" || name.starts_with(|c: char| c.is_ascii_digit()))
+        })
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn uses_git_module(user_dir: &Path) -> bool {
+    user_dir.join(NETBEANS_GIT_MODULE_PREFS).is_file()
+}
+
+// Visual Studio 2022
+
+/// Installation paths reported by vswhere, which ships with the
+/// Visual Studio Installer
+#[cfg(windows)]
+fn vswhere_installations() -> Vec<PathBuf> {
+    let Some(program_files) = std::env::var_os("ProgramFiles(x86)") else {
+        return Vec::new();
+    };
+    let vswhere = PathBuf::from(program_files)
+        .join("Microsoft Visual Studio")
+        .join("Installer")
+        .join("vswhere.exe");
+    if !vswhere.is_file() {
+        return Vec::new();
+    }
+    // Major version 17 is Visual Studio 2022
+    let Ok(output) = Command::new(vswhere)
+        .args([
+            "-version",
+            "[17.0,18.0)",
+            "-products",
+            "*",
+            "-property",
+            "installationPath",
+        ])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Default install locations, for machines where vswhere is missing
+#[cfg(windows)]
+fn default_visual_studio_installations() -> Vec<PathBuf> {
+    let Some(program_files) = std::env::var_os("ProgramFiles") else {
+        return Vec::new();
+    };
+    let root = PathBuf::from(program_files)
+        .join("Microsoft Visual Studio")
+        .join("2022");
+    ["Community", "Professional", "Enterprise"]
+        .iter()
+        .map(|edition| root.join(edition))
+        .collect()
+}
+
+#[cfg(windows)]
+fn visual_studio_installed() -> bool {
+    !vswhere_installations().is_empty()
+        || default_visual_studio_installations().iter().any(|path| {
+            path.join("Common7")
+                .join("IDE")
+                .join("devenv.exe")
+                .is_file()
+        })
+}
+
+#[cfg(not(windows))]
+fn visual_studio_installed() -> bool {
+    false
+}
+
+// Xcode
+
+/// The active developer directory, when it belongs to an Xcode install
+/// rather than the standalone Command Line Tools
+#[cfg(target_os = "macos")]
+fn selected_xcode() -> Option<PathBuf> {
+    let output = Command::new("xcode-select")
+        .arg("--print-path")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let developer_dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    developer_dir
+        .ancestors()
+        .find(|dir| dir.extension().is_some_and(|ext| ext == "app"))
+        .map(Path::to_path_buf)
+}
+
+#[cfg(target_os = "macos")]
+fn xcode_installed() -> bool {
+    selected_xcode().is_some()
+        || any_exists(&[
+            PathBuf::from("/Applications/Xcode.app"),
+            PathBuf::from("/Applications/Xcode-beta.app"),
+            home_dir().join("Applications").join("Xcode.app"),
+        ])
+        || find_app_by_bundle_id("com.apple.dt.Xcode").is_some()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn xcode_installed() -> bool {
+    false
+}
+
+// Zed
+
+#[cfg(target_os = "macos")]
+fn zed_installed() -> bool {
+    let home = home_dir();
+    find_app_by_bundle_id("dev.zed.Zed").is_some()
+        || any_exists(&[
+            home.join(".config").join("zed"),
+            PathBuf::from("/Applications/Zed.app"),
+            home.join("Applications").join("Zed.app"),
+        ])
+}
+
+#[cfg(target_os = "linux")]
+fn zed_installed() -> bool {
+    let home = home_dir();
+    // ~/.local/zed.app is where the official install script puts it
+    any_exists(&[
+        home.join(".config").join("zed"),
+        home.join(".local").join("zed.app"),
+    ])
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn zed_installed() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mdm::git_client_installer::InstallScope;
+
+    fn params() -> GitClientInstallerParams {
+        GitClientInstallerParams {
+            git_shim_path: PathBuf::from("/tmp/git-ai/bin/git"),
+            scope: InstallScope::User,
+        }
+    }
+
+    fn all() -> Vec<DetectionOnlyInstaller> {
+        vec![
+            DetectionOnlyInstaller::egit(),
+            DetectionOnlyInstaller::git_gui(),
+            DetectionOnlyInstaller::gitahead(),
+            DetectionOnlyInstaller::gitbutler(),
+            DetectionOnlyInstaller::gitg(),
+            DetectionOnlyInstaller::github_desktop(),
+            DetectionOnlyInstaller::gittyup(),
+            DetectionOnlyInstaller::gitui(),
+            DetectionOnlyInstaller::gitup(),
+            DetectionOnlyInstaller::lazygit(),
+            DetectionOnlyInstaller::netbeans(),
+            DetectionOnlyInstaller::visual_studio(),
+            DetectionOnlyInstaller::xcode(),
+            DetectionOnlyInstaller::zed(),
+        ]
+    }
+
+    #[test]
+    fn prefs_are_never_written() {
+        for installer in all() {
+            assert_eq!(installer.install_prefs(&params(), false).unwrap(), None);
+            assert_eq!(installer.uninstall_prefs(&params(), false).unwrap(), None);
+
+            let result = installer.check_client(&params()).unwrap();
+            if !installer.is_platform_supported() {
+                assert!(!result.client_installed, "{}", installer.id());
+            }
+            if result.client_installed && !result.prefs_configured {
+                let reason = result.unsupported_reason.unwrap();
+                assert!(reason.starts_with(installer.name()), "{}", reason);
+            }
+        }
+    }
+
+    #[test]
+    fn path_clients_are_covered_only_with_the_shim_first_on_path() {
+        let shim_dir = Path::new("/home/dev/.git-ai/bin");
+        let git_gui = Path::new("/usr/lib/git-core/git-gui");
+
+        assert!(runs_git_from_path("Zed", true, shim_dir).prefs_configured);
+        let reason = runs_git_from_path("Zed", false, shim_dir)
+            .unsupported_reason
+            .unwrap();
+        assert!(reason.contains("/home/dev/.git-ai/bin"));
+
+        assert!(!tcl_tools_check(false, None, true, shim_dir).client_installed);
+
+        let gitk_only = tcl_tools_check(true, None, true, shim_dir);
+        assert!(gitk_only.prefs_configured);
+        assert!(gitk_only.unsupported_reason.is_none());
+
+        let reason = tcl_tools_check(true, None, false, shim_dir)
+            .unsupported_reason
+            .unwrap();
+        assert!(reason.starts_with("gitk runs git from PATH"));
+
+        let reason = tcl_tools_check(true, Some(git_gui), true, shim_dir)
+            .unsupported_reason
+            .unwrap();
+        assert!(reason.contains("git gui"));
+    }
+
+    #[test]
+    fn recent_workspaces_are_unescaped_and_filtered_to_egit_users() {
+        let tmp = tempfile::tempdir().unwrap();
+        let with_egit = tmp.path().join("ws-app");
+        let without_egit = tmp.path().join("ws-scratch");
+        fs::create_dir_all(with_egit.join(EGIT_WORKSPACE_STATE)).unwrap();
+        fs::create_dir_all(&without_egit).unwrap();
+
+        let escape = |path: &Path| path.display().to_string().replace(':', "\\:");
+        let prefs = format!(
+            "MAX_RECENT_WORKSPACES=10\nRECENT_WORKSPACES={}\\n{}\nRECENT_WORKSPACES_PROTOCOL=3\n",
+            escape(&with_egit),
+            escape(&without_egit)
+        );
+
+        let workspaces = recent_workspaces(&prefs);
+        assert_eq!(workspaces, vec![with_egit.clone(), without_egit.clone()]);
+        assert!(uses_egit(&with_egit));
+        assert!(!uses_egit(&without_egit));
+        assert_eq!(
+            unescape_property(r"C\:\\Users\\dev\\workspace"),
+            r"C:\Users\dev\workspace"
+        );
+    }
+
+    #[test]
+    fn only_versioned_netbeans_user_dirs_with_git_module_prefs_are_reported() {
+        let root = tempfile::tempdir().unwrap();
+        for dir in ["21", "12.6", "dev", "cache", "registration"] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+        let prefs = root.path().join("21").join(NETBEANS_GIT_MODULE_PREFS);
+        fs::create_dir_all(prefs.parent().unwrap()).unwrap();
+        fs::write(&prefs, "autoRefresh=true\n").unwrap();
+
+        let dirs = versioned_user_dirs(root.path());
+        let names: Vec<_> = dirs
+            .iter()
+            .map(|dir| dir.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["12.6", "21", "dev"]);
+
+        let with_git: Vec<_> = dirs.iter().filter(|dir| uses_git_module(dir)).collect();
+        assert_eq!(with_git, vec![&root.path().join("21")]);
+        assert!(versioned_user_dirs(&root.path().join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+use crate::mdm::utils::{home_dir, read_jsonc_string_setting, update_jsonc_string_setting};
+use std::path::PathBuf;
+
+/// Fleet setting overriding the git executable (Settings → Git → "Git path")
+const GIT_PATH_SETTING: &str = "git.path";
+
+/// Points JetBrains Fleet at the git shim. Fleet does not share the IntelliJ
+/// platform's per-version config directories, so the JetBrains installer never
+/// sees it; its user settings live in `~/.fleet/settings.json` on every OS.
+pub struct FleetInstaller;
+
+impl FleetInstaller {
+    fn fleet_dir() -> PathBuf {
+        home_dir().join(".fleet")
+    }
+
+    fn settings_path() -> PathBuf {
+        Self::fleet_dir().join("settings.json")
+    }
+
+    #[cfg(target_os = "macos")]
+    fn app_candidates() -> Vec<PathBuf> {
+        let home = home_dir();
+        vec![
+            PathBuf::from("/Applications/Fleet.app"),
+            home.join("Applications").join("Fleet.app"),
+            home.join("Library")
+                .join("Application Support")
+                .join("JetBrains")
+                .join("Toolbox")
+                .join("apps")
+                .join("Fleet"),
+        ]
+    }
+
+    #[cfg(target_os = "linux")]
+    fn app_candidates() -> Vec<PathBuf> {
+        vec![
+            home_dir()
+                .join(".local")
+                .join("share")
+                .join("JetBrains")
+                .join("Toolbox")
+                .join("apps")
+                .join("fleet"),
+        ]
+    }
+
+    #[cfg(windows)]
+    fn app_candidates() -> Vec<PathBuf> {
+        std::env::var_os("LOCALAPPDATA")
+            .map(|local| {
+                let local = PathBuf::from(local);
+                vec![
+                    local.join("Programs").join("Fleet"),
+                    local
+                        .join("JetBrains")
+                        .join("Toolbox")
+                        .join("apps")
+                        .join("Fleet"),
+                ]
+            })
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    fn app_candidates() -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Fleet creates `~/.fleet` on first launch; a fresh install is still
+    /// found through its app or Toolbox directory
+    fn is_installed() -> bool {
+        Self::fleet_dir().is_dir() || Self::app_candidates().iter().any(|path| path.exists())
+    }
+}
+
+impl GitClientInstaller for FleetInstaller {
+    fn name(&self) -> &str {
+        "Fleet"
+    }
+
+    fn id(&self) -> &str {
+        "fleet"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        cfg!(any(target_os = "macos", target_os = "linux", windows))
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        if !Self::is_installed() {
+            return Ok(GitClientCheckResult::not_installed());
+        }
+
+        Ok(GitClientCheckResult::with_git_path(
+            read_jsonc_string_setting(&Self::settings_path(), GIT_PATH_SETTING)?,
+            &params.git_shim_path.to_string_lossy(),
+        ))
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        if !Self::is_installed() {
+            return Ok(None);
+        }
+        let shim = params.git_shim_path.to_string_lossy();
+        update_jsonc_string_setting(
+            &Self::settings_path(),
+            GIT_PATH_SETTING,
+            Some(&shim),
+            dry_run,
+        )
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let path = Self::settings_path();
+        // Leave a user-chosen git path alone
+        let shim = params.git_shim_path.to_string_lossy();
+        if read_jsonc_string_setting(&path, GIT_PATH_SETTING)?.as_deref() != Some(shim.as_ref()) {
+            return Ok(None);
+        }
+        update_jsonc_string_setting(&path, GIT_PATH_SETTING, None, dry_run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn git_path_is_a_flat_dotted_key() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("settings.json");
+        fs::write(
+            &path,
+            "{\n  // editor\n  \"editor.fontSize\": 13,\n  \"git.fetchPeriodically\": true\n}\n",
+        )
+        .unwrap();
+
+        let shim = "/Users/dev/.git-ai/bin/git";
+        let diff = update_jsonc_string_setting(&path, GIT_PATH_SETTING, Some(shim), false)
+            .unwrap()
+            .unwrap();
+        assert!(diff.contains("\"git.path\""));
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("// editor"));
+        assert!(content.contains("\"git.fetchPeriodically\": true"));
+        assert_eq!(
+            read_jsonc_string_setting(&path, GIT_PATH_SETTING)
+                .unwrap()
+                .as_deref(),
+            Some(shim)
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use crate::error::GitAiError;
+#[cfg(any(target_os = "macos", windows, test))]
+use crate::mdm::git_client_installer::select_prefs_variant;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams, InstallScope,
+    ManagedPreference, PrefsVariant, VersionRange, prefs_problem,
+};
+use crate::mdm::utils::parse_version;
+#[cfg(target_os = "macos")]
+use crate::mdm::utils::{
+    MacApp, find_macos_app, macos_app_version, macos_version, read_macos_default,
+    update_macos_default,
+};
+#[cfg(windows)]
+use crate::mdm::utils::{
+    WindowsApp, find_windows_install_dirs, read_jsonc_string_setting, update_jsonc_string_setting,
+    windows_exe_version,
+};
+use std::path::{Path, PathBuf};
+
+/// Fork's bundle identifier, which is also its defaults domain
+const FORK_BUNDLE_ID: &str = "com.DanPristupov.Fork";
+
+#[cfg(target_os = "macos")]
+const FORK_APP: MacApp = MacApp {
+    bundle_id: FORK_BUNDLE_ID,
+    app_name: "Fork.app",
+    cask: Some("fork"),
+};
+
+/// Which defaults key holds the custom git path, by Fork version. Fork 2.0
+/// renamed it when the bundled/system/custom git picker was added. Newest
+/// first, so an unreadable app version gets the current key.
+const FORK_MACOS_PREFS: &[PrefsVariant<&str>] = &[
+    PrefsVariant {
+        os: VersionRange::ANY,
+        app: VersionRange::from((2, 0)),
+        prefs: "customGitInstancePath",
+    },
+    PrefsVariant {
+        os: VersionRange::ANY,
+        app: VersionRange::below((2, 0)),
+        prefs: "gitInstancePath",
+    },
+];
+
+#[cfg(windows)]
+const FORK_WINDOWS_APP: WindowsApp = WindowsApp {
+    display_name: "Fork",
+    scoop: Some("fork"),
+    chocolatey: None,
+    winget: Some("Fork.Fork"),
+};
+
+/// `settings.json` key for the custom git path on Windows, where Fork is
+/// still versioned 1.x and has used one key throughout
+#[cfg_attr(not(windows), allow(dead_code))]
+const FORK_WINDOWS_PREFS: &[PrefsVariant<&str>] = &[PrefsVariant {
+    os: VersionRange::ANY,
+    app: VersionRange::ANY,
+    prefs: "GitInstancePath",
+}];
+
+/// Points Fork at the git shim through its custom git instance setting. The
+/// key written depends on the installed Fork version (see the tables above).
+pub struct ForkInstaller;
+
+/// Where the installed Fork keeps its custom git path
+#[cfg_attr(not(any(target_os = "macos", windows)), allow(dead_code))]
+struct GitPathSetting {
+    /// Fork's defaults domain, or its machine-wide plist for `--scope system`
+    #[cfg(target_os = "macos")]
+    domain: String,
+    #[cfg(windows)]
+    settings_path: PathBuf,
+    key: &'static str,
+}
+
+impl GitPathSetting {
+    #[cfg(target_os = "macos")]
+    fn detect(scope: InstallScope) -> Option<Self> {
+        let app = find_macos_app(&FORK_APP)?;
+        let key = select_prefs_variant(FORK_MACOS_PREFS, macos_version(), macos_app_version(&app))
+            .copied()?;
+        Some(Self {
+            domain: scope.defaults_domain(FORK_BUNDLE_ID),
+            key,
+        })
+    }
+
+    /// Fork on Windows only has per-user settings
+    #[cfg(windows)]
+    fn detect(scope: InstallScope) -> Option<Self> {
+        if scope == InstallScope::System {
+            return None;
+        }
+        // Squirrel installs per-user under %LOCALAPPDATA%\Fork unless its
+        // Uninstall entry or a package manager says otherwise
+        let dir = find_windows_install_dirs(&FORK_WINDOWS_APP)
+            .into_iter()
+            .chain(std::env::var_os("LOCALAPPDATA").map(|local| PathBuf::from(local).join("Fork")))
+            .find(|dir| dir.join("Fork.exe").exists())?;
+        // Squirrel's app-<version> directories, else Fork.exe's version info
+        let app_version = std::fs::read_dir(&dir)
+            .ok()
+            .and_then(|entries| {
+                latest_squirrel_version(entries.flatten().map(|entry| entry.file_name()))
+            })
+            .or_else(|| parse_version(&windows_exe_version(&dir.join("Fork.exe"))?));
+        let key = select_prefs_variant(FORK_WINDOWS_PREFS, None, app_version).copied()?;
+        Some(Self {
+            settings_path: dir.join("settings.json"),
+            key,
+        })
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    fn detect(_scope: InstallScope) -> Option<Self> {
+        None
+    }
+
+    #[cfg(target_os = "macos")]
+    fn read(&self) -> Result<Option<String>, GitAiError> {
+        Ok(read_macos_default(&self.domain, self.key))
+    }
+
+    #[cfg(windows)]
+    fn read(&self) -> Result<Option<String>, GitAiError> {
+        read_jsonc_string_setting(&self.settings_path, self.key)
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    fn read(&self) -> Result<Option<String>, GitAiError> {
+        Ok(None)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn write(&self, value: Option<&str>, dry_run: bool) -> Result<Option<String>, GitAiError> {
+        update_macos_default(&self.domain, self.key, value, dry_run)
+    }
+
+    #[cfg(windows)]
+    fn write(&self, value: Option<&str>, dry_run: bool) -> Result<Option<String>, GitAiError> {
+        update_jsonc_string_setting(&self.settings_path, self.key, value, dry_run)
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    fn write(&self, _value: Option<&str>, _dry_run: bool) -> Result<Option<String>, GitAiError> {
+        Ok(None)
+    }
+}
+
+/// Where Fork on Windows looks for the `bash.exe` it runs hooks with: beside
+/// the configured git, or in the Git for Windows layout around it
+#[cfg_attr(not(any(windows, test)), allow(dead_code))]
+fn bash_candidates(git_path: &Path) -> Vec<PathBuf> {
+    let Some(dir) = git_path.parent() else {
+        return Vec::new();
+    };
+    let mut candidates = vec![dir.join("bash.exe")];
+    if let Some(root) = dir.parent() {
+        candidates.push(root.join("bin").join("bash.exe"));
+        candidates.push(root.join("usr").join("bin").join("bash.exe"));
+    }
+    candidates
+}
+
+/// Highest version among Squirrel's `app-<version>` directories
+#[cfg_attr(not(any(windows, test)), allow(dead_code))]
+fn latest_squirrel_version(names: impl Iterator<Item = std::ffi::OsString>) -> Option<(u32, u32)> {
+    names
+        .filter_map(|name| parse_version(name.to_str()?.strip_prefix("app-")?))
+        .max()
+}
+
+impl GitClientInstaller for ForkInstaller {
+    fn name(&self) -> &str {
+        "Fork"
+    }
+
+    fn id(&self) -> &str {
+        "fork"
+    }
+
+    fn process_names(&self) -> Vec<&str> {
+        vec!["Fork"]
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        cfg!(any(target_os = "macos", windows))
+    }
+
+    fn supports_system_scope(&self) -> bool {
+        cfg!(target_os = "macos")
+    }
+
+    /// Every version's key, since a fleet may run any of them
+    fn managed_preferences(&self, params: &GitClientInstallerParams) -> Vec<ManagedPreference> {
+        FORK_MACOS_PREFS
+            .iter()
+            .map(|variant| ManagedPreference {
+                domain: FORK_BUNDLE_ID.to_string(),
+                key: variant.prefs.to_string(),
+                value: params.git_shim_path.to_string_lossy().into_owned(),
+            })
+            .collect()
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let Some(setting) = GitPathSetting::detect(params.scope) else {
+            return Ok(GitClientCheckResult::not_installed());
+        };
+
+        Ok(GitClientCheckResult::with_git_path(
+            setting.read()?,
+            &params.git_shim_path.to_string_lossy(),
+        ))
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(setting) = GitPathSetting::detect(params.scope) else {
+            return Ok(None);
+        };
+        setting.write(Some(&params.git_shim_path.to_string_lossy()), dry_run)
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(setting) = GitPathSetting::detect(params.scope) else {
+            return Ok(None);
+        };
+        // Leave a user-chosen git alone; without the key Fork goes back to
+        // its bundled git
+        let shim = params.git_shim_path.to_string_lossy();
+        if setting.read()?.as_deref() != Some(shim.as_ref()) {
+            return Ok(None);
+        }
+        setting.write(None, dry_run)
+    }
+
+    /// Also, on Windows, that Fork will find a `bash.exe` to go with the shim
+    fn verify_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<Option<String>, GitAiError> {
+        if let Some(problem) = prefs_problem(&self.check_client(params)?) {
+            return Ok(Some(problem));
+        }
+        if cfg!(windows)
+            && !bash_candidates(&params.git_shim_path)
+                .iter()
+                .any(|candidate| candidate.is_file())
+        {
+            return Ok(Some(format!(
+                "Fork needs a bash.exe beside {} (or in its Git for Windows layout) to run hooks",
+                params.git_shim_path.display()
+            )));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fork_2_uses_the_custom_git_instance_key() {
+        assert_eq!(
+            select_prefs_variant(FORK_MACOS_PREFS, Some((14, 5)), Some((2, 52))),
+            Some(&"customGitInstancePath")
+        );
+    }
+
+    #[test]
+    fn fork_1_uses_the_legacy_key() {
+        assert_eq!(
+            select_prefs_variant(FORK_MACOS_PREFS, Some((12, 7)), Some((1, 0))),
+            Some(&"gitInstancePath")
+        );
+    }
+
+    #[test]
+    fn unknown_fork_version_gets_the_current_key() {
+        assert_eq!(
+            select_prefs_variant(FORK_MACOS_PREFS, None, None),
+            Some(&"customGitInstancePath")
+        );
+    }
+
+    #[test]
+    fn bash_is_looked_for_beside_the_git_and_in_the_git_for_windows_layout() {
+        let candidates = bash_candidates(Path::new("/opt/Git/cmd/git.exe"));
+        assert_eq!(
+            candidates,
+            vec![
+                PathBuf::from("/opt/Git/cmd/bash.exe"),
+                PathBuf::from("/opt/Git/bin/bash.exe"),
+                PathBuf::from("/opt/Git/usr/bin/bash.exe"),
+            ]
+        );
+    }
+
+    #[test]
+    fn windows_key_and_squirrel_version() {
+        let names = ["app-1.99.0", "app-1.104.2", "packages", "Fork.exe"]
+            .into_iter()
+            .map(std::ffi::OsString::from);
+        let version = latest_squirrel_version(names);
+        assert_eq!(version, Some((1, 104)));
+        assert_eq!(
+            select_prefs_variant(FORK_WINDOWS_PREFS, None, version),
+            Some(&"GitInstancePath")
+        );
+    }
+}
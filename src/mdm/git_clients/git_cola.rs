@@ -0,0 +1,155 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+#[cfg(all(unix, not(target_os = "linux")))]
+use crate::mdm::utils::home_dir;
+#[cfg(windows)]
+use crate::mdm::utils::windows_app_data_dir;
+#[cfg(target_os = "linux")]
+use crate::mdm::utils::{
+    LinuxConfigHome, LinuxPackaging, find_linux_config_home, flatpak_access_problem,
+};
+use crate::mdm::utils::{read_jsonc_string_setting, update_jsonc_string_setting};
+use std::path::PathBuf;
+
+/// Settings key overriding the git executable Cola runs instead of the one
+/// found on PATH
+const GIT_PATH_SETTING: &str = "git_path";
+
+#[cfg(target_os = "linux")]
+const GIT_COLA_FLATPAK_ID: &str = "com.github.git_cola.git-cola";
+
+/// Points Git Cola at the git shim via the git path override in its JSON
+/// `settings` file.
+pub struct GitColaInstaller;
+
+impl GitColaInstaller {
+    /// Cola uses the XDG config directory on every Unix, macOS included
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn config_dir() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .unwrap_or_else(|| home_dir().join(".config"));
+        Some(config_home.join("git-cola"))
+    }
+
+    /// On Linux that may be the Flatpak's config home instead
+    #[cfg(target_os = "linux")]
+    fn config_home() -> LinuxConfigHome {
+        find_linux_config_home(
+            "git-cola",
+            LinuxPackaging {
+                flatpak_id: Some(GIT_COLA_FLATPAK_ID),
+                snap_name: None,
+            },
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    fn config_dir() -> Option<PathBuf> {
+        Some(Self::config_home().dir.join("git-cola"))
+    }
+
+    /// Why a sandboxed Cola couldn't run the shim
+    #[cfg(target_os = "linux")]
+    fn sandbox_problem(params: &GitClientInstallerParams) -> Option<String> {
+        Self::config_home()
+            .flatpak_id
+            .and_then(|id| flatpak_access_problem(id, &params.git_shim_path))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn sandbox_problem(_params: &GitClientInstallerParams) -> Option<String> {
+        None
+    }
+
+    #[cfg(windows)]
+    fn config_dir() -> Option<PathBuf> {
+        windows_app_data_dir("git-cola")
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn config_dir() -> Option<PathBuf> {
+        None
+    }
+
+    /// The settings file, when Cola has been run by this user
+    fn settings_path() -> Option<PathBuf> {
+        Self::config_dir()
+            .filter(|dir| dir.is_dir())
+            .map(|dir| dir.join("settings"))
+    }
+}
+
+impl GitClientInstaller for GitColaInstaller {
+    fn name(&self) -> &str {
+        "Git Cola"
+    }
+
+    fn id(&self) -> &str {
+        "git-cola"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        cfg!(any(unix, windows))
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(GitClientCheckResult::not_installed());
+        };
+        if let Some(reason) = Self::sandbox_problem(params) {
+            return Ok(GitClientCheckResult::unsupported(reason));
+        }
+
+        Ok(GitClientCheckResult::with_git_path(
+            read_jsonc_string_setting(&path, GIT_PATH_SETTING)?,
+            &params.git_shim_path.to_string_lossy(),
+        ))
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(None);
+        };
+        let shim = params.git_shim_path.to_string_lossy();
+        update_jsonc_string_setting(&path, GIT_PATH_SETTING, Some(&shim), dry_run)
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(None);
+        };
+        // Leave a user-chosen git path alone
+        let shim = params.git_shim_path.to_string_lossy();
+        if read_jsonc_string_setting(&path, GIT_PATH_SETTING)?.as_deref() != Some(shim.as_ref()) {
+            return Ok(None);
+        }
+        update_jsonc_string_setting(&path, GIT_PATH_SETTING, None, dry_run)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_follow_xdg_config_home() {
+        let dir = GitColaInstaller::config_dir().unwrap();
+        assert!(dir.ends_with("git-cola"));
+        assert!(dir.is_absolute());
+    }
+}
@@ -0,0 +1,278 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+use crate::mdm::utils::edit_settings_file;
+#[cfg(windows)]
+use crate::mdm::utils::windows_app_data_dir;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(windows)]
+use winreg::{
+    RegKey,
+    enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE},
+};
+
+/// Settings key holding the git executable Git Extensions runs
+const GIT_COMMAND_KEY: &str = "gitcommand";
+
+/// `GitExtensions.settings` is a serialized dictionary of
+/// `<item><key><string>name</string></key><value><string>v</string></value></item>`.
+static GIT_COMMAND_ITEM_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?s)([ \t]*)<item>\s*<key>\s*<string>gitcommand</string>\s*</key>\s*<value>\s*<string>([^<]*)</string>\s*</value>\s*</item>[ \t]*\r?\n?",
+    )
+    .unwrap()
+});
+
+/// Points Git Extensions (Windows) at the git shim via its `gitcommand`
+/// setting in `%APPDATA%\GitExtensions`.
+pub struct GitExtensionsInstaller;
+
+impl GitExtensionsInstaller {
+    /// The settings file Git Extensions reads. Newer releases nest it one
+    /// directory deeper; prefer whichever exists, defaulting to the nested one.
+    #[cfg(windows)]
+    fn settings_path() -> Option<PathBuf> {
+        let root = windows_app_data_dir("GitExtensions")?;
+        let candidates = [
+            root.join("GitExtensions").join("GitExtensions.settings"),
+            root.join("GitExtensions.settings"),
+        ];
+        candidates
+            .iter()
+            .find(|path| path.exists())
+            .or(candidates.first())
+            .cloned()
+    }
+
+    #[cfg(not(windows))]
+    fn settings_path() -> Option<PathBuf> {
+        None
+    }
+
+    #[cfg(windows)]
+    fn is_installed() -> bool {
+        let in_registry = [
+            RegKey::predef(HKEY_CURRENT_USER),
+            RegKey::predef(HKEY_LOCAL_MACHINE),
+        ]
+        .iter()
+        .any(|hive| {
+            hive.open_subkey("Software\\GitExtensions")
+                .and_then(|key| key.get_value::<String, _>("InstallDir"))
+                .is_ok_and(|dir| !dir.trim().is_empty())
+        });
+        in_registry
+            || Self::settings_path()
+                .and_then(|path| path.parent().map(Path::exists))
+                .unwrap_or(false)
+    }
+
+    #[cfg(not(windows))]
+    fn is_installed() -> bool {
+        false
+    }
+
+    fn read_settings(path: &Path) -> Result<String, GitAiError> {
+        if !path.exists() {
+            return Ok(String::new());
+        }
+        fs::read_to_string(path)
+            .map_err(|e| GitAiError::Generic(format!("Failed to read {}: {}", path.display(), e)))
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+fn git_command_item(indent: &str, git_path: &str) -> String {
+    format!(
+        "{indent}<item>\n{indent}  <key>\n{indent}    <string>{}</string>\n{indent}  </key>\n{indent}  <value>\n{indent}    <string>{}</string>\n{indent}  </value>\n{indent}</item>\n",
+        GIT_COMMAND_KEY,
+        xml_escape(git_path),
+    )
+}
+
+/// Read the configured git executable from a `GitExtensions.settings` document
+fn read_git_command(content: &str) -> Option<String> {
+    GIT_COMMAND_ITEM_RE
+        .captures(content)
+        .map(|caps| xml_unescape(&caps[2]))
+}
+
+/// Return `content` with `gitcommand` set to `git_path`, adding the item or
+/// the whole document as needed.
+fn set_git_command(content: &str, git_path: &str) -> String {
+    if let Some(caps) = GIT_COMMAND_ITEM_RE.captures(content) {
+        let value = caps.get(2).unwrap();
+        return format!(
+            "{}{}{}",
+            &content[..value.start()],
+            xml_escape(git_path),
+            &content[value.end()..]
+        );
+    }
+
+    match content.rfind("</dictionary>") {
+        Some(idx) => format!(
+            "{}{}{}",
+            &content[..idx],
+            git_command_item("  ", git_path),
+            &content[idx..]
+        ),
+        None => format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<dictionary>\n{}</dictionary>\n",
+            git_command_item("  ", git_path)
+        ),
+    }
+}
+
+/// Remove `gitcommand` if (and only if) it points at `git_path`; Git
+/// Extensions falls back to finding git itself.
+fn remove_git_command(content: &str, git_path: &str) -> Option<String> {
+    let caps = GIT_COMMAND_ITEM_RE.captures(content)?;
+    if xml_unescape(&caps[2]) != git_path {
+        return None;
+    }
+    let whole = caps.get(0).unwrap();
+    Some(format!(
+        "{}{}",
+        &content[..whole.start()],
+        &content[whole.end()..]
+    ))
+}
+
+impl GitClientInstaller for GitExtensionsInstaller {
+    fn name(&self) -> &str {
+        "Git Extensions"
+    }
+
+    fn id(&self) -> &str {
+        "git-extensions"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        cfg!(windows)
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let Some(path) = Self::settings_path().filter(|_| Self::is_installed()) else {
+            return Ok(GitClientCheckResult::not_installed());
+        };
+
+        let content = Self::read_settings(&path)?;
+        Ok(GitClientCheckResult::with_git_path(
+            read_git_command(&content),
+            &params.git_shim_path.to_string_lossy(),
+        ))
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path().filter(|_| Self::is_installed()) else {
+            return Ok(None);
+        };
+
+        let shim = params.git_shim_path.to_string_lossy();
+        edit_settings_file(&path, dry_run, |original| {
+            let updated = set_git_command(original, &shim);
+            Ok((updated != original).then_some(updated))
+        })
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(None);
+        };
+
+        let shim = params.git_shim_path.to_string_lossy();
+        edit_settings_file(&path, dry_run, |original| {
+            Ok(remove_git_command(original, &shim))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIM: &str = r"C:\Users\dev\.git-ai\bin\git.exe";
+
+    const SETTINGS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<dictionary>
+  <item>
+    <key>
+      <string>gitcommand</string>
+    </key>
+    <value>
+      <string>C:\Program Files\Git\cmd\git.exe</string>
+    </value>
+  </item>
+  <item>
+    <key>
+      <string>showgitstatusinbrowsetoolbar</string>
+    </key>
+    <value>
+      <string>True</string>
+    </value>
+  </item>
+</dictionary>
+"#;
+
+    #[test]
+    fn set_git_command_rewrites_existing_value() {
+        let updated = set_git_command(SETTINGS, SHIM);
+        assert_eq!(read_git_command(&updated).as_deref(), Some(SHIM));
+        assert_eq!(
+            updated,
+            SETTINGS.replace(r"C:\Program Files\Git\cmd\git.exe", SHIM)
+        );
+        assert_eq!(set_git_command(&updated, SHIM), updated);
+    }
+
+    #[test]
+    fn set_git_command_adds_item_or_document_when_missing() {
+        let without = remove_git_command(&set_git_command(SETTINGS, SHIM), SHIM).unwrap();
+        assert_eq!(read_git_command(&without), None);
+        assert!(without.contains("showgitstatusinbrowsetoolbar"));
+
+        let added = set_git_command(&without, SHIM);
+        assert_eq!(read_git_command(&added).as_deref(), Some(SHIM));
+        assert!(added.contains("showgitstatusinbrowsetoolbar"));
+
+        let created = set_git_command("", SHIM);
+        assert!(created.starts_with("<?xml"));
+        assert_eq!(read_git_command(&created).as_deref(), Some(SHIM));
+    }
+
+    #[test]
+    fn remove_git_command_keeps_user_chosen_git() {
+        assert_eq!(remove_git_command(SETTINGS, SHIM), None);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use crate::mdm::utils::home_dir;
+#[cfg(windows)]
+use crate::mdm::utils::windows_app_data_dir;
+use crate::mdm::utils::{read_jsonc_string_setting, update_jsonc_string_setting};
+use std::path::PathBuf;
+
+/// GitFiend setting overriding the git executable it runs
+const GIT_PATH_SETTING: &str = "gitPath";
+
+/// Points GitFiend at the git shim via the git path override in the
+/// `config.json` kept in its Electron user data directory.
+pub struct GitFiendInstaller;
+
+impl GitFiendInstaller {
+    /// Electron `userData` directory, created on first launch
+    #[cfg(target_os = "macos")]
+    fn data_dir() -> Option<PathBuf> {
+        Some(
+            home_dir()
+                .join("Library")
+                .join("Application Support")
+                .join("GitFiend"),
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    fn data_dir() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .unwrap_or_else(|| home_dir().join(".config"));
+        Some(config_home.join("GitFiend"))
+    }
+
+    #[cfg(windows)]
+    fn data_dir() -> Option<PathBuf> {
+        windows_app_data_dir("GitFiend")
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    fn data_dir() -> Option<PathBuf> {
+        None
+    }
+
+    /// The settings file, when GitFiend has been run on this machine
+    fn settings_path() -> Option<PathBuf> {
+        Self::data_dir()
+            .filter(|dir| dir.is_dir())
+            .map(|dir| dir.join("config.json"))
+    }
+}
+
+impl GitClientInstaller for GitFiendInstaller {
+    fn name(&self) -> &str {
+        "GitFiend"
+    }
+
+    fn id(&self) -> &str {
+        "gitfiend"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        cfg!(any(target_os = "macos", target_os = "linux", windows))
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(GitClientCheckResult::not_installed());
+        };
+
+        Ok(GitClientCheckResult::with_git_path(
+            read_jsonc_string_setting(&path, GIT_PATH_SETTING)?,
+            &params.git_shim_path.to_string_lossy(),
+        ))
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(None);
+        };
+        let shim = params.git_shim_path.to_string_lossy();
+        update_jsonc_string_setting(&path, GIT_PATH_SETTING, Some(&shim), dry_run)
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(None);
+        };
+        // Leave a user-chosen git path alone
+        let shim = params.git_shim_path.to_string_lossy();
+        if read_jsonc_string_setting(&path, GIT_PATH_SETTING)?.as_deref() != Some(shim.as_ref()) {
+            return Ok(None);
+        }
+        update_jsonc_string_setting(&path, GIT_PATH_SETTING, None, dry_run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn git_path_override_keeps_other_settings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.json");
+        fs::write(&path, "{\"theme\":\"dark\",\"gitPath\":\"\"}").unwrap();
+
+        let shim = "C:\\Users\\dev\\.git-ai\\bin\\git.exe";
+        assert!(
+            update_jsonc_string_setting(&path, GIT_PATH_SETTING, Some(shim), false)
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            read_jsonc_string_setting(&path, GIT_PATH_SETTING)
+                .unwrap()
+                .as_deref(),
+            Some(shim)
+        );
+        assert_eq!(
+            read_jsonc_string_setting(&path, "theme")
+                .unwrap()
+                .as_deref(),
+            Some("dark")
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+use crate::mdm::utils::{home_dir, read_ini_setting, update_ini_setting};
+use std::path::{Path, PathBuf};
+
+/// QSettings group and key Guitar keeps the git executable under
+const GITCOMMAND_SECTION: &str = "Global";
+const GITCOMMAND_KEY: &str = "GitCommand";
+
+/// Points Guitar at the git shim via `GitCommand` in its `Guitar.ini`.
+pub struct GuitarInstaller;
+
+impl GuitarInstaller {
+    /// Guitar's app data directories (organization `soramimi.jp`), newest
+    /// location first
+    fn data_dirs() -> Vec<PathBuf> {
+        let home = home_dir();
+        let mut dirs = Vec::new();
+        #[cfg(target_os = "macos")]
+        dirs.push(home.join("Library").join("Application Support"));
+        #[cfg(windows)]
+        for var in ["APPDATA", "LOCALAPPDATA"] {
+            if let Some(dir) = std::env::var_os(var) {
+                dirs.push(PathBuf::from(dir));
+            }
+        }
+        if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME") {
+            dirs.push(PathBuf::from(xdg));
+        }
+        dirs.push(home.join(".config"));
+        dirs.push(home.join(".local").join("share"));
+        dirs.into_iter()
+            .map(|dir| dir.join("soramimi.jp").join("Guitar"))
+            .collect()
+    }
+
+    /// The settings file, once Guitar has saved its settings on this machine
+    fn settings_path() -> Option<PathBuf> {
+        Self::data_dirs()
+            .into_iter()
+            .map(|dir| dir.join("Guitar.ini"))
+            .find(|path| path.is_file())
+    }
+}
+
+/// Qt accepts forward slashes on every platform, and they need no escaping
+/// in QSettings INI values
+fn shim_setting_value(git_shim_path: &Path) -> String {
+    git_shim_path.to_string_lossy().replace('\\', "/")
+}
+
+impl GitClientInstaller for GuitarInstaller {
+    fn name(&self) -> &str {
+        "Guitar"
+    }
+
+    fn id(&self) -> &str {
+        "guitar"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        cfg!(any(target_os = "macos", target_os = "linux", windows))
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(GitClientCheckResult::not_installed());
+        };
+
+        Ok(GitClientCheckResult::with_git_path(
+            read_ini_setting(&path, GITCOMMAND_SECTION, GITCOMMAND_KEY)?,
+            &shim_setting_value(&params.git_shim_path),
+        ))
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(None);
+        };
+        let shim = shim_setting_value(&params.git_shim_path);
+        update_ini_setting(
+            &path,
+            GITCOMMAND_SECTION,
+            GITCOMMAND_KEY,
+            Some(&shim),
+            dry_run,
+        )
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(None);
+        };
+        // Leave a user-chosen git command alone; without the key Guitar asks
+        // for git again on next launch
+        let shim = shim_setting_value(&params.git_shim_path);
+        if read_ini_setting(&path, GITCOMMAND_SECTION, GITCOMMAND_KEY)?.as_deref()
+            != Some(shim.as_str())
+        {
+            return Ok(None);
+        }
+        update_ini_setting(&path, GITCOMMAND_SECTION, GITCOMMAND_KEY, None, dry_run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shim_value_uses_forward_slashes() {
+        assert_eq!(
+            shim_setting_value(Path::new(r"C:\Users\dev\.git-ai\bin\git.exe")),
+            "C:/Users/dev/.git-ai/bin/git.exe"
+        );
+        assert!(
+            GuitarInstaller::data_dirs()
+                .iter()
+                .all(|dir| dir.ends_with("soramimi.jp/Guitar"))
+        );
+    }
+}
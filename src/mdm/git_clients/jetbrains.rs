@@ -0,0 +1,328 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+use crate::mdm::jetbrains::{find_jetbrains_installations, get_config_dir};
+use crate::mdm::utils::edit_settings_file;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Product code the IDE table uses for Android Studio
+pub(super) const ANDROID_STUDIO_PRODUCT_CODE: &str = "AI";
+
+/// Application-level component in `options/git.xml` that holds git settings
+const GIT_SETTINGS_COMPONENT: &str = "Git.Application.Settings";
+
+static PATH_TO_GIT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^([ \t]*)<option\s+name="myPathToGit"\s+value="([^"]*)"\s*/>[ \t]*\r?\n?"#)
+        .unwrap()
+});
+
+static GIT_COMPONENT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"<component\s+name="Git\.Application\.Settings"\s*(/?)>"#).unwrap());
+
+/// Points every installed JetBrains IDE (IntelliJ, PyCharm, WebStorm, ...) at
+/// the git shim via "Path to Git executable" (`myPathToGit`). Toolbox installs
+/// share the same per-version config directories. Android Studio has its own
+/// installer, since its config lives under Google's directories.
+pub struct JetBrainsGitInstaller;
+
+impl JetBrainsGitInstaller {
+    /// `options/git.xml` for each detected IDE, deduplicated (several installs
+    /// of the same IDE version share one config directory).
+    fn git_xml_paths() -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for detected in find_jetbrains_installations() {
+            if detected.ide.product_code == ANDROID_STUDIO_PRODUCT_CODE {
+                continue;
+            }
+            if let Some(config_dir) = get_config_dir(&detected) {
+                let path = config_dir.join("options").join("git.xml");
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+}
+
+fn read_git_xml(path: &Path) -> Result<String, GitAiError> {
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    fs::read_to_string(path)
+        .map_err(|e| GitAiError::Generic(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+/// Read the configured git executable from a `git.xml` document
+fn read_path_to_git(content: &str) -> Option<String> {
+    PATH_TO_GIT_RE
+        .captures(content)
+        .map(|caps| xml_unescape(&caps[2]))
+}
+
+/// Return `content` with `myPathToGit` set to `git_path`, adding the option,
+/// the settings component, or the whole document as needed.
+fn set_path_to_git(content: &str, git_path: &str) -> String {
+    let option = format!(
+        r#"<option name="myPathToGit" value="{}" />"#,
+        xml_escape(git_path)
+    );
+
+    if let Some(caps) = PATH_TO_GIT_RE.captures(content) {
+        let whole = caps.get(0).unwrap();
+        let trailing_newline = if whole.as_str().ends_with('\n') {
+            "\n"
+        } else {
+            ""
+        };
+        return format!(
+            "{}{}{}{}{}",
+            &content[..whole.start()],
+            &caps[1],
+            option,
+            trailing_newline,
+            &content[whole.end()..]
+        );
+    }
+
+    if let Some(caps) = GIT_COMPONENT_RE.captures(content) {
+        let whole = caps.get(0).unwrap();
+        let open_tag = format!(r#"<component name="{}">"#, GIT_SETTINGS_COMPONENT);
+        let body = format!("\n    {}\n", option);
+        let tail = if &caps[1] == "/" {
+            format!("{}  </component>", body)
+        } else {
+            body.trim_end_matches('\n').to_string()
+        };
+        return format!(
+            "{}{}{}{}",
+            &content[..whole.start()],
+            open_tag,
+            tail,
+            &content[whole.end()..]
+        );
+    }
+
+    let component = format!(
+        "  <component name=\"{}\">\n    {}\n  </component>\n",
+        GIT_SETTINGS_COMPONENT, option
+    );
+    match content.rfind("</application>") {
+        Some(idx) => format!("{}{}{}", &content[..idx], component, &content[idx..]),
+        None => format!("<application>\n{}</application>\n", component),
+    }
+}
+
+/// Remove `myPathToGit` if (and only if) it points at `git_path`, so a
+/// user-chosen git executable is never discarded.
+fn remove_path_to_git(content: &str, git_path: &str) -> Option<String> {
+    let caps = PATH_TO_GIT_RE.captures(content)?;
+    if xml_unescape(&caps[2]) != git_path {
+        return None;
+    }
+    let whole = caps.get(0).unwrap();
+    Some(format!(
+        "{}{}",
+        &content[..whole.start()],
+        &content[whole.end()..]
+    ))
+}
+
+impl GitClientInstaller for JetBrainsGitInstaller {
+    fn name(&self) -> &str {
+        "JetBrains IDEs"
+    }
+
+    fn id(&self) -> &str {
+        "jetbrains-git"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        true
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        check_git_xml_paths(&Self::git_xml_paths(), params)
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        install_git_xml_paths(&Self::git_xml_paths(), params, dry_run)
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        uninstall_git_xml_paths(&Self::git_xml_paths(), params, dry_run)
+    }
+}
+
+/// Check result across IntelliJ-platform `options/git.xml` files; an empty
+/// list means the IDE isn't installed
+pub(super) fn check_git_xml_paths(
+    paths: &[PathBuf],
+    params: &GitClientInstallerParams,
+) -> Result<GitClientCheckResult, GitAiError> {
+    if paths.is_empty() {
+        return Ok(GitClientCheckResult::not_installed());
+    }
+
+    let shim = params.git_shim_path.to_string_lossy();
+    let mut configured = 0;
+    let mut other_git_path = None;
+    for path in paths {
+        let git_path = read_path_to_git(&read_git_xml(path)?);
+        if git_path.as_deref() == Some(shim.as_ref()) {
+            configured += 1;
+        } else if git_path.is_some() && other_git_path.is_none() {
+            other_git_path = git_path;
+        }
+    }
+
+    Ok(GitClientCheckResult {
+        client_installed: true,
+        prefs_configured: configured > 0,
+        prefs_up_to_date: configured == paths.len(),
+        unsupported_reason: None,
+        configured_git_path: other_git_path.or_else(|| (configured > 0).then(|| shim.to_string())),
+    })
+}
+
+/// Set `myPathToGit` to the shim in each `options/git.xml`
+pub(super) fn install_git_xml_paths(
+    paths: &[PathBuf],
+    params: &GitClientInstallerParams,
+    dry_run: bool,
+) -> Result<Option<String>, GitAiError> {
+    let shim = params.git_shim_path.to_string_lossy();
+    let mut diffs = String::new();
+
+    for path in paths {
+        let diff = edit_settings_file(path, dry_run, |original| {
+            let updated = set_path_to_git(original, &shim);
+            Ok((updated != original).then_some(updated))
+        })?;
+        diffs.push_str(&diff.unwrap_or_default());
+    }
+
+    Ok((!diffs.is_empty()).then_some(diffs))
+}
+
+/// Drop `myPathToGit` from each `options/git.xml` where it still points at the shim
+pub(super) fn uninstall_git_xml_paths(
+    paths: &[PathBuf],
+    params: &GitClientInstallerParams,
+    dry_run: bool,
+) -> Result<Option<String>, GitAiError> {
+    let shim = params.git_shim_path.to_string_lossy();
+    let mut diffs = String::new();
+
+    for path in paths {
+        let diff = edit_settings_file(path, dry_run, |original| {
+            Ok(remove_path_to_git(original, &shim))
+        })?;
+        diffs.push_str(&diff.unwrap_or_default());
+    }
+
+    Ok((!diffs.is_empty()).then_some(diffs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIM: &str = "/Users/dev/.git-ai/bin/git";
+
+    #[test]
+    fn set_path_to_git_creates_document_when_missing() {
+        let updated = set_path_to_git("", SHIM);
+        assert_eq!(
+            updated,
+            "<application>\n  <component name=\"Git.Application.Settings\">\n    <option name=\"myPathToGit\" value=\"/Users/dev/.git-ai/bin/git\" />\n  </component>\n</application>\n"
+        );
+        assert_eq!(read_path_to_git(&updated).as_deref(), Some(SHIM));
+    }
+
+    #[test]
+    fn set_path_to_git_replaces_existing_value_and_keeps_other_options() {
+        let content = "<application>\n  <component name=\"Git.Application.Settings\">\n    <option name=\"SSH_EXECUTABLE\" value=\"NATIVE_SSH\" />\n    <option name=\"myPathToGit\" value=\"/usr/local/bin/git\" />\n  </component>\n</application>\n";
+        let updated = set_path_to_git(content, SHIM);
+        assert_eq!(updated, content.replace("/usr/local/bin/git", SHIM));
+    }
+
+    #[test]
+    fn set_path_to_git_inserts_into_existing_component() {
+        let content = "<application>\n  <component name=\"Git.Application.Settings\">\n    <option name=\"SSH_EXECUTABLE\" value=\"NATIVE_SSH\" />\n  </component>\n</application>\n";
+        let updated = set_path_to_git(content, SHIM);
+        assert!(updated.contains("<option name=\"SSH_EXECUTABLE\" value=\"NATIVE_SSH\" />"));
+        assert_eq!(read_path_to_git(&updated).as_deref(), Some(SHIM));
+        assert_eq!(updated.matches("<component").count(), 1);
+    }
+
+    #[test]
+    fn set_path_to_git_expands_self_closing_component() {
+        let content =
+            "<application>\n  <component name=\"Git.Application.Settings\" />\n</application>\n";
+        let updated = set_path_to_git(content, SHIM);
+        assert_eq!(
+            updated,
+            "<application>\n  <component name=\"Git.Application.Settings\">\n    <option name=\"myPathToGit\" value=\"/Users/dev/.git-ai/bin/git\" />\n  </component>\n</application>\n"
+        );
+    }
+
+    #[test]
+    fn set_path_to_git_escapes_windows_paths_with_special_chars() {
+        let path = r"C:\Users\A&B\.git-ai\bin\git.exe";
+        let updated = set_path_to_git("<application>\n</application>\n", path);
+        assert!(updated.contains("A&amp;B"));
+        assert_eq!(read_path_to_git(&updated).as_deref(), Some(path));
+    }
+
+    #[test]
+    fn set_path_to_git_is_idempotent() {
+        let once = set_path_to_git("", SHIM);
+        assert_eq!(set_path_to_git(&once, SHIM), once);
+    }
+
+    #[test]
+    fn remove_path_to_git_only_removes_shim_value() {
+        let installed = set_path_to_git(
+            "<application>\n  <component name=\"Git.Application.Settings\">\n    <option name=\"SSH_EXECUTABLE\" value=\"NATIVE_SSH\" />\n  </component>\n</application>\n",
+            SHIM,
+        );
+        let removed = remove_path_to_git(&installed, SHIM).unwrap();
+        assert_eq!(read_path_to_git(&removed), None);
+        assert!(removed.contains("SSH_EXECUTABLE"));
+
+        let custom = set_path_to_git("", "/opt/git/bin/git");
+        assert_eq!(remove_path_to_git(&custom, SHIM), None);
+    }
+}
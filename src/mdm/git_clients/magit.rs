@@ -0,0 +1,216 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+use crate::mdm::utils::{edit_settings_file, home_dir};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BLOCK_BEGIN: &str = ";; BEGIN git-ai (managed by `git-ai install-hooks`, do not edit)";
+const BLOCK_END: &str = ";; END git-ai";
+
+/// Points Magit at the git shim by appending a delimited
+/// `magit-git-executable` snippet to the user's Emacs init file. Uninstall
+/// removes that block and nothing else.
+pub struct MagitInstaller;
+
+impl MagitInstaller {
+    /// Doom reads `$DOOMDIR`, then `~/.config/doom`, then `~/.doom.d`
+    fn doom_config() -> Option<PathBuf> {
+        let home = home_dir();
+        std::env::var_os("DOOMDIR")
+            .map(PathBuf::from)
+            .into_iter()
+            .chain([xdg_config_home().join("doom"), home.join(".doom.d")])
+            .find(|dir| dir.is_dir())
+            .map(|dir| dir.join("config.el"))
+    }
+
+    /// Spacemacs keeps its dotfile outside `~/.emacs.d`, which holds the
+    /// distribution itself
+    fn spacemacs_config() -> Option<PathBuf> {
+        let home = home_dir();
+        let dotdir = home.join(".spacemacs.d");
+        if dotdir.is_dir() {
+            return Some(dotdir.join("init.el"));
+        }
+        Some(home.join(".spacemacs")).filter(|path| path.is_file())
+    }
+
+    /// Plain Emacs, in the order Emacs itself searches for an init file
+    fn vanilla_config() -> Option<PathBuf> {
+        let home = home_dir();
+        for dotfile in [home.join(".emacs.el"), home.join(".emacs")] {
+            if dotfile.is_file() {
+                return Some(dotfile);
+            }
+        }
+        [home.join(".emacs.d"), xdg_config_home().join("emacs")]
+            .into_iter()
+            .find(|dir| dir.is_dir())
+            .map(|dir| dir.join("init.el"))
+    }
+
+    /// The init file the snippet belongs in. Distributions are checked first
+    /// because their `~/.emacs.d/init.el` is overwritten on upgrade.
+    fn config_path() -> Option<PathBuf> {
+        Self::doom_config()
+            .or_else(Self::spacemacs_config)
+            .or_else(Self::vanilla_config)
+    }
+}
+
+fn xdg_config_home() -> PathBuf {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .unwrap_or_else(|| home_dir().join(".config"))
+}
+
+fn elisp_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn render_block(git_shim_path: &Path) -> String {
+    format!(
+        "{}\n(setq magit-git-executable {})\n{}\n",
+        BLOCK_BEGIN,
+        elisp_string(&git_shim_path.to_string_lossy()),
+        BLOCK_END
+    )
+}
+
+/// Byte range of the managed block, including its trailing newline
+fn find_block(content: &str) -> Option<std::ops::Range<usize>> {
+    let start = content.find(BLOCK_BEGIN)?;
+    let end_marker = start + content[start..].find(BLOCK_END)?;
+    let mut end = end_marker + BLOCK_END.len();
+    if content[end..].starts_with('\n') {
+        end += 1;
+    }
+    Some(start..end)
+}
+
+fn without_block(content: &str) -> String {
+    match find_block(content) {
+        Some(range) => {
+            let mut stripped = content[..range.start].to_string();
+            stripped.push_str(&content[range.end..]);
+            stripped
+        }
+        None => content.to_string(),
+    }
+}
+
+fn with_block(content: &str, block: &str) -> String {
+    let mut updated = without_block(content);
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated.push_str(block);
+    updated
+}
+
+/// Apply `change` to the init file, writing it only if that changes anything
+fn write_config(
+    path: &Path,
+    dry_run: bool,
+    change: impl Fn(&str) -> String,
+) -> Result<Option<String>, GitAiError> {
+    edit_settings_file(path, dry_run, |original| {
+        let updated = change(original);
+        Ok((updated != original).then_some(updated))
+    })
+}
+
+fn read_config(path: &Path) -> Result<String, GitAiError> {
+    if path.exists() {
+        Ok(fs::read_to_string(path)?)
+    } else {
+        Ok(String::new())
+    }
+}
+
+impl GitClientInstaller for MagitInstaller {
+    fn name(&self) -> &str {
+        "Magit"
+    }
+
+    fn id(&self) -> &str {
+        "magit"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        true
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let Some(path) = Self::config_path() else {
+            return Ok(GitClientCheckResult::not_installed());
+        };
+
+        let content = read_config(&path)?;
+        let current = find_block(&content).map(|range| &content[range]);
+        Ok(GitClientCheckResult {
+            client_installed: true,
+            prefs_configured: current.is_some(),
+            prefs_up_to_date: current == Some(render_block(&params.git_shim_path).as_str()),
+            unsupported_reason: None,
+            configured_git_path: None,
+        })
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::config_path() else {
+            return Ok(None);
+        };
+        let block = render_block(&params.git_shim_path);
+        write_config(&path, dry_run, |original| with_block(original, &block))
+    }
+
+    fn uninstall_prefs(
+        &self,
+        _params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::config_path().filter(|path| path.exists()) else {
+            return Ok(None);
+        };
+        write_config(&path, dry_run, without_block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_is_replaced_and_removed_cleanly() {
+        let user_config = "(load-theme 'modus-vivendi t)\n(setq magit-diff-refine-hunk t)";
+        let old = with_block(user_config, &render_block(Path::new("/old/git")));
+        let new_block = render_block(Path::new("/home/dev/.git-ai/bin/git"));
+        let updated = with_block(&old, &new_block);
+
+        assert_eq!(updated.matches(BLOCK_BEGIN).count(), 1);
+        assert!(updated.ends_with(&new_block));
+        assert!(!updated.contains("/old/git"));
+        assert_eq!(with_block(&updated, &new_block), updated);
+        assert_eq!(without_block(&updated), format!("{}\n", user_config));
+    }
+
+    #[test]
+    fn shim_path_is_escaped_as_an_elisp_string() {
+        let block = render_block(Path::new(r#"C:\Users\dev\.git-ai\bin\git.exe"#));
+        assert!(
+            block
+                .contains(r#"(setq magit-git-executable "C:\\Users\\dev\\.git-ai\\bin\\git.exe")"#)
+        );
+    }
+}
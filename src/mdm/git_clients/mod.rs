@@ -0,0 +1,88 @@
+mod android_studio;
+mod custom;
+mod detection_only;
+mod fleet;
+mod fork;
+mod git_cola;
+mod git_extensions;
+mod gitfiend;
+mod guitar;
+mod jetbrains;
+mod magit;
+mod nova;
+mod sublime_merge;
+mod tortoisegit;
+mod vscode;
+
+pub use android_studio::AndroidStudioInstaller;
+pub use custom::{
+    CustomClientInstaller, InvalidCustomClients, custom_clients_path, load_custom_clients,
+};
+pub use detection_only::DetectionOnlyInstaller;
+pub use fleet::FleetInstaller;
+pub use fork::ForkInstaller;
+pub use git_cola::GitColaInstaller;
+pub use git_extensions::GitExtensionsInstaller;
+pub use gitfiend::GitFiendInstaller;
+pub use guitar::GuitarInstaller;
+pub use jetbrains::JetBrainsGitInstaller;
+pub use magit::MagitInstaller;
+pub use nova::NovaInstaller;
+pub use sublime_merge::SublimeMergeInstaller;
+pub use tortoisegit::TortoiseGitInstaller;
+pub use vscode::VsCodeInstaller;
+
+use super::git_client_installer::GitClientInstaller;
+
+/// Get all available git client installers: the built-ins, then any clients
+/// defined in `clients.toml` (see [`custom`])
+pub fn get_all_git_client_installers() -> Vec<Box<dyn GitClientInstaller>> {
+    let mut installers = builtin_git_client_installers();
+    let builtin_ids: Vec<&str> = installers.iter().map(|installer| installer.id()).collect();
+    let custom: Vec<Box<dyn GitClientInstaller>> =
+        match load_custom_clients(&custom_clients_path(), &builtin_ids) {
+            Ok(custom) => custom
+                .into_iter()
+                .map(|installer| Box::new(installer) as Box<dyn GitClientInstaller>)
+                .collect(),
+            Err(err) => vec![Box::new(InvalidCustomClients::new(err))],
+        };
+    installers.extend(custom);
+    installers
+}
+
+fn builtin_git_client_installers() -> Vec<Box<dyn GitClientInstaller>> {
+    vec![
+        Box::new(AndroidStudioInstaller),
+        Box::new(DetectionOnlyInstaller::egit()),
+        Box::new(FleetInstaller),
+        Box::new(ForkInstaller),
+        Box::new(GitColaInstaller),
+        Box::new(GitExtensionsInstaller),
+        Box::new(DetectionOnlyInstaller::git_gui()),
+        Box::new(DetectionOnlyInstaller::gitahead()),
+        Box::new(DetectionOnlyInstaller::gitbutler()),
+        Box::new(GitFiendInstaller),
+        Box::new(DetectionOnlyInstaller::gitg()),
+        Box::new(DetectionOnlyInstaller::github_desktop()),
+        Box::new(DetectionOnlyInstaller::gitui()),
+        Box::new(DetectionOnlyInstaller::gittyup()),
+        Box::new(DetectionOnlyInstaller::gitup()),
+        Box::new(GuitarInstaller),
+        Box::new(JetBrainsGitInstaller),
+        Box::new(DetectionOnlyInstaller::lazygit()),
+        Box::new(MagitInstaller),
+        Box::new(DetectionOnlyInstaller::netbeans()),
+        Box::new(NovaInstaller),
+        Box::new(SublimeMergeInstaller),
+        Box::new(TortoiseGitInstaller),
+        Box::new(DetectionOnlyInstaller::visual_studio()),
+        Box::new(VsCodeInstaller::code()),
+        Box::new(VsCodeInstaller::insiders()),
+        Box::new(VsCodeInstaller::vscodium()),
+        Box::new(VsCodeInstaller::cursor()),
+        Box::new(VsCodeInstaller::windsurf()),
+        Box::new(DetectionOnlyInstaller::xcode()),
+        Box::new(DetectionOnlyInstaller::zed()),
+    ]
+}
@@ -0,0 +1,147 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams, ManagedPreference,
+};
+#[cfg(target_os = "macos")]
+use crate::mdm::utils::{MacApp, find_macos_app, read_macos_default, update_macos_default};
+
+/// Nova's bundle identifier, which is also its defaults domain
+const NOVA_BUNDLE_ID: &str = "com.panic.Nova";
+
+/// Preference behind Settings → Git → "Git tool path"
+const NOVA_GIT_PATH_KEY: &str = "GitToolPath";
+
+/// Points Panic's Nova at the git shim through its defaults domain.
+pub struct NovaInstaller;
+
+impl NovaInstaller {
+    #[cfg(target_os = "macos")]
+    fn is_installed() -> bool {
+        find_macos_app(&MacApp {
+            bundle_id: NOVA_BUNDLE_ID,
+            app_name: "Nova.app",
+            cask: Some("nova"),
+        })
+        .is_some()
+    }
+}
+
+impl GitClientInstaller for NovaInstaller {
+    fn name(&self) -> &str {
+        "Nova"
+    }
+
+    fn id(&self) -> &str {
+        "nova"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        cfg!(target_os = "macos")
+    }
+
+    fn supports_system_scope(&self) -> bool {
+        cfg!(target_os = "macos")
+    }
+
+    fn managed_preferences(&self, params: &GitClientInstallerParams) -> Vec<ManagedPreference> {
+        vec![ManagedPreference {
+            domain: NOVA_BUNDLE_ID.to_string(),
+            key: NOVA_GIT_PATH_KEY.to_string(),
+            value: params.git_shim_path.to_string_lossy().into_owned(),
+        }]
+    }
+
+    #[cfg(target_os = "macos")]
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        if !Self::is_installed() {
+            return Ok(GitClientCheckResult::not_installed());
+        }
+
+        let domain = params.scope.defaults_domain(NOVA_BUNDLE_ID);
+        Ok(GitClientCheckResult::with_git_path(
+            read_macos_default(&domain, NOVA_GIT_PATH_KEY),
+            &params.git_shim_path.to_string_lossy(),
+        ))
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn check_client(
+        &self,
+        _params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        Ok(GitClientCheckResult::not_installed())
+    }
+
+    #[cfg(target_os = "macos")]
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        if !Self::is_installed() {
+            return Ok(None);
+        }
+        update_macos_default(
+            &params.scope.defaults_domain(NOVA_BUNDLE_ID),
+            NOVA_GIT_PATH_KEY,
+            Some(&params.git_shim_path.to_string_lossy()),
+            dry_run,
+        )
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn install_prefs(
+        &self,
+        _params: &GitClientInstallerParams,
+        _dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        Ok(None)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        // Leave a user-chosen git alone; without the key Nova falls back to
+        // the git on its default search path
+        let shim = params.git_shim_path.to_string_lossy();
+        let domain = params.scope.defaults_domain(NOVA_BUNDLE_ID);
+        if read_macos_default(&domain, NOVA_GIT_PATH_KEY).as_deref() != Some(shim.as_ref()) {
+            return Ok(None);
+        }
+        update_macos_default(&domain, NOVA_GIT_PATH_KEY, None, dry_run)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn uninstall_prefs(
+        &self,
+        _params: &GitClientInstallerParams,
+        _dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        Ok(None)
+    }
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+    use crate::mdm::git_client_installer::InstallScope;
+
+    #[test]
+    fn nova_is_not_detected_off_macos() {
+        let params = GitClientInstallerParams {
+            git_shim_path: std::path::PathBuf::from("/tmp/git-ai/bin/git"),
+            scope: InstallScope::User,
+        };
+        let installer = NovaInstaller;
+        assert!(!installer.is_platform_supported());
+        assert!(!installer.check_client(&params).unwrap().client_installed);
+        assert_eq!(installer.install_prefs(&params, false).unwrap(), None);
+        assert_eq!(installer.uninstall_prefs(&params, false).unwrap(), None);
+    }
+}
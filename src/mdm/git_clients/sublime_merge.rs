@@ -0,0 +1,357 @@
+use crate::error::GitAiError;
+#[cfg(target_os = "linux")]
+use crate::mdm::discovery_cache::cached_discovery;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams, PrefsVariant, VersionRange,
+    select_prefs_variant,
+};
+#[cfg(target_os = "linux")]
+use crate::mdm::utils::{
+    LinuxConfigHome, LinuxPackaging, find_linux_config_home, flatpak_access_problem,
+};
+#[cfg(target_os = "macos")]
+use crate::mdm::utils::{MacApp, find_macos_app, home_dir, macos_app_version_string};
+#[cfg(windows)]
+use crate::mdm::utils::{
+    WindowsApp, find_windows_install_dir, windows_app_data_dir, windows_app_installed,
+    windows_exe_version,
+};
+use crate::mdm::utils::{
+    parse_build_number, read_jsonc_string_setting, update_jsonc_string_setting,
+};
+use std::path::PathBuf;
+
+/// Sublime Merge preference naming the git executable ("bundled", "system",
+/// or a path)
+const GIT_BINARY_SETTING: &str = "git_binary";
+
+/// What `git_binary` accepts in a Sublime Merge build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GitBinarySupport {
+    /// "bundled", "system" or a path to a git executable
+    Path,
+    /// Only "bundled" or "system"; a path is ignored
+    BundledOrSystem,
+}
+
+/// `git_binary` support by build number, held as `(build, 0)`. Paths arrived
+/// with Sublime Merge 2 (Build 2011). Newest first, so an unreadable build
+/// gets the current behavior.
+const SUBLIME_MERGE_PREFS: &[PrefsVariant<GitBinarySupport>] = &[
+    PrefsVariant {
+        os: VersionRange::ANY,
+        app: VersionRange::from((2011, 0)),
+        prefs: GitBinarySupport::Path,
+    },
+    PrefsVariant {
+        os: VersionRange::ANY,
+        app: VersionRange::below((2011, 0)),
+        prefs: GitBinarySupport::BundledOrSystem,
+    },
+];
+
+#[cfg(target_os = "macos")]
+const SUBLIME_MERGE_APP: MacApp = MacApp {
+    bundle_id: "com.sublimemerge",
+    app_name: "Sublime Merge.app",
+    cask: Some("sublime-merge"),
+};
+
+#[cfg(windows)]
+const SUBLIME_MERGE_WINDOWS_APP: WindowsApp = WindowsApp {
+    display_name: "Sublime Merge",
+    scoop: Some("sublime-merge"),
+    chocolatey: Some("sublimemerge"),
+    winget: Some("SublimeHQ.SublimeMerge"),
+};
+
+#[cfg(target_os = "linux")]
+const SUBLIME_MERGE_FLATPAK_ID: &str = "com.sublimemerge.App";
+
+/// Points Sublime Merge at the git shim via `git_binary` in its user
+/// `Preferences.sublime-settings`.
+pub struct SublimeMergeInstaller;
+
+impl SublimeMergeInstaller {
+    /// Per-user data directory, created on first launch
+    #[cfg(target_os = "macos")]
+    fn data_dir() -> Option<PathBuf> {
+        Some(
+            home_dir()
+                .join("Library")
+                .join("Application Support")
+                .join("Sublime Merge"),
+        )
+    }
+
+    /// Native, AppImage or Flatpak config home, whichever has been run. The
+    /// Snap is a classic snap and shares the native one.
+    #[cfg(target_os = "linux")]
+    fn config_home() -> LinuxConfigHome {
+        find_linux_config_home(
+            "sublime-merge",
+            LinuxPackaging {
+                flatpak_id: Some(SUBLIME_MERGE_FLATPAK_ID),
+                snap_name: None,
+            },
+        )
+    }
+
+    #[cfg(target_os = "linux")]
+    fn data_dir() -> Option<PathBuf> {
+        Some(Self::config_home().dir.join("sublime-merge"))
+    }
+
+    /// Why a sandboxed Sublime Merge couldn't run the shim
+    #[cfg(target_os = "linux")]
+    fn sandbox_problem(params: &GitClientInstallerParams) -> Option<String> {
+        Self::config_home()
+            .flatpak_id
+            .and_then(|id| flatpak_access_problem(id, &params.git_shim_path))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn sandbox_problem(_params: &GitClientInstallerParams) -> Option<String> {
+        None
+    }
+
+    /// `%APPDATA%\Sublime Merge`, unless the install directory has a `Data`
+    /// folder beside the executable (a portable install)
+    #[cfg(windows)]
+    fn data_dir() -> Option<PathBuf> {
+        Self::install_dir()
+            .map(|dir| dir.join("Data"))
+            .filter(|dir| dir.is_dir())
+            .or_else(|| windows_app_data_dir("Sublime Merge"))
+    }
+
+    /// Where the installer or a package manager put Sublime Merge. Scoop
+    /// installs it portable, with `Data` kept in Scoop's persist directory.
+    #[cfg(windows)]
+    fn install_dir() -> Option<PathBuf> {
+        find_windows_install_dir(&SUBLIME_MERGE_WINDOWS_APP)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    fn data_dir() -> Option<PathBuf> {
+        None
+    }
+
+    /// The preferences file, when Sublime Merge has been run on this machine
+    /// or its app is installed
+    fn settings_path() -> Option<PathBuf> {
+        Self::data_dir()
+            .filter(|dir| dir.is_dir() || Self::app_installed())
+            .map(preferences_path)
+    }
+
+    /// Found through Homebrew or Spotlight even if never launched
+    #[cfg(target_os = "macos")]
+    fn app_installed() -> bool {
+        find_macos_app(&SUBLIME_MERGE_APP).is_some()
+    }
+
+    /// Registered or installed by a package manager even if never launched
+    #[cfg(windows)]
+    fn app_installed() -> bool {
+        windows_app_installed(&SUBLIME_MERGE_WINDOWS_APP)
+    }
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    fn app_installed() -> bool {
+        false
+    }
+
+    /// The installed build, from the bundle's `CFBundleShortVersionString`
+    #[cfg(target_os = "macos")]
+    fn build() -> Option<u32> {
+        let app = find_macos_app(&SUBLIME_MERGE_APP)?;
+        parse_build_number(&macos_app_version_string(&app)?)
+    }
+
+    /// The installed build, from `sublime_merge.exe`'s version info
+    #[cfg(windows)]
+    fn build() -> Option<u32> {
+        let exe = Self::install_dir()?.join("sublime_merge.exe");
+        parse_build_number(&windows_exe_version(&exe)?)
+    }
+
+    /// The installed build, from `smerge --version` (cached). The Flatpak
+    /// doesn't put `smerge` on PATH and is assumed current.
+    #[cfg(target_os = "linux")]
+    fn build() -> Option<u32> {
+        cached_discovery(
+            "smerge_build",
+            |_| true,
+            || {
+                let output = std::process::Command::new("smerge")
+                    .arg("--version")
+                    .output()
+                    .ok()
+                    .filter(|output| output.status.success())?;
+                parse_build_number(&String::from_utf8_lossy(&output.stdout))
+            },
+        )
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", windows)))]
+    fn build() -> Option<u32> {
+        None
+    }
+
+    /// Why the installed build can't be pointed at the shim
+    fn version_problem() -> Option<String> {
+        let build = Self::build();
+        unsupported_build_reason(
+            build,
+            select_prefs_variant(SUBLIME_MERGE_PREFS, None, build.map(|b| (b, 0)))?,
+        )
+    }
+}
+
+fn unsupported_build_reason(build: Option<u32>, support: &GitBinarySupport) -> Option<String> {
+    match support {
+        GitBinarySupport::Path => None,
+        GitBinarySupport::BundledOrSystem => Some(format!(
+            "Sublime Merge Build {} only accepts \"bundled\" or \"system\" for git_binary; update to Build 2011 or later",
+            build.unwrap_or_default()
+        )),
+    }
+}
+
+fn preferences_path(data_dir: PathBuf) -> PathBuf {
+    data_dir
+        .join("Packages")
+        .join("User")
+        .join("Preferences.sublime-settings")
+}
+
+impl GitClientInstaller for SublimeMergeInstaller {
+    fn name(&self) -> &str {
+        "Sublime Merge"
+    }
+
+    fn id(&self) -> &str {
+        "sublime-merge"
+    }
+
+    fn process_names(&self) -> Vec<&str> {
+        vec!["sublime_merge"]
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        cfg!(any(target_os = "macos", target_os = "linux", windows))
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(GitClientCheckResult::not_installed());
+        };
+        if let Some(reason) = Self::sandbox_problem(params).or_else(Self::version_problem) {
+            return Ok(GitClientCheckResult::unsupported(reason));
+        }
+
+        Ok(GitClientCheckResult::with_git_path(
+            read_jsonc_string_setting(&path, GIT_BINARY_SETTING)?,
+            &params.git_shim_path.to_string_lossy(),
+        ))
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(None);
+        };
+        // An older build would ignore the path, or fail to find git at all
+        if Self::version_problem().is_some() {
+            return Ok(None);
+        }
+        let shim = params.git_shim_path.to_string_lossy();
+        update_jsonc_string_setting(&path, GIT_BINARY_SETTING, Some(&shim), dry_run)
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let Some(path) = Self::settings_path() else {
+            return Ok(None);
+        };
+        // Leave a user-chosen git_binary alone
+        let shim = params.git_shim_path.to_string_lossy();
+        if read_jsonc_string_setting(&path, GIT_BINARY_SETTING)?.as_deref() != Some(shim.as_ref()) {
+            return Ok(None);
+        }
+        update_jsonc_string_setting(&path, GIT_BINARY_SETTING, None, dry_run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn git_binary_is_set_without_disturbing_other_preferences() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = preferences_path(tmp.path().join("sublime-merge"));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "// Settings in here override those in Default\n{\n\t\"theme\": \"Merge Dark.sublime-theme\",\n}\n").unwrap();
+
+        let shim = "/home/dev/.git-ai/bin/git";
+        assert!(
+            update_jsonc_string_setting(&path, GIT_BINARY_SETTING, Some(shim), false)
+                .unwrap()
+                .is_some()
+        );
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("// Settings in here override"));
+        assert!(content.contains("Merge Dark.sublime-theme"));
+        assert_eq!(
+            read_jsonc_string_setting(&path, GIT_BINARY_SETTING)
+                .unwrap()
+                .as_deref(),
+            Some(shim)
+        );
+        assert!(
+            update_jsonc_string_setting(&path, GIT_BINARY_SETTING, Some(shim), false)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn git_binary_paths_need_sublime_merge_2() {
+        let support = |build: Option<u32>| {
+            *select_prefs_variant(SUBLIME_MERGE_PREFS, None, build.map(|b| (b, 0))).unwrap()
+        };
+        assert_eq!(support(Some(2096)), GitBinarySupport::Path);
+        assert_eq!(support(Some(2011)), GitBinarySupport::Path);
+        assert_eq!(support(None), GitBinarySupport::Path);
+        assert_eq!(support(Some(1119)), GitBinarySupport::BundledOrSystem);
+        assert!(
+            unsupported_build_reason(Some(1119), &GitBinarySupport::BundledOrSystem)
+                .unwrap()
+                .contains("Build 1119")
+        );
+        assert_eq!(
+            unsupported_build_reason(Some(2096), &GitBinarySupport::Path),
+            None
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn linux_preferences_live_under_config_sublime_merge() {
+        let path = preferences_path(SublimeMergeInstaller::data_dir().unwrap());
+        assert!(path.ends_with("sublime-merge/Packages/User/Preferences.sublime-settings"));
+        assert!(SublimeMergeInstaller.is_platform_supported());
+    }
+}
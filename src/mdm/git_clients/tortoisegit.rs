@@ -0,0 +1,281 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams, InstallScope,
+    ManagedRegistryValue, other_user,
+};
+#[cfg(windows)]
+use crate::mdm::utils::{
+    WindowsApp, delete_registry_value, read_registry_string, registry_key_exists,
+    windows_app_installed, write_registry_string,
+};
+use crate::mdm::utils::{generate_diff, registry_display};
+use std::path::Path;
+
+/// TortoiseGit's settings key, under HKCU per user. Values under the same
+/// key in HKLM are machine-wide defaults.
+const TORTOISEGIT_KEY: &str = "Software\\TortoiseGit";
+
+/// Directory TortoiseGit looks in for `git.exe`
+const MSYSGIT_VALUE: &str = "MSysGit";
+
+/// Points TortoiseGit at the git shim through `HKCU\Software\TortoiseGit\MSysGit`
+/// (HKLM for `--scope system`). Unlike most clients the setting names a
+/// directory, not the executable.
+pub struct TortoiseGitInstaller;
+
+impl TortoiseGitInstaller {
+    /// The settings key only appears once TortoiseGit has been run, so fall
+    /// back to its Uninstall entry and package managers
+    #[cfg(windows)]
+    fn is_installed(key: &str) -> bool {
+        registry_key_exists(key)
+            || windows_app_installed(&WindowsApp {
+                display_name: "TortoiseGit",
+                scoop: Some("tortoisegit"),
+                chocolatey: Some("tortoisegit"),
+                winget: Some("TortoiseGit.TortoiseGit"),
+            })
+    }
+
+    #[cfg(not(windows))]
+    fn is_installed(_key: &str) -> bool {
+        false
+    }
+
+    #[cfg(windows)]
+    fn msysgit_dir(key: &str) -> Option<String> {
+        read_registry_string(key, MSYSGIT_VALUE)
+    }
+
+    #[cfg(not(windows))]
+    fn msysgit_dir(_key: &str) -> Option<String> {
+        None
+    }
+
+    #[cfg(windows)]
+    fn set_msysgit_dir(key: &str, dir: &str) -> Result<(), GitAiError> {
+        write_registry_string(key, MSYSGIT_VALUE, dir)
+    }
+
+    #[cfg(not(windows))]
+    fn set_msysgit_dir(_key: &str, _dir: &str) -> Result<(), GitAiError> {
+        Err(GitAiError::Generic(
+            "TortoiseGit is only supported on Windows".to_string(),
+        ))
+    }
+
+    #[cfg(windows)]
+    fn clear_msysgit_dir(key: &str) -> Result<(), GitAiError> {
+        delete_registry_value(key, MSYSGIT_VALUE)
+    }
+
+    #[cfg(not(windows))]
+    fn clear_msysgit_dir(_key: &str) -> Result<(), GitAiError> {
+        Ok(())
+    }
+}
+
+/// The directory holding the shim, as TortoiseGit expects it. Split by hand
+/// rather than with `Path::parent` so `mdm export-policy` gets the same answer
+/// for a Windows shim path when run elsewhere.
+fn shim_dir(git_shim_path: &Path) -> String {
+    let path = git_shim_path.to_string_lossy();
+    match path.rfind(['\\', '/']) {
+        Some(0) => path[..1].to_string(),
+        // Keep the root of `C:\git.exe` as `C:\`, not the drive-relative `C:`
+        Some(at) if path[..at].ends_with(':') => path[..=at].to_string(),
+        Some(at) => path[..at].to_string(),
+        None => ".".to_string(),
+    }
+}
+
+/// Windows paths compare case-insensitively and TortoiseGit tolerates a
+/// trailing separator.
+fn same_dir(a: &str, b: &str) -> bool {
+    let normalize = |p: &str| p.trim().trim_end_matches(['\\', '/']).to_lowercase();
+    normalize(a) == normalize(b)
+}
+
+/// A diff of the key's `MSysGit = <dir>` entry, unset shown as absent
+fn describe_change(key: &str, old: Option<&str>, new: Option<&str>) -> String {
+    let entry = |value: Option<&str>| {
+        value
+            .map(|value| format!("{} = {}\n", MSYSGIT_VALUE, value))
+            .unwrap_or_default()
+    };
+    generate_diff(Path::new(&registry_display(key)), &entry(old), &entry(new))
+}
+
+impl GitClientInstaller for TortoiseGitInstaller {
+    fn name(&self) -> &str {
+        "TortoiseGit"
+    }
+
+    fn id(&self) -> &str {
+        "tortoisegit"
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        cfg!(windows)
+    }
+
+    fn supports_system_scope(&self) -> bool {
+        true
+    }
+
+    fn managed_registry_values(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Vec<ManagedRegistryValue> {
+        vec![ManagedRegistryValue {
+            key: TORTOISEGIT_KEY.to_string(),
+            name: MSYSGIT_VALUE.to_string(),
+            value: shim_dir(&params.git_shim_path),
+        }]
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let key = params.scope.registry_key(TORTOISEGIT_KEY);
+        if !Self::is_installed(&key) {
+            return Ok(GitClientCheckResult::not_installed());
+        }
+        // Another user's HKCU isn't the one this process can reach
+        if params.scope == InstallScope::User
+            && let Some(user) = other_user()
+        {
+            return Ok(GitClientCheckResult::unsupported(format!(
+                "TortoiseGit keeps its settings in {}'s registry; run `git-ai clients install` as them, or use --scope system",
+                user
+            )));
+        }
+
+        // TortoiseGit is given git's directory, not the executable
+        let dir = Self::msysgit_dir(&key);
+        let configured = dir
+            .as_deref()
+            .is_some_and(|dir| same_dir(dir, &shim_dir(&params.git_shim_path)));
+
+        Ok(GitClientCheckResult {
+            client_installed: true,
+            prefs_configured: configured,
+            prefs_up_to_date: configured,
+            unsupported_reason: None,
+            configured_git_path: dir,
+        })
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let key = params.scope.registry_key(TORTOISEGIT_KEY);
+        if !Self::is_installed(&key) {
+            return Ok(None);
+        }
+
+        let target = shim_dir(&params.git_shim_path);
+        let current = Self::msysgit_dir(&key);
+        if current.as_deref().is_some_and(|dir| same_dir(dir, &target)) {
+            return Ok(None);
+        }
+        if !dry_run {
+            Self::set_msysgit_dir(&key, &target)?;
+        }
+        Ok(Some(describe_change(
+            &key,
+            current.as_deref(),
+            Some(&target),
+        )))
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        // Only clear our own value; TortoiseGit then finds Git for Windows itself
+        let key = params.scope.registry_key(TORTOISEGIT_KEY);
+        let Some(current) =
+            Self::msysgit_dir(&key).filter(|dir| same_dir(dir, &shim_dir(&params.git_shim_path)))
+        else {
+            return Ok(None);
+        };
+        if !dry_run {
+            Self::clear_msysgit_dir(&key)?;
+        }
+        Ok(Some(describe_change(&key, Some(&current), None)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mdm::git_client_installer::InstallScope;
+
+    #[test]
+    fn same_dir_ignores_case_and_trailing_separator() {
+        assert!(same_dir(
+            r"C:\Users\Dev\.git-ai\bin\",
+            r"c:\users\dev\.git-ai\bin"
+        ));
+        assert!(!same_dir(
+            r"C:\Program Files\Git\bin",
+            r"C:\Users\dev\.git-ai\bin"
+        ));
+    }
+
+    #[test]
+    fn describe_change_names_the_registry_value() {
+        assert_eq!(
+            describe_change(TORTOISEGIT_KEY, Some(r"C:\Git\bin"), Some(r"C:\git-ai\bin")),
+            "--- a/HKCU/Software/TortoiseGit\n\
+             +++ b/HKCU/Software/TortoiseGit\n\
+             @@ -1 +1 @@\n\
+             -MSysGit = C:\\Git\\bin\n\
+             +MSysGit = C:\\git-ai\\bin\n"
+        );
+        assert!(
+            describe_change(TORTOISEGIT_KEY, None, Some("x"))
+                .starts_with("--- /dev/null\n+++ b/HKCU/Software/TortoiseGit\n")
+        );
+        assert!(
+            describe_change(
+                &InstallScope::System.registry_key(TORTOISEGIT_KEY),
+                None,
+                Some("x")
+            )
+            .contains("+++ b/HKLM/Software/TortoiseGit\n")
+        );
+    }
+
+    #[test]
+    fn shim_dir_splits_windows_paths_on_any_host() {
+        let dir = |path: &str| shim_dir(Path::new(path));
+        assert_eq!(
+            dir(r"C:\Program Files\git-ai\bin\git.exe"),
+            r"C:\Program Files\git-ai\bin"
+        );
+        assert_eq!(dir(r"C:\git.exe"), r"C:\");
+        assert_eq!(dir("/usr/local/git-ai/bin/git"), "/usr/local/git-ai/bin");
+        assert_eq!(dir("git"), ".");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn tortoisegit_is_not_detected_off_windows() {
+        let params = GitClientInstallerParams {
+            git_shim_path: std::path::PathBuf::from("/usr/local/bin/git"),
+            scope: InstallScope::User,
+        };
+        let result = TortoiseGitInstaller.check_client(&params).unwrap();
+        assert!(!result.client_installed);
+        assert_eq!(
+            TortoiseGitInstaller.install_prefs(&params, false).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::{
+    GitClientCheckResult, GitClientInstaller, GitClientInstallerParams,
+};
+use crate::mdm::utils::{
+    read_jsonc_string_setting, settings_path_candidates, should_process_settings_target,
+    update_jsonc_string_setting,
+};
+use std::path::PathBuf;
+
+/// VS Code setting holding the git executable used by the built-in Git extension
+const GIT_PATH_SETTING: &str = "git.path";
+
+/// Points a VS Code build's built-in Git extension at the git shim via the
+/// user-level `git.path` setting.
+pub struct VsCodeInstaller {
+    name: &'static str,
+    id: &'static str,
+    /// Settings directory name under the platform config dir (e.g. "Code")
+    product: &'static str,
+}
+
+impl VsCodeInstaller {
+    pub fn code() -> Self {
+        Self {
+            name: "VS Code",
+            id: "vscode-git",
+            product: "Code",
+        }
+    }
+
+    pub fn insiders() -> Self {
+        Self {
+            name: "VS Code Insiders",
+            id: "vscode-insiders-git",
+            product: "Code - Insiders",
+        }
+    }
+
+    pub fn vscodium() -> Self {
+        Self {
+            name: "VSCodium",
+            id: "vscodium-git",
+            product: "VSCodium",
+        }
+    }
+
+    /// Cursor is a VS Code fork and keeps the same `User/settings.json` layout
+    pub fn cursor() -> Self {
+        Self {
+            name: "Cursor",
+            id: "cursor-git",
+            product: "Cursor",
+        }
+    }
+
+    /// Windsurf (Codeium's editor) is also a VS Code derivative with its own
+    /// settings directory
+    pub fn windsurf() -> Self {
+        Self {
+            name: "Windsurf",
+            id: "windsurf-git",
+            product: "Windsurf",
+        }
+    }
+
+    fn settings_paths(&self) -> Vec<PathBuf> {
+        settings_path_candidates(self.product)
+            .into_iter()
+            .filter(|path| should_process_settings_target(path))
+            .collect()
+    }
+}
+
+impl GitClientInstaller for VsCodeInstaller {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn is_platform_supported(&self) -> bool {
+        true
+    }
+
+    fn check_client(
+        &self,
+        params: &GitClientInstallerParams,
+    ) -> Result<GitClientCheckResult, GitAiError> {
+        let paths = self.settings_paths();
+        if paths.is_empty() {
+            return Ok(GitClientCheckResult::not_installed());
+        }
+
+        let shim = params.git_shim_path.to_string_lossy();
+        let mut configured = 0;
+        let mut other_git_path = None;
+        for path in &paths {
+            let git_path = read_jsonc_string_setting(path, GIT_PATH_SETTING)?;
+            if git_path.as_deref() == Some(shim.as_ref()) {
+                configured += 1;
+            } else if git_path.is_some() && other_git_path.is_none() {
+                other_git_path = git_path;
+            }
+        }
+
+        Ok(GitClientCheckResult {
+            client_installed: true,
+            prefs_configured: configured > 0,
+            prefs_up_to_date: configured == paths.len(),
+            unsupported_reason: None,
+            configured_git_path: other_git_path
+                .or_else(|| (configured > 0).then(|| shim.to_string())),
+        })
+    }
+
+    fn install_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let shim = params.git_shim_path.to_string_lossy();
+        let mut diffs = String::new();
+
+        for path in self.settings_paths() {
+            if let Some(diff) =
+                update_jsonc_string_setting(&path, GIT_PATH_SETTING, Some(&shim), dry_run)?
+            {
+                diffs.push_str(&diff);
+            }
+        }
+
+        Ok((!diffs.is_empty()).then_some(diffs))
+    }
+
+    fn uninstall_prefs(
+        &self,
+        params: &GitClientInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let shim = params.git_shim_path.to_string_lossy();
+        let mut diffs = String::new();
+
+        for path in self.settings_paths() {
+            // Leave a user-chosen git.path alone
+            if read_jsonc_string_setting(&path, GIT_PATH_SETTING)?.as_deref() != Some(shim.as_ref())
+            {
+                continue;
+            }
+            if let Some(diff) = update_jsonc_string_setting(&path, GIT_PATH_SETTING, None, dry_run)?
+            {
+                diffs.push_str(&diff);
+            }
+        }
+
+        Ok((!diffs.is_empty()).then_some(diffs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants_are_reported_separately_with_their_own_settings_dirs() {
+        let variants = [
+            VsCodeInstaller::code(),
+            VsCodeInstaller::insiders(),
+            VsCodeInstaller::vscodium(),
+            VsCodeInstaller::cursor(),
+            VsCodeInstaller::windsurf(),
+        ];
+        for (i, a) in variants.iter().enumerate() {
+            for b in &variants[i + 1..] {
+                assert_ne!(a.id(), b.id());
+                assert_ne!(a.name(), b.name());
+            }
+        }
+
+        let insiders = settings_path_candidates(VsCodeInstaller::insiders().product);
+        assert!(
+            insiders
+                .iter()
+                .all(|p| p.to_string_lossy().contains("Code - Insiders"))
+        );
+        let codium = settings_path_candidates(VsCodeInstaller::vscodium().product);
+        assert!(
+            codium
+                .iter()
+                .all(|p| p.to_string_lossy().contains("VSCodium"))
+        );
+        let windsurf = settings_path_candidates(VsCodeInstaller::windsurf().product);
+        assert!(
+            windsurf
+                .iter()
+                .all(|p| p.to_string_lossy().contains("Windsurf"))
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use super::ide_types::{DetectedIde, JETBRAINS_IDES, JetBrainsIde};
+#[cfg(target_os = "macos")]
+use crate::mdm::utils::find_app_by_bundle_id;
 use crate::mdm::utils::home_dir;
 use std::path::{Path, PathBuf};
 #[cfg(target_os = "macos")]
@@ -82,21 +84,6 @@ fn find_macos_installations() -> Vec<DetectedIde> {
     detected
 }
 
-#[cfg(target_os = "macos")]
-fn find_app_by_bundle_id(bundle_id: &str) -> Option<PathBuf> {
-    let output = Command::new("mdfind")
-        .args([&format!("kMDItemCFBundleIdentifier == '{}'", bundle_id)])
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    stdout.lines().next().map(PathBuf::from)
-}
-
 #[cfg(target_os = "macos")]
 fn is_matching_macos_app(ide: &JetBrainsIde, app_path: &Path) -> bool {
     let app_name = app_path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
@@ -589,6 +576,38 @@ fn get_plugins_dir(
     }
 }
 
+/// The config directory shares its versioned name with the plugins directory:
+/// on macOS and Windows it is the plugins directory's parent, while Linux keeps
+/// it under `~/.config` instead of `~/.local/share`.
+fn config_dir_for_platform(
+    platform: JetBrainsPlatform,
+    home_dir: &Path,
+    plugins_dir: &Path,
+) -> Option<PathBuf> {
+    match platform {
+        JetBrainsPlatform::Macos | JetBrainsPlatform::Windows => {
+            plugins_dir.parent().map(Path::to_path_buf)
+        }
+        JetBrainsPlatform::Linux => {
+            let version_dir = plugins_dir.file_name()?;
+            let parent_dir = plugins_dir.parent()?.file_name()?;
+            Some(home_dir.join(".config").join(parent_dir).join(version_dir))
+        }
+    }
+}
+
+/// Get the config directory (holding `options/*.xml`) for a detected IDE
+pub fn get_config_dir(detected: &DetectedIde) -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let platform = JetBrainsPlatform::Macos;
+    #[cfg(windows)]
+    let platform = JetBrainsPlatform::Windows;
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let platform = JetBrainsPlatform::Linux;
+
+    config_dir_for_platform(platform, &home_dir(), &detected.plugins_dir)
+}
+
 #[cfg(windows)]
 fn windows_program_files_dirs() -> Vec<PathBuf> {
     [
@@ -846,6 +865,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_config_dir_for_macos_is_plugins_parent() {
+        let plugins_dir = PathBuf::from("home")
+            .join("Library")
+            .join("Application Support")
+            .join("JetBrains")
+            .join("IntelliJIdea2026.1")
+            .join("plugins");
+        assert_eq!(
+            config_dir_for_platform(JetBrainsPlatform::Macos, Path::new("home"), &plugins_dir),
+            plugins_dir.parent().map(Path::to_path_buf)
+        );
+    }
+
+    #[test]
+    fn test_config_dir_for_linux_moves_to_dot_config() {
+        let plugins_dir = PathBuf::from("home")
+            .join(".local")
+            .join("share")
+            .join("Google")
+            .join("AndroidStudio2025.3.3");
+        assert_eq!(
+            config_dir_for_platform(JetBrainsPlatform::Linux, Path::new("home"), &plugins_dir),
+            Some(
+                PathBuf::from("home")
+                    .join(".config")
+                    .join("Google")
+                    .join("AndroidStudio2025.3.3")
+            )
+        );
+    }
+
     #[test]
     fn test_is_plugin_installed_detects_legacy_extracted_directory() {
         let temp = tempfile::tempdir().unwrap();
@@ -15,6 +15,10 @@ pub fn download_plugin_from_marketplace(
         plugin_id, product_code, build_number
     );
 
+    if !crate::capabilities::available(crate::capabilities::Capability::Network) {
+        return Err(GitAiError::Generic("no network access".to_string()));
+    }
+
     tracing::debug!("JetBrains: Downloading plugin from {}", url);
 
     let agent = crate::http::build_agent(Some(120));
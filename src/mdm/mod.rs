@@ -1,6 +1,16 @@
 pub mod agents;
+pub mod audit_log;
+pub mod change_summary;
+pub mod discovery_cache;
+pub mod git_client_installer;
+pub mod git_clients;
 pub mod hook_installer;
 pub mod jetbrains;
+pub mod path_template;
+pub mod policy;
+pub mod prefs_backup;
+pub mod profile;
+pub mod remote_policy;
 pub mod skills_installer;
 pub mod spinner;
 pub mod utils;
@@ -0,0 +1,227 @@
+//! Path templates for the git shim, so one `--shim-path` names the right
+//! file for every user and machine it's applied to. `${NAME}` and `%NAME%`
+//! are replaced by a variable of the user being configured, and a leading
+//! `~` by their home directory:
+//!
+//! - `HOME`, `USERPROFILE`: the home directory
+//! - `APPDATA`, `LOCALAPPDATA`: the roaming and local application data
+//!   directories, `AppData\Roaming` and `AppData\Local` of the home unless set
+//! - `USER`, `USERNAME`: the user name
+//! - anything else: the environment variable of that name
+//!
+//! `clients --all-users` resolves the template in each profile's run, and
+//! `mdm export-policy` writes it as an expandable registry string that
+//! Windows resolves for whoever reads it.
+
+use crate::mdm::git_client_installer::other_user;
+use crate::mdm::utils::home_dir;
+use std::path::PathBuf;
+
+/// Whether `value` has any variable to resolve
+pub fn is_template(value: &str) -> bool {
+    value.starts_with('~') || !variables(value).is_empty()
+}
+
+/// Whether `value` uses `%NAME%` variables, which Windows expands itself
+/// from a `REG_EXPAND_SZ` value
+pub fn has_windows_variables(value: &str) -> bool {
+    variables(value).iter().any(|(_, windows)| *windows)
+}
+
+/// The variables in `value`, each with whether it's written `%NAME%`
+fn variables(value: &str) -> Vec<(String, bool)> {
+    let mut found = Vec::new();
+    let _ = substitute(value, |name, windows| {
+        found.push((name.to_string(), windows));
+        Some(String::new())
+    });
+    found
+}
+
+/// `template` with each `${NAME}` and `%NAME%` replaced by `lookup(NAME)`.
+/// A `%` or `$` that doesn't start a variable is kept as is; a variable
+/// `lookup` doesn't know is an error.
+pub fn expand(template: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    substitute(template, |name, _| lookup(name))
+}
+
+/// [`expand`], also telling `lookup` whether the variable is written `%NAME%`
+fn substitute(
+    template: &str,
+    mut lookup: impl FnMut(&str, bool) -> Option<String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(at) = rest.find(['$', '%']) {
+        out.push_str(&rest[..at]);
+        let tail = &rest[at..];
+        // (name, whether it's `%NAME%`, length of the whole variable)
+        let variable = if let Some(braced) = tail.strip_prefix("${") {
+            braced.find('}').map(|end| (&braced[..end], false, end + 3))
+        } else if let Some(percent) = tail.strip_prefix('%') {
+            percent
+                .find('%')
+                .map(|end| (&percent[..end], true, end + 2))
+                .filter(|(name, _, _)| is_name(name))
+        } else {
+            None
+        };
+        match variable {
+            Some((name, windows, len)) if is_name(name) => {
+                let value = lookup(name, windows)
+                    .ok_or_else(|| format!("unknown variable {} in {}", name, template))?;
+                out.push_str(&value);
+                rest = &tail[len..];
+            }
+            Some((name, _, _)) => {
+                return Err(format!("invalid variable name {:?} in {}", name, template));
+            }
+            None => {
+                out.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '(' || c == ')')
+}
+
+/// A variable's value for the user this run configures
+fn target_variable(name: &str) -> Option<String> {
+    let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+    let home = || home_dir().to_string_lossy().into_owned();
+    let app_data = |dir: &str| {
+        home_dir()
+            .join("AppData")
+            .join(dir)
+            .to_string_lossy()
+            .into_owned()
+    };
+    match name.to_ascii_uppercase().as_str() {
+        "HOME" | "USERPROFILE" => Some(home()),
+        "APPDATA" => Some(env("APPDATA").unwrap_or_else(|| app_data("Roaming"))),
+        "LOCALAPPDATA" => Some(env("LOCALAPPDATA").unwrap_or_else(|| app_data("Local"))),
+        "USER" | "USERNAME" => other_user()
+            .or_else(|| env("USER"))
+            .or_else(|| env("USERNAME")),
+        _ => env(name),
+    }
+}
+
+/// `template` resolved for the user this run configures
+pub fn resolve_path_template(template: &str) -> Result<PathBuf, String> {
+    let template = match template.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            format!("${{HOME}}{}", rest)
+        }
+        _ => template.to_string(),
+    };
+    expand(&template, target_variable).map(PathBuf::from)
+}
+
+/// `template` with every variable in the `%NAME%` form Windows expands,
+/// `HOME` and a leading `~` becoming `%USERPROFILE%`
+pub fn to_windows_variables(template: &str) -> Result<String, String> {
+    let template = match template.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            format!("%USERPROFILE%{}", rest)
+        }
+        _ => template.to_string(),
+    };
+    expand(&template, |name| {
+        Some(match name.to_ascii_uppercase().as_str() {
+            "HOME" => "%USERPROFILE%".to_string(),
+            "USER" => "%USERNAME%".to_string(),
+            _ => format!("%{}%", name),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/dev".to_string()),
+            "LOCALAPPDATA" => Some(r"C:\Users\dev\AppData\Local".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_both_variable_forms() {
+        assert_eq!(
+            expand("${HOME}/.git-ai/bin/git", lookup).unwrap(),
+            "/home/dev/.git-ai/bin/git"
+        );
+        assert_eq!(
+            expand(r"%LOCALAPPDATA%\git-ai\bin\git.exe", lookup).unwrap(),
+            r"C:\Users\dev\AppData\Local\git-ai\bin\git.exe"
+        );
+        // Lone markers aren't variables
+        assert_eq!(expand("/opt/100%/$x%", lookup).unwrap(), "/opt/100%/$x%");
+        assert_eq!(
+            expand("/opt/git-ai/bin/git", lookup).unwrap(),
+            "/opt/git-ai/bin/git"
+        );
+        let err = expand("${NOPE}/git", lookup).unwrap_err();
+        assert!(err.contains("unknown variable NOPE"), "{err}");
+        assert!(expand("${a b}/git", lookup).is_err());
+    }
+
+    #[test]
+    fn detects_templates() {
+        assert!(is_template("~/.git-ai/bin/git"));
+        assert!(is_template("${HOME}/.git-ai/bin/git"));
+        assert!(is_template(r"%LOCALAPPDATA%\git-ai\bin\git.exe"));
+        assert!(!is_template("/opt/git-ai/bin/git"));
+        assert!(!is_template(r"C:\Program Files\100%\git.exe"));
+        assert!(has_windows_variables(r"%LOCALAPPDATA%\git-ai\bin\git.exe"));
+        assert!(!has_windows_variables("${HOME}/.git-ai/bin/git"));
+    }
+
+    #[test]
+    fn converts_to_windows_variables() {
+        assert_eq!(
+            to_windows_variables(r"${HOME}\.git-ai\bin\git.exe").unwrap(),
+            r"%USERPROFILE%\.git-ai\bin\git.exe"
+        );
+        assert_eq!(
+            to_windows_variables(r"~\.git-ai\bin\git.exe").unwrap(),
+            r"%USERPROFILE%\.git-ai\bin\git.exe"
+        );
+        assert_eq!(
+            to_windows_variables(r"${LOCALAPPDATA}\git-ai\bin\git.exe").unwrap(),
+            r"%LOCALAPPDATA%\git-ai\bin\git.exe"
+        );
+    }
+
+    #[test]
+    fn resolves_against_the_target_home() {
+        let home = home_dir();
+        assert_eq!(
+            resolve_path_template("~/.git-ai/bin/git").unwrap(),
+            PathBuf::from(format!("{}/.git-ai/bin/git", home.display()))
+        );
+        assert_eq!(
+            resolve_path_template("${USERPROFILE}/bin/git").unwrap(),
+            PathBuf::from(format!("{}/bin/git", home.display()))
+        );
+        assert_eq!(
+            resolve_path_template("${LOCALAPPDATA}/git").unwrap(),
+            match std::env::var("LOCALAPPDATA") {
+                Ok(dir) if !dir.is_empty() => PathBuf::from(format!("{}/git", dir)),
+                _ => home.join("AppData").join("Local").join("git"),
+            }
+        );
+        assert!(resolve_path_template("~other/git").is_ok());
+    }
+}
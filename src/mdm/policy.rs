@@ -0,0 +1,378 @@
+//! Windows policy artifacts that enforce the git client registry settings
+//! through Group Policy, DSC or Intune, instead of git-ai writing them on each
+//! machine.
+//!
+//! - `reg`: a regedit export, importable by hand or with a GPO startup script
+//! - `dsc`: a PowerShell DSC configuration of `Registry` resources
+//! - `intune`: a Microsoft Graph custom configuration profile whose OMA-URI
+//!   settings ingest a small ADMX and enable one policy per value. Arbitrary
+//!   registry values have no settings-catalog entry, so ADMX ingestion is how
+//!   Intune sets them.
+//!
+//! Values with `%NAME%` variables (from a `--shim-path` template) are written
+//! as expandable strings, so each user's reads resolve them for that user.
+
+use crate::mdm::git_client_installer::{InstallScope, ManagedRegistryValue};
+use crate::mdm::path_template::has_windows_variables;
+use crate::mdm::profile::xml_escape;
+use serde_json::json;
+
+/// ADMX app and file name the Intune profile ingests
+const ADMX_APP: &str = "GitAi";
+const ADMX_FILE: &str = "GitAiClients";
+const ADMX_CATEGORY: &str = "GitAi";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyFormat {
+    Reg,
+    Dsc,
+    Intune,
+}
+
+impl PolicyFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "reg" => Ok(Self::Reg),
+            "dsc" => Ok(Self::Dsc),
+            "intune" => Ok(Self::Intune),
+            other => Err(format!(
+                "unknown policy format: {} (expected reg, dsc or intune)",
+                other
+            )),
+        }
+    }
+}
+
+/// The artifact for `values`, applied to HKLM for the system scope or HKCU
+/// for the user scope. `.reg` files come out as UTF-16LE with a BOM like
+/// regedit's own exports; the other formats are UTF-8.
+pub fn build_policy(
+    values: &[ManagedRegistryValue],
+    format: PolicyFormat,
+    scope: InstallScope,
+) -> Result<Vec<u8>, String> {
+    match format {
+        PolicyFormat::Reg => Ok(encode_utf16le(&build_reg(values, scope))),
+        PolicyFormat::Dsc => build_dsc(values, scope).map(String::into_bytes),
+        PolicyFormat::Intune => Ok(build_intune(values, scope).into_bytes()),
+    }
+}
+
+/// Whether `path` is absolute on Windows and outside any user profile, so it
+/// holds for every user of every managed machine
+pub fn is_machine_wide_windows_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let absolute = path.starts_with("\\\\")
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'\\' | b'/'));
+    let normalized = path.replace('/', "\\").to_lowercase();
+    absolute && !normalized.contains("\\users\\") && !normalized.contains("\\.git-ai\\")
+}
+
+fn hive(scope: InstallScope) -> &'static str {
+    match scope {
+        InstallScope::User => "HKEY_CURRENT_USER",
+        InstallScope::System => "HKEY_LOCAL_MACHINE",
+    }
+}
+
+/// `TortoiseGit_MSysGit` for `Software\TortoiseGit` / `MSysGit`: unique per
+/// value and usable as a DSC resource or ADMX policy name
+fn policy_name(value: &ManagedRegistryValue) -> String {
+    let key = value.key.rsplit('\\').next().unwrap_or(&value.key);
+    format!("{}_{}", key, value.name)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn build_reg(values: &[ManagedRegistryValue], scope: InstallScope) -> String {
+    let mut reg = String::from("Windows Registry Editor Version 5.00\r\n");
+    let mut current_key: Option<&str> = None;
+    for value in values {
+        if current_key != Some(value.key.as_str()) {
+            reg.push_str(&format!("\r\n[{}\\{}]\r\n", hive(scope), value.key));
+            current_key = Some(&value.key);
+        }
+        if has_windows_variables(&value.value) {
+            // regedit only takes REG_EXPAND_SZ as hex(2): UTF-16LE, null-terminated
+            let hex: Vec<String> = encode_utf16le(&format!("{}\0", value.value))[2..]
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            reg.push_str(&format!(
+                "\"{}\"=hex(2):{}\r\n",
+                reg_escape(&value.name),
+                hex.join(",")
+            ));
+        } else {
+            reg.push_str(&format!(
+                "\"{}\"=\"{}\"\r\n",
+                reg_escape(&value.name),
+                reg_escape(&value.value)
+            ));
+        }
+    }
+    reg
+}
+
+fn reg_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn encode_utf16le(text: &str) -> Vec<u8> {
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in text.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    bytes
+}
+
+fn build_dsc(values: &[ManagedRegistryValue], scope: InstallScope) -> Result<String, String> {
+    // The LCM applies configurations as SYSTEM, whose HKCU isn't any user's
+    if scope == InstallScope::User {
+        return Err(
+            "DSC applies configurations as SYSTEM, so it can only set machine-wide values; use --scope system"
+                .to_string(),
+        );
+    }
+    let quote = |value: &str| format!("'{}'", value.replace('\'', "''"));
+    let mut dsc = String::from(
+        "Configuration GitAiClientPreferences\n\
+         {\n\
+         \x20   Import-DscResource -ModuleName PSDesiredStateConfiguration\n\
+         \n\
+         \x20   Node 'localhost'\n\
+         \x20   {\n",
+    );
+    for value in values {
+        dsc.push_str(&format!(
+            "        Registry {}\n\
+             \x20       {{\n\
+             \x20           Key       = {}\n\
+             \x20           ValueName = {}\n\
+             \x20           ValueType = '{}'\n\
+             \x20           ValueData = {}\n\
+             \x20           Ensure    = 'Present'\n\
+             \x20       }}\n",
+            policy_name(value),
+            quote(&format!("{}\\{}", hive(scope), value.key)),
+            quote(&value.name),
+            if has_windows_variables(&value.value) {
+                "ExpandString"
+            } else {
+                "String"
+            },
+            quote(&value.value),
+        ));
+    }
+    dsc.push_str("    }\n}\n\nGitAiClientPreferences\n");
+    Ok(dsc)
+}
+
+fn build_admx(values: &[ManagedRegistryValue], scope: InstallScope) -> String {
+    let class = match scope {
+        InstallScope::User => "User",
+        InstallScope::System => "Machine",
+    };
+    let mut policies = String::new();
+    for value in values {
+        let name = policy_name(value);
+        policies.push_str(&format!(
+            "    <policy name=\"{name}\" class=\"{class}\" displayName=\"{name}\" explainText=\"Points the git client at the git-ai git shim.\" key=\"{}\">\n\
+             \x20     <parentCategory ref=\"{ADMX_CATEGORY}\" />\n\
+             \x20     <supportedOn ref=\"SUPPORTED_WINDOWS\" />\n\
+             \x20     <elements>\n\
+             \x20       <text id=\"{name}\" valueName=\"{}\"{} />\n\
+             \x20     </elements>\n\
+             \x20   </policy>\n",
+            xml_escape(&value.key),
+            xml_escape(&value.name),
+            if has_windows_variables(&value.value) {
+                " expandable=\"true\""
+            } else {
+                ""
+            },
+        ));
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <policyDefinitions revision=\"1.0\" schemaVersion=\"1.0\">\n\
+         \x20 <policyNamespaces>\n\
+         \x20   <target prefix=\"gitai\" namespace=\"{ADMX_APP}.Policies.{ADMX_FILE}\" />\n\
+         \x20 </policyNamespaces>\n\
+         \x20 <resources minRequiredRevision=\"1.0\" />\n\
+         \x20 <supportedOn>\n\
+         \x20   <definitions>\n\
+         \x20     <definition name=\"SUPPORTED_WINDOWS\" displayName=\"Windows\" />\n\
+         \x20   </definitions>\n\
+         \x20 </supportedOn>\n\
+         \x20 <categories>\n\
+         \x20   <category name=\"{ADMX_CATEGORY}\" displayName=\"git-ai\" />\n\
+         \x20 </categories>\n\
+         \x20 <policies>\n\
+         {policies}\
+         \x20 </policies>\n\
+         </policyDefinitions>\n"
+    )
+}
+
+fn build_intune(values: &[ManagedRegistryValue], scope: InstallScope) -> String {
+    // ADMX ingestion is always per device; the policies themselves follow
+    // the scope's class
+    let policy_root = match scope {
+        InstallScope::User => "./User",
+        InstallScope::System => "./Device",
+    };
+    let mut settings = vec![json!({
+        "@odata.type": "#microsoft.graph.omaSettingString",
+        "displayName": "git-ai ADMX",
+        "omaUri": format!(
+            "./Device/Vendor/MSFT/Policy/ConfigOperations/ADMXInstall/{}/Policy/{}",
+            ADMX_APP, ADMX_FILE
+        ),
+        "value": build_admx(values, scope),
+    })];
+    for value in values {
+        let name = policy_name(value);
+        settings.push(json!({
+            "@odata.type": "#microsoft.graph.omaSettingString",
+            "displayName": format!("{}\\{}", value.key, value.name),
+            "omaUri": format!(
+                "{}/Vendor/MSFT/Policy/Config/{}~Policy~{}/{}",
+                policy_root, ADMX_APP, ADMX_CATEGORY, name
+            ),
+            "value": format!(
+                "<enabled/><data id=\"{}\" value=\"{}\"/>",
+                name,
+                xml_escape(&value.value)
+            ),
+        }));
+    }
+    let profile = json!({
+        "@odata.type": "#microsoft.graph.windows10CustomConfiguration",
+        "displayName": "git-ai git client preferences",
+        "description": "Points git clients at the git-ai git shim so commits are attributed.",
+        "omaSettings": settings,
+    });
+    let mut out = serde_json::to_string_pretty(&profile).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tortoisegit(dir: &str) -> ManagedRegistryValue {
+        ManagedRegistryValue {
+            key: "Software\\TortoiseGit".to_string(),
+            name: "MSysGit".to_string(),
+            value: dir.to_string(),
+        }
+    }
+
+    #[test]
+    fn reg_export_escapes_values_and_is_utf16() {
+        let values = [tortoisegit(r"C:\Program Files\git-ai\bin")];
+        let bytes = build_policy(&values, PolicyFormat::Reg, InstallScope::System).unwrap();
+        assert_eq!(&bytes[..2], &[0xFF, 0xFE]);
+        let units: Vec<u16> = bytes[2..]
+            .chunks(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        let text = String::from_utf16(&units).unwrap();
+        assert_eq!(
+            text,
+            "Windows Registry Editor Version 5.00\r\n\
+             \r\n\
+             [HKEY_LOCAL_MACHINE\\Software\\TortoiseGit]\r\n\
+             \"MSysGit\"=\"C:\\\\Program Files\\\\git-ai\\\\bin\"\r\n"
+        );
+        assert!(build_reg(&values, InstallScope::User).contains("[HKEY_CURRENT_USER\\"));
+    }
+
+    #[test]
+    fn dsc_sets_machine_values_only() {
+        let values = [tortoisegit(r"C:\Tools\o'neil\bin")];
+        let dsc = build_dsc(&values, InstallScope::System).unwrap();
+        assert!(dsc.contains("        Registry TortoiseGit_MSysGit\n"));
+        assert!(dsc.contains("Key       = 'HKEY_LOCAL_MACHINE\\Software\\TortoiseGit'"));
+        assert!(dsc.contains("ValueData = 'C:\\Tools\\o''neil\\bin'"));
+        assert!(build_dsc(&values, InstallScope::User).is_err());
+    }
+
+    #[test]
+    fn intune_profile_ingests_admx_and_enables_each_value() {
+        let values = [tortoisegit(r"C:\Program Files\git-ai\bin")];
+        let profile: serde_json::Value =
+            serde_json::from_str(&build_intune(&values, InstallScope::System)).unwrap();
+        let settings = profile["omaSettings"].as_array().unwrap();
+        assert_eq!(settings.len(), 2);
+        assert_eq!(
+            settings[0]["omaUri"],
+            "./Device/Vendor/MSFT/Policy/ConfigOperations/ADMXInstall/GitAi/Policy/GitAiClients"
+        );
+        let admx = settings[0]["value"].as_str().unwrap();
+        assert!(admx.contains("class=\"Machine\""));
+        assert!(admx.contains("key=\"Software\\TortoiseGit\""));
+        assert_eq!(
+            settings[1]["omaUri"],
+            "./Device/Vendor/MSFT/Policy/Config/GitAi~Policy~GitAi/TortoiseGit_MSysGit"
+        );
+        assert_eq!(
+            settings[1]["value"],
+            "<enabled/><data id=\"TortoiseGit_MSysGit\" value=\"C:\\Program Files\\git-ai\\bin\"/>"
+        );
+
+        let user: serde_json::Value =
+            serde_json::from_str(&build_intune(&values, InstallScope::User)).unwrap();
+        assert!(
+            user["omaSettings"][1]["omaUri"]
+                .as_str()
+                .unwrap()
+                .starts_with("./User/")
+        );
+    }
+
+    #[test]
+    fn templated_values_are_expandable_strings() {
+        let values = [tortoisegit(r"%LOCALAPPDATA%\git-ai\bin")];
+        let reg = build_reg(&values, InstallScope::User);
+        assert!(
+            reg.contains("\"MSysGit\"=hex(2):25,00,4c,00,4f,00,"),
+            "{reg}"
+        );
+        assert!(reg.ends_with(",62,00,69,00,6e,00,00,00\r\n"), "{reg}");
+
+        let dsc = build_dsc(&values, InstallScope::System).unwrap();
+        assert!(dsc.contains("ValueType = 'ExpandString'"));
+        assert!(build_admx(&values, InstallScope::User).contains(" expandable=\"true\" />"));
+
+        let plain = [tortoisegit(r"C:\Program Files\git-ai\bin")];
+        assert!(!build_admx(&plain, InstallScope::System).contains("expandable"));
+        assert!(
+            build_dsc(&plain, InstallScope::System)
+                .unwrap()
+                .contains("ValueType = 'String'")
+        );
+    }
+
+    #[test]
+    fn machine_wide_windows_paths() {
+        assert!(is_machine_wide_windows_path(
+            r"C:\Program Files\git-ai\bin\git.exe"
+        ));
+        assert!(is_machine_wide_windows_path(r"\\fileserver\tools\git.exe"));
+        assert!(!is_machine_wide_windows_path(
+            r"C:\Users\dev\.git-ai\bin\git.exe"
+        ));
+        assert!(!is_machine_wide_windows_path(
+            r"D:\tools\.git-ai\bin\git.exe"
+        ));
+        assert!(!is_machine_wide_windows_path("/usr/local/git-ai/bin/git"));
+        assert!(!is_machine_wide_windows_path(r"git-ai\bin\git.exe"));
+    }
+}
@@ -0,0 +1,373 @@
+//! Snapshots of git client preferences, taken before `install_prefs` changes
+//! them and restorable with `git-ai clients restore <client>`.
+//!
+//! Every preference write made by the client installers goes through
+//! [`write_atomic`], [`update_macos_default`] or the registry helpers in
+//! [`crate::mdm::utils`]. While a [`BackupSession`] is active on the current
+//! thread those helpers record the prior value first, so installers don't
+//! need to know about backups. Each session that records anything becomes
+//! `<state dir>/backups/<client>/<timestamp>/` holding a `manifest.json` and a
+//! copy of each file as it was.
+
+use crate::app_context::AppContext;
+use crate::error::GitAiError;
+#[cfg(target_os = "macos")]
+use crate::mdm::utils::update_macos_default;
+#[cfg(windows)]
+use crate::mdm::utils::{delete_registry_value, write_registry_string};
+use crate::mdm::utils::{registry_display, write_atomic};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// One preference as it was before git-ai changed it. `None` values mean it
+/// didn't exist, so restoring removes it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackupEntry {
+    File {
+        path: PathBuf,
+        /// Name of the saved copy inside the snapshot directory
+        copy: Option<String>,
+    },
+    MacosDefault {
+        domain: String,
+        key: String,
+        value: Option<String>,
+    },
+    RegistryValue {
+        subkey: String,
+        value_name: String,
+        value: Option<String>,
+    },
+}
+
+impl BackupEntry {
+    fn same_target(&self, other: &BackupEntry) -> bool {
+        match (self, other) {
+            (BackupEntry::File { path: a, .. }, BackupEntry::File { path: b, .. }) => a == b,
+            (
+                BackupEntry::MacosDefault {
+                    domain: a_domain,
+                    key: a_key,
+                    ..
+                },
+                BackupEntry::MacosDefault {
+                    domain: b_domain,
+                    key: b_key,
+                    ..
+                },
+            ) => a_domain == b_domain && a_key == b_key,
+            (
+                BackupEntry::RegistryValue {
+                    subkey: a_subkey,
+                    value_name: a_name,
+                    ..
+                },
+                BackupEntry::RegistryValue {
+                    subkey: b_subkey,
+                    value_name: b_name,
+                    ..
+                },
+            ) => a_subkey == b_subkey && a_name == b_name,
+            _ => false,
+        }
+    }
+
+    /// One-line description for restore output
+    pub fn describe(&self) -> String {
+        match self {
+            BackupEntry::File { path, copy } => match copy {
+                Some(_) => format!("{}: restored", path.display()),
+                None => format!("{}: removed (did not exist before)", path.display()),
+            },
+            BackupEntry::MacosDefault { domain, key, value } => match value {
+                Some(value) => format!("defaults {} {} = {}", domain, key, value),
+                None => format!("defaults {} {}: deleted", domain, key),
+            },
+            BackupEntry::RegistryValue {
+                subkey,
+                value_name,
+                value,
+            } => match value {
+                Some(value) => format!("{}\\{} = {}", registry_display(subkey), value_name, value),
+                None => format!("{}\\{}: deleted", registry_display(subkey), value_name),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    client: String,
+    entries: Vec<BackupEntry>,
+}
+
+struct ActiveBackup {
+    dir: PathBuf,
+    manifest: Manifest,
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<ActiveBackup>> = const { RefCell::new(None) };
+}
+
+/// `<state dir>/backups` (`~/.git-ai/internal/backups` by default)
+pub fn backups_root() -> Option<PathBuf> {
+    Some(AppContext::current().state_dir.join("backups"))
+}
+
+/// Records prior preference values on this thread until dropped
+pub struct BackupSession {
+    _private: (),
+}
+
+impl BackupSession {
+    /// Start recording into a new snapshot for `client_id` under `root`. The
+    /// snapshot directory is only created once something is recorded.
+    pub fn begin(root: &Path, client_id: &str) -> Self {
+        let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+        let active = ActiveBackup {
+            dir: root.join(client_id).join(timestamp.to_string()),
+            manifest: Manifest {
+                client: client_id.to_string(),
+                entries: Vec::new(),
+            },
+        };
+        ACTIVE.with(|cell| *cell.borrow_mut() = Some(active));
+        Self { _private: () }
+    }
+
+    /// The snapshot directory, if anything was recorded
+    pub fn snapshot_dir(&self) -> Option<PathBuf> {
+        ACTIVE.with(|cell| {
+            cell.borrow()
+                .as_ref()
+                .filter(|active| !active.manifest.entries.is_empty())
+                .map(|active| active.dir.clone())
+        })
+    }
+}
+
+impl Drop for BackupSession {
+    fn drop(&mut self) {
+        ACTIVE.with(|cell| *cell.borrow_mut() = None);
+    }
+}
+
+/// Add `entry` to the active snapshot unless its target is already recorded
+/// (the first value seen is the one to go back to). `save` stores any copy
+/// the entry refers to.
+fn record(
+    entry: impl FnOnce(usize) -> BackupEntry,
+    save: impl FnOnce(&Path, usize) -> Result<(), GitAiError>,
+) -> Result<(), GitAiError> {
+    ACTIVE.with(|cell| {
+        let mut active = cell.borrow_mut();
+        let Some(active) = active.as_mut() else {
+            return Ok(());
+        };
+        let index = active.manifest.entries.len();
+        let entry = entry(index);
+        if active
+            .manifest
+            .entries
+            .iter()
+            .any(|existing| existing.same_target(&entry))
+        {
+            return Ok(());
+        }
+        fs::create_dir_all(&active.dir).map_err(|e| {
+            GitAiError::Generic(format!(
+                "Failed to create backup directory {}: {}",
+                active.dir.display(),
+                e
+            ))
+        })?;
+        save(&active.dir, index)?;
+        active.manifest.entries.push(entry);
+        // Written after every entry so an interrupted install still leaves
+        // a usable snapshot
+        fs::write(
+            active.dir.join(MANIFEST_FILE),
+            serde_json::to_vec_pretty(&active.manifest)?,
+        )?;
+        Ok(())
+    })
+}
+
+fn copy_name(index: usize) -> String {
+    format!("{}.bak", index)
+}
+
+/// Save `path` as it is now, before it gets overwritten
+pub fn record_file(path: &Path) -> Result<(), GitAiError> {
+    // write_atomic is used well beyond client prefs; skip the read when no
+    // session is recording
+    if !ACTIVE.with(|cell| cell.borrow().is_some()) {
+        return Ok(());
+    }
+    let existing = fs::read(path).ok();
+    let exists = existing.is_some();
+    record(
+        |index| BackupEntry::File {
+            path: path.to_path_buf(),
+            copy: exists.then(|| copy_name(index)),
+        },
+        |dir, index| match &existing {
+            Some(content) => Ok(fs::write(dir.join(copy_name(index)), content)?),
+            None => Ok(()),
+        },
+    )
+}
+
+/// Save a macOS defaults value before it is written or deleted
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn record_macos_default(
+    domain: &str,
+    key: &str,
+    value: Option<String>,
+) -> Result<(), GitAiError> {
+    record(
+        |_| BackupEntry::MacosDefault {
+            domain: domain.to_string(),
+            key: key.to_string(),
+            value,
+        },
+        |_, _| Ok(()),
+    )
+}
+
+/// Save a registry string value before it is written or deleted
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn record_registry_value(
+    subkey: &str,
+    value_name: &str,
+    value: Option<String>,
+) -> Result<(), GitAiError> {
+    record(
+        |_| BackupEntry::RegistryValue {
+            subkey: subkey.to_string(),
+            value_name: value_name.to_string(),
+            value,
+        },
+        |_, _| Ok(()),
+    )
+}
+
+/// Snapshot directories for `client_id`, oldest first
+pub fn list_snapshots(root: &Path, client_id: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root.join(client_id)) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(MANIFEST_FILE).is_file())
+        .collect();
+    // Timestamps sort lexically
+    dirs.sort();
+    dirs
+}
+
+/// Put every preference recorded in `snapshot_dir` back the way it was.
+/// Returns the restored entries.
+pub fn restore_snapshot(snapshot_dir: &Path) -> Result<Vec<BackupEntry>, GitAiError> {
+    let manifest_path = snapshot_dir.join(MANIFEST_FILE);
+    let content = fs::read(&manifest_path).map_err(|e| {
+        GitAiError::Generic(format!(
+            "Failed to read backup manifest {}: {}",
+            manifest_path.display(),
+            e
+        ))
+    })?;
+    let manifest: Manifest = serde_json::from_slice(&content)?;
+    for entry in &manifest.entries {
+        restore_entry(snapshot_dir, entry)?;
+    }
+    Ok(manifest.entries)
+}
+
+fn restore_entry(snapshot_dir: &Path, entry: &BackupEntry) -> Result<(), GitAiError> {
+    match entry {
+        BackupEntry::File {
+            path,
+            copy: Some(copy),
+        } => {
+            let content = fs::read(snapshot_dir.join(copy))?;
+            write_atomic(path, &content)
+        }
+        BackupEntry::File { path, copy: None } => match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        },
+        #[cfg(target_os = "macos")]
+        BackupEntry::MacosDefault { domain, key, value } => {
+            update_macos_default(domain, key, value.as_deref(), false).map(|_| ())
+        }
+        #[cfg(windows)]
+        BackupEntry::RegistryValue {
+            subkey,
+            value_name,
+            value,
+        } => match value {
+            Some(value) => write_registry_string(subkey, value_name, value),
+            None => delete_registry_value(subkey, value_name),
+        },
+        other => Err(GitAiError::Generic(format!(
+            "Cannot restore {} on this platform",
+            other.describe()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_records_first_value_and_restores_it() {
+        let root = tempfile::tempdir().unwrap();
+        let prefs = tempfile::tempdir().unwrap();
+        let settings = prefs.path().join("settings.json");
+        let created = prefs.path().join("new.json");
+        fs::write(&settings, "{\"git.path\": \"/usr/bin/git\"}").unwrap();
+
+        let snapshot = {
+            let session = BackupSession::begin(root.path(), "vscode");
+            assert_eq!(session.snapshot_dir(), None);
+            write_atomic(&settings, b"{\"git.path\": \"/shim/git\"}").unwrap();
+            write_atomic(&settings, b"{\"git.path\": \"/shim/git2\"}").unwrap();
+            write_atomic(&created, b"{}").unwrap();
+            session.snapshot_dir().unwrap()
+        };
+
+        // Writes outside a session are not recorded
+        write_atomic(&settings, b"{\"git.path\": \"/shim/git3\"}").unwrap();
+        assert_eq!(
+            list_snapshots(root.path(), "vscode"),
+            vec![snapshot.clone()]
+        );
+        assert!(list_snapshots(root.path(), "fork").is_empty());
+
+        let restored = restore_snapshot(&snapshot).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(
+            fs::read_to_string(&settings).unwrap(),
+            "{\"git.path\": \"/usr/bin/git\"}"
+        );
+        assert!(!created.exists());
+    }
+
+    #[test]
+    fn session_without_changes_leaves_no_snapshot() {
+        let root = tempfile::tempdir().unwrap();
+        {
+            let _session = BackupSession::begin(root.path(), "fork");
+        }
+        assert!(!root.path().join("fork").exists());
+    }
+}
@@ -0,0 +1,234 @@
+//! macOS configuration profiles (`.mobileconfig`) that enforce the git client
+//! preferences through an MDM such as Jamf or Kandji, instead of git-ai
+//! writing them on each machine.
+//!
+//! Each preference domain becomes one custom settings payload whose
+//! `PayloadType` is the domain itself. Payload UUIDs are derived from the
+//! profile's content, so re-exporting an unchanged profile yields the same
+//! file and an MDM sees no new version.
+
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::ManagedPreference;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// `PayloadIdentifier` of the profile unless `--identifier` overrides it
+pub const DEFAULT_PROFILE_IDENTIFIER: &str = "com.git-ai.client-preferences";
+
+/// Top-level fields of an exported profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileOptions {
+    /// Reverse-DNS `PayloadIdentifier`; payloads get `<identifier>.<domain>`
+    pub identifier: String,
+    pub organization: Option<String>,
+}
+
+/// The unsigned `.mobileconfig` XML for `preferences`
+pub fn build_profile(preferences: &[ManagedPreference], options: &ProfileOptions) -> String {
+    let mut domains: BTreeMap<&str, BTreeMap<&str, &str>> = BTreeMap::new();
+    for preference in preferences {
+        domains
+            .entry(preference.domain.as_str())
+            .or_default()
+            .insert(preference.key.as_str(), preference.value.as_str());
+    }
+
+    let mut payloads = String::new();
+    let mut content_seed = options.identifier.clone();
+    for (domain, keys) in &domains {
+        let identifier = format!("{}.{}", options.identifier, domain);
+        let mut seed = identifier.clone();
+        for (key, value) in keys {
+            seed.push_str(&format!("\n{}={}", key, value));
+        }
+        content_seed.push_str(&seed);
+
+        payloads.push_str("\t\t<dict>\n");
+        push_entry(&mut payloads, 3, "PayloadDisplayName", domain);
+        push_entry(&mut payloads, 3, "PayloadIdentifier", &identifier);
+        push_entry(&mut payloads, 3, "PayloadType", domain);
+        push_entry(&mut payloads, 3, "PayloadUUID", &payload_uuid(&seed));
+        payloads.push_str("\t\t\t<key>PayloadVersion</key>\n\t\t\t<integer>1</integer>\n");
+        for (key, value) in keys {
+            push_entry(&mut payloads, 3, key, value);
+        }
+        payloads.push_str("\t\t</dict>\n");
+    }
+
+    let mut profile = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n\
+         \t<key>PayloadContent</key>\n\
+         \t<array>\n",
+    );
+    profile.push_str(&payloads);
+    profile.push_str("\t</array>\n");
+    push_entry(
+        &mut profile,
+        1,
+        "PayloadDescription",
+        "Points git clients at the git-ai git shim so commits are attributed.",
+    );
+    push_entry(
+        &mut profile,
+        1,
+        "PayloadDisplayName",
+        "git-ai git client preferences",
+    );
+    push_entry(&mut profile, 1, "PayloadIdentifier", &options.identifier);
+    if let Some(organization) = &options.organization {
+        push_entry(&mut profile, 1, "PayloadOrganization", organization);
+    }
+    push_entry(&mut profile, 1, "PayloadScope", "System");
+    push_entry(&mut profile, 1, "PayloadType", "Configuration");
+    push_entry(&mut profile, 1, "PayloadUUID", &payload_uuid(&content_seed));
+    profile.push_str("\t<key>PayloadVersion</key>\n\t<integer>1</integer>\n");
+    profile.push_str("</dict>\n</plist>\n");
+    profile
+}
+
+/// Sign a profile with a keychain identity through `security cms`, so MDMs
+/// and System Settings show it as verified. macOS only.
+#[cfg(target_os = "macos")]
+pub fn sign_profile(unsigned: &[u8], identity: &str) -> Result<Vec<u8>, GitAiError> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new("security")
+        .args(["cms", "-S", "-N", identity])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| GitAiError::Generic(format!("Failed to run security cms: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(unsigned)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(GitAiError::Generic(format!(
+            "Failed to sign the profile with {:?}: {}",
+            identity,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn sign_profile(_unsigned: &[u8], _identity: &str) -> Result<Vec<u8>, GitAiError> {
+    Err(GitAiError::Generic(
+        "Signing profiles needs macOS's security tool; export unsigned and sign it there or in your MDM"
+            .to_string(),
+    ))
+}
+
+fn push_entry(out: &mut String, depth: usize, key: &str, value: &str) {
+    let indent = "\t".repeat(depth);
+    out.push_str(&format!(
+        "{indent}<key>{}</key>\n{indent}<string>{}</string>\n",
+        xml_escape(key),
+        xml_escape(value)
+    ));
+}
+
+pub(crate) fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A stable UUID for `seed`, laid out as an RFC 9562 version 8 UUID
+fn payload_uuid(seed: &str) -> String {
+    let digest = Sha256::digest(seed.as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    bytes[6] = (bytes[6] & 0x0f) | 0x80;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preference(domain: &str, key: &str, value: &str) -> ManagedPreference {
+        ManagedPreference {
+            domain: domain.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn profile_has_one_payload_per_domain() {
+        let options = ProfileOptions {
+            identifier: "com.example.git".to_string(),
+            organization: Some("Example & Co".to_string()),
+        };
+        let preferences = [
+            preference(
+                "com.DanPristupov.Fork",
+                "customGitInstancePath",
+                "/opt/git-ai/bin/git",
+            ),
+            preference("com.panic.Nova", "GitToolPath", "/opt/git-ai/bin/git"),
+            preference(
+                "com.DanPristupov.Fork",
+                "gitInstancePath",
+                "/opt/git-ai/bin/git",
+            ),
+        ];
+        let profile = build_profile(&preferences, &options);
+
+        assert_eq!(profile.matches("<key>PayloadType</key>").count(), 3);
+        assert!(profile.contains("<string>com.example.git.com.DanPristupov.Fork</string>"));
+        assert!(profile.contains(
+            "\t\t\t<key>customGitInstancePath</key>\n\t\t\t<string>/opt/git-ai/bin/git</string>"
+        ));
+        assert!(profile.contains("<string>Example &amp; Co</string>"));
+        assert!(profile.contains("<string>Configuration</string>"));
+        // Fork's payload comes first and holds both of its keys
+        let fork = profile
+            .find("<string>com.DanPristupov.Fork</string>")
+            .unwrap();
+        let nova = profile.find("<string>com.panic.Nova</string>").unwrap();
+        assert!(fork < nova);
+        assert!(profile.find("gitInstancePath").unwrap() < nova);
+
+        // Stable for the same content, different when it changes
+        assert_eq!(profile, build_profile(&preferences, &options));
+        let moved = [preference(
+            "com.panic.Nova",
+            "GitToolPath",
+            "/usr/local/git-ai/bin/git",
+        )];
+        let other = build_profile(&moved, &options);
+        let uuid = |profile: &str| {
+            let at = profile.rfind("<key>PayloadUUID</key>").unwrap();
+            profile[at..].lines().nth(1).unwrap().to_string()
+        };
+        assert_ne!(uuid(&profile), uuid(&other));
+    }
+
+    #[test]
+    fn payload_uuids_are_well_formed() {
+        let uuid = payload_uuid("seed");
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "8");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "A" | "B"));
+        assert_ne!(uuid, payload_uuid("other seed"));
+    }
+}
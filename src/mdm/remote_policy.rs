@@ -0,0 +1,404 @@
+//! The desired git client state, pulled from a policy server so a fleet is
+//! managed centrally instead of by per-machine flags. `git-ai mdm sync`
+//! fetches the policy, verifies it, and runs the git client installers as it
+//! says.
+//!
+//! The policy is JSON or TOML at an HTTPS URL, with a detached Ed25519
+//! signature of its exact bytes, base64-encoded, at the same URL plus `.sig`
+//! (as `openssl pkeyutl -sign -rawin` makes). For example:
+//!
+//! ```toml
+//! version = 1
+//! serial = 42               # raise on every publish
+//! enforcement = "enforce"   # or "audit" to only report
+//! scope = "user"            # or "system"
+//! shim_path = "${HOME}/.git-ai/bin/git"
+//! force = false
+//!
+//! [clients]
+//! include = ["fork", "vscode"]
+//! exclude = []
+//! ```
+//!
+//! Every field but `version` (the format) and `serial` is optional. The last
+//! policy that verified is kept in `~/.git-ai/internal/mdm_policy`, so a
+//! machine that can't reach the server still enforces it. Since it is also the
+//! highest `serial` seen, a validly signed but older policy is refused: an
+//! attacker who can replay old responses can't roll the fleet back.
+
+use crate::config::{ClientsConfig, internal_dir_path};
+use crate::error::GitAiError;
+use crate::mdm::git_client_installer::InstallScope;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The only policy format version so far
+pub const POLICY_VERSION: u32 = 1;
+
+const FETCH_TIMEOUT_SECS: u64 = 30;
+
+/// What a policy asks of the clients it names
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Enforcement {
+    /// Only report what would change, like `clients check`
+    Audit,
+    /// Point the clients at the shim, like `clients install`
+    #[default]
+    Enforce,
+}
+
+impl Enforcement {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Audit => "audit",
+            Self::Enforce => "enforce",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    version: u32,
+    serial: u64,
+    #[serde(default)]
+    enforcement: Enforcement,
+    #[serde(default)]
+    scope: Option<String>,
+    #[serde(default)]
+    shim_path: Option<String>,
+    #[serde(default)]
+    force: bool,
+    #[serde(default)]
+    clients: Option<PolicyClients>,
+}
+
+/// The policy's `clients` table: the `clients` config's lists
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyClients {
+    #[serde(default)]
+    include: Option<Vec<String>>,
+    #[serde(default)]
+    exclude: Option<Vec<String>>,
+}
+
+/// A verified policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemotePolicy {
+    /// Raised by the publisher on every change; never goes backwards
+    pub serial: u64,
+    pub enforcement: Enforcement,
+    pub scope: InstallScope,
+    /// A shim path template (see [`crate::mdm::path_template`]); this
+    /// install's shim when unset
+    pub shim_path: Option<String>,
+    /// Configure clients even while they are running
+    pub force: bool,
+    /// Replaces the local `clients` config when set
+    pub clients: Option<ClientsConfig>,
+}
+
+impl RemotePolicy {
+    /// Parse a policy body: JSON when it starts with `{`, otherwise TOML
+    pub fn parse(body: &[u8]) -> Result<Self, String> {
+        let text = std::str::from_utf8(body).map_err(|_| "policy isn't UTF-8".to_string())?;
+        let file: PolicyFile = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|e| format!("invalid policy JSON: {}", e))?
+        } else {
+            toml::from_str(text).map_err(|e| format!("invalid policy TOML: {}", e))?
+        };
+        if file.version != POLICY_VERSION {
+            return Err(format!(
+                "unsupported policy version {} (expected {})",
+                file.version, POLICY_VERSION
+            ));
+        }
+        let scope = match file.scope.as_deref() {
+            None => InstallScope::User,
+            Some(value) => InstallScope::parse(value).ok_or_else(|| {
+                format!("invalid policy scope: {} (expected user or system)", value)
+            })?,
+        };
+        Ok(Self {
+            serial: file.serial,
+            enforcement: file.enforcement,
+            scope,
+            shim_path: file.shim_path,
+            force: file.force,
+            clients: file.clients.map(|clients| ClientsConfig {
+                include: clients.include,
+                exclude: clients.exclude,
+                ..Default::default()
+            }),
+        })
+    }
+}
+
+/// Where a policy comes from and the key it must be signed with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicySource {
+    pub url: String,
+    /// PEM Ed25519 public key
+    pub public_key: PathBuf,
+}
+
+/// A policy and how it was obtained
+#[derive(Debug)]
+pub struct FetchedPolicy {
+    pub policy: RemotePolicy,
+    /// Why the server's copy couldn't be used, when the last known good
+    /// policy was used instead
+    pub fallback_reason: Option<String>,
+}
+
+/// Only HTTPS, so the policy can't be swapped in transit even before the
+/// signature check; plain HTTP is allowed to this machine for testing
+fn check_url(url: &str) -> Result<(), GitAiError> {
+    let parsed = url::Url::parse(url)
+        .map_err(|e| GitAiError::Generic(format!("invalid policy URL {}: {}", url, e)))?;
+    let loopback = matches!(
+        parsed.host_str(),
+        Some("localhost") | Some("127.0.0.1") | Some("[::1]")
+    );
+    if parsed.scheme() == "https" || (parsed.scheme() == "http" && loopback) {
+        Ok(())
+    } else {
+        Err(GitAiError::Generic(format!(
+            "the policy URL must use https, not {}",
+            url
+        )))
+    }
+}
+
+fn get(url: &str) -> Result<Vec<u8>, String> {
+    let agent = crate::http::build_agent(Some(FETCH_TIMEOUT_SECS));
+    let response = crate::http::send(agent.get(url))?;
+    if response.status_code != 200 {
+        return Err(format!("{} returned HTTP {}", url, response.status_code));
+    }
+    Ok(response.into_bytes())
+}
+
+/// The body and its decoded signature
+fn download(url: &str) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let body = get(url)?;
+    let signature = get(&format!("{}.sig", url))?;
+    let signature = BASE64
+        .decode(String::from_utf8_lossy(&signature).trim())
+        .map_err(|e| format!("{}.sig isn't base64: {}", url, e))?;
+    Ok((body, signature))
+}
+
+/// Fetch, verify and parse the policy at `source`. If the server can't be
+/// reached the last verified policy is used; a policy that fails
+/// verification, or is older than the last one, is an error either way, so a
+/// tampered or replayed response can't fall back silently.
+pub fn fetch_policy(source: &PolicySource) -> Result<FetchedPolicy, GitAiError> {
+    check_url(&source.url)?;
+    let cache = last_known_good_path();
+    let last_known_good = cache
+        .as_deref()
+        .and_then(|cache| load_last_known_good(cache, &source.url))
+        .and_then(|cached| verified_last_known_good(source, &cached));
+    match download(&source.url) {
+        Ok((body, signature)) => {
+            verify_signature(&source.public_key, &body, &signature)?;
+            let policy = RemotePolicy::parse(&body).map_err(GitAiError::Generic)?;
+            if let Some(last) = &last_known_good {
+                check_not_rolled_back(&policy, last)?;
+            }
+            if let Some(cache) = &cache {
+                save_last_known_good(cache, &source.url, &body, &signature);
+            }
+            Ok(FetchedPolicy {
+                policy,
+                fallback_reason: None,
+            })
+        }
+        Err(reason) => Ok(FetchedPolicy {
+            policy: last_known_good.ok_or_else(|| {
+                GitAiError::Generic(format!("couldn't fetch the policy: {}", reason))
+            })?,
+            fallback_reason: Some(reason),
+        }),
+    }
+}
+
+/// The saved policy, if it still verifies against the configured key. One that
+/// doesn't (say the signing key was rotated) is treated as absent rather than
+/// an error, so it can't block fetching the policy signed with the new key.
+fn verified_last_known_good(source: &PolicySource, cached: &LastKnownGood) -> Option<RemotePolicy> {
+    let parsed = verify_signature(&source.public_key, &cached.body, &cached.signature)
+        .and_then(|()| RemotePolicy::parse(&cached.body).map_err(GitAiError::Generic));
+    match parsed {
+        Ok(policy) => Some(policy),
+        Err(e) => {
+            tracing::warn!("ignoring the saved MDM policy: {}", e);
+            None
+        }
+    }
+}
+
+/// A policy may repeat the last one's serial (an unchanged policy) but never
+/// go below it
+fn check_not_rolled_back(policy: &RemotePolicy, last: &RemotePolicy) -> Result<(), GitAiError> {
+    if policy.serial < last.serial {
+        return Err(GitAiError::Generic(format!(
+            "the policy's serial {} is older than {} already applied; refusing to roll back",
+            policy.serial, last.serial
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedPolicy {
+    url: String,
+    /// Base64
+    body: String,
+    /// Base64
+    signature: String,
+}
+
+struct LastKnownGood {
+    body: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+fn last_known_good_path() -> Option<PathBuf> {
+    internal_dir_path().map(|dir| dir.join("mdm_policy"))
+}
+
+/// Best effort: without it the next offline run just fails
+fn save_last_known_good(path: &Path, url: &str, body: &[u8], signature: &[u8]) {
+    let entry = CachedPolicy {
+        url: url.to_string(),
+        body: BASE64.encode(body),
+        signature: BASE64.encode(signature),
+    };
+    let Ok(json) = serde_json::to_vec(&entry) else {
+        return;
+    };
+    if let Err(e) = crate::mdm::utils::write_atomic(path, &json) {
+        tracing::debug!("failed to save the MDM policy: {}", e);
+    }
+}
+
+/// The saved policy, if it came from `url`
+fn load_last_known_good(path: &Path, url: &str) -> Option<LastKnownGood> {
+    let entry: CachedPolicy = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    if entry.url != url {
+        return None;
+    }
+    Some(LastKnownGood {
+        body: BASE64.decode(entry.body).ok()?,
+        signature: BASE64.decode(entry.signature).ok()?,
+    })
+}
+
+/// Check an Ed25519 `signature` of `message` with the PEM public key at
+/// `public_key`, through the `openssl` CLI like `ci attest` signs. `-rawin`
+/// needs the input as a file.
+pub fn verify_signature(
+    public_key: &Path,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), GitAiError> {
+    let message_file = crate::utils::private_temp_file(message)?;
+    let signature_file = crate::utils::private_temp_file(signature)?;
+    let output = Command::new("openssl")
+        .args(["pkeyutl", "-verify", "-pubin", "-rawin", "-inkey"])
+        .arg(public_key)
+        .arg("-in")
+        .arg(message_file.path())
+        .arg("-sigfile")
+        .arg(signature_file.path())
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| GitAiError::Generic(format!("Failed to run openssl: {}", e)))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(GitAiError::Generic(format!(
+            "the policy's signature doesn't match {}",
+            public_key.display()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_and_toml_policies() {
+        let toml = br#"
+version = 1
+serial = 7
+enforcement = "audit"
+scope = "system"
+shim_path = "/opt/git-ai/bin/git"
+
+[clients]
+include = ["fork"]
+"#;
+        let policy = RemotePolicy::parse(toml).unwrap();
+        assert_eq!(policy.serial, 7);
+        assert_eq!(policy.enforcement, Enforcement::Audit);
+        assert_eq!(policy.scope, InstallScope::System);
+        assert_eq!(policy.shim_path.as_deref(), Some("/opt/git-ai/bin/git"));
+        assert!(!policy.force);
+        let clients = policy.clients.unwrap();
+        assert!(clients.allows("fork") && !clients.allows("vscode"));
+
+        let json = RemotePolicy::parse(br#"{"version": 1, "serial": 1, "force": true}"#).unwrap();
+        assert_eq!(json.enforcement, Enforcement::Enforce);
+        assert_eq!(json.scope, InstallScope::User);
+        assert!(json.force && json.clients.is_none() && json.shim_path.is_none());
+
+        assert!(RemotePolicy::parse(br#"{"version": 2, "serial": 1}"#).is_err());
+        assert!(
+            RemotePolicy::parse(br#"{"version": 1, "serial": 1, "scope": "machine"}"#).is_err()
+        );
+        assert!(RemotePolicy::parse(br#"{"version": 1, "serial": 1, "bogus": true}"#).is_err());
+        assert!(RemotePolicy::parse(b"enforcement = \"enforce\"").is_err());
+        assert!(RemotePolicy::parse(br#"{"version": 1}"#).is_err());
+    }
+
+    #[test]
+    fn an_older_serial_is_a_rollback() {
+        let policy = |serial| {
+            RemotePolicy::parse(format!("version = 1\nserial = {serial}\n").as_bytes()).unwrap()
+        };
+        assert!(check_not_rolled_back(&policy(3), &policy(3)).is_ok());
+        assert!(check_not_rolled_back(&policy(4), &policy(3)).is_ok());
+        let err = check_not_rolled_back(&policy(2), &policy(3)).unwrap_err();
+        assert!(err.to_string().contains("roll back"), "{}", err);
+    }
+
+    #[test]
+    fn only_https_or_loopback_urls() {
+        assert!(check_url("https://mdm.example.com/git-ai.toml").is_ok());
+        assert!(check_url("http://127.0.0.1:8080/policy.json").is_ok());
+        assert!(check_url("http://mdm.example.com/git-ai.toml").is_err());
+        assert!(check_url("file:///etc/policy.json").is_err());
+        assert!(check_url("not a url").is_err());
+    }
+
+    #[test]
+    fn last_known_good_is_per_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mdm_policy");
+        save_last_known_good(&path, "https://a/policy", b"body", b"sig");
+        let saved = load_last_known_good(&path, "https://a/policy").unwrap();
+        assert_eq!(saved.body, b"body");
+        assert_eq!(saved.signature, b"sig");
+        assert!(load_last_known_good(&path, "https://b/policy").is_none());
+    }
+}
@@ -1,12 +1,22 @@
+use crate::output::{self, Status};
 use indicatif::{ProgressBar, ProgressStyle};
 
 /// Spinner UI component for showing progress
 pub struct Spinner {
     pb: ProgressBar,
+    quiet: bool,
 }
 
 impl Spinner {
     pub fn new(message: &str) -> Self {
+        // Plain mode announces only the outcome, never an animation
+        if output::is_plain() {
+            return Self {
+                pb: ProgressBar::hidden(),
+                quiet: false,
+            };
+        }
+
         let pb = ProgressBar::new_spinner();
         pb.set_style(
             ProgressStyle::default_spinner()
@@ -17,7 +27,15 @@ impl Spinner {
         pb.set_message(message.to_string());
         pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
-        Self { pb }
+        Self { pb, quiet: false }
+    }
+
+    /// A spinner that draws and prints nothing, for `--json` output
+    pub fn hidden() -> Self {
+        Self {
+            pb: ProgressBar::hidden(),
+            quiet: true,
+        }
     }
 
     pub fn start(&self) {
@@ -41,26 +59,33 @@ impl Spinner {
     pub fn success(&self, message: &str) {
         // Clear spinner and show success with green checkmark and bold green text
         self.pb.finish_and_clear();
-        println!("\x1b[1;32m✓ {}\x1b[0m", message);
+        if !self.quiet {
+            println!("{}", Status::Ok.line(message));
+        }
     }
 
     pub fn pending(&self, message: &str) {
         // Clear spinner and show pending with yellow warning triangle and bold yellow text
         self.pb.finish_and_clear();
-        println!("\x1b[1;33m⚠ {}\x1b[0m", message);
+        if !self.quiet {
+            println!("{}", Status::Pending.line(message));
+        }
     }
 
     pub fn error(&self, message: &str) {
         // Clear spinner and show error with red X and bold red text
         self.pb.finish_and_clear();
-        println!("\x1b[1;31m✗ {}\x1b[0m", message);
+        if !self.quiet {
+            println!("{}", Status::Failed.line(message));
+        }
     }
 
-    #[allow(dead_code)]
     pub fn skipped(&self, message: &str) {
         // Clear spinner and show skipped with gray circle and gray text
         self.pb.finish_and_clear();
-        println!("\x1b[90m○ {}\x1b[0m", message);
+        if !self.quiet {
+            println!("{}", Status::Skipped.line(message));
+        }
     }
 }
 
@@ -68,7 +93,9 @@ impl Spinner {
 pub fn print_diff(diff_text: &str) {
     // Print a formatted diff using colors
     for line in diff_text.lines() {
-        if line.starts_with("+++") || line.starts_with("---") {
+        if output::is_plain() {
+            println!("{}", line);
+        } else if line.starts_with("+++") || line.starts_with("---") {
             // File headers in bold
             println!("\x1b[1m{}\x1b[0m", line);
         } else if line.starts_with('+') {
@@ -1,11 +1,18 @@
 use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
+use crate::config::internal_dir_path;
 use crate::error::GitAiError;
+#[cfg(any(target_os = "macos", windows))]
+use crate::mdm::discovery_cache::{cached_discovery, path_exists};
+use crate::mdm::prefs_backup;
+use crate::utils::LockFile;
 use jsonc_parser::ParseOptions;
-use jsonc_parser::cst::CstRootNode;
+use jsonc_parser::cst::{CstInputValue, CstRootNode};
+use std::ffi::OsStr;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, SystemTime};
 
 // Minimum version requirements
 pub const MIN_CURSOR_VERSION: (u32, u32) = (1, 7);
@@ -85,6 +92,20 @@ pub fn version_meets_requirement(version: (u32, u32), min_version: (u32, u32)) -
     false
 }
 
+/// The `git` a PATH lookup over `path_var` resolves to.
+fn first_git_on_path(path_var: &OsStr) -> Option<PathBuf> {
+    let exe = if cfg!(windows) { "git.exe" } else { "git" };
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(exe))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Whether the shim is what a PATH lookup for `git` would find. Compares
+/// directories so the symlinked shim isn't resolved to the git-ai binary.
+pub fn shim_first_on_path(path_var: &OsStr, git_shim_path: &Path) -> bool {
+    first_git_on_path(path_var).is_some_and(|git| git.parent() == git_shim_path.parent())
+}
+
 /// Check if a binary with the given name exists in the system PATH
 pub fn binary_exists(name: &str) -> bool {
     if let Ok(path_var) = std::env::var("PATH") {
@@ -461,6 +482,7 @@ pub fn gemini_config_dir() -> PathBuf {
 /// Write data to a file atomically (write to temp, then rename)
 /// If the path is a symlink, writes to the target file (preserving the symlink)
 pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), GitAiError> {
+    prefs_backup::record_file(path)?;
     let target_path = if path.is_symlink() {
         fs::canonicalize(path).map_err(|e| {
             GitAiError::Generic(format!(
@@ -521,25 +543,278 @@ pub fn is_git_ai_checkpoint_command(cmd: &str) -> bool {
     cmd.contains("git-ai") && cmd.contains("checkpoint")
 }
 
-/// Generate a diff between old and new content
+/// Lines of context around each change in [`generate_diff`], as `diff -u`
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// A unified diff of `path` going from `old_content` to `new_content`, with
+/// `a/` and `b/` prefixes on the path relative to the filesystem root so the
+/// patch applies with `patch -d / -p1`. Empty content stands for a missing
+/// file (`/dev/null`). Returns an empty string when nothing changed.
 pub fn generate_diff(path: &Path, old_content: &str, new_content: &str) -> String {
     let changes = compute_line_changes(old_content, new_content);
+    let changed: Vec<usize> = changes
+        .iter()
+        .enumerate()
+        .filter(|(_, change)| *change.tag() != LineChangeTag::Equal)
+        .map(|(index, _)| index)
+        .collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    let display = diff_path(path);
     let mut diff_output = String::new();
-    diff_output.push_str(&format!("--- {}\n", path.display()));
-    diff_output.push_str(&format!("+++ {}\n", path.display()));
-
-    for change in changes {
-        let sign = match change.tag() {
-            LineChangeTag::Delete => "-",
-            LineChangeTag::Insert => "+",
-            LineChangeTag::Equal => " ",
+    if old_content.is_empty() {
+        diff_output.push_str("--- /dev/null\n");
+    } else {
+        diff_output.push_str(&format!("--- a/{}\n", display));
+    }
+    if new_content.is_empty() {
+        diff_output.push_str("+++ /dev/null\n");
+    } else {
+        diff_output.push_str(&format!("+++ b/{}\n", display));
+    }
+
+    // Group changes whose context would overlap into one hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &index in &changed {
+        let start = index.saturating_sub(DIFF_CONTEXT_LINES);
+        let end = (index + DIFF_CONTEXT_LINES + 1).min(changes.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    // 1-based line numbers in the old and new content where each change starts
+    let mut old_line = 0;
+    let mut new_line = 0;
+    let mut positions = Vec::with_capacity(changes.len());
+    for change in &changes {
+        positions.push((old_line, new_line));
+        match change.tag() {
+            LineChangeTag::Delete => old_line += 1,
+            LineChangeTag::Insert => new_line += 1,
+            LineChangeTag::Equal => {
+                old_line += 1;
+                new_line += 1;
+            }
+        }
+    }
+
+    for (start, end) in hunks {
+        let hunk = &changes[start..end];
+        let old_count = hunk
+            .iter()
+            .filter(|change| *change.tag() != LineChangeTag::Insert)
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|change| *change.tag() != LineChangeTag::Delete)
+            .count();
+        let (old_start, new_start) = positions[start];
+        // An empty range is numbered by the line before it, and a count of
+        // one is left out, as `diff -u` writes them
+        let range = |line: usize, count: usize| match count {
+            0 => format!("{},0", line),
+            1 => format!("{}", line + 1),
+            _ => format!("{},{}", line + 1, count),
         };
-        diff_output.push_str(&format!("{}{}", sign, change.value()));
+        diff_output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_start, old_count),
+            range(new_start, new_count)
+        ));
+        for change in hunk {
+            let sign = match change.tag() {
+                LineChangeTag::Delete => "-",
+                LineChangeTag::Insert => "+",
+                LineChangeTag::Equal => " ",
+            };
+            let line = change.value();
+            diff_output.push_str(sign);
+            diff_output.push_str(line);
+            if !line.ends_with('\n') {
+                diff_output.push_str("\n\\ No newline at end of file\n");
+            }
+        }
     }
 
     diff_output
 }
 
+/// `path` as a diff header names it: relative to the filesystem root, with
+/// forward slashes
+fn diff_path(path: &Path) -> String {
+    let display = path.to_string_lossy().replace('\\', "/");
+    display.trim_start_matches('/').to_string()
+}
+
+/// How a Linux client may be packaged besides a native install. AppImages run
+/// unsandboxed and share the native XDG config home, so only Flatpak and Snap
+/// need locations of their own.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinuxPackaging {
+    /// Flatpak app id, e.g. `com.sublimemerge.App`
+    pub flatpak_id: Option<&'static str>,
+    /// Name of a strictly confined snap
+    pub snap_name: Option<&'static str>,
+}
+
+/// An XDG config home a Linux client reads, and the Flatpak it belongs to
+/// when it is inside that sandbox
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinuxConfigHome {
+    pub dir: PathBuf,
+    pub flatpak_id: Option<&'static str>,
+}
+
+/// Config homes a Linux client may use: the native one first, then those of
+/// its Flatpak (`~/.var/app/<id>/config`) and Snap
+/// (`~/snap/<name>/current/.config`) when they are installed for this user
+pub fn linux_config_homes(packaging: LinuxPackaging) -> Vec<LinuxConfigHome> {
+    let xdg_config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute());
+    linux_config_homes_in(&home_dir(), xdg_config_home, packaging)
+}
+
+fn linux_config_homes_in(
+    home: &Path,
+    xdg_config_home: Option<PathBuf>,
+    packaging: LinuxPackaging,
+) -> Vec<LinuxConfigHome> {
+    let mut homes = vec![LinuxConfigHome {
+        dir: xdg_config_home.unwrap_or_else(|| home.join(".config")),
+        flatpak_id: None,
+    }];
+    if let Some(id) = packaging.flatpak_id {
+        let app_dir = home.join(".var").join("app").join(id);
+        if app_dir.is_dir() {
+            homes.push(LinuxConfigHome {
+                dir: app_dir.join("config"),
+                flatpak_id: Some(id),
+            });
+        }
+    }
+    if let Some(name) = packaging.snap_name {
+        let snap_dir = home.join("snap").join(name);
+        if snap_dir.is_dir() {
+            homes.push(LinuxConfigHome {
+                dir: snap_dir.join("current").join(".config"),
+                flatpak_id: None,
+            });
+        }
+    }
+    homes
+}
+
+/// The config home holding a Linux client's `app_dir`, preferring the native
+/// one; the native home when the client hasn't been run yet
+pub fn find_linux_config_home(app_dir: &str, packaging: LinuxPackaging) -> LinuxConfigHome {
+    let mut homes = linux_config_homes(packaging);
+    let index = homes
+        .iter()
+        .position(|home| home.dir.join(app_dir).is_dir())
+        .unwrap_or(0);
+    homes.swap_remove(index)
+}
+
+/// Why the Flatpak `app_id` can't run `path`, going by the filesystem
+/// permissions in its metadata and any `flatpak override`s. `None` when it
+/// can, or when the app isn't installed where Flatpak keeps its metadata.
+pub fn flatpak_access_problem(app_id: &str, path: &Path) -> Option<String> {
+    let home = home_dir();
+    let system_root = PathBuf::from("/var/lib/flatpak");
+    let user_root = home.join(".local").join("share").join("flatpak");
+    let metadata: Vec<String> = [&system_root, &user_root]
+        .iter()
+        .filter_map(|root| {
+            fs::read_to_string(
+                root.join("app")
+                    .join(app_id)
+                    .join("current")
+                    .join("active")
+                    .join("metadata"),
+            )
+            .ok()
+        })
+        .collect();
+    if metadata.is_empty() {
+        return None;
+    }
+
+    // Overrides apply after the app's own permissions, per-user ones last
+    let mut entries: Vec<String> = metadata
+        .iter()
+        .flat_map(|content| flatpak_filesystems(content))
+        .collect();
+    for root in [&system_root, &user_root] {
+        for name in ["global", app_id] {
+            if let Ok(content) = fs::read_to_string(root.join("overrides").join(name)) {
+                entries.extend(flatpak_filesystems(&content));
+            }
+        }
+    }
+    if flatpak_filesystems_allow(&entries, path, &home) {
+        return None;
+    }
+    let dir = path.parent().unwrap_or(path).display().to_string();
+    Some(format!(
+        "the {} Flatpak can't see {}; allow it with `flatpak override --user --filesystem={}:ro {}`",
+        app_id, dir, dir, app_id
+    ))
+}
+
+/// `filesystems=` entries from the `[Context]` group of a Flatpak keyfile
+fn flatpak_filesystems(keyfile: &str) -> Vec<String> {
+    let mut in_context = false;
+    let mut entries = Vec::new();
+    for line in keyfile.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_context = line == "[Context]";
+        } else if in_context && let Some(value) = line.strip_prefix("filesystems=") {
+            entries.extend(
+                value
+                    .split(';')
+                    .filter(|entry| !entry.is_empty())
+                    .map(str::to_string),
+            );
+        }
+    }
+    entries
+}
+
+/// Whether `filesystems` entries, applied in order, expose `path`
+fn flatpak_filesystems_allow(entries: &[String], path: &Path, home: &Path) -> bool {
+    let mut allowed = false;
+    for entry in entries {
+        let (negated, entry) = match entry.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, entry.as_str()),
+        };
+        let location = match entry.rsplit_once(':') {
+            Some((location, "ro" | "rw" | "create")) => location,
+            _ => entry,
+        };
+        let covered = match location {
+            "host" => true,
+            "home" | "~" => path.starts_with(home),
+            _ => {
+                if let Some(rest) = location.strip_prefix("~/") {
+                    path.starts_with(home.join(rest))
+                } else {
+                    location.starts_with('/') && path.starts_with(location)
+                }
+            }
+        };
+        if covered {
+            allowed = !negated;
+        }
+    }
+    allowed
+}
+
 /// Check if a settings target path should be processed
 pub fn should_process_settings_target(path: &Path) -> bool {
     path.exists() || path.parent().map(|parent| parent.exists()).unwrap_or(false)
@@ -590,6 +865,21 @@ pub fn settings_path_candidates(product: &str) -> Vec<PathBuf> {
                 .join("User")
                 .join("settings.json"),
         );
+        let packaging = LinuxPackaging {
+            flatpak_id: vscode_flatpak_id(product),
+            snap_name: None,
+        };
+        for config_home in linux_config_homes(packaging) {
+            if config_home.flatpak_id.is_some() {
+                paths.push(
+                    config_home
+                        .dir
+                        .join(product)
+                        .join("User")
+                        .join("settings.json"),
+                );
+            }
+        }
     }
 
     paths.sort();
@@ -597,6 +887,18 @@ pub fn settings_path_candidates(product: &str) -> Vec<PathBuf> {
     paths
 }
 
+/// Flathub app id of a VS Code family product. The Snap builds are classic
+/// snaps that use the native config directory.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn vscode_flatpak_id(product: &str) -> Option<&'static str> {
+    match product {
+        "Code" => Some("com.visualstudio.code"),
+        "Code - Insiders" => Some("com.visualstudio.code.insiders"),
+        "VSCodium" => Some("com.vscodium.codium"),
+        _ => None,
+    }
+}
+
 /// Get settings paths for multiple products
 pub fn settings_paths_for_products(product_names: &[&str]) -> Vec<PathBuf> {
     let mut paths: Vec<PathBuf> = product_names
@@ -724,6 +1026,960 @@ pub fn get_current_binary_path() -> Result<PathBuf, GitAiError> {
     Ok(clean_path(canonical))
 }
 
+/// Path of the git shim that sits next to the git-ai binary.
+/// Any binary not named `git-ai` proxies to real git, so the shim is simply
+/// a `git` symlink (or `git.exe` copy on Windows) of the git-ai binary.
+pub fn git_shim_path(binary_path: &Path) -> PathBuf {
+    let install_dir = binary_path.parent().unwrap_or_else(|| Path::new("."));
+
+    #[cfg(windows)]
+    {
+        install_dir.join("git.exe")
+    }
+
+    #[cfg(not(windows))]
+    {
+        install_dir.join("git")
+    }
+}
+
+/// How the git shim next to the git-ai binary is materialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShimStrategy {
+    /// A symlink to the git-ai binary, which follows upgrades automatically
+    Symlink,
+    /// A copy of the binary, for Windows and for filesystems without
+    /// symlinks (exFAT, FAT32, many SMB/NFS home directories). Refreshed
+    /// whenever it no longer matches the binary.
+    Copy,
+}
+
+impl ShimStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShimStrategy::Symlink => "symlink",
+            ShimStrategy::Copy => "copy",
+        }
+    }
+}
+
+/// Pick the shim strategy `install_dir` supports. On unix this creates and
+/// removes a probe symlink; an unwritable directory is an error up front
+/// rather than a failure halfway through creating the shim.
+pub fn shim_strategy_for_dir(install_dir: &Path) -> Result<ShimStrategy, GitAiError> {
+    let probe = install_dir.join(format!(".git-ai-fs-probe-{}", std::process::id()));
+    let _ = fs::remove_file(&probe);
+    fs::write(&probe, b"").map_err(|e| {
+        GitAiError::Generic(format!(
+            "Install directory {} is not writable: {}",
+            install_dir.display(),
+            e
+        ))
+    })?;
+    let _ = fs::remove_file(&probe);
+
+    #[cfg(windows)]
+    {
+        Ok(ShimStrategy::Copy)
+    }
+
+    #[cfg(not(windows))]
+    {
+        // exFAT and some network mounts refuse symlinks with EPERM or
+        // ENOTSUP; the write above already ruled out plain permission errors
+        match std::os::unix::fs::symlink("git-ai", &probe) {
+            Ok(()) => {
+                let _ = fs::remove_file(&probe);
+                Ok(ShimStrategy::Symlink)
+            }
+            Err(e) => {
+                tracing::debug!(
+                    "{} does not support symlinks ({}); copying the git shim",
+                    install_dir.display(),
+                    e
+                );
+                Ok(ShimStrategy::Copy)
+            }
+        }
+    }
+}
+
+/// How the existing shim for `binary_path` was created, if there is one
+pub fn installed_shim_strategy(binary_path: &Path) -> Option<ShimStrategy> {
+    let metadata = fs::symlink_metadata(git_shim_path(binary_path)).ok()?;
+    if metadata.file_type().is_symlink() {
+        Some(ShimStrategy::Symlink)
+    } else {
+        Some(ShimStrategy::Copy)
+    }
+}
+
+/// A copied shim is stale once the binary has been replaced by an upgrade
+fn shim_copy_is_current(binary_path: &Path, shim_path: &Path) -> bool {
+    let (Ok(binary), Ok(shim)) = (fs::metadata(binary_path), fs::metadata(shim_path)) else {
+        return false;
+    };
+    let newer_or_same = match (binary.modified(), shim.modified()) {
+        (Ok(binary_mtime), Ok(shim_mtime)) => shim_mtime >= binary_mtime,
+        _ => true,
+    };
+    binary.len() == shim.len() && newer_or_same
+}
+
+/// Create the git shim next to the git-ai binary if it doesn't exist yet,
+/// symlinking where the filesystem allows it and copying otherwise. A
+/// copied shim that no longer matches the binary is refreshed.
+/// Returns true if the shim was created or refreshed.
+pub fn ensure_git_shim(binary_path: &Path) -> Result<bool, GitAiError> {
+    let shim_path = git_shim_path(binary_path);
+    match installed_shim_strategy(binary_path) {
+        // A dangling symlink reports !exists(); fall through and replace it
+        Some(ShimStrategy::Symlink) if shim_path.exists() => return Ok(false),
+        Some(ShimStrategy::Copy) => {
+            if shim_copy_is_current(binary_path, &shim_path) {
+                return Ok(false);
+            }
+            // A running git (common on Windows) can hold the old copy open;
+            // it still works, so keep it and retry on the next install
+            return match fs::copy(binary_path, &shim_path) {
+                Ok(_) => Ok(true),
+                Err(e) => {
+                    tracing::debug!("failed to refresh git shim {}: {}", shim_path.display(), e);
+                    Ok(false)
+                }
+            };
+        }
+        _ => {}
+    }
+
+    let install_dir = shim_path.parent().unwrap_or_else(|| Path::new("."));
+    let strategy = shim_strategy_for_dir(install_dir)?;
+    let _ = fs::remove_file(&shim_path);
+    let result = match strategy {
+        #[cfg(not(windows))]
+        ShimStrategy::Symlink => std::os::unix::fs::symlink(binary_path, &shim_path),
+        _ => fs::copy(binary_path, &shim_path).map(|_| ()),
+    };
+    result.map_err(|e| {
+        GitAiError::Generic(format!(
+            "Failed to create git shim {} ({}): {}",
+            shim_path.display(),
+            strategy.as_str(),
+            e
+        ))
+    })?;
+
+    Ok(true)
+}
+
+/// Remove the git shim [`ensure_git_shim`] created next to the git-ai binary.
+/// Returns true if there was one (or, on a dry run, would be removed).
+pub fn remove_git_shim(binary_path: &Path, dry_run: bool) -> Result<bool, GitAiError> {
+    if installed_shim_strategy(binary_path).is_none() {
+        return Ok(false);
+    }
+    let shim_path = git_shim_path(binary_path);
+    if !dry_run {
+        fs::remove_file(&shim_path).map_err(|e| {
+            GitAiError::Generic(format!(
+                "Failed to remove git shim {}: {}",
+                shim_path.display(),
+                e
+            ))
+        })?;
+    }
+    Ok(true)
+}
+
+/// Find an installed macOS app bundle by its bundle identifier using Spotlight
+#[cfg(target_os = "macos")]
+pub fn find_app_by_bundle_id(bundle_id: &str) -> Option<PathBuf> {
+    let output = Command::new("mdfind")
+        .args([&format!("kMDItemCFBundleIdentifier == '{}'", bundle_id)])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().next().map(PathBuf::from)
+}
+
+/// A macOS app to look for: where installers put it, the Homebrew cask that
+/// installs it, and Spotlight as a last resort
+#[derive(Debug, Clone, Copy)]
+pub struct MacApp {
+    pub bundle_id: &'static str,
+    /// Bundle directory name, e.g. `Fork.app`
+    pub app_name: &'static str,
+    /// Homebrew cask token, e.g. `sublime-merge`
+    pub cask: Option<&'static str>,
+}
+
+/// Find an installed macOS app. Checks `/Applications` and `~/Applications`,
+/// then the `--appdir` and Caskroom of its Homebrew cask, before asking
+/// Spotlight, which misses apps on unindexed volumes or when indexing is off.
+/// Cached, since Spotlight can take seconds.
+#[cfg(target_os = "macos")]
+pub fn find_macos_app(app: &MacApp) -> Option<PathBuf> {
+    cached_discovery(&format!("macos_app:{}", app.bundle_id), path_exists, || {
+        discover_macos_app(app)
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn discover_macos_app(app: &MacApp) -> Option<PathBuf> {
+    let home = home_dir();
+    let mut candidates = vec![
+        PathBuf::from("/Applications").join(app.app_name),
+        home.join("Applications").join(app.app_name),
+    ];
+    if let Some(cask) = app.cask {
+        let appdir = std::env::var("HOMEBREW_CASK_OPTS")
+            .ok()
+            .and_then(|opts| cask_appdir(&opts, &home));
+        candidates.extend(cask_app_candidates(
+            &homebrew_caskrooms(),
+            cask,
+            app.app_name,
+            appdir.as_deref(),
+        ));
+    }
+    candidates
+        .into_iter()
+        .find(|path| path.exists())
+        .or_else(|| find_app_by_bundle_id(app.bundle_id))
+}
+
+/// Caskroom directories of the Homebrew installs on this machine
+#[cfg(target_os = "macos")]
+fn homebrew_caskrooms() -> Vec<PathBuf> {
+    let mut prefixes: Vec<PathBuf> = std::env::var_os("HOMEBREW_PREFIX")
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+    // Apple Silicon and Intel defaults
+    prefixes.extend(["/opt/homebrew", "/usr/local"].map(PathBuf::from));
+    prefixes.dedup();
+    prefixes
+        .into_iter()
+        .map(|prefix| prefix.join("Caskroom"))
+        .collect()
+}
+
+/// The `--appdir` set in `HOMEBREW_CASK_OPTS`, where casks move their apps
+/// instead of `/Applications`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn cask_appdir(opts: &str, home: &Path) -> Option<PathBuf> {
+    let mut words = opts.split_whitespace();
+    let mut appdir = None;
+    while let Some(word) = words.next() {
+        if let Some(value) = word.strip_prefix("--appdir=") {
+            appdir = Some(value);
+        } else if word == "--appdir" {
+            appdir = words.next();
+        }
+    }
+    let appdir = appdir?.trim_matches(|c| c == '"' || c == '\'');
+    match appdir.strip_prefix("~/") {
+        Some(rest) => Some(home.join(rest)),
+        None if appdir == "~" => Some(home.to_path_buf()),
+        None => Some(PathBuf::from(appdir)),
+    }
+}
+
+/// Where an installed cask's app may be: its `--appdir`, or kept in the
+/// Caskroom under the installed version. Empty when the cask isn't installed.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn cask_app_candidates(
+    caskrooms: &[PathBuf],
+    cask: &str,
+    app_name: &str,
+    appdir: Option<&Path>,
+) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    for cask_dir in caskrooms.iter().map(|caskroom| caskroom.join(cask)) {
+        let Ok(versions) = fs::read_dir(&cask_dir) else {
+            continue;
+        };
+        if let Some(appdir) = appdir {
+            candidates.push(appdir.join(app_name));
+        }
+        let mut version_dirs: Vec<PathBuf> = versions
+            .flatten()
+            .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+            .map(|entry| entry.path().join(app_name))
+            .collect();
+        version_dirs.sort();
+        candidates.extend(version_dirs);
+    }
+    candidates.dedup();
+    candidates
+}
+
+/// Read a string preference from a macOS defaults domain (e.g. an app's
+/// bundle identifier)
+#[cfg(target_os = "macos")]
+pub fn read_macos_default(domain: &str, key: &str) -> Option<String> {
+    let output = Command::new("defaults")
+        .args(["read", domain, key])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end_matches('\n')
+            .to_string(),
+    )
+}
+
+/// Set `key` in a macOS defaults domain to `value`, or delete it for `None`.
+/// Goes through `defaults` rather than editing the plist so `cfprefsd`'s
+/// cached copy stays coherent. Returns a diff of the change, or `None` when
+/// nothing needed to change.
+#[cfg(target_os = "macos")]
+pub fn update_macos_default(
+    domain: &str,
+    key: &str,
+    value: Option<&str>,
+    dry_run: bool,
+) -> Result<Option<String>, GitAiError> {
+    let current = read_macos_default(domain, key);
+    if current.as_deref() == value {
+        return Ok(None);
+    }
+
+    let entry = |value: &Option<String>| {
+        value
+            .as_ref()
+            .map(|value| format!("{} = {}\n", key, value))
+            .unwrap_or_default()
+    };
+    // An absolute domain is already the plist path, minus its extension
+    let plist = if domain.starts_with('/') {
+        PathBuf::from(format!("{}.plist", domain))
+    } else {
+        home_dir()
+            .join("Library")
+            .join("Preferences")
+            .join(format!("{}.plist", domain))
+    };
+    let diff_output = generate_diff(&plist, &entry(&current), &entry(&value.map(str::to_string)));
+
+    if !dry_run {
+        prefs_backup::record_macos_default(domain, key, current.clone())?;
+        let mut command = Command::new("defaults");
+        match value {
+            Some(value) => command.args(["write", domain, key, "-string", value]),
+            None => command.args(["delete", domain, key]),
+        };
+        let output = command.output()?;
+        if !output.status.success() {
+            return Err(GitAiError::Generic(format!(
+                "defaults failed to update {} {}: {}",
+                domain,
+                key,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+
+    Ok(Some(diff_output))
+}
+
+/// The running macOS release as `(major, minor)`, e.g. `(14, 5)`
+#[cfg(target_os = "macos")]
+pub fn macos_version() -> Option<(u32, u32)> {
+    let output = Command::new("sw_vers")
+        .arg("-productVersion")
+        .output()
+        .ok()?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    // Major-only releases print e.g. "26"
+    parse_version(&version).or_else(|| Some((version.parse().ok()?, 0)))
+}
+
+/// An app bundle's `CFBundleShortVersionString` as `(major, minor)`
+#[cfg(target_os = "macos")]
+pub fn macos_app_version(app: &Path) -> Option<(u32, u32)> {
+    parse_version(&macos_app_version_string(app)?)
+}
+
+/// An app bundle's `CFBundleShortVersionString` as written, for clients
+/// that don't use `major.minor` numbering
+#[cfg(target_os = "macos")]
+pub fn macos_app_version_string(app: &Path) -> Option<String> {
+    let info = app.join("Contents").join("Info");
+    let plist = info.with_extension("plist");
+    cached_discovery(
+        &format!(
+            "macos_app_version:{}:{}",
+            plist.display(),
+            modified_secs(&plist)
+        ),
+        |_| true,
+        || read_macos_default(&info.to_string_lossy(), "CFBundleShortVersionString"),
+    )
+}
+
+/// An executable's `ProductVersion` from its version resource
+#[cfg(windows)]
+pub fn windows_exe_version(exe: &Path) -> Option<String> {
+    cached_discovery(
+        &format!(
+            "windows_exe_version:{}:{}",
+            exe.display(),
+            modified_secs(exe)
+        ),
+        |_| true,
+        || discover_windows_exe_version(exe),
+    )
+}
+
+#[cfg(windows)]
+fn discover_windows_exe_version(exe: &Path) -> Option<String> {
+    let script = format!(
+        "(Get-Item -LiteralPath '{}').VersionInfo.ProductVersion",
+        exe.to_string_lossy().replace('\'', "''")
+    );
+    let output = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+/// Modification time in seconds, keying cached version probes so an update
+/// is noticed before the cache expires
+#[cfg(any(target_os = "macos", windows))]
+fn modified_secs(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// The build number ending a version string, for clients versioned by build
+/// ("Build 2096", "Sublime Merge Build 2096", "1.0.0.2096")
+pub fn parse_build_number(version_str: &str) -> Option<u32> {
+    let trimmed = version_str.trim_end();
+    let digits = trimmed.len() - trimmed.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    trimmed[trimmed.len() - digits..].parse().ok()
+}
+
+/// Whether a GSettings schema is installed, i.e. the app that ships it is
+#[cfg(target_os = "linux")]
+pub fn gsettings_schema_installed(schema: &str) -> bool {
+    let Ok(output) = Command::new("gsettings").arg("list-schemas").output() else {
+        return false;
+    };
+    output.status.success()
+        && String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .any(|line| line.trim() == schema)
+}
+
+/// Registry keys passed to the helpers below are under `HKCU` unless they
+/// start with `HKLM\` (see `InstallScope::registry_key`)
+const HKLM_PREFIX: &str = "HKLM\\";
+
+/// A registry key as shown to users, with its hive
+pub fn registry_display(subkey: &str) -> String {
+    if subkey.starts_with(HKLM_PREFIX) {
+        subkey.to_string()
+    } else {
+        format!("HKCU\\{}", subkey)
+    }
+}
+
+/// The hive a helper's `subkey` lives in, and the path within it
+#[cfg(windows)]
+fn registry_root(subkey: &str) -> (winreg::RegKey, &str) {
+    use winreg::{
+        RegKey,
+        enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE},
+    };
+
+    match subkey.strip_prefix(HKLM_PREFIX) {
+        Some(path) => (RegKey::predef(HKEY_LOCAL_MACHINE), path),
+        None => (RegKey::predef(HKEY_CURRENT_USER), subkey),
+    }
+}
+
+/// Whether the registry key `subkey` exists
+#[cfg(windows)]
+pub fn registry_key_exists(subkey: &str) -> bool {
+    let (root, path) = registry_root(subkey);
+    root.open_subkey(path).is_ok()
+}
+
+/// Read a non-empty string value from `subkey`
+#[cfg(windows)]
+pub fn read_registry_string(subkey: &str, value_name: &str) -> Option<String> {
+    let (root, path) = registry_root(subkey);
+    root.open_subkey(path)
+        .and_then(|key| key.get_value::<String, _>(value_name))
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Write a string value under `subkey`, creating the key if needed
+#[cfg(windows)]
+pub fn write_registry_string(
+    subkey: &str,
+    value_name: &str,
+    value: &str,
+) -> Result<(), GitAiError> {
+    prefs_backup::record_registry_value(
+        subkey,
+        value_name,
+        read_registry_string(subkey, value_name),
+    )?;
+    let (root, path) = registry_root(subkey);
+    let (key, _) = root.create_subkey(path).map_err(|e| {
+        GitAiError::Generic(format!(
+            "Failed to open {}: {}",
+            registry_display(subkey),
+            e
+        ))
+    })?;
+    key.set_value(value_name, &value.to_string()).map_err(|e| {
+        GitAiError::Generic(format!(
+            "Failed to write {}\\{}: {}",
+            registry_display(subkey),
+            value_name,
+            e
+        ))
+    })
+}
+
+/// Delete a value from `subkey`. Missing keys or values are not an error.
+#[cfg(windows)]
+pub fn delete_registry_value(subkey: &str, value_name: &str) -> Result<(), GitAiError> {
+    let (root, path) = registry_root(subkey);
+    let Ok(key) = root.open_subkey_with_flags(path, winreg::enums::KEY_SET_VALUE) else {
+        return Ok(());
+    };
+    prefs_backup::record_registry_value(
+        subkey,
+        value_name,
+        read_registry_string(subkey, value_name),
+    )?;
+    match key.delete_value(value_name) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(GitAiError::Generic(format!(
+            "Failed to delete {}\\{}: {}",
+            registry_display(subkey),
+            value_name,
+            e
+        ))),
+    }
+}
+
+/// Where installers register programs for Apps & Features, under both hives
+#[cfg(windows)]
+const UNINSTALL_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Uninstall";
+
+/// A program registered under a Windows `Uninstall` key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowsProgram {
+    pub display_name: String,
+    /// Install directory, when the entry records one
+    pub install_dir: Option<PathBuf>,
+}
+
+/// Programs named `name` in the Uninstall keys of HKCU and HKLM, in both the
+/// 64-bit and 32-bit registry views. Finds per-user installs and ones in a
+/// custom directory that a Program Files lookup would miss.
+#[cfg(windows)]
+pub fn find_windows_programs(name: &str) -> Vec<WindowsProgram> {
+    use winreg::{
+        RegKey,
+        enums::{
+            HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ, KEY_WOW64_32KEY, KEY_WOW64_64KEY,
+        },
+    };
+
+    let mut programs: Vec<WindowsProgram> = Vec::new();
+    for hive in [HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE] {
+        for view in [KEY_WOW64_64KEY, KEY_WOW64_32KEY] {
+            let Ok(uninstall) =
+                RegKey::predef(hive).open_subkey_with_flags(UNINSTALL_KEY, KEY_READ | view)
+            else {
+                continue;
+            };
+            for entry in uninstall.enum_keys().flatten() {
+                let Ok(key) = uninstall.open_subkey_with_flags(&entry, KEY_READ | view) else {
+                    continue;
+                };
+                let Ok(display_name) = key.get_value::<String, _>("DisplayName") else {
+                    continue;
+                };
+                if !uninstall_name_matches(&display_name, name) {
+                    continue;
+                }
+                let value = |value_name: &str| key.get_value::<String, _>(value_name).ok();
+                let program = WindowsProgram {
+                    display_name,
+                    install_dir: uninstall_install_dir(
+                        value("InstallLocation").as_deref(),
+                        value("DisplayIcon").as_deref(),
+                        value("UninstallString").as_deref(),
+                    ),
+                };
+                // HKCU isn't split into views, so its entries show up twice
+                if !programs.contains(&program) {
+                    programs.push(program);
+                }
+            }
+        }
+    }
+    programs
+}
+
+/// A Windows client as its Uninstall entry and package managers name it
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindowsApp {
+    /// Uninstall entry `DisplayName`, without any version suffix
+    pub display_name: &'static str,
+    /// Scoop app name, e.g. `sublime-merge`
+    pub scoop: Option<&'static str>,
+    /// Chocolatey package id
+    pub chocolatey: Option<&'static str>,
+    /// winget package identifier, e.g. `SublimeHQ.SublimeMerge`
+    pub winget: Option<&'static str>,
+}
+
+/// Directories `app` may be installed in, most authoritative first: its
+/// Uninstall entries, then Scoop's `apps\<name>\current`, Chocolatey's
+/// `lib\<id>` and winget's portable package directories. Cached, since the
+/// Uninstall keys are walked in full.
+#[cfg(windows)]
+pub fn find_windows_install_dirs(app: &WindowsApp) -> Vec<PathBuf> {
+    cached_discovery(
+        &format!("windows_install_dirs:{}", app.display_name),
+        |dirs: &Vec<PathBuf>| dirs.iter().all(|dir| dir.exists()),
+        || discover_windows_install_dirs(app),
+    )
+}
+
+#[cfg(windows)]
+fn discover_windows_install_dirs(app: &WindowsApp) -> Vec<PathBuf> {
+    let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    let program_data = env_dir("ProgramData").unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"));
+
+    let mut dirs: Vec<PathBuf> = find_windows_programs(app.display_name)
+        .into_iter()
+        .filter_map(|program| program.install_dir)
+        .collect();
+    if let Some(name) = app.scoop {
+        let roots = [
+            env_dir("SCOOP").unwrap_or_else(|| home_dir().join("scoop")),
+            env_dir("SCOOP_GLOBAL").unwrap_or_else(|| program_data.join("scoop")),
+        ];
+        dirs.extend(scoop_app_dirs(&roots, name));
+    }
+    if let Some(id) = app.chocolatey {
+        let root = env_dir("ChocolateyInstall").unwrap_or_else(|| program_data.join("chocolatey"));
+        dirs.extend(chocolatey_package_dir(&root, id));
+    }
+    if let Some(id) = app.winget {
+        let roots = [
+            env_dir("LOCALAPPDATA")
+                .map(|local| local.join("Microsoft").join("WinGet").join("Packages")),
+            env_dir("ProgramFiles")
+                .map(|program_files| program_files.join("WinGet").join("Packages")),
+        ];
+        for root in roots.iter().flatten() {
+            dirs.extend(winget_portable_dirs(root, id));
+        }
+    }
+    dirs.dedup();
+    dirs
+}
+
+/// The most authoritative install directory of `app`
+#[cfg(windows)]
+pub fn find_windows_install_dir(app: &WindowsApp) -> Option<PathBuf> {
+    find_windows_install_dirs(app).into_iter().next()
+}
+
+/// Whether `app` is installed: an install directory was found, or, failing
+/// that, `winget list` reports its package (winget also tracks installs it
+/// didn't make)
+#[cfg(windows)]
+pub fn windows_app_installed(app: &WindowsApp) -> bool {
+    if !find_windows_install_dirs(app).is_empty() {
+        return true;
+    }
+    let Some(id) = app.winget else {
+        return false;
+    };
+    cached_discovery(
+        &format!("winget_list:{}", id),
+        |_| true,
+        || winget_lists(id),
+    )
+}
+
+/// Whether `winget list` reports package `id`
+#[cfg(windows)]
+fn winget_lists(id: &str) -> bool {
+    Command::new("winget")
+        .args([
+            "list",
+            "--id",
+            id,
+            "--exact",
+            "--accept-source-agreements",
+            "--disable-interactivity",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| winget_list_contains(&String::from_utf8_lossy(&output.stdout), id))
+}
+
+/// `apps\<name>\current` under each Scoop root where it exists
+#[cfg_attr(not(windows), allow(dead_code))]
+fn scoop_app_dirs(roots: &[PathBuf], name: &str) -> Vec<PathBuf> {
+    roots
+        .iter()
+        .map(|root| root.join("apps").join(name).join("current"))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// A Chocolatey package's directory: `tools`, where portable packages unpack
+/// the app, or the package root for ones that ran an installer
+#[cfg_attr(not(windows), allow(dead_code))]
+fn chocolatey_package_dir(root: &Path, id: &str) -> Option<PathBuf> {
+    let package = root.join("lib").join(id);
+    if !package.is_dir() {
+        return None;
+    }
+    let tools = package.join("tools");
+    Some(if tools.is_dir() { tools } else { package })
+}
+
+/// winget unpacks portable packages into `<id>_<source>` directories
+#[cfg_attr(not(windows), allow(dead_code))]
+fn winget_portable_dirs(packages_dir: &Path, id: &str) -> Vec<PathBuf> {
+    let prefix = format!("{}_", id.to_ascii_lowercase());
+    let Ok(entries) = fs::read_dir(packages_dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .to_ascii_lowercase()
+                .starts_with(&prefix)
+        })
+        .map(|entry| entry.path())
+        .filter(|dir| dir.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Whether `winget list` output has a row for package `id`
+#[cfg_attr(not(windows), allow(dead_code))]
+fn winget_list_contains(output: &str, id: &str) -> bool {
+    output.lines().any(|line| {
+        line.split_whitespace()
+            .any(|column| column.eq_ignore_ascii_case(id))
+    })
+}
+
+/// Whether an Uninstall entry's `DisplayName` is `name`, allowing for the
+/// version or architecture installers append ("TortoiseGit 2.15.0.0 (64 bit)")
+#[cfg_attr(not(windows), allow(dead_code))]
+fn uninstall_name_matches(display_name: &str, name: &str) -> bool {
+    let display_name = display_name.trim();
+    display_name.len() >= name.len()
+        && display_name.is_char_boundary(name.len())
+        && display_name[..name.len()].eq_ignore_ascii_case(name)
+        && display_name[name.len()..]
+            .chars()
+            .next()
+            .is_none_or(|c| c == ' ')
+}
+
+/// An Uninstall entry's install directory: `InstallLocation` when set,
+/// otherwise the directory of the executable in `DisplayIcon` or
+/// `UninstallString`
+#[cfg_attr(not(windows), allow(dead_code))]
+fn uninstall_install_dir(
+    install_location: Option<&str>,
+    display_icon: Option<&str>,
+    uninstall_string: Option<&str>,
+) -> Option<PathBuf> {
+    let location = install_location
+        .map(|location| location.trim().trim_matches('"'))
+        .filter(|location| !location.is_empty());
+    if let Some(location) = location {
+        return Some(PathBuf::from(location.trim_end_matches('\\')));
+    }
+    [display_icon, uninstall_string]
+        .into_iter()
+        .flatten()
+        .find_map(executable_dir)
+}
+
+/// Directory of the executable a registry command or icon value points at:
+/// `"C:\App\app.exe" --uninstall` or `C:\App\app.exe,0`
+#[cfg_attr(not(windows), allow(dead_code))]
+fn executable_dir(value: &str) -> Option<PathBuf> {
+    let value = value.trim();
+    let exe = match value.strip_prefix('"') {
+        Some(rest) => rest.split('"').next()?,
+        None => {
+            let lower = value.to_ascii_lowercase();
+            let end = lower.find(".exe").map(|i| i + 4).unwrap_or(value.len());
+            &value[..end]
+        }
+    };
+    let exe = exe.split(',').next()?.trim();
+    // Not a Path: the separators are Windows ones on every platform here
+    let (dir, _) = exe.rsplit_once('\\')?;
+    (!dir.is_empty()).then(|| PathBuf::from(dir))
+}
+
+/// `%APPDATA%\<app_dir>` as a client sees it. Microsoft Store (MSIX) apps
+/// have their AppData writes redirected into their package's
+/// `%LOCALAPPDATA%\Packages\<family>\LocalCache\Roaming`, and read from
+/// there first, so a client whose directory turns up in a package's
+/// virtualized AppData must have its prefs written there instead.
+#[cfg(windows)]
+pub fn windows_app_data_dir(app_dir: &str) -> Option<PathBuf> {
+    std::env::var_os("LOCALAPPDATA")
+        .and_then(|local| msix_roaming_dir(&PathBuf::from(local).join("Packages"), app_dir))
+        .or_else(|| {
+            std::env::var_os("APPDATA").map(|app_data| PathBuf::from(app_data).join(app_dir))
+        })
+}
+
+/// The virtualized `Roaming\<app_dir>` of whichever MSIX package under
+/// `packages_dir` has one
+#[cfg_attr(not(windows), allow(dead_code))]
+fn msix_roaming_dir(packages_dir: &Path, app_dir: &str) -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = fs::read_dir(packages_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| {
+            entry
+                .path()
+                .join("LocalCache")
+                .join("Roaming")
+                .join(app_dir)
+        })
+        .filter(|dir| dir.is_dir())
+        .collect();
+    // Stable choice if two packages somehow share a directory name
+    candidates.sort();
+    candidates.into_iter().next()
+}
+
+/// Longest a settings edit waits for another git-ai process editing the same file
+const SETTINGS_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many times a settings edit is redone when the file changes under it
+const SETTINGS_EDIT_ATTEMPTS: usize = 3;
+
+/// A settings file as it was read, to notice a write by someone else
+#[derive(Debug, PartialEq, Eq)]
+struct SettingsSnapshot {
+    modified: Option<SystemTime>,
+    content: Option<String>,
+}
+
+impl SettingsSnapshot {
+    fn take(path: &Path) -> Result<Self, GitAiError> {
+        let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => Some(content),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self { modified, content })
+    }
+}
+
+/// Take the advisory lock serializing git-ai's edits of `settings_path`. It
+/// lives in the internal state dir, keyed by path, so nothing is left beside
+/// the client's own files.
+fn lock_settings_file(settings_path: &Path) -> Result<LockFile, GitAiError> {
+    use sha2::{Digest, Sha256};
+
+    let lock_dir = internal_dir_path()
+        .ok_or_else(|| GitAiError::Generic("Could not find the git-ai state directory".into()))?
+        .join("locks");
+    fs::create_dir_all(&lock_dir)?;
+    let digest = Sha256::digest(settings_path.to_string_lossy().as_bytes());
+    let lock_path = lock_dir.join(format!("settings-{:x}.lock", digest));
+    LockFile::acquire(&lock_path, SETTINGS_LOCK_TIMEOUT).ok_or_else(|| {
+        GitAiError::Generic(format!(
+            "Timed out after {}s waiting for another git-ai process to edit {}",
+            SETTINGS_LOCK_TIMEOUT.as_secs(),
+            settings_path.display()
+        ))
+    })
+}
+
+/// Rewrite a client settings file through `edit`, which maps its current
+/// content ("" when missing) to the new content, or `None` to leave it be.
+/// Returns Ok(Some(diff)) if the file changed.
+///
+/// git-ai processes take turns through an advisory lock. The client itself
+/// doesn't, so the file is checked again just before writing; if it changed
+/// since it was read the edit is redone on the new content, and after a few
+/// attempts this gives up rather than overwrite the client's write.
+pub fn edit_settings_file(
+    settings_path: &Path,
+    dry_run: bool,
+    mut edit: impl FnMut(&str) -> Result<Option<String>, GitAiError>,
+) -> Result<Option<String>, GitAiError> {
+    let _lock = if dry_run {
+        None
+    } else {
+        Some(lock_settings_file(settings_path)?)
+    };
+
+    for _ in 0..SETTINGS_EDIT_ATTEMPTS {
+        let before = SettingsSnapshot::take(settings_path)?;
+        let original = before.content.as_deref().unwrap_or_default();
+        let Some(new_content) = edit(original)? else {
+            return Ok(None);
+        };
+        let diff_output = generate_diff(settings_path, original, &new_content);
+        if dry_run {
+            return Ok(Some(diff_output));
+        }
+        if SettingsSnapshot::take(settings_path)? != before {
+            continue;
+        }
+        write_atomic(settings_path, new_content.as_bytes())?;
+        return Ok(Some(diff_output));
+    }
+
+    Err(GitAiError::Generic(format!(
+        "{} kept changing while git-ai was editing it; quit the app that owns it and try again",
+        settings_path.display()
+    )))
+}
+
 /// Update VS Code chat hook settings in a settings.json/jsonc file.
 ///
 /// Ensures `"chat.useHooks"` is set to `true`.
@@ -731,70 +1987,436 @@ pub fn update_vscode_chat_hook_settings(
     settings_path: &Path,
     dry_run: bool,
 ) -> Result<Option<String>, GitAiError> {
-    let original = if settings_path.exists() {
-        fs::read_to_string(settings_path)?
-    } else {
-        String::new()
-    };
+    edit_settings_file(settings_path, dry_run, |original| {
+        let root = parse_jsonc_settings(settings_path, original)?;
+        let object = root.object_value_or_set();
+        let mut changed = false;
+        let mut enable_setting = |key: &str| match object.get(key) {
+            Some(prop) => {
+                let should_update = match prop.value() {
+                    Some(node) => match node.as_boolean_lit() {
+                        Some(bool_node) => !bool_node.value(),
+                        None => true,
+                    },
+                    None => true,
+                };
 
-    let parse_input = if original.trim().is_empty() {
-        "{}".to_string()
+                if should_update {
+                    prop.set_value(jsonc_parser::json!(true));
+                    changed = true;
+                }
+            }
+            None => {
+                object.append(key, jsonc_parser::json!(true));
+                changed = true;
+            }
+        };
+
+        enable_setting("chat.useHooks");
+        enable_setting("github.copilot.chat.otel.dbSpanExporter.enabled");
+
+        Ok(changed.then(|| root.to_string()))
+    })
+}
+
+fn parse_jsonc_settings(settings_path: &Path, content: &str) -> Result<CstRootNode, GitAiError> {
+    let parse_input = if content.trim().is_empty() {
+        "{}"
     } else {
-        original.clone()
+        content
     };
-
-    let parse_options = ParseOptions::default();
-    let root = CstRootNode::parse(&parse_input, &parse_options).map_err(|err| {
+    CstRootNode::parse(parse_input, &ParseOptions::default()).map_err(|err| {
         GitAiError::Generic(format!(
             "Failed to parse {}: {}",
             settings_path.display(),
             err
         ))
-    })?;
+    })
+}
 
-    let object = root.object_value_or_set();
-    let mut changed = false;
-    let mut enable_setting = |key: &str| match object.get(key) {
-        Some(prop) => {
-            let should_update = match prop.value() {
-                Some(node) => match node.as_boolean_lit() {
-                    Some(bool_node) => !bool_node.value(),
-                    None => true,
-                },
-                None => true,
-            };
+/// Read a top-level string setting from a JSONC settings file.
+/// Returns Ok(None) if the file or key is missing or the value isn't a string.
+pub fn read_jsonc_string_setting(
+    settings_path: &Path,
+    key: &str,
+) -> Result<Option<String>, GitAiError> {
+    if !settings_path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(settings_path)?;
+    let root = parse_jsonc_settings(settings_path, &content)?;
+    Ok(root
+        .object_value()
+        .and_then(|object| object.get(key))
+        .and_then(|prop| prop.value())
+        .and_then(|node| node.as_string_lit())
+        .and_then(|lit| lit.decoded_value().ok()))
+}
+
+/// Set (or, with `value: None`, remove) a top-level string setting in a JSONC
+/// settings file, preserving comments and formatting elsewhere.
+/// Returns Ok(Some(diff)) if the file changed, Ok(None) if it was already as requested.
+pub fn update_jsonc_string_setting(
+    settings_path: &Path,
+    key: &str,
+    value: Option<&str>,
+    dry_run: bool,
+) -> Result<Option<String>, GitAiError> {
+    edit_settings_file(settings_path, dry_run, |original| {
+        let root = parse_jsonc_settings(settings_path, original)?;
+        let object = root.object_value_or_set();
+
+        let existing = object.get(key);
+        let current = existing
+            .as_ref()
+            .and_then(|prop| prop.value())
+            .and_then(|node| node.as_string_lit())
+            .and_then(|lit| lit.decoded_value().ok());
+
+        match (existing, value) {
+            (Some(_), Some(value)) if current.as_deref() == Some(value) => return Ok(None),
+            (Some(prop), Some(value)) => prop.set_value(CstInputValue::String(value.to_string())),
+            (None, Some(value)) => {
+                object.append(key, CstInputValue::String(value.to_string()));
+            }
+            (Some(prop), None) => prop.remove(),
+            (None, None) => return Ok(None),
+        }
+
+        Ok(Some(root.to_string()))
+    })
+}
+
+/// `[section]` header name, if `line` is one
+fn ini_section_name(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .map(str::trim)
+}
+
+/// `(key, value)` of a `key=value` line; comments and headers are skipped
+fn ini_entry(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with([';', '#', '[']) {
+        return None;
+    }
+    let (key, value) = trimmed.split_once('=')?;
+    Some((key.trim(), value.trim()))
+}
+
+fn unquote_ini_value(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Read `key` from `[section]` of an INI settings file (as written by
+/// QSettings and most GUI clients). Surrounding double quotes are removed.
+/// Returns Ok(None) if the file, section or key is missing.
+pub fn read_ini_setting(
+    settings_path: &Path,
+    section: &str,
+    key: &str,
+) -> Result<Option<String>, GitAiError> {
+    if !settings_path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(settings_path)?;
+    let mut in_section = false;
+    for line in content.lines() {
+        if let Some(name) = ini_section_name(line) {
+            in_section = name.eq_ignore_ascii_case(section);
+        } else if in_section
+            && let Some((entry_key, value)) = ini_entry(line)
+            && entry_key == key
+        {
+            return Ok(Some(unquote_ini_value(value).to_string()));
+        }
+    }
+    Ok(None)
+}
+
+/// Set (or, with `value: None`, remove) `key` in `[section]` of an INI
+/// settings file, adding the section if needed and leaving every other line
+/// untouched. Returns Ok(Some(diff)) if the file changed, Ok(None) if it was
+/// already as requested.
+pub fn update_ini_setting(
+    settings_path: &Path,
+    section: &str,
+    key: &str,
+    value: Option<&str>,
+    dry_run: bool,
+) -> Result<Option<String>, GitAiError> {
+    edit_settings_file(settings_path, dry_run, |original| {
+        let newline = if original.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+
+        let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+        let mut section_range: Option<(usize, usize)> = None;
+        for (index, line) in lines.iter().enumerate() {
+            match (ini_section_name(line), section_range) {
+                (Some(name), None) if name.eq_ignore_ascii_case(section) => {
+                    section_range = Some((index, lines.len()));
+                }
+                (Some(_), Some((start, end))) if end == lines.len() => {
+                    section_range = Some((start, index));
+                }
+                _ => {}
+            }
+        }
+        let existing = section_range.and_then(|(start, end)| {
+            (start + 1..end).find(|&index| ini_entry(&lines[index]).is_some_and(|(k, _)| k == key))
+        });
+        let entry = value.map(|value| format!("{}={}", key, value));
+
+        match (existing, entry, section_range) {
+            (Some(index), Some(entry), _) => {
+                if ini_entry(&lines[index]).map(|(_, v)| unquote_ini_value(v)) == value {
+                    return Ok(None);
+                }
+                lines[index] = entry;
+            }
+            (Some(index), None, _) => {
+                lines.remove(index);
+            }
+            (None, Some(entry), Some((start, end))) => {
+                // After the section's last non-blank line, before any spacing
+                let insert_at = (start + 1..end)
+                    .rev()
+                    .find(|&index| !lines[index].trim().is_empty())
+                    .map_or(start + 1, |index| index + 1);
+                lines.insert(insert_at, entry);
+            }
+            (None, Some(entry), None) => {
+                if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(format!("[{}]", section));
+                lines.push(entry);
+            }
+            (None, None, _) => return Ok(None),
+        }
+
+        let mut new_content = lines.join(newline);
+        new_content.push_str(newline);
+        Ok(Some(new_content))
+    })
+}
+
+/// A `key: value` line of a block-style YAML mapping
+struct YamlEntry {
+    line: usize,
+    indent: usize,
+    /// Keys from the document root down to this one
+    path: Vec<String>,
+    /// The scalar on the same line; `None` for a nested mapping's header
+    value: Option<String>,
+}
 
-            if should_update {
-                prop.set_value(jsonc_parser::json!(true));
-                changed = true;
-            }
+/// `key: value` entries of a block-style YAML document. Flow style, anchors
+/// and multi-line scalars aren't understood; list items are skipped.
+fn yaml_entries(lines: &[String]) -> Vec<YamlEntry> {
+    let mut entries: Vec<YamlEntry> = Vec::new();
+    let mut parents: Vec<(usize, String)> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with(['#', '-']) || trimmed.starts_with("...") {
+            continue;
         }
-        None => {
-            object.append(key, jsonc_parser::json!(true));
-            changed = true;
+        let indent = line.len() - trimmed.len();
+        let Some((key, rest)) = trimmed
+            .split_once(": ")
+            .or_else(|| trimmed.strip_suffix(':').map(|key| (key, "")))
+        else {
+            continue;
+        };
+        while parents.last().is_some_and(|(depth, _)| *depth >= indent) {
+            parents.pop();
         }
-    };
+        let key = unquote_yaml_scalar(key.trim());
+        let mut path: Vec<String> = parents.iter().map(|(_, key)| key.clone()).collect();
+        path.push(key.clone());
+        let rest = rest.trim();
+        let value = if rest.is_empty() || rest.starts_with('#') {
+            parents.push((indent, key));
+            None
+        } else {
+            Some(unquote_yaml_scalar(rest))
+        };
+        entries.push(YamlEntry {
+            line: index,
+            indent,
+            path,
+            value,
+        });
+    }
+    entries
+}
+
+fn unquote_yaml_scalar(raw: &str) -> String {
+    if let Some(inner) = raw
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+    {
+        inner.replace("''", "'")
+    } else if let Some(inner) = raw
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        inner.replace("\\\"", "\"").replace("\\\\", "\\")
+    } else {
+        // A plain scalar ends at a comment
+        raw.split(" #").next().unwrap_or(raw).trim_end().to_string()
+    }
+}
 
-    enable_setting("chat.useHooks");
-    enable_setting("github.copilot.chat.otel.dbSpanExporter.enabled");
+/// The mapping on the way to `path` (or at it) that is written in flow style,
+/// e.g. `os` in `os: {editPreset: vim}`, or `"the document"` when the whole
+/// file is one. Editing those line by line would duplicate keys.
+fn yaml_flow_collection(lines: &[String], entries: &[YamlEntry], path: &[&str]) -> Option<String> {
+    let is_flow = |value: &str| value.starts_with(['{', '[']);
+    let document_is_flow = lines
+        .iter()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty() && !line.starts_with('#') && *line != "---")
+        .is_some_and(is_flow);
+    if document_is_flow {
+        return Some("the document".to_string());
+    }
+    entries
+        .iter()
+        .find(|entry| {
+            entry.path.len() <= path.len()
+                && entry.path.iter().zip(path).all(|(key, part)| key == part)
+                && entry.value.as_deref().is_some_and(is_flow)
+        })
+        .map(|entry| entry.path.join("."))
+}
 
-    if !changed {
+/// Read the scalar at a dotted `key_path` (e.g. `git.path`) of a YAML
+/// settings file. Returns Ok(None) if the file or key is missing.
+pub fn read_yaml_setting(
+    settings_path: &Path,
+    key_path: &str,
+) -> Result<Option<String>, GitAiError> {
+    if !settings_path.exists() {
         return Ok(None);
     }
+    let content = fs::read_to_string(settings_path)?;
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let path: Vec<&str> = key_path.split('.').collect();
+    Ok(yaml_entries(&lines)
+        .into_iter()
+        .find(|entry| entry.path == path)
+        .and_then(|entry| entry.value))
+}
 
-    let new_content = root.to_string();
-    let diff_output = generate_diff(settings_path, &original, &new_content);
+/// Set (or, with `value: None`, remove) the scalar at a dotted `key_path` of
+/// a block-style YAML settings file, creating missing parent mappings and
+/// leaving every other line untouched. Values are written single-quoted, so
+/// Windows paths need no escaping. A flow-style (`{...}`) mapping on the way
+/// is an error rather than something to rewrite. Returns Ok(Some(diff)) if
+/// the file changed, Ok(None) if it was already as requested.
+pub fn update_yaml_setting(
+    settings_path: &Path,
+    key_path: &str,
+    value: Option<&str>,
+    dry_run: bool,
+) -> Result<Option<String>, GitAiError> {
+    edit_settings_file(settings_path, dry_run, |original| {
+        let newline = if original.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let path: Vec<&str> = key_path.split('.').collect();
+        let quoted = value.map(|value| format!("'{}'", value.replace('\'', "''")));
+
+        let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+        let entries = yaml_entries(&lines);
+        if let Some(flow) = yaml_flow_collection(&lines, &entries, &path) {
+            return Err(GitAiError::Generic(format!(
+                "{} in {} is written in flow style ({{...}} or [...]), which git-ai can't edit; set {} by hand",
+                flow,
+                settings_path.display(),
+                key_path
+            )));
+        }
+        let existing = entries.iter().find(|entry| entry.path == path);
 
-    if !dry_run {
-        if let Some(parent) = settings_path.parent()
-            && !parent.exists()
-        {
-            fs::create_dir_all(parent)?;
+        match (existing, &quoted) {
+            (Some(entry), Some(_)) if entry.value.as_deref() == value => return Ok(None),
+            (Some(entry), Some(quoted)) => {
+                let key = path.last().copied().unwrap_or_default();
+                lines[entry.line] = format!("{}{}: {}", " ".repeat(entry.indent), key, quoted);
+            }
+            (Some(entry), None) => {
+                if entry.value.is_none() {
+                    return Err(GitAiError::Generic(format!(
+                        "{} in {} is a mapping, not a value",
+                        key_path,
+                        settings_path.display()
+                    )));
+                }
+                lines.remove(entry.line);
+            }
+            (None, None) => return Ok(None),
+            (None, Some(quoted)) => {
+                // The deepest mapping on the path that already exists
+                let (depth, parent) = (0..path.len())
+                    .rev()
+                    .find_map(|depth| {
+                        let parent = entries
+                            .iter()
+                            .find(|entry| entry.value.is_none() && entry.path == path[..depth]);
+                        match (depth, parent) {
+                            (0, _) => Some((0, None)),
+                            (_, Some(parent)) => Some((depth, Some(parent))),
+                            _ => None,
+                        }
+                    })
+                    .unwrap_or((0, None));
+                let (start, end, child_indent) = match parent {
+                    Some(parent) => {
+                        let end = entries
+                            .iter()
+                            .find(|entry| entry.line > parent.line && entry.indent <= parent.indent)
+                            .map_or(lines.len(), |entry| entry.line);
+                        let child_indent = entries
+                            .iter()
+                            .find(|entry| entry.line > parent.line && entry.line < end)
+                            .map_or(parent.indent + 2, |entry| entry.indent);
+                        (parent.line + 1, end, child_indent)
+                    }
+                    None => (0, lines.len(), 0),
+                };
+                // After the mapping's last content line, before any spacing
+                let insert_at = (start..end)
+                    .rev()
+                    .find(|&index| !lines[index].trim().is_empty())
+                    .map_or(start, |index| index + 1);
+                let mut new_lines = Vec::new();
+                for (offset, key) in path[depth..].iter().enumerate() {
+                    let indent = " ".repeat(child_indent + offset * 2);
+                    if depth + offset + 1 == path.len() {
+                        new_lines.push(format!("{}{}: {}", indent, key, quoted));
+                    } else {
+                        new_lines.push(format!("{}{}:", indent, key));
+                    }
+                }
+                lines.splice(insert_at..insert_at, new_lines);
+            }
         }
-        write_atomic(settings_path, new_content.as_bytes())?;
-    }
 
-    Ok(Some(diff_output))
+        let mut new_content = lines.join(newline);
+        new_content.push_str(newline);
+        Ok(Some(new_content))
+    })
 }
 
 #[cfg(test)]
@@ -824,6 +2446,15 @@ mod tests {
         assert_eq!(parse_version(""), None);
     }
 
+    #[test]
+    fn test_parse_build_number() {
+        assert_eq!(parse_build_number("Build 2096"), Some(2096));
+        assert_eq!(parse_build_number("Sublime Merge Build 2096\n"), Some(2096));
+        assert_eq!(parse_build_number("1.0.0.2096"), Some(2096));
+        assert_eq!(parse_build_number("Build"), None);
+        assert_eq!(parse_build_number(""), None);
+    }
+
     #[test]
     fn test_version_meets_requirement() {
         // Test exact match
@@ -881,6 +2512,34 @@ mod tests {
         assert!(!version_meets_requirement(old_claude, MIN_CLAUDE_VERSION));
     }
 
+    #[test]
+    fn test_generate_diff_is_a_unified_diff() {
+        let old: String = (1..=12).map(|n| format!("line {}\n", n)).collect();
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 11\n", "line 11\nline 11.5\n");
+        let path = Path::new("/home/dev/.config/app/settings.ini");
+        assert_eq!(
+            generate_diff(path, &old, &new),
+            "--- a/home/dev/.config/app/settings.ini\n\
+             +++ b/home/dev/.config/app/settings.ini\n\
+             @@ -1,5 +1,5 @@\n line 1\n-line 2\n+line two\n line 3\n line 4\n line 5\n\
+             @@ -9,4 +9,5 @@\n line 9\n line 10\n line 11\n+line 11.5\n line 12\n"
+        );
+        assert_eq!(generate_diff(path, &old, &old), "");
+
+        assert_eq!(
+            generate_diff(path, "", "a = 1"),
+            "--- /dev/null\n\
+             +++ b/home/dev/.config/app/settings.ini\n\
+             @@ -0,0 +1 @@\n+a = 1\n\\ No newline at end of file\n"
+        );
+        assert!(
+            generate_diff(Path::new(r"C:\Users\dev\settings.json"), "{}\n", "")
+                .starts_with("--- a/C:/Users/dev/settings.json\n+++ /dev/null\n@@ -1 +0,0 @@\n")
+        );
+    }
+
     #[test]
     fn test_is_git_ai_checkpoint_command() {
         assert!(is_git_ai_checkpoint_command("git-ai checkpoint"));
@@ -978,6 +2637,273 @@ mod tests {
         assert_eq!(final_content, initial);
     }
 
+    #[test]
+    fn test_update_jsonc_string_setting_sets_and_preserves_comments() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        let initial = r#"{
+    // editor prefs
+    "editor.fontSize": 13,
+}
+"#;
+        fs::write(&settings_path, initial).unwrap();
+
+        let diff = update_jsonc_string_setting(&settings_path, "git.path", Some("/bin/git"), true)
+            .unwrap();
+        assert!(diff.unwrap().contains("+    \"git.path\": \"/bin/git\""));
+        assert_eq!(fs::read_to_string(&settings_path).unwrap(), initial);
+
+        update_jsonc_string_setting(&settings_path, "git.path", Some("/bin/git"), false).unwrap();
+        let final_content = fs::read_to_string(&settings_path).unwrap();
+        assert!(final_content.contains("// editor prefs"));
+        assert_eq!(
+            read_jsonc_string_setting(&settings_path, "git.path").unwrap(),
+            Some("/bin/git".to_string())
+        );
+        assert!(
+            update_jsonc_string_setting(&settings_path, "git.path", Some("/bin/git"), false)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_update_jsonc_string_setting_removes_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        fs::write(
+            &settings_path,
+            "{\n    \"git.path\": \"/bin/git\",\n    \"editor.fontSize\": 13\n}\n",
+        )
+        .unwrap();
+
+        assert!(
+            update_jsonc_string_setting(&settings_path, "git.path", None, false)
+                .unwrap()
+                .is_some()
+        );
+        let final_content = fs::read_to_string(&settings_path).unwrap();
+        assert!(!final_content.contains("git.path"));
+        assert!(final_content.contains("\"editor.fontSize\": 13"));
+        assert!(
+            update_jsonc_string_setting(&settings_path, "git.path", None, false)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_edit_settings_file_redoes_edit_after_concurrent_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        fs::write(&settings_path, "{\"theme\": \"dark\"}").unwrap();
+
+        // The client saves its own change while we're computing ours
+        let mut calls = 0;
+        let diff = edit_settings_file(&settings_path, false, |original| {
+            calls += 1;
+            if calls == 1 {
+                fs::write(&settings_path, "{\"theme\": \"light\"}").unwrap();
+            }
+            Ok(Some(
+                original.replace('}', ", \"git.path\": \"/shim/git\"}"),
+            ))
+        })
+        .unwrap();
+
+        assert_eq!(calls, 2);
+        assert!(diff.is_some());
+        assert_eq!(
+            fs::read_to_string(&settings_path).unwrap(),
+            "{\"theme\": \"light\", \"git.path\": \"/shim/git\"}"
+        );
+    }
+
+    #[test]
+    fn test_edit_settings_file_gives_up_when_file_keeps_changing() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        fs::write(&settings_path, "{}").unwrap();
+
+        let mut calls = 0;
+        let err = edit_settings_file(&settings_path, false, |original| {
+            calls += 1;
+            fs::write(&settings_path, format!("{{\"saves\": {}}}", calls)).unwrap();
+            Ok(Some(format!("{}\n", original)))
+        })
+        .unwrap_err();
+
+        assert_eq!(calls, SETTINGS_EDIT_ATTEMPTS);
+        assert!(err.to_string().contains("kept changing"));
+        assert_eq!(
+            fs::read_to_string(&settings_path).unwrap(),
+            format!("{{\"saves\": {}}}", SETTINGS_EDIT_ATTEMPTS)
+        );
+    }
+
+    #[test]
+    fn test_read_jsonc_string_setting_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        assert_eq!(
+            read_jsonc_string_setting(&settings_path, "git.path").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_update_ini_setting_replaces_inserts_and_removes_in_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("App.ini");
+        fs::write(
+            &settings_path,
+            "; app settings\n[General]\nGitCommand=/usr/bin/other\n\n[Global]\nTheme=dark\n\n[Remote]\nName=x\n",
+        )
+        .unwrap();
+
+        let shim = "/home/dev/.git-ai/bin/git";
+        assert!(
+            update_ini_setting(&settings_path, "Global", "GitCommand", Some(shim), false)
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            fs::read_to_string(&settings_path).unwrap(),
+            format!(
+                "; app settings\n[General]\nGitCommand=/usr/bin/other\n\n[Global]\nTheme=dark\nGitCommand={}\n\n[Remote]\nName=x\n",
+                shim
+            )
+        );
+        assert_eq!(
+            read_ini_setting(&settings_path, "Global", "GitCommand")
+                .unwrap()
+                .as_deref(),
+            Some(shim)
+        );
+        assert!(
+            update_ini_setting(&settings_path, "Global", "GitCommand", Some(shim), false)
+                .unwrap()
+                .is_none()
+        );
+
+        update_ini_setting(&settings_path, "Global", "GitCommand", None, false).unwrap();
+        assert_eq!(
+            read_ini_setting(&settings_path, "Global", "GitCommand").unwrap(),
+            None
+        );
+        assert_eq!(
+            read_ini_setting(&settings_path, "General", "GitCommand")
+                .unwrap()
+                .as_deref(),
+            Some("/usr/bin/other")
+        );
+    }
+
+    #[test]
+    fn test_update_ini_setting_creates_missing_section_and_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("nested").join("App.ini");
+
+        update_ini_setting(&settings_path, "Global", "GitCommand", Some("git"), false).unwrap();
+        assert_eq!(
+            fs::read_to_string(&settings_path).unwrap(),
+            "[Global]\nGitCommand=git\n"
+        );
+        fs::write(
+            &settings_path,
+            "[Global]\r\nGitCommand=\"C:/Program Files/Git/bin/git.exe\"\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            read_ini_setting(&settings_path, "Global", "GitCommand")
+                .unwrap()
+                .as_deref(),
+            Some("C:/Program Files/Git/bin/git.exe")
+        );
+        update_ini_setting(&settings_path, "Global", "GitCommand", Some("git"), false).unwrap();
+        assert_eq!(
+            fs::read_to_string(&settings_path).unwrap(),
+            "[Global]\r\nGitCommand=git\r\n"
+        );
+    }
+
+    #[test]
+    fn test_update_yaml_setting_sets_nested_keys_and_removes_them() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("config.yml");
+        fs::write(
+            &settings_path,
+            "gui:\n  theme: dark # keep\ngit:\n  paging:\n    colorArg: always\n\nos:\n  editor: vim\n",
+        )
+        .unwrap();
+
+        let shim = "C:\\Users\\dev's\\git.exe";
+        update_yaml_setting(&settings_path, "git.path", Some(shim), false)
+            .unwrap()
+            .expect("key added");
+        assert_eq!(
+            fs::read_to_string(&settings_path).unwrap(),
+            "gui:\n  theme: dark # keep\ngit:\n  paging:\n    colorArg: always\n  path: 'C:\\Users\\dev''s\\git.exe'\n\nos:\n  editor: vim\n"
+        );
+        assert_eq!(
+            read_yaml_setting(&settings_path, "git.path")
+                .unwrap()
+                .as_deref(),
+            Some(shim)
+        );
+        assert_eq!(
+            read_yaml_setting(&settings_path, "gui.theme")
+                .unwrap()
+                .as_deref(),
+            Some("dark")
+        );
+        assert!(
+            update_yaml_setting(&settings_path, "git.path", Some(shim), false)
+                .unwrap()
+                .is_none()
+        );
+
+        update_yaml_setting(&settings_path, "tools.git.binary", Some("git"), false).unwrap();
+        assert!(
+            fs::read_to_string(&settings_path)
+                .unwrap()
+                .ends_with("  editor: vim\ntools:\n  git:\n    binary: 'git'\n")
+        );
+
+        update_yaml_setting(&settings_path, "git.path", None, false).unwrap();
+        assert_eq!(read_yaml_setting(&settings_path, "git.path").unwrap(), None);
+        assert!(
+            update_yaml_setting(&settings_path, "git", None, false).is_err(),
+            "a mapping isn't removed as a value"
+        );
+    }
+
+    #[test]
+    fn test_update_yaml_setting_refuses_flow_style_mappings() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("config.yml");
+        let original = "gui:\n  theme: dark\nos: {editPreset: vim}\n";
+        fs::write(&settings_path, original).unwrap();
+
+        let err = update_yaml_setting(&settings_path, "os.gitBinary", Some("git"), false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("os in "), "{}", err);
+        assert!(update_yaml_setting(&settings_path, "os", Some("git"), false).is_err());
+        assert_eq!(fs::read_to_string(&settings_path).unwrap(), original);
+
+        // Siblings of a flow mapping are still block style
+        update_yaml_setting(&settings_path, "gui.theme", Some("light"), false)
+            .unwrap()
+            .expect("sibling updated");
+
+        fs::write(&settings_path, "# lazygit\n{os: {editPreset: vim}}\n").unwrap();
+        let err = update_yaml_setting(&settings_path, "git.path", Some("git"), false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("the document in "), "{}", err);
+    }
+
     #[test]
     fn test_update_vscode_chat_hook_settings_adds_use_hooks_to_empty() {
         let temp_dir = TempDir::new().unwrap();
@@ -1511,4 +3437,274 @@ mod tests {
         let path = Path::new("standalone_file.txt");
         ensure_parent_dir(path).unwrap();
     }
+
+    #[test]
+    fn test_git_shim_path_is_next_to_binary() {
+        let binary = Path::new("/opt/git-ai/bin/git-ai");
+        let shim = git_shim_path(binary);
+        assert_eq!(shim.parent(), Some(Path::new("/opt/git-ai/bin")));
+        assert!(shim.file_stem().is_some_and(|stem| stem == "git"));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_ensure_git_shim_creates_symlink_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let binary = temp_dir.path().join("git-ai");
+        fs::write(&binary, "").unwrap();
+
+        assert!(ensure_git_shim(&binary).unwrap());
+        let shim = git_shim_path(&binary);
+        assert_eq!(fs::read_link(&shim).unwrap(), binary);
+
+        assert!(!ensure_git_shim(&binary).unwrap());
+        assert_eq!(
+            installed_shim_strategy(&binary),
+            Some(ShimStrategy::Symlink)
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_remove_git_shim_leaves_the_binary() {
+        let temp_dir = TempDir::new().unwrap();
+        let binary = temp_dir.path().join("git-ai");
+        fs::write(&binary, "").unwrap();
+        ensure_git_shim(&binary).unwrap();
+
+        assert!(remove_git_shim(&binary, true).unwrap());
+        assert!(installed_shim_strategy(&binary).is_some());
+        assert!(remove_git_shim(&binary, false).unwrap());
+        assert_eq!(installed_shim_strategy(&binary), None);
+        assert!(binary.exists());
+        assert!(!remove_git_shim(&binary, false).unwrap());
+    }
+
+    #[test]
+    fn test_ensure_git_shim_refreshes_stale_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        let binary = temp_dir.path().join("git-ai");
+        fs::write(&binary, "git-ai v2").unwrap();
+        // Left behind by an older version on a filesystem without symlinks
+        let shim = git_shim_path(&binary);
+        fs::write(&shim, "v1").unwrap();
+        assert_eq!(installed_shim_strategy(&binary), Some(ShimStrategy::Copy));
+
+        assert!(ensure_git_shim(&binary).unwrap());
+        assert_eq!(fs::read_to_string(&shim).unwrap(), "git-ai v2");
+        assert!(!ensure_git_shim(&binary).unwrap());
+    }
+
+    #[test]
+    fn test_shim_strategy_rejects_missing_install_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let err = shim_strategy_for_dir(&temp_dir.path().join("missing")).unwrap_err();
+        assert!(err.to_string().contains("is not writable"));
+    }
+
+    #[test]
+    fn test_linux_config_homes_include_installed_sandboxes() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = temp_dir.path();
+        let packaging = LinuxPackaging {
+            flatpak_id: Some("com.example.Client"),
+            snap_name: Some("example-client"),
+        };
+
+        let native_only = linux_config_homes_in(home, None, packaging);
+        assert_eq!(
+            native_only,
+            vec![LinuxConfigHome {
+                dir: home.join(".config"),
+                flatpak_id: None,
+            }]
+        );
+
+        fs::create_dir_all(home.join(".var/app/com.example.Client")).unwrap();
+        fs::create_dir_all(home.join("snap/example-client")).unwrap();
+        let homes = linux_config_homes_in(home, Some(home.join("xdg")), packaging);
+        assert_eq!(
+            homes,
+            vec![
+                LinuxConfigHome {
+                    dir: home.join("xdg"),
+                    flatpak_id: None,
+                },
+                LinuxConfigHome {
+                    dir: home.join(".var/app/com.example.Client/config"),
+                    flatpak_id: Some("com.example.Client"),
+                },
+                LinuxConfigHome {
+                    dir: home.join("snap/example-client/current/.config"),
+                    flatpak_id: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flatpak_filesystems_follow_permissions_and_overrides() {
+        let metadata = "[Application]\nname=com.example.Client\n\n[Context]\nshared=network;ipc;\nfilesystems=xdg-documents;~/Projects:create;\n";
+        let mut entries = flatpak_filesystems(metadata);
+        assert_eq!(entries, vec!["xdg-documents", "~/Projects:create"]);
+
+        let home = Path::new("/home/dev");
+        let shim = Path::new("/home/dev/.git-ai/bin/git");
+        assert!(!flatpak_filesystems_allow(&entries, shim, home));
+        assert!(flatpak_filesystems_allow(
+            &entries,
+            Path::new("/home/dev/Projects/app"),
+            home
+        ));
+
+        entries.extend(flatpak_filesystems(
+            "[Context]\nfilesystems=~/.git-ai:ro;\n",
+        ));
+        assert!(flatpak_filesystems_allow(&entries, shim, home));
+
+        // A later negation takes the access away again
+        let revoked = vec!["home".to_string(), "!home".to_string()];
+        assert!(!flatpak_filesystems_allow(&revoked, shim, home));
+        assert!(flatpak_filesystems_allow(&["host".to_string()], shim, home));
+    }
+
+    #[test]
+    fn test_uninstall_entries_name_and_locate_programs() {
+        assert!(uninstall_name_matches("Fork", "Fork"));
+        assert!(uninstall_name_matches(
+            "TortoiseGit 2.15.0.0 (64 bit)",
+            "TortoiseGit"
+        ));
+        assert!(uninstall_name_matches("sublime merge", "Sublime Merge"));
+        assert!(!uninstall_name_matches("ForkLift", "Fork"));
+        assert!(!uninstall_name_matches("For", "Fork"));
+
+        assert_eq!(
+            uninstall_install_dir(Some(r"D:\Tools\Sublime Merge\"), None, None),
+            Some(PathBuf::from(r"D:\Tools\Sublime Merge"))
+        );
+        assert_eq!(
+            uninstall_install_dir(
+                Some(""),
+                Some(r"C:\Users\dev\AppData\Local\Fork\app.ico"),
+                None
+            ),
+            Some(PathBuf::from(r"C:\Users\dev\AppData\Local\Fork"))
+        );
+        assert_eq!(
+            uninstall_install_dir(
+                None,
+                None,
+                Some(r#""C:\Program Files\GitAhead\Uninstall.exe" /S"#)
+            ),
+            Some(PathBuf::from(r"C:\Program Files\GitAhead"))
+        );
+        assert_eq!(
+            uninstall_install_dir(
+                None,
+                Some(r"C:\Program Files\TortoiseGit\bin\TortoiseGitProc.exe,0"),
+                None
+            ),
+            Some(PathBuf::from(r"C:\Program Files\TortoiseGit\bin"))
+        );
+        assert_eq!(uninstall_install_dir(None, Some("app.ico"), None), None);
+    }
+
+    #[test]
+    fn test_msix_roaming_dir_finds_virtualized_app_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let packages = temp_dir.path().join("Packages");
+        fs::create_dir_all(
+            packages.join("Microsoft.WindowsTerminal_8wekyb3d8bbwe/LocalCache/Roaming/Other"),
+        )
+        .unwrap();
+        assert_eq!(msix_roaming_dir(&packages, "GitExtensions"), None);
+        assert_eq!(
+            msix_roaming_dir(&temp_dir.path().join("missing"), "GitExtensions"),
+            None
+        );
+
+        let virtualized = packages
+            .join("GitExtensions.GitExtensions_a1b2c3d4e5f6g/LocalCache/Roaming/GitExtensions");
+        fs::create_dir_all(&virtualized).unwrap();
+        assert_eq!(
+            msix_roaming_dir(&packages, "GitExtensions"),
+            Some(virtualized)
+        );
+    }
+
+    #[test]
+    fn test_cask_appdir_and_candidates() {
+        let home = Path::new("/Users/dev");
+        assert_eq!(
+            cask_appdir("--no-quarantine --appdir=~/Apps", home),
+            Some(PathBuf::from("/Users/dev/Apps"))
+        );
+        assert_eq!(
+            cask_appdir("--appdir \"/Volumes/Tools/Applications\"", home),
+            Some(PathBuf::from("/Volumes/Tools/Applications"))
+        );
+        assert_eq!(cask_appdir("--no-quarantine", home), None);
+
+        let temp_dir = TempDir::new().unwrap();
+        let caskrooms = [temp_dir.path().join("Caskroom")];
+        let caskroom = &caskrooms[0];
+        let appdir = temp_dir.path().join("Apps");
+        assert!(cask_app_candidates(&caskrooms, "fork", "Fork.app", Some(&appdir)).is_empty());
+
+        fs::create_dir_all(caskroom.join("fork/2.52/Fork.app")).unwrap();
+        fs::create_dir_all(caskroom.join("fork/.metadata")).unwrap();
+        assert_eq!(
+            cask_app_candidates(&caskrooms, "fork", "Fork.app", Some(&appdir)),
+            vec![appdir.join("Fork.app"), caskroom.join("fork/2.52/Fork.app")]
+        );
+    }
+
+    #[test]
+    fn test_package_manager_install_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let scoop = root.join("scoop");
+        fs::create_dir_all(scoop.join("apps/sublime-merge/current")).unwrap();
+        assert_eq!(
+            scoop_app_dirs(&[scoop.clone(), root.join("global")], "sublime-merge"),
+            vec![scoop.join("apps/sublime-merge/current")]
+        );
+        assert!(scoop_app_dirs(&[scoop], "fork").is_empty());
+
+        let choco = root.join("chocolatey");
+        fs::create_dir_all(choco.join("lib/sublimemerge")).unwrap();
+        assert_eq!(
+            chocolatey_package_dir(&choco, "sublimemerge"),
+            Some(choco.join("lib/sublimemerge"))
+        );
+        fs::create_dir_all(choco.join("lib/sublimemerge/tools")).unwrap();
+        assert_eq!(
+            chocolatey_package_dir(&choco, "sublimemerge"),
+            Some(choco.join("lib/sublimemerge/tools"))
+        );
+        assert_eq!(chocolatey_package_dir(&choco, "tortoisegit"), None);
+
+        let winget = root.join("WinGet/Packages");
+        let portable = winget.join("SublimeHQ.SublimeMerge_Microsoft.Winget.Source_8wekyb3d8bbwe");
+        fs::create_dir_all(&portable).unwrap();
+        fs::create_dir_all(
+            winget.join("SublimeHQ.SublimeText.4_Microsoft.Winget.Source_8wekyb3d8bbwe"),
+        )
+        .unwrap();
+        assert_eq!(
+            winget_portable_dirs(&winget, "SublimeHQ.SublimeMerge"),
+            vec![portable]
+        );
+
+        let listed = "Name           Id                     Version Source\n\
+                      ---------------------------------------------------\n\
+                      Sublime Merge  SublimeHQ.SublimeMerge 2102    winget\n";
+        assert!(winget_list_contains(listed, "SublimeHQ.SublimeMerge"));
+        assert!(!winget_list_contains(
+            "No installed package found matching input criteria.",
+            "SublimeHQ.SublimeMerge"
+        ));
+    }
 }
@@ -4,6 +4,7 @@
 //! still pending upload; delivered rows are retained as the local history.
 //! Server handles idempotency.
 
+use crate::app_context::AppContext;
 use crate::error::GitAiError;
 use crate::metrics::attrs::attr_pos;
 use crate::metrics::events::{checkpoint_pos, otel_trace_pos, session_event_pos};
@@ -278,9 +279,7 @@ impl MetricsDatabase {
             return Ok(PathBuf::from(test_path));
         }
 
-        let home = dirs::home_dir()
-            .ok_or_else(|| GitAiError::Generic("Could not determine home directory".to_string()))?;
-        Ok(home.join(".git-ai").join("internal").join("metrics-db"))
+        Ok(AppContext::current().state_dir.join("metrics-db"))
     }
 
     /// Initialize schema and handle migrations
@@ -994,6 +993,51 @@ impl MetricsDatabase {
         Ok(records)
     }
 
+    /// Delete every metric row (delivered or pending) whose author attribute
+    /// satisfies `matches`. Returns the number of matching rows; with `dry_run`
+    /// nothing is deleted.
+    pub fn delete_events_by_author(
+        &mut self,
+        matches: impl Fn(&str) -> bool,
+        dry_run: bool,
+    ) -> Result<usize, GitAiError> {
+        let ids = {
+            let mut stmt = self.conn.prepare("SELECT id, event_json FROM metrics")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            let mut ids = Vec::new();
+            for row in rows {
+                let (id, event_json) = row?;
+                let Ok(event) = serde_json::from_str::<MetricEvent>(&event_json) else {
+                    continue;
+                };
+                if sparse_get_string(&event.attrs, attr_pos::AUTHOR)
+                    .flatten()
+                    .is_some_and(|author| matches(&author))
+                {
+                    ids.push(id);
+                }
+            }
+            ids
+        };
+
+        if dry_run || ids.is_empty() {
+            return Ok(ids.len());
+        }
+
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare("DELETE FROM metrics WHERE id = ?1")?;
+            for id in &ids {
+                stmt.execute(params![id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(ids.len())
+    }
+
     pub(crate) fn session_event_candidates_near_timestamps(
         &self,
         timestamps_ns: &[u128],
@@ -2822,6 +2866,35 @@ mod tests {
         assert_eq!(db.count().unwrap(), 3);
     }
 
+    #[test]
+    fn test_delete_events_by_author_removes_delivered_and_pending_rows() {
+        let (mut db, _temp_dir) = create_test_db();
+        let ts = days_ago(1);
+        let by = |author: &str| format!(r#"{{"t":{ts},"e":1,"v":{{}},"a":{{"2":"{author}"}}}}"#);
+
+        db.insert_events_with_delivered_ts(&[by("Alice <alice@example.com>")], Some(unix_now()))
+            .unwrap();
+        db.insert_events(&[
+            by("Alice <alice@example.com>"),
+            by("Bob <bob@example.com>"),
+            event_json(ts),
+        ])
+        .unwrap();
+
+        let is_alice = |author: &str| author.contains("alice@example.com");
+        assert_eq!(db.delete_events_by_author(is_alice, true).unwrap(), 2);
+        assert_eq!(db.get_metric_history(0, None, &[1]).unwrap().len(), 4);
+
+        assert_eq!(db.delete_events_by_author(is_alice, false).unwrap(), 2);
+        let remaining = db.get_metric_history(0, None, &[1]).unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.iter().all(|r| {
+            sparse_get_string(&r.event.attrs, attr_pos::AUTHOR)
+                .flatten()
+                .is_none_or(|author| !is_alice(&author))
+        }));
+    }
+
     #[test]
     fn test_get_metric_history_reads_legacy_rows_before_and_after_metadata_backfill() {
         let (mut db, _temp_dir) = create_test_db();
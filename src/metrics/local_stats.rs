@@ -1,4 +1,5 @@
-//! In-memory aggregation of persisted metric events for `git-ai usage`.
+//! In-memory aggregation of persisted metric events for `git-ai usage` and
+//! `git-ai report`.
 
 /// How long after a session's last message a subsequent commit is attributed
 /// to that session for yield and ai_lines_committed calculations.
@@ -13,6 +14,7 @@ use crate::metrics::pos_encoded::{
 };
 use crate::metrics::types::MetricEvent;
 use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Timelike};
+use rand::RngExt;
 use serde::Serialize;
 use std::cmp::Reverse;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
@@ -1287,6 +1289,225 @@ pub fn compute_repo_summaries(
     repo_summaries_from_records(&all_records, since_ts, granularity)
 }
 
+/// AI share of committed lines for one period, optionally split by a
+/// custom attribute (e.g. `team`).
+#[derive(Debug, Serialize)]
+pub struct TrendPoint {
+    /// First day of the period (the Monday for weekly, the 1st for monthly).
+    pub period_start: NaiveDate,
+    pub period: String,
+    /// Value of the grouping attribute; `None` when the trend is ungrouped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// All commits in the period, including human-only ones.
+    pub commits: u32,
+    pub ai_lines: u32,
+    pub human_lines: u32,
+    pub diff_added_lines: u32,
+    /// Distinct commit authors behind this point, used for k-anonymity.
+    pub contributors: u32,
+    /// AI lines as a percentage of attributed (AI + human) lines, or `None`
+    /// when nothing in the period was attributed.
+    pub ai_share_pct: Option<f64>,
+}
+
+/// Reserved `group_by` key that splits trends by the commit author attribute
+/// instead of a custom attribute.
+pub const GROUP_BY_AUTHOR: &str = "author";
+
+/// Group label used for commits that don't carry the grouping attribute.
+const UNGROUPED_LABEL: &str = "unknown";
+
+/// Per-author cap on what one developer adds to a single trend point before
+/// noise is applied; it is the sensitivity the Laplace scale is derived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContributionBound {
+    pub commits: u32,
+    pub lines: u32,
+}
+
+/// Bound used whenever report counts are noised.
+pub const NOISE_CONTRIBUTION_BOUND: ContributionBound = ContributionBound {
+    commits: 20,
+    lines: 1_000,
+};
+
+/// One author's totals within a trend point.
+#[derive(Debug, Default, Clone, Copy)]
+struct Contribution {
+    commits: u32,
+    ai_lines: u32,
+    human_lines: u32,
+    diff_added_lines: u32,
+}
+
+impl Contribution {
+    fn clipped(self, bound: Option<ContributionBound>) -> Self {
+        let Some(bound) = bound else {
+            return self;
+        };
+        Self {
+            commits: self.commits.min(bound.commits),
+            ai_lines: self.ai_lines.min(bound.lines),
+            human_lines: self.human_lines.min(bound.lines),
+            diff_added_lines: self.diff_added_lines.min(bound.lines),
+        }
+    }
+}
+
+/// Compute AI share over time from committed events since `since_ts`.
+///
+/// With `group_by = Some(key)`, each period is split by the value of that
+/// custom attribute (see `custom_attributes` in config), or by commit author
+/// when the key is [`GROUP_BY_AUTHOR`]. Periods without commits are omitted.
+/// Points are ordered by period, then group. With `bound`, each author's
+/// contribution to a point is clipped to it (see [`add_laplace_noise`]).
+pub fn compute_trend(
+    since_ts: u32,
+    granularity: BucketGranularity,
+    repo_filter: Option<&str>,
+    group_by: Option<&str>,
+    bound: Option<ContributionBound>,
+) -> Result<Vec<TrendPoint>, GitAiError> {
+    let records = fetch_metric_history(since_ts, repo_filter)?;
+    Ok(trend_from_records(&records, granularity, group_by, bound))
+}
+
+fn trend_from_records(
+    records: &[MetricHistoryRecord],
+    granularity: BucketGranularity,
+    group_by: Option<&str>,
+    bound: Option<ContributionBound>,
+) -> Vec<TrendPoint> {
+    let mut points: BTreeMap<(i64, Option<String>), TrendPoint> = BTreeMap::new();
+    // Commits without an author attribute share one (unnamed) contribution.
+    let mut contributions: HashMap<(i64, Option<String>), HashMap<Option<String>, Contribution>> =
+        HashMap::new();
+
+    for record in records.iter().filter(|r| r.event_id == 1) {
+        let event = &record.event;
+        let (label, order) = bucket_key(&ts_to_local(record.ts), granularity);
+        let Some(period_start) = bucket_start_date(order, granularity) else {
+            continue;
+        };
+        let author = sparse_get_string(&event.attrs, attr_pos::AUTHOR)
+            .flatten()
+            .filter(|v| !v.is_empty());
+        let group = group_by.map(|key| {
+            let value = if key == GROUP_BY_AUTHOR {
+                author.clone()
+            } else {
+                custom_attribute(event, key)
+            };
+            value.unwrap_or_else(|| UNGROUPED_LABEL.to_string())
+        });
+
+        let human = sparse_get_u32(&event.values, committed_pos::HUMAN_ADDITIONS)
+            .flatten()
+            .unwrap_or(0);
+        let diff_added = sparse_get_u32(&event.values, committed_pos::GIT_DIFF_ADDED_LINES)
+            .flatten()
+            .unwrap_or(0);
+        let ai = sparse_get_vec_u32(&event.values, committed_pos::AI_ADDITIONS)
+            .flatten()
+            .and_then(|v| v.first().copied())
+            .unwrap_or(0);
+
+        let contribution = contributions
+            .entry((order, group.clone()))
+            .or_default()
+            .entry(author)
+            .or_default();
+        contribution.commits += 1;
+        contribution.ai_lines += ai;
+        contribution.human_lines += human;
+        contribution.diff_added_lines += diff_added;
+        points
+            .entry((order, group.clone()))
+            .or_insert_with(|| TrendPoint {
+                period_start,
+                period: label,
+                group,
+                commits: 0,
+                ai_lines: 0,
+                human_lines: 0,
+                diff_added_lines: 0,
+                contributors: 0,
+                ai_share_pct: None,
+            });
+    }
+
+    points
+        .into_iter()
+        .map(|(key, mut point)| {
+            for (author, contribution) in contributions.get(&key).into_iter().flatten() {
+                let contribution = contribution.clipped(bound);
+                point.commits += contribution.commits;
+                point.ai_lines += contribution.ai_lines;
+                point.human_lines += contribution.human_lines;
+                point.diff_added_lines += contribution.diff_added_lines;
+                if author.is_some() {
+                    point.contributors += 1;
+                }
+            }
+            point.ai_share_pct = ai_share_pct(point.ai_lines, point.human_lines);
+            point
+        })
+        .collect()
+}
+
+fn ai_share_pct(ai_lines: u32, human_lines: u32) -> Option<f64> {
+    let attributed = ai_lines as u64 + human_lines as u64;
+    if attributed == 0 {
+        return None;
+    }
+    let pct = ai_lines as f64 * 100.0 / attributed as f64;
+    Some((pct * 10.0).round() / 10.0)
+}
+
+/// Drop points backed by fewer than `k` distinct authors so no individual
+/// developer can be singled out. Returns how many points were suppressed.
+pub fn suppress_small_groups(points: &mut Vec<TrendPoint>, k: u32) -> usize {
+    let before = points.len();
+    points.retain(|p| p.contributors >= k);
+    before - points.len()
+}
+
+/// Add Laplace noise to every count of points computed with `bound`
+/// (rounded and clamped at zero), then recompute AI share from the noisy
+/// counts. Each count gets scale `sensitivity / epsilon`: the bound's commit
+/// or line cap, or 1 for `contributors`. Suppression must run first, since it
+/// reads the exact `contributors`.
+pub fn add_laplace_noise(
+    points: &mut [TrendPoint],
+    epsilon: f64,
+    bound: ContributionBound,
+    rng: &mut impl RngExt,
+) {
+    let mut noisy = |value: u32, sensitivity: u32| -> u32 {
+        let scale = sensitivity as f64 / epsilon;
+        // Inverse CDF of Laplace(0, scale) from u ~ Uniform(-0.5, 0.5)
+        let u: f64 = rng.random_range(-0.5..0.5);
+        let noise = -scale * u.signum() * (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE).ln();
+        (value as f64 + noise).round().clamp(0.0, u32::MAX as f64) as u32
+    };
+    for point in points.iter_mut() {
+        point.commits = noisy(point.commits, bound.commits);
+        point.ai_lines = noisy(point.ai_lines, bound.lines);
+        point.human_lines = noisy(point.human_lines, bound.lines);
+        point.diff_added_lines = noisy(point.diff_added_lines, bound.lines);
+        point.contributors = noisy(point.contributors, 1);
+        point.ai_share_pct = ai_share_pct(point.ai_lines, point.human_lines);
+    }
+}
+
+/// Look up a single key in an event's `custom_attributes` JSON object.
+fn custom_attribute(event: &MetricEvent, key: &str) -> Option<String> {
+    let raw = sparse_get_string(&event.attrs, attr_pos::CUSTOM_ATTRIBUTES).flatten()?;
+    let map: HashMap<String, String> = serde_json::from_str(&raw).ok()?;
+    map.get(key).filter(|v| !v.is_empty()).cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1549,4 +1770,177 @@ mod tests {
         assert_eq!(summaries[0].sessions, 1);
         assert!(summaries[0].estimated_cost_usd > 0.0);
     }
+
+    fn committed_with_team(
+        ts: u32,
+        team: Option<&str>,
+        ai: u32,
+        human: u32,
+    ) -> MetricHistoryRecord {
+        let mut rec = committed(ts, "github.com/acme/project", ai, human, ai + human);
+        if let Some(team) = team {
+            let mut custom = HashMap::new();
+            custom.insert("team".to_string(), team.to_string());
+            rec.event.attrs = EventAttributes::with_version("test")
+                .tool("claude")
+                .repo_url("github.com/acme/project")
+                .custom_attributes_map(&custom)
+                .to_sparse();
+        }
+        rec
+    }
+
+    fn local_ts(y: i32, m: u32, d: u32) -> u32 {
+        Local
+            .from_local_datetime(&day(y, m, d).and_hms_opt(12, 0, 0).unwrap())
+            .single()
+            .unwrap()
+            .timestamp() as u32
+    }
+
+    #[test]
+    fn trend_buckets_commits_by_month_and_computes_ai_share() {
+        let records = [
+            committed(local_ts(2026, 3, 5), "github.com/acme/project", 30, 10, 40),
+            committed(local_ts(2026, 3, 20), "github.com/acme/project", 0, 20, 20),
+            committed(local_ts(2026, 4, 2), "github.com/acme/project", 10, 0, 10),
+        ];
+
+        let points = trend_from_records(&records, BucketGranularity::Monthly, None, None);
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].period_start, day(2026, 3, 1));
+        assert_eq!(points[0].period, "Mar 2026");
+        assert_eq!(points[0].group, None);
+        // Human-only commits still count toward the period's commits.
+        assert_eq!(points[0].commits, 2);
+        assert_eq!(points[0].ai_lines, 30);
+        assert_eq!(points[0].human_lines, 30);
+        assert_eq!(points[0].diff_added_lines, 60);
+        assert_eq!(points[0].ai_share_pct, Some(50.0));
+        assert_eq!(points[1].period_start, day(2026, 4, 1));
+        assert_eq!(points[1].ai_share_pct, Some(100.0));
+    }
+
+    #[test]
+    fn trend_groups_by_custom_attribute() {
+        let ts = local_ts(2026, 5, 11);
+        let records = [
+            committed_with_team(ts, Some("web"), 20, 0),
+            committed_with_team(ts, Some("infra"), 1, 2),
+            committed_with_team(ts, None, 5, 5),
+            claude_session(ts, Some("github.com/acme/project"), "session-1"),
+        ];
+
+        let points = trend_from_records(&records, BucketGranularity::Weekly, Some("team"), None);
+
+        let groups: Vec<_> = points.iter().map(|p| p.group.as_deref()).collect();
+        assert_eq!(groups, vec![Some("infra"), Some("unknown"), Some("web")]);
+        assert!(points.iter().all(|p| p.period_start == day(2026, 5, 11)));
+        assert_eq!(points[0].ai_share_pct, Some(33.3));
+        assert_eq!(points[2].ai_lines, 20);
+    }
+
+    #[test]
+    fn trend_groups_by_author_attribute() {
+        let ts = local_ts(2026, 5, 11);
+        let mut alice = committed(ts, "github.com/acme/project", 4, 0, 4);
+        alice.event.attrs = EventAttributes::with_version("test")
+            .author("alice@example.com")
+            .to_sparse();
+        let records = [alice, committed(ts, "github.com/acme/project", 1, 1, 2)];
+
+        let points = trend_from_records(
+            &records,
+            BucketGranularity::Daily,
+            Some(GROUP_BY_AUTHOR),
+            None,
+        );
+
+        let groups: Vec<_> = points.iter().map(|p| p.group.as_deref()).collect();
+        assert_eq!(groups, vec![Some("alice@example.com"), Some("unknown")]);
+    }
+
+    fn committed_by(ts: u32, author: &str, team: &str, ai: u32) -> MetricHistoryRecord {
+        let mut rec = committed(ts, "github.com/acme/project", ai, 0, ai);
+        let mut custom = HashMap::new();
+        custom.insert("team".to_string(), team.to_string());
+        rec.event.attrs = EventAttributes::with_version("test")
+            .author(author)
+            .custom_attributes_map(&custom)
+            .to_sparse();
+        rec
+    }
+
+    #[test]
+    fn suppress_small_groups_drops_groups_below_k_distinct_authors() {
+        let ts = local_ts(2026, 5, 11);
+        let records = [
+            committed_by(ts, "alice@example.com", "web", 5),
+            committed_by(ts, "bob@example.com", "web", 5),
+            committed_by(ts, "alice@example.com", "web", 5),
+            committed_by(ts, "carol@example.com", "infra", 5),
+            committed_by(ts, "carol@example.com", "infra", 5),
+        ];
+
+        let mut points =
+            trend_from_records(&records, BucketGranularity::Monthly, Some("team"), None);
+        assert_eq!(points[0].group.as_deref(), Some("infra"));
+        assert_eq!(points[0].contributors, 1);
+        assert_eq!(points[1].contributors, 2);
+
+        assert_eq!(suppress_small_groups(&mut points, 2), 1);
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].group.as_deref(), Some("web"));
+        assert_eq!(points[0].commits, 3);
+    }
+
+    #[test]
+    fn laplace_noise_recomputes_share_from_noisy_counts() {
+        let ts = local_ts(2026, 5, 11);
+        let records = [committed(ts, "github.com/acme/project", 30, 10, 40)];
+
+        // A huge epsilon means negligible noise.
+        let bound = Some(NOISE_CONTRIBUTION_BOUND);
+        let mut points = trend_from_records(&records, BucketGranularity::Monthly, None, bound);
+        add_laplace_noise(&mut points, 1e9, NOISE_CONTRIBUTION_BOUND, &mut rand::rng());
+        assert_eq!(points[0].ai_lines, 30);
+        assert_eq!(points[0].ai_share_pct, Some(75.0));
+
+        // A tiny epsilon perturbs counts; the share must follow the noisy values.
+        let mut points = trend_from_records(&records, BucketGranularity::Monthly, None, bound);
+        add_laplace_noise(
+            &mut points,
+            0.01,
+            NOISE_CONTRIBUTION_BOUND,
+            &mut rand::rng(),
+        );
+        assert_eq!(
+            points[0].ai_share_pct,
+            ai_share_pct(points[0].ai_lines, points[0].human_lines)
+        );
+    }
+
+    #[test]
+    fn trend_clips_each_authors_contribution_to_the_bound() {
+        let ts = local_ts(2026, 5, 11);
+        let records = [
+            committed_by(ts, "alice@example.com", "web", 800),
+            committed_by(ts, "alice@example.com", "web", 800),
+            committed_by(ts, "bob@example.com", "web", 300),
+        ];
+        let bound = ContributionBound {
+            commits: 1,
+            lines: 1_000,
+        };
+
+        let exact = trend_from_records(&records, BucketGranularity::Monthly, None, None);
+        assert_eq!((exact[0].commits, exact[0].ai_lines), (3, 1_900));
+
+        let clipped = trend_from_records(&records, BucketGranularity::Monthly, None, Some(bound));
+        assert_eq!(clipped[0].commits, 2);
+        assert_eq!(clipped[0].ai_lines, 1_300);
+        assert_eq!(clipped[0].diff_added_lines, 1_300);
+        assert_eq!(clipped[0].contributors, 2);
+    }
 }
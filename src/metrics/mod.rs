@@ -21,6 +21,9 @@ pub use events::{
 pub use pos_encoded::PosEncoded;
 pub use types::{EventValues, METRICS_API_VERSION, MetricEvent, MetricsBatch};
 
+use crate::config::Config;
+use std::borrow::Cow;
+
 /// Record an event with values and attributes.
 ///
 /// Events are sent to the daemon telemetry worker which batches
@@ -58,6 +61,26 @@ pub fn record<V: EventValues>(values: V, attrs: EventAttributes) {
     crate::observability::log_metrics(vec![event]);
 }
 
+/// The batch as it may leave this machine. While a report privacy policy is
+/// set, the commit author is dropped so the central backend can only
+/// aggregate above the individual developer.
+pub fn batch_for_upload<'a>(batch: &'a MetricsBatch, config: &Config) -> Cow<'a, MetricsBatch> {
+    if config.report_privacy_enabled() {
+        Cow::Owned(without_authors(batch))
+    } else {
+        Cow::Borrowed(batch)
+    }
+}
+
+fn without_authors(batch: &MetricsBatch) -> MetricsBatch {
+    let author = attrs::attr_pos::AUTHOR.to_string();
+    let mut batch = batch.clone();
+    for event in &mut batch.events {
+        event.attrs.remove(&author);
+    }
+    batch
+}
+
 fn should_ignore_debug_self_check_event(attrs: &EventAttributes) -> bool {
     if attrs.repo_url.as_ref().is_some_and(|repo_url| {
         repo_url
@@ -108,6 +131,21 @@ mod tests {
         record(values, attrs);
     }
 
+    #[test]
+    fn test_without_authors_drops_only_the_author_attribute() {
+        let attrs = EventAttributes::with_version("1.0.0")
+            .author("alice@example.com")
+            .repo_url("github.com/acme/project");
+        let values = CommittedValues::new().human_additions(5);
+        let batch = MetricsBatch::new(vec![MetricEvent::new(&values, attrs.to_sparse())]);
+
+        let redacted = without_authors(&batch);
+        let attrs = &redacted.events[0].attrs;
+        assert!(!attrs.contains_key(&attrs::attr_pos::AUTHOR.to_string()));
+        assert!(attrs.contains_key(&attrs::attr_pos::REPO_URL.to_string()));
+        assert_eq!(redacted.events[0].values, batch.events[0].values);
+    }
+
     #[test]
     fn test_debug_self_check_repo_url_is_ignored() {
         let attrs = EventAttributes::with_version("1.0.0")
@@ -0,0 +1,114 @@
+//! Resolution of which HTTP notes backend a repository syncs with.
+//!
+//! `notes_backend.backend_url` is the default target. Each `sync_backends`
+//! entry adds a per-org target selected by remote URL, with its own upload
+//! queue (keyed by name in `notes-db`) and its own API key.
+
+use crate::api::client::{ApiClient, ApiContext};
+use crate::config::{Config, NotesBackendKind, SyncBackendConfig};
+use crate::git::repository::Repository;
+use crate::notes::db::DEFAULT_BACKEND;
+
+/// One place notes are uploaded to and fetched from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncTarget {
+    /// Queue key in `notes-db`; `DEFAULT_BACKEND` for the default backend
+    pub queue: String,
+    pub backend_url: Option<String>,
+    /// Remote patterns routed here; empty for the default backend
+    pub remotes: Vec<String>,
+    /// API key for a per-org backend. Never set for the default backend,
+    /// which uses the stored login or `api_key` instead.
+    api_key: Option<String>,
+}
+
+impl SyncTarget {
+    pub fn default_backend(backend_url: Option<&str>) -> Self {
+        Self {
+            queue: DEFAULT_BACKEND.to_string(),
+            backend_url: backend_url.map(str::to_string),
+            remotes: Vec::new(),
+            api_key: None,
+        }
+    }
+
+    pub fn from_config(backend: &SyncBackendConfig) -> Self {
+        Self {
+            queue: backend.name.clone(),
+            backend_url: Some(backend.backend_url.clone()),
+            remotes: backend.remotes.clone(),
+            api_key: backend.api_key(),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.queue == DEFAULT_BACKEND
+    }
+
+    /// Label for status output
+    pub fn display_name(&self) -> &str {
+        if self.is_default() {
+            "default"
+        } else {
+            &self.queue
+        }
+    }
+
+    /// An authenticated client for this target, or `None` when there is no URL
+    /// or nothing to authenticate with. Per-org backends only ever send their
+    /// own key, so the default login never leaks to another org's server.
+    pub fn client(&self) -> Option<ApiClient> {
+        let backend_url = self.backend_url.clone()?;
+        let client = if self.is_default() {
+            ApiClient::new(ApiContext::new(Some(backend_url)))
+        } else {
+            ApiClient::new(ApiContext::with_api_key(backend_url, self.api_key.clone()?))
+        };
+        (client.is_logged_in() || client.has_api_key()).then_some(client)
+    }
+}
+
+/// Every configured target, default first. Empty unless the HTTP notes
+/// backend is enabled.
+pub fn all_targets(cfg: &Config) -> Vec<SyncTarget> {
+    if cfg.notes_backend_kind() != NotesBackendKind::Http {
+        return Vec::new();
+    }
+    std::iter::once(SyncTarget::default_backend(cfg.notes_backend_url()))
+        .chain(cfg.sync_backends().iter().map(SyncTarget::from_config))
+        .collect()
+}
+
+/// The target owning `repo`'s notes.
+pub fn target_for_repository(cfg: &Config, repo: &Repository) -> SyncTarget {
+    cfg.sync_backend_for_repository(repo)
+        .map(SyncTarget::from_config)
+        .unwrap_or_else(|| SyncTarget::default_backend(cfg.notes_backend_url()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn org_target_without_key_has_no_client() {
+        let backend = SyncBackendConfig {
+            name: "acme".to_string(),
+            remotes: vec!["https://github.com/acme/*".to_string()],
+            backend_url: "https://notes.acme.example".to_string(),
+            api_key_env: Some("GIT_AI_TEST_UNSET_SYNC_KEY".to_string()),
+        };
+        let target = SyncTarget::from_config(&backend);
+        assert!(!target.is_default());
+        assert_eq!(target.display_name(), "acme");
+        assert!(target.client().is_none());
+
+        let keyed = SyncTarget {
+            api_key: Some("acme-key".to_string()),
+            ..target
+        };
+        let client = keyed.client().expect("keyed org backend has a client");
+        assert!(client.has_api_key());
+        assert!(!client.is_logged_in());
+    }
+}
@@ -4,12 +4,17 @@
 //!  - `synced = 0` — the row is pending upload to the remote backend
 //!  - `synced = 1` — the row has been uploaded (kept for local read cache)
 //!
+//! Each row also records which HTTP backend its upload is queued for
+//! (`backend`, empty for `notes_backend.backend_url`), so every configured
+//! `sync_backends` entry drains and retries independently.
+//!
 //! Rows are NEVER deleted on successful upload — they are retained as the local
 //! read cache so that subsequent reads can be served without git or a network call.
 //!
 //! This database is SEPARATE from `src/authorship/internal_db.rs`. Adding columns
 //! or migrations to `internal_db` for this feature is explicitly not what we do here.
 
+use crate::app_context::AppContext;
 use crate::error::GitAiError;
 use rusqlite::{Connection, params};
 use std::collections::{HashMap, HashSet};
@@ -17,7 +22,7 @@ use std::path::PathBuf;
 use std::sync::{Mutex, OnceLock};
 
 /// Current schema version (must equal MIGRATIONS.len()).
-const SCHEMA_VERSION: usize = 1;
+const SCHEMA_VERSION: usize = 2;
 
 /// Database migrations — each entry upgrades the schema by one version.
 const MIGRATIONS: &[&str] = &[
@@ -39,8 +44,21 @@ const MIGRATIONS: &[&str] = &[
     CREATE INDEX IF NOT EXISTS idx_notes_pending
         ON notes(synced, next_retry_at) WHERE synced = 0;
     "#,
+    // Migration 1 → 2: per-backend upload queues
+    r#"
+    ALTER TABLE notes ADD COLUMN backend TEXT NOT NULL DEFAULT '';
+
+    CREATE INDEX IF NOT EXISTS idx_notes_backend_pending
+        ON notes(backend, synced, next_retry_at) WHERE synced = 0;
+    "#,
 ];
 
+/// Queue key for the default backend (`notes_backend.backend_url`).
+pub const DEFAULT_BACKEND: &str = "";
+
+/// Rows at this many failed attempts are no longer retried.
+const MAX_SYNC_ATTEMPTS: i64 = 6;
+
 /// Global singleton for the notes database.
 static NOTES_DB: OnceLock<Mutex<NotesDatabase>> = OnceLock::new();
 
@@ -52,6 +70,20 @@ pub struct PendingNote {
     pub attempts: i64,
 }
 
+/// Upload queue health for one backend, as shown by `git-ai sync status`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackendQueueStatus {
+    /// Unsynced rows that will still be uploaded
+    pub pending: usize,
+    /// Pending rows that have failed at least once
+    pub retrying: usize,
+    /// Rows that gave up after too many failed attempts
+    pub stopped: usize,
+    pub synced: usize,
+    pub last_synced_at: Option<i64>,
+    pub latest_error: Option<String>,
+}
+
 /// SQLite wrapper for notes storage and queue.
 pub struct NotesDatabase {
     conn: Connection,
@@ -129,9 +161,7 @@ impl NotesDatabase {
             return Ok(PathBuf::from(test_path));
         }
 
-        let home = dirs::home_dir()
-            .ok_or_else(|| GitAiError::Generic("Could not determine home directory".to_string()))?;
-        Ok(home.join(".git-ai").join("internal").join("notes-db"))
+        Ok(AppContext::current().state_dir.join("notes-db"))
     }
 
     /// Apply schema migrations until the DB is at `SCHEMA_VERSION`.
@@ -233,25 +263,24 @@ impl NotesDatabase {
     /// - If the content changed, `synced` and `attempts` are reset to 0 so the
     ///   updated note is queued for re-upload.
     pub fn upsert_note(&mut self, commit_sha: &str, content: &str) -> Result<(), GitAiError> {
-        let now = unix_now();
-        self.conn.execute(
-            r#"
-            INSERT INTO notes (commit_sha, content, synced, created_at, updated_at, next_retry_at)
-            VALUES (?1, ?2, 0, ?3, ?3, ?3)
-            ON CONFLICT(commit_sha) DO UPDATE SET
-                content        = excluded.content,
-                synced         = CASE WHEN notes.content = excluded.content THEN notes.synced ELSE 0 END,
-                attempts       = CASE WHEN notes.content = excluded.content THEN notes.attempts ELSE 0 END,
-                next_retry_at  = CASE WHEN notes.content = excluded.content THEN notes.next_retry_at ELSE excluded.next_retry_at END,
-                updated_at     = excluded.updated_at
-            "#,
-            params![commit_sha, content, now],
-        )?;
-        Ok(())
+        self.upsert_notes_batch_for_backend(
+            DEFAULT_BACKEND,
+            &[(commit_sha.to_string(), content.to_string())],
+        )
     }
 
     /// Upsert a batch of notes inside a single transaction.
     pub fn upsert_notes_batch(&mut self, entries: &[(String, String)]) -> Result<(), GitAiError> {
+        self.upsert_notes_batch_for_backend(DEFAULT_BACKEND, entries)
+    }
+
+    /// Upsert a batch of notes queued for `backend`. A note moving to another
+    /// backend is re-queued like a content change.
+    pub fn upsert_notes_batch_for_backend(
+        &mut self,
+        backend: &str,
+        entries: &[(String, String)],
+    ) -> Result<(), GitAiError> {
         if entries.is_empty() {
             return Ok(());
         }
@@ -260,18 +289,19 @@ impl NotesDatabase {
         {
             let mut stmt = tx.prepare_cached(
                 r#"
-                INSERT INTO notes (commit_sha, content, synced, created_at, updated_at, next_retry_at)
-                VALUES (?1, ?2, 0, ?3, ?3, ?3)
+                INSERT INTO notes (commit_sha, content, synced, created_at, updated_at, next_retry_at, backend)
+                VALUES (?1, ?2, 0, ?3, ?3, ?3, ?4)
                 ON CONFLICT(commit_sha) DO UPDATE SET
                     content        = excluded.content,
-                    synced         = CASE WHEN notes.content = excluded.content THEN notes.synced ELSE 0 END,
-                    attempts       = CASE WHEN notes.content = excluded.content THEN notes.attempts ELSE 0 END,
-                    next_retry_at  = CASE WHEN notes.content = excluded.content THEN notes.next_retry_at ELSE excluded.next_retry_at END,
+                    synced         = CASE WHEN notes.content = excluded.content AND notes.backend = excluded.backend THEN notes.synced ELSE 0 END,
+                    attempts       = CASE WHEN notes.content = excluded.content AND notes.backend = excluded.backend THEN notes.attempts ELSE 0 END,
+                    next_retry_at  = CASE WHEN notes.content = excluded.content AND notes.backend = excluded.backend THEN notes.next_retry_at ELSE excluded.next_retry_at END,
+                    backend        = excluded.backend,
                     updated_at     = excluded.updated_at
                 "#,
             )?;
             for (sha, content) in entries {
-                stmt.execute(params![sha, content, now])?;
+                stmt.execute(params![sha, content, now, backend])?;
             }
         }
         tx.commit()?;
@@ -318,6 +348,15 @@ impl NotesDatabase {
     ///
     /// Rows with `attempts >= 6` are skipped (permanent failure backoff).
     pub fn dequeue_pending(&mut self, batch_size: usize) -> Result<Vec<PendingNote>, GitAiError> {
+        self.dequeue_pending_for_backend(DEFAULT_BACKEND, batch_size)
+    }
+
+    /// `dequeue_pending` restricted to rows queued for `backend`.
+    pub fn dequeue_pending_for_backend(
+        &mut self,
+        backend: &str,
+        batch_size: usize,
+    ) -> Result<Vec<PendingNote>, GitAiError> {
         let now = unix_now();
         let stale_cutoff = now - 600; // 10 minutes
 
@@ -337,15 +376,17 @@ impl NotesDatabase {
             let mut stmt = self.conn.prepare(
                 r#"SELECT commit_sha FROM notes
                    WHERE synced = 0
+                     AND backend = ?3
                      AND processing_started_at IS NULL
                      AND next_retry_at <= ?1
-                     AND attempts < 6
+                     AND attempts < ?4
                    ORDER BY next_retry_at
                    LIMIT ?2"#,
             )?;
-            let rows = stmt.query_map(params![now, batch_size as i64], |row| {
-                row.get::<_, String>(0)
-            })?;
+            let rows = stmt.query_map(
+                params![now, batch_size as i64, backend, MAX_SYNC_ATTEMPTS],
+                |row| row.get::<_, String>(0),
+            )?;
             rows.filter_map(|r| r.ok()).collect()
         };
 
@@ -462,6 +503,64 @@ impl NotesDatabase {
 
     // ----- Read operations -----
 
+    /// Count unsynced notes for `backend` that can be dequeued right now.
+    pub fn count_pending_uploadable_for_backend(&self, backend: &str) -> Result<usize, GitAiError> {
+        let now = unix_now();
+        let count: i64 = self.conn.query_row(
+            r#"SELECT COUNT(*) FROM notes
+               WHERE synced = 0
+                 AND backend = ?2
+                 AND processing_started_at IS NULL
+                 AND next_retry_at <= ?1
+                 AND attempts < ?3"#,
+            params![now, backend, MAX_SYNC_ATTEMPTS],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Upload queue health for `backend`.
+    pub fn backend_queue_status(&self, backend: &str) -> Result<BackendQueueStatus, GitAiError> {
+        let (pending, retrying, stopped, synced, last_synced_at): (
+            i64,
+            i64,
+            i64,
+            i64,
+            Option<i64>,
+        ) = self.conn.query_row(
+            r#"SELECT
+                   COALESCE(SUM(CASE WHEN synced = 0 AND attempts < ?2 THEN 1 ELSE 0 END), 0),
+                   COALESCE(SUM(CASE WHEN synced = 0 AND attempts > 0 AND attempts < ?2 THEN 1 ELSE 0 END), 0),
+                   COALESCE(SUM(CASE WHEN synced = 0 AND attempts >= ?2 THEN 1 ELSE 0 END), 0),
+                   COALESCE(SUM(CASE WHEN synced = 1 THEN 1 ELSE 0 END), 0),
+                   MAX(CASE WHEN synced = 1 THEN last_sync_at END)
+               FROM notes
+               WHERE backend = ?1"#,
+            params![backend, MAX_SYNC_ATTEMPTS],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )?;
+        let latest_error = match self.conn.query_row(
+            r#"SELECT last_sync_error FROM notes
+               WHERE backend = ?1 AND synced = 0 AND last_sync_error IS NOT NULL
+               ORDER BY last_sync_at DESC
+               LIMIT 1"#,
+            params![backend],
+            |row| row.get::<_, String>(0),
+        ) {
+            Ok(error) => Some(error),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(BackendQueueStatus {
+            pending: pending as usize,
+            retrying: retrying as usize,
+            stopped: stopped as usize,
+            synced: synced as usize,
+            last_synced_at,
+            latest_error,
+        })
+    }
+
     /// Count unsynced notes that can be dequeued for upload right now.
     pub fn count_pending_uploadable(&self) -> Result<usize, GitAiError> {
         let now = unix_now();
@@ -597,8 +696,48 @@ impl NotesDatabase {
         )?;
         Ok(deleted)
     }
+
+    /// Bytes of database pages in use. The file itself stays larger after
+    /// deletes until it is vacuumed.
+    pub fn used_bytes(&self) -> Result<u64, GitAiError> {
+        let pragma = |name: &str| -> Result<i64, GitAiError> {
+            Ok(self
+                .conn
+                .query_row(&format!("PRAGMA {}", name), [], |row| row.get(0))?)
+        };
+        let used_pages = pragma("page_count")? - pragma("freelist_count")?;
+        Ok((used_pages.max(0) * pragma("page_size")?) as u64)
+    }
+
+    /// Evict the least recently synced cache rows until the database fits in
+    /// `max_bytes`, then vacuum so the file shrinks too. Unsynced rows are
+    /// never evicted, so a queue larger than the budget is left alone.
+    /// Returns the number of rows deleted.
+    pub fn evict_to_size_budget(&mut self, max_bytes: u64) -> Result<usize, GitAiError> {
+        let mut deleted = 0;
+        while self.used_bytes()? > max_bytes {
+            let batch = self.conn.execute(
+                "DELETE FROM notes WHERE commit_sha IN (
+                    SELECT commit_sha FROM notes WHERE synced = 1
+                    ORDER BY COALESCE(last_sync_at, updated_at) ASC LIMIT ?1
+                )",
+                params![SIZE_EVICTION_BATCH],
+            )?;
+            if batch == 0 {
+                break;
+            }
+            deleted += batch;
+        }
+        if deleted > 0 {
+            self.conn.execute_batch("VACUUM")?;
+        }
+        Ok(deleted)
+    }
 }
 
+/// Rows deleted per step while shrinking to the size budget
+const SIZE_EVICTION_BATCH: i64 = 200;
+
 fn unix_now() -> i64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -655,7 +794,7 @@ mod tests {
                 |row| row.get(0),
             )
             .unwrap();
-        assert_eq!(version, "1");
+        assert_eq!(version, SCHEMA_VERSION.to_string());
     }
 
     #[test]
@@ -681,6 +820,7 @@ mod tests {
             "processing_started_at",
             "created_at",
             "updated_at",
+            "backend",
         ];
         for col in &required {
             assert!(
@@ -923,6 +1063,85 @@ mod tests {
         );
     }
 
+    // --- Per-backend queues ---
+
+    #[test]
+    fn test_backend_queues_drain_and_fail_independently() {
+        let (mut db, _tmp) = create_test_db();
+
+        db.upsert_note("sha_default", "d").unwrap();
+        db.upsert_notes_batch_for_backend("acme", &[("sha_acme".to_string(), "a".to_string())])
+            .unwrap();
+
+        let acme = db.dequeue_pending_for_backend("acme", 10).unwrap();
+        assert_eq!(acme.len(), 1);
+        assert_eq!(acme[0].commit_sha, "sha_acme");
+        db.mark_failed(&["sha_acme".to_string()], "401 Unauthorized")
+            .unwrap();
+
+        let acme_status = db.backend_queue_status("acme").unwrap();
+        assert_eq!(acme_status.pending, 1);
+        assert_eq!(acme_status.retrying, 1);
+        assert_eq!(
+            acme_status.latest_error.as_deref(),
+            Some("401 Unauthorized")
+        );
+        assert_eq!(db.count_pending_uploadable_for_backend("acme").unwrap(), 0);
+
+        // The default queue is unaffected by the other backend's failure
+        let default = db.dequeue_pending(10).unwrap();
+        assert_eq!(default.len(), 1);
+        assert_eq!(default[0].commit_sha, "sha_default");
+        db.mark_synced(&["sha_default".to_string()]).unwrap();
+        let default_status = db.backend_queue_status(DEFAULT_BACKEND).unwrap();
+        assert_eq!(default_status.pending, 0);
+        assert_eq!(default_status.synced, 1);
+        assert!(default_status.last_synced_at.is_some());
+        assert_eq!(default_status.latest_error, None);
+    }
+
+    #[test]
+    fn test_size_budget_evicts_synced_rows_only() {
+        let (mut db, _tmp) = create_test_db();
+
+        let body = "x".repeat(4096);
+        let cached: Vec<(String, String)> = (0..100)
+            .map(|i| (format!("sha_cached_{}", i), body.clone()))
+            .collect();
+        db.cache_synced_notes(&cached).unwrap();
+        db.upsert_note("sha_pending", &body).unwrap();
+
+        let before = db.used_bytes().unwrap();
+        assert!(before > 400 * 1024, "{}", before);
+        assert_eq!(db.evict_to_size_budget(before).unwrap(), 0);
+
+        let budget = 100 * 1024;
+        let deleted = db.evict_to_size_budget(budget).unwrap();
+        assert!(deleted > 0 && deleted <= 100, "{}", deleted);
+        assert!(db.used_bytes().unwrap() <= budget);
+
+        // Once nothing synced is left an unreachable budget stops there,
+        // keeping the queued note
+        db.evict_to_size_budget(0).unwrap();
+        let status = db.backend_queue_status(DEFAULT_BACKEND).unwrap();
+        assert_eq!((status.pending, status.synced), (1, 0));
+    }
+
+    #[test]
+    fn test_note_moving_backend_is_requeued() {
+        let (mut db, _tmp) = create_test_db();
+
+        db.upsert_note("sha_move", "same").unwrap();
+        let _ = db.dequeue_pending(10).unwrap();
+        db.mark_synced(&["sha_move".to_string()]).unwrap();
+
+        db.upsert_notes_batch_for_backend("acme", &[("sha_move".to_string(), "same".to_string())])
+            .unwrap();
+        let pending = db.dequeue_pending_for_backend("acme", 10).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(db.dequeue_pending(10).unwrap().is_empty());
+    }
+
     // --- get_notes (batch) ---
 
     #[test]
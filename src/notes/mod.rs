@@ -3,9 +3,13 @@
 //! `notes::db` provides the dedicated `~/.git-ai/internal/notes-db` SQLite store
 //! used by the HTTP notes backend as both a write queue and a local read cache.
 //!
+//! `notes::backends` picks which HTTP backend (default or per-org) a repository
+//! syncs with, along with its upload queue and credentials.
+//!
 //! `notes::reference_server` is an in-memory reference implementation of the
 //! HTTP wire contract — used for local testing, benchmarking, and as
 //! documentation of what a real backend must implement.
 
+pub mod backends;
 pub mod db;
 pub mod reference_server;
@@ -0,0 +1,206 @@
+//! Terminal styling and the plain output mode.
+//!
+//! Plain mode — `git-ai --plain <command>`, `GIT_AI_PLAIN_OUTPUT=1`, or
+//! `TERM=dumb` — is for screen readers and CI logs: no colors, spinners,
+//! box drawing or block-character bars, and status words (`OK:`,
+//! `FAILED:`, ...) in place of symbols. Commands style their output through
+//! these helpers rather than writing escape codes directly.
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const BOLD: &str = "\x1b[1m";
+pub const GRAY: &str = "\x1b[90m";
+pub const YELLOW: &str = "\x1b[33m";
+pub const BOLD_RED: &str = "\x1b[1;31m";
+pub const BOLD_GREEN: &str = "\x1b[1;32m";
+pub const BOLD_YELLOW: &str = "\x1b[1;33m";
+pub const BOLD_CYAN: &str = "\x1b[1;36m";
+pub const ORANGE: &str = "\x1b[38;5;208m";
+pub const BLUE: &str = "\x1b[38;5;33m";
+pub const RESET: &str = "\x1b[0m";
+
+static PLAIN_FLAG: AtomicBool = AtomicBool::new(false);
+static PLAIN_ENV: OnceLock<bool> = OnceLock::new();
+
+/// Turn plain mode on for this process (the `--plain` flag)
+pub fn set_plain(plain: bool) {
+    PLAIN_FLAG.store(plain, Ordering::Relaxed);
+}
+
+pub fn is_plain() -> bool {
+    PLAIN_FLAG.load(Ordering::Relaxed)
+        || *PLAIN_ENV.get_or_init(|| {
+            plain_from_env(
+                std::env::var("GIT_AI_PLAIN_OUTPUT").ok().as_deref(),
+                std::env::var("TERM").ok().as_deref(),
+            )
+        })
+}
+
+fn plain_from_env(plain_output: Option<&str>, term: Option<&str>) -> bool {
+    matches!(
+        plain_output.map(str::trim),
+        Some("1" | "true" | "TRUE" | "True" | "yes")
+    ) || term == Some("dumb")
+}
+
+/// `text` in `style`, or unchanged in plain mode
+pub fn paint(style: &str, text: &str) -> String {
+    if is_plain() {
+        text.to_string()
+    } else {
+        format!("{}{}{}", style, text, RESET)
+    }
+}
+
+/// How an outcome is marked at the start of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Pending,
+    Failed,
+    Skipped,
+    Warning,
+}
+
+impl Status {
+    fn symbol(self) -> &'static str {
+        match self {
+            Status::Ok => "✓",
+            Status::Pending | Status::Warning => "⚠",
+            Status::Failed => "✗",
+            Status::Skipped => "○",
+        }
+    }
+
+    fn word(self) -> &'static str {
+        match self {
+            Status::Ok => "OK:",
+            Status::Pending => "PENDING:",
+            Status::Failed => "FAILED:",
+            Status::Skipped => "SKIPPED:",
+            Status::Warning => "WARNING:",
+        }
+    }
+
+    fn style(self) -> &'static str {
+        match self {
+            Status::Ok => BOLD_GREEN,
+            Status::Pending => BOLD_YELLOW,
+            Status::Warning => YELLOW,
+            Status::Failed => BOLD_RED,
+            Status::Skipped => GRAY,
+        }
+    }
+
+    /// `message` behind the status symbol, styled as a whole; in plain mode,
+    /// behind the status word
+    pub fn line(self, message: &str) -> String {
+        self.line_for(message, is_plain())
+    }
+
+    fn line_for(self, message: &str, plain: bool) -> String {
+        if plain {
+            format!("{} {}", self.word(), message)
+        } else {
+            format!("{}{} {}{}", self.style(), self.symbol(), message, RESET)
+        }
+    }
+
+    /// Just the mark, styled, for lines that style the rest themselves
+    pub fn mark(self) -> String {
+        self.mark_for(is_plain())
+    }
+
+    fn mark_for(self, plain: bool) -> String {
+        if plain {
+            self.word().to_string()
+        } else {
+            format!("{}{}{}", self.style(), self.symbol(), RESET)
+        }
+    }
+}
+
+/// Colors and bar glyphs for hand-drawn tables and charts. In plain mode the
+/// colors are empty and the glyphs are ASCII.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub bold: &'static str,
+    pub gray: &'static str,
+    pub orange: &'static str,
+    pub blue: &'static str,
+    pub reset: &'static str,
+    pub bar_filled: &'static str,
+    pub bar_empty: &'static str,
+    /// A bar with no data
+    pub bar_none: &'static str,
+    /// Heat levels, empty to full
+    pub levels: [&'static str; 5],
+}
+
+impl Palette {
+    pub fn current() -> Self {
+        Self::for_mode(is_plain())
+    }
+
+    fn for_mode(plain: bool) -> Self {
+        if plain {
+            Palette {
+                bold: "",
+                gray: "",
+                orange: "",
+                blue: "",
+                reset: "",
+                bar_filled: "#",
+                bar_empty: "-",
+                bar_none: ".",
+                levels: [".", ":", "+", "*", "#"],
+            }
+        } else {
+            Palette {
+                bold: BOLD,
+                gray: GRAY,
+                orange: ORANGE,
+                blue: BLUE,
+                reset: RESET,
+                bar_filled: "█",
+                bar_empty: "░",
+                bar_none: "·",
+                levels: ["·", "░", "▒", "▓", "█"],
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_mode_from_env() {
+        assert!(plain_from_env(Some("1"), None));
+        assert!(plain_from_env(None, Some("dumb")));
+        assert!(!plain_from_env(Some("0"), Some("xterm-256color")));
+        assert!(!plain_from_env(None, None));
+    }
+
+    #[test]
+    fn plain_mode_uses_words_and_no_escapes() {
+        assert_eq!(
+            Status::Failed.line_for("Fork: check failed", true),
+            "FAILED: Fork: check failed"
+        );
+        assert_eq!(Status::Ok.mark_for(true), "OK:");
+        assert_eq!(Status::Ok.mark_for(false), "\x1b[1;32m✓\x1b[0m");
+
+        let palette = Palette::for_mode(true);
+        assert_eq!(palette.reset, "");
+        assert!(palette.levels.iter().all(|glyph| glyph.is_ascii()));
+        assert!(
+            [palette.bar_filled, palette.bar_empty, palette.bar_none]
+                .iter()
+                .all(|glyph| glyph.is_ascii())
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Redaction of repository identifiers, local paths and the hostname from
+//! payloads sent off the machine (API uploads, Sentry, PostHog).
+//!
+//! Payloads are redacted as JSON after serialization, so the policy covers
+//! every string regardless of which field or position-encoded slot it sits
+//! in. Repository URLs become `repo:<hash>` with an org salt, keeping them
+//! stable across machines for aggregation without revealing project names.
+
+use crate::config::{Config, RedactionConfig};
+use crate::repo_url::normalize_repo_url;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+
+const PATH_PLACEHOLDER: &str = "<path>";
+const HOST_PLACEHOLDER: &str = "<host>";
+
+/// `scheme://...` URLs and scp-like `user@host:path` remotes
+static REPO_URL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:https?|ssh|git)://[^\s"'<>()\[\]{}]+|[\w.-]+@[\w.-]+:[\w.~/-]+"#).unwrap()
+});
+
+/// Absolute paths with at least two components, at the start of a string or
+/// after whitespace, a quote, `=` or an opening bracket. URL paths never
+/// match because their slashes follow `:` or another `/`.
+static UNIX_PATH_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(^|[\s"'=(\[,])/[^/\s"',:)\]]+(?:/[^/\s"',:)\]]*)+"#).unwrap());
+
+static WINDOWS_PATH_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\b[A-Za-z]:[\\/][^\s"'<>|]*"#).unwrap());
+
+static HOSTNAME: Lazy<Option<String>> = Lazy::new(|| {
+    crate::api::client::resolve_hostname()
+        .filter(|host| host.len() >= 3 && !host.eq_ignore_ascii_case("localhost"))
+});
+
+#[derive(Debug, Clone)]
+pub struct RedactionPolicy {
+    /// Salt for repository hashes; `None` leaves repository URLs alone
+    repo_salt: Option<String>,
+    strip_local_paths: bool,
+    hostname: Option<String>,
+}
+
+impl RedactionPolicy {
+    /// The policy for `config`, or `None` when it redacts nothing
+    pub fn from_config(config: &RedactionConfig) -> Option<Self> {
+        if !config.is_enabled() {
+            return None;
+        }
+        Some(Self {
+            repo_salt: config
+                .hash_repo_urls
+                .then(|| config.salt.clone().unwrap_or_default()),
+            strip_local_paths: config.strip_local_paths,
+            hostname: if config.strip_local_paths {
+                HOSTNAME.clone()
+            } else {
+                None
+            },
+        })
+    }
+
+    pub fn redact_str<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        if let Some(salt) = &self.repo_salt {
+            text = replace_cow(text, &REPO_URL_RE, |caps| {
+                let matched = &caps[0];
+                // Sentence punctuation after a URL in free text isn't part of it
+                let url = matched.trim_end_matches(['.', ',', ';', ':']);
+                match normalize_repo_url(url) {
+                    Ok(normalized) => {
+                        format!("{}{}", repo_hash(salt, &normalized), &matched[url.len()..])
+                    }
+                    Err(_) => matched.to_string(),
+                }
+            });
+        }
+        if self.strip_local_paths {
+            text = replace_cow(text, &UNIX_PATH_RE, |caps| {
+                format!("{}{}", &caps[1], PATH_PLACEHOLDER)
+            });
+            text = replace_cow(text, &WINDOWS_PATH_RE, |_| PATH_PLACEHOLDER.to_string());
+            if let Some(host) = &self.hostname
+                && text.contains(host.as_str())
+            {
+                text = Cow::Owned(text.replace(host.as_str(), HOST_PLACEHOLDER));
+            }
+        }
+        text
+    }
+
+    /// Redact every string in `value`, object keys included
+    pub fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => {
+                if let Cow::Owned(redacted) = self.redact_str(text) {
+                    *text = redacted;
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_value(item)),
+            Value::Object(map) => {
+                let entries = std::mem::take(map);
+                for (key, mut item) in entries {
+                    self.redact_value(&mut item);
+                    map.insert(self.redact_str(&key).into_owned(), item);
+                }
+            }
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+        }
+    }
+}
+
+/// Stable pseudonym for a repository; remotes that normalize to the same URL
+/// (ssh vs https, `.git` suffix) get the same hash
+fn repo_hash(salt: &str, normalized_url: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(normalized_url.as_bytes());
+    format!("repo:{}", &format!("{:x}", hasher.finalize())[..16])
+}
+
+fn replace_cow<'a>(
+    text: Cow<'a, str>,
+    pattern: &Regex,
+    replacement: impl Fn(&Captures) -> String,
+) -> Cow<'a, str> {
+    if !pattern.is_match(&text) {
+        return text;
+    }
+    Cow::Owned(pattern.replace_all(&text, replacement).into_owned())
+}
+
+/// Apply the configured redaction policy to an outbound JSON payload.
+pub fn redact_outbound(value: &mut Value) {
+    if let Some(policy) = RedactionPolicy::from_config(Config::fresh().redaction()) {
+        policy.redact_value(value);
+    }
+}
+
+/// Serialize an outbound request body with the configured redaction applied.
+/// Every payload that leaves the machine goes through here.
+pub fn outbound_json<T: Serialize + ?Sized>(body: &T) -> Result<String, serde_json::Error> {
+    let mut value = serde_json::to_value(body)?;
+    redact_outbound(&mut value);
+    serde_json::to_string(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(hash_repo_urls: bool, strip_local_paths: bool) -> RedactionPolicy {
+        RedactionPolicy {
+            repo_salt: hash_repo_urls.then(|| "acme-salt".to_string()),
+            strip_local_paths,
+            hostname: strip_local_paths.then(|| "dev-laptop-42".to_string()),
+        }
+    }
+
+    #[test]
+    fn repo_urls_hash_to_the_same_salted_id_in_any_form() {
+        let policy = policy(true, false);
+        let https = policy.redact_str("https://github.com/acme/payments");
+        assert!(https.starts_with("repo:"));
+        assert_eq!(policy.redact_str("git@github.com:acme/payments.git"), https);
+        assert_eq!(
+            policy.redact_str("pushed to https://github.com/acme/payments."),
+            format!("pushed to {}.", https)
+        );
+
+        let other_org = RedactionPolicy {
+            repo_salt: Some("other-salt".to_string()),
+            ..policy.clone()
+        };
+        assert_ne!(
+            other_org.redact_str("https://github.com/acme/payments"),
+            https
+        );
+    }
+
+    #[test]
+    fn local_paths_and_hostname_are_stripped_but_relative_paths_kept() {
+        let policy = policy(false, true);
+        assert_eq!(
+            policy.redact_str("failed to open /Users/dana/src/payments/.git/HEAD: denied"),
+            "failed to open <path>: denied"
+        );
+        assert_eq!(
+            policy.redact_str(r"cwd=C:\Users\dana\src\payments"),
+            "cwd=<path>"
+        );
+        assert_eq!(
+            policy.redact_str("daemon on dev-laptop-42"),
+            "daemon on <host>"
+        );
+        assert_eq!(policy.redact_str("src/lib.rs"), "src/lib.rs");
+        assert_eq!(
+            policy.redact_str("https://api.example.com/v1/metrics"),
+            "https://api.example.com/v1/metrics"
+        );
+    }
+
+    #[test]
+    fn values_are_redacted_in_nested_fields_arrays_and_keys() {
+        let policy = policy(true, true);
+        let mut payload = json!({
+            "repo_url": "https://gitlab.com/acme/infra",
+            "events": [{ "a": { "1": "git@gitlab.com:acme/infra.git" } }],
+            "files": { "/home/dana/infra/main.tf": { "added": 3 } },
+        });
+        policy.redact_value(&mut payload);
+
+        let repo = payload["repo_url"].as_str().unwrap();
+        assert!(repo.starts_with("repo:"));
+        assert_eq!(payload["events"][0]["a"]["1"], repo);
+        assert_eq!(payload["files"]["<path>"]["added"], 3);
+    }
+}
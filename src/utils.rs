@@ -1,5 +1,5 @@
 use crate::error::GitAiError;
-use std::io::IsTerminal;
+use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
@@ -104,6 +104,16 @@ pub fn spawn_internal_git_ai_subcommand(
         .is_ok()
 }
 
+/// `contents` in a new temp file that only this user can read, created
+/// under an unpredictable name and removed when dropped. For tools such as
+/// `openssl` that only take their input as a path.
+pub fn private_temp_file(contents: &[u8]) -> std::io::Result<tempfile::NamedTempFile> {
+    let mut file = tempfile::Builder::new().prefix("git-ai-").tempfile()?;
+    file.write_all(contents)?;
+    file.flush()?;
+    Ok(file)
+}
+
 pub fn is_interactive_terminal() -> bool {
     *IS_TERMINAL.get_or_init(|| std::io::stdin().is_terminal())
 }
@@ -283,6 +293,9 @@ pub fn print_superuser_warning() {
     );
 }
 
+/// How often `LockFile::acquire` retries while another process holds the lock
+const LOCK_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(20);
+
 /// A cross-platform exclusive file lock.
 ///
 /// Holds an exclusive advisory lock (Unix) or exclusive-access file handle (Windows)
@@ -299,6 +312,21 @@ impl LockFile {
         let file = try_lock_exclusive(path)?;
         Some(Self { _file: file })
     }
+
+    /// Wait up to `timeout` for an exclusive lock on the given path.
+    /// Returns `None` if another process still holds it when the time is up.
+    pub fn acquire(path: &std::path::Path, timeout: std::time::Duration) -> Option<Self> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            if let Some(lock) = Self::try_acquire(path) {
+                return Some(lock);
+            }
+            if std::time::Instant::now() >= deadline {
+                return None;
+            }
+            std::thread::sleep(LOCK_POLL_INTERVAL);
+        }
+    }
 }
 
 #[cfg(unix)]
@@ -486,6 +514,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lockfile_acquire_waits_for_holder() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join("test.lock");
+        let first = LockFile::try_acquire(&lock_path).expect("first acquire should succeed");
+        assert!(
+            LockFile::acquire(&lock_path, std::time::Duration::from_millis(50)).is_none(),
+            "acquire should time out while the lock is held"
+        );
+
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            drop(first);
+        });
+        let second = LockFile::acquire(&lock_path, std::time::Duration::from_secs(10));
+        releaser.join().unwrap();
+        assert!(
+            second.is_some(),
+            "acquire should succeed once the holder drops"
+        );
+    }
+
     #[test]
     fn test_lockfile_nonexistent_parent_returns_none() {
         let dir = tempfile::tempdir().unwrap();
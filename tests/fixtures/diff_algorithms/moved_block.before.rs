@@ -0,0 +1,25 @@
+fn title(out: &mut String) {
+    out.push_str("# Report");
+    out.push_str("# Report");
+    out.push_str("# Report");
+    out.push_str("=========");
+}
+
+fn spacing(out: &mut String) {
+    out.push('\n');
+    out.push('\n');
+    out.push_str("# Report");
+    out.push('\n');
+}
+
+fn footer(out: &mut String) {
+    out.push('\n');
+    out.push_str("# Report");
+}
+
+fn body(out: &mut String) {
+    out.push('\n');
+    out.push_str("=========");
+    out.push_str("# Report");
+    out.push_str("rows");
+}
@@ -0,0 +1,47 @@
+use crate::repos::test_file::ExpectedLineExt;
+use crate::repos::test_repo::TestRepo;
+use serde_json::Value;
+
+#[test]
+fn test_bisect_report_describes_first_bad_commit() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("calc.rs");
+
+    file.set_contents(crate::lines!["fn add(a: i32, b: i32) -> i32 { a + b }"]);
+    let good = repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.insert_at(
+        1,
+        crate::lines!["fn sub(a: i32, b: i32) -> i32 { a + b }".ai()],
+    );
+    let bad = repo.stage_all_and_commit("Add sub (#7)").unwrap();
+
+    file.insert_at(2, crate::lines!["// docs"]);
+    let head = repo.stage_all_and_commit("Document calc").unwrap();
+
+    repo.git(&["bisect", "start", &head.commit_sha, &good.commit_sha])
+        .unwrap();
+    repo.git(&["bisect", "bad"]).unwrap();
+    // HEAD is now at the midpoint, which is the AI commit
+    repo.git(&["bisect", "bad"]).unwrap();
+
+    let output = repo.git_ai(&["bisect-report", "--json"]).unwrap();
+    let report: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(report["commit"], bad.commit_sha.as_str());
+    assert_eq!(report["merge_request"], "#7");
+    assert!(report["stats"]["ai_additions"].as_u64().unwrap() > 0);
+    assert_eq!(report["sessions"].as_array().unwrap().len(), 1);
+
+    repo.git(&["bisect", "reset"]).unwrap();
+
+    // After reset the range still narrows to one commit
+    let range = format!("{}..{}", bad.commit_sha, good.commit_sha);
+    let output = repo.git_ai(&["bisect-report", &range, "--json"]).unwrap();
+    let report: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(report["commit"], bad.commit_sha.as_str());
+
+    let range = format!("{}..{}", head.commit_sha, good.commit_sha);
+    let output = repo.git_ai(&["bisect-report", &range, "--json"]).unwrap();
+    let candidates: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(candidates.as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,135 @@
+use crate::repos::test_file::ExpectedLineExt;
+use crate::repos::test_repo::TestRepo;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::process::Command;
+
+fn openssl_available() -> bool {
+    Command::new("openssl")
+        .arg("version")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+#[test]
+fn test_ci_attest_signs_provenance_for_merge() {
+    if !openssl_available() {
+        eprintln!("skipping: openssl CLI not available");
+        return;
+    }
+
+    let repo = TestRepo::new();
+    let mut base = repo.filename("base.txt");
+    base.set_contents(crate::lines!["base"]);
+    let base_sha = repo.stage_all_and_commit("base").unwrap().commit_sha;
+    repo.git(&["branch", "-M", "main"]).unwrap();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    let mut feature = repo.filename("feature.js");
+    feature.set_contents(crate::lines![
+        "export const a = 1;".ai(),
+        "export const b = 2;".ai(),
+        "export const c = 3;"
+    ]);
+    repo.stage_all_and_commit("Add feature").unwrap();
+
+    repo.git_og(&["checkout", "main"]).unwrap();
+    repo.git_og(&[
+        "merge",
+        "--no-ff",
+        "-m",
+        "Merge pull request #12 from acme/feature",
+        "feature",
+    ])
+    .unwrap();
+    let merge_sha = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    let dir = tempfile::tempdir().unwrap();
+    let key = dir.path().join("org.pem");
+    let public_key = dir.path().join("org.pub.pem");
+    let attestation = dir.path().join("merge.intoto.json");
+    let status = Command::new("openssl")
+        .args(["genpkey", "-algorithm", "ed25519", "-out"])
+        .arg(&key)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let status = Command::new("openssl")
+        .args(["pkey", "-pubout", "-in"])
+        .arg(&key)
+        .arg("-out")
+        .arg(&public_key)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    repo.git_ai(&[
+        "ci",
+        "attest",
+        "--merge-commit-sha",
+        &merge_sha,
+        "--base-sha",
+        &base_sha,
+        "--key",
+        key.to_str().unwrap(),
+        "--output",
+        attestation.to_str().unwrap(),
+    ])
+    .expect("ci attest should succeed");
+
+    let envelope: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&attestation).unwrap()).unwrap();
+    assert_eq!(envelope["payloadType"], "application/vnd.in-toto+json");
+    let payload = BASE64
+        .decode(envelope["payload"].as_str().unwrap())
+        .unwrap();
+    let statement: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+    assert_eq!(statement["subject"][0]["digest"]["gitCommit"], merge_sha);
+    let predicate = &statement["predicate"];
+    assert_eq!(
+        predicate["buildDefinition"]["externalParameters"]["mergeRequest"],
+        "#12"
+    );
+    let attribution = &predicate["runDetails"]["byproducts"][0]["annotations"];
+    assert_eq!(attribution["commits"], 1);
+    assert_eq!(attribution["commits_with_notes"], 1);
+    assert_eq!(attribution["ai_additions"], 2);
+
+    // The signature covers the DSSE pre-authentication encoding
+    let mut signed = format!(
+        "DSSEv1 {} application/vnd.in-toto+json {} ",
+        "application/vnd.in-toto+json".len(),
+        payload.len()
+    )
+    .into_bytes();
+    signed.extend_from_slice(&payload);
+    let message = dir.path().join("pae.bin");
+    let signature = dir.path().join("sig.bin");
+    std::fs::write(&message, signed).unwrap();
+    std::fs::write(
+        &signature,
+        BASE64
+            .decode(envelope["signatures"][0]["sig"].as_str().unwrap())
+            .unwrap(),
+    )
+    .unwrap();
+    let verify = Command::new("openssl")
+        .args(["pkeyutl", "-verify", "-rawin", "-pubin", "-inkey"])
+        .arg(&public_key)
+        .arg("-in")
+        .arg(&message)
+        .arg("-sigfile")
+        .arg(&signature)
+        .output()
+        .unwrap();
+    assert!(
+        verify.status.success(),
+        "{}",
+        String::from_utf8_lossy(&verify.stdout)
+    );
+}
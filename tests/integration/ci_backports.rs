@@ -0,0 +1,138 @@
+use crate::repos::test_file::ExpectedLineExt;
+use crate::repos::test_repo::TestRepo;
+
+#[test]
+fn test_ci_merge_into_release_branch_links_backport_to_source() {
+    let repo = TestRepo::new();
+    let mut base = repo.filename("base.txt");
+    base.set_contents(crate::lines!["base"]);
+    let base_sha = repo.stage_all_and_commit("base").unwrap().commit_sha;
+    repo.git(&["branch", "-M", "main"]).unwrap();
+    repo.git(&["branch", "release/1.0"]).unwrap();
+    repo.git_og(&["commit", "--allow-empty", "-m", "Start 2.0 development"])
+        .unwrap();
+
+    let mut fix = repo.filename("fix.js");
+    fix.set_contents(crate::lines!["export const fixed = true;".ai()]);
+    let source_sha = repo
+        .stage_all_and_commit("Fix crash (#5)")
+        .unwrap()
+        .commit_sha;
+    repo.git_og(&["update-ref", "refs/remotes/origin/main", "main"])
+        .unwrap();
+
+    // A bot cherry-picks the fix without git-ai, so the backport has no note
+    repo.git_og(&["checkout", "-b", "backport-5", "release/1.0"])
+        .unwrap();
+    repo.git_og(&["cherry-pick", &source_sha]).unwrap();
+    let head_sha = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+    assert!(repo.read_authorship_note(&head_sha).is_none());
+
+    repo.git_og(&["checkout", "release/1.0"]).unwrap();
+    repo.git_og(&["merge", "--no-ff", "-m", "Merge backport-5", "backport-5"])
+        .unwrap();
+    let merge_sha = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    let output = repo
+        .git_ai_with_env(
+            &[
+                "ci",
+                "local",
+                "merge",
+                "--merge-commit-sha",
+                &merge_sha,
+                "--base-ref",
+                "release/1.0",
+                "--head-ref",
+                "backport-5",
+                "--head-sha",
+                &head_sha,
+                "--base-sha",
+                &base_sha,
+                "--skip-fetch",
+                "--skip-push",
+            ],
+            &[("GIT_AI_RELEASE_BRANCHES", "release/*")],
+        )
+        .expect("ci local merge should succeed");
+
+    assert!(
+        output.contains(&format!(
+            "Backport {} <- {} on refs/remotes/origin/main (#5)",
+            head_sha, source_sha
+        )),
+        "{output}"
+    );
+    assert!(
+        output.contains("authorship linked for 1 backported commits"),
+        "{output}"
+    );
+    assert!(repo.read_authorship_note(&head_sha).is_some());
+    fix.assert_lines_and_blame(crate::lines!["export const fixed = true;".ai()]);
+}
+
+#[test]
+fn test_ci_merge_into_unconfigured_branch_skips_backport_matching() {
+    let repo = TestRepo::new();
+    let mut base = repo.filename("base.txt");
+    base.set_contents(crate::lines!["base"]);
+    let base_sha = repo.stage_all_and_commit("base").unwrap().commit_sha;
+    repo.git(&["branch", "-M", "main"]).unwrap();
+    repo.git(&["branch", "release/1.0"]).unwrap();
+    repo.git_og(&["commit", "--allow-empty", "-m", "Start 2.0 development"])
+        .unwrap();
+
+    let mut fix = repo.filename("fix.js");
+    fix.set_contents(crate::lines!["export const fixed = true;".ai()]);
+    let source_sha = repo.stage_all_and_commit("Fix crash").unwrap().commit_sha;
+    repo.git_og(&["update-ref", "refs/remotes/origin/main", "main"])
+        .unwrap();
+
+    repo.git_og(&["checkout", "-b", "backport", "release/1.0"])
+        .unwrap();
+    repo.git_og(&["cherry-pick", &source_sha]).unwrap();
+    let head_sha = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+    repo.git_og(&["checkout", "release/1.0"]).unwrap();
+    repo.git_og(&["merge", "--no-ff", "-m", "Merge backport", "backport"])
+        .unwrap();
+    let merge_sha = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    let output = repo
+        .git_ai(&[
+            "ci",
+            "local",
+            "merge",
+            "--merge-commit-sha",
+            &merge_sha,
+            "--base-ref",
+            "release/1.0",
+            "--head-ref",
+            "backport",
+            "--head-sha",
+            &head_sha,
+            "--base-sha",
+            &base_sha,
+            "--skip-fetch",
+            "--skip-push",
+        ])
+        .expect("ci local merge should succeed");
+
+    assert!(output.contains("skipped simple merge"), "{output}");
+    assert!(repo.read_authorship_note(&head_sha).is_none());
+}
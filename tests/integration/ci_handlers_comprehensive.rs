@@ -144,6 +144,99 @@ fn test_ci_github_run_noops_when_synchronize_has_no_previous_head() {
     );
 }
 
+/// Answer one HTTP request on loopback with `body` as JSON; the base URL
+fn serve_json_once(body: &'static str) -> String {
+    use std::io::{BufRead, BufReader};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+    let url = format!("http://{}", listener.local_addr().expect("addr"));
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("accept");
+        let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap_or(0) > 0 && line != "\r\n" {
+            line.clear();
+        }
+        let _ = write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+    });
+    url
+}
+
+#[test]
+fn test_ci_github_run_noops_when_push_is_not_a_merged_pr() {
+    let repo = TestRepo::new();
+    let api_url = serve_json_once("[]");
+
+    let output = repo
+        .git_ai_with_env(
+            &["ci", "github", "run", "--no-cleanup"],
+            &[
+                ("GITHUB_EVENT_NAME", "push"),
+                ("GITHUB_SHA", "cccccccccccccccccccccccccccccccccccccccc"),
+                ("GITHUB_REPOSITORY", "acme/repo"),
+                ("GITHUB_API_URL", &api_url),
+            ],
+        )
+        .expect("github ci run should no-op successfully");
+
+    assert!(
+        output.contains("No merged PR found for this commit"),
+        "Expected no-op output, got: {}",
+        output
+    );
+}
+
+#[test]
+fn test_ci_github_install_print_writes_nothing() {
+    let repo = TestRepo::new();
+    let output = repo
+        .git_ai(&["ci", "github", "install", "--print"])
+        .expect("install --print should succeed");
+    assert!(output.contains("git-ai ci github run"), "{}", output);
+    assert!(!repo.path().join(".github").exists());
+}
+
+#[test]
+fn test_ci_bitbucket_run_noops_when_commit_is_not_a_merged_pr() {
+    let repo = TestRepo::new();
+    let api_url = serve_json_once(r#"{"pagelen": 50, "values": []}"#);
+
+    let output = repo
+        .git_ai_with_env(
+            &["ci", "bitbucket", "run", "--no-cleanup"],
+            &[
+                (
+                    "BITBUCKET_COMMIT",
+                    "cccccccccccccccccccccccccccccccccccccccc",
+                ),
+                ("BITBUCKET_REPO_FULL_NAME", "acme/repo"),
+                ("GIT_AI_BITBUCKET_TOKEN", "token"),
+                ("GIT_AI_BITBUCKET_API_URL", &api_url),
+            ],
+        )
+        .expect("bitbucket ci run should no-op successfully");
+
+    assert!(
+        output.contains("No recent PR found corresponding to this commit"),
+        "Expected no-op output, got: {}",
+        output
+    );
+}
+
+#[test]
+fn test_ci_bitbucket_install_prints_pipeline_step() {
+    let repo = TestRepo::new();
+    let output = repo
+        .git_ai(&["ci", "bitbucket", "install"])
+        .expect("bitbucket install should succeed");
+    assert!(output.contains("bitbucket-pipelines.yml"), "{}", output);
+    assert!(output.contains("git-ai ci bitbucket run"), "{}", output);
+}
+
 #[test]
 fn test_ci_github_run_fetches_missing_previous_head_after_force_push() {
     let ci_repo = TestRepo::new();
@@ -0,0 +1,833 @@
+//! `git-ai clients check --json` / `clients install --json` emit one object
+//! per git client for fleet tooling to parse.
+
+use crate::repos::test_repo::{TestRepo, get_binary_path};
+use serde_json::Value;
+use std::fs;
+
+/// Run `git-ai <args>`, which must exit with `code` (0 up to date, 1
+/// changes, 2 failed, 3 no clients); its stdout
+fn git_ai_exit(repo: &TestRepo, args: &[&str], envs: &[(&str, &str)], code: i32) -> String {
+    let output = repo
+        .git_ai_command_without_pre_sync_for_test(args, envs)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert_eq!(
+        output.status.code(),
+        Some(code),
+        "{args:?}: {stdout}{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    stdout
+}
+
+/// `git_ai_exit`, parsing the JSON it prints
+fn git_ai_json(repo: &TestRepo, args: &[&str], envs: &[(&str, &str)], code: i32) -> Value {
+    let out = git_ai_exit(repo, args, envs, code);
+    serde_json::from_str(out.trim())
+        .unwrap_or_else(|e| panic!("{args:?} returned non-JSON {out:?}: {e}"))
+}
+
+fn client<'a>(reports: &'a Value, id: &str) -> &'a Value {
+    reports
+        .as_array()
+        .expect("reports are an array")
+        .iter()
+        .find(|report| report["id"] == id)
+        .unwrap_or_else(|| panic!("no report for {id}: {reports}"))
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_clients_check_and_install_json_reports() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let gitfiend_dir = config_home.join("GitFiend");
+    fs::create_dir_all(&gitfiend_dir).unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let run = |args: &[&str], code: i32| git_ai_json(&repo, args, &envs, code);
+
+    let checked = run(&["clients", "check", "--json"], 1);
+    let gitfiend = client(&checked, "gitfiend");
+    assert_eq!(gitfiend["name"], "GitFiend");
+    assert_eq!(gitfiend["client_installed"], true);
+    assert_eq!(gitfiend["prefs_configured"], false);
+    assert_eq!(gitfiend["prefs_up_to_date"], false);
+    assert_eq!(gitfiend["status"], "pending");
+    assert!(gitfiend["diff"].is_string(), "{gitfiend}");
+    // check changes nothing
+    assert!(!gitfiend_dir.join("config.json").exists());
+
+    let installed = run(&["clients", "install", "--json"], 1);
+    assert_eq!(client(&installed, "gitfiend")["status"], "installed");
+    assert!(gitfiend_dir.join("config.json").exists());
+
+    let rechecked = run(&["clients", "check", "--json"], 0);
+    let gitfiend = client(&rechecked, "gitfiend");
+    assert_eq!(gitfiend["prefs_configured"], true);
+    assert_eq!(gitfiend["status"], "already_installed");
+    assert!(gitfiend["diff"].is_null());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_install_verify_reports_verified() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    fs::create_dir_all(config_home.join("GitFiend")).unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let run = |args: &[&str], code: i32| git_ai_json(&repo, args, &envs, code);
+
+    let installed = run(&["clients", "install", "--json"], 1);
+    assert!(client(&installed, "gitfiend")["verified"].is_null());
+
+    // Already up to date clients are verified too
+    let verified = run(&["clients", "install", "--verify", "--json"], 0);
+    let gitfiend = client(&verified, "gitfiend");
+    assert_eq!(gitfiend["status"], "already_installed");
+    assert_eq!(gitfiend["verified"], true, "{gitfiend}");
+
+    let err = repo
+        .git_ai_with_env(&["clients", "install", "--verify", "--dry-run"], &envs)
+        .expect_err("--verify needs a real install");
+    assert!(err.contains("can't be combined"), "{err}");
+    // A usage error exits like a failure
+    git_ai_exit(
+        &repo,
+        &["clients", "install", "--verify", "--dry-run"],
+        &envs,
+        2,
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_clients_toml_defines_custom_clients() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let acme_dir = config_home.join("acme");
+    fs::create_dir_all(&acme_dir).unwrap();
+    fs::create_dir_all(config_home.join("git-ai")).unwrap();
+    let clients_toml = config_home.join("git-ai").join("clients.toml");
+    fs::write(
+        &clients_toml,
+        format!(
+            "[[client]]\nid = \"acme\"\nname = \"Acme Git\"\nsettings_path = {:?}\nformat = \"yaml\"\nkeys = {{ \"git.executable\" = \"{{shim}}\" }}\n",
+            acme_dir.join("config.yml")
+        ),
+    )
+    .unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let run = |args: &[&str], code: i32| git_ai_json(&repo, args, &envs, code);
+
+    let checked = run(&["clients", "check", "--json"], 1);
+    let acme = client(&checked, "acme");
+    assert_eq!(acme["name"], "Acme Git");
+    assert_eq!(acme["status"], "pending");
+
+    let installed = run(&["clients", "install", "--json"], 1);
+    assert_eq!(client(&installed, "acme")["status"], "installed");
+    let written = fs::read_to_string(acme_dir.join("config.yml")).unwrap();
+    assert!(written.starts_with("git:\n  executable: '"), "{written}");
+
+    let rechecked = run(&["clients", "check", "--json"], 0);
+    assert_eq!(client(&rechecked, "acme")["status"], "already_installed");
+
+    // A broken clients.toml is reported as a failed client
+    fs::write(&clients_toml, "[[client]]\nid = \"gitfiend\"\n").unwrap();
+    let broken = run(&["clients", "check", "--json"], 2);
+    let entry = client(&broken, "clients-toml");
+    assert_eq!(entry["status"], "failed", "{entry}");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_running_client_is_skipped_unless_forced() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let acme_dir = config_home.join("acme");
+    fs::create_dir_all(&acme_dir).unwrap();
+    fs::create_dir_all(config_home.join("git-ai")).unwrap();
+
+    // A stand-in for the client app, under a name no other process has
+    // (and short enough for ps's 15-character comm)
+    let process_name = format!("acme{}", std::process::id() % 100_000);
+    let app = repo.test_home_path().join(&process_name);
+    fs::copy("/bin/sleep", &app).unwrap();
+    let mut app_process = std::process::Command::new(&app).arg("60").spawn().unwrap();
+
+    fs::write(
+        config_home.join("git-ai").join("clients.toml"),
+        format!(
+            "[[client]]\nid = \"acme\"\nname = \"Acme Git\"\nsettings_path = {:?}\nformat = \"json\"\nkeys = {{ gitPath = \"{{shim}}\" }}\nprocess_names = [{:?}]\n",
+            acme_dir.join("settings.json"),
+            process_name
+        ),
+    )
+    .unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let parse = |out: &str| -> Value {
+        let json_end = out.rfind(']').expect("JSON reports in output") + 1;
+        serde_json::from_str(out[..json_end].trim())
+            .unwrap_or_else(|e| panic!("non-JSON {out:?}: {e}"))
+    };
+
+    let checked = parse(&git_ai_exit(
+        &repo,
+        &["clients", "check", "--json"],
+        &envs,
+        1,
+    ));
+    let acme = client(&checked, "acme");
+    assert_eq!(acme["running"], true, "{acme}");
+    assert_eq!(acme["status"], "pending");
+
+    // install refuses while the client runs
+    let refused = parse(&git_ai_exit(
+        &repo,
+        &["clients", "install", "--json"],
+        &envs,
+        2,
+    ));
+    let acme = client(&refused, "acme");
+    assert_eq!(acme["status"], "failed", "{acme}");
+    assert!(
+        acme["message"].as_str().unwrap_or("").contains("--force"),
+        "{acme}"
+    );
+    assert!(!acme_dir.join("settings.json").exists());
+
+    let forced = parse(&git_ai_exit(
+        &repo,
+        &["clients", "install", "--force", "--json"],
+        &envs,
+        1,
+    ));
+    let acme = client(&forced, "acme");
+    assert_eq!(acme["status"], "installed", "{acme}");
+    assert_eq!(acme["running"], true);
+    assert!(acme_dir.join("settings.json").exists());
+
+    app_process.kill().ok();
+    app_process.wait().ok();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_clients_watch_reapplies_reset_preferences() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let gitfiend_dir = config_home.join("GitFiend");
+    fs::create_dir_all(&gitfiend_dir).unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+
+    git_ai_exit(&repo, &["clients", "install", "--json"], &envs, 1);
+    let config_path = gitfiend_dir.join("config.json");
+    let configured = fs::read_to_string(&config_path).unwrap();
+
+    // A client update wipes the git path
+    let mut reset: Value = serde_json::from_str(&configured).unwrap();
+    reset["gitPath"] = Value::String(String::new());
+    fs::write(&config_path, reset.to_string()).unwrap();
+
+    // Installed after git-ai configured the others, so never managed
+    let acme_dir = config_home.join("acme");
+    fs::create_dir_all(&acme_dir).unwrap();
+    fs::create_dir_all(config_home.join("git-ai")).unwrap();
+    fs::write(
+        config_home.join("git-ai").join("clients.toml"),
+        format!(
+            "[[client]]\nid = \"acme\"\nname = \"Acme Git\"\nsettings_path = {:?}\nformat = \"json\"\nkeys = {{ gitPath = \"{{shim}}\" }}\n",
+            acme_dir.join("settings.json")
+        ),
+    )
+    .unwrap();
+
+    let out = repo
+        .git_ai_with_env(&["clients", "watch", "--once"], &envs)
+        .expect("watch");
+    assert!(
+        out.contains("GitFiend: preferences were reset; re-applied"),
+        "{out}"
+    );
+    let reapplied: Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    let original: Value = serde_json::from_str(&configured).unwrap();
+    assert_eq!(reapplied["gitPath"], original["gitPath"]);
+    assert!(!out.contains("Acme"), "{out}");
+    assert!(!acme_dir.join("settings.json").exists());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_system_scope_only_checks_clients_with_machine_wide_settings() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    fs::create_dir_all(config_home.join("GitFiend")).unwrap();
+    let system_dir = repo.test_home_path().join("etc-acme");
+    fs::create_dir_all(&system_dir).unwrap();
+    fs::create_dir_all(config_home.join("git-ai")).unwrap();
+    fs::write(
+        config_home.join("git-ai").join("clients.toml"),
+        format!(
+            "[[client]]\nid = \"acme\"\nname = \"Acme Git\"\nsettings_path = \"~/.config/acme/settings.json\"\nsystem_settings_path = {:?}\nformat = \"json\"\nkeys = {{ gitPath = \"{{shim}}\" }}\n",
+            system_dir.join("settings.json")
+        ),
+    )
+    .unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+
+    let checked = git_ai_json(
+        &repo,
+        &["clients", "check", "--scope", "system", "--json"],
+        &envs,
+        1,
+    );
+    let ids: Vec<&str> = checked
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|report| report["id"].as_str())
+        .collect();
+    assert_eq!(ids, vec!["acme"], "{checked}");
+    assert_eq!(client(&checked, "acme")["status"], "pending");
+
+    let err = repo
+        .git_ai_with_env(&["clients", "check", "--scope", "machine"], &envs)
+        .expect_err("unknown scope");
+    assert!(err.contains("invalid --scope"), "{err}");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_flatpak_client_is_configured_in_its_sandbox() {
+    let repo = TestRepo::new();
+    let home = repo.test_home_path();
+    let config_home = home.join(".config");
+    fs::create_dir_all(&config_home).unwrap();
+    let app_id = "com.sublimemerge.App";
+    let sandbox_dir = home
+        .join(".var/app")
+        .join(app_id)
+        .join("config/sublime-merge");
+    fs::create_dir_all(&sandbox_dir).unwrap();
+    let flatpak_root = home.join(".local/share/flatpak");
+    let metadata_dir = flatpak_root.join("app").join(app_id).join("current/active");
+    fs::create_dir_all(&metadata_dir).unwrap();
+    fs::write(
+        metadata_dir.join("metadata"),
+        "[Application]\nname=com.sublimemerge.App\n\n[Context]\nshared=network;ipc;\nfilesystems=xdg-download;\n",
+    )
+    .unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let run = |args: &[&str], code: i32| git_ai_json(&repo, args, &envs, code);
+
+    // The sandbox can't see the shim, so there's nothing to point it at yet
+    // Nothing else is installed, so there's no client to configure
+    let checked = run(&["clients", "check", "--json"], 3);
+    let sublime = client(&checked, "sublime-merge");
+    assert_eq!(sublime["status"], "unsupported");
+    let reason = sublime["message"].as_str().unwrap_or_default();
+    assert!(reason.contains("flatpak override"), "{sublime}");
+
+    fs::create_dir_all(flatpak_root.join("overrides")).unwrap();
+    fs::write(
+        flatpak_root.join("overrides").join(app_id),
+        "[Context]\nfilesystems=host;\n",
+    )
+    .unwrap();
+    let installed = run(&["clients", "install", "--json"], 1);
+    assert_eq!(client(&installed, "sublime-merge")["status"], "installed");
+    let prefs = sandbox_dir.join("Packages/User/Preferences.sublime-settings");
+    assert!(
+        fs::read_to_string(&prefs).unwrap().contains("git_binary"),
+        "{installed}"
+    );
+    assert!(!config_home.join("sublime-merge").exists());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_install_emit_patch_writes_applicable_patches() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let gitfiend_dir = config_home.join("GitFiend");
+    fs::create_dir_all(&gitfiend_dir).unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let patches = repo.test_home_path().join("patches");
+
+    let out = git_ai_exit(
+        &repo,
+        &[
+            "clients",
+            "install",
+            "--emit-patch",
+            patches.to_str().unwrap(),
+        ],
+        &envs,
+        1,
+    );
+    assert!(out.contains("Wrote "), "{out}");
+    // Nothing is installed, only described
+    assert!(!gitfiend_dir.join("config.json").exists());
+    let patch = patches.join("gitfiend.patch");
+    let diff = fs::read_to_string(&patch).unwrap();
+    assert!(diff.starts_with("--- /dev/null\n+++ b/"), "{diff}");
+    assert!(diff.contains("\n@@ -0,0 +1"), "{diff}");
+
+    // The patch applies from the filesystem root and leaves the client configured
+    let applied = std::process::Command::new("git")
+        .args(["apply", "-p1", patch.to_str().unwrap()])
+        .current_dir("/")
+        .output()
+        .unwrap();
+    assert!(
+        applied.status.success(),
+        "{}",
+        String::from_utf8_lossy(&applied.stderr)
+    );
+    let checked = git_ai_json(&repo, &["clients", "check", "--json"], &envs, 0);
+    assert_eq!(client(&checked, "gitfiend")["prefs_configured"], true);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_dry_run_summary_lists_clients_keys_and_files() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let gitfiend_dir = config_home.join("GitFiend");
+    fs::create_dir_all(&gitfiend_dir).unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let config_json = gitfiend_dir.join("config.json");
+
+    let out = git_ai_exit(&repo, &["clients", "check"], &envs, 1);
+    assert!(out.contains("Dry-run summary: "), "{out}");
+    assert!(
+        out.contains(&format!("{}: gitPath", config_json.display())),
+        "{out}"
+    );
+
+    let summary = git_ai_json(
+        &repo,
+        &["clients", "install", "--dry-run", "--summary", "--json"],
+        &envs,
+        1,
+    );
+    assert!(
+        summary["clients_to_change"].as_u64().unwrap() >= 1,
+        "{summary}"
+    );
+    assert!(
+        summary["files_touched"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from(config_json.to_str().unwrap())),
+        "{summary}"
+    );
+    let gitfiend = client(&summary["clients"], "gitfiend");
+    assert_eq!(gitfiend["files"][0]["path"], config_json.to_str().unwrap());
+    assert_eq!(gitfiend["files"][0]["keys"][0], "gitPath");
+    assert!(!config_json.exists());
+
+    // Only dry runs have a summary
+    git_ai_exit(&repo, &["clients", "install", "--summary"], &envs, 2);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_preference_changes_are_audited() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let gitfiend_dir = config_home.join("GitFiend");
+    fs::create_dir_all(&gitfiend_dir).unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let audit_log = repo
+        .test_home_path()
+        .join(".git-ai")
+        .join("internal")
+        .join("audit.log");
+    let entries = || -> Vec<Value> {
+        fs::read_to_string(&audit_log)
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    };
+
+    // Dry runs change nothing, so record nothing
+    git_ai_exit(&repo, &["clients", "check"], &envs, 1);
+    assert!(entries().is_empty());
+
+    git_ai_exit(&repo, &["clients", "install", "--json"], &envs, 1);
+    let installed = entries();
+    let entry = installed
+        .iter()
+        .find(|entry| entry["client"] == "gitfiend")
+        .unwrap_or_else(|| panic!("no gitfiend entry: {installed:?}"));
+    assert_eq!(entry["action"], "install");
+    assert!(entry["timestamp"].is_string());
+    assert!(entry["user"].is_string());
+    let change = &entry["changes"][0];
+    assert_eq!(change["key"], "gitPath");
+    assert_eq!(
+        change["file"],
+        gitfiend_dir.join("config.json").to_str().unwrap()
+    );
+    assert!(change["before"].is_null());
+    assert!(
+        change["after"].as_str().unwrap().ends_with("git"),
+        "{change}"
+    );
+
+    git_ai_exit(
+        &repo,
+        &["clients", "uninstall", "--all", "--json"],
+        &envs,
+        1,
+    );
+    let entries = entries();
+    // Appended, never rewritten
+    assert_eq!(&entries[..installed.len()], &installed[..]);
+    assert!(
+        entries[installed.len()..]
+            .iter()
+            .any(|entry| entry["client"] == "gitfiend" && entry["action"] == "uninstall"),
+        "{entries:?}"
+    );
+}
+
+#[test]
+fn test_install_interactive_needs_a_terminal() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+
+    let err = repo
+        .git_ai_with_env(&["clients", "install", "--interactive"], &envs)
+        .expect_err("no terminal in tests");
+    assert!(err.contains("needs a terminal"), "{err}");
+
+    let err = repo
+        .git_ai_with_env(&["clients", "install", "-i", "--json"], &envs)
+        .expect_err("--json can't be interactive");
+    assert!(err.contains("can't be combined"), "{err}");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_excluded_clients_are_reported_but_left_alone() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let gitfiend_dir = config_home.join("GitFiend");
+    fs::create_dir_all(&gitfiend_dir).unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    repo.git_ai(&["config", "set", "clients.exclude", "gitfiend"])
+        .expect("set clients.exclude");
+
+    // The only client is excluded, so none is left to configure
+    let installed = git_ai_json(&repo, &["clients", "install", "--json"], &envs, 3);
+    let gitfiend = client(&installed, "gitfiend");
+    assert_eq!(gitfiend["status"], "excluded");
+    assert!(
+        gitfiend["message"]
+            .as_str()
+            .is_some_and(|message| message.contains("clients.exclude")),
+        "{gitfiend}"
+    );
+    assert!(!gitfiend_dir.join("config.json").exists());
+
+    // An include list leaves out everything else
+    repo.git_ai(&["config", "unset", "clients.exclude"])
+        .expect("unset clients.exclude");
+    repo.git_ai(&["config", "set", "clients.include", "fork"])
+        .expect("set clients.include");
+    let checked = git_ai_json(&repo, &["clients", "check", "--json"], &envs, 3);
+    assert_eq!(client(&checked, "gitfiend")["status"], "excluded");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_uninstall_all_reverts_clients_and_removes_the_shim() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let gitfiend_dir = config_home.join("GitFiend");
+    fs::create_dir_all(&gitfiend_dir).unwrap();
+    // A per-user install, so the shim lands in the test home
+    let bin_dir = repo.test_home_path().join(".git-ai").join("bin");
+    fs::create_dir_all(&bin_dir).unwrap();
+    let binary = bin_dir.join("git-ai");
+    fs::copy(get_binary_path(), &binary).unwrap();
+    let shim = bin_dir.join("git");
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let run = |args: &[&str]| -> (Option<i32>, Value) {
+        let template = repo.git_ai_command_without_pre_sync_for_test(args, &envs);
+        let mut command = std::process::Command::new(&binary);
+        command.args(template.get_args()).current_dir(repo.path());
+        for (key, value) in template.get_envs() {
+            match value {
+                Some(value) => command.env(key, value),
+                None => command.env_remove(key),
+            };
+        }
+        let output = command.output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json = serde_json::from_str(stdout.trim())
+            .unwrap_or_else(|e| panic!("{args:?} returned non-JSON {stdout:?}: {e}"));
+        (output.status.code(), json)
+    };
+
+    let (code, installed) = run(&["clients", "install", "--json"]);
+    assert_eq!(code, Some(1), "{installed}");
+    assert_eq!(client(&installed, "gitfiend")["status"], "installed");
+    assert!(fs::symlink_metadata(&shim).is_ok());
+
+    let (code, pending) = run(&["clients", "uninstall", "--all", "--dry-run", "--json"]);
+    assert_eq!(code, Some(1), "{pending}");
+    assert_eq!(client(&pending["clients"], "gitfiend")["status"], "pending");
+    assert_eq!(pending["shim"]["status"], "pending");
+    assert!(fs::symlink_metadata(&shim).is_ok());
+
+    let (code, reverted) = run(&["clients", "uninstall", "--all", "--json"]);
+    assert_eq!(code, Some(1), "{reverted}");
+    let gitfiend = client(&reverted["clients"], "gitfiend");
+    assert_eq!(gitfiend["status"], "reverted");
+    assert!(
+        gitfiend["diff"].as_str().unwrap().contains("gitPath"),
+        "{gitfiend}"
+    );
+    assert_eq!(reverted["shim"]["status"], "removed");
+    assert!(fs::symlink_metadata(&shim).is_err());
+    assert!(binary.exists());
+    let config = fs::read_to_string(gitfiend_dir.join("config.json")).unwrap();
+    assert!(!config.contains(".git-ai"), "{config}");
+    // Nothing left to revert
+    let (code, _) = run(&["clients", "uninstall", "--all", "--json"]);
+    assert_eq!(code, Some(0));
+
+    let err = repo
+        .git_ai_with_env(&["clients", "uninstall"], &envs)
+        .expect_err("uninstall needs --all");
+    assert!(err.contains("--all"), "{err}");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_clients_drift_reports_a_stale_shim() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let gitfiend_dir = config_home.join("GitFiend");
+    fs::create_dir_all(&gitfiend_dir).unwrap();
+    // Configured before the home directory moved
+    fs::write(
+        gitfiend_dir.join("config.json"),
+        r#"{"gitPath": "/home/old/.git-ai/bin/git"}"#,
+    )
+    .unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let run = |args: &[&str], code: i32| git_ai_json(&repo, args, &envs, code);
+
+    let drift = run(&["clients", "drift", "--json"], 1);
+    let gitfiend = client(&drift, "gitfiend");
+    assert_eq!(gitfiend["state"], "stale_shim", "{gitfiend}");
+    assert_eq!(gitfiend["drift"], true);
+    assert_eq!(gitfiend["configured_git_path"], "/home/old/.git-ai/bin/git");
+    assert_eq!(gitfiend["configured_git_path_exists"], false);
+    assert_ne!(
+        gitfiend["expected_git_path"],
+        gitfiend["configured_git_path"]
+    );
+
+    let out = git_ai_exit(&repo, &["clients", "drift"], &envs, 1);
+    assert!(out.contains("stale_shim"), "{out}");
+
+    run(&["clients", "install", "--json"], 1);
+    let drift = run(&["clients", "drift", "--json"], 0);
+    let gitfiend = client(&drift, "gitfiend");
+    assert_eq!(gitfiend["state"], "in_sync", "{gitfiend}");
+    assert_eq!(
+        gitfiend["configured_git_path"],
+        gitfiend["expected_git_path"]
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_client_discovery_is_cached_until_no_cache() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    fs::create_dir_all(config_home.join("sublime-merge")).unwrap();
+    let bin = repo.test_home_path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let smerge = bin.join("smerge");
+    let write_smerge = |build: u32| {
+        fs::write(
+            &smerge,
+            format!("#!/bin/sh\necho 'Sublime Merge Build {build}'\n"),
+        )
+        .unwrap();
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&smerge, fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let envs = [
+        ("XDG_CONFIG_HOME", config_home.to_str().unwrap()),
+        ("PATH", path.as_str()),
+    ];
+    let sublime_status = |args: &[&str], code: i32| -> Value {
+        let reports = git_ai_json(&repo, args, &envs, code);
+        client(&reports, "sublime-merge")["status"].clone()
+    };
+
+    write_smerge(1119);
+    assert_eq!(
+        sublime_status(&["clients", "check", "--json"], 3),
+        "unsupported"
+    );
+
+    // Upgraded, but the cached build is still trusted
+    write_smerge(2096);
+    assert_eq!(
+        sublime_status(&["clients", "check", "--json"], 3),
+        "unsupported"
+    );
+    assert_eq!(
+        sublime_status(&["clients", "check", "--json", "--no-cache"], 1),
+        "pending"
+    );
+    // --no-cache refreshed the cache too
+    assert_eq!(
+        sublime_status(&["clients", "check", "--json"], 1),
+        "pending"
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_all_users_checks_every_profile() {
+    let repo = TestRepo::new();
+    let users = repo.test_home_path().join("Users");
+    fs::create_dir_all(users.join("alice").join(".config").join("GitFiend")).unwrap();
+    fs::create_dir_all(users.join("bob")).unwrap();
+    fs::create_dir_all(users.join("Shared")).unwrap();
+    let config_home = repo.test_home_path().join(".config");
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let users_dir = users.to_str().unwrap();
+
+    let profiles = git_ai_json(
+        &repo,
+        &[
+            "clients",
+            "check",
+            "--all-users",
+            "--users-dir",
+            users_dir,
+            "--json",
+        ],
+        &envs,
+        1,
+    );
+    let profile = |user: &str| -> &Value {
+        profiles
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|profile| profile["user"] == user)
+            .unwrap_or_else(|| panic!("no profile for {user}: {profiles}"))
+    };
+    assert_eq!(profiles.as_array().unwrap().len(), 2, "{profiles}");
+    let alice = profile("alice");
+    assert_eq!(alice["exit_code"], 1, "{alice}");
+    assert_eq!(client(&alice["clients"], "gitfiend")["status"], "pending");
+    let bob = profile("bob");
+    assert_eq!(bob["exit_code"], 3, "{bob}");
+    assert_eq!(client(&bob["clients"], "gitfiend")["status"], "not_found");
+    // Only checked
+    assert!(!users.join("alice/.config/GitFiend/config.json").exists());
+
+    let out = git_ai_exit(
+        &repo,
+        &["clients", "check", "--all-users", "--users-dir", users_dir],
+        &envs,
+        1,
+    );
+    assert!(out.contains("User alice"), "{out}");
+    assert!(out.contains("User bob"), "{out}");
+
+    git_ai_exit(
+        &repo,
+        &["clients", "install", "--all-users", "--interactive"],
+        &envs,
+        2,
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_shim_path_template_is_resolved_per_profile() {
+    let repo = TestRepo::new();
+    let users = repo.test_home_path().join("Users");
+    fs::create_dir_all(users.join("alice").join(".config").join("GitFiend")).unwrap();
+    fs::create_dir_all(users.join("bob").join(".config").join("GitFiend")).unwrap();
+    let config_home = repo.test_home_path().join(".config");
+    fs::create_dir_all(config_home.join("GitFiend")).unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+
+    let profiles = git_ai_json(
+        &repo,
+        &[
+            "clients",
+            "check",
+            "--all-users",
+            "--users-dir",
+            users.to_str().unwrap(),
+            "--shim-path",
+            "${HOME}/.git-ai/bin/git",
+            "--json",
+        ],
+        &envs,
+        1,
+    );
+    for user in ["alice", "bob"] {
+        let profile = profiles
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|profile| profile["user"] == user)
+            .unwrap_or_else(|| panic!("no profile for {user}: {profiles}"));
+        let diff = client(&profile["clients"], "gitfiend")["diff"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let shim = users.join(user).join(".git-ai/bin/git");
+        assert!(diff.contains(shim.to_str().unwrap()), "{diff}");
+    }
+
+    // A given shim is never created, so it has to exist
+    let missing = repo.test_home_path().join("elsewhere").join("git");
+    let reports = git_ai_json(
+        &repo,
+        &[
+            "clients",
+            "install",
+            "--shim-path",
+            missing.to_str().unwrap(),
+            "--json",
+        ],
+        &envs,
+        2,
+    );
+    assert_eq!(client(&reports, "gitfiend")["status"], "failed");
+    assert!(!config_home.join("GitFiend").join("config.json").exists());
+
+    git_ai_exit(
+        &repo,
+        &["clients", "check", "--shim-path", "${NO_SUCH_VARIABLE}/git"],
+        &envs,
+        2,
+    );
+}
@@ -0,0 +1,52 @@
+//! `git-ai clients restore` reverts a client's preferences to the snapshot
+//! taken before install-hooks changed them.
+
+use crate::repos::test_repo::TestRepo;
+use git_ai::mdm::prefs_backup::BackupSession;
+use git_ai::mdm::utils::write_atomic;
+use std::fs;
+
+#[test]
+fn test_clients_restore_reverts_the_newest_snapshot() {
+    let repo = TestRepo::new();
+    let settings = repo.test_home_path().join("gitfiend").join("settings.json");
+    fs::create_dir_all(settings.parent().unwrap()).unwrap();
+    fs::write(&settings, "{\"gitPath\":\"/opt/git/bin/git\"}").unwrap();
+
+    let backups = repo
+        .test_home_path()
+        .join(".git-ai")
+        .join("internal")
+        .join("backups");
+    {
+        let _session = BackupSession::begin(&backups, "gitfiend");
+        write_atomic(&settings, b"{\"gitPath\":\"/home/dev/.git-ai/bin/git\"}").unwrap();
+    }
+
+    let listed = repo
+        .git_ai(&["clients", "restore", "gitfiend", "--list"])
+        .unwrap();
+    assert_eq!(listed.lines().count(), 1, "{}", listed);
+
+    let output = repo.git_ai(&["clients", "restore", "gitfiend"]).unwrap();
+    assert!(
+        output.contains("Restored gitfiend preferences"),
+        "{}",
+        output
+    );
+    assert_eq!(
+        fs::read_to_string(&settings).unwrap(),
+        "{\"gitPath\":\"/opt/git/bin/git\"}"
+    );
+}
+
+#[test]
+fn test_clients_restore_reports_missing_backups_and_unknown_clients() {
+    let repo = TestRepo::new();
+
+    let err = repo.git_ai(&["clients", "restore", "fork"]).unwrap_err();
+    assert!(err.contains("no backups for fork"), "{}", err);
+
+    let err = repo.git_ai(&["clients", "restore", "notepad"]).unwrap_err();
+    assert!(err.contains("unknown client 'notepad'"), "{}", err);
+}
@@ -0,0 +1,84 @@
+//! Conventional Commits checks on commits made through the git shim.
+
+#![cfg(unix)]
+
+use crate::repos::test_repo::{TestRepo, get_binary_path};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Run `git <args>` through the shim: the git-ai binary invoked as `git`
+fn shim_git(repo: &TestRepo, args: &[&str], envs: &[(&str, &str)]) -> Output {
+    let shim_dir = repo.test_home_path().join("shim");
+    let shim = shim_dir.join("git");
+    if !shim.exists() {
+        fs::create_dir_all(&shim_dir).unwrap();
+        std::os::unix::fs::symlink(get_binary_path(), &shim).unwrap();
+    }
+    let template = repo.git_ai_command_without_pre_sync_for_test(&[], envs);
+    let mut command = Command::new(&shim);
+    command.args(args).current_dir(repo.path());
+    for (key, value) in template.get_envs() {
+        match value {
+            Some(value) => command.env(key, value),
+            None => command.env_remove(key),
+        };
+    }
+    command.output().unwrap()
+}
+
+/// An editor script `name` that replaces the message file with `message`
+fn editor_writing(dir: &Path, name: &str, message: &str) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    let script = dir.join(name);
+    fs::write(
+        &script,
+        format!("#!/bin/sh\nprintf '%s\\n' '{}' > \"$1\"\n", message),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    script.to_str().unwrap().to_string()
+}
+
+fn head(repo: &TestRepo) -> Option<String> {
+    repo.git(&["rev-parse", "--verify", "-q", "HEAD"])
+        .ok()
+        .map(|out| out.trim().to_string())
+}
+
+#[test]
+fn test_strict_mode_rejects_editor_messages_before_committing() {
+    let repo = TestRepo::new();
+    repo.git_ai(&["config", "set", "commit_lint.mode", "strict"])
+        .expect("set commit_lint.mode");
+    fs::write(repo.path().join("a.txt"), "a\n").unwrap();
+    repo.git(&["add", "a.txt"]).expect("add");
+    let editors = repo.test_home_path().to_path_buf();
+
+    // The first commit on a branch: nothing is created
+    let bad = editor_writing(&editors, "bad-editor", "added a file");
+    let output = shim_git(&repo, &["commit"], &[("GIT_EDITOR", &bad)]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{stderr}");
+    assert!(stderr.contains("commit aborted"), "{stderr}");
+    assert!(stderr.contains("COMMIT_EDITMSG"), "{stderr}");
+    assert_eq!(head(&repo), None);
+
+    let good = editor_writing(&editors, "good-editor", "feat: add a file");
+    let output = shim_git(&repo, &["commit"], &[("GIT_EDITOR", &good)]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let first = head(&repo).expect("committed");
+    assert_eq!(
+        repo.git(&["log", "-1", "--format=%s"]).unwrap().trim(),
+        "feat: add a file"
+    );
+
+    // An amend keeps the original commit
+    let output = shim_git(&repo, &["commit", "--amend"], &[("GIT_EDITOR", &bad)]);
+    assert!(!output.status.success());
+    assert_eq!(head(&repo).as_deref(), Some(first.as_str()));
+}
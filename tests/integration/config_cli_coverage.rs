@@ -1,12 +1,16 @@
 //! Integration coverage for `git-ai config` keys that previously had no CLI
 //! handling: `allow_superuser`, `transcript_streaming_lookback_days`, and
-//! `custom_attributes` (including its nested `custom_attributes.<key>` form).
+//! `custom_attributes` (including its nested `custom_attributes.<key>` form),
+//! plus `config explain` / `config dump --provenance`.
 //!
 //! These run the real binary against an isolated test HOME, so `config set`
 //! writes land in the sandboxed `~/.git-ai/config.json` rather than the user's.
 
 use crate::repos::test_repo::TestRepo;
-use git_ai::config::{AuthorConfig, FileConfig, NotesBackendConfig};
+use git_ai::config::{
+    AuthorConfig, CommitLintConfig, CommitLintMode, FileConfig, NotesBackendConfig,
+    SyncBackendConfig,
+};
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -74,6 +78,287 @@ fn test_config_transcript_streaming_lookback_days_set_get_unset() {
     );
 }
 
+#[test]
+fn test_config_daemon_idle_shutdown_set_get_unset() {
+    let repo = TestRepo::new();
+
+    // The daemon stays resident by default, surfaced as 0.
+    assert_eq!(
+        get_json(&repo, "daemon_idle_shutdown_secs"),
+        Value::Number(0.into())
+    );
+
+    repo.git_ai(&["config", "set", "daemon_idle_shutdown_secs", "900"])
+        .expect("set daemon_idle_shutdown_secs");
+    assert_eq!(
+        get_json(&repo, "daemon_idle_shutdown_secs"),
+        Value::Number(900.into())
+    );
+
+    assert!(
+        repo.git_ai(&["config", "set", "daemon_idle_shutdown_secs", "soon"])
+            .is_err()
+    );
+
+    repo.git_ai(&["config", "unset", "daemon_idle_shutdown_secs"])
+        .expect("unset daemon_idle_shutdown_secs");
+    assert_eq!(
+        get_json(&repo, "daemon_idle_shutdown_secs"),
+        Value::Number(0.into())
+    );
+}
+
+#[test]
+fn test_config_redaction_set_get_unset_masks_salt() {
+    let repo = TestRepo::new();
+
+    assert_eq!(
+        get_json(&repo, "redaction"),
+        serde_json::json!({ "hash_repo_urls": false, "strip_local_paths": false })
+    );
+
+    repo.git_ai(&[
+        "config",
+        "set",
+        "redaction",
+        r#"{"hash_repo_urls":true,"salt":"acme-org-salt-2026","strip_local_paths":true}"#,
+    ])
+    .expect("set redaction");
+    let redaction = get_json(&repo, "redaction");
+    assert_eq!(redaction["hash_repo_urls"], Value::Bool(true));
+    assert_eq!(redaction["strip_local_paths"], Value::Bool(true));
+    let salt = redaction["salt"].as_str().expect("salt shown");
+    assert_ne!(salt, "acme-org-salt-2026");
+
+    assert!(
+        repo.git_ai(&["config", "set", "redaction", "[true]"])
+            .is_err()
+    );
+
+    repo.git_ai(&["config", "unset", "redaction"])
+        .expect("unset redaction");
+    assert_eq!(
+        get_json(&repo, "redaction")["hash_repo_urls"],
+        Value::Bool(false)
+    );
+}
+
+#[test]
+fn test_config_disk_budgets_set_get_unset() {
+    let repo = TestRepo::new();
+
+    assert_eq!(
+        get_json(&repo, "disk_budgets"),
+        serde_json::json!({ "min_free_bytes": 256 * 1024 * 1024 })
+    );
+
+    repo.git_ai(&[
+        "config",
+        "set",
+        "disk_budgets",
+        r#"{"cache_max_bytes":1048576,"ci_clone_max_bytes":2147483648,"min_free_bytes":0}"#,
+    ])
+    .expect("set disk_budgets");
+    assert_eq!(
+        get_json(&repo, "disk_budgets"),
+        serde_json::json!({
+            "cache_max_bytes": 1048576,
+            "ci_clone_max_bytes": 2147483648u64,
+            "min_free_bytes": 0
+        })
+    );
+
+    assert!(
+        repo.git_ai(&["config", "set", "disk_budgets", r#"{"cache_max_bytes":-1}"#])
+            .is_err()
+    );
+
+    repo.git_ai(&["config", "unset", "disk_budgets"])
+        .expect("unset disk_budgets");
+    assert!(
+        get_json(&repo, "disk_budgets")
+            .get("cache_max_bytes")
+            .is_none()
+    );
+}
+
+#[test]
+fn test_config_clients_include_exclude_set_get_unset() {
+    let repo = TestRepo::new();
+
+    assert_eq!(get_json(&repo, "clients"), serde_json::json!({}));
+
+    repo.git_ai(&["config", "set", "clients.include", "fork,vscode"])
+        .expect("set clients.include");
+    repo.git_ai(&["config", "set", "--add", "clients.include", "gitfiend"])
+        .expect("add to clients.include");
+    repo.git_ai(&["config", "set", "clients.exclude", r#"["vscode"]"#])
+        .expect("set clients.exclude");
+    assert_eq!(
+        get_json(&repo, "clients"),
+        serde_json::json!({
+            "include": ["fork", "vscode", "gitfiend"],
+            "exclude": ["vscode"]
+        })
+    );
+    assert_eq!(
+        get_json(&repo, "clients.exclude"),
+        serde_json::json!(["vscode"])
+    );
+    assert!(
+        repo.git_ai(&["config", "set", "clients.only", "fork"])
+            .is_err()
+    );
+
+    repo.git_ai(&["config", "unset", "clients.include"])
+        .expect("unset clients.include");
+    assert_eq!(
+        get_json(&repo, "clients"),
+        serde_json::json!({ "exclude": ["vscode"] })
+    );
+    repo.git_ai(&["config", "unset", "clients"])
+        .expect("unset clients");
+    assert_eq!(get_json(&repo, "clients"), serde_json::json!({}));
+}
+
+#[test]
+fn test_config_explain_and_provenance_dump_report_sources() {
+    let repo = TestRepo::new();
+
+    let out = repo
+        .git_ai(&["config", "explain", "quiet"])
+        .expect("explain default key");
+    assert!(out.contains("quiet = false"), "{}", out);
+    assert!(out.contains("source: default"), "{}", out);
+
+    repo.git_ai(&["config", "set", "max_checkpoint_total_lines", "500"])
+        .expect("set max_checkpoint_total_lines");
+    let out = repo
+        .git_ai(&["config", "explain", "max_checkpoint_total_lines"])
+        .expect("explain file key");
+    assert!(out.contains("max_checkpoint_total_lines = 500"), "{}", out);
+    assert!(out.contains("source: file"), "{}", out);
+
+    // Env beats the file
+    let envs = [
+        ("GIT_AI_MAX_CHECKPOINT_TOTAL_LINES", "42"),
+        ("GIT_AI_NOTES_BACKEND_KIND", "http"),
+    ];
+    let out = repo
+        .git_ai_with_env(&["config", "explain", "max_checkpoint_total_lines"], &envs)
+        .expect("explain env key");
+    assert!(out.contains("max_checkpoint_total_lines = 42"), "{}", out);
+    assert!(
+        out.contains("source: env GIT_AI_MAX_CHECKPOINT_TOTAL_LINES"),
+        "{}",
+        out
+    );
+
+    let dump: Value = serde_json::from_str(
+        repo.git_ai_with_env(&["config", "dump", "--provenance"], &envs)
+            .expect("dump --provenance")
+            .trim(),
+    )
+    .expect("provenance dump is JSON");
+    assert_eq!(
+        dump["max_checkpoint_total_lines"],
+        serde_json::json!({
+            "value": 42,
+            "source": "env",
+            "env": "GIT_AI_MAX_CHECKPOINT_TOTAL_LINES"
+        })
+    );
+    assert_eq!(dump["notes_backend.kind"]["value"], "http");
+    assert_eq!(dump["notes_backend.kind"]["source"], "env");
+    assert_eq!(dump["quiet"]["source"], "default");
+    assert!(dump["feature_flags.transcript_sweep"]["value"].is_boolean());
+
+    assert!(repo.git_ai(&["config", "explain", "no_such_key"]).is_err());
+    assert!(repo.git_ai(&["config", "dump", "--bogus"]).is_err());
+}
+
+#[test]
+fn test_config_report_privacy_set_get_unset() {
+    let repo = TestRepo::new();
+
+    // Both controls are off by default, surfaced as 0.
+    assert_eq!(
+        get_json(&repo, "report_min_group_size"),
+        Value::Number(0.into())
+    );
+    assert_eq!(
+        get_json(&repo, "report_noise_epsilon"),
+        serde_json::json!(0.0)
+    );
+
+    repo.git_ai(&["config", "set", "report_min_group_size", "5"])
+        .expect("set report_min_group_size");
+    repo.git_ai(&["config", "set", "report_noise_epsilon", "0.5"])
+        .expect("set report_noise_epsilon");
+    assert_eq!(
+        get_json(&repo, "report_min_group_size"),
+        Value::Number(5.into())
+    );
+    assert_eq!(
+        get_json(&repo, "report_noise_epsilon"),
+        serde_json::json!(0.5)
+    );
+
+    assert!(
+        repo.git_ai(&["config", "set", "report_noise_epsilon", "-1"])
+            .is_err()
+    );
+
+    repo.git_ai(&["config", "unset", "report_min_group_size"])
+        .expect("unset report_min_group_size");
+    repo.git_ai(&["config", "unset", "report_noise_epsilon"])
+        .expect("unset report_noise_epsilon");
+    assert_eq!(
+        get_json(&repo, "report_min_group_size"),
+        Value::Number(0.into())
+    );
+}
+
+#[test]
+fn test_config_commit_lint_set_get_unset() {
+    let repo = TestRepo::new();
+
+    assert_eq!(
+        get_json(&repo, "commit_lint.mode"),
+        Value::String("off".to_string())
+    );
+
+    repo.git_ai(&["config", "set", "commit_lint.mode", "strict"])
+        .expect("set commit_lint.mode");
+    repo.git_ai(&["config", "set", "commit_lint.scopes", "cli, daemon"])
+        .expect("set commit_lint.scopes");
+    repo.git_ai(&["config", "set", "commit_lint.scopes", "mdm", "--add"])
+        .expect("add commit_lint.scopes");
+    assert_eq!(
+        get_json(&repo, "commit_lint"),
+        serde_json::json!({"mode": "strict", "scopes": ["cli", "daemon", "mdm"]})
+    );
+
+    assert!(
+        repo.git_ai(&["config", "set", "commit_lint.mode", "loud"])
+            .is_err()
+    );
+    assert!(
+        repo.git_ai(&["config", "set", "commit_lint.max_subject_length", "0"])
+            .is_err()
+    );
+
+    repo.git_ai(&["config", "unset", "commit_lint.scopes"])
+        .expect("unset commit_lint.scopes");
+    assert_eq!(get_json(&repo, "commit_lint.scopes"), Value::Null);
+    repo.git_ai(&["config", "unset", "commit_lint"])
+        .expect("unset commit_lint");
+    assert_eq!(
+        get_json(&repo, "commit_lint.mode"),
+        Value::String("off".to_string())
+    );
+}
+
 #[test]
 fn test_config_checkpoint_budget_set_get_unset() {
     let repo = TestRepo::new();
@@ -226,6 +511,36 @@ fn test_config_custom_attributes_nested_unset_trims_name() {
     assert_eq!(get_json(&repo, "custom_attributes.team"), Value::Null);
 }
 
+#[test]
+fn test_config_attribution_diff_algorithm_set_get_unset() {
+    let repo = TestRepo::new();
+
+    // Default is histogram.
+    assert_eq!(
+        get_json(&repo, "attribution_diff_algorithm"),
+        Value::String("histogram".to_string())
+    );
+
+    repo.git_ai(&["config", "set", "attribution_diff_algorithm", "Myers"])
+        .expect("set attribution_diff_algorithm");
+    assert_eq!(
+        get_json(&repo, "attribution_diff_algorithm"),
+        Value::String("myers".to_string())
+    );
+
+    assert!(
+        repo.git_ai(&["config", "set", "attribution_diff_algorithm", "minimal"])
+            .is_err()
+    );
+
+    repo.git_ai(&["config", "unset", "attribution_diff_algorithm"])
+        .expect("unset attribution_diff_algorithm");
+    assert_eq!(
+        get_json(&repo, "attribution_diff_algorithm"),
+        Value::String("histogram".to_string())
+    );
+}
+
 #[test]
 fn test_config_show_all_includes_new_keys() {
     let repo = TestRepo::new();
@@ -298,11 +613,32 @@ fn fully_populated_file_config() -> FileConfig {
         custom_attributes: Some(custom_attributes),
         git_ai_hooks: Some(git_ai_hooks),
         codex_hooks_format: Some("config_toml".to_string()),
+        attribution_diff_algorithm: Some("histogram".to_string()),
         notes_backend: Some(NotesBackendConfig::default()),
+        sync_backends: Some(vec![SyncBackendConfig {
+            name: "acme".to_string(),
+            remotes: vec!["https://github.com/acme/*".to_string()],
+            backend_url: "https://notes.acme.example".to_string(),
+            api_key_env: Some("ACME_GIT_AI_KEY".to_string()),
+        }]),
         transcript_streaming_lookback_days: Some(7),
         max_checkpoint_file_size_bytes: Some(3 * 1024 * 1024),
         max_checkpoint_total_size_bytes: Some(32 * 1024 * 1024),
         max_checkpoint_total_lines: Some(500_000),
+        report_min_group_size: Some(5),
+        report_noise_epsilon: Some(1.0),
+        commit_lint: Some(CommitLintConfig {
+            mode: CommitLintMode::Warn,
+            types: Some(vec!["feat".to_string()]),
+            scopes: Some(vec!["cli".to_string()]),
+            max_subject_length: Some(72),
+        }),
+        release_branches: Some(vec!["release/*".to_string()]),
+        issue_trackers: None,
+        daemon_idle_shutdown_secs: None,
+        redaction: None,
+        disk_budgets: None,
+        clients: None,
     }
 }
 
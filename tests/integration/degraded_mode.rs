@@ -0,0 +1,65 @@
+//! Commands degrade according to the capability matrix when optional
+//! components (here, the network) are missing.
+
+use crate::repos::test_repo::TestRepo;
+
+#[test]
+fn test_offline_commands_fail_up_front_and_local_ones_still_run() {
+    let repo = TestRepo::new();
+    let offline = [("GIT_AI_OFFLINE", "1")];
+
+    let err = repo
+        .git_ai_with_env(&["upgrade"], &offline)
+        .expect_err("upgrade needs the network");
+    assert!(err.contains("git-ai upgrade needs network access"), "{err}");
+
+    let version = repo
+        .git_ai_with_env(&["version"], &offline)
+        .expect("version works offline");
+    assert!(version.contains(env!("CARGO_PKG_VERSION")), "{version}");
+
+    repo.git_ai_with_env(&["git-path"], &offline)
+        .expect("git-path works offline");
+}
+
+#[test]
+fn test_local_subcommands_of_network_commands_run_offline() {
+    let repo = TestRepo::new();
+    let offline = [("GIT_AI_OFFLINE", "1")];
+
+    repo.git_ai_with_env(&["sync", "status"], &offline)
+        .expect("sync status only reads the local queues");
+
+    // Reaches ci local's own usage check instead of the network check
+    let err = repo
+        .git_ai_with_env(&["ci", "local"], &offline)
+        .expect_err("ci local without an event prints usage");
+    assert!(!err.contains("needs network access"), "{err}");
+    assert!(err.contains("git-ai ci local"), "{err}");
+
+    std::fs::write(repo.path().join("base.txt"), "base\n").unwrap();
+    let base_sha = repo.stage_all_and_commit("base").unwrap().commit_sha;
+    std::fs::write(repo.path().join("feature.txt"), "feature\n").unwrap();
+    let merge_sha = repo.stage_all_and_commit("feature").unwrap().commit_sha;
+    let attestation = repo.path().join("merge.intoto.json");
+    repo.git_ai_with_env(
+        &[
+            "ci",
+            "attest",
+            "--merge-commit-sha",
+            &merge_sha,
+            "--base-sha",
+            &base_sha,
+            "--output",
+            attestation.to_str().unwrap(),
+        ],
+        &offline,
+    )
+    .expect("ci attest works offline");
+    assert!(attestation.exists());
+
+    let err = repo
+        .git_ai_with_env(&["ci", "github", "run"], &offline)
+        .expect_err("ci github run needs the network");
+    assert!(err.contains("git-ai ci needs network access"), "{err}");
+}
@@ -0,0 +1,88 @@
+//! Gerrit repos install a `commit-msg` hook that appends a `Change-Id` trailer.
+//! git-ai no longer installs repo hooks of its own, so that hook has to keep
+//! running untouched, and commits it rewrites must keep their AI attribution.
+
+#![cfg(unix)]
+
+use crate::repos::test_file::ExpectedLineExt;
+use crate::repos::test_repo::TestRepo;
+use std::fs;
+
+/// Stand-in for Gerrit's `commit-msg` hook: adds a `Change-Id` trailer unless
+/// one is already present (so `--amend` keeps the same change)
+const GERRIT_COMMIT_MSG_HOOK: &str = r#"#!/bin/sh
+grep -q '^Change-Id:' "$1" && exit 0
+id="I$(git hash-object -t blob "$1")"
+git interpret-trailers --in-place --trailer "Change-Id: $id" "$1"
+"#;
+
+fn install_commit_msg_hook(hooks_dir: &std::path::Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::create_dir_all(hooks_dir).unwrap();
+    let hook = hooks_dir.join("commit-msg");
+    fs::write(&hook, GERRIT_COMMIT_MSG_HOOK).unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn head_change_ids(repo: &TestRepo) -> Vec<String> {
+    repo.git(&["log", "-1", "--format=%B"])
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.strip_prefix("Change-Id: "))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_gerrit_commit_msg_hook_runs_and_ai_attribution_survives() {
+    let repo = TestRepo::new();
+    install_commit_msg_hook(&repo.path().join(".git").join("hooks"));
+
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(crate::lines!["fn human() {}", "fn ai() {}".ai()]);
+    let commit = repo.stage_all_and_commit("Add lib").unwrap();
+
+    let change_ids = head_change_ids(&repo);
+    assert_eq!(change_ids.len(), 1, "expected one Change-Id trailer");
+    assert!(change_ids[0].starts_with('I'));
+    assert!(
+        repo.read_authorship_note(&commit.commit_sha).is_some(),
+        "authorship note should be attached to the hook-rewritten commit"
+    );
+    file.assert_lines_and_blame(crate::lines!["fn human() {}".human(), "fn ai() {}".ai()]);
+}
+
+#[test]
+fn test_gerrit_change_id_is_kept_across_amend() {
+    let repo = TestRepo::new();
+    install_commit_msg_hook(&repo.path().join(".git").join("hooks"));
+
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(crate::lines!["fn human() {}"]);
+    repo.stage_all_and_commit("Add lib").unwrap();
+    let original = head_change_ids(&repo);
+
+    file.set_contents(crate::lines!["fn human() {}", "fn ai() {}".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "--amend", "--no-edit"]).unwrap();
+
+    assert_eq!(head_change_ids(&repo), original);
+    file.assert_lines_and_blame(crate::lines!["fn human() {}".human(), "fn ai() {}".ai()]);
+}
+
+#[test]
+fn test_gerrit_hook_in_custom_hooks_path_still_runs() {
+    let repo = TestRepo::new();
+    let hooks_dir = repo.path().join("tools").join("hooks");
+    install_commit_msg_hook(&hooks_dir);
+    repo.git(&["config", "core.hooksPath", hooks_dir.to_str().unwrap()])
+        .unwrap();
+
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(crate::lines!["fn ai() {}".ai()]);
+    repo.stage_all_and_commit("Add lib").unwrap();
+
+    assert_eq!(head_change_ids(&repo).len(), 1);
+    file.assert_lines_and_blame(crate::lines!["fn ai() {}".ai()]);
+}
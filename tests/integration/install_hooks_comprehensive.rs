@@ -24,6 +24,7 @@ fn test_install_status_as_str() {
         "already_installed"
     );
     assert_eq!(InstallStatus::Failed.as_str(), "failed");
+    assert_eq!(InstallStatus::Unsupported.as_str(), "unsupported");
 }
 
 #[test]
@@ -95,6 +96,17 @@ fn test_install_result_failed_with_string() {
     assert_eq!(result.error, Some(error_msg));
 }
 
+#[test]
+fn test_install_result_unsupported() {
+    let result = InstallResult::unsupported("No git executable setting");
+    assert_eq!(result.status, InstallStatus::Unsupported);
+    assert!(result.error.is_none());
+    assert_eq!(
+        result.message_for_metrics(),
+        Some("No git executable setting".to_string())
+    );
+}
+
 #[test]
 fn test_install_result_with_warning() {
     let result = InstallResult::installed().with_warning("Minor issue detected");
@@ -20,6 +20,7 @@ mod bash_tool_benchmark;
 mod bash_tool_conformance;
 mod bash_tool_provenance;
 mod bash_tool_timeouts;
+mod bisect_report;
 mod blame_comprehensive;
 mod blame_flags;
 mod blame_subdirectory;
@@ -32,6 +33,8 @@ mod checkpoint_telemetry;
 mod checkpoint_unit;
 mod cherry_pick;
 mod chinese_text_edits;
+mod ci_attestation;
+mod ci_backports;
 mod ci_context_unit;
 mod ci_fork_notes;
 mod ci_handlers_comprehensive;
@@ -41,8 +44,11 @@ mod ci_partial_clone;
 mod ci_squash_rebase;
 mod claude_code;
 mod cli_parser_rebase_args;
+mod clients_check;
+mod clients_restore;
 mod codex;
 mod cold_trace2_repo;
+mod commit_lint;
 mod commit_metric_metadata;
 mod commit_post_stats_benchmark;
 mod config_cli_coverage;
@@ -51,6 +57,7 @@ mod continue_cli;
 mod cross_repo_cwd_attribution;
 mod cursor;
 mod daemon_commit_carryover;
+mod degraded_mode;
 mod diff;
 mod diff_comprehensive;
 mod diff_ignore_binary;
@@ -64,6 +71,7 @@ mod firebender;
 mod formatting_non_substantial_ai_attribution;
 mod fuzzer;
 mod gemini;
+mod gerrit_change_id;
 mod git_alias_resolution;
 mod git_cli_arg_parsing;
 mod git_repository_comprehensive;
@@ -84,18 +92,25 @@ mod issue_1204_multi_agent;
 mod jetbrains_download;
 mod jetbrains_ide_types;
 mod log;
+mod mdm_export_policy;
+mod mdm_export_profile;
+mod mdm_sync;
 mod merge_rebase;
 mod metrics_retry_idle;
 mod multi_repo_workspace;
 mod non_utf8_files;
 mod notes_merge_mixed_fanout;
 mod opencode;
+mod path_overrides;
 mod pending_ai_edit_suppression;
 mod performance;
 mod performance_targets;
 mod pi;
+mod plain_output;
+mod plugin_subcommands;
 mod post_commit_unit;
 mod pre_commit_unit;
+mod privacy_delete;
 mod prompt_across_commit;
 mod prompt_hash_migration;
 mod prompt_utils_unit;
@@ -115,6 +130,8 @@ mod repository_unit;
 mod reset;
 mod rewrite_ops_attribution;
 mod secrets_benchmark;
+mod selftest;
+mod server_hook;
 mod session_event_attribution;
 mod session_event_repo_url;
 mod sessions_backwards_compat;
@@ -122,6 +139,7 @@ mod sessions_cutover;
 mod show_prompt;
 mod simple_additions;
 mod simple_benchmark;
+mod split;
 mod sqlite_connection_policy;
 mod squash_merge;
 mod stale_prompt_carry;
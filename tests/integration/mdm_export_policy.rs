@@ -0,0 +1,92 @@
+//! `git-ai mdm export-policy` writes Windows policy artifacts for Group Policy
+//! or Intune to enforce the git client registry settings.
+
+use crate::repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+fn test_export_policy_writes_tortoisegit_settings() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let shim = r"C:\Program Files\git-ai\bin\git.exe";
+
+    let dsc = repo
+        .git_ai_with_env(
+            &[
+                "mdm",
+                "export-policy",
+                "--format",
+                "dsc",
+                "--shim-path",
+                shim,
+            ],
+            &envs,
+        )
+        .expect("export-policy dsc");
+    assert!(dsc.contains("Registry TortoiseGit_MSysGit"), "{dsc}");
+    assert!(
+        dsc.contains("ValueData = 'C:\\Program Files\\git-ai\\bin'"),
+        "{dsc}"
+    );
+
+    let output = repo.test_home_path().join("git-ai.reg");
+    repo.git_ai_with_env(
+        &[
+            "mdm",
+            "export-policy",
+            "--format",
+            "reg",
+            "--scope",
+            "user",
+            "--shim-path",
+            shim,
+            "--output",
+            output.to_str().unwrap(),
+        ],
+        &envs,
+    )
+    .expect("export-policy reg");
+    let bytes = fs::read(&output).unwrap();
+    let units: Vec<u16> = bytes[2..]
+        .chunks(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    let reg = String::from_utf16(&units).unwrap();
+    assert!(
+        reg.contains("[HKEY_CURRENT_USER\\Software\\TortoiseGit]"),
+        "{reg}"
+    );
+
+    let err = repo
+        .git_ai_with_env(
+            &[
+                "mdm",
+                "export-policy",
+                "--format",
+                "intune",
+                "--client",
+                "fork",
+                "--shim-path",
+                shim,
+            ],
+            &envs,
+        )
+        .expect_err("Fork isn't configured through the registry");
+    assert!(err.contains("can't manage it"), "{err}");
+
+    let err = repo
+        .git_ai_with_env(
+            &[
+                "mdm",
+                "export-policy",
+                "--format",
+                "reg",
+                "--shim-path",
+                "/opt/git-ai/bin/git",
+            ],
+            &envs,
+        )
+        .expect_err("not a Windows path");
+    assert!(err.contains("machine-wide Windows path"), "{err}");
+}
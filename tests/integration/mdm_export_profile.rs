@@ -0,0 +1,84 @@
+//! `git-ai mdm export-profile` writes a `.mobileconfig` for an MDM to enforce
+//! the git client preferences instead of git-ai writing them locally.
+
+use crate::repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+fn test_export_profile_covers_defaults_clients() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    fs::create_dir_all(config_home.join("git-ai")).unwrap();
+    fs::write(
+        config_home.join("git-ai").join("clients.toml"),
+        "[[client]]\nid = \"acme\"\nname = \"Acme Git\"\nsettings_path = \"~/Library/Preferences/com.acme.Git.plist\"\nformat = \"plist\"\nkeys = { GitDirectory = \"{shim_dir}\" }\n",
+    )
+    .unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let shim = "/opt/git-ai/bin/git";
+
+    let profile = repo
+        .git_ai_with_env(&["mdm", "export-profile", "--shim-path", shim], &envs)
+        .expect("export-profile");
+    assert!(profile.starts_with("<?xml"), "{profile}");
+    for domain in ["com.DanPristupov.Fork", "com.panic.Nova", "com.acme.Git"] {
+        assert!(
+            profile.contains(&format!(
+                "<key>PayloadType</key>\n\t\t\t<string>{domain}</string>"
+            )),
+            "{profile}"
+        );
+    }
+    assert!(profile.contains(&format!(
+        "<key>customGitInstancePath</key>\n\t\t\t<string>{shim}</string>"
+    )));
+    assert!(profile.contains("<key>GitDirectory</key>\n\t\t\t<string>/opt/git-ai/bin</string>"));
+
+    let output = repo.test_home_path().join("fork.mobileconfig");
+    repo.git_ai_with_env(
+        &[
+            "mdm",
+            "export-profile",
+            "--shim-path",
+            shim,
+            "--client",
+            "fork",
+            "--output",
+            output.to_str().unwrap(),
+        ],
+        &envs,
+    )
+    .expect("export-profile --client fork");
+    let fork_only = fs::read_to_string(&output).unwrap();
+    assert!(fork_only.contains("com.DanPristupov.Fork"));
+    assert!(!fork_only.contains("com.panic.Nova"));
+
+    let err = repo
+        .git_ai_with_env(
+            &[
+                "mdm",
+                "export-profile",
+                "--shim-path",
+                shim,
+                "--client",
+                "gitfiend",
+            ],
+            &envs,
+        )
+        .expect_err("GitFiend has no defaults domain");
+    assert!(err.contains("can't manage it"), "{err}");
+
+    let per_user = repo.test_home_path().join(".git-ai/bin/git");
+    let err = repo
+        .git_ai_with_env(
+            &[
+                "mdm",
+                "export-profile",
+                "--shim-path",
+                per_user.to_str().unwrap(),
+            ],
+            &envs,
+        )
+        .expect_err("per-user shim");
+    assert!(err.contains("machine-wide path"), "{err}");
+}
@@ -0,0 +1,261 @@
+//! `git-ai mdm sync` pulls a signed client policy from a server and applies
+//! it.
+
+use crate::repos::test_repo::TestRepo;
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Serve the files in `root` over plain HTTP on loopback, 404 for missing
+/// ones; the base URL
+fn serve(root: PathBuf) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    break;
+                }
+            }
+            let path = request_line.split(' ').nth(1).unwrap_or("/");
+            let response = match fs::read(root.join(path.trim_start_matches('/'))) {
+                Ok(body) => {
+                    let mut response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    )
+                    .into_bytes();
+                    response.extend(body);
+                    response
+                }
+                Err(_) => {
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        .to_vec()
+                }
+            };
+            let _ = stream.write_all(&response);
+        }
+    });
+    url
+}
+
+fn openssl(args: &[&str]) -> Vec<u8> {
+    let output = Command::new("openssl").args(args).output().unwrap();
+    assert!(output.status.success(), "openssl {args:?} failed");
+    output.stdout
+}
+
+/// Write `policy` to `<root>/policy.toml` with its base64 signature beside it
+fn publish(root: &Path, key: &Path, policy: &str) {
+    let body = root.join("policy.toml");
+    fs::write(&body, policy).unwrap();
+    let signature = root.join("policy.raw-sig");
+    openssl(&[
+        "pkeyutl",
+        "-sign",
+        "-rawin",
+        "-inkey",
+        key.to_str().unwrap(),
+        "-in",
+        body.to_str().unwrap(),
+        "-out",
+        signature.to_str().unwrap(),
+    ]);
+    let encoded = openssl(&["base64", "-A", "-in", signature.to_str().unwrap()]);
+    fs::write(root.join("policy.toml.sig"), encoded).unwrap();
+}
+
+fn sync(repo: &TestRepo, envs: &[(&str, &str)], code: i32) -> (Value, String) {
+    let output = repo
+        .git_ai_command_without_pre_sync_for_test(&["mdm", "sync", "--json"], envs)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert_eq!(output.status.code(), Some(code), "{stdout}{stderr}");
+    let json = serde_json::from_str(stdout.trim()).unwrap_or(Value::Null);
+    (json, stderr)
+}
+
+fn gitfiend(out: &Value) -> &Value {
+    out["clients"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|report| report["id"] == "gitfiend")
+        .unwrap_or_else(|| panic!("no gitfiend report: {out}"))
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mdm_sync_applies_a_signed_policy() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    let gitfiend_dir = config_home.join("GitFiend");
+    fs::create_dir_all(&gitfiend_dir).unwrap();
+    let site = repo.test_home_path().join("site");
+    fs::create_dir_all(&site).unwrap();
+    let keys = repo.test_home_path().join("keys");
+    fs::create_dir_all(&keys).unwrap();
+    let private_key = keys.join("policy.pem");
+    let public_key = keys.join("policy.pub.pem");
+    openssl(&[
+        "genpkey",
+        "-algorithm",
+        "ed25519",
+        "-out",
+        private_key.to_str().unwrap(),
+    ]);
+    openssl(&[
+        "pkey",
+        "-in",
+        private_key.to_str().unwrap(),
+        "-pubout",
+        "-out",
+        public_key.to_str().unwrap(),
+    ]);
+    let url = format!("{}/policy.toml", serve(site.clone()));
+    repo.git_ai(&["config", "set", "clients.policy_url", &url])
+        .expect("set clients.policy_url");
+    repo.git_ai(&[
+        "config",
+        "set",
+        "clients.policy_public_key",
+        public_key.to_str().unwrap(),
+    ])
+    .expect("set clients.policy_public_key");
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+
+    // Audit only reports
+    publish(
+        &site,
+        &private_key,
+        "version = 1\nserial = 1\nenforcement = \"audit\"\n\n[clients]\ninclude = [\"gitfiend\"]\n",
+    );
+    let (out, _) = sync(&repo, &envs, 1);
+    assert_eq!(out["policy"]["enforcement"], "audit");
+    assert_eq!(out["policy"]["serial"], 1);
+    assert_eq!(gitfiend(&out)["status"], "pending");
+    assert!(!gitfiend_dir.join("config.json").exists());
+    // Clients the policy doesn't include are left alone
+    let others = out["clients"].as_array().unwrap();
+    assert!(
+        others
+            .iter()
+            .filter(|report| report["id"] != "gitfiend")
+            .all(|report| report["status"] == "excluded"),
+        "{out}"
+    );
+
+    // Enforce configures them
+    publish(
+        &site,
+        &private_key,
+        "version = 1\nserial = 2\n\n[clients]\ninclude = [\"gitfiend\"]\n",
+    );
+    let (out, _) = sync(&repo, &envs, 1);
+    assert_eq!(gitfiend(&out)["status"], "installed");
+    assert!(gitfiend_dir.join("config.json").exists());
+
+    // A body that doesn't match its signature is refused
+    fs::write(
+        site.join("policy.toml"),
+        "version = 1\nserial = 3\nenforcement = \"audit\"\n",
+    )
+    .unwrap();
+    let (_, stderr) = sync(&repo, &envs, 2);
+    assert!(stderr.contains("signature"), "{stderr}");
+
+    // So is a validly signed policy older than the one applied (a replay)
+    publish(
+        &site,
+        &private_key,
+        "version = 1\nserial = 1\nenforcement = \"audit\"\n",
+    );
+    let (_, stderr) = sync(&repo, &envs, 2);
+    assert!(stderr.contains("refusing to roll back"), "{stderr}");
+
+    // An unreachable policy falls back to the last one that verified
+    fs::remove_file(site.join("policy.toml")).unwrap();
+    let (out, stderr) = sync(&repo, &envs, 0);
+    assert!(stderr.contains("last one that verified"), "{stderr}");
+    assert_eq!(gitfiend(&out)["status"], "already_installed");
+    assert!(out["policy"]["fallback_reason"].is_string(), "{out}");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mdm_sync_accepts_a_policy_signed_with_a_rotated_key() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    fs::create_dir_all(config_home.join("GitFiend")).unwrap();
+    let site = repo.test_home_path().join("site");
+    fs::create_dir_all(&site).unwrap();
+    let keys = repo.test_home_path().join("keys");
+    fs::create_dir_all(&keys).unwrap();
+    let public_key = keys.join("policy.pub.pem");
+    let new_key = |name: &str| {
+        let private_key = keys.join(name);
+        openssl(&[
+            "genpkey",
+            "-algorithm",
+            "ed25519",
+            "-out",
+            private_key.to_str().unwrap(),
+        ]);
+        openssl(&[
+            "pkey",
+            "-in",
+            private_key.to_str().unwrap(),
+            "-pubout",
+            "-out",
+            public_key.to_str().unwrap(),
+        ]);
+        private_key
+    };
+    let url = format!("{}/policy.toml", serve(site.clone()));
+    repo.git_ai(&["config", "set", "clients.policy_url", &url])
+        .expect("set clients.policy_url");
+    repo.git_ai(&[
+        "config",
+        "set",
+        "clients.policy_public_key",
+        public_key.to_str().unwrap(),
+    ])
+    .expect("set clients.policy_public_key");
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+    let policy = |serial: u32| {
+        format!(
+            "version = 1\nserial = {serial}\nenforcement = \"audit\"\n\n[clients]\ninclude = [\"gitfiend\"]\n"
+        )
+    };
+
+    // The saved policy is signed with the old key
+    let old_key = new_key("old.pem");
+    publish(&site, &old_key, &policy(5));
+    let (out, _) = sync(&repo, &envs, 1);
+    assert_eq!(out["policy"]["serial"], 5);
+
+    // After rotation the saved policy no longer verifies, which must not stop
+    // the policy signed with the new key from being fetched
+    let rotated_key = new_key("new.pem");
+    publish(&site, &rotated_key, &policy(1));
+    let (out, stderr) = sync(&repo, &envs, 1);
+    assert_eq!(out["policy"]["serial"], 1, "{stderr}");
+    assert!(out["policy"]["fallback_reason"].is_null(), "{out}");
+
+    // And the new policy becomes the one to fall back to
+    fs::remove_file(site.join("policy.toml")).unwrap();
+    let (out, stderr) = sync(&repo, &envs, 1);
+    assert!(stderr.contains("last one that verified"), "{stderr}");
+    assert_eq!(out["policy"]["serial"], 1);
+}
@@ -0,0 +1,106 @@
+//! Global path overrides: `--config`, `--state-dir`, `--cache-dir` and their variables.
+
+use crate::repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+fn test_config_flag_and_env_redirect_the_config_file() {
+    let repo = TestRepo::new();
+    let alt_dir = tempfile::tempdir().unwrap();
+    let alt_config = alt_dir.path().join("alt-config.json");
+    let alt = alt_config.to_str().unwrap();
+    let default_config = repo.test_home_path().join(".git-ai").join("config.json");
+
+    repo.git_ai(&[
+        "--config",
+        alt,
+        "config",
+        "set",
+        "max_checkpoint_total_lines",
+        "77",
+    ])
+    .expect("config set with --config");
+    let written = fs::read_to_string(&alt_config).expect("override file written");
+    assert!(written.contains("77"), "{written}");
+    let default_content = fs::read_to_string(&default_config).unwrap_or_default();
+    assert!(!default_content.contains("77"), "{default_content}");
+
+    let out = repo
+        .git_ai_with_env(
+            &["config", "explain", "max_checkpoint_total_lines"],
+            &[("GIT_AI_CONFIG_FILE", alt)],
+        )
+        .expect("explain with GIT_AI_CONFIG_FILE");
+    assert!(out.contains("max_checkpoint_total_lines = 77"), "{out}");
+    assert!(out.contains(alt), "{out}");
+
+    let out = repo
+        .git_ai(&[
+            &format!("--config={alt}"),
+            "config",
+            "explain",
+            "max_checkpoint_total_lines",
+        ])
+        .expect("explain with --config=");
+    assert!(out.contains("max_checkpoint_total_lines = 77"), "{out}");
+
+    let err = repo
+        .git_ai(&["--state-dir"])
+        .expect_err("a path flag needs a value");
+    assert!(err.contains("--state-dir requires a path"), "{err}");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_state_and_cache_dir_flags_redirect_client_files() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    fs::create_dir_all(config_home.join("GitFiend")).unwrap();
+    fs::create_dir_all(config_home.join("sublime-merge")).unwrap();
+    let alt_dir = tempfile::tempdir().unwrap();
+    let state_dir = alt_dir.path().join("state");
+    let cache_dir = alt_dir.path().join("cache");
+    let git_ai_dir = repo.test_home_path().join(".git-ai");
+    // A client whose version probe is cached
+    let bin = repo.test_home_path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let smerge = bin.join("smerge");
+    fs::write(&smerge, "#!/bin/sh\necho 'Sublime Merge Build 2096'\n").unwrap();
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(&smerge, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let _ = repo.git_ai_with_env(
+        &[
+            "--state-dir",
+            state_dir.to_str().unwrap(),
+            "--cache-dir",
+            cache_dir.to_str().unwrap(),
+            "clients",
+            "install",
+            "--json",
+        ],
+        &[
+            ("XDG_CONFIG_HOME", config_home.to_str().unwrap()),
+            ("PATH", path.as_str()),
+        ],
+    );
+
+    let audit = fs::read_to_string(state_dir.join("audit.log")).expect("audit log in state dir");
+    assert!(audit.contains("gitfiend"), "{audit}");
+    assert!(state_dir.join("backups").join("gitfiend").is_dir());
+    assert!(cache_dir.join("client_discovery").is_file());
+    assert!(!state_dir.join("client_discovery").exists());
+    for default in [
+        "audit.log",
+        "backups",
+        "internal/audit.log",
+        "internal/backups",
+    ] {
+        assert!(!git_ai_dir.join(default).exists(), "{default}");
+    }
+}
@@ -0,0 +1,28 @@
+//! `git-ai --plain` output carries no escape codes or symbols, only words.
+
+use crate::repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_plain_clients_check_uses_status_words() {
+    let repo = TestRepo::new();
+    let config_home = repo.test_home_path().join(".config");
+    fs::create_dir_all(config_home.join("GitFiend")).unwrap();
+    let envs = [("XDG_CONFIG_HOME", config_home.to_str().unwrap())];
+
+    // Exits 1: GitFiend has pending changes
+    let output = repo
+        .git_ai_command_without_pre_sync_for_test(&["--plain", "clients", "check"], &envs)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let output = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.contains('\x1b'), "escape codes in {output:?}");
+    assert!(
+        !output.contains(['✓', '⚠', '✗', '○', '╔']),
+        "symbols in {output:?}"
+    );
+    assert!(output.contains("Git Clients"), "{output}");
+    assert!(output.contains("PENDING: GitFiend"), "{output}");
+}
@@ -0,0 +1,56 @@
+//! `git-ai <name>` falls back to a `git-ai-<name>` executable on PATH.
+
+use crate::repos::test_repo::TestRepo;
+
+#[test]
+#[cfg(unix)]
+fn test_unknown_command_dispatches_to_plugin_on_path() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = TestRepo::new();
+    let plugins = tempfile::tempdir().unwrap();
+    let plugin = plugins.path().join("git-ai-hello");
+    std::fs::write(
+        &plugin,
+        "#!/bin/sh\n\
+         echo \"args: $*\"\n\
+         echo \"protocol: $GIT_AI_PLUGIN_PROTOCOL\"\n\
+         echo \"repo: $GIT_AI_REPO_ROOT\"\n\
+         echo \"stdin: $(cat)\"\n\
+         exit 3\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path = format!(
+        "{}:{}",
+        plugins.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let err = repo
+        .git_ai_with_env(&["hello", "one", "two"], &[("PATH", &path)])
+        .expect_err("the plugin's exit code is passed through");
+
+    assert!(err.contains("args: one two"), "{}", err);
+    assert!(err.contains("protocol: 1"), "{}", err);
+    let repo_root = repo.path().canonicalize().unwrap();
+    assert!(
+        err.contains(&format!("repo: {}", repo_root.display()))
+            || err.contains(&format!("repo: {}", repo.path().display())),
+        "{}",
+        err
+    );
+    let stdin_line = err
+        .lines()
+        .find_map(|line| line.strip_prefix("stdin: "))
+        .expect("plugin echoed its stdin");
+    let context: serde_json::Value = serde_json::from_str(stdin_line).unwrap();
+    assert_eq!(context["command"], "hello");
+    assert_eq!(context["args"], serde_json::json!(["one", "two"]));
+    assert_eq!(context["protocol"], 1);
+
+    let err = repo
+        .git_ai_with_env(&["no-such-plugin"], &[("PATH", &path)])
+        .unwrap_err();
+    assert!(err.contains("Unknown git-ai command"), "{}", err);
+}
@@ -0,0 +1,81 @@
+use crate::repos::test_file::ExpectedLineExt;
+use crate::repos::test_repo::TestRepo;
+use serde_json::Value;
+
+/// The JSON report at the start of the combined output
+fn report(output: &str) -> Value {
+    let start = output.find('{').expect("report JSON in output");
+    serde_json::Deserializer::from_str(&output[start..])
+        .into_iter::<Value>()
+        .next()
+        .expect("report")
+        .expect("valid report JSON")
+}
+
+fn commit_ai_lines(repo: &TestRepo) -> String {
+    let mut file = repo.filename("feature.js");
+    file.set_contents(crate::lines![
+        "export const a = 1;".ai(),
+        "export const b = 2;"
+    ]);
+    repo.stage_all_and_commit("Add feature").unwrap().commit_sha
+}
+
+#[test]
+fn test_privacy_delete_without_any_notes() {
+    let repo = TestRepo::new();
+    std::fs::write(repo.path().join("README.md"), "hello\n").unwrap();
+    repo.git_og(&["add", "."]).unwrap();
+    repo.git_og(&["commit", "-m", "Initial"]).unwrap();
+
+    let output = repo
+        .git_ai(&["privacy", "delete", "--author", "bob@example.com"])
+        .expect("privacy delete succeeds without refs/notes/ai");
+    let report = report(&output);
+    assert_eq!(report["report"]["notes_rewritten"], serde_json::json!([]));
+    assert!(report["report"]["notes_error"].is_null());
+    assert!(report["report"]["metric_events_deleted"].is_number());
+    assert!(report["report"]["local_prompts_deleted"].is_number());
+}
+
+#[test]
+fn test_privacy_delete_with_no_matching_notes() {
+    let repo = TestRepo::new();
+    let sha = commit_ai_lines(&repo);
+    let before = repo.read_authorship_note(&sha).expect("note");
+
+    let output = repo
+        .git_ai(&["privacy", "delete", "--author", "nobody@example.org"])
+        .expect("privacy delete succeeds when nothing matches");
+    let report = report(&output);
+    assert_eq!(report["report"]["notes_rewritten"], serde_json::json!([]));
+    assert_eq!(repo.read_authorship_note(&sha).expect("note"), before);
+}
+
+#[test]
+fn test_privacy_delete_matches_mixed_case_emails() {
+    let repo = TestRepo::new();
+    repo.git_og(&["config", "user.name", "Alice"]).unwrap();
+    repo.git_og(&["config", "user.email", "Alice@Example.com"])
+        .unwrap();
+    let sha = commit_ai_lines(&repo);
+    let before = repo.read_authorship_note(&sha).expect("note");
+    assert!(before.contains("Alice@Example.com"), "{}", before);
+
+    let output = repo
+        .git_ai(&["privacy", "delete", "--author", "alice@example.com"])
+        .expect("privacy delete");
+    let report = report(&output);
+    assert_eq!(
+        report["report"]["notes_rewritten"],
+        serde_json::json!([sha])
+    );
+    assert!(report["report"]["subject_hmac_sha256"].is_string());
+
+    let after = repo.read_authorship_note(&sha).expect("note");
+    assert!(
+        !after.to_lowercase().contains("alice@example.com"),
+        "{}",
+        after
+    );
+}
@@ -5,7 +5,7 @@ use git_ai::git::notes_api;
 use git_ai::git::notes_api::{read_authorship_v3, read_note, write_note};
 use git_ai::git::refs::git_backend_for_tests::{
     commits_with_authorship_notes, get_commits_with_notes_from_list, grep_ai_notes,
-    note_blob_oids_for_commits, notes_add_batch, notes_add_blob_batch,
+    note_blob_oids_for_commits, notes_add, notes_add_batch, notes_add_blob_batch,
 };
 use git_ai::git::refs::{
     AI_AUTHORSHIP_FORK_TRACKING_REF, CommitAuthorship, copy_missing_notes_for_commits_from_ref,
@@ -657,3 +657,52 @@ fn test_get_reference_as_authorship_log_v3_version_mismatch() {
         panic!("Expected version mismatch error");
     }
 }
+
+#[test]
+fn test_concurrent_notes_writes_from_linked_worktrees_all_land() {
+    let repo = TestRepo::new();
+    fs::write(repo.path().join("base.txt"), "base\n").unwrap();
+    repo.stage_all_and_commit("Base").expect("commit");
+
+    let worktree_dir = tempfile::tempdir().expect("tempdir");
+    let worktree = worktree_dir.path().join("feature");
+    repo.git_og(&["worktree", "add", "--detach", worktree.to_str().unwrap()])
+        .expect("worktree add");
+
+    // Distinct commits to annotate, reachable from both worktrees
+    let mut commit_shas = Vec::new();
+    for i in 0..8 {
+        fs::write(repo.path().join("base.txt"), format!("v{}\n", i)).unwrap();
+        repo.stage_all_and_commit(&format!("Commit {}", i))
+            .expect("commit");
+        commit_shas.push(head_sha(&repo));
+    }
+
+    let workdirs = [repo.path().to_path_buf(), worktree.clone()];
+    let handles: Vec<_> = commit_shas
+        .iter()
+        .enumerate()
+        .map(|(i, sha)| {
+            let workdir = workdirs[i % workdirs.len()].clone();
+            let sha = sha.clone();
+            std::thread::spawn(move || {
+                let gitai_repo =
+                    find_repository_in_path(workdir.to_str().unwrap()).expect("find repository");
+                notes_add(&gitai_repo, &sha, &format!("note for {}", sha))
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap().expect("concurrent notes write");
+    }
+
+    let gitai_repo = find_repository_in_path(worktree.to_str().unwrap()).expect("find worktree");
+    for sha in &commit_shas {
+        assert_eq!(
+            read_note(&gitai_repo, sha).as_deref(),
+            Some(format!("note for {}", sha).as_str()),
+            "note for {} should survive concurrent writes",
+            sha
+        );
+    }
+}
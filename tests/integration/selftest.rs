@@ -0,0 +1,30 @@
+use crate::repos::test_repo::TestRepo;
+
+#[test]
+fn selftest_shim_behaves_exactly_like_direct_git() {
+    let repo = TestRepo::new();
+    let output = repo
+        .git_ai(&["selftest", "--json"])
+        .unwrap_or_else(|out| panic!("selftest found the shim changing git's behavior:\n{out}"));
+
+    let report: serde_json::Value = serde_json::from_str(&output).expect("selftest JSON report");
+    let scenarios = report["scenarios"].as_array().expect("scenarios array");
+    let status_of = |name: &str| {
+        scenarios
+            .iter()
+            .find(|scenario| scenario["name"] == name)
+            .and_then(|scenario| scenario["status"].as_str())
+            .unwrap_or_else(|| panic!("missing scenario {name}: {output}"))
+    };
+    for name in [
+        "commit",
+        "clone-fetch-push",
+        "stash",
+        "bisect",
+        "worktree",
+        "submodule",
+        "merge-conflict",
+    ] {
+        assert_eq!(status_of(name), "passed", "{output}");
+    }
+}
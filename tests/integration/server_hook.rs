@@ -0,0 +1,90 @@
+use crate::repos::test_file::ExpectedLineExt;
+use crate::repos::test_repo::TestRepo;
+
+const ZERO_OID: &str = "0000000000000000000000000000000000000000";
+
+/// Commits made on a detached HEAD that no ref reaches, like objects a
+/// pre-receive hook sees in quarantine: (attributed, unattributed)
+fn unreferenced_commits(repo: &TestRepo) -> (String, String) {
+    let mut base = repo.filename("base.txt");
+    base.set_contents(crate::lines!["base"]);
+    repo.stage_all_and_commit("base").unwrap();
+    repo.git(&["branch", "-M", "main"]).unwrap();
+
+    repo.git(&["checkout", "--detach"]).unwrap();
+    let mut ai_file = repo.filename("ai.js");
+    ai_file.set_contents(crate::lines!["const a = 1;".ai(), "const b = 2;".ai()]);
+    let attributed = repo.stage_all_and_commit("ai change").unwrap().commit_sha;
+
+    std::fs::write(repo.path().join("human.txt"), "by hand\n").unwrap();
+    repo.git_og(&["add", "human.txt"]).unwrap();
+    repo.git_og(&["commit", "-m", "made without git-ai"])
+        .unwrap();
+    let unattributed = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    repo.git_og(&["checkout", "main"]).unwrap();
+    (attributed, unattributed)
+}
+
+#[test]
+fn test_server_hook_enforce_rejects_commits_without_notes() {
+    let repo = TestRepo::new();
+    let (attributed, unattributed) = unreferenced_commits(&repo);
+    repo.git_og(&["config", "git-ai.serverHook.mode", "enforce"])
+        .unwrap();
+
+    let stdin = format!("{} {} refs/heads/feature\n", ZERO_OID, unattributed);
+    let err = repo
+        .git_ai_with_stdin(&["server-hook"], stdin.as_bytes())
+        .expect_err("push with an unattributed commit should be rejected");
+    assert!(err.contains("rejected refs/heads/feature"), "{err}");
+    assert!(
+        err.contains(&format!("{} has no authorship note", &unattributed[..12])),
+        "{err}"
+    );
+    assert!(!err.contains(&attributed[..12]), "{err}");
+
+    // The same update passes as an `update` hook once it's scoped to other refs
+    repo.git_og(&["config", "git-ai.serverHook.ref", "refs/heads/main"])
+        .unwrap();
+    repo.git_ai(&["server-hook", "refs/heads/feature", ZERO_OID, &unattributed])
+        .expect("refs outside git-ai.serverHook.ref are not checked");
+}
+
+#[test]
+fn test_server_hook_warn_mode_reports_without_rejecting() {
+    let repo = TestRepo::new();
+    let (_attributed, unattributed) = unreferenced_commits(&repo);
+
+    let stdin = format!("{} {} refs/heads/feature\n", ZERO_OID, unattributed);
+    let output = repo
+        .git_ai_with_stdin(&["server-hook"], stdin.as_bytes())
+        .expect("warn is the default mode");
+    assert!(output.contains("warning refs/heads/feature"), "{output}");
+}
+
+#[test]
+fn test_server_hook_enforces_max_ai_percent() {
+    let repo = TestRepo::new();
+    let (attributed, _unattributed) = unreferenced_commits(&repo);
+    repo.git_og(&["config", "git-ai.serverHook.mode", "enforce"])
+        .unwrap();
+
+    let stdin = format!("{} {} refs/heads/feature\n", ZERO_OID, attributed);
+    repo.git_ai_with_stdin(&["server-hook"], stdin.as_bytes())
+        .expect("an attributed commit satisfies the default policy");
+
+    repo.git_og(&["config", "git-ai.serverHook.maxAiPercent", "50"])
+        .unwrap();
+    let err = repo
+        .git_ai_with_stdin(&["server-hook"], stdin.as_bytes())
+        .expect_err("an all-AI push exceeds a 50% limit");
+    assert!(
+        err.contains("AI-attributed lines are 100% of the 2 lines added (limit 50%)"),
+        "{err}"
+    );
+}
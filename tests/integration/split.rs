@@ -0,0 +1,41 @@
+use crate::repos::test_file::ExpectedLineExt;
+use crate::repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+fn test_split_commits_each_staged_group() {
+    let repo = TestRepo::new();
+    let mut source = repo.filename("src/app/main.rs");
+    let mut readme = repo.filename("README.md");
+    fs::write(repo.path().join("obsolete.txt"), "old\n").unwrap();
+
+    source.set_contents(crate::lines!["fn main() {}"]);
+    readme.set_contents(crate::lines!["# Project"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    source.insert_at(1, crate::lines!["fn helper() {}".ai()]);
+    readme.insert_at(1, crate::lines!["Usage notes"]);
+    fs::remove_file(repo.path().join("obsolete.txt")).unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+
+    let dry_run = repo.git_ai(&["split", "--dry-run"]).unwrap();
+    assert!(dry_run.contains("chore(app): update main.rs"), "{dry_run}");
+    assert!(dry_run.contains("docs: update README.md"), "{dry_run}");
+
+    repo.git_ai(&["split", "--yes"]).unwrap();
+
+    let log = repo.git(&["log", "--format=%s", "-4"]).unwrap();
+    let subjects: Vec<&str> = log.lines().collect();
+    assert_eq!(
+        subjects,
+        vec![
+            "docs: update README.md",
+            "chore(app): update main.rs",
+            "chore: update obsolete.txt",
+            "Initial commit"
+        ]
+    );
+    let staged = repo.git(&["diff", "--cached", "--name-only"]).unwrap();
+    assert!(staged.trim().is_empty(), "{staged}");
+    assert!(!repo.git(&["ls-files"]).unwrap().contains("obsolete.txt"));
+}
@@ -120,3 +120,55 @@ fn superuser_guard_allows_root_in_ci_environment() {
         "should NOT warn in CI environment (silent pass), got: {stderr}"
     );
 }
+
+#[test]
+#[cfg(unix)]
+fn superuser_guard_exempts_system_scope_clients_after_path_flags() {
+    if unsafe { libc::geteuid() } != 0 {
+        return;
+    }
+
+    let state_dir = tempfile::tempdir().unwrap();
+    let binary_path = get_binary_path();
+    let mut cmd = Command::new(binary_path);
+    cmd.arg("--state-dir")
+        .arg(state_dir.path())
+        .args(["clients", "check", "--scope", "system"])
+        .env_remove("GIT_AI_ALLOW_SUPERUSER");
+    remove_all_ci_env_vars(&mut cmd);
+    let output = cmd.output().expect("failed to execute binary");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("is not recommended"),
+        "clients --scope system should be exempt behind --state-dir, got: {stderr}"
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn superuser_guard_exempts_machine_wide_commands() {
+    if unsafe { libc::geteuid() } != 0 {
+        return;
+    }
+
+    let users_dir = tempfile::tempdir().unwrap();
+    let users_dir = users_dir.path().to_str().unwrap();
+    let cases: [&[&str]; 3] = [
+        &["clients", "check", "--scope", "system"],
+        &["clients", "check", "--all-users", "--users-dir", users_dir],
+        &["mdm", "sync", "--dry-run"],
+    ];
+    for args in cases {
+        let mut cmd = Command::new(get_binary_path());
+        cmd.args(args).env_remove("GIT_AI_ALLOW_SUPERUSER");
+        remove_all_ci_env_vars(&mut cmd);
+        let output = cmd.output().expect("failed to execute binary");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            !stderr.contains("is not recommended"),
+            "{args:?} should be exempt from the superuser guard, got: {stderr}"
+        );
+    }
+}